@@ -0,0 +1,42 @@
+package dev.opendata.common;
+
+/**
+ * Local disk cache for SlateDB object-store reads.
+ *
+ * <p>With a disk cache, parts of objects fetched from the object store are
+ * kept under {@code path} on local disk, typically NVMe, and later reads of
+ * them are served from there. Once the cache holds more than
+ * {@code maxBytes}, SlateDB evicts parts to make room.
+ *
+ * @param path     directory holding cached object parts, or null to disable
+ *                 the cache
+ * @param maxBytes most bytes to keep in the cache
+ */
+public record DiskCacheConfig(String path, long maxBytes) {
+
+    /**
+     * Configuration without a disk cache.
+     */
+    public static final DiskCacheConfig DISABLED = new DiskCacheConfig(null, 0);
+
+    public DiskCacheConfig {
+        if (path != null && path.isBlank()) {
+            throw new IllegalArgumentException("path must not be blank");
+        }
+        if (path != null && maxBytes <= 0) {
+            throw new IllegalArgumentException("maxBytes must be positive");
+        }
+        if (path == null && maxBytes != 0) {
+            throw new IllegalArgumentException("maxBytes requires a path");
+        }
+    }
+
+    /**
+     * Returns whether the disk cache is enabled.
+     *
+     * @return true if a path is set
+     */
+    public boolean enabled() {
+        return path != null;
+    }
+}
@@ -0,0 +1,49 @@
+package dev.opendata.common;
+
+/**
+ * Scheduling of SlateDB garbage collection.
+ *
+ * <p>SlateDB's garbage collector periodically deletes manifests, WAL files
+ * and compacted SSTs that are no longer referenced. Every {@code intervalMs}
+ * it deletes those objects older than {@code minAgeMs}; the same schedule is
+ * applied to all three kinds. A long interval defers reclamation, for example
+ * through peak traffic, and a short one lets soak tests observe space being
+ * reclaimed. With both values zero, the schedule of the settings file, or
+ * SlateDB's default, is kept.
+ *
+ * <p>There is no call that runs a collection on demand: the upstream log
+ * this binding wraps runs the collector only on its own schedule and exposes
+ * no way to trigger one.
+ *
+ * @param intervalMs how often the collector runs, in milliseconds
+ * @param minAgeMs   how old an unreferenced object must be before it is
+ *                   deleted, in milliseconds
+ */
+public record GarbageCollectorConfig(long intervalMs, long minAgeMs) {
+
+    /**
+     * Configuration keeping the schedule of the settings file.
+     */
+    public static final GarbageCollectorConfig DEFAULT = new GarbageCollectorConfig(0, 0);
+
+    public GarbageCollectorConfig {
+        if (intervalMs < 0) {
+            throw new IllegalArgumentException("intervalMs must not be negative");
+        }
+        if (minAgeMs < 0) {
+            throw new IllegalArgumentException("minAgeMs must not be negative");
+        }
+        if ((intervalMs == 0) != (minAgeMs == 0)) {
+            throw new IllegalArgumentException("intervalMs and minAgeMs must be set together");
+        }
+    }
+
+    /**
+     * Returns whether this config overrides the collector's schedule.
+     *
+     * @return true if an interval and minimum age are set
+     */
+    public boolean enabled() {
+        return intervalMs > 0;
+    }
+}
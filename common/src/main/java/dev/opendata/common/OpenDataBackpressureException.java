@@ -0,0 +1,19 @@
+package dev.opendata.common;
+
+/**
+ * Exception thrown when a native write queue is full and its policy rejects
+ * further appends.
+ *
+ * <p>The append was not submitted and can be retried once earlier appends
+ * have completed.
+ */
+public class OpenDataBackpressureException extends OpenDataNativeException {
+
+    public OpenDataBackpressureException(String message) {
+        super(message);
+    }
+
+    public OpenDataBackpressureException(String message, Throwable cause) {
+        super(message, cause);
+    }
+}
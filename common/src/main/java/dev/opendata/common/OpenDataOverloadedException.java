@@ -0,0 +1,19 @@
+package dev.opendata.common;
+
+/**
+ * Exception thrown when a native handle already runs as many blocking calls
+ * as its call limit allows and the limit's policy refuses another.
+ *
+ * <p>The call did not reach storage and can be retried once earlier calls
+ * have returned.
+ */
+public class OpenDataOverloadedException extends OpenDataNativeException {
+
+    public OpenDataOverloadedException(String message) {
+        super(message);
+    }
+
+    public OpenDataOverloadedException(String message, Throwable cause) {
+        super(message, cause);
+    }
+}
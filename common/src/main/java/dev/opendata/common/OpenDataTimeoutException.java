@@ -0,0 +1,17 @@
+package dev.opendata.common;
+
+/**
+ * Exception thrown when a native call is aborted for exceeding its time limit.
+ *
+ * <p>For writes, the operation may or may not have been applied.
+ */
+public class OpenDataTimeoutException extends OpenDataNativeException {
+
+    public OpenDataTimeoutException(String message) {
+        super(message);
+    }
+
+    public OpenDataTimeoutException(String message, Throwable cause) {
+        super(message, cause);
+    }
+}
@@ -7,7 +7,7 @@ package dev.opendata.common;
  * available for OpenData systems.
  */
 public sealed interface StorageConfig
-        permits StorageConfig.InMemory, StorageConfig.SlateDb {
+        permits StorageConfig.InMemory, StorageConfig.SlateDb, StorageConfig.NoOp {
 
     /**
      * In-memory storage (fast, no persistence).
@@ -16,17 +16,62 @@ public sealed interface StorageConfig
      */
     record InMemory() implements StorageConfig {}
 
+    /**
+     * Storage that does no work, for measuring binding overhead in isolation.
+     *
+     * <p>Appends only allocate sequences, and scans return synthetic entries
+     * for allocated sequences with a zero-filled payload of {@code payloadSize}
+     * bytes, whichever key they were appended with. Operations other than
+     * plain appends and scans fall back to in-memory storage. Not supported by
+     * readers.
+     *
+     * @param payloadSize size of the payload of scanned entries in bytes
+     */
+    record NoOp(int payloadSize) implements StorageConfig {
+
+        /**
+         * Creates a NoOp config whose scanned entries have empty payloads.
+         */
+        public NoOp() {
+            this(0);
+        }
+
+        public NoOp {
+            if (payloadSize < 0) {
+                throw new IllegalArgumentException("payloadSize must not be negative");
+            }
+        }
+    }
+
     /**
      * SlateDB-backed storage (persistent).
      *
+     * <p>Typed fields such as {@code diskCache}, {@code garbageCollector} and
+     * {@code writeBuffer} are written by the native binding into a copy of the
+     * settings file, which must then be TOML.
+     *
+     * <p>SlateDB's in-memory block cache cannot be sized here or in the
+     * settings file: it is not a SlateDB setting but a cache object handed to
+     * SlateDB's {@code DbBuilder}, and the upstream log open path the binding
+     * calls passes none, so SlateDB's default block cache applies. The local
+     * {@link DiskCacheConfig disk cache} is the read cache this config sizes.
+     *
      * @param path         path prefix for SlateDB data in the object store
      * @param objectStore  object store provider configuration
-     * @param settingsPath optional path to SlateDB settings file (TOML/YAML/JSON)
+     * @param settingsPath optional path to SlateDB settings file (TOML, YAML or
+     *                     JSON; TOML when any typed setting is configured, as
+     *                     these are written into a copy of the file)
+     * @param diskCache        local disk cache for object-store reads
+     * @param garbageCollector schedule of SlateDB garbage collection
+     * @param writeBuffer      write buffer, memtable, flush interval and WAL settings
      */
     record SlateDb(
             String path,
             ObjectStoreConfig objectStore,
-            String settingsPath
+            String settingsPath,
+            DiskCacheConfig diskCache,
+            GarbageCollectorConfig garbageCollector,
+            WriteBufferConfig writeBuffer
     ) implements StorageConfig {
 
         /**
@@ -39,6 +84,45 @@ public sealed interface StorageConfig
             this(path, objectStore, null);
         }
 
+        /**
+         * Creates a SlateDb config without a disk cache.
+         *
+         * @param path         path prefix for SlateDB data
+         * @param objectStore  object store provider configuration
+         * @param settingsPath optional path to SlateDB settings file
+         */
+        public SlateDb(String path, ObjectStoreConfig objectStore, String settingsPath) {
+            this(path, objectStore, settingsPath, DiskCacheConfig.DISABLED);
+        }
+
+        /**
+         * Creates a SlateDb config keeping the settings file's garbage
+         * collection schedule.
+         *
+         * @param path         path prefix for SlateDB data
+         * @param objectStore  object store provider configuration
+         * @param settingsPath optional path to SlateDB settings file
+         * @param diskCache    local disk cache for object-store reads
+         */
+        public SlateDb(String path, ObjectStoreConfig objectStore, String settingsPath,
+                DiskCacheConfig diskCache) {
+            this(path, objectStore, settingsPath, diskCache, GarbageCollectorConfig.DEFAULT);
+        }
+
+        /**
+         * Creates a SlateDb config keeping the settings file's write settings.
+         *
+         * @param path             path prefix for SlateDB data
+         * @param objectStore      object store provider configuration
+         * @param settingsPath     optional path to SlateDB settings file
+         * @param diskCache        local disk cache for object-store reads
+         * @param garbageCollector schedule of SlateDB garbage collection
+         */
+        public SlateDb(String path, ObjectStoreConfig objectStore, String settingsPath,
+                DiskCacheConfig diskCache, GarbageCollectorConfig garbageCollector) {
+            this(path, objectStore, settingsPath, diskCache, garbageCollector, WriteBufferConfig.DEFAULT);
+        }
+
         public SlateDb {
             if (path == null || path.isBlank()) {
                 throw new IllegalArgumentException("path must not be null or blank");
@@ -46,6 +130,45 @@ public sealed interface StorageConfig
             if (objectStore == null) {
                 throw new IllegalArgumentException("objectStore must not be null");
             }
+            if (diskCache == null) {
+                throw new IllegalArgumentException("diskCache must not be null");
+            }
+            if (garbageCollector == null) {
+                throw new IllegalArgumentException("garbageCollector must not be null");
+            }
+            if (writeBuffer == null) {
+                throw new IllegalArgumentException("writeBuffer must not be null");
+            }
+        }
+
+        /**
+         * Returns a copy of this config with the given disk cache.
+         *
+         * @param diskCache local disk cache for object-store reads
+         * @return a new SlateDb config
+         */
+        public SlateDb withDiskCache(DiskCacheConfig diskCache) {
+            return new SlateDb(path, objectStore, settingsPath, diskCache, garbageCollector, writeBuffer);
+        }
+
+        /**
+         * Returns a copy of this config with the given garbage collection schedule.
+         *
+         * @param garbageCollector schedule of SlateDB garbage collection
+         * @return a new SlateDb config
+         */
+        public SlateDb withGarbageCollector(GarbageCollectorConfig garbageCollector) {
+            return new SlateDb(path, objectStore, settingsPath, diskCache, garbageCollector, writeBuffer);
+        }
+
+        /**
+         * Returns a copy of this config with the given write settings.
+         *
+         * @param writeBuffer write buffer, memtable, flush interval and WAL settings
+         * @return a new SlateDb config
+         */
+        public SlateDb withWriteBuffer(WriteBufferConfig writeBuffer) {
+            return new SlateDb(path, objectStore, settingsPath, diskCache, garbageCollector, writeBuffer);
         }
     }
 }
@@ -0,0 +1,93 @@
+package dev.opendata.common;
+
+/**
+ * SlateDB write path settings, which dominate publish latency.
+ *
+ * <p>Appends are buffered in SlateDB's memtable and written to the WAL every
+ * {@code flushIntervalMs}; a full memtable of {@code memtableBytes} is
+ * flushed as an L0 SST, and writers block once {@code writeBufferBytes} of
+ * writes are unflushed. Without the WAL, appends reach the object store only
+ * with memtable flushes, trading durability of recent appends for fewer
+ * puts. A zero value, or {@link Wal#DEFAULT}, keeps the settings file's
+ * value, or SlateDB's default.
+ *
+ * @param writeBufferBytes bytes of unflushed writes before writers block
+ * @param memtableBytes    size at which the memtable is flushed as an L0 SST
+ * @param flushIntervalMs  how often buffered writes are flushed to the WAL,
+ *                         in milliseconds
+ * @param wal              whether writes go through the WAL
+ */
+public record WriteBufferConfig(long writeBufferBytes, long memtableBytes, long flushIntervalMs, Wal wal) {
+
+    /**
+     * Configuration keeping the write settings of the settings file.
+     */
+    public static final WriteBufferConfig DEFAULT = new WriteBufferConfig(0, 0, 0, Wal.DEFAULT);
+
+    /**
+     * Whether writes go through SlateDB's write-ahead log.
+     */
+    public enum Wal {
+        /** Keep the settings file's choice. */
+        DEFAULT,
+        /** Write through the WAL. */
+        ENABLED,
+        /** Write to the memtable only. */
+        DISABLED
+    }
+
+    public WriteBufferConfig {
+        if (writeBufferBytes < 0) {
+            throw new IllegalArgumentException("writeBufferBytes must not be negative");
+        }
+        if (memtableBytes < 0) {
+            throw new IllegalArgumentException("memtableBytes must not be negative");
+        }
+        if (flushIntervalMs < 0) {
+            throw new IllegalArgumentException("flushIntervalMs must not be negative");
+        }
+        if (wal == null) {
+            throw new IllegalArgumentException("wal must not be null");
+        }
+    }
+
+    /**
+     * Returns a copy of this config with the given write buffer size.
+     *
+     * @param writeBufferBytes bytes of unflushed writes before writers block
+     * @return a new config
+     */
+    public WriteBufferConfig withWriteBufferBytes(long writeBufferBytes) {
+        return new WriteBufferConfig(writeBufferBytes, memtableBytes, flushIntervalMs, wal);
+    }
+
+    /**
+     * Returns a copy of this config with the given memtable size.
+     *
+     * @param memtableBytes size at which the memtable is flushed as an L0 SST
+     * @return a new config
+     */
+    public WriteBufferConfig withMemtableBytes(long memtableBytes) {
+        return new WriteBufferConfig(writeBufferBytes, memtableBytes, flushIntervalMs, wal);
+    }
+
+    /**
+     * Returns a copy of this config with the given flush interval.
+     *
+     * @param flushIntervalMs how often buffered writes are flushed, in milliseconds
+     * @return a new config
+     */
+    public WriteBufferConfig withFlushIntervalMs(long flushIntervalMs) {
+        return new WriteBufferConfig(writeBufferBytes, memtableBytes, flushIntervalMs, wal);
+    }
+
+    /**
+     * Returns a copy of this config with the given WAL choice.
+     *
+     * @param wal whether writes go through the WAL
+     * @return a new config
+     */
+    public WriteBufferConfig withWal(Wal wal) {
+        return new WriteBufferConfig(writeBufferBytes, memtableBytes, flushIntervalMs, wal);
+    }
+}
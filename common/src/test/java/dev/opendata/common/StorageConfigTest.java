@@ -56,6 +56,81 @@ class StorageConfigTest {
                 .hasMessageContaining("objectStore");
     }
 
+    @Test
+    void shouldDisableDiskCacheByDefault() {
+        var config = new StorageConfig.SlateDb("prefix", new ObjectStoreConfig.Local("/data"));
+
+        assertThat(config.diskCache()).isEqualTo(DiskCacheConfig.DISABLED);
+        assertThat(config.diskCache().enabled()).isFalse();
+    }
+
+    @Test
+    void shouldConfigureDiskCache() {
+        var config = new StorageConfig.SlateDb("prefix", new ObjectStoreConfig.Local("/data"))
+                .withDiskCache(new DiskCacheConfig("/nvme/cache", 1L << 30));
+
+        assertThat(config.diskCache().path()).isEqualTo("/nvme/cache");
+        assertThat(config.diskCache().maxBytes()).isEqualTo(1L << 30);
+        assertThat(config.diskCache().enabled()).isTrue();
+    }
+
+    @Test
+    void shouldRejectDiskCacheWithoutPositiveMaxBytes() {
+        assertThatThrownBy(() -> new DiskCacheConfig("/nvme/cache", 0))
+                .isInstanceOf(IllegalArgumentException.class)
+                .hasMessageContaining("maxBytes");
+    }
+
+    @Test
+    void shouldConfigureGarbageCollector() {
+        var config = new StorageConfig.SlateDb("prefix", new ObjectStoreConfig.Local("/data"))
+                .withGarbageCollector(new GarbageCollectorConfig(60_000, 3_600_000));
+
+        assertThat(config.garbageCollector().intervalMs()).isEqualTo(60_000);
+        assertThat(config.garbageCollector().minAgeMs()).isEqualTo(3_600_000);
+        assertThat(config.garbageCollector().enabled()).isTrue();
+        assertThat(config.withDiskCache(DiskCacheConfig.DISABLED).garbageCollector())
+                .isEqualTo(config.garbageCollector());
+    }
+
+    @Test
+    void shouldRejectGarbageCollectorIntervalWithoutMinAge() {
+        assertThatThrownBy(() -> new GarbageCollectorConfig(60_000, 0))
+                .isInstanceOf(IllegalArgumentException.class)
+                .hasMessageContaining("together");
+    }
+
+    @Test
+    void shouldConfigureWriteBuffer() {
+        var writeBuffer = WriteBufferConfig.DEFAULT
+                .withWriteBufferBytes(256L << 20)
+                .withFlushIntervalMs(5)
+                .withWal(WriteBufferConfig.Wal.DISABLED);
+        var config = new StorageConfig.SlateDb("prefix", new ObjectStoreConfig.Local("/data"))
+                .withWriteBuffer(writeBuffer);
+
+        assertThat(config.writeBuffer().writeBufferBytes()).isEqualTo(256L << 20);
+        assertThat(config.writeBuffer().memtableBytes()).isZero();
+        assertThat(config.writeBuffer().flushIntervalMs()).isEqualTo(5);
+        assertThat(config.writeBuffer().wal()).isEqualTo(WriteBufferConfig.Wal.DISABLED);
+        assertThat(config.withGarbageCollector(GarbageCollectorConfig.DEFAULT).writeBuffer())
+                .isEqualTo(writeBuffer);
+    }
+
+    @Test
+    void shouldRejectNegativeFlushInterval() {
+        assertThatThrownBy(() -> WriteBufferConfig.DEFAULT.withFlushIntervalMs(-1))
+                .isInstanceOf(IllegalArgumentException.class)
+                .hasMessageContaining("flushIntervalMs");
+    }
+
+    @Test
+    void shouldRejectNegativeNoOpPayloadSize() {
+        assertThatThrownBy(() -> new StorageConfig.NoOp(-1))
+                .isInstanceOf(IllegalArgumentException.class)
+                .hasMessageContaining("payloadSize");
+    }
+
     @Test
     void shouldSupportInstanceofPatternMatching() {
         StorageConfig inMemory = new StorageConfig.InMemory();
@@ -0,0 +1,67 @@
+//! Records build information reported by `LogDb.nativeVersion()`.
+//!
+//! Sets `OPENDATA_GIT_SHA` to the commit the library was built from,
+//! `OPENDATA_BUILD_PROFILE` to the Cargo profile, and
+//! `OPENDATA_LOGDB_VERSION` and `OPENDATA_SLATEDB_VERSION` to the upstream
+//! versions resolved in `Cargo.lock`, each followed by the commit for git
+//! dependencies. Values that cannot be determined are `unknown`.
+
+use std::fs;
+use std::process::Command;
+
+fn main() {
+    println!("cargo:rerun-if-changed=Cargo.lock");
+    println!("cargo:rerun-if-changed=../../.git/HEAD");
+    println!("cargo:rerun-if-changed=../../.git/refs");
+
+    let git_sha = Command::new("git")
+        .args(["rev-parse", "--short=12", "HEAD"])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .and_then(|output| String::from_utf8(output.stdout).ok())
+        .map(|sha| sha.trim().to_string())
+        .unwrap_or_else(|| "unknown".to_string());
+    let profile = std::env::var("PROFILE").unwrap_or_else(|_| "unknown".to_string());
+    let lock = fs::read_to_string("Cargo.lock").unwrap_or_default();
+
+    println!("cargo:rustc-env=OPENDATA_GIT_SHA={}", git_sha);
+    println!("cargo:rustc-env=OPENDATA_BUILD_PROFILE={}", profile);
+    println!(
+        "cargo:rustc-env=OPENDATA_LOGDB_VERSION={}",
+        locked_version(&lock, "log", "opendata")
+    );
+    println!(
+        "cargo:rustc-env=OPENDATA_SLATEDB_VERSION={}",
+        locked_version(&lock, "slatedb", "slatedb")
+    );
+}
+
+/// Returns the locked version of the package `name` whose source mentions
+/// `source`, with the commit appended for git sources.
+fn locked_version(lock: &str, name: &str, source: &str) -> String {
+    for package in lock.split("[[package]]") {
+        let field = |key: &str| {
+            package.lines().find_map(|line| {
+                line.strip_prefix(key)
+                    .and_then(|rest| rest.trim().strip_prefix('='))
+                    .map(|value| value.trim().trim_matches('"').to_string())
+            })
+        };
+        let (Some(package_name), Some(version), Some(package_source)) =
+            (field("name"), field("version"), field("source"))
+        else {
+            continue;
+        };
+        if package_name != name || !package_source.contains(source) {
+            continue;
+        }
+        return match package_source.rsplit_once('#') {
+            Some((_, commit)) if package_source.starts_with("git+") => {
+                format!("{}+{}", version, &commit[..commit.len().min(12)])
+            }
+            _ => version,
+        };
+    }
+    "unknown".to_string()
+}
@@ -0,0 +1,94 @@
+/*
+ * C ABI for OpenData Log, exported by the opendata_log_jni shared library
+ * alongside its JNI bindings. See src/ffi.rs for the conventions.
+ */
+#ifndef OPENDATA_LOG_H
+#define OPENDATA_LOG_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+#define OPENDATA_OK 0
+#define OPENDATA_NULL_POINTER 1
+#define OPENDATA_INVALID_ARGUMENT 2
+#define OPENDATA_STORAGE_ERROR 3
+#define OPENDATA_BUFFER_TOO_SMALL 4
+
+#define OPENDATA_STORAGE_IN_MEMORY 0u
+#define OPENDATA_STORAGE_LOCAL 1u
+#define OPENDATA_STORAGE_AWS 2u
+
+typedef struct OpendataLog OpendataLog;
+typedef struct OpendataEntries OpendataEntries;
+
+typedef struct {
+    const uint8_t *ptr;
+    size_t len;
+} OpendataSlice;
+
+typedef struct {
+    uint32_t kind;
+    OpendataSlice path;
+    OpendataSlice location;
+    OpendataSlice region;
+    OpendataSlice settings_path;
+} OpendataStorageConfig;
+
+typedef struct {
+    OpendataSlice key;
+    OpendataSlice value;
+    int64_t timestamp_ms;
+} OpendataRecord;
+
+typedef struct {
+    uint64_t sequence;
+    int64_t timestamp_ms;
+    OpendataSlice key;
+    OpendataSlice value;
+} OpendataEntry;
+
+const char *opendata_last_error(void);
+
+int32_t opendata_log_open(const OpendataStorageConfig *config, OpendataLog **out_log);
+int32_t opendata_log_close(OpendataLog *log);
+int32_t opendata_log_append(OpendataLog *log, const OpendataRecord *records, size_t count,
+                            uint64_t *out_start_sequence);
+/* Appends a FlatBuffers AppendBatch, see opendata_log_batch.fbs. */
+int32_t opendata_log_append_flatbuffer(OpendataLog *log, OpendataSlice batch,
+                                       uint64_t *out_start_sequence);
+int32_t opendata_log_flush(OpendataLog *log);
+int32_t opendata_log_scan(OpendataLog *log, OpendataSlice key, uint64_t start_sequence,
+                          size_t max_entries, OpendataEntries **out_entries);
+
+/*
+ * Writes scanned entries into a caller-provided buffer, each starting at an
+ * 8-byte aligned offset and laid out in native byte order as
+ * [u64 sequence][i64 timestamp_ms][u32 key_len][u32 value_len][key][value].
+ */
+int32_t opendata_log_scan_into(OpendataLog *log, OpendataSlice key, uint64_t start_sequence,
+                               size_t max_entries, uint8_t *buffer, size_t capacity,
+                               size_t *out_entry_count, size_t *out_written);
+
+/*
+ * Writes scanned entries into a caller-provided buffer as a FlatBuffers
+ * ScanResult, see opendata_log_batch.fbs. Fails with
+ * OPENDATA_BUFFER_TOO_SMALL and the needed size in out_written if the result
+ * does not fit whole.
+ */
+int32_t opendata_log_scan_flatbuffer(OpendataLog *log, OpendataSlice key, uint64_t start_sequence,
+                                     size_t max_entries, uint8_t *buffer, size_t capacity,
+                                     size_t *out_written);
+
+size_t opendata_entries_len(const OpendataEntries *entries);
+int32_t opendata_entries_get(const OpendataEntries *entries, size_t index, OpendataEntry *out_entry);
+void opendata_entries_free(OpendataEntries *entries);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* OPENDATA_LOG_H */
@@ -0,0 +1,29 @@
+//! Global allocator of the native library.
+//!
+//! Appends allocate a key and a value buffer per record, and with many
+//! producer threads the system allocator can become a point of contention.
+//! Building with the `jemalloc` or `mimalloc` Cargo feature replaces it for
+//! every native allocation, SlateDB's included; allocations of the JVM are
+//! unaffected. The choice is made at build time, since a Rust library cannot
+//! switch its global allocator once loaded, and is reported through
+//! `LogDb.nativeVersion()`.
+
+#[cfg(all(feature = "jemalloc", feature = "mimalloc"))]
+compile_error!("the jemalloc and mimalloc features are mutually exclusive");
+
+#[cfg(feature = "jemalloc")]
+#[global_allocator]
+static GLOBAL: tikv_jemallocator::Jemalloc = tikv_jemallocator::Jemalloc;
+
+#[cfg(all(feature = "mimalloc", not(feature = "jemalloc")))]
+#[global_allocator]
+static GLOBAL: mimalloc::MiMalloc = mimalloc::MiMalloc;
+
+/// Name of the allocator the library was built with.
+pub(crate) const NAME: &str = if cfg!(feature = "jemalloc") {
+    "jemalloc"
+} else if cfg!(feature = "mimalloc") {
+    "mimalloc"
+} else {
+    "system"
+};
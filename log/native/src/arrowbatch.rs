@@ -0,0 +1,129 @@
+//! Scans handed to Java as Arrow record batches.
+//!
+//! Analytics pipelines and Flight-based tooling consume Arrow, and turning a
+//! `LogEntry[]` into vectors on the Java side costs a copy per entry on top
+//! of the objects themselves. An Arrow scan instead builds the batch natively
+//! and exports it through the Arrow C Data Interface into an `ArrowArray`
+//! and `ArrowSchema` the caller allocated, so Arrow Java imports the batch
+//! without copying it again. The binding does not depend on Arrow Java;
+//! callers pass the addresses of the structs.
+//!
+//! The batch is exported as a struct array of four columns, in this order:
+//!
+//! | column      | type                          |
+//! |-------------|-------------------------------|
+//! | `sequence`  | int64                         |
+//! | `timestamp` | timestamp, milliseconds, UTC  |
+//! | `key`       | binary                        |
+//! | `value`     | large binary                  |
+//!
+//! Values use 64-bit offsets so a batch of large values cannot overflow its
+//! offsets. The exported buffers are freed once the importer releases them.
+
+use std::sync::Arc;
+
+use arrow::array::{
+    Array, BinaryBuilder, Int64Builder, LargeBinaryBuilder, StructArray,
+    TimestampMillisecondBuilder,
+};
+use arrow::datatypes::{DataType, Field, Fields, TimeUnit};
+use arrow::error::ArrowError;
+use arrow::ffi::{to_ffi, FFI_ArrowArray, FFI_ArrowSchema};
+use log::LogEntry;
+
+use crate::extract_timestamp_and_payload;
+
+/// Builds the struct array of a batch of entries.
+pub(crate) fn to_struct_array(entries: &[LogEntry]) -> StructArray {
+    let mut sequences = Int64Builder::with_capacity(entries.len());
+    let mut timestamps = TimestampMillisecondBuilder::with_capacity(entries.len());
+    let key_bytes = entries.iter().map(|e| e.key.len()).sum();
+    let value_bytes = entries.iter().map(|e| e.value.len()).sum();
+    let mut keys = BinaryBuilder::with_capacity(entries.len(), key_bytes);
+    let mut values = LargeBinaryBuilder::with_capacity(entries.len(), value_bytes);
+    for entry in entries {
+        let (timestamp_ms, payload) = extract_timestamp_and_payload(&entry.value);
+        sequences.append_value(entry.sequence as i64);
+        timestamps.append_value(timestamp_ms);
+        keys.append_value(&entry.key);
+        values.append_value(payload);
+    }
+    let timestamps = timestamps.finish().with_timezone("UTC");
+    let fields = Fields::from(vec![
+        Field::new("sequence", DataType::Int64, false),
+        Field::new(
+            "timestamp",
+            DataType::Timestamp(TimeUnit::Millisecond, Some("UTC".into())),
+            false,
+        ),
+        Field::new("key", DataType::Binary, false),
+        Field::new("value", DataType::LargeBinary, false),
+    ]);
+    StructArray::new(
+        fields,
+        vec![
+            Arc::new(sequences.finish()),
+            Arc::new(timestamps),
+            Arc::new(keys.finish()),
+            Arc::new(values.finish()),
+        ],
+        None,
+    )
+}
+
+/// Exports a batch of entries into caller-allocated C Data Interface structs.
+///
+/// # Safety
+/// `array` and `schema` must point to writable `ArrowArray` and `ArrowSchema`
+/// structs that hold no data, or whose data was already released.
+pub(crate) unsafe fn export(
+    entries: &[LogEntry],
+    array: *mut FFI_ArrowArray,
+    schema: *mut FFI_ArrowSchema,
+) -> Result<(), ArrowError> {
+    let (ffi_array, ffi_schema) = to_ffi(&to_struct_array(entries).into_data())?;
+    std::ptr::write_unaligned(array, ffi_array);
+    std::ptr::write_unaligned(schema, ffi_schema);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use arrow::array::{AsArray, RecordBatch};
+    use arrow::datatypes::Int64Type;
+    use arrow::ffi::from_ffi;
+    use bytes::Bytes;
+
+    use super::*;
+
+    fn entry(sequence: u64, timestamp_ms: i64, payload: &[u8]) -> LogEntry {
+        let mut value = timestamp_ms.to_be_bytes().to_vec();
+        value.extend_from_slice(payload);
+        LogEntry {
+            key: Bytes::from_static(b"key"),
+            sequence,
+            value: Bytes::from(value),
+        }
+    }
+
+    #[test]
+    fn should_export_entries_as_struct_array() {
+        // given
+        let entries = [entry(3, 1000, b"first"), entry(4, 1001, b"")];
+        let mut array = FFI_ArrowArray::empty();
+        let mut schema = FFI_ArrowSchema::empty();
+
+        // when
+        unsafe { export(&entries, &mut array, &mut schema) }.unwrap();
+        let imported = unsafe { from_ffi(array, &schema) }.unwrap();
+        let batch = RecordBatch::from(StructArray::from(imported));
+
+        // then
+        assert_eq!(batch.num_rows(), 2);
+        let sequences = batch.column(0).as_primitive::<Int64Type>();
+        assert_eq!(sequences.values(), &[3, 4]);
+        let values = batch.column(3).as_binary::<i64>();
+        assert_eq!(values.value(0), b"first");
+        assert_eq!(values.value(1), b"");
+    }
+}
@@ -0,0 +1,129 @@
+//! Attachment of native threads calling back into Java.
+//!
+//! Completions, listeners, JFR events and log lines are delivered on threads
+//! the JVM did not start: bridge, watch and open threads, and the workers of
+//! a handle's runtimes. Every such callback gets its `JNIEnv` through
+//! `with_env`, which attaches the thread as a daemon on first use, so no
+//! native thread ever holds up JVM exit, and caches its env for the thread's
+//! lifetime, so later callbacks skip the JNI lookup.
+//!
+//! Threads attached here are detached when they stop: runtimes detach their
+//! threads in `on_thread_stop`, and the library's own threads detach as
+//! their loop ends. Once the JVM runs its shutdown hooks, the hook
+//! registered by `init` stops all attachment: callbacks are dropped and
+//! threads are no longer detached, since detaching from a JVM being destroyed
+//! can crash it. `jni.attached_threads` in the stats counts the native
+//! threads currently attached.
+
+use std::cell::Cell;
+use std::ptr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::OnceLock;
+
+use jni::{JNIEnv, JavaVM};
+
+use crate::metrics::Stats;
+
+static VM: OnceLock<JavaVM> = OnceLock::new();
+
+/// Set once the JVM started shutting down.
+static SHUTDOWN: AtomicBool = AtomicBool::new(false);
+
+thread_local! {
+    /// Env of the current thread, if it was attached here
+    static ENV: Cell<*mut jni::sys::JNIEnv> = const { Cell::new(ptr::null_mut()) };
+}
+
+/// Records the JVM and registers the shutdown hook, if not done yet.
+///
+/// Must be called from a Java thread. Until it succeeds, callbacks from
+/// native threads are dropped.
+pub(crate) fn init(env: &mut JNIEnv<'_>) {
+    if VM.get().is_some() {
+        return;
+    }
+    let Ok(vm) = env.get_java_vm() else {
+        let _ = env.exception_clear();
+        return;
+    };
+    if VM.set(vm).is_err() {
+        return;
+    }
+    if env
+        .call_static_method("dev/opendata/NativeThreads", "register", "()V", &[])
+        .is_err()
+    {
+        let _ = env.exception_clear();
+    }
+}
+
+/// Stops attaching threads, as the JVM shuts down.
+pub(crate) fn shutdown() {
+    SHUTDOWN.store(true, Ordering::Release);
+}
+
+/// Runs `f` with the env of the current thread, attaching it first if
+/// needed.
+///
+/// Returns None without running `f` once the JVM is shutting down, before
+/// `init`, or if the thread cannot be attached.
+pub(crate) fn with_env<R>(f: impl FnOnce(&mut JNIEnv<'_>) -> R) -> Option<R> {
+    if SHUTDOWN.load(Ordering::Acquire) {
+        return None;
+    }
+    let cached = ENV.with(Cell::get);
+    if !cached.is_null() {
+        // SAFETY: the env was attached on this thread, which stays attached
+        // until detach_current_thread clears the cache
+        let mut env = unsafe { JNIEnv::from_raw(cached) }.ok()?;
+        return Some(f(&mut env));
+    }
+    let vm = VM.get()?;
+    // Threads attached by the JVM or other libraries are not cached, since
+    // they may detach without this module knowing
+    if let Ok(mut env) = vm.get_env() {
+        return Some(f(&mut env));
+    }
+    let mut env = vm.attach_current_thread_as_daemon().ok()?;
+    ENV.with(|env_cell| env_cell.set(env.get_raw()));
+    Some(f(&mut env))
+}
+
+/// Runs `f` in a local reference frame of `capacity` on the env of the
+/// current thread, clearing any exception it leaves.
+///
+/// Returns None if the thread has no env or `f` failed.
+pub(crate) fn call<R>(
+    capacity: i32,
+    f: impl FnOnce(&mut JNIEnv<'_>) -> jni::errors::Result<R>,
+) -> Option<R> {
+    with_env(|env| {
+        let result = env.with_local_frame(capacity, f);
+        if result.is_err() {
+            let _ = env.exception_clear();
+        }
+        result.ok()
+    })
+    .flatten()
+}
+
+/// Detaches the current thread if it was attached here.
+///
+/// Must only be called as the thread stops, when no local reference of its
+/// env remains.
+pub(crate) fn detach_current_thread() {
+    let attached = ENV.with(|env| env.replace(ptr::null_mut()));
+    if attached.is_null() || SHUTDOWN.load(Ordering::Acquire) {
+        return;
+    }
+    if let Some(vm) = VM.get() {
+        // SAFETY: per the contract, no reference of the thread's env is live
+        unsafe { vm.detach_current_thread() };
+    }
+}
+
+/// Appends the number of native threads attached to the JVM.
+pub(crate) fn append_stats(stats: &mut Stats) {
+    let attached = VM.get().map_or(0, |vm| vm.threads_attached());
+    stats.push(("jni.attached_threads".to_string(), attached as i64));
+}
@@ -0,0 +1,213 @@
+//! Bounded write queue for submitted appends.
+//!
+//! Appends submitted through the submission queue are spawned without the
+//! caller waiting, so without a bound a fast producer can queue unbounded
+//! records in native memory. With a write queue configured, each submitted
+//! append reserves its records and bytes until it completes; once the queue
+//! is full the configured policy blocks the submitter, rejects the append, or
+//! reports that the queue is not ready so Java can poll and retry.
+//!
+//! A batch larger than the whole queue is admitted once the queue is empty,
+//! so that it cannot wait forever.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Condvar, Mutex, PoisonError};
+
+use jni::sys::jint;
+
+use crate::metrics::Stats;
+
+/// What a submission does when the write queue is full.
+///
+/// Mirrors the Java `BackpressurePolicy` enum by ordinal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum BackpressurePolicy {
+    /// Block the submitting thread until space frees up
+    Block,
+    /// Throw `OpenDataBackpressureException`
+    Reject,
+    /// Return without submitting so the caller can retry
+    NotReady,
+}
+
+impl BackpressurePolicy {
+    /// Converts a Java `BackpressurePolicy.ordinal()` value into a policy.
+    pub(crate) fn from_ordinal(ordinal: jint) -> Option<Self> {
+        match ordinal {
+            0 => Some(Self::Block),
+            1 => Some(Self::Reject),
+            2 => Some(Self::NotReady),
+            _ => None,
+        }
+    }
+}
+
+/// Write queue settings extracted from a Java `WriteQueueConfig`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct WriteQueueSettings {
+    /// Records queued before the policy applies, or 0 for no limit
+    pub(crate) max_records: usize,
+    /// Key and value bytes queued before the policy applies, or 0 for no limit
+    pub(crate) max_bytes: usize,
+    pub(crate) policy: BackpressurePolicy,
+}
+
+#[derive(Default)]
+struct Queued {
+    records: usize,
+    bytes: usize,
+}
+
+/// Per-handle bound on records and bytes of submitted appends in flight.
+pub(crate) struct WriteQueue {
+    settings: WriteQueueSettings,
+    queued: Mutex<Queued>,
+    space: Condvar,
+    refused: AtomicU64,
+}
+
+/// Space reserved by one submitted append, released when dropped.
+pub(crate) struct Reservation {
+    queue: Arc<WriteQueue>,
+    records: usize,
+    bytes: usize,
+}
+
+impl WriteQueue {
+    pub(crate) fn new(settings: WriteQueueSettings) -> Self {
+        Self {
+            settings,
+            queued: Mutex::new(Queued::default()),
+            space: Condvar::new(),
+            refused: AtomicU64::new(0),
+        }
+    }
+
+    pub(crate) fn policy(&self) -> BackpressurePolicy {
+        self.settings.policy
+    }
+
+    /// Reserves space for an append, applying the policy if the queue is full.
+    ///
+    /// Blocks under `Block`; returns None under `Reject` and `NotReady`.
+    pub(crate) fn reserve(self: &Arc<Self>, records: usize, bytes: usize) -> Option<Reservation> {
+        let mut queued = self.lock();
+        while !self.fits(&queued, records, bytes) {
+            if self.settings.policy != BackpressurePolicy::Block {
+                self.refused.fetch_add(1, Ordering::Relaxed);
+                return None;
+            }
+            queued = self
+                .space
+                .wait(queued)
+                .unwrap_or_else(PoisonError::into_inner);
+        }
+        queued.records += records;
+        queued.bytes += bytes;
+        Some(Reservation {
+            queue: Arc::clone(self),
+            records,
+            bytes,
+        })
+    }
+
+    fn fits(&self, queued: &Queued, records: usize, bytes: usize) -> bool {
+        if queued.records == 0 {
+            return true;
+        }
+        let within = |limit: usize, used: usize, more: usize| limit == 0 || used + more <= limit;
+        within(self.settings.max_records, queued.records, records)
+            && within(self.settings.max_bytes, queued.bytes, bytes)
+    }
+
+    /// Appends the queued records and bytes and the submissions refused.
+    pub(crate) fn append_stats(&self, stats: &mut Stats) {
+        let queued = self.lock();
+        stats.push(("write_queue.records".to_string(), queued.records as i64));
+        stats.push(("write_queue.bytes".to_string(), queued.bytes as i64));
+        drop(queued);
+        stats.push((
+            "write_queue.refused".to_string(),
+            self.refused.load(Ordering::Relaxed) as i64,
+        ));
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Queued> {
+        self.queued.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl Drop for Reservation {
+    fn drop(&mut self) {
+        let mut queued = self.queue.lock();
+        queued.records -= self.records;
+        queued.bytes -= self.bytes;
+        drop(queued);
+        self.queue.space.notify_all();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    fn queue(max_records: usize, policy: BackpressurePolicy) -> Arc<WriteQueue> {
+        Arc::new(WriteQueue::new(WriteQueueSettings {
+            max_records,
+            max_bytes: 0,
+            policy,
+        }))
+    }
+
+    #[test]
+    fn should_refuse_append_beyond_limit_until_space_is_released() {
+        // given
+        let queue = queue(10, BackpressurePolicy::Reject);
+        let first = queue.reserve(8, 100).unwrap();
+
+        // when
+        let refused = queue.reserve(3, 100);
+        drop(first);
+        let admitted = queue.reserve(3, 100);
+
+        // then
+        assert!(refused.is_none());
+        assert!(admitted.is_some());
+        let mut stats = Stats::new();
+        queue.append_stats(&mut stats);
+        assert!(stats.contains(&("write_queue.records".to_string(), 3)));
+        assert!(stats.contains(&("write_queue.refused".to_string(), 1)));
+    }
+
+    #[test]
+    fn should_admit_oversized_append_into_empty_queue() {
+        // given
+        let queue = queue(10, BackpressurePolicy::NotReady);
+
+        // when
+        let reservation = queue.reserve(100, 0);
+
+        // then
+        assert!(reservation.is_some());
+    }
+
+    #[test]
+    fn should_block_until_space_is_released() {
+        // given
+        let queue = queue(10, BackpressurePolicy::Block);
+        let first = queue.reserve(10, 0).unwrap();
+        let releaser = std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(10));
+            drop(first);
+        });
+
+        // when
+        let second = queue.reserve(5, 0);
+        releaser.join().unwrap();
+
+        // then
+        assert!(second.is_some());
+    }
+}
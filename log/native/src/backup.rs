@@ -0,0 +1,262 @@
+//! Backups of a checkpoint to a second object store, and restores from them.
+//!
+//! A backup copies the objects of a log's SlateDB storage as of one of its
+//! checkpoints into a path of another object store, bucket or region,
+//! object by object. The checkpoint keeps every object its manifest
+//! references from garbage collection while the copy runs, so the copy needs
+//! no pause of the log. What the source holds beyond the checkpoint is left
+//! out, so the backup opens as the log stood at the checkpoint:
+//!
+//! - manifests newer than the one the checkpoint pins,
+//! - WAL SSTs written after the checkpoint was taken, which the restored log
+//!   would otherwise replay.
+//!
+//! WAL SSTs are told apart by their last-modified time in the object store
+//! against the checkpoint's creation time on this host, so a skewed clock
+//! moves the cut by the skew. The log is flushed before it is checkpointed,
+//! so appends acknowledged before the checkpoint are always in WAL SSTs older
+//! than it by that flush.
+//!
+//! Compacted SSTs are all copied; those no manifest of the backup references
+//! are removed by the first garbage collection of the restored log.
+//!
+//! A restore copies every object of a backup into an empty path, where the
+//! log then opens as usual. The backup itself is left as it was, so one
+//! backup can seed any number of identical logs.
+
+use std::sync::Arc;
+
+use bytes::Bytes;
+use common::storage::config::SlateDbStorageConfig;
+use futures::TryStreamExt;
+use object_store::path::Path;
+use object_store::{ObjectMeta, ObjectStore};
+
+use crate::checkpoints::{self, CheckpointInfo};
+
+/// Directory of SlateDB manifests under a path.
+const MANIFEST_DIR: &str = "manifest";
+
+/// Directory of SlateDB WAL SSTs under a path.
+const WAL_DIR: &str = "wal";
+
+/// How far a backup has got.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub(crate) struct BackupProgress {
+    pub(crate) objects_copied: u64,
+    pub(crate) objects_total: u64,
+    pub(crate) bytes_copied: u64,
+}
+
+/// Copies storage as of a checkpoint to a destination path, calling
+/// `progress` after each object.
+///
+/// Objects that vanish from the source between listing and copying are
+/// skipped: only those the checkpoint does not reference can.
+pub(crate) async fn backup(
+    source: &SlateDbStorageConfig,
+    checkpoint: &CheckpointInfo,
+    destination: &SlateDbStorageConfig,
+    mut progress: impl FnMut(BackupProgress),
+) -> Result<BackupProgress, String> {
+    let source_store = checkpoints::object_store(&source.object_store)?;
+    let destination_store = checkpoints::object_store(&destination.object_store)?;
+    let source_path = Path::from(source.path.as_str());
+    let destination_path = Path::from(destination.path.as_str());
+
+    let objects: Vec<ObjectMeta> = list(&source_store, &source_path)
+        .await?
+        .into_iter()
+        .filter(|object| {
+            relative(&source_path, &object.location).is_some_and(|name| {
+                in_checkpoint(&name, object.last_modified.timestamp_millis(), checkpoint)
+            })
+        })
+        .collect();
+    let mut copied = BackupProgress {
+        objects_total: objects.len() as u64,
+        ..BackupProgress::default()
+    };
+    progress(copied);
+    for object in objects {
+        let Some(name) = relative(&source_path, &object.location) else {
+            continue;
+        };
+        let target = child(&destination_path, &name);
+        match copy(&source_store, &object.location, &destination_store, &target).await? {
+            Some(bytes) => {
+                copied.objects_copied += 1;
+                copied.bytes_copied += bytes;
+            }
+            None => copied.objects_total -= 1,
+        }
+        progress(copied);
+    }
+    Ok(copied)
+}
+
+/// Copies every object of a backup to an empty path, returning the number
+/// copied.
+pub(crate) async fn restore(
+    backup: &SlateDbStorageConfig,
+    target: &SlateDbStorageConfig,
+) -> Result<u64, String> {
+    let backup_store = checkpoints::object_store(&backup.object_store)?;
+    let target_store = checkpoints::object_store(&target.object_store)?;
+    let backup_path = Path::from(backup.path.as_str());
+    let target_path = Path::from(target.path.as_str());
+
+    if !list(&target_store, &target_path).await?.is_empty() {
+        return Err(format!(
+            "Cannot restore into {}: it already holds data",
+            target.path
+        ));
+    }
+    let objects = list(&backup_store, &backup_path).await?;
+    if objects.is_empty() {
+        return Err(format!("No backup at {}", backup.path));
+    }
+    for object in &objects {
+        let Some(name) = relative(&backup_path, &object.location) else {
+            continue;
+        };
+        let target = child(&target_path, &name);
+        if copy(&backup_store, &object.location, &target_store, &target)
+            .await?
+            .is_none()
+        {
+            return Err(format!(
+                "{} was removed from the backup during the restore",
+                object.location
+            ));
+        }
+    }
+    Ok(objects.len() as u64)
+}
+
+/// Lists every object under a path.
+pub(crate) async fn list(
+    store: &Arc<dyn ObjectStore>,
+    path: &Path,
+) -> Result<Vec<ObjectMeta>, String> {
+    store
+        .list(Some(path))
+        .try_collect()
+        .await
+        .map_err(|e| format!("Failed to list {}: {}", path, e))
+}
+
+/// Copies one object, returning its size, or None if it no longer exists.
+pub(crate) async fn copy(
+    from: &Arc<dyn ObjectStore>,
+    location: &Path,
+    to: &Arc<dyn ObjectStore>,
+    target: &Path,
+) -> Result<Option<u64>, String> {
+    let bytes: Bytes = match from.get(location).await {
+        Ok(object) => object
+            .bytes()
+            .await
+            .map_err(|e| format!("Failed to read {}: {}", location, e))?,
+        Err(object_store::Error::NotFound { .. }) => return Ok(None),
+        Err(e) => return Err(format!("Failed to read {}: {}", location, e)),
+    };
+    let size = bytes.len() as u64;
+    to.put(target, bytes.into())
+        .await
+        .map_err(|e| format!("Failed to write {}: {}", target, e))?;
+    Ok(Some(size))
+}
+
+/// Returns an object's location relative to a path, as `/`-separated parts.
+pub(crate) fn relative(path: &Path, location: &Path) -> Option<String> {
+    let parts: Vec<String> = location
+        .prefix_match(path)?
+        .map(|part| part.as_ref().to_string())
+        .collect();
+    (!parts.is_empty()).then(|| parts.join("/"))
+}
+
+/// Returns the location of a relative name under a path.
+pub(crate) fn child(path: &Path, name: &str) -> Path {
+    name.split('/')
+        .fold(path.clone(), |location, part| location.child(part))
+}
+
+/// Returns whether an object, named relative to the storage path and last
+/// written at `last_modified_ms`, belongs in a backup of a checkpoint.
+fn in_checkpoint(name: &str, last_modified_ms: i64, checkpoint: &CheckpointInfo) -> bool {
+    let Some((dir, file)) = name.split_once('/') else {
+        return true;
+    };
+    match dir {
+        MANIFEST_DIR => {
+            object_id(file).map_or(true, |manifest_id| manifest_id <= checkpoint.manifest_id)
+        }
+        WAL_DIR => last_modified_ms <= checkpoint.create_time_ms,
+        _ => true,
+    }
+}
+
+/// Parses the numeric id of a manifest or WAL SST from its file name.
+fn object_id(file: &str) -> Option<u64> {
+    file.split_once('.').map_or(file, |(id, _)| id).parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use uuid::Uuid;
+
+    fn checkpoint(manifest_id: u64, create_time_ms: i64) -> CheckpointInfo {
+        CheckpointInfo {
+            id: Uuid::new_v4(),
+            name: Some("golden".to_string()),
+            manifest_id,
+            create_time_ms,
+            expire_time_ms: None,
+        }
+    }
+
+    #[test]
+    fn should_keep_manifests_up_to_the_checkpoint() {
+        let checkpoint = checkpoint(5, 1000);
+
+        let kept = |name: &str| in_checkpoint(name, 0, &checkpoint);
+
+        assert!(kept("manifest/00000000000000000004.manifest"));
+        assert!(kept("manifest/00000000000000000005.manifest"));
+        assert!(!kept("manifest/00000000000000000006.manifest"));
+    }
+
+    #[test]
+    fn should_keep_wal_written_before_the_checkpoint() {
+        let checkpoint = checkpoint(5, 1000);
+        let name = "wal/00000000000000000009.sst";
+
+        assert!(in_checkpoint(name, 1000, &checkpoint));
+        assert!(!in_checkpoint(name, 1001, &checkpoint));
+    }
+
+    #[test]
+    fn should_keep_compacted_ssts_whenever_written() {
+        let checkpoint = checkpoint(5, 1000);
+        let name = "compacted/01JEXAMPLE.sst";
+
+        assert!(in_checkpoint(name, 5000, &checkpoint));
+    }
+
+    #[test]
+    fn should_map_locations_between_paths() {
+        let location = child(&Path::from("source/log"), "wal/00000000000000000001.sst");
+
+        let name = relative(&Path::from("source/log"), &location).unwrap();
+
+        assert_eq!(name, "wal/00000000000000000001.sst");
+        assert_eq!(
+            child(&Path::from("backups/log"), &name).as_ref(),
+            "backups/log/wal/00000000000000000001.sst"
+        );
+        assert_eq!(relative(&Path::from("other"), &location), None);
+    }
+}
@@ -0,0 +1,152 @@
+//! In-process loopback benchmark.
+//!
+//! Runs a produce/consume loop entirely in Rust against a handle's LogDb so
+//! that its results can be compared with the same workload driven through JNI.
+//! The difference between the two isolates the cost of the binding.
+//!
+//! The producer appends fixed-size batches to a fresh reserved key while the
+//! consumer concurrently scans the key from its tail. Append latency covers
+//! each append call; end-to-end latency runs from just before a record's
+//! append until the consumer reads it. Both run on the calling task, and the
+//! consumer yields to the producer whenever it has caught up.
+
+use std::sync::{Mutex, PoisonError};
+use std::time::{Duration, Instant};
+
+use bytes::{BufMut, Bytes, BytesMut};
+use hdrhistogram::Histogram;
+use log::{LogDb, LogRead, Record};
+
+use crate::metrics;
+
+/// Prefix of the reserved keys benchmarks write to.
+const BENCH_KEY_PREFIX: &[u8] = b"\xff__opendata/bench/";
+
+/// Workload settings extracted from a Java `BenchConfig`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct BenchSettings {
+    /// Number of records per append
+    pub(crate) batch_size: usize,
+    /// Number of appends
+    pub(crate) batches: usize,
+}
+
+/// Outcome of a benchmark run.
+pub(crate) struct BenchReport {
+    /// Records produced and consumed
+    pub(crate) records: u64,
+    /// Payload bytes produced, excluding the timestamp headers
+    pub(crate) bytes: u64,
+    /// Wall-clock time from the first append until the last record was read
+    pub(crate) elapsed: Duration,
+    /// Latency of each append call
+    pub(crate) append: Histogram<u64>,
+    /// Latency from append until read, per record
+    pub(crate) end_to_end: Histogram<u64>,
+}
+
+/// Builds a reserved key unique to one benchmark run.
+pub(crate) fn bench_key(run_id: u64) -> Bytes {
+    let mut buffer = BytesMut::with_capacity(BENCH_KEY_PREFIX.len() + 8);
+    buffer.put_slice(BENCH_KEY_PREFIX);
+    buffer.put_u64(run_id);
+    buffer.freeze()
+}
+
+/// Runs the benchmark, appending `value` under `key` for every record.
+///
+/// `append_lock` is held shared around each append, like any other append
+/// through the handle.
+pub(crate) async fn run(
+    log: &LogDb,
+    append_lock: &tokio::sync::RwLock<()>,
+    key: Bytes,
+    value: Bytes,
+    payload_size: usize,
+    settings: BenchSettings,
+) -> Result<BenchReport, log::Error> {
+    let total = settings.batch_size * settings.batches;
+
+    // Start reading after anything already stored under the key
+    let mut next = 0;
+    let mut iter = log.scan(key.clone(), 0..).await?;
+    while let Some(entry) = iter.next().await? {
+        next = entry.sequence + 1;
+    }
+    drop(iter);
+
+    // Send time of each batch, pushed before its append so the consumer
+    // never reads a record whose batch is missing
+    let sent: Mutex<Vec<Instant>> = Mutex::new(Vec::with_capacity(settings.batches));
+    let mut append = metrics::new_histogram();
+    let mut end_to_end = metrics::new_histogram();
+    let started = Instant::now();
+
+    let produce = async {
+        for _ in 0..settings.batches {
+            let records = (0..settings.batch_size)
+                .map(|_| Record {
+                    key: key.clone(),
+                    value: value.clone(),
+                })
+                .collect();
+            let batch_started = Instant::now();
+            sent.lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .push(batch_started);
+            let _append = append_lock.read().await;
+            log.append(records).await?;
+            metrics::record_latency(&mut append, batch_started.elapsed());
+        }
+        Ok::<(), log::Error>(())
+    };
+
+    let consume = async {
+        let mut consumed = 0;
+        while consumed < total {
+            let mut iter = log.scan(key.clone(), next..).await?;
+            let mut caught_up = true;
+            while let Some(entry) = iter.next().await? {
+                let sent_at = sent.lock().unwrap_or_else(PoisonError::into_inner)
+                    [consumed / settings.batch_size];
+                metrics::record_latency(&mut end_to_end, sent_at.elapsed());
+                next = entry.sequence + 1;
+                consumed += 1;
+                caught_up = false;
+                if consumed == total {
+                    break;
+                }
+            }
+            if caught_up {
+                tokio::task::yield_now().await;
+            }
+        }
+        Ok::<(), log::Error>(())
+    };
+
+    tokio::try_join!(produce, consume)?;
+
+    Ok(BenchReport {
+        records: total as u64,
+        bytes: (total * payload_size) as u64,
+        elapsed: started.elapsed(),
+        append,
+        end_to_end,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_build_distinct_reserved_keys_per_run() {
+        // given / when
+        let a = bench_key(1);
+        let b = bench_key(2);
+
+        // then
+        assert!(a.starts_with(BENCH_KEY_PREFIX));
+        assert_ne!(a, b);
+    }
+}
@@ -0,0 +1,137 @@
+//! Bridge threads completing Java futures.
+//!
+//! By default a JNI call waits for its storage future in `Handle::block_on`,
+//! so the calling Java thread sits in a native frame for the whole storage
+//! round trip; a virtual thread doing so pins its carrier. With a bridge
+//! pool, the JNI call only copies its arguments and queues the future to a
+//! bounded pool of native bridge threads, then returns. A bridge thread awaits
+//! the future and completes a Java `CompletableFuture` with the result, which
+//! the caller waits on in Java, where a virtual thread unmounts instead of
+//! pinning.
+//!
+//! Bridge threads are attached to the JVM once, see `attach.rs`, and run
+//! each completion in its own local reference frame. Jobs queue in a bounded
+//! channel; a caller submitting to a full queue blocks until a bridge thread
+//! takes a job. Jobs queued or running are counted for the handle's pending
+//! operations.
+
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::{self, Receiver, SyncSender};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::thread::{self, JoinHandle};
+
+use jni::JNIEnv;
+use tokio::runtime::Handle;
+
+use crate::{attach, logging};
+
+/// SLF4J logger failed completions are logged under.
+const TARGET: &str = "dev.opendata.Bridge";
+
+/// Jobs queued per bridge thread before submitters block.
+const QUEUE_DEPTH_PER_THREAD: usize = 64;
+
+/// Local references a completion may create.
+const LOCAL_FRAME_CAPACITY: i32 = 16;
+
+/// Completes the Java side of a job once its future has finished.
+pub(crate) type Deliver = Box<dyn FnOnce(&mut JNIEnv<'_>) -> jni::errors::Result<()> + Send>;
+
+/// A storage future whose output completes a Java future.
+pub(crate) type Job = Pin<Box<dyn Future<Output = Deliver> + Send>>;
+
+/// Bounded pool of threads awaiting storage futures for Java callers.
+pub(crate) struct BridgePool {
+    jobs: Option<SyncSender<Job>>,
+    threads: Vec<JoinHandle<()>>,
+    /// Jobs queued or running
+    pending: Arc<AtomicU64>,
+}
+
+impl BridgePool {
+    /// Starts `threads` bridge threads awaiting futures on `runtime`.
+    pub(crate) fn start(threads: usize, runtime: Handle) -> std::io::Result<Self> {
+        let (jobs, receiver) = mpsc::sync_channel(threads * QUEUE_DEPTH_PER_THREAD);
+        let receiver = Arc::new(Mutex::new(receiver));
+        let mut pool = Self {
+            jobs: Some(jobs),
+            threads: Vec::with_capacity(threads),
+            pending: Arc::default(),
+        };
+        for i in 0..threads {
+            let receiver = Arc::clone(&receiver);
+            let runtime = runtime.clone();
+            let pending = Arc::clone(&pool.pending);
+            let thread = thread::Builder::new()
+                .name(format!("opendata-bridge-{}", i))
+                .spawn(move || {
+                    let attached = attach::with_env(|env| run(env, &receiver, &runtime, &pending));
+                    if attached.is_none() {
+                        logging::warn(TARGET, "Failed to attach a bridge thread to the JVM");
+                    }
+                    attach::detach_current_thread();
+                })?;
+            pool.threads.push(thread);
+        }
+        Ok(pool)
+    }
+
+    /// Queues a job, blocking while the queue is full.
+    ///
+    /// Returns false if the pool is shutting down and the job was dropped.
+    pub(crate) fn submit(&self, job: Job) -> bool {
+        self.pending.fetch_add(1, Ordering::Relaxed);
+        let queued = self
+            .jobs
+            .as_ref()
+            .is_some_and(|jobs| jobs.send(job).is_ok());
+        if !queued {
+            self.pending.fetch_sub(1, Ordering::Relaxed);
+        }
+        queued
+    }
+
+    /// Returns the jobs queued or running, including submitters blocked on a
+    /// full queue.
+    pub(crate) fn pending(&self) -> u64 {
+        self.pending.load(Ordering::Relaxed)
+    }
+}
+
+impl Drop for BridgePool {
+    /// Finishes every queued job, then stops the bridge threads.
+    fn drop(&mut self) {
+        drop(self.jobs.take());
+        for thread in self.threads.drain(..) {
+            let _ = thread.join();
+        }
+    }
+}
+
+/// Bridge thread loop: awaits queued jobs until the pool is dropped.
+fn run(
+    env: &mut JNIEnv<'_>,
+    receiver: &Mutex<Receiver<Job>>,
+    runtime: &Handle,
+    pending: &AtomicU64,
+) {
+    loop {
+        let job = match receiver
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .recv()
+        {
+            Ok(job) => job,
+            // All senders dropped: the pool is shutting down
+            Err(_) => return,
+        };
+        let deliver = runtime.block_on(job);
+        if let Err(e) = env.with_local_frame(LOCAL_FRAME_CAPACITY, |env| deliver(env)) {
+            let _ = env.exception_clear();
+            logging::warn(TARGET, &format!("Failed to complete a Java future: {}", e));
+        }
+        pending.fetch_sub(1, Ordering::Relaxed);
+    }
+}
@@ -0,0 +1,223 @@
+//! Named checkpoints of SlateDB storage.
+//!
+//! A SlateDB checkpoint pins a manifest, and with it every SST the manifest
+//! references, so the log can later be read, copied or cloned as it stood
+//! when the checkpoint was taken, whatever compaction and garbage collection
+//! do meanwhile. The upstream LogDb keeps its SlateDB instance to itself, so
+//! checkpoints go through SlateDB's admin API instead, on an object store
+//! built here from the config the log was opened with. The admin API adds
+//! a checkpoint by writing the next manifest with a conditional put, retried
+//! when the log's writer wins the race, so it runs safely next to the open
+//! log.
+//!
+//! A clone is a new log at another path of the same object store that starts
+//! from a checkpoint of its parent. SlateDB writes only the clone's manifest,
+//! which refers to the parent's SSTs, so cloning costs the same however much
+//! the log holds. Writes to either log are not seen by the other, and the
+//! parent keeps the SSTs its clones refer to, through the checkpoint, for as
+//! long as it exists.
+//!
+//! An in-memory object store is private to the store the log opened, so
+//! checkpoints and clones need a local or S3 object store.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use common::storage::config::{ObjectStoreConfig, SlateDbStorageConfig, StorageConfig};
+use object_store::aws::AmazonS3Builder;
+use object_store::local::LocalFileSystem;
+use object_store::ObjectStore;
+use slatedb::admin::{Admin, AdminBuilder};
+use slatedb::config::CheckpointOptions;
+use uuid::Uuid;
+
+/// A checkpoint of a log's storage.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct CheckpointInfo {
+    pub(crate) id: Uuid,
+    pub(crate) name: Option<String>,
+    /// Manifest the checkpoint pins
+    pub(crate) manifest_id: u64,
+    pub(crate) create_time_ms: i64,
+    /// When the checkpoint expires; None keeps it until it is deleted
+    pub(crate) expire_time_ms: Option<i64>,
+}
+
+/// Returns the SlateDB config of storage that checkpoints can be taken of.
+pub(crate) fn slatedb_storage(storage: &StorageConfig) -> Result<&SlateDbStorageConfig, String> {
+    match storage {
+        StorageConfig::SlateDb(slatedb)
+            if !matches!(slatedb.object_store, ObjectStoreConfig::InMemory) =>
+        {
+            Ok(slatedb)
+        }
+        StorageConfig::SlateDb(_) => Err(
+            "SlateDB storage on an in-memory object store cannot be reached from outside the log"
+                .to_string(),
+        ),
+        StorageConfig::InMemory => Err("In-memory storage has no SlateDB manifests".to_string()),
+    }
+}
+
+/// Builds an object store client for a config, like the log's own.
+pub(crate) fn object_store(config: &ObjectStoreConfig) -> Result<Arc<dyn ObjectStore>, String> {
+    match config {
+        ObjectStoreConfig::InMemory => {
+            Err("An in-memory object store cannot be shared with the log".to_string())
+        }
+        ObjectStoreConfig::Aws(aws) => AmazonS3Builder::from_env()
+            .with_region(&aws.region)
+            .with_bucket_name(&aws.bucket)
+            .build()
+            .map(|store| Arc::new(store) as Arc<dyn ObjectStore>)
+            .map_err(|e| format!("Failed to create S3 object store: {}", e)),
+        ObjectStoreConfig::Local(local) => {
+            std::fs::create_dir_all(&local.path)
+                .map_err(|e| format!("Failed to create {}: {}", local.path, e))?;
+            LocalFileSystem::new_with_prefix(&local.path)
+                .map(|store| Arc::new(store) as Arc<dyn ObjectStore>)
+                .map_err(|e| format!("Failed to create local object store: {}", e))
+        }
+    }
+}
+
+/// Returns SlateDB's admin API for storage.
+pub(crate) fn admin(slatedb: &SlateDbStorageConfig) -> Result<Admin, String> {
+    let object_store = object_store(&slatedb.object_store)?;
+    Ok(AdminBuilder::new(slatedb.path.as_str(), object_store).build())
+}
+
+/// Checkpoints the current manifest of storage under a name.
+///
+/// Entries the log has not flushed are not in any manifest yet; callers
+/// flush first.
+pub(crate) async fn create(
+    storage: &StorageConfig,
+    name: &str,
+    lifetime: Option<Duration>,
+) -> Result<CheckpointInfo, String> {
+    let admin = admin(slatedb_storage(storage)?)?;
+    let options = CheckpointOptions {
+        lifetime,
+        name: Some(name.to_string()),
+        ..CheckpointOptions::default()
+    };
+    let created = admin
+        .create_detached_checkpoint(&options)
+        .await
+        .map_err(|e| format!("Failed to create checkpoint {}: {}", name, e))?;
+    list_in(&admin, Some(name))
+        .await?
+        .into_iter()
+        .find(|checkpoint| checkpoint.id == created.id)
+        .ok_or_else(|| format!("Checkpoint {} vanished once created", created.id))
+}
+
+/// Lists the checkpoints of storage, oldest first.
+pub(crate) async fn list(storage: &StorageConfig) -> Result<Vec<CheckpointInfo>, String> {
+    let admin = admin(slatedb_storage(storage)?)?;
+    list_in(&admin, None).await
+}
+
+/// Finds a checkpoint of storage by id.
+pub(crate) async fn find(storage: &StorageConfig, id: Uuid) -> Result<CheckpointInfo, String> {
+    list(storage)
+        .await?
+        .into_iter()
+        .find(|checkpoint| checkpoint.id == id)
+        .ok_or_else(|| format!("No checkpoint {}", id))
+}
+
+/// Deletes a checkpoint of storage, releasing what it pins to garbage
+/// collection.
+pub(crate) async fn delete(storage: &StorageConfig, id: Uuid) -> Result<(), String> {
+    let admin = admin(slatedb_storage(storage)?)?;
+    admin
+        .delete_checkpoint(id)
+        .await
+        .map_err(|e| format!("Failed to delete checkpoint {}: {}", id, e))
+}
+
+async fn list_in(admin: &Admin, name: Option<&str>) -> Result<Vec<CheckpointInfo>, String> {
+    let mut checkpoints: Vec<CheckpointInfo> = admin
+        .list_checkpoints(name)
+        .await
+        .map_err(|e| format!("Failed to list checkpoints: {}", e))?
+        .into_iter()
+        .map(|checkpoint| CheckpointInfo {
+            id: checkpoint.id,
+            name: checkpoint.name,
+            manifest_id: checkpoint.manifest_id,
+            create_time_ms: checkpoint.create_time.timestamp_millis(),
+            expire_time_ms: checkpoint.expire_time.map(|t| t.timestamp_millis()),
+        })
+        .collect();
+    checkpoints.sort_by_key(|checkpoint| (checkpoint.create_time_ms, checkpoint.manifest_id));
+    Ok(checkpoints)
+}
+
+/// Clones storage to a new path of the same object store.
+///
+/// As with checkpoints, entries the log has not flushed are left out.
+pub(crate) async fn clone_to(storage: &StorageConfig, path: &str) -> Result<(), String> {
+    let parent = slatedb_storage(storage)?;
+    if path.trim_matches('/') == parent.path.trim_matches('/') {
+        return Err(format!("Cannot clone {} onto itself", parent.path));
+    }
+    let object_store = object_store(&parent.object_store)?;
+    AdminBuilder::new(path, object_store)
+        .build()
+        .create_clone(parent.path.as_str(), None)
+        .await
+        .map_err(|e| format!("Failed to clone {} to {}: {}", parent.path, path, e))
+}
+
+/// Parses a checkpoint id as given to Java.
+pub(crate) fn parse_id(id: &str) -> Result<Uuid, String> {
+    Uuid::parse_str(id).map_err(|e| format!("Invalid checkpoint id {:?}: {}", id, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use common::storage::config::LocalObjectStoreConfig;
+
+    fn local(path: &str) -> StorageConfig {
+        StorageConfig::SlateDb(SlateDbStorageConfig {
+            path: "log".to_string(),
+            object_store: ObjectStoreConfig::Local(LocalObjectStoreConfig {
+                path: path.to_string(),
+            }),
+            settings_path: None,
+        })
+    }
+
+    #[test]
+    fn should_accept_storage_on_a_shared_object_store() {
+        let storage = local("/tmp/objects");
+
+        let slatedb = slatedb_storage(&storage).unwrap();
+
+        assert_eq!(slatedb.path, "log");
+    }
+
+    #[test]
+    fn should_reject_storage_private_to_the_log() {
+        let in_memory_store = StorageConfig::SlateDb(SlateDbStorageConfig {
+            path: "log".to_string(),
+            object_store: ObjectStoreConfig::InMemory,
+            settings_path: None,
+        });
+
+        assert!(slatedb_storage(&StorageConfig::InMemory).is_err());
+        assert!(slatedb_storage(&in_memory_store).is_err());
+    }
+
+    #[test]
+    fn should_parse_ids_as_formatted() {
+        let id = Uuid::new_v4();
+
+        assert_eq!(parse_id(&id.to_string()).unwrap(), id);
+        assert!(parse_id("not-a-checkpoint").is_err());
+    }
+}
@@ -0,0 +1,305 @@
+//! Splitting of large values into chunks, and their reassembly on scan.
+//!
+//! Values of hundreds of megabytes make for oversized memtables, SSTs and
+//! object-store puts. With a chunk threshold configured, an appended value
+//! whose payload exceeds it is stored as a run of chunk entries under the
+//! record's key, each carrying at most the threshold's worth of payload.
+//! The chunks of a record are appended in the same batch, so they take
+//! consecutive sequences. Scans reassemble runs into one entry with the
+//! sequence of the run's first chunk; a scan starting inside a run skips the
+//! rest of it, so paging from the sequence after the last entry scanned
+//! never returns a fragment.
+//!
+//! A chunk is stored in an extended envelope: the envelope magic and the
+//! chunk version precede the record's timestamp header, which is followed by
+//! the chunk's index and the run's chunk count:
+//!
+//! ```text
+//! ┌────────────┬─────────────┬───────────────────┬───────────┬───────────┬───────┐
+//! │ magic (7B) │ version (1B)│ timestamp_ms (8B) │ index (4B)│ count (4B)│ piece │
+//! └────────────┴─────────────┴───────────────────┴───────────┴───────────┴───────┘
+//! ```
+//!
+//! The timestamp is stored as is, so no regular entry reads as a chunk,
+//! whatever its timestamp, short of the few `ENVELOPE_MAGIC` describes.
+//!
+//! Reassembly is driven by the envelope alone, so readers and handles
+//! without a threshold read chunked values too. Values reach the native
+//! layer whole, so chunking does not shrink the JNI copy of a value. Ring
+//! appends and appends through the C ABI leave values whole.
+
+use bytes::BytesMut;
+use log::{LogEntry, Record};
+
+use crate::{
+    envelope_prefix, envelope_version, extract_timestamp_and_payload, ENVELOPE_PREFIX_SIZE,
+    TIMESTAMP_HEADER_SIZE,
+};
+
+/// Version of the extended envelope of a chunk.
+const CHUNK_VERSION: u8 = 1;
+
+/// Size of a chunk's envelope, up to its piece.
+const CHUNK_HEADER_SIZE: usize = ENVELOPE_PREFIX_SIZE + TIMESTAMP_HEADER_SIZE + 8;
+
+/// Splits records whose payload exceeds `threshold` bytes into chunk
+/// records; a threshold of 0 leaves records whole.
+pub(crate) fn split(records: Vec<Record>, threshold: usize) -> Vec<Record> {
+    if threshold == 0
+        || records
+            .iter()
+            .all(|r| r.value.len() <= TIMESTAMP_HEADER_SIZE + threshold)
+    {
+        return records;
+    }
+    let mut split = Vec::with_capacity(records.len());
+    for record in records {
+        let (timestamp_ms, payload) = extract_timestamp_and_payload(&record.value);
+        if payload.len() <= threshold {
+            split.push(record);
+            continue;
+        }
+        let count = payload.len().div_ceil(threshold);
+        for (index, piece) in payload.chunks(threshold).enumerate() {
+            let mut value = BytesMut::with_capacity(CHUNK_HEADER_SIZE + piece.len());
+            value.extend_from_slice(&envelope_prefix(CHUNK_VERSION));
+            value.extend_from_slice(&timestamp_ms.to_be_bytes());
+            value.extend_from_slice(&(index as u32).to_be_bytes());
+            value.extend_from_slice(&(count as u32).to_be_bytes());
+            value.extend_from_slice(piece);
+            split.push(Record {
+                key: record.key.clone(),
+                value: value.freeze(),
+            });
+        }
+    }
+    split
+}
+
+/// Returns the number of entries `record` is stored as under `threshold`.
+pub(crate) fn entry_count(record: &Record, threshold: usize) -> usize {
+    let payload = record.value.len().saturating_sub(TIMESTAMP_HEADER_SIZE);
+    if threshold == 0 || payload <= threshold {
+        1
+    } else {
+        payload.div_ceil(threshold)
+    }
+}
+
+/// A chunk entry's fields.
+struct Chunk<'a> {
+    timestamp_ms: i64,
+    index: u32,
+    count: u32,
+    piece: &'a [u8],
+}
+
+/// Parses a chunk entry, or returns None for a regular one.
+fn parse_chunk(value: &[u8]) -> Option<Chunk<'_>> {
+    if value.len() < CHUNK_HEADER_SIZE || envelope_version(value) != Some(CHUNK_VERSION) {
+        return None;
+    }
+    let fields = &value[ENVELOPE_PREFIX_SIZE..CHUNK_HEADER_SIZE];
+    Some(Chunk {
+        timestamp_ms: i64::from_be_bytes(fields[..8].try_into().expect("8 bytes")),
+        index: u32::from_be_bytes(fields[8..12].try_into().expect("4 bytes")),
+        count: u32::from_be_bytes(fields[12..16].try_into().expect("4 bytes")),
+        piece: &value[CHUNK_HEADER_SIZE..],
+    })
+}
+
+/// A run being reassembled.
+struct Run {
+    first: LogEntry,
+    count: u32,
+    next_index: u32,
+    value: BytesMut,
+}
+
+/// Reassembles the chunk runs of scanned entries, passing regular entries
+/// through.
+#[derive(Default)]
+pub(crate) struct Assembler {
+    entries: Vec<LogEntry>,
+    run: Option<Run>,
+}
+
+impl Assembler {
+    /// Adds the next scanned entry.
+    pub(crate) fn push(&mut self, entry: LogEntry) {
+        let Some(chunk) = parse_chunk(&entry.value) else {
+            // A regular entry ends any run, which cannot be complete
+            self.run = None;
+            self.entries.push(entry);
+            return;
+        };
+        let continues = self
+            .run
+            .as_ref()
+            .is_some_and(|run| run.first.key == entry.key && run.next_index == chunk.index);
+        if !continues {
+            self.run = None;
+            // Chunks past the first of a run cut by the scan start are skipped
+            if chunk.index != 0 {
+                return;
+            }
+            let mut value = BytesMut::new();
+            value.extend_from_slice(&chunk.timestamp_ms.to_be_bytes());
+            self.run = Some(Run {
+                first: entry.clone(),
+                count: chunk.count,
+                next_index: 0,
+                value,
+            });
+        }
+        let run = self.run.as_mut().expect("run started above");
+        run.value.extend_from_slice(chunk.piece);
+        run.next_index += 1;
+        if run.next_index == run.count {
+            let run = self.run.take().expect("run present");
+            self.entries.push(LogEntry {
+                value: run.value.freeze(),
+                ..run.first
+            });
+        }
+    }
+
+    /// Returns the number of complete entries.
+    pub(crate) fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Drops the most recently completed entry unless `keep` accepts it.
+    pub(crate) fn keep_newest_if(&mut self, keep: impl FnOnce(&LogEntry) -> bool) {
+        if self.entries.last().is_some_and(|entry| !keep(entry)) {
+            self.entries.pop();
+        }
+    }
+
+    /// Takes the complete entries, leaving a run in progress.
+    pub(crate) fn take(&mut self) -> Vec<LogEntry> {
+        std::mem::take(&mut self.entries)
+    }
+
+    /// Returns the complete entries, dropping a run cut short.
+    pub(crate) fn finish(self) -> Vec<LogEntry> {
+        self.entries
+    }
+}
+
+/// Reassembles entries, dropping runs they cut short.
+pub(crate) fn assemble(entries: impl IntoIterator<Item = LogEntry>, max: usize) -> Vec<LogEntry> {
+    let mut assembler = Assembler::default();
+    for entry in entries {
+        if assembler.len() >= max {
+            break;
+        }
+        assembler.push(entry);
+    }
+    assembler.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+
+    use super::*;
+
+    fn record(timestamp_ms: i64, payload: &[u8]) -> Record {
+        let mut value = timestamp_ms.to_be_bytes().to_vec();
+        value.extend_from_slice(payload);
+        Record {
+            key: Bytes::from_static(b"key"),
+            value: Bytes::from(value),
+        }
+    }
+
+    fn entries(records: Vec<Record>) -> Vec<LogEntry> {
+        records
+            .into_iter()
+            .enumerate()
+            .map(|(sequence, record)| LogEntry {
+                key: record.key,
+                sequence: sequence as u64,
+                value: record.value,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn should_split_large_values_and_reassemble_them() {
+        // given
+        let records = vec![
+            record(7, b"abc"),
+            record(8, b"abcdefghij"),
+            record(9, b"tail"),
+        ];
+
+        // when
+        let split = split(records.clone(), 4);
+        let scanned = assemble(entries(split.clone()), usize::MAX);
+
+        // then
+        assert_eq!(split.len(), 5);
+        assert_eq!(scanned.len(), 3);
+        assert_eq!(scanned[1].sequence, 1);
+        assert_eq!(scanned[1].value, records[1].value);
+        assert_eq!(scanned[2].sequence, 4);
+        assert_eq!(scanned[2].value, records[2].value);
+    }
+
+    #[test]
+    fn should_skip_run_cut_by_scan_start() {
+        // given
+        let split = split(vec![record(1, b"abcdefgh"), record(2, b"next")], 4);
+
+        // when
+        let scanned = assemble(entries(split).into_iter().skip(1), usize::MAX);
+
+        // then
+        assert_eq!(scanned.len(), 1);
+        assert_eq!(scanned[0].value, record(2, b"next").value);
+    }
+
+    #[test]
+    fn should_pass_through_entries_with_negative_timestamps() {
+        // given
+        let regular = vec![
+            record(-1, b"\x00\x00\x00\x00\x00\x00\x00\x01payload"),
+            record(i64::MIN, b"\x00\x00\x00\x00\x00\x00\x00\x01payload"),
+        ];
+
+        // when
+        let scanned = assemble(entries(regular.clone()), usize::MAX);
+
+        // then
+        assert_eq!(scanned.len(), 2);
+        assert_eq!(scanned[0].value, regular[0].value);
+        assert_eq!(scanned[1].value, regular[1].value);
+    }
+
+    #[test]
+    fn should_split_values_with_negative_timestamps() {
+        // given
+        let records = vec![record(-7, b"abcdefghij")];
+
+        // when
+        let scanned = assemble(entries(split(records.clone(), 4)), usize::MAX);
+
+        // then
+        assert_eq!(scanned.len(), 1);
+        assert_eq!(scanned[0].value, records[0].value);
+    }
+
+    #[test]
+    fn should_drop_run_cut_by_scan_end() {
+        // given
+        let split = split(vec![record(1, b"whole"), record(2, b"abcdefgh")], 5);
+
+        // when
+        let scanned = assemble(entries(split).into_iter().take(2), usize::MAX);
+
+        // then
+        assert_eq!(scanned.len(), 1);
+    }
+}
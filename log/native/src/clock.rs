@@ -0,0 +1,200 @@
+//! Source of the timestamps stored with appended records.
+//!
+//! By default a record keeps the `timestampMs` Java gave it, usually the
+//! time it was created, which measures end-to-end latency but trusts every
+//! producer's clock. A handle can instead stamp records itself when they
+//! reach the native layer, giving Kafka's `LogAppendTime` semantics: every
+//! record of a batch gets the same timestamp, taken as the batch is copied
+//! out of Java, and `AppendResult.timestamp()` reports it.
+//!
+//! - A wall clock reads the system time at each append.
+//! - A monotonic clock reads the system time once, when the handle opens,
+//!   and advances it by monotonic time, so stamps never go backwards when
+//!   the system time is stepped.
+//! - A manual clock only moves when set or advanced through JNI, so tests
+//!   can assert exact timestamps.
+//!
+//! Synthetic appends take their timestamp from the clock too. Records
+//! appended through an `AppendRing` or the C ABI, and imported NDJSON
+//! entries, keep their own timestamps.
+
+use std::sync::atomic::{AtomicI64, Ordering};
+use std::time::Instant;
+
+use jni::sys::jint;
+
+use crate::current_timestamp_ms;
+
+/// Kind of clock a handle stamps records with.
+///
+/// Mirrors the Java `ClockSource` enum by ordinal.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) enum ClockSource {
+    /// Records keep the timestamp they were created with
+    #[default]
+    Record,
+    /// The system time at each append
+    Wall,
+    /// The system time at open advanced by monotonic time
+    Monotonic,
+    /// A time set and advanced through JNI, starting at 0
+    Manual,
+}
+
+impl ClockSource {
+    /// Converts a Java `ClockSource.ordinal()` value into a source.
+    pub(crate) fn from_ordinal(ordinal: jint) -> Option<Self> {
+        match ordinal {
+            0 => Some(Self::Record),
+            1 => Some(Self::Wall),
+            2 => Some(Self::Monotonic),
+            3 => Some(Self::Manual),
+            _ => None,
+        }
+    }
+
+    /// Parses a config file name of a source.
+    pub(crate) fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "record" => Some(Self::Record),
+            "wall" => Some(Self::Wall),
+            "monotonic" => Some(Self::Monotonic),
+            "manual" => Some(Self::Manual),
+            _ => None,
+        }
+    }
+}
+
+/// Timestamps a handle writes into the values of an appended batch.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) struct Stamps {
+    /// Timestamp replacing each record's own, if the clock assigns them
+    pub(crate) record_ms: Option<i64>,
+    /// Receive time recorded after the timestamp, if configured
+    pub(crate) received_ms: Option<i64>,
+}
+
+/// The clock of a handle.
+#[derive(Debug)]
+pub(crate) enum Clock {
+    Record,
+    Wall,
+    Monotonic { base_ms: i64, started: Instant },
+    Manual(AtomicI64),
+}
+
+impl Clock {
+    /// Starts a clock of the given kind.
+    pub(crate) fn start(source: ClockSource) -> Self {
+        match source {
+            ClockSource::Record => Self::Record,
+            ClockSource::Wall => Self::Wall,
+            ClockSource::Monotonic => Self::Monotonic {
+                base_ms: current_timestamp_ms(),
+                started: Instant::now(),
+            },
+            ClockSource::Manual => Self::Manual(AtomicI64::new(0)),
+        }
+    }
+
+    /// Returns the time of the clock, in milliseconds since the epoch; the
+    /// system time for a clock leaving records their own timestamps.
+    pub(crate) fn now_ms(&self) -> i64 {
+        match self {
+            Self::Record | Self::Wall => current_timestamp_ms(),
+            Self::Monotonic { base_ms, started } => base_ms + started.elapsed().as_millis() as i64,
+            Self::Manual(now_ms) => now_ms.load(Ordering::Acquire),
+        }
+    }
+
+    /// Whether the clock stamps appended records, rather than leaving them
+    /// their own timestamps.
+    pub(crate) fn assigns(&self) -> bool {
+        !matches!(self, Self::Record)
+    }
+
+    /// Returns the stamps of a batch arriving now, with its receive time if
+    /// `receive_times`, see `skew`.
+    pub(crate) fn stamps(&self, receive_times: bool) -> Stamps {
+        let now_ms = self.now_ms();
+        Stamps {
+            record_ms: self.assigns().then_some(now_ms),
+            received_ms: receive_times.then_some(now_ms),
+        }
+    }
+
+    /// Sets a manual clock, returning false for other clocks.
+    pub(crate) fn set(&self, timestamp_ms: i64) -> bool {
+        match self {
+            Self::Manual(now_ms) => {
+                now_ms.store(timestamp_ms, Ordering::Release);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Advances a manual clock, returning its new time, or None for other
+    /// clocks.
+    pub(crate) fn advance(&self, delta_ms: i64) -> Option<i64> {
+        match self {
+            Self::Manual(now_ms) => Some(
+                now_ms
+                    .fetch_add(delta_ms, Ordering::AcqRel)
+                    .saturating_add(delta_ms),
+            ),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_map_java_ordinals_to_sources() {
+        assert_eq!(ClockSource::from_ordinal(0), Some(ClockSource::Record));
+        assert_eq!(ClockSource::from_ordinal(3), Some(ClockSource::Manual));
+        assert_eq!(ClockSource::from_ordinal(4), None);
+    }
+
+    #[test]
+    fn should_stamp_batches_only_when_assigning_timestamps() {
+        // given
+        let record = Clock::start(ClockSource::Record);
+        let manual = Clock::start(ClockSource::Manual);
+
+        // when
+        manual.set(1_000);
+        let advanced = manual.advance(250);
+
+        // then
+        assert_eq!(record.stamps(false).record_ms, None);
+        assert_eq!(advanced, Some(1_250));
+        assert_eq!(
+            manual.stamps(true),
+            Stamps {
+                record_ms: Some(1_250),
+                received_ms: Some(1_250),
+            }
+        );
+        assert!(!record.set(1_000));
+    }
+
+    #[test]
+    fn should_start_monotonic_clock_at_wall_time() {
+        // given
+        let before = current_timestamp_ms();
+
+        // when
+        let stamp = Clock::start(ClockSource::Monotonic)
+            .stamps(false)
+            .record_ms
+            .unwrap();
+
+        // then
+        assert!(stamp >= before);
+        assert!(stamp <= current_timestamp_ms());
+    }
+}
@@ -0,0 +1,110 @@
+//! Closes of handles that must not block the calling thread.
+//!
+//! `nativeClose` waits for in-flight calls, SlateDB's final flush and the
+//! runtimes to stop on the calling thread. That is wrong for a
+//! `java.lang.ref.Cleaner` action, which runs on the cleaner's single thread
+//! and must return promptly, and for callers that only want the handle gone.
+//! `nativeCloseAsync` instead hands the handle to a dedicated
+//! `opendata-close` thread, which closes it exactly like `nativeClose`, and
+//! returns a token at once. `nativeAwaitClose` waits for the close of a
+//! token, for tests and orderly shutdowns.
+//!
+//! Closes run on their own thread rather than as a task on the handle's
+//! runtime, since a runtime cannot be shut down from one of its own tasks.
+//! A failed close is logged as a warning under the handle's tags, as no Java
+//! caller is left to throw to.
+
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Condvar, Mutex, MutexGuard, PoisonError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::attach;
+
+/// Token of the next close; 0 is never handed out.
+static NEXT_TOKEN: AtomicU64 = AtomicU64::new(1);
+
+/// Tokens of the closes still running.
+static PENDING: Mutex<Option<HashSet<u64>>> = Mutex::new(None);
+
+/// Signalled whenever a close finishes.
+static FINISHED: Condvar = Condvar::new();
+
+/// Runs `close` on a new close thread, returning its token.
+pub(crate) fn spawn(close: impl FnOnce() + Send + 'static) -> std::io::Result<u64> {
+    let token = NEXT_TOKEN.fetch_add(1, Ordering::Relaxed);
+    lock().get_or_insert_with(HashSet::new).insert(token);
+    let spawned = thread::Builder::new()
+        .name("opendata-close".to_string())
+        .spawn(move || {
+            close();
+            attach::detach_current_thread();
+            finish(token);
+        });
+    if let Err(e) = spawned {
+        finish(token);
+        return Err(e);
+    }
+    Ok(token)
+}
+
+/// Waits up to `timeout` for the close of `token`, returning whether it
+/// finished. Unknown tokens count as finished.
+pub(crate) fn wait(token: u64, timeout: Duration) -> bool {
+    let deadline = Instant::now() + timeout;
+    let mut pending = lock();
+    while pending.as_ref().is_some_and(|p| p.contains(&token)) {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        if remaining.is_zero() {
+            return false;
+        }
+        pending = FINISHED
+            .wait_timeout(pending, remaining)
+            .unwrap_or_else(PoisonError::into_inner)
+            .0;
+    }
+    true
+}
+
+fn finish(token: u64) {
+    if let Some(pending) = lock().as_mut() {
+        pending.remove(&token);
+    }
+    FINISHED.notify_all();
+}
+
+fn lock() -> MutexGuard<'static, Option<HashSet<u64>>> {
+    PENDING.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::mpsc;
+
+    use super::*;
+
+    #[test]
+    fn should_wait_for_spawned_close() {
+        // given
+        let (release, released) = mpsc::channel::<()>();
+        let token = spawn(move || {
+            let _ = released.recv();
+        })
+        .unwrap();
+
+        // when
+        let before_release = wait(token, Duration::from_millis(10));
+        release.send(()).unwrap();
+        let after_release = wait(token, Duration::from_secs(5));
+
+        // then
+        assert!(!before_release);
+        assert!(after_release);
+    }
+
+    #[test]
+    fn should_treat_unknown_token_as_finished() {
+        assert!(wait(u64::MAX, Duration::ZERO));
+    }
+}
@@ -0,0 +1,214 @@
+//! Group commit of appends from concurrent Java threads.
+//!
+//! With coalescing configured, a blocking append does not call
+//! `LogDb::append` itself. Instead it adds its records to the current window
+//! and waits. The first append into an empty window spawns a task that waits
+//! until the window expires or reaches its record or byte limit, then appends
+//! every record in the window as a single batch and hands each caller the
+//! sequence of its first record. Many small producers thereby share one
+//! storage append instead of contending for one each.
+//!
+//! Records of one call stay contiguous and in order. A window containing any
+//! durable append is flushed before any of its callers is acknowledged.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::Duration;
+
+use log::{LogDb, Record};
+use tokio::sync::{oneshot, Notify, RwLock};
+
+use crate::metrics::Stats;
+use crate::tailcache::TailCache;
+use crate::watch::Watches;
+use crate::AckMode;
+
+/// Coalescing settings extracted from a Java `CoalesceConfig`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct CoalesceSettings {
+    /// How long a window stays open after its first append
+    pub(crate) window: Duration,
+    /// Records that close a window early
+    pub(crate) max_records: usize,
+    /// Key and value bytes that close a window early
+    pub(crate) max_bytes: usize,
+}
+
+/// Records gathered in the open window, with the callers waiting on them.
+#[derive(Default)]
+struct Window {
+    records: Vec<Record>,
+    bytes: usize,
+    durable: bool,
+    /// Offset of each caller's first record, and where to send its sequence
+    waiters: Vec<(u64, oneshot::Sender<Result<u64, String>>)>,
+}
+
+impl Window {
+    fn is_full(&self, settings: &CoalesceSettings) -> bool {
+        self.records.len() >= settings.max_records || self.bytes >= settings.max_bytes
+    }
+}
+
+/// Per-handle write coalescer.
+pub(crate) struct Coalescer {
+    settings: CoalesceSettings,
+    /// Tail cache of the handle, which windows are appended through
+    tail_cache: Option<Arc<TailCache>>,
+    /// Watches of the handle, notified of appended windows
+    watches: Arc<Watches>,
+    window: Mutex<Window>,
+    full: Notify,
+    batches: AtomicU64,
+    records: AtomicU64,
+}
+
+impl Coalescer {
+    pub(crate) fn new(
+        settings: CoalesceSettings,
+        tail_cache: Option<Arc<TailCache>>,
+        watches: Arc<Watches>,
+    ) -> Self {
+        Self {
+            settings,
+            tail_cache,
+            watches,
+            window: Mutex::new(Window::default()),
+            full: Notify::new(),
+            batches: AtomicU64::new(0),
+            records: AtomicU64::new(0),
+        }
+    }
+
+    /// Appends `records` as part of the current window, returning the
+    /// sequence assigned to the first of them.
+    ///
+    /// Must be called within the runtime the window task is spawned on.
+    pub(crate) async fn append(
+        self: &Arc<Self>,
+        log: &Arc<LogDb>,
+        append_lock: &Arc<RwLock<()>>,
+        records: Vec<Record>,
+        ack_mode: AckMode,
+    ) -> Result<u64, String> {
+        let (sender, receiver) = oneshot::channel();
+        let opens_window = {
+            let mut window = self.lock();
+            let opens_window = window.waiters.is_empty();
+            window.waiters.push((window.records.len() as u64, sender));
+            window.bytes += records
+                .iter()
+                .map(|r| r.key.len() + r.value.len())
+                .sum::<usize>();
+            window.records.extend(records);
+            window.durable |= ack_mode == AckMode::Durable;
+            if !opens_window && window.is_full(&self.settings) {
+                self.full.notify_one();
+            }
+            opens_window
+        };
+        if opens_window {
+            // Runs detached so that a caller giving up cannot strand the window
+            tokio::spawn(Arc::clone(self).close_window(Arc::clone(log), Arc::clone(append_lock)));
+        }
+        receiver
+            .await
+            .unwrap_or_else(|_| Err("Coalesced append was dropped".to_string()))
+    }
+
+    /// Waits for the open window to fill or expire, then appends it.
+    async fn close_window(self: Arc<Self>, log: Arc<LogDb>, append_lock: Arc<RwLock<()>>) {
+        let full = self.lock().is_full(&self.settings);
+        if !full {
+            let _ = tokio::time::timeout(self.settings.window, self.full.notified()).await;
+        }
+        let window = std::mem::take(&mut *self.lock());
+        self.batches.fetch_add(1, Ordering::Relaxed);
+        self.records
+            .fetch_add(window.records.len() as u64, Ordering::Relaxed);
+
+        let ack_mode = if window.durable {
+            AckMode::Durable
+        } else {
+            AckMode::Buffered
+        };
+        let result = crate::append_records(
+            &log,
+            &append_lock,
+            self.tail_cache.as_deref(),
+            &self.watches,
+            window.records,
+            ack_mode,
+        )
+        .await
+        .map_err(|e| e.to_string());
+        for (offset, waiter) in window.waiters {
+            let _ = waiter.send(result.clone().map(|start| start + offset));
+        }
+    }
+
+    /// Appends the number of coalesced batches and the records they carried.
+    pub(crate) fn append_stats(&self, stats: &mut Stats) {
+        stats.push((
+            "coalesce.batches".to_string(),
+            self.batches.load(Ordering::Relaxed) as i64,
+        ));
+        stats.push((
+            "coalesce.records".to_string(),
+            self.records.load(Ordering::Relaxed) as i64,
+        ));
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Window> {
+        self.window.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+
+    use super::*;
+
+    fn record(value: &'static [u8]) -> Record {
+        Record {
+            key: Bytes::from_static(b"key"),
+            value: Bytes::from_static(value),
+        }
+    }
+
+    fn settings(max_records: usize) -> CoalesceSettings {
+        CoalesceSettings {
+            window: Duration::from_secs(60),
+            max_records,
+            max_bytes: usize::MAX,
+        }
+    }
+
+    #[test]
+    fn should_close_window_at_record_limit() {
+        // given
+        let mut window = Window::default();
+        window.records.push(record(b"a"));
+
+        // when / then
+        assert!(!window.is_full(&settings(2)));
+        window.records.push(record(b"b"));
+        assert!(window.is_full(&settings(2)));
+    }
+
+    #[test]
+    fn should_close_window_at_byte_limit() {
+        // given
+        let window = Window {
+            bytes: 100,
+            ..Window::default()
+        };
+
+        // when / then
+        assert!(window.is_full(&CoalesceSettings {
+            max_bytes: 100,
+            ..settings(usize::MAX)
+        }));
+    }
+}
@@ -0,0 +1,260 @@
+//! Submission/completion queues for asynchronous operations.
+//!
+//! Instead of blocking in `block_on` for every call, a Java thread can submit
+//! appends and scans, each of which is spawned onto the handle's runtime and
+//! identified by a ticket, and later poll for completions, io_uring-style.
+//! One Java thread can thereby keep thousands of operations in flight.
+//! Completions are queued in the order operations finish, which need not be
+//! the order they were submitted in.
+
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Condvar, Mutex, PoisonError};
+use std::time::{Duration, Instant};
+
+use log::LogEntry;
+
+use crate::memory;
+use crate::metrics::Stats;
+
+/// Result of a successful asynchronous operation.
+pub(crate) enum Outcome {
+    Append {
+        start_sequence: u64,
+        timestamp_ms: i64,
+    },
+    Scan(Vec<LogEntry>),
+}
+
+/// A finished asynchronous operation.
+pub(crate) struct Completion {
+    pub(crate) ticket: u64,
+    pub(crate) result: Result<Outcome, String>,
+}
+
+/// Per-handle queue of finished operations.
+pub(crate) struct CompletionQueue {
+    next_ticket: AtomicU64,
+    in_flight: AtomicU64,
+    completed: Mutex<VecDeque<Completion>>,
+    ready: Condvar,
+}
+
+impl Default for CompletionQueue {
+    fn default() -> Self {
+        Self {
+            // Ticket 0 is never issued
+            next_ticket: AtomicU64::new(1),
+            in_flight: AtomicU64::new(0),
+            completed: Mutex::new(VecDeque::new()),
+            ready: Condvar::new(),
+        }
+    }
+}
+
+impl CompletionQueue {
+    /// Issues the ticket of a newly submitted operation.
+    pub(crate) fn submit(&self) -> u64 {
+        self.in_flight.fetch_add(1, Ordering::Relaxed);
+        self.next_ticket.fetch_add(1, Ordering::Relaxed)
+    }
+
+    /// Queues the result of the operation with `ticket`.
+    pub(crate) fn complete(&self, ticket: u64, result: Result<Outcome, String>) {
+        let mut completed = self.lock();
+        completed.push_back(Completion { ticket, result });
+        self.in_flight.fetch_sub(1, Ordering::Relaxed);
+        drop(completed);
+        self.ready.notify_all();
+    }
+
+    /// Takes up to `max` completions, waiting up to `timeout` for the first one.
+    pub(crate) fn poll(&self, max: usize, timeout: Duration) -> Vec<Completion> {
+        let deadline = Instant::now() + timeout;
+        let mut completed = self.lock();
+        while completed.is_empty() {
+            let now = Instant::now();
+            if now >= deadline {
+                return Vec::new();
+            }
+            completed = self
+                .ready
+                .wait_timeout(completed, deadline - now)
+                .unwrap_or_else(PoisonError::into_inner)
+                .0;
+        }
+        let count = max.min(completed.len());
+        completed.drain(..count).collect()
+    }
+
+    /// Waits until no operation is in flight, returning false if some still
+    /// are at `deadline`.
+    pub(crate) fn wait_idle(&self, deadline: Instant) -> bool {
+        let mut completed = self.lock();
+        while self.in_flight.load(Ordering::Relaxed) > 0 {
+            let now = Instant::now();
+            if now >= deadline {
+                return false;
+            }
+            completed = self
+                .ready
+                .wait_timeout(completed, deadline - now)
+                .unwrap_or_else(PoisonError::into_inner)
+                .0;
+        }
+        true
+    }
+
+    /// Returns the operations submitted and not yet completed.
+    pub(crate) fn in_flight(&self) -> u64 {
+        self.in_flight.load(Ordering::Relaxed)
+    }
+
+    /// Returns the completions not yet polled.
+    pub(crate) fn completed(&self) -> usize {
+        self.lock().len()
+    }
+
+    /// Returns the key and value bytes of scan results not yet polled.
+    pub(crate) fn held_bytes(&self) -> usize {
+        self.lock()
+            .iter()
+            .map(|completion| match &completion.result {
+                Ok(Outcome::Scan(entries)) => memory::entry_bytes(entries),
+                _ => 0,
+            })
+            .sum()
+    }
+
+    /// Appends the number of in-flight operations and unpolled completions.
+    pub(crate) fn append_stats(&self, stats: &mut Stats) {
+        stats.push(("async.in_flight".to_string(), self.in_flight() as i64));
+        stats.push(("async.completed".to_string(), self.completed() as i64));
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, VecDeque<Completion>> {
+        self.completed
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+
+    fn append_outcome(start_sequence: u64) -> Result<Outcome, String> {
+        Ok(Outcome::Append {
+            start_sequence,
+            timestamp_ms: 0,
+        })
+    }
+
+    #[test]
+    fn should_issue_distinct_nonzero_tickets() {
+        // given
+        let queue = CompletionQueue::default();
+
+        // when
+        let tickets: Vec<u64> = (0..3).map(|_| queue.submit()).collect();
+
+        // then
+        assert_eq!(tickets, vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn should_poll_completions_in_completion_order() {
+        // given
+        let queue = CompletionQueue::default();
+        let first = queue.submit();
+        let second = queue.submit();
+        let third = queue.submit();
+        queue.complete(second, append_outcome(10));
+        queue.complete(first, Err("failed".to_string()));
+        queue.complete(third, append_outcome(20));
+
+        // when
+        let polled = queue.poll(2, Duration::ZERO);
+        let rest = queue.poll(10, Duration::ZERO);
+
+        // then
+        let tickets: Vec<u64> = polled.iter().map(|c| c.ticket).collect();
+        assert_eq!(tickets, vec![second, first]);
+        assert_eq!(
+            polled[1].result.as_ref().err().map(String::as_str),
+            Some("failed")
+        );
+        assert_eq!(rest.len(), 1);
+        assert_eq!(rest[0].ticket, third);
+    }
+
+    #[test]
+    fn should_time_out_without_completions() {
+        // given
+        let queue = CompletionQueue::default();
+        queue.submit();
+
+        // when
+        let started = Instant::now();
+        let polled = queue.poll(10, Duration::from_millis(20));
+
+        // then
+        assert!(polled.is_empty());
+        assert!(started.elapsed() >= Duration::from_millis(20));
+    }
+
+    #[test]
+    fn should_wake_poller_when_an_operation_completes() {
+        // given
+        let queue = Arc::new(CompletionQueue::default());
+        let ticket = queue.submit();
+        let completer = Arc::clone(&queue);
+
+        // when
+        let thread = std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(10));
+            completer.complete(ticket, append_outcome(0));
+        });
+        let polled = queue.poll(10, Duration::from_secs(10));
+        thread.join().unwrap();
+
+        // then
+        assert_eq!(polled.len(), 1);
+        assert_eq!(polled[0].ticket, ticket);
+    }
+
+    #[test]
+    fn should_wait_until_no_operation_is_in_flight() {
+        // given
+        let queue = CompletionQueue::default();
+        let ticket = queue.submit();
+
+        // when
+        let busy = queue.wait_idle(Instant::now() + Duration::from_millis(10));
+        queue.complete(ticket, append_outcome(0));
+        let idle = queue.wait_idle(Instant::now());
+
+        // then
+        assert!(!busy);
+        assert!(idle);
+    }
+
+    #[test]
+    fn should_count_operations_until_completed_and_polled() {
+        // given
+        let queue = CompletionQueue::default();
+        let first = queue.submit();
+        queue.submit();
+
+        // when
+        queue.complete(first, append_outcome(0));
+        let pending = (queue.in_flight(), queue.completed());
+        queue.poll(10, Duration::ZERO);
+
+        // then
+        assert_eq!(pending, (1, 1));
+        assert_eq!((queue.in_flight(), queue.completed()), (1, 0));
+    }
+}
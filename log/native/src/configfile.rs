@@ -0,0 +1,925 @@
+//! Log configuration loaded from a file.
+//!
+//! `LogDb.open(Path)` passes the path of a TOML or YAML file instead of a
+//! Java `LogDbConfig`, so benchmark variations can be swept by editing files
+//! rather than building a new config object for every knob. The file holds
+//! the settings of the record under snake_case names, with the nested records
+//! as tables; omitted settings take the record's defaults, and unknown names
+//! are rejected so a misspelt knob does not silently keep its default:
+//!
+//! ```toml
+//! bridge_threads = 4
+//! tail_cache_bytes = 67108864
+//! background_concurrency = 2
+//! key_stats_capacity = 1000
+//! chunk_threshold_bytes = 1048576
+//! slow_operation_threshold_ms = 50
+//! receive_timestamps = true
+//! clock = "wall"            # or "record", "monotonic", "manual"
+//!
+//! [storage]
+//! type = "slatedb"          # or "in_memory", or "noop" with payload_size
+//! path = "bench"
+//! settings_path = "slatedb.toml"
+//! object_store = { type = "local", path = "/tmp/bench" }
+//! disk_cache = { path = "/tmp/bench-cache", max_bytes = 1073741824 }
+//! garbage_collector = { interval_ms = 60000, min_age_ms = 3600000 }
+//! write_buffer = { write_buffer_bytes = 268435456, flush_interval_ms = 5, wal = "disabled" }
+//!
+//! [coalesce]
+//! window_micros = 200
+//!
+//! [write_queue]
+//! max_records = 100000
+//! policy = "reject"         # or "block", "not_ready"
+//!
+//! [tags]
+//! topic = "orders"
+//! workerId = "3"
+//! ```
+//!
+//! The other tables are `watchdog`, `faults`, `rate_limits`, `reopen`,
+//! `memory_limit`, `call_limit` with `max_calls`, a `policy` of `queue`,
+//! `block` or `reject` and `max_queued`, `statsd` with a `host`, `port`,
+//! `interval_ms`, `prefix` and `dogstatsd`, and `runtime` and
+//! `compaction_runtime`, each with a `thread_name_prefix`, a `cpus` list and
+//! `worker_threads`, and for the compaction runtime `shared`.
+//! YAML files use the same names and nesting.
+
+use std::fs;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+
+use common::storage::config::{
+    AwsObjectStoreConfig, LocalObjectStoreConfig, ObjectStoreConfig, SlateDbStorageConfig,
+    StorageConfig,
+};
+use toml::{Table, Value};
+
+use crate::backpressure::{BackpressurePolicy, WriteQueueSettings};
+use crate::clock::ClockSource;
+use crate::coalesce::CoalesceSettings;
+use crate::diskcache::DiskCacheSettings;
+use crate::fault::FaultSettings;
+use crate::memory::MemoryLimitSettings;
+use crate::metrics::Operation;
+use crate::overload::{CallLimitSettings, OverloadPolicy};
+use crate::ratelimit::RateLimitSettings;
+use crate::reopen::ReopenSettings;
+use crate::runtimes::RuntimeSettings;
+use crate::settings::{GarbageCollectorSettings, WriteBufferSettings};
+use crate::statsd::StatsdSettings;
+use crate::tags::{self, Tags};
+use crate::watchdog::WatchdogSettings;
+use crate::LogSettings;
+
+/// Default number of batches remembered per producer and key, as in `LogDbConfig`.
+const DEFAULT_DEDUP_WINDOW: i64 = 5;
+
+/// Coalescing bounds used when a window is set, as in `CoalesceConfig`.
+const DEFAULT_COALESCE_MAX_RECORDS: i64 = 10_000;
+const DEFAULT_COALESCE_MAX_BYTES: i64 = 1 << 20;
+
+/// Delay before retrying a failed reopen, as in `ReopenConfig`.
+const DEFAULT_REOPEN_BACKOFF_MS: i64 = 1_000;
+
+/// Interval and name prefix of the StatsD export, as in `StatsdConfig`.
+const DEFAULT_STATSD_INTERVAL_MS: i64 = 10_000;
+const DEFAULT_STATSD_PREFIX: &str = "opendata.log";
+
+/// Reads the settings of a LogDb from a TOML or YAML file.
+pub(crate) fn load_log_settings(path: &str) -> Result<LogSettings, String> {
+    let text = fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read config file {}: {}", path, e))?;
+    let table = match Path::new(path).extension().and_then(|e| e.to_str()) {
+        Some("toml") => text
+            .parse::<Table>()
+            .map_err(|e| format!("Failed to parse config file {}: {}", path, e))?,
+        Some("yaml" | "yml") => match serde_yaml::from_str::<Value>(&text) {
+            Ok(Value::Table(table)) => table,
+            Ok(_) => return Err(format!("Config file {} must hold a mapping", path)),
+            Err(e) => return Err(format!("Failed to parse config file {}: {}", path, e)),
+        },
+        _ => {
+            return Err(format!(
+                "Config file {} must end in .toml, .yaml or .yml",
+                path
+            ))
+        }
+    };
+    parse_log_settings(&table).map_err(|e| format!("Invalid config file {}: {}", path, e))
+}
+
+/// Converts a parsed config file into log settings.
+fn parse_log_settings(table: &Table) -> Result<LogSettings, String> {
+    let root = Section::root(table);
+    root.check_keys(&[
+        "storage",
+        "profiling",
+        "watchdog",
+        "dedup_window",
+        "faults",
+        "bridge_threads",
+        "coalesce",
+        "write_queue",
+        "rate_limits",
+        "background_concurrency",
+        "key_stats_capacity",
+        "tail_cache_bytes",
+        "slow_operation_threshold_ms",
+        "open_timeout_ms",
+        "reopen",
+        "memory_limit",
+        "call_limit",
+        "runtime",
+        "compaction_runtime",
+        "chunk_threshold_bytes",
+        "critical_value_copy",
+        "tags",
+        "clock",
+        "receive_timestamps",
+        "statsd",
+    ])?;
+    let storage = root.section("storage")?.ok_or("storage must be set")?;
+    let ParsedStorage {
+        storage: storage_config,
+        noop_payload_size,
+        disk_cache,
+        garbage_collector,
+        write_buffer,
+    } = parse_storage(&storage)?;
+    let dedup_window = root.int("dedup_window", DEFAULT_DEDUP_WINDOW)?;
+    if dedup_window <= 0 {
+        return Err("dedup_window must be positive".to_string());
+    }
+    let background_concurrency = root.int("background_concurrency", 1)?;
+    if background_concurrency <= 0 {
+        return Err("background_concurrency must be positive".to_string());
+    }
+    Ok(LogSettings {
+        storage: storage_config,
+        profiling: root.bool("profiling", false)?,
+        watchdog: root
+            .section("watchdog")?
+            .map(|s| parse_watchdog(&s))
+            .transpose()?,
+        dedup_window: dedup_window as usize,
+        faults: root
+            .section("faults")?
+            .map(|s| parse_faults(&s))
+            .transpose()?
+            .flatten(),
+        noop_payload_size,
+        bridge_threads: root.non_negative("bridge_threads", 0)? as usize,
+        coalesce: root
+            .section("coalesce")?
+            .map(|s| parse_coalesce(&s))
+            .transpose()?
+            .flatten(),
+        write_queue: root
+            .section("write_queue")?
+            .map(|s| parse_write_queue(&s))
+            .transpose()?
+            .flatten(),
+        rate_limits: root
+            .section("rate_limits")?
+            .map(|s| parse_rate_limits(&s))
+            .transpose()?
+            .unwrap_or_default(),
+        background_concurrency: background_concurrency as usize,
+        key_stats_capacity: root.non_negative("key_stats_capacity", 0)? as usize,
+        tail_cache_bytes: root.non_negative("tail_cache_bytes", 0)? as usize,
+        disk_cache,
+        garbage_collector,
+        write_buffer,
+        slow_operation_threshold: Duration::from_millis(
+            root.non_negative("slow_operation_threshold_ms", 0)? as u64,
+        ),
+        open_timeout: Duration::from_millis(root.non_negative("open_timeout_ms", 0)? as u64),
+        reopen: root
+            .section("reopen")?
+            .map(|s| parse_reopen(&s))
+            .transpose()?
+            .flatten(),
+        memory_limit: root
+            .section("memory_limit")?
+            .map(|s| parse_memory_limit(&s))
+            .transpose()?
+            .flatten(),
+        call_limit: root
+            .section("call_limit")?
+            .map(|s| parse_call_limit(&s))
+            .transpose()?
+            .flatten(),
+        runtime: parse_runtime(
+            root.section("runtime")?,
+            RuntimeSettings::operation(),
+            &["thread_name_prefix", "cpus", "worker_threads"],
+        )?,
+        compaction_runtime: parse_runtime(
+            root.section("compaction_runtime")?,
+            RuntimeSettings::compaction(),
+            &["thread_name_prefix", "cpus", "worker_threads", "shared"],
+        )?,
+        chunk_threshold: root.non_negative("chunk_threshold_bytes", 0)? as usize,
+        critical_copy: root.bool("critical_value_copy", false)?,
+        tags: Arc::new(
+            root.section("tags")?
+                .map(|s| parse_tags(&s))
+                .transpose()?
+                .unwrap_or_default(),
+        ),
+        clock: match root.string("clock")? {
+            None => ClockSource::default(),
+            Some(name) => {
+                ClockSource::from_name(&name).ok_or_else(|| format!("unknown clock {}", name))?
+            }
+        },
+        receive_timestamps: root.bool("receive_timestamps", false)?,
+        statsd: root
+            .section("statsd")?
+            .map(|s| parse_statsd(&s))
+            .transpose()?,
+    })
+}
+
+/// Storage table of a file, with the settings stored beside its config.
+struct ParsedStorage {
+    storage: StorageConfig,
+    noop_payload_size: Option<usize>,
+    disk_cache: Option<DiskCacheSettings>,
+    garbage_collector: Option<GarbageCollectorSettings>,
+    write_buffer: WriteBufferSettings,
+}
+
+impl ParsedStorage {
+    fn in_memory(noop_payload_size: Option<usize>) -> Self {
+        Self {
+            storage: StorageConfig::InMemory,
+            noop_payload_size,
+            disk_cache: None,
+            garbage_collector: None,
+            write_buffer: WriteBufferSettings::default(),
+        }
+    }
+}
+
+fn parse_storage(storage: &Section<'_>) -> Result<ParsedStorage, String> {
+    match storage.string("type")?.as_deref() {
+        Some("in_memory") => {
+            storage.check_keys(&["type"])?;
+            Ok(ParsedStorage::in_memory(None))
+        }
+        Some("noop") => {
+            storage.check_keys(&["type", "payload_size"])?;
+            let payload_size = storage.non_negative("payload_size", 0)?;
+            // Offsets, groups and conditional appends still need a real log
+            Ok(ParsedStorage::in_memory(Some(payload_size as usize)))
+        }
+        Some("slatedb") => {
+            storage.check_keys(&[
+                "type",
+                "path",
+                "object_store",
+                "settings_path",
+                "disk_cache",
+                "garbage_collector",
+                "write_buffer",
+            ])?;
+            let path = storage.string("path")?.ok_or("storage.path must be set")?;
+            if path.trim().is_empty() {
+                return Err("storage.path must not be blank".to_string());
+            }
+            let object_store = storage
+                .section("object_store")?
+                .ok_or("storage.object_store must be set")?;
+            let disk_cache = storage
+                .section("disk_cache")?
+                .map(|s| parse_disk_cache(&s))
+                .transpose()?;
+            let garbage_collector = storage
+                .section("garbage_collector")?
+                .map(|s| parse_garbage_collector(&s))
+                .transpose()?;
+            let write_buffer = storage
+                .section("write_buffer")?
+                .map(|s| parse_write_buffer(&s))
+                .transpose()?
+                .unwrap_or_default();
+            let config = SlateDbStorageConfig {
+                path,
+                object_store: parse_object_store(&object_store)?,
+                settings_path: storage.string("settings_path")?,
+            };
+            Ok(ParsedStorage {
+                storage: StorageConfig::SlateDb(config),
+                noop_payload_size: None,
+                disk_cache,
+                garbage_collector,
+                write_buffer,
+            })
+        }
+        Some(other) => Err(format!("unknown storage.type {}", other)),
+        None => Err("storage.type must be set".to_string()),
+    }
+}
+
+fn parse_object_store(object_store: &Section<'_>) -> Result<ObjectStoreConfig, String> {
+    match object_store.string("type")?.as_deref() {
+        Some("in_memory") => {
+            object_store.check_keys(&["type"])?;
+            Ok(ObjectStoreConfig::InMemory)
+        }
+        Some("local") => {
+            object_store.check_keys(&["type", "path"])?;
+            let path = object_store.required_string("path")?;
+            Ok(ObjectStoreConfig::Local(LocalObjectStoreConfig { path }))
+        }
+        Some("aws") => {
+            object_store.check_keys(&["type", "region", "bucket"])?;
+            Ok(ObjectStoreConfig::Aws(AwsObjectStoreConfig {
+                region: object_store.required_string("region")?,
+                bucket: object_store.required_string("bucket")?,
+            }))
+        }
+        Some(other) => Err(format!("unknown storage.object_store.type {}", other)),
+        None => Err("storage.object_store.type must be set".to_string()),
+    }
+}
+
+fn parse_disk_cache(disk_cache: &Section<'_>) -> Result<DiskCacheSettings, String> {
+    disk_cache.check_keys(&["path", "max_bytes"])?;
+    let root = disk_cache.required_string("path")?;
+    let max_bytes = disk_cache.int("max_bytes", 0)?;
+    if max_bytes <= 0 {
+        return Err("storage.disk_cache.max_bytes must be positive".to_string());
+    }
+    Ok(DiskCacheSettings {
+        root: root.into(),
+        max_bytes: max_bytes as u64,
+    })
+}
+
+fn parse_garbage_collector(gc: &Section<'_>) -> Result<GarbageCollectorSettings, String> {
+    gc.check_keys(&["interval_ms", "min_age_ms"])?;
+    let interval_ms = gc.int("interval_ms", 0)?;
+    let min_age_ms = gc.int("min_age_ms", 0)?;
+    if interval_ms <= 0 || min_age_ms <= 0 {
+        return Err(
+            "storage.garbage_collector.interval_ms and min_age_ms must be positive".to_string(),
+        );
+    }
+    Ok(GarbageCollectorSettings {
+        interval: Duration::from_millis(interval_ms as u64),
+        min_age: Duration::from_millis(min_age_ms as u64),
+    })
+}
+
+fn parse_write_buffer(write_buffer: &Section<'_>) -> Result<WriteBufferSettings, String> {
+    write_buffer.check_keys(&[
+        "write_buffer_bytes",
+        "memtable_bytes",
+        "flush_interval_ms",
+        "wal",
+    ])?;
+    let positive = |value: i64| (value > 0).then_some(value as u64);
+    Ok(WriteBufferSettings {
+        max_unflushed_bytes: positive(write_buffer.non_negative("write_buffer_bytes", 0)?),
+        l0_sst_size_bytes: positive(write_buffer.non_negative("memtable_bytes", 0)?),
+        flush_interval: positive(write_buffer.non_negative("flush_interval_ms", 0)?)
+            .map(Duration::from_millis),
+        wal_enabled: match write_buffer.string("wal")?.as_deref() {
+            None | Some("default") => None,
+            Some("enabled") => Some(true),
+            Some("disabled") => Some(false),
+            Some(other) => return Err(format!("unknown storage.write_buffer.wal {}", other)),
+        },
+    })
+}
+
+fn parse_watchdog(watchdog: &Section<'_>) -> Result<WatchdogSettings, String> {
+    watchdog.check_keys(&["threshold_ms", "fail_stuck_calls"])?;
+    let threshold_ms = watchdog.int("threshold_ms", 0)?;
+    if threshold_ms <= 0 {
+        return Err("watchdog.threshold_ms must be positive".to_string());
+    }
+    Ok(WatchdogSettings {
+        threshold: Duration::from_millis(threshold_ms as u64),
+        fail_stuck_calls: watchdog.bool("fail_stuck_calls", false)?,
+    })
+}
+
+/// Returns None when no operations are targeted.
+fn parse_faults(faults: &Section<'_>) -> Result<Option<FaultSettings>, String> {
+    faults.check_keys(&[
+        "error_probability",
+        "throttle_probability",
+        "timeout_probability",
+        "timeout_ms",
+        "min_latency_ms",
+        "max_latency_ms",
+        "operations",
+        "seed",
+    ])?;
+    let error_probability = faults.probability("error_probability")?;
+    let throttle_probability = faults.probability("throttle_probability")?;
+    let timeout_probability = faults.probability("timeout_probability")?;
+    if error_probability + throttle_probability + timeout_probability > 1.0 {
+        return Err("fault probabilities must not sum to more than 1".to_string());
+    }
+    let min_latency_ms = faults.non_negative("min_latency_ms", 0)?;
+    let max_latency_ms = faults.non_negative("max_latency_ms", min_latency_ms)?;
+    if max_latency_ms < min_latency_ms {
+        return Err("faults.max_latency_ms must not be less than min_latency_ms".to_string());
+    }
+    let operations = match faults.table.get("operations") {
+        None => all_operations_mask(),
+        Some(Value::Array(names)) => {
+            names.iter().try_fold(0, |mask, name| match name.as_str() {
+                Some("append") => Ok(mask | 1 << Operation::Append as u32),
+                Some("scan") => Ok(mask | 1 << Operation::Scan as u32),
+                _ => Err(format!("unknown faults.operations entry {}", name)),
+            })?
+        }
+        Some(_) => return Err("faults.operations must be a list".to_string()),
+    };
+    if operations == 0 {
+        return Ok(None);
+    }
+    Ok(Some(FaultSettings {
+        error_probability,
+        throttle_probability,
+        timeout_probability,
+        timeout: Duration::from_millis(faults.non_negative("timeout_ms", 0)? as u64),
+        min_latency: Duration::from_millis(min_latency_ms as u64),
+        max_latency: Duration::from_millis(max_latency_ms as u64),
+        operations,
+        seed: faults.int("seed", 0)? as u64,
+    }))
+}
+
+fn all_operations_mask() -> u32 {
+    1 << Operation::Append as u32 | 1 << Operation::Scan as u32
+}
+
+/// Returns None when the window is zero.
+fn parse_coalesce(coalesce: &Section<'_>) -> Result<Option<CoalesceSettings>, String> {
+    coalesce.check_keys(&["window_micros", "max_records", "max_bytes"])?;
+    let window_micros = coalesce.non_negative("window_micros", 0)?;
+    let max_records = coalesce.int("max_records", DEFAULT_COALESCE_MAX_RECORDS)?;
+    let max_bytes = coalesce.int("max_bytes", DEFAULT_COALESCE_MAX_BYTES)?;
+    if max_records <= 0 || max_bytes <= 0 {
+        return Err("coalesce.max_records and max_bytes must be positive".to_string());
+    }
+    if window_micros == 0 {
+        return Ok(None);
+    }
+    Ok(Some(CoalesceSettings {
+        window: Duration::from_micros(window_micros as u64),
+        max_records: max_records as usize,
+        max_bytes: max_bytes as usize,
+    }))
+}
+
+/// Returns None when neither bound is set.
+fn parse_write_queue(write_queue: &Section<'_>) -> Result<Option<WriteQueueSettings>, String> {
+    write_queue.check_keys(&["max_records", "max_bytes", "policy"])?;
+    let max_records = write_queue.non_negative("max_records", 0)? as usize;
+    let max_bytes = write_queue.non_negative("max_bytes", 0)? as usize;
+    let policy = match write_queue.string("policy")?.as_deref() {
+        None | Some("block") => BackpressurePolicy::Block,
+        Some("reject") => BackpressurePolicy::Reject,
+        Some("not_ready") => BackpressurePolicy::NotReady,
+        Some(other) => return Err(format!("unknown write_queue.policy {}", other)),
+    };
+    if max_records == 0 && max_bytes == 0 {
+        return Ok(None);
+    }
+    Ok(Some(WriteQueueSettings {
+        max_records,
+        max_bytes,
+        policy,
+    }))
+}
+
+fn parse_rate_limits(rate_limits: &Section<'_>) -> Result<RateLimitSettings, String> {
+    rate_limits.check_keys(&[
+        "produce_records_per_sec",
+        "produce_bytes_per_sec",
+        "consume_records_per_sec",
+        "consume_bytes_per_sec",
+    ])?;
+    Ok(RateLimitSettings {
+        produce_records_per_sec: rate_limits.non_negative("produce_records_per_sec", 0)? as u64,
+        produce_bytes_per_sec: rate_limits.non_negative("produce_bytes_per_sec", 0)? as u64,
+        consume_records_per_sec: rate_limits.non_negative("consume_records_per_sec", 0)? as u64,
+        consume_bytes_per_sec: rate_limits.non_negative("consume_bytes_per_sec", 0)? as u64,
+    })
+}
+
+/// Returns None when the failure threshold is zero.
+fn parse_reopen(reopen: &Section<'_>) -> Result<Option<ReopenSettings>, String> {
+    reopen.check_keys(&["failure_threshold", "backoff_ms"])?;
+    let failure_threshold = reopen.non_negative("failure_threshold", 0)?;
+    let backoff_ms = reopen.non_negative("backoff_ms", DEFAULT_REOPEN_BACKOFF_MS)?;
+    if failure_threshold == 0 {
+        return Ok(None);
+    }
+    Ok(Some(ReopenSettings {
+        failure_threshold: failure_threshold as u32,
+        backoff: Duration::from_millis(backoff_ms as u64),
+    }))
+}
+
+fn parse_statsd(statsd: &Section<'_>) -> Result<StatsdSettings, String> {
+    statsd.check_keys(&["host", "port", "interval_ms", "prefix", "dogstatsd"])?;
+    let port = statsd.int("port", 0)?;
+    if !(1..=i64::from(u16::MAX)).contains(&port) {
+        return Err("statsd.port must be between 1 and 65535".to_string());
+    }
+    let interval_ms = statsd.int("interval_ms", DEFAULT_STATSD_INTERVAL_MS)?;
+    if interval_ms <= 0 {
+        return Err("statsd.interval_ms must be positive".to_string());
+    }
+    Ok(StatsdSettings {
+        host: statsd.required_string("host")?,
+        port: port as u16,
+        interval: Duration::from_millis(interval_ms as u64),
+        prefix: statsd
+            .string("prefix")?
+            .unwrap_or_else(|| DEFAULT_STATSD_PREFIX.to_string()),
+        dogstatsd: statsd.bool("dogstatsd", false)?,
+    })
+}
+
+/// Returns None when no limit is set.
+fn parse_memory_limit(memory_limit: &Section<'_>) -> Result<Option<MemoryLimitSettings>, String> {
+    memory_limit.check_keys(&["max_bytes", "policy"])?;
+    let max_bytes = memory_limit.non_negative("max_bytes", 0)? as usize;
+    let policy = match memory_limit.string("policy")?.as_deref() {
+        None | Some("block") => BackpressurePolicy::Block,
+        Some("reject") => BackpressurePolicy::Reject,
+        Some(other) => return Err(format!("unknown memory_limit.policy {}", other)),
+    };
+    if max_bytes == 0 {
+        return Ok(None);
+    }
+    Ok(Some(MemoryLimitSettings { max_bytes, policy }))
+}
+
+/// Returns None when no limit is set.
+fn parse_call_limit(call_limit: &Section<'_>) -> Result<Option<CallLimitSettings>, String> {
+    call_limit.check_keys(&["max_calls", "policy", "max_queued"])?;
+    let max_calls = call_limit.non_negative("max_calls", 0)? as usize;
+    let policy = match call_limit.string("policy")?.as_deref() {
+        None | Some("block") => OverloadPolicy::Block,
+        Some("queue") => OverloadPolicy::Queue,
+        Some("reject") => OverloadPolicy::Reject,
+        Some(other) => return Err(format!("unknown call_limit.policy {}", other)),
+    };
+    let max_queued = call_limit.non_negative("max_queued", 0)? as usize;
+    if max_calls == 0 {
+        return Ok(None);
+    }
+    Ok(Some(CallLimitSettings {
+        max_calls,
+        policy,
+        max_queued,
+    }))
+}
+
+fn parse_tags(section: &Section<'_>) -> Result<Tags, String> {
+    let mut pairs = Vec::new();
+    for (name, value) in section.table {
+        if !tags::is_valid_name(name) {
+            return Err(format!("invalid tag name {}", section.path(name)));
+        }
+        let value = section
+            .string(name)?
+            .ok_or_else(|| format!("{} must be set", section.path(name)))?;
+        pairs.push((name.clone(), value));
+    }
+    Ok(Tags::new(pairs))
+}
+
+/// Returns `defaults` for an omitted table or setting.
+fn parse_runtime(
+    runtime: Option<Section<'_>>,
+    defaults: RuntimeSettings,
+    known: &[&str],
+) -> Result<RuntimeSettings, String> {
+    let Some(runtime) = runtime else {
+        return Ok(defaults);
+    };
+    runtime.check_keys(known)?;
+    let thread_name_prefix = runtime
+        .string("thread_name_prefix")?
+        .unwrap_or(defaults.thread_name_prefix);
+    if thread_name_prefix.is_empty() {
+        return Err(format!(
+            "{} must not be empty",
+            runtime.path("thread_name_prefix")
+        ));
+    }
+    let mut cpus = match runtime.table.get("cpus") {
+        None => Vec::new(),
+        Some(Value::Array(cpus)) => cpus
+            .iter()
+            .map(|cpu| match cpu.as_integer() {
+                Some(cpu) if cpu >= 0 => Ok(cpu as usize),
+                _ => Err(format!("invalid {} entry {}", runtime.path("cpus"), cpu)),
+            })
+            .collect::<Result<_, _>>()?,
+        Some(_) => return Err(format!("{} must be a list", runtime.path("cpus"))),
+    };
+    cpus.sort_unstable();
+    cpus.dedup();
+    Ok(RuntimeSettings {
+        thread_name_prefix,
+        cpus,
+        worker_threads: runtime.non_negative("worker_threads", 0)? as usize,
+        shared: runtime.bool("shared", false)?,
+    })
+}
+
+/// A table of the config file, named by its dotted path for error messages.
+struct Section<'a> {
+    name: String,
+    table: &'a Table,
+}
+
+impl<'a> Section<'a> {
+    fn root(table: &'a Table) -> Self {
+        Self {
+            name: String::new(),
+            table,
+        }
+    }
+
+    fn path(&self, key: &str) -> String {
+        if self.name.is_empty() {
+            key.to_string()
+        } else {
+            format!("{}.{}", self.name, key)
+        }
+    }
+
+    fn check_keys(&self, known: &[&str]) -> Result<(), String> {
+        match self.table.keys().find(|key| !known.contains(&key.as_str())) {
+            Some(key) => Err(format!("unknown setting {}", self.path(key))),
+            None => Ok(()),
+        }
+    }
+
+    fn section(&self, key: &str) -> Result<Option<Section<'a>>, String> {
+        match self.table.get(key) {
+            None => Ok(None),
+            Some(Value::Table(table)) => Ok(Some(Section {
+                name: self.path(key),
+                table,
+            })),
+            Some(_) => Err(format!("{} must be a table", self.path(key))),
+        }
+    }
+
+    fn bool(&self, key: &str, default: bool) -> Result<bool, String> {
+        match self.table.get(key) {
+            None => Ok(default),
+            Some(value) => value
+                .as_bool()
+                .ok_or_else(|| format!("{} must be a boolean", self.path(key))),
+        }
+    }
+
+    fn int(&self, key: &str, default: i64) -> Result<i64, String> {
+        match self.table.get(key) {
+            None => Ok(default),
+            Some(value) => value
+                .as_integer()
+                .ok_or_else(|| format!("{} must be an integer", self.path(key))),
+        }
+    }
+
+    fn non_negative(&self, key: &str, default: i64) -> Result<i64, String> {
+        let value = self.int(key, default)?;
+        if value < 0 {
+            return Err(format!("{} must not be negative", self.path(key)));
+        }
+        Ok(value)
+    }
+
+    fn probability(&self, key: &str) -> Result<f64, String> {
+        let probability = match self.table.get(key) {
+            None => 0.0,
+            Some(Value::Integer(value)) => *value as f64,
+            Some(Value::Float(value)) => *value,
+            Some(_) => return Err(format!("{} must be a number", self.path(key))),
+        };
+        if !(0.0..=1.0).contains(&probability) {
+            return Err(format!("{} must be between 0 and 1", self.path(key)));
+        }
+        Ok(probability)
+    }
+
+    fn string(&self, key: &str) -> Result<Option<String>, String> {
+        match self.table.get(key) {
+            None => Ok(None),
+            Some(Value::String(value)) => Ok(Some(value.clone())),
+            Some(_) => Err(format!("{} must be a string", self.path(key))),
+        }
+    }
+
+    fn required_string(&self, key: &str) -> Result<String, String> {
+        self.string(key)?
+            .ok_or_else(|| format!("{} must be set", self.path(key)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(text: &str) -> Result<LogSettings, String> {
+        parse_log_settings(&text.parse::<Table>().unwrap())
+    }
+
+    #[test]
+    fn should_parse_settings_and_default_the_rest() {
+        // given
+        let text = r#"
+            bridge_threads = 4
+            slow_operation_threshold_ms = 50
+
+            [storage]
+            type = "slatedb"
+            path = "bench"
+            object_store = { type = "local", path = "/tmp/bench" }
+
+            [write_queue]
+            max_records = 1000
+            policy = "reject"
+        "#;
+
+        // when
+        let settings = parse(text).unwrap();
+
+        // then
+        let StorageConfig::SlateDb(slatedb) = &settings.storage else {
+            panic!("expected SlateDB storage");
+        };
+        assert_eq!(slatedb.path, "bench");
+        assert_eq!(slatedb.settings_path, None);
+        assert!(settings.garbage_collector.is_none());
+        assert_eq!(settings.bridge_threads, 4);
+        assert_eq!(settings.slow_operation_threshold, Duration::from_millis(50));
+        let write_queue = settings.write_queue.unwrap();
+        assert_eq!(write_queue.max_records, 1000);
+        assert_eq!(write_queue.policy, BackpressurePolicy::Reject);
+        assert_eq!(settings.dedup_window, DEFAULT_DEDUP_WINDOW as usize);
+        assert!(settings.coalesce.is_none());
+        assert!(settings.rate_limits.is_unlimited());
+        assert_eq!(settings.background_concurrency, 1);
+        assert_eq!(settings.key_stats_capacity, 0);
+        assert!(settings.statsd.is_none());
+        assert!(settings.call_limit.is_none());
+        assert_eq!(settings.runtime, RuntimeSettings::operation());
+    }
+
+    #[test]
+    fn should_parse_runtime_cores() {
+        // given
+        let text = r#"
+            [storage]
+            type = "in_memory"
+
+            [compaction_runtime]
+            thread_name_prefix = "bench-compaction"
+            cpus = [3, 2]
+        "#;
+
+        // when
+        let settings = parse(text).unwrap();
+
+        // then
+        assert_eq!(
+            settings.compaction_runtime,
+            RuntimeSettings {
+                thread_name_prefix: "bench-compaction".to_string(),
+                cpus: vec![2, 3],
+                ..RuntimeSettings::compaction()
+            }
+        );
+    }
+
+    #[test]
+    fn should_parse_write_buffer() {
+        // given
+        let text = r#"
+            [storage]
+            type = "slatedb"
+            path = "bench"
+            object_store = { type = "local", path = "/tmp/bench" }
+            write_buffer = { memtable_bytes = 67108864, wal = "disabled" }
+        "#;
+
+        // when
+        let settings = parse(text).unwrap();
+
+        // then
+        assert_eq!(
+            settings.write_buffer,
+            WriteBufferSettings {
+                l0_sst_size_bytes: Some(64 << 20),
+                wal_enabled: Some(false),
+                ..WriteBufferSettings::default()
+            }
+        );
+    }
+
+    #[test]
+    fn should_parse_yaml_with_same_names() {
+        // given
+        let text = "storage:\n  type: noop\n  payload_size: 100\ncoalesce:\n  window_micros: 200\n";
+
+        // when
+        let table = match serde_yaml::from_str::<Value>(text).unwrap() {
+            Value::Table(table) => table,
+            other => panic!("expected a table, got {}", other),
+        };
+        let settings = parse_log_settings(&table).unwrap();
+
+        // then
+        assert_eq!(settings.noop_payload_size, Some(100));
+        let coalesce = settings.coalesce.unwrap();
+        assert_eq!(coalesce.window, Duration::from_micros(200));
+        assert_eq!(coalesce.max_records, DEFAULT_COALESCE_MAX_RECORDS as usize);
+    }
+
+    #[test]
+    fn should_parse_tags() {
+        // given
+        let text =
+            "[storage]\ntype = \"in_memory\"\n\n[tags]\nworkerId = \"3\"\ntopic = \"orders\"\n";
+
+        // when
+        let settings = parse(text).unwrap();
+
+        // then
+        assert_eq!(settings.tags.as_str(), "topic=\"orders\",workerId=\"3\"");
+    }
+
+    #[test]
+    fn should_parse_clock() {
+        // given
+        let text = "clock = \"monotonic\"\n\n[storage]\ntype = \"in_memory\"\n";
+
+        // when
+        let settings = parse(text).unwrap();
+
+        // then
+        assert_eq!(settings.clock, ClockSource::Monotonic);
+    }
+
+    #[test]
+    fn should_parse_call_limit() {
+        // given
+        let text = r#"
+            [storage]
+            type = "in_memory"
+
+            [call_limit]
+            max_calls = 64
+            policy = "queue"
+            max_queued = 256
+        "#;
+
+        // when
+        let settings = parse(text).unwrap();
+
+        // then
+        assert_eq!(
+            settings.call_limit,
+            Some(CallLimitSettings {
+                max_calls: 64,
+                policy: OverloadPolicy::Queue,
+                max_queued: 256,
+            })
+        );
+    }
+
+    #[test]
+    fn should_reject_unknown_setting() {
+        // given
+        let text = "[storage]\ntype = \"in_memory\"\n\n[coalesce]\nwindow_micro = 200\n";
+
+        // when
+        let result = parse(text);
+
+        // then
+        assert_eq!(
+            result.err(),
+            Some("unknown setting coalesce.window_micro".to_string())
+        );
+    }
+}
@@ -0,0 +1,282 @@
+//! Consumer-group membership and key assignment.
+//!
+//! Members of a group announce themselves by appending heartbeats under a
+//! reserved key derived from the group name, and leave by appending a leave
+//! event:
+//!
+//! ```text
+//! ┌──────────────┬──────────────────────────┬─────────────────────┐
+//! │ kind (1B)    │ timestamp_ms (8B)         │ member id (UTF-8)   │
+//! │ 0=heartbeat  │ big-endian i64            │                     │
+//! │ 1=leave      │                           │                     │
+//! └──────────────┴──────────────────────────┴─────────────────────┘
+//! ```
+//!
+//! A member is live if its latest event is a heartbeat newer than the session
+//! timeout. Every member replays the same events and assigns the group's keys
+//! round-robin over the live members in sorted order, so members agree on the
+//! assignment without electing a leader. Until all members have seen the same
+//! heartbeats, two members may briefly both own a key.
+
+use std::collections::HashMap;
+use std::sync::{Mutex, PoisonError};
+
+use bytes::{BufMut, Bytes, BytesMut};
+
+/// Prefix of all reserved group membership keys.
+pub(crate) const GROUP_KEY_PREFIX: &[u8] = b"\xff__opendata/groups/";
+
+const HEARTBEAT_EVENT: u8 = 0;
+const LEAVE_EVENT: u8 = 1;
+
+/// Size of the event header preceding the member id.
+const EVENT_HEADER_SIZE: usize = 9;
+
+/// Builds the reserved key holding a group's membership events.
+pub(crate) fn group_key(group: &str) -> Bytes {
+    let mut buffer = BytesMut::with_capacity(GROUP_KEY_PREFIX.len() + group.len());
+    buffer.put_slice(GROUP_KEY_PREFIX);
+    buffer.put_slice(group.as_bytes());
+    buffer.freeze()
+}
+
+/// A change to a member's liveness.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum MemberEvent {
+    Heartbeat { member: String, timestamp_ms: i64 },
+    Leave { member: String, timestamp_ms: i64 },
+}
+
+impl MemberEvent {
+    /// Encodes the event as an entry value.
+    pub(crate) fn encode(&self) -> Bytes {
+        let (kind, member, timestamp_ms) = match self {
+            MemberEvent::Heartbeat {
+                member,
+                timestamp_ms,
+            } => (HEARTBEAT_EVENT, member, *timestamp_ms),
+            MemberEvent::Leave {
+                member,
+                timestamp_ms,
+            } => (LEAVE_EVENT, member, *timestamp_ms),
+        };
+        let mut buffer = BytesMut::with_capacity(EVENT_HEADER_SIZE + member.len());
+        buffer.put_u8(kind);
+        buffer.put_i64(timestamp_ms);
+        buffer.put_slice(member.as_bytes());
+        buffer.freeze()
+    }
+
+    /// Decodes an entry value, returning None if it is not a valid event.
+    pub(crate) fn decode(value: &[u8]) -> Option<Self> {
+        if value.len() < EVENT_HEADER_SIZE {
+            return None;
+        }
+        let timestamp_ms = i64::from_be_bytes(value[1..EVENT_HEADER_SIZE].try_into().ok()?);
+        let member = std::str::from_utf8(&value[EVENT_HEADER_SIZE..])
+            .ok()?
+            .to_string();
+        match value[0] {
+            HEARTBEAT_EVENT => Some(MemberEvent::Heartbeat {
+                member,
+                timestamp_ms,
+            }),
+            LEAVE_EVENT => Some(MemberEvent::Leave {
+                member,
+                timestamp_ms,
+            }),
+            _ => None,
+        }
+    }
+}
+
+/// Membership of one group, rebuilt from its events.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct GroupView {
+    /// Next entry sequence to replay
+    next_sequence: u64,
+    /// Latest heartbeat per member; members that left are removed
+    heartbeats: HashMap<String, i64>,
+}
+
+impl GroupView {
+    /// Returns the next entry sequence to replay.
+    pub(crate) fn next_sequence(&self) -> u64 {
+        self.next_sequence
+    }
+
+    /// Applies the event stored at entry sequence `sequence`.
+    pub(crate) fn apply(&mut self, sequence: u64, event: MemberEvent) {
+        self.next_sequence = self.next_sequence.max(sequence + 1);
+        match event {
+            MemberEvent::Heartbeat {
+                member,
+                timestamp_ms,
+            } => {
+                let latest = self.heartbeats.entry(member).or_insert(timestamp_ms);
+                *latest = (*latest).max(timestamp_ms);
+            }
+            MemberEvent::Leave {
+                member,
+                timestamp_ms,
+            } => {
+                // A heartbeat after the leave means the member rejoined
+                if self
+                    .heartbeats
+                    .get(&member)
+                    .is_some_and(|&latest| latest <= timestamp_ms)
+                {
+                    self.heartbeats.remove(&member);
+                }
+            }
+        }
+    }
+
+    /// Returns the members live at `now_ms`, in sorted order.
+    pub(crate) fn live_members(&self, now_ms: i64, session_timeout_ms: i64) -> Vec<&str> {
+        let mut members: Vec<&str> = self
+            .heartbeats
+            .iter()
+            .filter(|(_, &latest)| now_ms - latest < session_timeout_ms)
+            .map(|(member, _)| member.as_str())
+            .collect();
+        members.sort_unstable();
+        members
+    }
+}
+
+/// Returns the indexes of the keys assigned to `member`.
+///
+/// Keys are sorted and dealt round-robin to the sorted live members, so every
+/// member computes the same assignment from the same view. A member that is
+/// not live is assigned nothing.
+pub(crate) fn assign(keys: &[Bytes], members: &[&str], member: &str) -> Vec<usize> {
+    let Some(position) = members.iter().position(|m| *m == member) else {
+        return Vec::new();
+    };
+    let mut order: Vec<usize> = (0..keys.len()).collect();
+    order.sort_by(|&a, &b| keys[a].cmp(&keys[b]));
+    order.dedup_by(|a, b| keys[*a] == keys[*b]);
+    order
+        .into_iter()
+        .enumerate()
+        .filter(|(i, _)| i % members.len() == position)
+        .map(|(_, index)| index)
+        .collect()
+}
+
+/// Per-handle cache of group views, so heartbeats replay only new events.
+#[derive(Default)]
+pub(crate) struct GroupViews {
+    views: Mutex<HashMap<Bytes, GroupView>>,
+}
+
+impl GroupViews {
+    /// Returns a copy of the cached view for a group key.
+    pub(crate) fn get(&self, key: &Bytes) -> GroupView {
+        self.lock().get(key).cloned().unwrap_or_default()
+    }
+
+    /// Caches a view unless a more recent one is already cached.
+    pub(crate) fn update(&self, key: Bytes, view: GroupView) {
+        let mut views = self.lock();
+        let current = views.entry(key).or_default();
+        if view.next_sequence >= current.next_sequence {
+            *current = view;
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<Bytes, GroupView>> {
+        self.views.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn heartbeat(member: &str, timestamp_ms: i64) -> MemberEvent {
+        MemberEvent::Heartbeat {
+            member: member.to_string(),
+            timestamp_ms,
+        }
+    }
+
+    #[test]
+    fn should_round_trip_member_events() {
+        // given
+        let event = MemberEvent::Leave {
+            member: "consumer-1".to_string(),
+            timestamp_ms: 1_234,
+        };
+
+        // when
+        let decoded = MemberEvent::decode(&event.encode());
+
+        // then
+        assert_eq!(decoded, Some(event));
+        assert_eq!(MemberEvent::decode(b"short"), None);
+    }
+
+    #[test]
+    fn should_expire_members_after_session_timeout() {
+        // given
+        let mut view = GroupView::default();
+        view.apply(0, heartbeat("a", 1_000));
+        view.apply(1, heartbeat("b", 5_000));
+
+        // when
+        let live = view.live_members(6_000, 3_000);
+
+        // then
+        assert_eq!(live, vec!["b"]);
+        assert_eq!(view.next_sequence(), 2);
+    }
+
+    #[test]
+    fn should_remove_member_that_left_unless_it_rejoined() {
+        // given
+        let mut view = GroupView::default();
+        view.apply(0, heartbeat("a", 1_000));
+        view.apply(1, heartbeat("b", 1_000));
+
+        // when
+        view.apply(
+            2,
+            MemberEvent::Leave {
+                member: "a".to_string(),
+                timestamp_ms: 1_500,
+            },
+        );
+        view.apply(
+            3,
+            MemberEvent::Leave {
+                member: "b".to_string(),
+                timestamp_ms: 500,
+            },
+        );
+
+        // then
+        assert_eq!(view.live_members(2_000, 10_000), vec!["b"]);
+    }
+
+    #[test]
+    fn should_assign_every_key_to_exactly_one_member() {
+        // given
+        let keys: Vec<Bytes> = ["p-2", "p-0", "p-1", "p-3", "p-4"]
+            .iter()
+            .map(|k| Bytes::from(k.to_string()))
+            .collect();
+        let members = ["a", "b"];
+
+        // when
+        let a = assign(&keys, &members, "a");
+        let b = assign(&keys, &members, "b");
+
+        // then
+        let a_keys: Vec<&Bytes> = a.iter().map(|&i| &keys[i]).collect();
+        assert_eq!(a_keys, vec!["p-0", "p-2", "p-4"]);
+        assert_eq!(a.len() + b.len(), keys.len());
+        assert!(assign(&keys, &members, "c").is_empty());
+    }
+}
@@ -0,0 +1,315 @@
+//! Storage cursors behind scan resume tokens.
+//!
+//! A scan by sequence opens a storage iterator, seeking to its start, and
+//! drops it once the page is full. A consumer paging through a key thus pays
+//! a seek per page. Scans taking a resume token instead leave their iterator
+//! parked in a cursor, and a scan passing the token back reads the following
+//! page from the same iterator.
+//!
+//! A token is 25 bytes: a version byte, then the cursor id, a hash of the key
+//! and the next sequence, each big-endian. It is opaque to callers. When its
+//! cursor is gone, because the reader restarted, the cursor idled out or was
+//! evicted, or the iterator reached the end of the key, the scan seeks again
+//! from the token's sequence, so a token stays valid for as long as its
+//! entries are retained. Cursor ids are seeded from the clock so that a
+//! restarted reader does not mistake old tokens for its own cursors.
+//!
+//! Each cursor's iterator lives in a task on the reader's runtime, which
+//! reads a page per request. Chunk runs are reassembled across pages.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use bytes::Bytes;
+use log::{LogDbReader, LogEntry, LogRead};
+use tokio::runtime::Handle;
+use tokio::sync::{mpsc, oneshot};
+
+use crate::chunking::Assembler;
+use crate::metrics::Stats;
+
+/// Length of an encoded resume token.
+pub(crate) const TOKEN_LEN: usize = 25;
+
+/// Version byte of the token encoding.
+const TOKEN_VERSION: u8 = 1;
+
+/// Cursors parked per reader before the least recently used one is dropped.
+const MAX_CURSORS: usize = 256;
+
+/// How long a cursor stays parked without being resumed.
+const IDLE_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Where a resumed scan continues.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct ResumeToken {
+    /// Id of the parked cursor; 0 for none
+    pub(crate) cursor: u64,
+    /// Hash of the key the token was issued for
+    pub(crate) key_hash: u64,
+    /// Sequence the next scan starts at
+    pub(crate) next_sequence: u64,
+}
+
+impl ResumeToken {
+    /// Returns a token starting a scan of `key` at `sequence`, without a cursor.
+    pub(crate) fn start(key: &[u8], sequence: u64) -> Self {
+        Self {
+            cursor: 0,
+            key_hash: key_hash(key),
+            next_sequence: sequence,
+        }
+    }
+
+    /// Encodes the token.
+    pub(crate) fn encode(&self) -> [u8; TOKEN_LEN] {
+        let mut bytes = [0u8; TOKEN_LEN];
+        bytes[0] = TOKEN_VERSION;
+        bytes[1..9].copy_from_slice(&self.cursor.to_be_bytes());
+        bytes[9..17].copy_from_slice(&self.key_hash.to_be_bytes());
+        bytes[17..25].copy_from_slice(&self.next_sequence.to_be_bytes());
+        bytes
+    }
+
+    /// Decodes a token issued for `key`.
+    pub(crate) fn decode(bytes: &[u8], key: &[u8]) -> Result<Self, String> {
+        if bytes.len() != TOKEN_LEN || bytes[0] != TOKEN_VERSION {
+            return Err("Malformed resume token".to_string());
+        }
+        let field = |at: usize| u64::from_be_bytes(bytes[at..at + 8].try_into().expect("8 bytes"));
+        let token = Self {
+            cursor: field(1),
+            key_hash: field(9),
+            next_sequence: field(17),
+        };
+        if token.key_hash != key_hash(key) {
+            return Err("Resume token was issued for another key".to_string());
+        }
+        Ok(token)
+    }
+
+    /// Moves the token past `entries`, onto `cursor`.
+    pub(crate) fn advance(&mut self, cursor: u64, entries: &[LogEntry]) {
+        self.cursor = cursor;
+        if let Some(last) = entries.last() {
+            self.next_sequence = last.sequence + 1;
+        }
+    }
+}
+
+/// FNV-1a hash of a key, stable across processes.
+fn key_hash(key: &[u8]) -> u64 {
+    key.iter().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(0x0000_0100_0000_01b3)
+    })
+}
+
+/// A request for the next page of at most the given entries.
+type PageRequest = (usize, oneshot::Sender<Result<Vec<LogEntry>, log::Error>>);
+
+/// An iterator positioned at `next_sequence`, reading pages in its task.
+pub(crate) struct Cursor {
+    key_hash: u64,
+    next_sequence: u64,
+    last_used: Instant,
+    pages: mpsc::Sender<PageRequest>,
+}
+
+impl Cursor {
+    /// Reads the next page, or returns None if the cursor's task is gone.
+    pub(crate) async fn read(&mut self, max: usize) -> Option<Result<Vec<LogEntry>, log::Error>> {
+        let (reply, page) = oneshot::channel();
+        self.pages.send((max, reply)).await.ok()?;
+        let result = page.await.ok()?;
+        if let Ok(entries) = &result {
+            if let Some(last) = entries.last() {
+                self.next_sequence = last.sequence + 1;
+            }
+        }
+        Some(result)
+    }
+}
+
+/// Per-reader parked cursors.
+pub(crate) struct Cursors {
+    next_id: AtomicU64,
+    parked: Mutex<HashMap<u64, Cursor>>,
+    /// Scans continued on a parked cursor
+    resumed: AtomicU64,
+    /// Cursors opened, each seeking to its start
+    opened: AtomicU64,
+}
+
+impl Default for Cursors {
+    fn default() -> Self {
+        let seed = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |since| since.as_nanos() as u64);
+        Self {
+            // 0 is reserved for tokens without a cursor
+            next_id: AtomicU64::new(seed.max(1)),
+            parked: Mutex::new(HashMap::new()),
+            resumed: AtomicU64::new(0),
+            opened: AtomicU64::new(0),
+        }
+    }
+}
+
+impl Cursors {
+    /// Takes the cursor `token` names, if it is parked at the token's
+    /// position; a cursor parked elsewhere is dropped.
+    pub(crate) fn take(&self, token: &ResumeToken) -> Option<Cursor> {
+        let cursor = self.lock().remove(&token.cursor)?;
+        if cursor.key_hash != token.key_hash || cursor.next_sequence != token.next_sequence {
+            return None;
+        }
+        self.resumed.fetch_add(1, Ordering::Relaxed);
+        Some(cursor)
+    }
+
+    /// Opens a cursor reading `key` from `start` on `runtime`.
+    pub(crate) fn open(
+        &self,
+        reader: Arc<LogDbReader>,
+        runtime: &Handle,
+        key: Bytes,
+        start: u64,
+    ) -> Cursor {
+        self.opened.fetch_add(1, Ordering::Relaxed);
+        let (pages, requests) = mpsc::channel(1);
+        let key_hash = key_hash(&key);
+        runtime.spawn(run(reader, key, start, requests));
+        Cursor {
+            key_hash,
+            next_sequence: start,
+            last_used: Instant::now(),
+            pages,
+        }
+    }
+
+    /// Parks a cursor for a later scan, returning its id.
+    ///
+    /// Idle cursors are dropped first, then the least recently used one if
+    /// too many are parked.
+    pub(crate) fn park(&self, mut cursor: Cursor) -> u64 {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let now = Instant::now();
+        cursor.last_used = now;
+        let mut parked = self.lock();
+        parked.retain(|_, c| now.duration_since(c.last_used) < IDLE_TIMEOUT);
+        if parked.len() >= MAX_CURSORS {
+            let oldest = parked
+                .iter()
+                .min_by_key(|(_, c)| c.last_used)
+                .map(|(id, _)| *id);
+            if let Some(oldest) = oldest {
+                parked.remove(&oldest);
+            }
+        }
+        parked.insert(id, cursor);
+        id
+    }
+
+    /// Appends the `cursors.*` stats.
+    pub(crate) fn append_stats(&self, stats: &mut Stats) {
+        stats.push(("cursors.parked".to_string(), self.lock().len() as i64));
+        stats.push((
+            "cursors.resumed".to_string(),
+            self.resumed.load(Ordering::Relaxed) as i64,
+        ));
+        stats.push((
+            "cursors.opened".to_string(),
+            self.opened.load(Ordering::Relaxed) as i64,
+        ));
+    }
+
+    fn lock(&self) -> MutexGuard<'_, HashMap<u64, Cursor>> {
+        self.parked.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// Serves page requests from one iterator until the key is exhausted, the
+/// iterator fails, or the cursor is dropped.
+async fn run(
+    reader: Arc<LogDbReader>,
+    key: Bytes,
+    start: u64,
+    mut requests: mpsc::Receiver<PageRequest>,
+) {
+    let Some((mut max, mut reply)) = requests.recv().await else {
+        return;
+    };
+    let mut iter = match reader.scan(key, start..).await {
+        Ok(iter) => iter,
+        Err(e) => {
+            let _ = reply.send(Err(e));
+            return;
+        }
+    };
+    let mut entries = Assembler::default();
+    loop {
+        let mut exhausted = false;
+        while entries.len() < max {
+            match iter.next().await {
+                Ok(Some(entry)) => entries.push(entry),
+                Ok(None) => {
+                    exhausted = true;
+                    break;
+                }
+                Err(e) => {
+                    let _ = reply.send(Err(e));
+                    return;
+                }
+            }
+        }
+        let _ = reply.send(Ok(entries.take()));
+        if exhausted {
+            return;
+        }
+        let Some(request) = requests.recv().await else {
+            return;
+        };
+        (max, reply) = request;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_round_trip_token() {
+        // given
+        let token = ResumeToken {
+            cursor: 7,
+            key_hash: key_hash(b"orders"),
+            next_sequence: 42,
+        };
+
+        // when
+        let decoded = ResumeToken::decode(&token.encode(), b"orders");
+
+        // then
+        assert_eq!(decoded, Ok(token));
+    }
+
+    #[test]
+    fn should_reject_token_of_another_key() {
+        // given
+        let token = ResumeToken::start(b"orders", 0);
+
+        // when
+        let decoded = ResumeToken::decode(&token.encode(), b"payments");
+
+        // then
+        assert!(decoded.is_err());
+    }
+
+    #[test]
+    fn should_reject_malformed_token() {
+        assert!(ResumeToken::decode(&[TOKEN_VERSION; 3], b"orders").is_err());
+        assert!(ResumeToken::decode(&[0u8; TOKEN_LEN], b"orders").is_err());
+    }
+}
@@ -0,0 +1,151 @@
+//! Producer dedup windows for idempotent appends.
+//!
+//! Java callers tag each batch with a producer ID and a per-batch sequence
+//! number. For every (producer, key) pair — where the key is that of the
+//! batch's first record — the native layer remembers the last few batch
+//! sequences and the log sequence each was assigned. A retransmitted batch
+//! whose sequence is still in the window is not appended again; the caller
+//! receives the original assignment instead.
+//!
+//! Windows are held in memory only and are lost when the LogDb is closed.
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex, PoisonError};
+
+use bytes::Bytes;
+
+/// Batches remembered for one (producer, key) pair, oldest first.
+pub(crate) type ProducerWindow = VecDeque<(u64, u64)>;
+
+/// Outcome of checking a batch against its producer window.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum DedupCheck {
+    /// The batch has not been seen and should be appended
+    New,
+    /// The batch was already appended starting at this log sequence
+    Duplicate(u64),
+    /// The batch is older than anything in the window, so it cannot be checked
+    BeforeWindow { oldest: u64 },
+}
+
+/// Per-handle dedup windows keyed by (producer ID, first record key).
+pub(crate) struct DedupWindows {
+    capacity: usize,
+    windows: Mutex<HashMap<(u64, Bytes), Arc<tokio::sync::Mutex<ProducerWindow>>>>,
+}
+
+impl DedupWindows {
+    /// Creates dedup windows remembering `capacity` batches per producer and key.
+    pub(crate) fn new(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            windows: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Returns the window for a producer and key, creating it if needed.
+    ///
+    /// Callers hold the window's async lock across check, append, and record so
+    /// that concurrent retransmissions of one batch cannot both be appended.
+    pub(crate) fn window(
+        &self,
+        producer_id: u64,
+        key: &Bytes,
+    ) -> Arc<tokio::sync::Mutex<ProducerWindow>> {
+        let mut windows = self.windows.lock().unwrap_or_else(PoisonError::into_inner);
+        Arc::clone(
+            windows
+                .entry((producer_id, key.clone()))
+                .or_insert_with(|| Arc::new(tokio::sync::Mutex::new(VecDeque::new()))),
+        )
+    }
+
+    /// Checks a batch sequence against a window.
+    pub(crate) fn check(window: &ProducerWindow, batch_sequence: u64) -> DedupCheck {
+        if let Some(&(_, start_sequence)) =
+            window.iter().find(|(batch, _)| *batch == batch_sequence)
+        {
+            return DedupCheck::Duplicate(start_sequence);
+        }
+        match window.front() {
+            Some(&(oldest, _)) if batch_sequence < oldest => DedupCheck::BeforeWindow { oldest },
+            _ => DedupCheck::New,
+        }
+    }
+
+    /// Records an appended batch, evicting the oldest entry when full.
+    pub(crate) fn record(&self, window: &mut ProducerWindow, batch_sequence: u64, start: u64) {
+        if window.len() == self.capacity {
+            window.pop_front();
+        }
+        window.push_back((batch_sequence, start));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_detect_duplicate_batch_in_window() {
+        // given
+        let windows = DedupWindows::new(3);
+        let mut window = ProducerWindow::new();
+        windows.record(&mut window, 7, 100);
+
+        // when
+        let check = DedupWindows::check(&window, 7);
+
+        // then
+        assert_eq!(check, DedupCheck::Duplicate(100));
+    }
+
+    #[test]
+    fn should_accept_new_batch() {
+        // given
+        let windows = DedupWindows::new(3);
+        let mut window = ProducerWindow::new();
+        windows.record(&mut window, 1, 10);
+
+        // when
+        let check = DedupWindows::check(&window, 2);
+
+        // then
+        assert_eq!(check, DedupCheck::New);
+    }
+
+    #[test]
+    fn should_evict_oldest_batch_when_full() {
+        // given
+        let windows = DedupWindows::new(2);
+        let mut window = ProducerWindow::new();
+        windows.record(&mut window, 1, 10);
+        windows.record(&mut window, 2, 20);
+
+        // when
+        windows.record(&mut window, 3, 30);
+
+        // then
+        assert_eq!(
+            DedupWindows::check(&window, 1),
+            DedupCheck::BeforeWindow { oldest: 2 }
+        );
+        assert_eq!(DedupWindows::check(&window, 3), DedupCheck::Duplicate(30));
+    }
+
+    #[test]
+    fn should_share_window_per_producer_and_key() {
+        // given
+        let windows = DedupWindows::new(2);
+        let key = Bytes::from_static(b"key");
+
+        // when
+        let first = windows.window(1, &key);
+        let same = windows.window(1, &key);
+        let other_producer = windows.window(2, &key);
+
+        // then
+        assert!(Arc::ptr_eq(&first, &same));
+        assert!(!Arc::ptr_eq(&first, &other_producer));
+    }
+}
@@ -0,0 +1,118 @@
+//! Per-key tails of appended entries, for durability barriers.
+//!
+//! A crash-recovery test checks that every append acknowledged before a
+//! crash survives it, which needs to know how far each key was durable. A
+//! sync snapshots the tail, one past the last sequence, of every key
+//! appended through the handle, then flushes; once the flush returns, each
+//! key is durable up to its snapshotted tail. Appends acknowledged after the
+//! snapshot may be flushed too but are not reported.
+//!
+//! Readers paired with the handle read their writes by waiting until their
+//! view reaches a key's tail before scanning it.
+//!
+//! Tails are recorded once storage accepted an append, alongside watch
+//! notifications, and are held in memory for every key the handle appended
+//! to. Appends through another handle or process, or through the C ABI, are
+//! not recorded.
+
+use std::collections::HashMap;
+use std::sync::{Mutex, MutexGuard, PoisonError};
+
+use bytes::Bytes;
+use log::Record;
+
+/// Tail of each key appended through a handle.
+#[derive(Default)]
+pub(crate) struct AppendedTails {
+    tails: Mutex<HashMap<Bytes, u64>>,
+}
+
+/// Offset past the last record of each key of a batch, captured before the
+/// batch is appended.
+pub(crate) struct BatchOffsets(HashMap<Bytes, u64>);
+
+impl AppendedTails {
+    /// Captures the keys of a batch.
+    pub(crate) fn capture(records: &[Record]) -> BatchOffsets {
+        let mut offsets = HashMap::new();
+        for (i, record) in records.iter().enumerate() {
+            offsets.insert(record.key.clone(), i as u64 + 1);
+        }
+        BatchOffsets(offsets)
+    }
+
+    /// Records the tails of a batch appended at `start_sequence`.
+    pub(crate) fn record(&self, start_sequence: u64, offsets: BatchOffsets) {
+        let mut tails = self.lock();
+        for (key, offset) in offsets.0 {
+            let tail = tails.entry(key).or_default();
+            *tail = (*tail).max(start_sequence + offset);
+        }
+    }
+
+    /// Returns the tail of `key`, if it was appended to.
+    pub(crate) fn tail(&self, key: &[u8]) -> Option<u64> {
+        self.lock().get(key).copied()
+    }
+
+    /// Returns the tail of every key appended to, ordered by key.
+    pub(crate) fn snapshot(&self) -> Vec<(Bytes, u64)> {
+        let mut tails: Vec<(Bytes, u64)> = self
+            .lock()
+            .iter()
+            .map(|(key, tail)| (key.clone(), *tail))
+            .collect();
+        tails.sort();
+        tails
+    }
+
+    fn lock(&self) -> MutexGuard<'_, HashMap<Bytes, u64>> {
+        self.tails.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(key: &'static [u8]) -> Record {
+        Record {
+            key: Bytes::from_static(key),
+            value: Bytes::new(),
+        }
+    }
+
+    #[test]
+    fn should_record_tail_of_each_key() {
+        // given
+        let tails = AppendedTails::default();
+        let first = AppendedTails::capture(&[record(b"b"), record(b"a"), record(b"b")]);
+        let second = AppendedTails::capture(&[record(b"a")]);
+
+        // when
+        tails.record(10, first);
+        tails.record(13, second);
+
+        // then
+        assert_eq!(
+            tails.snapshot(),
+            vec![
+                (Bytes::from_static(b"a"), 14),
+                (Bytes::from_static(b"b"), 13),
+            ]
+        );
+    }
+
+    #[test]
+    fn should_keep_highest_tail_when_batches_record_out_of_order() {
+        // given
+        let tails = AppendedTails::default();
+
+        // when
+        tails.record(20, AppendedTails::capture(&[record(b"a")]));
+        tails.record(5, AppendedTails::capture(&[record(b"a")]));
+
+        // then
+        assert_eq!(tails.snapshot(), vec![(Bytes::from_static(b"a"), 21)]);
+    }
+}
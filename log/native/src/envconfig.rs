@@ -0,0 +1,267 @@
+//! Environment-variable overrides of extracted settings.
+//!
+//! After the settings of a handle have been extracted, from a Java config or
+//! a config file, `OPENDATA_*` environment variables replace individual
+//! values, so a harness can inject per-pod settings such as the bucket or
+//! cache size without changing the config it passes. A variable is named
+//! after the setting's dotted path in a config file (see `configfile.rs`),
+//! upper-cased with dots as underscores: `storage.object_store.bucket` is
+//! overridden by `OPENDATA_STORAGE_OBJECT_STORE_BUCKET`.
+//!
+//! Only the settings listed in [`LOG_OVERRIDES`], [`STORAGE_OVERRIDES`] and
+//! [`DISK_CACHE_OVERRIDES`] can be overridden. A value that does not parse,
+//! or that targets storage or a disk cache the config does not use, fails
+//! the open, and every applied override is logged so a pod's effective
+//! settings can be told from its log.
+
+use std::time::Duration;
+
+use common::storage::config::{ObjectStoreConfig, StorageConfig};
+
+use crate::diskcache::DiskCacheSettings;
+use crate::logging;
+use crate::LogSettings;
+
+/// SLF4J logger applied overrides are logged under.
+const TARGET: &str = "dev.opendata.EnvironmentOverrides";
+
+type Override<T> = (&'static str, fn(&mut T, &str) -> Result<(), String>);
+
+/// Overrides of LogDb settings other than storage.
+const LOG_OVERRIDES: &[Override<LogSettings>] = &[
+    ("OPENDATA_PROFILING", |s, v| {
+        s.profiling = parse(v)?;
+        Ok(())
+    }),
+    ("OPENDATA_DEDUP_WINDOW", |s, v| {
+        s.dedup_window = parse(v)?;
+        if s.dedup_window == 0 {
+            return Err("must be positive".to_string());
+        }
+        Ok(())
+    }),
+    ("OPENDATA_BRIDGE_THREADS", |s, v| {
+        s.bridge_threads = parse(v)?;
+        Ok(())
+    }),
+    ("OPENDATA_TAIL_CACHE_BYTES", |s, v| {
+        s.tail_cache_bytes = parse(v)?;
+        Ok(())
+    }),
+    ("OPENDATA_SLOW_OPERATION_THRESHOLD_MS", |s, v| {
+        s.slow_operation_threshold = Duration::from_millis(parse(v)?);
+        Ok(())
+    }),
+    ("OPENDATA_OPEN_TIMEOUT_MS", |s, v| {
+        s.open_timeout = Duration::from_millis(parse(v)?);
+        Ok(())
+    }),
+    ("OPENDATA_RATE_LIMITS_PRODUCE_RECORDS_PER_SEC", |s, v| {
+        s.rate_limits.produce_records_per_sec = parse(v)?;
+        Ok(())
+    }),
+    ("OPENDATA_RATE_LIMITS_PRODUCE_BYTES_PER_SEC", |s, v| {
+        s.rate_limits.produce_bytes_per_sec = parse(v)?;
+        Ok(())
+    }),
+    ("OPENDATA_RATE_LIMITS_CONSUME_RECORDS_PER_SEC", |s, v| {
+        s.rate_limits.consume_records_per_sec = parse(v)?;
+        Ok(())
+    }),
+    ("OPENDATA_RATE_LIMITS_CONSUME_BYTES_PER_SEC", |s, v| {
+        s.rate_limits.consume_bytes_per_sec = parse(v)?;
+        Ok(())
+    }),
+];
+
+/// Overrides of storage settings, shared by LogDb and LogDbReader.
+const STORAGE_OVERRIDES: &[Override<StorageConfig>] = &[
+    ("OPENDATA_STORAGE_PATH", |s, v| {
+        match s {
+            StorageConfig::SlateDb(slatedb) => slatedb.path = v.to_string(),
+            _ => return Err("requires SlateDB storage".to_string()),
+        }
+        Ok(())
+    }),
+    ("OPENDATA_STORAGE_SETTINGS_PATH", |s, v| {
+        match s {
+            StorageConfig::SlateDb(slatedb) => slatedb.settings_path = Some(v.to_string()),
+            _ => return Err("requires SlateDB storage".to_string()),
+        }
+        Ok(())
+    }),
+    ("OPENDATA_STORAGE_OBJECT_STORE_BUCKET", |s, v| {
+        match s {
+            StorageConfig::SlateDb(slatedb) => match &mut slatedb.object_store {
+                ObjectStoreConfig::Aws(aws) => aws.bucket = v.to_string(),
+                _ => return Err("requires an AWS object store".to_string()),
+            },
+            _ => return Err("requires SlateDB storage".to_string()),
+        }
+        Ok(())
+    }),
+    ("OPENDATA_STORAGE_OBJECT_STORE_REGION", |s, v| {
+        match s {
+            StorageConfig::SlateDb(slatedb) => match &mut slatedb.object_store {
+                ObjectStoreConfig::Aws(aws) => aws.region = v.to_string(),
+                _ => return Err("requires an AWS object store".to_string()),
+            },
+            _ => return Err("requires SlateDB storage".to_string()),
+        }
+        Ok(())
+    }),
+    ("OPENDATA_STORAGE_OBJECT_STORE_PATH", |s, v| {
+        match s {
+            StorageConfig::SlateDb(slatedb) => match &mut slatedb.object_store {
+                ObjectStoreConfig::Local(local) => local.path = v.to_string(),
+                _ => return Err("requires a local object store".to_string()),
+            },
+            _ => return Err("requires SlateDB storage".to_string()),
+        }
+        Ok(())
+    }),
+];
+
+/// Overrides of the disk cache of SlateDB storage, shared by LogDb and LogDbReader.
+const DISK_CACHE_OVERRIDES: &[Override<Option<DiskCacheSettings>>] = &[
+    ("OPENDATA_STORAGE_DISK_CACHE_PATH", |s, v| {
+        match s {
+            Some(disk_cache) => disk_cache.root = v.into(),
+            None => return Err("requires a configured disk cache".to_string()),
+        }
+        Ok(())
+    }),
+    ("OPENDATA_STORAGE_DISK_CACHE_MAX_BYTES", |s, v| {
+        let max_bytes: u64 = parse(v)?;
+        if max_bytes == 0 {
+            return Err("must be positive".to_string());
+        }
+        match s {
+            Some(disk_cache) => disk_cache.max_bytes = max_bytes,
+            None => return Err("requires a configured disk cache".to_string()),
+        }
+        Ok(())
+    }),
+];
+
+/// Applies the `OPENDATA_*` variables of the process environment to LogDb settings.
+pub(crate) fn apply_log(settings: &mut LogSettings) -> Result<(), String> {
+    apply_overrides(settings, LOG_OVERRIDES, env_var)?;
+    apply_storage(&mut settings.storage, &mut settings.disk_cache)
+}
+
+/// Applies the storage `OPENDATA_*` variables of the process environment.
+pub(crate) fn apply_storage(
+    storage: &mut StorageConfig,
+    disk_cache: &mut Option<DiskCacheSettings>,
+) -> Result<(), String> {
+    apply_overrides(storage, STORAGE_OVERRIDES, env_var)?;
+    apply_overrides(disk_cache, DISK_CACHE_OVERRIDES, env_var)
+}
+
+fn env_var(name: &str) -> Option<String> {
+    std::env::var(name).ok()
+}
+
+fn apply_overrides<T>(
+    target: &mut T,
+    overrides: &[Override<T>],
+    lookup: impl Fn(&str) -> Option<String>,
+) -> Result<(), String> {
+    for (name, set) in overrides {
+        let Some(value) = lookup(name) else {
+            continue;
+        };
+        set(target, value.trim()).map_err(|e| format!("Invalid {}={}: {}", name, value, e))?;
+        logging::info(TARGET, &format!("Applied {}={}", name, value));
+    }
+    Ok(())
+}
+
+fn parse<T: std::str::FromStr>(value: &str) -> Result<T, String>
+where
+    T::Err: std::fmt::Display,
+{
+    value.parse().map_err(|e: T::Err| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use common::storage::config::{AwsObjectStoreConfig, SlateDbStorageConfig};
+
+    use super::*;
+
+    fn aws_storage() -> StorageConfig {
+        StorageConfig::SlateDb(SlateDbStorageConfig {
+            path: "data".to_string(),
+            object_store: ObjectStoreConfig::Aws(AwsObjectStoreConfig {
+                region: "us-east-1".to_string(),
+                bucket: "bucket".to_string(),
+            }),
+            settings_path: None,
+        })
+    }
+
+    fn lookup(vars: &[(&str, &str)]) -> impl Fn(&str) -> Option<String> {
+        let vars: HashMap<String, String> = vars
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect();
+        move |name| vars.get(name).cloned()
+    }
+
+    #[test]
+    fn should_override_object_store_bucket() {
+        // given
+        let mut storage = aws_storage();
+        let vars = lookup(&[("OPENDATA_STORAGE_OBJECT_STORE_BUCKET", "pod-7")]);
+
+        // when
+        apply_overrides(&mut storage, STORAGE_OVERRIDES, vars).unwrap();
+
+        // then
+        let StorageConfig::SlateDb(slatedb) = storage else {
+            panic!("expected SlateDB storage");
+        };
+        let ObjectStoreConfig::Aws(aws) = slatedb.object_store else {
+            panic!("expected an AWS object store");
+        };
+        assert_eq!(aws.bucket, "pod-7");
+        assert_eq!(aws.region, "us-east-1");
+    }
+
+    #[test]
+    fn should_reject_override_of_unused_storage() {
+        // given
+        let mut storage = StorageConfig::InMemory;
+        let vars = lookup(&[("OPENDATA_STORAGE_PATH", "data")]);
+
+        // when
+        let result = apply_overrides(&mut storage, STORAGE_OVERRIDES, vars);
+
+        // then
+        assert_eq!(
+            result,
+            Err("Invalid OPENDATA_STORAGE_PATH=data: requires SlateDB storage".to_string())
+        );
+    }
+
+    #[test]
+    fn should_reject_unparsable_value() {
+        // given
+        let mut disk_cache = Some(DiskCacheSettings {
+            root: "/cache".into(),
+            max_bytes: 1 << 20,
+        });
+        let vars = lookup(&[("OPENDATA_STORAGE_DISK_CACHE_MAX_BYTES", "1GB")]);
+
+        // when
+        let result = apply_overrides(&mut disk_cache, DISK_CACHE_OVERRIDES, vars);
+
+        // then
+        assert!(result.is_err());
+        assert_eq!(disk_cache.map(|d| d.max_bytes), Some(1 << 20));
+    }
+}
@@ -0,0 +1,354 @@
+//! Export of a key's entries to a local file, and import of such files.
+//!
+//! Debugging a benchmark run, or checking that OMB payloads came back intact,
+//! means reading every entry of a key. Scanning them into the JVM costs a
+//! Java object and two arrays per entry, so the export instead streams them
+//! from the log's scan iterator straight into a buffered file. Entries are
+//! written as they are read, without holding the range in memory.
+//!
+//! The file is written under a temporary name and renamed into place once
+//! complete, so a failed export never leaves a partial file at the path.
+//!
+//! Import reads an export back in batches and appends them, so backlog
+//! scenarios can be pre-populated without marshalling each record through
+//! Java. Entries get new sequences; NDJSON entries keep their key, unless one
+//! is given, and timestamp, while raw payloads, which carry neither, are
+//! appended under the given key with the time of the import.
+
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader, BufWriter, Read, Write};
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use bytes::Bytes;
+use jni::sys::jint;
+use log::{LogEntry, LogRead, Record};
+use serde_json::Value;
+
+use crate::chunking::Assembler;
+use crate::{extract_timestamp_and_payload, TIMESTAMP_HEADER_SIZE};
+
+/// Most records an import appends at once.
+const IMPORT_BATCH_RECORDS: usize = 1000;
+
+/// Most payload bytes an import appends at once.
+const IMPORT_BATCH_BYTES: usize = 4 << 20;
+
+/// Layout of an export file.
+///
+/// Mirrors the Java `ExportFormat` enum by ordinal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ExportFormat {
+    /// One JSON object per line with the sequence, timestamp, and base64 key
+    /// and payload
+    Ndjson,
+    /// Each payload as a 4-byte big-endian length followed by its bytes
+    Raw,
+}
+
+impl ExportFormat {
+    /// Converts a Java `ExportFormat.ordinal()` value into a format.
+    pub(crate) fn from_ordinal(ordinal: jint) -> Option<Self> {
+        match ordinal {
+            0 => Some(Self::Ndjson),
+            1 => Some(Self::Raw),
+            _ => None,
+        }
+    }
+}
+
+/// Why an export failed.
+#[derive(Debug)]
+pub(crate) enum ExportError {
+    /// Scanning the log failed
+    Storage(log::Error),
+    /// Writing the file failed
+    Io(PathBuf, io::Error),
+}
+
+impl std::fmt::Display for ExportError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Storage(e) => write!(f, "{}", e),
+            Self::Io(path, e) => write!(f, "Failed to write {}: {}", path.display(), e),
+        }
+    }
+}
+
+/// Writes the entries of `key` with sequences in `range` to `path`,
+/// returning the number of entries written.
+pub(crate) async fn export<R: LogRead>(
+    log: &R,
+    key: Bytes,
+    range: Range<u64>,
+    format: ExportFormat,
+    path: &Path,
+) -> Result<u64, ExportError> {
+    let partial = path.with_extension(format!("partial.{}", std::process::id()));
+    let io_error = |e| ExportError::Io(partial.clone(), e);
+    let mut writer = BufWriter::new(File::create(&partial).map_err(io_error)?);
+    let result = async {
+        let mut iter = log.scan(key, range).await.map_err(ExportError::Storage)?;
+        let mut entries = Assembler::default();
+        let mut written = 0;
+        while let Some(entry) = iter.next().await.map_err(ExportError::Storage)? {
+            entries.push(entry);
+            for entry in entries.take() {
+                write_entry(&mut writer, format, &entry).map_err(io_error)?;
+                written += 1;
+            }
+        }
+        writer.flush().map_err(io_error)?;
+        Ok(written)
+    }
+    .await;
+    drop(writer);
+    match result {
+        Ok(written) => {
+            fs::rename(&partial, path).map_err(|e| ExportError::Io(path.to_path_buf(), e))?;
+            Ok(written)
+        }
+        Err(e) => {
+            let _ = fs::remove_file(&partial);
+            Err(e)
+        }
+    }
+}
+
+fn write_entry(writer: &mut impl Write, format: ExportFormat, entry: &LogEntry) -> io::Result<()> {
+    let (timestamp_ms, payload) = extract_timestamp_and_payload(&entry.value);
+    match format {
+        ExportFormat::Ndjson => writeln!(
+            writer,
+            "{{\"sequence\":{},\"timestamp_ms\":{},\"key\":\"{}\",\"value\":\"{}\"}}",
+            entry.sequence,
+            timestamp_ms,
+            STANDARD.encode(&entry.key),
+            STANDARD.encode(payload)
+        ),
+        ExportFormat::Raw => {
+            let len = u32::try_from(payload.len())
+                .map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "payload too large"))?;
+            writer.write_all(&len.to_be_bytes())?;
+            writer.write_all(payload)
+        }
+    }
+}
+
+/// Reads the records of an export file, batch by batch.
+pub(crate) struct Importer {
+    reader: BufReader<File>,
+    path: PathBuf,
+    format: ExportFormat,
+    /// Key every record is appended under, instead of the file's
+    key: Option<Bytes>,
+    /// Lines of an NDJSON file read so far
+    line: usize,
+}
+
+impl Importer {
+    /// Opens an export file; raw files need a key to append under.
+    pub(crate) fn open(
+        path: &Path,
+        format: ExportFormat,
+        key: Option<Bytes>,
+    ) -> Result<Self, String> {
+        if format == ExportFormat::Raw && key.is_none() {
+            return Err("Importing raw payloads requires a key".to_string());
+        }
+        let file =
+            File::open(path).map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+        Ok(Self {
+            reader: BufReader::new(file),
+            path: path.to_path_buf(),
+            format,
+            key,
+            line: 0,
+        })
+    }
+
+    /// Reads the next batch of records with timestamp headers, empty at the
+    /// end of the file. Raw payloads are stamped with `now_ms`.
+    pub(crate) fn next_batch(&mut self, now_ms: i64) -> Result<Vec<Record>, String> {
+        let mut batch = Vec::new();
+        let mut bytes = 0;
+        while batch.len() < IMPORT_BATCH_RECORDS && bytes < IMPORT_BATCH_BYTES {
+            let record = match self.format {
+                ExportFormat::Ndjson => self.read_json()?,
+                ExportFormat::Raw => self.read_raw(now_ms)?,
+            };
+            let Some(record) = record else {
+                break;
+            };
+            bytes += record.value.len();
+            batch.push(record);
+        }
+        Ok(batch)
+    }
+
+    fn read_json(&mut self) -> Result<Option<Record>, String> {
+        let mut text = String::new();
+        loop {
+            text.clear();
+            let read = self
+                .reader
+                .read_line(&mut text)
+                .map_err(|e| format!("Failed to read {}: {}", self.path.display(), e))?;
+            if read == 0 {
+                return Ok(None);
+            }
+            self.line += 1;
+            if !text.trim().is_empty() {
+                break;
+            }
+        }
+        let invalid = |reason: &str| {
+            format!(
+                "Invalid entry on line {} of {}: {}",
+                self.line,
+                self.path.display(),
+                reason
+            )
+        };
+        let entry: Value = serde_json::from_str(&text).map_err(|e| invalid(&e.to_string()))?;
+        let decode = |field: &str| {
+            let text = entry[field]
+                .as_str()
+                .ok_or_else(|| invalid(&format!("{} must be a base64 string", field)))?;
+            STANDARD
+                .decode(text)
+                .map_err(|e| invalid(&format!("{}: {}", field, e)))
+        };
+        let payload = decode("value")?;
+        let key = match &self.key {
+            Some(key) => key.clone(),
+            None => Bytes::from(decode("key")?),
+        };
+        let timestamp_ms = entry["timestamp_ms"]
+            .as_i64()
+            .ok_or_else(|| invalid("timestamp_ms must be an integer"))?;
+        Ok(Some(Record {
+            key,
+            value: timestamped(timestamp_ms, &payload),
+        }))
+    }
+
+    fn read_raw(&mut self, now_ms: i64) -> Result<Option<Record>, String> {
+        let failed = |e: io::Error| format!("Failed to read {}: {}", self.path.display(), e);
+        let mut len = [0; 4];
+        match self.reader.read_exact(&mut len) {
+            Ok(()) => {}
+            Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+            Err(e) => return Err(failed(e)),
+        }
+        let mut payload = vec![0; u32::from_be_bytes(len) as usize];
+        self.reader.read_exact(&mut payload).map_err(failed)?;
+        Ok(Some(Record {
+            key: self.key.clone().unwrap_or_default(),
+            value: timestamped(now_ms, &payload),
+        }))
+    }
+}
+
+/// Prepends the timestamp header to a payload.
+fn timestamped(timestamp_ms: i64, payload: &[u8]) -> Bytes {
+    let mut value = Vec::with_capacity(TIMESTAMP_HEADER_SIZE + payload.len());
+    value.extend_from_slice(&timestamp_ms.to_be_bytes());
+    value.extend_from_slice(payload);
+    Bytes::from(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(sequence: u64, timestamp_ms: i64, payload: &[u8]) -> LogEntry {
+        let mut value = timestamp_ms.to_be_bytes().to_vec();
+        value.extend_from_slice(payload);
+        LogEntry {
+            key: Bytes::from_static(b"key"),
+            sequence,
+            value: Bytes::from(value),
+        }
+    }
+
+    #[test]
+    fn should_write_entry_as_json_line_without_timestamp_header() {
+        // given
+        let mut out = Vec::new();
+
+        // when
+        write_entry(&mut out, ExportFormat::Ndjson, &entry(7, 1234, b"hi")).unwrap();
+
+        // then
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "{\"sequence\":7,\"timestamp_ms\":1234,\"key\":\"a2V5\",\"value\":\"aGk=\"}\n"
+        );
+    }
+
+    #[test]
+    fn should_write_raw_payload_with_length_prefix() {
+        // given
+        let mut out = Vec::new();
+
+        // when
+        write_entry(&mut out, ExportFormat::Raw, &entry(0, 1, b"abc")).unwrap();
+
+        // then
+        assert_eq!(out, [0, 0, 0, 3, b'a', b'b', b'c']);
+    }
+
+    fn imported(format: ExportFormat, key: Option<Bytes>, entries: &[LogEntry]) -> Vec<Record> {
+        let mut out = Vec::new();
+        for entry in entries {
+            write_entry(&mut out, format, entry).unwrap();
+        }
+        let path = std::env::temp_dir().join(format!(
+            "opendata-import-test-{}-{:?}",
+            std::process::id(),
+            format
+        ));
+        fs::write(&path, out).unwrap();
+        let mut importer = Importer::open(&path, format, key).unwrap();
+        let batch = importer.next_batch(99).unwrap();
+        assert!(importer.next_batch(99).unwrap().is_empty());
+        fs::remove_file(&path).unwrap();
+        batch
+    }
+
+    #[test]
+    fn should_import_exported_json_with_keys_and_timestamps() {
+        // given
+        let entries = [entry(3, 1234, b"first"), entry(4, 1235, b"second")];
+
+        // when
+        let records = imported(ExportFormat::Ndjson, None, &entries);
+
+        // then
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].key, Bytes::from_static(b"key"));
+        assert_eq!(records[0].value, entries[0].value);
+        assert_eq!(records[1].value, entries[1].value);
+    }
+
+    #[test]
+    fn should_import_raw_payloads_under_given_key_at_import_time() {
+        // given
+        let entries = [entry(0, 1234, b"payload")];
+
+        // when
+        let records = imported(
+            ExportFormat::Raw,
+            Some(Bytes::from_static(b"backlog")),
+            &entries,
+        );
+
+        // then
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].key, Bytes::from_static(b"backlog"));
+        assert_eq!(records[0].value, timestamped(99, b"payload"));
+    }
+}
@@ -0,0 +1,529 @@
+//! Object-store fault injection for resilience testing.
+//!
+//! When configured, the object store the log's SlateDB instance opens on is
+//! wrapped in a [`FaultyObjectStore`], which delays each request by a latency
+//! drawn uniformly from a range and fails it at fixed probabilities, so retry
+//! and error handling can be exercised without a flaky network. Requests are
+//! targeted by the Java operation they serve: puts, copies and deletes of
+//! WAL SSTs, compacted SSTs and manifests by appends, gets, heads and lists
+//! by scans. Requests made by flushes, compaction and manifest polling are
+//! targeted as well, as they are the same requests.
+//!
+//! SlateDB retries failed object-store requests with backoff, as it does
+//! those of a real store, so a fault shows in Java as the latency of the
+//! call it held up, typically a durable append, a flush or a scan reading an
+//! SST. Appends acknowledged before their WAL SST is written never wait on
+//! the object store at all.
+//!
+//! The handle builds the object store itself, from its storage config, and
+//! hands it to the log through its storage runtime, so fault injection needs
+//! SlateDB storage; in-memory storage has no object store to wrap. The handle
+//! keeps the store for reopens, so a reopened log sees the same faults, and
+//! an in-memory object store the same data.
+//!
+//! Random draws come from a seeded xorshift generator, so runs with the same
+//! seed and request order inject the same faults.
+
+use std::ops::Range;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use bytes::Bytes;
+use common::storage::config::{ObjectStoreConfig, StorageConfig};
+use futures::stream::BoxStream;
+use futures::{StreamExt, TryFutureExt};
+use object_store::memory::InMemory;
+use object_store::path::Path;
+use object_store::{
+    GetOptions, GetResult, ListResult, MultipartUpload, ObjectMeta, ObjectStore, PutMultipartOpts,
+    PutOptions, PutPayload, PutResult,
+};
+
+use crate::checkpoints;
+use crate::metrics::{Operation, Stats};
+
+/// Store named in the errors of injected faults.
+const STORE: &str = "FaultInjection";
+
+/// Fault injection settings extracted from a Java `FaultInjection`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct FaultSettings {
+    /// Probability that a targeted request fails with a storage error
+    pub(crate) error_probability: f64,
+    /// Probability that a targeted request is rejected as throttled
+    pub(crate) throttle_probability: f64,
+    /// Probability that a targeted request times out
+    pub(crate) timeout_probability: f64,
+    /// How long a timed-out request hangs before failing
+    pub(crate) timeout: Duration,
+    /// Lower bound of the latency added to targeted requests
+    pub(crate) min_latency: Duration,
+    /// Upper bound of the latency added to targeted requests
+    pub(crate) max_latency: Duration,
+    /// Targeted operations, one bit per `Operation` ordinal
+    pub(crate) operations: u32,
+    /// Seed of the random generator
+    pub(crate) seed: u64,
+}
+
+/// How an injected fault fails a request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum FaultKind {
+    Error,
+    Throttled,
+    TimedOut,
+}
+
+/// An error injected in place of an object-store request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct InjectedFault {
+    /// Operation the request was targeted as
+    pub(crate) operation: Operation,
+    pub(crate) kind: FaultKind,
+}
+
+impl std::fmt::Display for InjectedFault {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.kind {
+            FaultKind::Error => write!(f, "Injected fault: {:?} request failed", self.operation),
+            FaultKind::Throttled => write!(
+                f,
+                "Injected fault: {:?} request throttled (503 SlowDown)",
+                self.operation
+            ),
+            FaultKind::TimedOut => {
+                write!(f, "Injected fault: {:?} request timed out", self.operation)
+            }
+        }
+    }
+}
+
+impl std::error::Error for InjectedFault {}
+
+impl From<InjectedFault> for object_store::Error {
+    fn from(fault: InjectedFault) -> Self {
+        object_store::Error::Generic {
+            store: STORE,
+            source: Box::new(fault),
+        }
+    }
+}
+
+/// Per-handle fault injector.
+#[derive(Debug)]
+pub(crate) struct FaultInjector {
+    settings: FaultSettings,
+    state: Mutex<u64>,
+    injected_errors: AtomicU64,
+    injected_throttles: AtomicU64,
+    injected_timeouts: AtomicU64,
+    injected_latency_nanos: AtomicU64,
+}
+
+impl FaultInjector {
+    pub(crate) fn new(settings: FaultSettings) -> Self {
+        Self {
+            settings,
+            // xorshift never leaves the all-zero state
+            state: Mutex::new(settings.seed.max(1)),
+            injected_errors: AtomicU64::new(0),
+            injected_throttles: AtomicU64::new(0),
+            injected_timeouts: AtomicU64::new(0),
+            injected_latency_nanos: AtomicU64::new(0),
+        }
+    }
+
+    /// Delays and possibly fails a request, returning whether it may proceed.
+    pub(crate) async fn inject(&self, op: Operation) -> Result<(), InjectedFault> {
+        let Some((mut latency, kind)) = self.draw(op) else {
+            return Ok(());
+        };
+        let counter = match kind {
+            Some(FaultKind::Error) => Some(&self.injected_errors),
+            Some(FaultKind::Throttled) => Some(&self.injected_throttles),
+            Some(FaultKind::TimedOut) => {
+                latency += self.settings.timeout;
+                Some(&self.injected_timeouts)
+            }
+            None => None,
+        };
+        if let Some(counter) = counter {
+            counter.fetch_add(1, Ordering::Relaxed);
+        }
+        if !latency.is_zero() {
+            self.injected_latency_nanos
+                .fetch_add(latency.as_nanos() as u64, Ordering::Relaxed);
+            tokio::time::sleep(latency).await;
+        }
+        match kind {
+            Some(kind) => Err(InjectedFault {
+                operation: op,
+                kind,
+            }),
+            None => Ok(()),
+        }
+    }
+
+    /// Draws the latency and fault for a request, or None if `op` is not
+    /// targeted.
+    fn draw(&self, op: Operation) -> Option<(Duration, Option<FaultKind>)> {
+        if self.settings.operations & (1 << op as u32) == 0 {
+            return None;
+        }
+        let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+        let span = self
+            .settings
+            .max_latency
+            .saturating_sub(self.settings.min_latency);
+        let latency =
+            self.settings.min_latency + span.mul_f64(unit_interval(next_random(&mut state)));
+        let outcome = unit_interval(next_random(&mut state));
+        let settings = &self.settings;
+        let kind = if outcome < settings.error_probability {
+            Some(FaultKind::Error)
+        } else if outcome < settings.error_probability + settings.throttle_probability {
+            Some(FaultKind::Throttled)
+        } else if outcome
+            < settings.error_probability
+                + settings.throttle_probability
+                + settings.timeout_probability
+        {
+            Some(FaultKind::TimedOut)
+        } else {
+            None
+        };
+        Some((latency, kind))
+    }
+
+    /// Appends the number of injected faults of each kind and total injected latency.
+    pub(crate) fn append_stats(&self, stats: &mut Stats) {
+        stats.push((
+            "faults.injected_errors".to_string(),
+            self.injected_errors.load(Ordering::Relaxed) as i64,
+        ));
+        stats.push((
+            "faults.injected_throttles".to_string(),
+            self.injected_throttles.load(Ordering::Relaxed) as i64,
+        ));
+        stats.push((
+            "faults.injected_timeouts".to_string(),
+            self.injected_timeouts.load(Ordering::Relaxed) as i64,
+        ));
+        stats.push((
+            "faults.injected_latency_nanos".to_string(),
+            self.injected_latency_nanos.load(Ordering::Relaxed) as i64,
+        ));
+    }
+}
+
+/// Builds the object store of SlateDB storage, wrapped to inject faults.
+pub(crate) fn faulty_object_store(
+    storage: &StorageConfig,
+    faults: &Arc<FaultInjector>,
+) -> Result<Arc<dyn ObjectStore>, String> {
+    let StorageConfig::SlateDb(slatedb) = storage else {
+        return Err(
+            "Fault injection needs SlateDB storage, whose object store it wraps".to_string(),
+        );
+    };
+    let inner = match &slatedb.object_store {
+        ObjectStoreConfig::InMemory => Arc::new(InMemory::new()) as Arc<dyn ObjectStore>,
+        config => checkpoints::object_store(config)?,
+    };
+    Ok(Arc::new(FaultyObjectStore {
+        inner,
+        faults: Arc::clone(faults),
+    }))
+}
+
+/// Object store injecting faults into the requests made of another.
+#[derive(Debug)]
+pub(crate) struct FaultyObjectStore {
+    inner: Arc<dyn ObjectStore>,
+    faults: Arc<FaultInjector>,
+}
+
+impl std::fmt::Display for FaultyObjectStore {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "FaultyObjectStore({})", self.inner)
+    }
+}
+
+#[async_trait]
+impl ObjectStore for FaultyObjectStore {
+    async fn put_opts(
+        &self,
+        location: &Path,
+        payload: PutPayload,
+        opts: PutOptions,
+    ) -> object_store::Result<PutResult> {
+        self.faults.inject(Operation::Append).await?;
+        self.inner.put_opts(location, payload, opts).await
+    }
+
+    async fn put_multipart_opts(
+        &self,
+        location: &Path,
+        opts: PutMultipartOpts,
+    ) -> object_store::Result<Box<dyn MultipartUpload>> {
+        self.faults.inject(Operation::Append).await?;
+        self.inner.put_multipart_opts(location, opts).await
+    }
+
+    async fn get_opts(
+        &self,
+        location: &Path,
+        options: GetOptions,
+    ) -> object_store::Result<GetResult> {
+        self.faults.inject(Operation::Scan).await?;
+        self.inner.get_opts(location, options).await
+    }
+
+    async fn get_range(&self, location: &Path, range: Range<u64>) -> object_store::Result<Bytes> {
+        self.faults.inject(Operation::Scan).await?;
+        self.inner.get_range(location, range).await
+    }
+
+    async fn get_ranges(
+        &self,
+        location: &Path,
+        ranges: &[Range<u64>],
+    ) -> object_store::Result<Vec<Bytes>> {
+        self.faults.inject(Operation::Scan).await?;
+        self.inner.get_ranges(location, ranges).await
+    }
+
+    async fn head(&self, location: &Path) -> object_store::Result<ObjectMeta> {
+        self.faults.inject(Operation::Scan).await?;
+        self.inner.head(location).await
+    }
+
+    async fn delete(&self, location: &Path) -> object_store::Result<()> {
+        self.faults.inject(Operation::Append).await?;
+        self.inner.delete(location).await
+    }
+
+    fn list(&self, prefix: Option<&Path>) -> BoxStream<'static, object_store::Result<ObjectMeta>> {
+        let faults = Arc::clone(&self.faults);
+        let listed = self.inner.list(prefix);
+        async move {
+            faults.inject(Operation::Scan).await?;
+            Ok::<_, object_store::Error>(listed)
+        }
+        .try_flatten_stream()
+        .boxed()
+    }
+
+    async fn list_with_delimiter(&self, prefix: Option<&Path>) -> object_store::Result<ListResult> {
+        self.faults.inject(Operation::Scan).await?;
+        self.inner.list_with_delimiter(prefix).await
+    }
+
+    async fn copy(&self, from: &Path, to: &Path) -> object_store::Result<()> {
+        self.faults.inject(Operation::Append).await?;
+        self.inner.copy(from, to).await
+    }
+
+    async fn rename(&self, from: &Path, to: &Path) -> object_store::Result<()> {
+        self.faults.inject(Operation::Append).await?;
+        self.inner.rename(from, to).await
+    }
+
+    async fn copy_if_not_exists(&self, from: &Path, to: &Path) -> object_store::Result<()> {
+        self.faults.inject(Operation::Append).await?;
+        self.inner.copy_if_not_exists(from, to).await
+    }
+
+    async fn rename_if_not_exists(&self, from: &Path, to: &Path) -> object_store::Result<()> {
+        self.faults.inject(Operation::Append).await?;
+        self.inner.rename_if_not_exists(from, to).await
+    }
+}
+
+pub(crate) fn next_random(state: &mut u64) -> u64 {
+    *state ^= *state << 13;
+    *state ^= *state >> 7;
+    *state ^= *state << 17;
+    *state
+}
+
+/// Maps a random value to `[0, 1)`.
+pub(crate) fn unit_interval(value: u64) -> f64 {
+    (value >> 11) as f64 / (1u64 << 53) as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settings(error_probability: f64, operations: u32) -> FaultSettings {
+        FaultSettings {
+            error_probability,
+            throttle_probability: 0.0,
+            timeout_probability: 0.0,
+            timeout: Duration::ZERO,
+            min_latency: Duration::from_millis(5),
+            max_latency: Duration::from_millis(10),
+            operations,
+            seed: 42,
+        }
+    }
+
+    #[test]
+    fn should_only_target_configured_operations() {
+        // given
+        let injector = FaultInjector::new(settings(1.0, 1 << Operation::Scan as u32));
+
+        // when / then
+        assert_eq!(injector.draw(Operation::Append), None);
+        assert!(injector
+            .draw(Operation::Scan)
+            .is_some_and(|(_, kind)| kind == Some(FaultKind::Error)));
+    }
+
+    #[test]
+    fn should_draw_latency_within_bounds() {
+        // given
+        let injector = FaultInjector::new(settings(0.0, u32::MAX));
+
+        // when
+        let latencies: Vec<Duration> = (0..100)
+            .filter_map(|_| injector.draw(Operation::Append))
+            .map(|(latency, _)| latency)
+            .collect();
+
+        // then
+        assert_eq!(latencies.len(), 100);
+        assert!(latencies
+            .iter()
+            .all(|l| *l >= Duration::from_millis(5) && *l <= Duration::from_millis(10)));
+    }
+
+    #[test]
+    fn should_fail_at_roughly_the_configured_rate() {
+        // given
+        let injector = FaultInjector::new(settings(0.25, u32::MAX));
+
+        // when
+        let failures = (0..10_000)
+            .filter_map(|_| injector.draw(Operation::Append))
+            .filter(|(_, kind)| kind.is_some())
+            .count();
+
+        // then
+        assert!((2_000..3_000).contains(&failures), "failures: {}", failures);
+    }
+
+    #[test]
+    fn should_split_outcomes_between_fault_kinds() {
+        // given
+        let injector = FaultInjector::new(FaultSettings {
+            error_probability: 0.1,
+            throttle_probability: 0.2,
+            timeout_probability: 0.3,
+            ..settings(0.0, u32::MAX)
+        });
+
+        // when
+        let kinds: Vec<Option<FaultKind>> = (0..10_000)
+            .filter_map(|_| injector.draw(Operation::Append))
+            .map(|(_, kind)| kind)
+            .collect();
+        let count = |kind| kinds.iter().filter(|k| **k == kind).count();
+
+        // then
+        assert!((800..1_200).contains(&count(Some(FaultKind::Error))));
+        assert!((1_800..2_200).contains(&count(Some(FaultKind::Throttled))));
+        assert!((2_700..3_300).contains(&count(Some(FaultKind::TimedOut))));
+        assert!((3_700..4_300).contains(&count(None)));
+    }
+
+    #[test]
+    fn should_fail_targeted_requests_of_wrapped_store() {
+        // given
+        let faults = Arc::new(FaultInjector::new(FaultSettings {
+            min_latency: Duration::ZERO,
+            max_latency: Duration::ZERO,
+            ..settings(1.0, 1 << Operation::Append as u32)
+        }));
+        let store = FaultyObjectStore {
+            inner: Arc::new(InMemory::new()),
+            faults: Arc::clone(&faults),
+        };
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_time()
+            .build()
+            .unwrap();
+        let location = Path::from("wal/00000000000000000001.sst");
+
+        // when
+        let put = runtime.block_on(store.put(&location, Bytes::from_static(b"sst").into()));
+        let listed = runtime.block_on(store.list_with_delimiter(None));
+
+        // then
+        assert!(matches!(
+            put,
+            Err(object_store::Error::Generic { store: STORE, .. })
+        ));
+        assert!(listed.unwrap().objects.is_empty());
+        let mut stats = Stats::new();
+        faults.append_stats(&mut stats);
+        assert!(stats.contains(&("faults.injected_errors".to_string(), 1)));
+    }
+
+    #[test]
+    fn should_let_retries_of_throttled_requests_through() {
+        // given
+        let faults = Arc::new(FaultInjector::new(FaultSettings {
+            throttle_probability: 0.5,
+            min_latency: Duration::ZERO,
+            max_latency: Duration::ZERO,
+            ..settings(0.0, u32::MAX)
+        }));
+        let store = FaultyObjectStore {
+            inner: Arc::new(InMemory::new()),
+            faults: Arc::clone(&faults),
+        };
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_time()
+            .build()
+            .unwrap();
+        let location = Path::from("manifest/00000000000000000001.manifest");
+
+        // when
+        let mut throttled = Vec::new();
+        let put = loop {
+            match runtime.block_on(store.put(&location, Bytes::from_static(b"m").into())) {
+                Err(e) if throttled.len() < 20 => throttled.push(e.to_string()),
+                result => break result,
+            }
+        };
+
+        // then
+        assert!(put.is_ok());
+        assert!(throttled.iter().all(|e| e.contains("503 SlowDown")));
+        let mut stats = Stats::new();
+        faults.append_stats(&mut stats);
+        assert!(stats.contains(&(
+            "faults.injected_throttles".to_string(),
+            throttled.len() as i64
+        )));
+        assert!(runtime.block_on(store.head(&location)).is_ok());
+    }
+
+    #[test]
+    fn should_repeat_faults_for_the_same_seed() {
+        // given
+        let first = FaultInjector::new(settings(0.5, u32::MAX));
+        let second = FaultInjector::new(settings(0.5, u32::MAX));
+
+        // when
+        let a: Vec<_> = (0..20).map(|_| first.draw(Operation::Scan)).collect();
+        let b: Vec<_> = (0..20).map(|_| second.draw(Operation::Scan)).collect();
+
+        // then
+        assert_eq!(a, b);
+    }
+}
@@ -0,0 +1,757 @@
+//! Plain C ABI for callers that do not use JNI.
+//!
+//! Java 22+ callers can bind these functions through the Foreign Function &
+//! Memory API, and any other language with a C FFI can use them directly. The
+//! declarations are in `include/opendata_log.h`.
+//!
+//! Conventions:
+//! - Logs are opaque handles created by `opendata_log_open` and released by
+//!   `opendata_log_close`.
+//! - Byte strings are passed as pointer/length slices and are copied before
+//!   the call returns, except for scan results, which borrow from their
+//!   `OpendataEntries` until it is freed.
+//! - Every fallible function returns an `OPENDATA_*` status code. On failure,
+//!   `opendata_last_error` returns a message describing the error, valid
+//!   until the next call on the same thread.
+//!
+//! Values carry the same timestamp header as values appended through JNI, so
+//! logs can be shared between both surfaces.
+//!
+//! Callers that manage their own memory, such as FFM callers allocating
+//! `MemorySegment`s from an arena, can append records whose slices point
+//! into that memory and scan with `opendata_log_scan_into`, which writes
+//! entries straight into a caller-provided buffer instead of allocating a
+//! result. Each written entry is laid out in native byte order as:
+//!
+//! ```text
+//! ┌──────────────┬──────────────────┬──────────────┬────────────────┬─────┬───────┐
+//! │ sequence u64 │ timestamp_ms i64 │ key_len u32  │ value_len u32  │ key │ value │
+//! └──────────────┴──────────────────┴──────────────┴────────────────┴─────┴───────┘
+//! ```
+//!
+//! Entries start at 8-byte aligned offsets from the start of the buffer, so
+//! the fixed fields can be read with aligned accesses when the buffer itself
+//! is 8-byte aligned.
+
+use std::cell::RefCell;
+use std::ffi::{c_char, CString};
+use std::sync::Arc;
+
+use bytes::Bytes;
+use common::storage::config::{
+    AwsObjectStoreConfig, LocalObjectStoreConfig, ObjectStoreConfig, SlateDbStorageConfig,
+    StorageConfig,
+};
+use log::{LogDb, LogEntry, LogRead, Record};
+use tokio::runtime::Runtime;
+
+use crate::chunking::Assembler;
+use crate::clock::Stamps;
+use crate::runtimes::RuntimeSettings;
+use crate::{
+    current_timestamp_ms, extract_timestamp_and_payload, flatbatch, open_log, TIMESTAMP_HEADER_SIZE,
+};
+
+/// The call succeeded.
+pub const OPENDATA_OK: i32 = 0;
+/// A required pointer was null.
+pub const OPENDATA_NULL_POINTER: i32 = 1;
+/// An argument was invalid, such as a non-UTF-8 path.
+pub const OPENDATA_INVALID_ARGUMENT: i32 = 2;
+/// The storage layer returned an error.
+pub const OPENDATA_STORAGE_ERROR: i32 = 3;
+/// A caller-provided buffer cannot hold even a single result.
+pub const OPENDATA_BUFFER_TOO_SMALL: i32 = 4;
+
+/// Size of the fixed fields preceding each entry written by `opendata_log_scan_into`.
+const PACKED_ENTRY_HEADER_SIZE: usize = 24;
+
+/// In-memory storage; all other config fields are ignored.
+pub const OPENDATA_STORAGE_IN_MEMORY: u32 = 0;
+/// SlateDB on a local filesystem rooted at `location`.
+pub const OPENDATA_STORAGE_LOCAL: u32 = 1;
+/// SlateDB on S3, in bucket `location` and region `region`.
+pub const OPENDATA_STORAGE_AWS: u32 = 2;
+
+/// A borrowed byte string.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct OpendataSlice {
+    pub ptr: *const u8,
+    pub len: usize,
+}
+
+impl OpendataSlice {
+    fn empty() -> Self {
+        Self {
+            ptr: std::ptr::null(),
+            len: 0,
+        }
+    }
+
+    fn borrowed(bytes: &[u8]) -> Self {
+        Self {
+            ptr: bytes.as_ptr(),
+            len: bytes.len(),
+        }
+    }
+
+    /// Views the slice as bytes; a null pointer is an empty slice.
+    ///
+    /// # Safety
+    /// `ptr` must be valid for reads of `len` bytes for the returned lifetime.
+    unsafe fn as_bytes<'a>(&self) -> &'a [u8] {
+        if self.ptr.is_null() {
+            &[]
+        } else {
+            std::slice::from_raw_parts(self.ptr, self.len)
+        }
+    }
+
+    /// Decodes the slice as UTF-8, returning None if it is empty.
+    ///
+    /// # Safety
+    /// As for [`OpendataSlice::as_bytes`].
+    unsafe fn to_optional_string(self, name: &str) -> Result<Option<String>, String> {
+        let bytes = self.as_bytes();
+        if bytes.is_empty() {
+            return Ok(None);
+        }
+        std::str::from_utf8(bytes)
+            .map(|s| Some(s.to_string()))
+            .map_err(|e| format!("{} is not valid UTF-8: {}", name, e))
+    }
+}
+
+/// Storage configuration for `opendata_log_open`.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct OpendataStorageConfig {
+    /// One of the `OPENDATA_STORAGE_*` constants
+    pub kind: u32,
+    /// Path prefix for SlateDB data in the object store
+    pub path: OpendataSlice,
+    /// Local root directory or S3 bucket
+    pub location: OpendataSlice,
+    /// S3 region; unused for local storage
+    pub region: OpendataSlice,
+    /// Optional SlateDB settings file; empty for the defaults
+    pub settings_path: OpendataSlice,
+}
+
+/// A record to append.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct OpendataRecord {
+    pub key: OpendataSlice,
+    pub value: OpendataSlice,
+    /// Timestamp stored in the value's header, in milliseconds since the epoch
+    pub timestamp_ms: i64,
+}
+
+/// An entry returned by a scan, borrowed from its `OpendataEntries`.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct OpendataEntry {
+    pub sequence: u64,
+    pub timestamp_ms: i64,
+    pub key: OpendataSlice,
+    pub value: OpendataSlice,
+}
+
+/// An open log and the runtimes driving it.
+pub struct OpendataLog {
+    log: LogDb,
+    runtime: Runtime,
+    // Kept alive for SlateDB's background tasks
+    _compaction_runtime: Runtime,
+}
+
+/// Entries returned by `opendata_log_scan`.
+pub struct OpendataEntries {
+    entries: Vec<LogEntry>,
+}
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+/// Records an error message for `opendata_last_error` and returns its code.
+fn fail(code: i32, message: impl Into<String>) -> i32 {
+    let message = CString::new(message.into().replace('\0', " ")).unwrap_or_default();
+    LAST_ERROR.with(|last| *last.borrow_mut() = Some(message));
+    code
+}
+
+/// Returns the message of the last error on this thread, or null if none.
+///
+/// The message is valid until the next failing call on the same thread.
+#[no_mangle]
+pub extern "C" fn opendata_last_error() -> *const c_char {
+    LAST_ERROR.with(|last| {
+        last.borrow()
+            .as_ref()
+            .map_or(std::ptr::null(), |message| message.as_ptr())
+    })
+}
+
+/// Converts a C storage configuration into the upstream storage configuration.
+///
+/// # Safety
+/// All slices in `config` must be valid.
+unsafe fn storage_config(config: &OpendataStorageConfig) -> Result<StorageConfig, String> {
+    let object_store = match config.kind {
+        OPENDATA_STORAGE_IN_MEMORY => return Ok(StorageConfig::InMemory),
+        OPENDATA_STORAGE_LOCAL => ObjectStoreConfig::Local(LocalObjectStoreConfig {
+            path: config
+                .location
+                .to_optional_string("location")?
+                .ok_or("location is required for local storage")?,
+        }),
+        OPENDATA_STORAGE_AWS => ObjectStoreConfig::Aws(AwsObjectStoreConfig {
+            region: config
+                .region
+                .to_optional_string("region")?
+                .ok_or("region is required for AWS storage")?,
+            bucket: config
+                .location
+                .to_optional_string("location")?
+                .ok_or("location is required for AWS storage")?,
+        }),
+        kind => return Err(format!("Unknown storage kind: {}", kind)),
+    };
+    Ok(StorageConfig::SlateDb(SlateDbStorageConfig {
+        path: config
+            .path
+            .to_optional_string("path")?
+            .ok_or("path is required for SlateDB storage")?,
+        object_store,
+        settings_path: config.settings_path.to_optional_string("settings_path")?,
+    }))
+}
+
+/// Opens a log and stores its handle in `out_log`.
+///
+/// # Safety
+/// `config` must point to a valid config whose slices are valid, and
+/// `out_log` must be valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn opendata_log_open(
+    config: *const OpendataStorageConfig,
+    out_log: *mut *mut OpendataLog,
+) -> i32 {
+    if config.is_null() || out_log.is_null() {
+        return fail(OPENDATA_NULL_POINTER, "config and out_log must not be null");
+    }
+    let storage = match storage_config(&*config) {
+        Ok(s) => s,
+        Err(e) => return fail(OPENDATA_INVALID_ARGUMENT, e),
+    };
+
+    let runtimes = [RuntimeSettings::operation(), RuntimeSettings::compaction()]
+        .map(|settings| settings.build(&Arc::default()));
+    let [Ok(runtime), Ok(compaction_runtime)] = runtimes else {
+        return fail(OPENDATA_STORAGE_ERROR, "Failed to create runtimes");
+    };
+
+    match runtime.block_on(open_log(storage, None, compaction_runtime.handle())) {
+        Ok(log) => {
+            *out_log = Box::into_raw(Box::new(OpendataLog {
+                log,
+                runtime,
+                _compaction_runtime: compaction_runtime,
+            }));
+            OPENDATA_OK
+        }
+        Err(e) => fail(OPENDATA_STORAGE_ERROR, e.to_string()),
+    }
+}
+
+/// Closes a log and releases its handle. Null is ignored.
+///
+/// # Safety
+/// `log` must be null or a handle from `opendata_log_open` that has not
+/// been closed, and must not be used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn opendata_log_close(log: *mut OpendataLog) -> i32 {
+    if log.is_null() {
+        return OPENDATA_OK;
+    }
+    let handle = Box::from_raw(log);
+    let OpendataLog {
+        log,
+        runtime,
+        _compaction_runtime,
+    } = *handle;
+    match runtime.block_on(log.close()) {
+        Ok(()) => OPENDATA_OK,
+        Err(e) => fail(OPENDATA_STORAGE_ERROR, e.to_string()),
+    }
+}
+
+/// Appends a batch of records and stores the first record's sequence in
+/// `out_start_sequence`. Sequences within a batch are contiguous.
+///
+/// # Safety
+/// `log` must be an open handle, `records` must point to `count` records
+/// whose slices are valid, and `out_start_sequence` must be valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn opendata_log_append(
+    log: *mut OpendataLog,
+    records: *const OpendataRecord,
+    count: usize,
+    out_start_sequence: *mut u64,
+) -> i32 {
+    if log.is_null() || records.is_null() || out_start_sequence.is_null() {
+        return fail(
+            OPENDATA_NULL_POINTER,
+            "log, records and out_start_sequence must not be null",
+        );
+    }
+    if count == 0 {
+        return fail(OPENDATA_INVALID_ARGUMENT, "records must not be empty");
+    }
+    let handle = &*log;
+    let batch: Vec<Record> = std::slice::from_raw_parts(records, count)
+        .iter()
+        .map(|record| {
+            let payload = record.value.as_bytes();
+            let mut value = Vec::with_capacity(TIMESTAMP_HEADER_SIZE + payload.len());
+            value.extend_from_slice(&record.timestamp_ms.to_be_bytes());
+            value.extend_from_slice(payload);
+            Record {
+                key: Bytes::copy_from_slice(record.key.as_bytes()),
+                value: Bytes::from(value),
+            }
+        })
+        .collect();
+
+    match handle.runtime.block_on(handle.log.append(batch)) {
+        Ok(result) => {
+            *out_start_sequence = result.start_sequence;
+            OPENDATA_OK
+        }
+        Err(e) => fail(OPENDATA_STORAGE_ERROR, e.to_string()),
+    }
+}
+
+/// Appends the records of a FlatBuffers `AppendBatch` and stores the first
+/// record's sequence in `out_start_sequence`.
+///
+/// The schema is `include/opendata_log_batch.fbs`. Records without a
+/// timestamp are stamped with the time of the call.
+///
+/// # Safety
+/// `log` must be an open handle, `batch` must be valid, and
+/// `out_start_sequence` must be valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn opendata_log_append_flatbuffer(
+    log: *mut OpendataLog,
+    batch: OpendataSlice,
+    out_start_sequence: *mut u64,
+) -> i32 {
+    if log.is_null() || out_start_sequence.is_null() {
+        return fail(
+            OPENDATA_NULL_POINTER,
+            "log and out_start_sequence must not be null",
+        );
+    }
+    let handle = &*log;
+    let records = match flatbatch::decode_batch(
+        batch.as_bytes(),
+        current_timestamp_ms(),
+        Stamps::default(),
+    ) {
+        Ok((records, _)) if records.is_empty() => {
+            return fail(OPENDATA_INVALID_ARGUMENT, "records must not be empty")
+        }
+        Ok((records, _)) => records,
+        Err(e) => return fail(OPENDATA_INVALID_ARGUMENT, e),
+    };
+
+    match handle.runtime.block_on(handle.log.append(records)) {
+        Ok(result) => {
+            *out_start_sequence = result.start_sequence;
+            OPENDATA_OK
+        }
+        Err(e) => fail(OPENDATA_STORAGE_ERROR, e.to_string()),
+    }
+}
+
+/// Flushes buffered appends to durable storage.
+///
+/// # Safety
+/// `log` must be an open handle.
+#[no_mangle]
+pub unsafe extern "C" fn opendata_log_flush(log: *mut OpendataLog) -> i32 {
+    if log.is_null() {
+        return fail(OPENDATA_NULL_POINTER, "log must not be null");
+    }
+    let handle = &*log;
+    match handle.runtime.block_on(handle.log.flush()) {
+        Ok(()) => OPENDATA_OK,
+        Err(e) => fail(OPENDATA_STORAGE_ERROR, e.to_string()),
+    }
+}
+
+/// Scans up to `max_entries` entries for a key from `start_sequence` and
+/// stores them in `out_entries`, which the caller frees with
+/// `opendata_entries_free`.
+///
+/// # Safety
+/// `log` must be an open handle, `key` must be valid, and `out_entries`
+/// must be valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn opendata_log_scan(
+    log: *mut OpendataLog,
+    key: OpendataSlice,
+    start_sequence: u64,
+    max_entries: usize,
+    out_entries: *mut *mut OpendataEntries,
+) -> i32 {
+    if log.is_null() || out_entries.is_null() {
+        return fail(
+            OPENDATA_NULL_POINTER,
+            "log and out_entries must not be null",
+        );
+    }
+    let handle = &*log;
+    match scan_entries(handle, key.as_bytes(), start_sequence, max_entries) {
+        Ok(entries) => {
+            *out_entries = Box::into_raw(Box::new(OpendataEntries { entries }));
+            OPENDATA_OK
+        }
+        Err(e) => fail(OPENDATA_STORAGE_ERROR, e.to_string()),
+    }
+}
+
+/// Scans entries for a key and writes as many as fit into `buffer`.
+///
+/// At most `max_entries` entries are written, in the layout described in the
+/// module documentation. The number of entries and bytes written are stored
+/// in `out_entry_count` and `out_written`; fewer entries than requested are
+/// written when the buffer fills up, and the caller continues from the last
+/// written sequence plus one. If the first entry does not fit, returns
+/// `OPENDATA_BUFFER_TOO_SMALL` with the size it needs in `out_written`.
+///
+/// # Safety
+/// `log` must be an open handle, `key` must be valid, `buffer` must be valid
+/// for writes of `capacity` bytes, and both out pointers must be valid for
+/// writes.
+#[no_mangle]
+#[allow(clippy::too_many_arguments)]
+pub unsafe extern "C" fn opendata_log_scan_into(
+    log: *mut OpendataLog,
+    key: OpendataSlice,
+    start_sequence: u64,
+    max_entries: usize,
+    buffer: *mut u8,
+    capacity: usize,
+    out_entry_count: *mut usize,
+    out_written: *mut usize,
+) -> i32 {
+    if log.is_null() || buffer.is_null() || out_entry_count.is_null() || out_written.is_null() {
+        return fail(
+            OPENDATA_NULL_POINTER,
+            "log, buffer, out_entry_count and out_written must not be null",
+        );
+    }
+    let handle = &*log;
+    let entries = match scan_entries(handle, key.as_bytes(), start_sequence, max_entries) {
+        Ok(entries) => entries,
+        Err(e) => return fail(OPENDATA_STORAGE_ERROR, e.to_string()),
+    };
+    let buffer = std::slice::from_raw_parts_mut(buffer, capacity);
+    let (count, written) = pack_entries(&entries, buffer);
+    *out_entry_count = count;
+    if count == 0 {
+        if let Some(first) = entries.first() {
+            *out_written = packed_size(first);
+            return fail(
+                OPENDATA_BUFFER_TOO_SMALL,
+                format!(
+                    "entry needs {} bytes, buffer has {}",
+                    packed_size(first),
+                    capacity
+                ),
+            );
+        }
+    }
+    *out_written = written;
+    OPENDATA_OK
+}
+
+/// Scans entries for a key and writes them into `buffer` as a FlatBuffers
+/// `ScanResult`, storing its size in `out_written`.
+///
+/// The result must fit whole: if it does not, returns
+/// `OPENDATA_BUFFER_TOO_SMALL` with the size it needs in `out_written`, and
+/// the caller retries with a larger buffer or fewer entries.
+///
+/// # Safety
+/// `log` must be an open handle, `key` must be valid, `buffer` must be valid
+/// for writes of `capacity` bytes, and `out_written` must be valid for
+/// writes.
+#[no_mangle]
+pub unsafe extern "C" fn opendata_log_scan_flatbuffer(
+    log: *mut OpendataLog,
+    key: OpendataSlice,
+    start_sequence: u64,
+    max_entries: usize,
+    buffer: *mut u8,
+    capacity: usize,
+    out_written: *mut usize,
+) -> i32 {
+    if log.is_null() || buffer.is_null() || out_written.is_null() {
+        return fail(
+            OPENDATA_NULL_POINTER,
+            "log, buffer and out_written must not be null",
+        );
+    }
+    let handle = &*log;
+    let entries = match scan_entries(handle, key.as_bytes(), start_sequence, max_entries) {
+        Ok(entries) => entries,
+        Err(e) => return fail(OPENDATA_STORAGE_ERROR, e.to_string()),
+    };
+    let encoded = flatbatch::encode_entries(&entries);
+    *out_written = encoded.len();
+    if encoded.len() > capacity {
+        return fail(
+            OPENDATA_BUFFER_TOO_SMALL,
+            format!(
+                "result needs {} bytes, buffer has {}",
+                encoded.len(),
+                capacity
+            ),
+        );
+    }
+    std::slice::from_raw_parts_mut(buffer, encoded.len()).copy_from_slice(&encoded);
+    OPENDATA_OK
+}
+
+/// Reads up to `max_entries` entries for a key from `start_sequence`.
+fn scan_entries(
+    handle: &OpendataLog,
+    key: &[u8],
+    start_sequence: u64,
+    max_entries: usize,
+) -> Result<Vec<LogEntry>, log::Error> {
+    let key = Bytes::copy_from_slice(key);
+    handle.runtime.block_on(async {
+        let mut iter = handle.log.scan(key, start_sequence..).await?;
+        let mut entries = Assembler::default();
+        while entries.len() < max_entries {
+            match iter.next().await? {
+                Some(entry) => entries.push(entry),
+                None => break,
+            }
+        }
+        Ok(entries.finish())
+    })
+}
+
+/// Returns the bytes an entry occupies in a packed buffer, excluding padding.
+fn packed_size(entry: &LogEntry) -> usize {
+    let (_, payload) = extract_timestamp_and_payload(&entry.value);
+    PACKED_ENTRY_HEADER_SIZE + entry.key.len() + payload.len()
+}
+
+/// Writes entries into `buffer` until the next one does not fit, returning
+/// the number of entries and bytes written.
+fn pack_entries(entries: &[LogEntry], buffer: &mut [u8]) -> (usize, usize) {
+    let mut offset = 0;
+    let mut count = 0;
+    for entry in entries {
+        let start = offset.next_multiple_of(8);
+        let end = start + packed_size(entry);
+        if end > buffer.len() {
+            break;
+        }
+        let (timestamp_ms, payload) = extract_timestamp_and_payload(&entry.value);
+        let out = &mut buffer[start..end];
+        out[0..8].copy_from_slice(&entry.sequence.to_ne_bytes());
+        out[8..16].copy_from_slice(&timestamp_ms.to_ne_bytes());
+        out[16..20].copy_from_slice(&(entry.key.len() as u32).to_ne_bytes());
+        out[20..24].copy_from_slice(&(payload.len() as u32).to_ne_bytes());
+        let key_end = PACKED_ENTRY_HEADER_SIZE + entry.key.len();
+        out[PACKED_ENTRY_HEADER_SIZE..key_end].copy_from_slice(&entry.key);
+        out[key_end..].copy_from_slice(payload);
+        offset = end;
+        count += 1;
+    }
+    (count, offset)
+}
+
+/// Returns the number of entries in a scan result. Null has no entries.
+///
+/// # Safety
+/// `entries` must be null or a result from `opendata_log_scan` that has not
+/// been freed.
+#[no_mangle]
+pub unsafe extern "C" fn opendata_entries_len(entries: *const OpendataEntries) -> usize {
+    entries.as_ref().map_or(0, |e| e.entries.len())
+}
+
+/// Stores the entry at `index` in `out_entry`. Its slices stay valid until
+/// the result is freed.
+///
+/// # Safety
+/// `entries` must be a result from `opendata_log_scan` that has not been
+/// freed, and `out_entry` must be valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn opendata_entries_get(
+    entries: *const OpendataEntries,
+    index: usize,
+    out_entry: *mut OpendataEntry,
+) -> i32 {
+    if entries.is_null() || out_entry.is_null() {
+        return fail(
+            OPENDATA_NULL_POINTER,
+            "entries and out_entry must not be null",
+        );
+    }
+    let Some(entry) = (*entries).entries.get(index) else {
+        return fail(
+            OPENDATA_INVALID_ARGUMENT,
+            format!("index {} is out of bounds", index),
+        );
+    };
+    let (timestamp_ms, payload) = extract_timestamp_and_payload(&entry.value);
+    *out_entry = OpendataEntry {
+        sequence: entry.sequence,
+        timestamp_ms,
+        key: OpendataSlice::borrowed(&entry.key),
+        value: if payload.is_empty() {
+            OpendataSlice::empty()
+        } else {
+            OpendataSlice::borrowed(payload)
+        },
+    };
+    OPENDATA_OK
+}
+
+/// Frees a scan result. Null is ignored.
+///
+/// # Safety
+/// `entries` must be null or a result from `opendata_log_scan` that has not
+/// been freed, and must not be used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn opendata_entries_free(entries: *mut OpendataEntries) {
+    if !entries.is_null() {
+        drop(Box::from_raw(entries));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ffi::CStr;
+
+    #[test]
+    fn should_reject_unknown_storage_kind() {
+        // given
+        let config = OpendataStorageConfig {
+            kind: 7,
+            path: OpendataSlice::empty(),
+            location: OpendataSlice::empty(),
+            region: OpendataSlice::empty(),
+            settings_path: OpendataSlice::empty(),
+        };
+        let mut log = std::ptr::null_mut();
+
+        // when
+        let status = unsafe { opendata_log_open(&config, &mut log) };
+
+        // then
+        assert_eq!(status, OPENDATA_INVALID_ARGUMENT);
+        assert!(log.is_null());
+        let message = unsafe { CStr::from_ptr(opendata_last_error()) };
+        assert!(message.to_string_lossy().contains("Unknown storage kind"));
+    }
+
+    fn entry(sequence: u64, key: &'static [u8], payload: &[u8]) -> LogEntry {
+        let mut value = 77i64.to_be_bytes().to_vec();
+        value.extend_from_slice(payload);
+        LogEntry {
+            key: Bytes::from_static(key),
+            sequence,
+            value: Bytes::from(value),
+        }
+    }
+
+    #[test]
+    fn should_pack_entries_at_aligned_offsets() {
+        // given
+        let entries = [entry(3, b"k", b"abc"), entry(4, b"k", b"de")];
+        let mut buffer = [0u8; 64];
+
+        // when
+        let (count, written) = pack_entries(&entries, &mut buffer);
+
+        // then
+        assert_eq!(count, 2);
+        // 24 + 1 + 3 = 28, padded to 32 for the second entry
+        assert_eq!(written, 32 + 24 + 1 + 2);
+        assert_eq!(u64::from_ne_bytes(buffer[0..8].try_into().unwrap()), 3);
+        assert_eq!(i64::from_ne_bytes(buffer[8..16].try_into().unwrap()), 77);
+        assert_eq!(&buffer[24..28], b"kabc");
+        assert_eq!(u64::from_ne_bytes(buffer[32..40].try_into().unwrap()), 4);
+        assert_eq!(&buffer[56..59], b"kde");
+    }
+
+    #[test]
+    fn should_stop_packing_when_buffer_is_full() {
+        // given
+        let entries = [entry(0, b"k", b"abc"), entry(1, b"k", b"abc")];
+        let mut buffer = [0u8; 40];
+
+        // when
+        let (count, written) = pack_entries(&entries, &mut buffer);
+
+        // then
+        assert_eq!((count, written), (1, 28));
+    }
+
+    #[test]
+    fn should_append_and_scan_through_c_abi() {
+        // given
+        let config = OpendataStorageConfig {
+            kind: OPENDATA_STORAGE_IN_MEMORY,
+            path: OpendataSlice::empty(),
+            location: OpendataSlice::empty(),
+            region: OpendataSlice::empty(),
+            settings_path: OpendataSlice::empty(),
+        };
+        let mut log = std::ptr::null_mut();
+        assert_eq!(unsafe { opendata_log_open(&config, &mut log) }, OPENDATA_OK);
+        let key = OpendataSlice::borrowed(b"ffi-key");
+        let records = [OpendataRecord {
+            key,
+            value: OpendataSlice::borrowed(b"value"),
+            timestamp_ms: 1_234,
+        }];
+
+        // when
+        let mut start = u64::MAX;
+        let appended = unsafe { opendata_log_append(log, records.as_ptr(), 1, &mut start) };
+        let mut entries = std::ptr::null_mut();
+        let scanned = unsafe { opendata_log_scan(log, key, 0, 10, &mut entries) };
+
+        // then
+        assert_eq!((appended, scanned), (OPENDATA_OK, OPENDATA_OK));
+        assert_eq!(unsafe { opendata_entries_len(entries) }, 1);
+        let mut entry = std::mem::MaybeUninit::<OpendataEntry>::uninit();
+        assert_eq!(
+            unsafe { opendata_entries_get(entries, 0, entry.as_mut_ptr()) },
+            OPENDATA_OK
+        );
+        let entry = unsafe { entry.assume_init() };
+        assert_eq!(entry.sequence, start);
+        assert_eq!(entry.timestamp_ms, 1_234);
+        assert_eq!(unsafe { entry.value.as_bytes() }, b"value");
+        unsafe {
+            opendata_entries_free(entries);
+            assert_eq!(opendata_log_close(log), OPENDATA_OK);
+        }
+    }
+}
@@ -0,0 +1,93 @@
+//! Filters evaluated natively on scanned entries.
+//!
+//! A consumer interested in a slice of a key, such as its large payloads or
+//! an hour of it, would otherwise have every entry copied into Java only to
+//! discard most of them. A filtered scan tests each entry against the
+//! filter after reassembling chunk runs and skips those failing it, so only
+//! matching entries cross JNI; the scan continues past skipped entries until
+//! it has found as many matches as asked for or reaches the end of the key.
+//! Skipped entries are still read from storage.
+//!
+//! Filters test the payload length and the producer timestamp, each against
+//! an inclusive range. Entries carry no headers yet, so there is nothing
+//! else to test.
+
+use log::LogEntry;
+
+use crate::extract_timestamp_and_payload;
+
+/// Inclusive bounds an entry must fall within to be returned.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct ScanFilter {
+    pub(crate) min_value_length: u64,
+    pub(crate) max_value_length: u64,
+    pub(crate) min_timestamp_ms: i64,
+    pub(crate) max_timestamp_ms: i64,
+}
+
+impl ScanFilter {
+    /// A filter every entry passes.
+    pub(crate) const ALL: Self = Self {
+        min_value_length: 0,
+        max_value_length: u64::MAX,
+        min_timestamp_ms: i64::MIN,
+        max_timestamp_ms: i64::MAX,
+    };
+
+    /// Returns whether `entry` passes the filter.
+    pub(crate) fn matches(&self, entry: &LogEntry) -> bool {
+        let (timestamp_ms, payload) = extract_timestamp_and_payload(&entry.value);
+        let length = payload.len() as u64;
+        (self.min_value_length..=self.max_value_length).contains(&length)
+            && (self.min_timestamp_ms..=self.max_timestamp_ms).contains(&timestamp_ms)
+    }
+
+    /// Drops the entries failing the filter.
+    pub(crate) fn retain(&self, entries: &mut Vec<LogEntry>) {
+        if *self != Self::ALL {
+            entries.retain(|entry| self.matches(entry));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+
+    use super::*;
+
+    fn entry(timestamp_ms: i64, payload_len: usize) -> LogEntry {
+        let mut value = timestamp_ms.to_be_bytes().to_vec();
+        value.resize(value.len() + payload_len, 0);
+        LogEntry {
+            key: Bytes::from_static(b"key"),
+            sequence: 0,
+            value: Bytes::from(value),
+        }
+    }
+
+    #[test]
+    fn should_match_entries_within_bounds() {
+        // given
+        let filter = ScanFilter {
+            min_value_length: 10,
+            max_value_length: 100,
+            min_timestamp_ms: 1_000,
+            max_timestamp_ms: 2_000,
+        };
+
+        // when / then
+        assert!(filter.matches(&entry(1_000, 10)));
+        assert!(filter.matches(&entry(2_000, 100)));
+        assert!(!filter.matches(&entry(1_500, 9)));
+        assert!(!filter.matches(&entry(1_500, 101)));
+        assert!(!filter.matches(&entry(999, 50)));
+        assert!(!filter.matches(&entry(2_001, 50)));
+    }
+
+    #[test]
+    fn should_pass_every_entry_without_bounds() {
+        assert!(ScanFilter::ALL.matches(&entry(0, 0)));
+        assert!(ScanFilter::ALL.matches(&entry(i64::MAX >> 2, 1 << 20)));
+    }
+}
@@ -0,0 +1,296 @@
+//! FlatBuffers framing of append batches and scan results.
+//!
+//! Tooling outside the JVM, and C ABI callers, can build and read batches
+//! with FlatBuffers code generated for any language from one schema,
+//! `include/opendata_log_batch.fbs`, instead of mirroring the Java records or
+//! the packed layout of `opendata_log_scan_into`. The JNI and C ABI surfaces
+//! both accept an `AppendBatch` and return a `ScanResult`.
+//!
+//! The schema is small enough that the tables are read and written here with
+//! the `flatbuffers` runtime directly rather than through generated code;
+//! the slot constants below must follow the field order of the schema.
+//! Batches are verified before they are read, so a malformed batch fails
+//! its append instead of reading out of bounds.
+
+use bytes::Bytes;
+use flatbuffers::{
+    FlatBufferBuilder, Follow, ForwardsUOffset, InvalidFlatbuffer, Table, VOffsetT, Vector,
+    Verifiable, Verifier, WIPOffset,
+};
+use log::{LogEntry, Record};
+
+use crate::clock::Stamps;
+use crate::{extract_timestamp_and_payload, skew};
+
+/// Slot of the n-th field of a table.
+const fn slot(field: VOffsetT) -> VOffsetT {
+    4 + 2 * field
+}
+
+const RECORD_KEY: VOffsetT = slot(0);
+const RECORD_VALUE: VOffsetT = slot(1);
+const RECORD_TIMESTAMP_MS: VOffsetT = slot(2);
+const APPEND_BATCH_RECORDS: VOffsetT = slot(0);
+const ENTRY_SEQUENCE: VOffsetT = slot(0);
+const ENTRY_TIMESTAMP_MS: VOffsetT = slot(1);
+const ENTRY_KEY: VOffsetT = slot(2);
+const ENTRY_VALUE: VOffsetT = slot(3);
+const SCAN_RESULT_ENTRIES: VOffsetT = slot(0);
+
+/// A `[ubyte]` field.
+type ByteVector<'a> = ForwardsUOffset<Vector<'a, u8>>;
+
+/// A `Record` table.
+struct RecordTable<'a> {
+    table: Table<'a>,
+}
+
+impl<'a> Follow<'a> for RecordTable<'a> {
+    type Inner = Self;
+
+    unsafe fn follow(buf: &'a [u8], loc: usize) -> Self {
+        Self {
+            table: Table::new(buf, loc),
+        }
+    }
+}
+
+impl Verifiable for RecordTable<'_> {
+    fn run_verifier(v: &mut Verifier<'_, '_>, pos: usize) -> Result<(), InvalidFlatbuffer> {
+        v.visit_table(pos)?
+            .visit_field::<ByteVector<'_>>("key", RECORD_KEY, false)?
+            .visit_field::<ByteVector<'_>>("value", RECORD_VALUE, false)?
+            .visit_field::<i64>("timestamp_ms", RECORD_TIMESTAMP_MS, false)?
+            .finish();
+        Ok(())
+    }
+}
+
+impl<'a> RecordTable<'a> {
+    fn bytes(&self, slot: VOffsetT) -> &'a [u8] {
+        // Safety: the buffer was verified, and the slot holds a byte vector
+        unsafe { self.table.get::<ByteVector<'a>>(slot, None) }.map_or(&[], |v| v.bytes())
+    }
+
+    fn timestamp_ms(&self) -> i64 {
+        // Safety: the buffer was verified, and the slot holds a long
+        unsafe { self.table.get::<i64>(RECORD_TIMESTAMP_MS, Some(0)) }.unwrap_or(0)
+    }
+}
+
+/// An `AppendBatch` table.
+struct AppendBatch<'a> {
+    table: Table<'a>,
+}
+
+/// The `records` field of an `AppendBatch`.
+type Records<'a> = ForwardsUOffset<Vector<'a, ForwardsUOffset<RecordTable<'a>>>>;
+
+impl<'a> Follow<'a> for AppendBatch<'a> {
+    type Inner = Self;
+
+    unsafe fn follow(buf: &'a [u8], loc: usize) -> Self {
+        Self {
+            table: Table::new(buf, loc),
+        }
+    }
+}
+
+impl Verifiable for AppendBatch<'_> {
+    fn run_verifier(v: &mut Verifier<'_, '_>, pos: usize) -> Result<(), InvalidFlatbuffer> {
+        v.visit_table(pos)?
+            .visit_field::<Records<'_>>("records", APPEND_BATCH_RECORDS, false)?
+            .finish();
+        Ok(())
+    }
+}
+
+/// Decodes an `AppendBatch` into records with timestamp headers, stamping
+/// records without a timestamp with `now_ms`, and every record with the
+/// timestamp and receive time of `stamps` if set.
+///
+/// Returns the records and the timestamp of the first record (0 if empty).
+pub(crate) fn decode_batch(
+    buf: &[u8],
+    now_ms: i64,
+    stamps: Stamps,
+) -> Result<(Vec<Record>, i64), String> {
+    let batch = flatbuffers::root::<AppendBatch<'_>>(buf)
+        .map_err(|e| format!("Invalid AppendBatch: {}", e))?;
+    // Safety: the buffer was verified, and the slot holds a vector of records
+    let Some(tables) = (unsafe { batch.table.get::<Records<'_>>(APPEND_BATCH_RECORDS, None) })
+    else {
+        return Ok((Vec::new(), 0));
+    };
+    let mut first_timestamp_ms = 0;
+    let mut records = Vec::with_capacity(tables.len());
+    for (i, table) in tables.iter().enumerate() {
+        let timestamp_ms = match (stamps.record_ms, table.timestamp_ms()) {
+            (Some(stamp), _) => stamp,
+            (None, 0) => now_ms,
+            (None, timestamp_ms) => timestamp_ms,
+        };
+        if i == 0 {
+            first_timestamp_ms = timestamp_ms;
+        }
+        let payload = table.bytes(RECORD_VALUE);
+        let header_size = skew::header_size(stamps.received_ms);
+        let mut value = vec![0; header_size + payload.len()];
+        skew::write_header(&mut value, timestamp_ms, stamps.received_ms);
+        value[header_size..].copy_from_slice(payload);
+        records.push(Record {
+            key: Bytes::copy_from_slice(table.bytes(RECORD_KEY)),
+            value: Bytes::from(value),
+        });
+    }
+    Ok((records, first_timestamp_ms))
+}
+
+/// Encodes scanned entries as a `ScanResult`, without their timestamp headers.
+pub(crate) fn encode_entries(entries: &[LogEntry]) -> Vec<u8> {
+    let capacity = entries
+        .iter()
+        .map(|e| e.key.len() + e.value.len() + 64)
+        .sum::<usize>();
+    let mut fbb = FlatBufferBuilder::with_capacity(capacity);
+    let tables: Vec<WIPOffset<Table<'_>>> = entries
+        .iter()
+        .map(|entry| {
+            let (timestamp_ms, payload) = extract_timestamp_and_payload(&entry.value);
+            let key = fbb.create_vector(&entry.key[..]);
+            let value = fbb.create_vector(payload);
+            let start = fbb.start_table();
+            fbb.push_slot::<u64>(ENTRY_SEQUENCE, entry.sequence, 0);
+            fbb.push_slot::<i64>(ENTRY_TIMESTAMP_MS, timestamp_ms, 0);
+            fbb.push_slot_always(ENTRY_KEY, key);
+            fbb.push_slot_always(ENTRY_VALUE, value);
+            WIPOffset::new(fbb.end_table(start).value())
+        })
+        .collect();
+    let tables = fbb.create_vector(&tables);
+    let start = fbb.start_table();
+    fbb.push_slot_always(SCAN_RESULT_ENTRIES, tables);
+    let root = fbb.end_table(start);
+    fbb.finish_minimal(root);
+    fbb.finished_data().to_vec()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn append_batch(records: &[(&str, &str, i64)]) -> Vec<u8> {
+        let mut fbb = FlatBufferBuilder::new();
+        let tables: Vec<WIPOffset<Table<'_>>> = records
+            .iter()
+            .map(|(key, value, timestamp_ms)| {
+                let key = fbb.create_vector(key.as_bytes());
+                let value = fbb.create_vector(value.as_bytes());
+                let start = fbb.start_table();
+                fbb.push_slot_always(RECORD_KEY, key);
+                fbb.push_slot_always(RECORD_VALUE, value);
+                fbb.push_slot::<i64>(RECORD_TIMESTAMP_MS, *timestamp_ms, 0);
+                WIPOffset::new(fbb.end_table(start).value())
+            })
+            .collect();
+        let tables = fbb.create_vector(&tables);
+        let start = fbb.start_table();
+        fbb.push_slot_always(APPEND_BATCH_RECORDS, tables);
+        let root = fbb.end_table(start);
+        fbb.finish_minimal(root);
+        fbb.finished_data().to_vec()
+    }
+
+    #[test]
+    fn should_decode_batch_stamping_records_without_timestamp() {
+        // given
+        let batch = append_batch(&[("key", "first", 1234), ("key", "second", 0)]);
+
+        // when
+        let (records, first_timestamp_ms) = decode_batch(&batch, 99, Stamps::default()).unwrap();
+
+        // then
+        assert_eq!(first_timestamp_ms, 1234);
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].key, Bytes::from_static(b"key"));
+        assert_eq!(
+            extract_timestamp_and_payload(&records[0].value),
+            (1234, b"first".as_slice())
+        );
+        assert_eq!(
+            extract_timestamp_and_payload(&records[1].value),
+            (99, b"second".as_slice())
+        );
+    }
+
+    #[test]
+    fn should_stamp_every_record_when_assigning_timestamps() {
+        // given
+        let batch = append_batch(&[("key", "first", 1234), ("key", "second", 0)]);
+
+        // when
+        let stamps = Stamps {
+            record_ms: Some(99),
+            received_ms: Some(100),
+        };
+        let (records, first_timestamp_ms) = decode_batch(&batch, 99, stamps).unwrap();
+
+        // then
+        assert_eq!(first_timestamp_ms, 99);
+        assert_eq!(
+            extract_timestamp_and_payload(&records[0].value),
+            (99, b"first".as_slice())
+        );
+        assert_eq!(extract_timestamp_and_payload(&records[1].value).0, 99);
+        assert_eq!(skew::received_ms(&records[1].value), Some(100));
+    }
+
+    #[test]
+    fn should_reject_malformed_batch() {
+        // given
+        let batch = [0xff; 7];
+
+        // when
+        let result = decode_batch(&batch, 0, Stamps::default());
+
+        // then
+        assert!(result.unwrap_err().contains("Invalid AppendBatch"));
+    }
+
+    #[test]
+    fn should_encode_entries_without_timestamp_header() {
+        // given
+        let mut value = 1000i64.to_be_bytes().to_vec();
+        value.extend_from_slice(b"payload");
+        let entries = [LogEntry {
+            key: Bytes::from_static(b"key"),
+            sequence: 7,
+            value: Bytes::from(value),
+        }];
+
+        // when
+        let encoded = encode_entries(&entries);
+
+        // then
+        // Safety: the buffer was just built with a ScanResult at its root
+        let root = unsafe { flatbuffers::root_unchecked::<Table<'_>>(&encoded) };
+        let tables = unsafe {
+            root.get::<ForwardsUOffset<Vector<'_, ForwardsUOffset<Table<'_>>>>>(
+                SCAN_RESULT_ENTRIES,
+                None,
+            )
+        }
+        .unwrap();
+        let entry = tables.get(0);
+        assert_eq!(
+            unsafe { entry.get::<u64>(ENTRY_SEQUENCE, Some(0)) },
+            Some(7)
+        );
+        assert_eq!(
+            unsafe { entry.get::<i64>(ENTRY_TIMESTAMP_MS, Some(0)) },
+            Some(1000)
+        );
+        let payload = unsafe { entry.get::<ByteVector<'_>>(ENTRY_VALUE, None) }.unwrap();
+        assert_eq!(payload.bytes(), b"payload");
+    }
+}
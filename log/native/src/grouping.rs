@@ -0,0 +1,130 @@
+//! Grouping of multi-key batches by key.
+//!
+//! A driver producing to many topics with tiny batches would make a JNI call
+//! per topic. A grouped append takes records for any number of keys in one
+//! call, orders them so each key's records are consecutive, keeping their
+//! order within the key, and returns each key's first sequence and count.
+//!
+//! Sequences are global to the log, so appends for different keys contend
+//! on the same writer; the groups are therefore written as one storage
+//! batch, which appends them together rather than one after another, and
+//! each key's records take consecutive sequences.
+
+use std::collections::HashMap;
+
+use bytes::Bytes;
+use log::Record;
+
+use crate::chunking;
+use crate::extract_timestamp_and_payload;
+
+/// The records of one key within a grouped batch.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Group {
+    pub(crate) key: Bytes,
+    /// Entries stored before the group's first, counting chunks
+    pub(crate) offset: u64,
+    /// Records of the key
+    pub(crate) count: usize,
+    /// Timestamp of the key's first record
+    pub(crate) timestamp_ms: i64,
+}
+
+/// Orders `records` by key, in order of each key's first record, returning
+/// the reordered records and their groups.
+///
+/// Offsets count the chunk entries that `threshold` splits records into.
+pub(crate) fn group(records: Vec<Record>, threshold: usize) -> (Vec<Record>, Vec<Group>) {
+    let mut index: HashMap<Bytes, usize> = HashMap::new();
+    let mut by_key: Vec<Vec<Record>> = Vec::new();
+    for record in records {
+        let slot = *index.entry(record.key.clone()).or_insert_with(|| {
+            by_key.push(Vec::new());
+            by_key.len() - 1
+        });
+        by_key[slot].push(record);
+    }
+
+    let mut grouped = Vec::with_capacity(by_key.iter().map(Vec::len).sum());
+    let mut groups = Vec::with_capacity(by_key.len());
+    let mut offset = 0u64;
+    for records in by_key {
+        let first = &records[0];
+        groups.push(Group {
+            key: first.key.clone(),
+            offset,
+            count: records.len(),
+            timestamp_ms: extract_timestamp_and_payload(&first.value).0,
+        });
+        for record in records {
+            offset += chunking::entry_count(&record, threshold) as u64;
+            grouped.push(record);
+        }
+    }
+    (grouped, groups)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(key: &'static [u8], timestamp_ms: i64, payload_len: usize) -> Record {
+        let mut value = timestamp_ms.to_be_bytes().to_vec();
+        value.resize(value.len() + payload_len, 0);
+        Record {
+            key: Bytes::from_static(key),
+            value: Bytes::from(value),
+        }
+    }
+
+    #[test]
+    fn should_group_records_by_key_in_order_of_first_record() {
+        // given
+        let records = vec![
+            record(b"b", 1, 1),
+            record(b"a", 2, 1),
+            record(b"b", 3, 1),
+            record(b"a", 4, 1),
+            record(b"b", 5, 1),
+        ];
+
+        // when
+        let (grouped, groups) = group(records, 0);
+
+        // then
+        let timestamps: Vec<i64> = grouped
+            .iter()
+            .map(|r| extract_timestamp_and_payload(&r.value).0)
+            .collect();
+        assert_eq!(timestamps, vec![1, 3, 5, 2, 4]);
+        assert_eq!(
+            groups,
+            vec![
+                Group {
+                    key: Bytes::from_static(b"b"),
+                    offset: 0,
+                    count: 3,
+                    timestamp_ms: 1,
+                },
+                Group {
+                    key: Bytes::from_static(b"a"),
+                    offset: 3,
+                    count: 2,
+                    timestamp_ms: 2,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn should_count_chunks_in_offsets() {
+        // given
+        let records = vec![record(b"a", 1, 250), record(b"b", 2, 10)];
+
+        // when
+        let (_, groups) = group(records, 100);
+
+        // then
+        assert_eq!(groups[1].offset, 3);
+    }
+}
@@ -0,0 +1,225 @@
+//! Registry of the live LogDb and LogDbReader handles, for hunting leaks.
+//!
+//! A benchmark driver that opens handles per worker and forgets to close
+//! some only notices once the process runs out of memory or threads. Every
+//! handle registers here as it opens and unregisters when it is freed, and
+//! `nativeDumpHandles` lists those still live: their kind, the hash of the
+//! Java stack that opened them, their age, the appends and scans recorded
+//! since the last warmup mark and their tags. Handles opened from one call
+//! site share a stack hash, so a hash whose count keeps growing points at
+//! the leak. The hash is computed in Java once the handle is wrapped, and is
+//! 0 until then.
+//!
+//! A log also registers how to drain it, which `drain_all` runs for every
+//! live log as the JVM shuts down, or when `LogDb.drainAll` is called: it
+//! waits for submitted operations and flushes, so a benchmark ending in
+//! `System.exit` does not abort an object-store upload half-way. Handles
+//! are drained but stay open, as Java threads may still be using them while
+//! shutdown hooks run; the process exits with their runtimes.
+
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{mpsc, Arc, Mutex, MutexGuard, PoisonError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::metrics::{LatencyRecorder, Operation};
+use crate::tags::Tags;
+
+/// Kinds of registered handles.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum HandleKind {
+    Log,
+    Reader,
+}
+
+impl HandleKind {
+    /// Returns the simple name of the Java class wrapping the handle.
+    pub(crate) fn class_name(self) -> &'static str {
+        match self {
+            HandleKind::Log => "LogDb",
+            HandleKind::Reader => "LogDbReader",
+        }
+    }
+}
+
+/// Drains a handle by the given deadline, returning whether it finished.
+pub(crate) type Drain = Arc<dyn Fn(Instant) -> bool + Send + Sync>;
+
+struct Entry {
+    kind: HandleKind,
+    created_at: Instant,
+    stack_hash: i32,
+    latency: Arc<LatencyRecorder>,
+    tags: Arc<Tags>,
+    drain: Option<Drain>,
+}
+
+/// A live handle, as listed by `nativeDumpHandles`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct HandleSummary {
+    pub(crate) kind: HandleKind,
+    pub(crate) stack_hash: i32,
+    pub(crate) age: Duration,
+    pub(crate) appends: u64,
+    pub(crate) scans: u64,
+    pub(crate) tags: String,
+}
+
+static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+
+/// Live handles by registration order.
+static LIVE: Mutex<BTreeMap<u64, Entry>> = Mutex::new(BTreeMap::new());
+
+/// Entry of a handle in the registry, removed when dropped with the handle.
+pub(crate) struct Registration {
+    id: u64,
+}
+
+impl Registration {
+    /// Records the hash of the Java stack that opened the handle.
+    pub(crate) fn set_stack_hash(&self, stack_hash: i32) {
+        if let Some(entry) = lock().get_mut(&self.id) {
+            entry.stack_hash = stack_hash;
+        }
+    }
+}
+
+impl Drop for Registration {
+    fn drop(&mut self) {
+        lock().remove(&self.id);
+    }
+}
+
+/// Registers a handle opening now, counting its operations in `latency`
+/// and drained by `drain`, if any.
+pub(crate) fn register(
+    kind: HandleKind,
+    latency: Arc<LatencyRecorder>,
+    tags: Arc<Tags>,
+    drain: Option<Drain>,
+) -> Registration {
+    let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+    lock().insert(
+        id,
+        Entry {
+            kind,
+            created_at: Instant::now(),
+            stack_hash: 0,
+            latency,
+            tags,
+            drain,
+        },
+    );
+    Registration { id }
+}
+
+/// Drains every live handle in parallel, waiting up to `timeout`.
+///
+/// Returns whether all of them finished.
+pub(crate) fn drain_all(timeout: Duration) -> bool {
+    let deadline = Instant::now() + timeout;
+    let drains: Vec<Drain> = lock()
+        .values()
+        .filter_map(|entry| entry.drain.clone())
+        .collect();
+    let (finished, drained) = mpsc::channel();
+    let mut pending = 0;
+    for drain in drains {
+        let finished = finished.clone();
+        let spawned = thread::Builder::new()
+            .name("opendata-drain".to_string())
+            .spawn(move || {
+                let _ = finished.send(drain(deadline));
+            });
+        if spawned.is_ok() {
+            pending += 1;
+        }
+    }
+    let mut all_finished = true;
+    for _ in 0..pending {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        match drained.recv_timeout(remaining) {
+            Ok(finished) => all_finished &= finished,
+            Err(_) => return false,
+        }
+    }
+    all_finished
+}
+
+/// Lists the live handles, oldest first.
+pub(crate) fn live() -> Vec<HandleSummary> {
+    lock()
+        .values()
+        .map(|entry| HandleSummary {
+            kind: entry.kind,
+            stack_hash: entry.stack_hash,
+            age: entry.created_at.elapsed(),
+            appends: entry.latency.count(Operation::Append),
+            scans: entry.latency.count(Operation::Scan),
+            tags: entry.tags.as_str().to_string(),
+        })
+        .collect()
+}
+
+fn lock() -> MutexGuard<'static, BTreeMap<u64, Entry>> {
+    LIVE.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn listed(stack_hash: i32) -> Vec<HandleSummary> {
+        live()
+            .into_iter()
+            .filter(|h| h.stack_hash == stack_hash)
+            .collect()
+    }
+
+    #[test]
+    fn should_list_handles_until_dropped() {
+        // given
+        let latency = Arc::new(LatencyRecorder::new());
+        latency.record(Operation::Append, Duration::from_micros(10));
+        let registration = register(HandleKind::Reader, latency, Arc::default(), None);
+        registration.set_stack_hash(0x5eed);
+
+        // when
+        let before_drop = listed(0x5eed);
+        drop(registration);
+        let after_drop = listed(0x5eed);
+
+        // then
+        assert_eq!(before_drop.len(), 1);
+        assert_eq!(before_drop[0].kind, HandleKind::Reader);
+        assert_eq!(before_drop[0].appends, 1);
+        assert_eq!(before_drop[0].scans, 0);
+        assert!(after_drop.is_empty());
+    }
+
+    #[test]
+    fn should_drain_registered_handles() {
+        // given
+        let drained = Arc::new(AtomicU64::new(0));
+        let counter = Arc::clone(&drained);
+        let drain: Drain = Arc::new(move |_| {
+            counter.fetch_add(1, Ordering::Relaxed);
+            true
+        });
+        let registration = register(
+            HandleKind::Log,
+            Arc::new(LatencyRecorder::new()),
+            Arc::default(),
+            Some(drain),
+        );
+
+        // when
+        let finished = drain_all(Duration::from_secs(5));
+
+        // then
+        assert!(finished);
+        assert_eq!(drained.load(Ordering::Relaxed), 1);
+        drop(registration);
+    }
+}
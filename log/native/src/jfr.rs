@@ -0,0 +1,198 @@
+//! Java Flight Recorder events for native appends, scans and flushes.
+//!
+//! Time spent in the native layer shows up in a JFR recording as a thread
+//! stuck in a native method, without saying what it was doing. While a
+//! recording enables the `dev.opendata.NativeOperation` event, each append,
+//! scan and flush is handed to the Java `NativeEvents` class, which commits
+//! an event naming the operation with its native duration, entries and
+//! bytes, so native activity lines up with the rest of the JDK Mission
+//! Control timeline, tagged with the handle's tags and the traceparent of
+//! the call, if it passed one (`tracecontext.rs`). Events are committed
+//! when the operation ends; JFR cannot backdate an event's start, so the
+//! storage time is the event's `nativeDuration` field rather than its own
+//! duration.
+//!
+//! `NativeEvents` tells the native layer whenever recordings start or stop,
+//! so without a recording enabling the event nothing is emitted and the cost
+//! is one atomic load per operation. With one, an event costs a JNI upcall,
+//! and attaches runtime workers completing asynchronous calls to the JVM,
+//! see `attach.rs`. Compactions run inside SlateDB and the upstream `LogDb` does not
+//! report them, so they emit no events.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
+
+use jni::objects::{GlobalRef, JClass, JValue};
+use jni::JNIEnv;
+use log::{LogEntry, Record};
+
+use crate::attach;
+use crate::tags::Tags;
+use crate::tracecontext;
+
+/// Local references a committed event needs.
+const LOCAL_FRAME_CAPACITY: i32 = 3;
+
+/// Operation arguments of `NativeEvents.commit`.
+const APPEND: i32 = 0;
+const SCAN: i32 = 1;
+const FLUSH: i32 = 2;
+
+struct Bridge {
+    class: GlobalRef,
+}
+
+static BRIDGE: OnceLock<Bridge> = OnceLock::new();
+
+/// Whether a recording enables the event, as last reported by Java.
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// Tags, entries and bytes of an append, captured before the records move.
+pub(crate) struct AppendSize {
+    tags: Arc<Tags>,
+    entries: u64,
+    bytes: u64,
+}
+
+/// Resolves the Java `NativeEvents` class and has it report recording
+/// changes, if not done yet.
+///
+/// Must be called from a Java thread. Failures leave events off.
+pub(crate) fn init(env: &mut JNIEnv<'_>) {
+    if BRIDGE.get().is_some() {
+        return;
+    }
+    let bridge = env
+        .find_class("dev/opendata/NativeEvents")
+        .and_then(|class| env.new_global_ref(class))
+        .map(|class| Bridge { class });
+    let Ok(bridge) = bridge else {
+        let _ = env.exception_clear();
+        return;
+    };
+    if BRIDGE.set(bridge).is_err() {
+        return;
+    }
+    let class: &JClass<'_> = BRIDGE.get().expect("set above").class.as_obj().into();
+    if env
+        .call_static_method(class, "register", "()V", &[])
+        .is_err()
+    {
+        let _ = env.exception_clear();
+    }
+}
+
+/// Records whether a recording enables the event.
+pub(crate) fn set_enabled(enabled: bool) {
+    ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+/// Captures the size of an append, or None when no event will be emitted.
+pub(crate) fn summarize(tags: &Arc<Tags>, records: &[Record]) -> Option<AppendSize> {
+    if !ENABLED.load(Ordering::Relaxed) {
+        return None;
+    }
+    Some(AppendSize {
+        tags: Arc::clone(tags),
+        entries: records.len() as u64,
+        bytes: records.iter().map(|r| r.value.len() as u64).sum(),
+    })
+}
+
+/// Emits an append event.
+pub(crate) fn append(elapsed: Duration, size: Option<AppendSize>, succeeded: bool) {
+    if let Some(size) = size {
+        commit(
+            &size.tags,
+            APPEND,
+            elapsed,
+            size.entries,
+            size.bytes,
+            succeeded,
+        );
+    }
+}
+
+/// Emits a scan event; `entries` is None if the scan failed.
+pub(crate) fn scan(tags: &Tags, elapsed: Duration, entries: Option<&[LogEntry]>) {
+    if !ENABLED.load(Ordering::Relaxed) {
+        return;
+    }
+    let (count, bytes) = entries.map_or((0, 0), |entries| {
+        (
+            entries.len() as u64,
+            entries.iter().map(|e| e.value.len() as u64).sum(),
+        )
+    });
+    commit(tags, SCAN, elapsed, count, bytes, entries.is_some());
+}
+
+/// Emits a flush event.
+pub(crate) fn flush(tags: &Tags, elapsed: Duration, succeeded: bool) {
+    if ENABLED.load(Ordering::Relaxed) {
+        commit(tags, FLUSH, elapsed, 0, 0, succeeded);
+    }
+}
+
+fn commit(
+    tags: &Tags,
+    operation: i32,
+    elapsed: Duration,
+    entries: u64,
+    bytes: u64,
+    succeeded: bool,
+) {
+    let Some(bridge) = BRIDGE.get() else {
+        return;
+    };
+    let traceparent = tracecontext::current().unwrap_or_default();
+    attach::call(LOCAL_FRAME_CAPACITY, |env| {
+        let tags = env.new_string(tags.as_str())?;
+        let traceparent = env.new_string(&traceparent)?;
+        let class: &JClass<'_> = bridge.class.as_obj().into();
+        env.call_static_method(
+            class,
+            "commit",
+            "(IJJJZLjava/lang/String;Ljava/lang/String;)V",
+            &[
+                JValue::Int(operation),
+                JValue::Long(elapsed.as_nanos().min(i64::MAX as u128) as i64),
+                JValue::Long(entries as i64),
+                JValue::Long(bytes as i64),
+                JValue::Bool(succeeded.into()),
+                JValue::Object(&tags),
+                JValue::Object(&traceparent),
+            ],
+        )?;
+        Ok(())
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+
+    use super::*;
+
+    #[test]
+    fn should_summarize_appends_only_while_enabled() {
+        // given
+        let records = [Record {
+            key: Bytes::from_static(b"key"),
+            value: Bytes::from_static(b"12345678payload"),
+        }];
+
+        // when
+        let disabled = summarize(&Arc::default(), &records);
+        set_enabled(true);
+        let enabled = summarize(&Arc::default(), &records);
+        set_enabled(false);
+
+        // then
+        assert!(disabled.is_none());
+        let size = enabled.unwrap();
+        assert_eq!(size.entries, 1);
+        assert_eq!(size.bytes, 15);
+    }
+}
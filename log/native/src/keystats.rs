@@ -0,0 +1,219 @@
+//! Per-key read and write statistics.
+//!
+//! A benchmark whose key distribution is skewed, or a production log with a
+//! hotspot, shows up in the handle's stats only as uneven latency. With key
+//! statistics configured, every append and scan of the handle also counts
+//! the records and bytes it moved per key, along with when the key was last
+//! appended to and scanned, and the hot-keys report lists the keys with the
+//! most operations.
+//!
+//! Statistics are kept for at most the configured number of keys. Past it,
+//! the least recently used key is forgotten, so keys that were hot long ago
+//! drop out and a key that comes back starts counting again; a hot key is
+//! recently used by definition. Appends record the records of each key
+//! once storage accepted them, chunks counted as records; scans record the
+//! entries served, so a scan that found nothing counts for no key. All
+//! counts go through one mutex per handle.
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Mutex, MutexGuard, PoisonError};
+
+use bytes::Bytes;
+use log::{LogEntry, Record};
+
+use crate::metrics::Stats;
+
+/// Counts of one key.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct KeyCounts {
+    pub(crate) records_appended: u64,
+    pub(crate) bytes_appended: u64,
+    pub(crate) scans: u64,
+    pub(crate) entries_scanned: u64,
+    pub(crate) bytes_scanned: u64,
+    /// Wall-clock time of the last append, or 0
+    pub(crate) last_append_ms: i64,
+    /// Wall-clock time of the last scan, or 0
+    pub(crate) last_scan_ms: i64,
+    /// Tick of the last append or scan
+    used: u64,
+}
+
+impl KeyCounts {
+    /// Returns the records appended plus the scans that served entries.
+    pub(crate) fn operations(&self) -> u64 {
+        self.records_appended + self.scans
+    }
+}
+
+#[derive(Default)]
+struct Inner {
+    keys: HashMap<Bytes, KeyCounts>,
+    /// Keys by the tick they were last used at, oldest first
+    lru: BTreeMap<u64, Bytes>,
+    clock: u64,
+    evicted: u64,
+}
+
+impl Inner {
+    fn touch(&mut self, key: &Bytes, capacity: usize) -> &mut KeyCounts {
+        self.clock += 1;
+        let clock = self.clock;
+        if !self.keys.contains_key(key) && self.keys.len() >= capacity {
+            if let Some((_, oldest)) = self.lru.pop_first() {
+                self.keys.remove(&oldest);
+                self.evicted += 1;
+            }
+        }
+        let counts = self.keys.entry(key.clone()).or_default();
+        if counts.used != 0 {
+            self.lru.remove(&counts.used);
+        }
+        counts.used = clock;
+        self.lru.insert(clock, key.clone());
+        counts
+    }
+}
+
+/// Records and bytes of each key of a batch, captured before the batch is
+/// appended.
+pub(crate) struct BatchCounts(Vec<(Bytes, u64, u64)>);
+
+/// Per-handle statistics of the most recently used keys.
+pub(crate) struct KeyStats {
+    capacity: usize,
+    inner: Mutex<Inner>,
+}
+
+impl KeyStats {
+    /// Creates statistics kept for at most `capacity` keys.
+    pub(crate) fn new(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            inner: Mutex::new(Inner::default()),
+        }
+    }
+
+    /// Captures the records and bytes of each key of a batch.
+    pub(crate) fn capture(records: &[Record]) -> BatchCounts {
+        // Batches mostly hold runs of one key; count each run at once
+        let runs = records
+            .chunk_by(|a, b| a.key == b.key)
+            .map(|run| {
+                let bytes = run
+                    .iter()
+                    .map(|r| (r.key.len() + r.value.len()) as u64)
+                    .sum();
+                (run[0].key.clone(), run.len() as u64, bytes)
+            })
+            .collect();
+        BatchCounts(runs)
+    }
+
+    /// Records a batch storage accepted at `now_ms`.
+    pub(crate) fn record_append(&self, batch: BatchCounts, now_ms: i64) {
+        let mut inner = self.lock();
+        for (key, records, bytes) in batch.0 {
+            let counts = inner.touch(&key, self.capacity);
+            counts.records_appended += records;
+            counts.bytes_appended += bytes;
+            counts.last_append_ms = now_ms;
+        }
+    }
+
+    /// Records the entries a scan served at `now_ms`.
+    pub(crate) fn record_scan(&self, entries: &[LogEntry], now_ms: i64) {
+        let Some(first) = entries.first() else {
+            return;
+        };
+        let bytes = entries
+            .iter()
+            .map(|e| (e.key.len() + e.value.len()) as u64)
+            .sum::<u64>();
+        let mut inner = self.lock();
+        let counts = inner.touch(&first.key, self.capacity);
+        counts.scans += 1;
+        counts.entries_scanned += entries.len() as u64;
+        counts.bytes_scanned += bytes;
+        counts.last_scan_ms = now_ms;
+    }
+
+    /// Returns up to `limit` keys with the most operations, most first.
+    pub(crate) fn hot_keys(&self, limit: usize) -> Vec<(Bytes, KeyCounts)> {
+        let mut keys: Vec<(Bytes, KeyCounts)> = self
+            .lock()
+            .keys
+            .iter()
+            .map(|(key, counts)| (key.clone(), counts.clone()))
+            .collect();
+        keys.sort_by(|(a_key, a), (b_key, b)| {
+            b.operations()
+                .cmp(&a.operations())
+                .then_with(|| a_key.cmp(b_key))
+        });
+        keys.truncate(limit);
+        keys
+    }
+
+    /// Appends the number of keys tracked and forgotten.
+    pub(crate) fn append_stats(&self, stats: &mut Stats) {
+        let inner = self.lock();
+        stats.push(("key_stats.keys".to_string(), inner.keys.len() as i64));
+        stats.push(("key_stats.evicted".to_string(), inner.evicted as i64));
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Inner> {
+        self.inner.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(key: &'static [u8]) -> Record {
+        Record {
+            key: Bytes::from_static(key),
+            value: Bytes::from_static(b"value"),
+        }
+    }
+
+    #[test]
+    fn should_rank_keys_by_operations() {
+        // given
+        let stats = KeyStats::new(10);
+        stats.record_append(
+            KeyStats::capture(&[record(b"a"), record(b"b"), record(b"b")]),
+            1,
+        );
+
+        // when
+        let hot = stats.hot_keys(1);
+
+        // then
+        assert_eq!(hot.len(), 1);
+        assert_eq!(hot[0].0, Bytes::from_static(b"b"));
+        assert_eq!(hot[0].1.records_appended, 2);
+        assert_eq!(hot[0].1.bytes_appended, 12);
+        assert_eq!(hot[0].1.last_append_ms, 1);
+    }
+
+    #[test]
+    fn should_forget_least_recently_used_key_past_capacity() {
+        // given
+        let stats = KeyStats::new(2);
+        stats.record_append(KeyStats::capture(&[record(b"a")]), 1);
+        stats.record_append(KeyStats::capture(&[record(b"b")]), 2);
+        stats.record_append(KeyStats::capture(&[record(b"a")]), 3);
+
+        // when
+        stats.record_append(KeyStats::capture(&[record(b"c")]), 4);
+
+        // then
+        let keys: Vec<Bytes> = stats.hot_keys(10).into_iter().map(|(k, _)| k).collect();
+        assert_eq!(
+            keys,
+            vec![Bytes::from_static(b"a"), Bytes::from_static(b"c")]
+        );
+    }
+}
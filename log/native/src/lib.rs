@@ -15,7 +15,9 @@
 //! └─────────────────────┴──────────────────────┘
 //! ```
 //!
-//! - On `append`: timestamp from Java Record is prepended to the value (captured at submission time)
+//! - On `append`: timestamp from Java Record is prepended to the value (captured at submission time),
+//!   unless the handle's clock assigns timestamps itself, see `clock`; with receive timestamps
+//!   configured, the time the record reached the native layer follows it, see `skew`
 //! - On `read`: timestamp is extracted from the header and returned separately
 //!
 //! This is transparent to the Java caller and will be removed once upstream
@@ -32,6 +34,8 @@
 //! |-----------|--------|-------|
 //! | `append(key, value)` | 1 + 1 | key: Java→Rust; value: Java→Rust (directly into timestamped buffer) |
 //! | `read()` → entries | 2 per entry | Rust `Bytes` → Java `byte[]` for key and value |
+//! | `scanInto()` | 1 per entry | payload Rust → caller's direct buffer |
+//! | `scanArrow()` | 2 per entry | key and value Rust → Arrow buffers, imported by Java without a copy |
 //!
 //! The value copy on append is optimized using `get_byte_array_region` to copy
 //! directly into a pre-allocated buffer that includes space for the timestamp
@@ -50,30 +54,420 @@
 //! for argument marshalling and stack frame setup. This is negligible for
 //! non-trivial operations but adds up for high-frequency calls.
 //!
+//! ## Latency Recording
+//!
+//! Every append and scan records its storage latency (the time spent inside
+//! `block_on`, excluding JNI marshalling) into a per-handle HdrHistogram. This
+//! costs one uncontended mutex acquisition and a histogram increment per call,
+//! which is small relative to a storage round trip. The encoded histograms are
+//! exposed via `nativeGetLatencyHistogram`.
+//!
+//! With `profiling` enabled in the config, each call additionally captures a
+//! handful of `Instant::now()` timestamps to split its duration into JNI
+//! marshalling, runtime queueing, storage, and result marshalling phases
+//! (`nativeGetTimingBreakdown`). Timestamps are always taken; only the atomic
+//! accumulation is skipped when profiling is disabled.
+//!
+//! ## Runtime Statistics
+//!
+//! `nativeStats` reports Tokio runtime health for each handle: worker count,
+//! cumulative worker busy time, injection queue depth, alive tasks, and the
+//! number of Java threads currently blocked in `block_on`. Reading runtime
+//! metrics is lock-free; the blocked-caller gauge costs two relaxed atomic
+//! updates per call. With a disk cache configured, each `nativeStats` call
+//! also walks the cache directory (`diskcache.rs`).
+//!
+//! `nativeIntervalStats` reports the records and bytes appended and served
+//! since its previous call. Appends and scans pay two relaxed atomic adds
+//! each to total them, and appends sum their record sizes.
+//!
+//! ## Watchdog
+//!
+//! With a watchdog configured, each call is also registered in a per-handle
+//! in-flight map (one mutex acquisition on entry and exit), and a dedicated
+//! thread polls the map to report calls exceeding the threshold.
+//!
+//! ## No-op Storage
+//!
+//! With `StorageConfig.NoOp`, appends and scans skip storage but still go
+//! through JNI marshalling, `block_on`, latency recording and the watchdog,
+//! so their latency is the floor these bindings add to every call.
+//!
+//! ## Fault Injection
+//!
+//! Faults are injected into the object store the log opens on, so without
+//! fault injection configured appends and scans pay nothing for it. With it,
+//! each object-store request takes a mutex for the random draw, and the
+//! retries of failed requests stall flushes and reads, so fault-injected runs
+//! are not suitable for measuring throughput.
+//!
+//! ## C ABI
+//!
+//! The library also exports a plain C API (`ffi.rs`, declared in
+//! `include/opendata_log.h`) for Foreign Function & Memory API and non-Java
+//! callers. It has no per-call JNI overhead, latency recording or watchdog,
+//! and copies keys and values once into the timestamped buffer.
+//!
+//! ## Write Coalescing
+//!
+//! With `coalesce` configured, a blocking append joins the current window:
+//! one mutex acquisition and a oneshot channel per call, plus one spawned
+//! task per window. Its latency then includes the time the window stays open,
+//! up to the configured window, in exchange for fewer storage appends.
+//!
+//! ## Bridge Threads
+//!
+//! With `bridgeThreads` configured, appends, scans and flushes are awaited by
+//! a bounded pool of native threads (`bridge.rs`) instead of in `block_on` on
+//! the calling thread, which waits for a `CompletableFuture` in Java. Each call
+//! costs a global reference, a bounded channel send and a JNI upcall to
+//! complete the future. Bridged calls record storage latency but bypass
+//! profiling and the watchdog.
+//!
+//! ## Submission Queues
+//!
+//! `nativeSubmitAppend` and `nativeSubmitScan` copy their arguments and spawn
+//! the operation instead of blocking in `block_on`; `nativePollCompletions`
+//! waits on a condition variable and converts finished operations to Java.
+//! Each submission costs a task spawn and a mutex acquisition on completion.
+//! Submitted operations record storage latency but bypass profiling, fault
+//! injection and the watchdog. With `writeQueue` configured, each submitted
+//! append also reserves its records and bytes in a mutex-guarded counter
+//! (`backpressure.rs`) until it completes.
+//!
+//! ## Append Rings
+//!
+//! Records offered to an `AppendRing` are written by Java straight into
+//! native memory and appended by a dedicated consumer thread, so a producer
+//! pays no JNI call unless it finds the consumer parked on an empty ring. The
+//! consumer copies each frame once more into a timestamped value. Ring appends
+//! bypass no-op storage, latency recording and the watchdog.
+//!
+//! ## Append Pipelines
+//!
+//! An `AppendPipeline` (`pipeline.rs`) spawns each batch onto the runtime,
+//! chained behind its predecessor's append through a oneshot channel, and
+//! completes its `CompletableFuture` from a single bridge thread in submission
+//! order. Each batch costs a semaphore permit, a task spawn, a global
+//! reference and a JNI upcall. Pipelined appends record storage latency but
+//! bypass no-op storage, profiling and the watchdog.
+//!
+//! ## Rate Limits
+//!
+//! With `rateLimits` configured, plain and partitioned appends and scans,
+//! blocking, bridged or submitted, reserve tokens from a mutex-guarded bucket
+//! (`ratelimit.rs`) and may sleep on a runtime timer before reaching storage.
+//! The wait never counts toward the watchdog; blocking calls also exclude it
+//! from recorded storage latency.
+//! Bridged and submitted calls against no-op storage are not limited.
+//!
+//! ## Read-Ahead
+//!
+//! With `readAhead` enabled on a reader, a scan that fills its page spawns a
+//! background read of the next page (`readahead.rs`), and a scan starting
+//! where that page starts takes it from a mutex-guarded map. A hit still
+//! copies the entries into Java but skips the storage round trip; each
+//! prefetch costs a task spawn and a read that is wasted if the consumer
+//! jumps elsewhere.
+//!
+//! ## Resume Tokens
+//!
+//! Scans of a reader through `nativeScanResumable` read from a storage
+//! iterator parked between calls (`cursors.rs`) instead of seeking for every
+//! page. Each page costs two channel hops to the cursor's task and a map
+//! lookup; a parked cursor holds its iterator, and whatever the iterator
+//! buffers, for up to a minute. Such scans never read ahead.
+//!
+//! ## Tail Cache
+//!
+//! With `tailCacheBytes` set, every append through the handle also copies
+//! its entries into a per-handle LRU map (`tailcache.rs`), under one mutex
+//! taken twice per append. Scans of the handle, or of readers attached to
+//! it, that start within a key's cached tail skip storage entirely, so
+//! measurements of tail-following consumers with a cache say nothing about
+//! storage read latency.
+//!
+//! ## Slow-Operation Log
+//!
+//! With `slowOperationThresholdMs` set, each append and scan compares its
+//! storage latency against the threshold, and appends also note their first
+//! key and whether the batch holds others before the records move
+//! (`slowlog.rs`). Operations past the threshold are formatted and logged
+//! through a JNI upcall to SLF4J (`logging.rs`) on the thread that ran them,
+//! so a burst of slow calls adds its own latency to the runtime workers.
+//!
+//! ## Automatic Reopen
+//!
+//! Every call clones the handle's current LogDb `Arc` under a read lock
+//! (`reopen.rs`). With `reopen` configured, appends, scans and flushes also
+//! record their outcome in an atomic counter, and a reopen opens the storage
+//! on the handle's runtimes while calls continue, so its object-store reads
+//! compete with theirs.
+//!
+//! ## Memory Accounting
+//!
+//! Every append adds its record bytes to an atomic gauge and subtracts them
+//! when it finishes (`memory.rs`). Stats then take the tail cache lock and
+//! the completion lock to sum what they hold, so polling stats often
+//! contends with appends that cache their entries and with polled scans.
+//! With `memoryLimit` set, every append takes both locks before it is
+//! charged, and a release under `BLOCK` also takes a mutex while an append
+//! is waiting.
+//!
+//! ## Call Limit
+//!
+//! With `callLimit` set, every blocking LogDb call takes a mutex to claim a
+//! slot before it enters the runtime and again to release it (`overload.rs`).
+//! Calls beyond the limit wait on a condition variable in arrival order, so
+//! under `QUEUE` or `BLOCK` a saturated handle adds the wait for a slot to
+//! the latency of each call; refused calls throw without touching storage.
+//!
+//! ## Reader Runtime Mode
+//!
+//! In `CURRENT_THREAD` mode a reader's tasks, timers and IO are driven by a
+//! single thread (`runtimes.rs`). A call waiting on storage is woken by that
+//! thread rather than by an idle worker, so a reader whose driver is busy
+//! with read-ahead or a refresh delays the completion of concurrent scans.
+//!
+//! ## Configuration Updates
+//!
+//! Rate limits are held behind a read-write lock so an update can replace
+//! them, which every append and scan of a rate-limited handle takes shared,
+//! and the tail cache budget is an atomic read on every cached append. An
+//! update shrinking the tail cache evicts under the cache lock, stalling
+//! appends and scans of the handle until it is done.
+//!
+//! ## Priority Classes
+//!
+//! Foreground appends and scans only compare their priority. Background ones
+//! take a semaphore permit, an atomic operation while permits are free, and
+//! otherwise queue on the runtime until one is released; see `priority.rs`.
+//!
+//! ## Key Statistics
+//!
+//! With key statistics configured, each append groups its records into runs
+//! of one key and each scan sums its entry sizes, then both take one
+//! per-handle mutex to update an LRU map of key counts (`keystats.rs`).
+//! Without them, appends and scans only test an `Option`.
+//!
+//! ## StatsD Export
+//!
+//! With a StatsD agent configured, a thread per handle takes a stats
+//! snapshot every interval, as `nativeStats` does, and sends it as UDP
+//! datagrams (`statsd.rs`). Appends and scans are not involved; the
+//! snapshot briefly takes the locks of the latency histograms.
+//!
 //! ## Comparison Baseline
 //!
 //! For fair comparison with systems like WarpStream (which use native clients),
 //! consider that this JNI layer adds constant overhead per operation. The
 //! overhead should be relatively smaller for larger payloads and batch sizes.
 
+mod alloc;
+mod arrowbatch;
+mod attach;
+mod backpressure;
+mod backup;
+mod bench;
+mod bridge;
+mod checkpoints;
+mod chunking;
+mod clock;
+mod closing;
+mod coalesce;
+mod completion;
+mod configfile;
+mod coordination;
+mod cursors;
+mod dedup;
+mod diskcache;
+mod durability;
+mod envconfig;
+mod export;
+mod fault;
+mod ffi;
+mod filter;
+mod flatbatch;
+mod grouping;
+mod handles;
+mod jfr;
+mod keystats;
+mod logging;
+mod memory;
+mod metrics;
+mod noop;
+mod offsets;
+mod overload;
+mod partition;
+mod pipeline;
+mod positions;
+mod priority;
+mod ratelimit;
+mod readahead;
+mod reopen;
+mod ring;
+mod runtimes;
+mod sample;
+mod scanstream;
+mod settings;
+mod skew;
+mod slowlog;
+mod snapshots;
+mod statsd;
+mod tags;
+mod tailcache;
+mod topics;
+mod tracecontext;
+mod verify;
+mod watch;
+mod watchdog;
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::{Arc, Mutex, PoisonError, Weak};
+use std::time::Instant;
+
 use bytes::Bytes;
-use jni::objects::{JByteArray, JClass, JObject, JObjectArray, JValue};
-use jni::sys::{jlong, jobject, jobjectArray};
+use jni::objects::{
+    GlobalRef, JByteArray, JByteBuffer, JClass, JIntArray, JLongArray, JObject, JObjectArray,
+    JString, JThrowable, JValue, ReleaseMode,
+};
+use jni::sys::{
+    jboolean, jbyteArray, jdouble, jint, jintArray, jlong, jlongArray, jobject, jobjectArray,
+    jstring, JNI_FALSE, JNI_TRUE,
+};
 use jni::JNIEnv;
 use tokio::runtime::{Handle, Runtime};
 
 /// Size of the timestamp header prepended to values.
 const TIMESTAMP_HEADER_SIZE: usize = 8;
 
+/// Magic opening an extended envelope, that of a chunk (`chunking.rs`) or of
+/// a value carrying its receive time (`skew.rs`).
+///
+/// A plain value starts with its timestamp, so any marker is also the start
+/// of some timestamps. Seven bytes narrow those to the 256 milliseconds
+/// starting 0x806f_6465_6e76_6c00, some 290 million years before the epoch;
+/// every other plain value reads as it always has.
+const ENVELOPE_MAGIC: [u8; 7] = [0x80, b'o', b'd', b'e', b'n', b'v', b'l'];
+
+/// Size of the magic and version byte preceding an extended envelope's
+/// timestamp header.
+const ENVELOPE_PREFIX_SIZE: usize = 8;
+
+/// Returns the version of a value's extended envelope, or None for a plain
+/// value.
+fn envelope_version(value: &[u8]) -> Option<u8> {
+    if value.len() < ENVELOPE_PREFIX_SIZE + TIMESTAMP_HEADER_SIZE
+        || value[..ENVELOPE_MAGIC.len()] != ENVELOPE_MAGIC
+    {
+        return None;
+    }
+    Some(value[ENVELOPE_MAGIC.len()])
+}
+
+/// Returns the magic and version opening an extended envelope.
+fn envelope_prefix(version: u8) -> [u8; ENVELOPE_PREFIX_SIZE] {
+    let mut prefix = [version; ENVELOPE_PREFIX_SIZE];
+    prefix[..ENVELOPE_MAGIC.len()].copy_from_slice(&ENVELOPE_MAGIC);
+    prefix
+}
+
+/// When an append is acknowledged, mirroring the Java `AckMode` enum.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum AckMode {
+    /// Acknowledged once the storage layer accepts the batch
+    Buffered,
+    /// Acknowledged after the batch has been flushed to durable storage
+    Durable,
+}
+
+impl AckMode {
+    /// Converts a Java `AckMode.ordinal()` value into an ack mode.
+    fn from_ordinal(ordinal: jint) -> Option<Self> {
+        match ordinal {
+            0 => Some(AckMode::Buffered),
+            1 => Some(AckMode::Durable),
+            _ => None,
+        }
+    }
+}
+
+/// Which writes a reader scan observes, mirroring the Java `ReadConsistency` enum.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ReadConsistency {
+    /// Whatever the reader's view includes when the scan starts
+    LatestCommitted,
+    /// At least the entries appended through the paired writer
+    ReadYourWrites,
+}
+
+impl ReadConsistency {
+    /// Converts a Java `ReadConsistency.ordinal()` value into a consistency level.
+    fn from_ordinal(ordinal: jint) -> Option<Self> {
+        match ordinal {
+            0 => Some(ReadConsistency::LatestCommitted),
+            1 => Some(ReadConsistency::ReadYourWrites),
+            _ => None,
+        }
+    }
+}
+
 // Re-export log crate types with explicit naming to avoid confusion with std log
+use backpressure::{BackpressurePolicy, WriteQueue, WriteQueueSettings};
+use bridge::{BridgePool, Deliver};
+use checkpoints::CheckpointInfo;
+use chunking::Assembler;
+use clock::{Clock, ClockSource, Stamps};
+use coalesce::{CoalesceSettings, Coalescer};
 use common::storage::config::{
     AwsObjectStoreConfig, LocalObjectStoreConfig, ObjectStoreConfig, SlateDbStorageConfig,
     StorageConfig,
 };
 use common::StorageRuntime;
+use completion::{Completion, CompletionQueue, Outcome};
+use coordination::{GroupViews, MemberEvent};
+use cursors::{Cursor, Cursors, ResumeToken};
+use dedup::{DedupCheck, DedupWindows};
+use diskcache::{DiskCache, DiskCacheSettings};
+use durability::AppendedTails;
+use export::{ExportError, ExportFormat, Importer};
+use fault::{FaultInjector, FaultSettings};
+use filter::ScanFilter;
+use handles::{Drain, HandleKind, Registration};
+use keystats::{KeyCounts, KeyStats};
 use log::{
     AppendResult, Config, LogDb, LogDbBuilder, LogDbReader, LogEntry, LogRead, ReaderConfig, Record,
 };
+use memory::{HandleMemory, MemoryCharge, MemoryLimitSettings};
+use metrics::{
+    BlockedCallerGuard, BlockedCallers, CallTimer, Intervals, LatencyRecorder, Measurement,
+    Operation, Profiler, ServedBytes, Stats, SteadyStateSettings, Traffic,
+};
+use noop::NoOpLog;
+use object_store::ObjectStore;
+use offsets::{AutoCommitter, CachedOffset, OffsetCache};
+use overload::{CallLimitSettings, CallLimiter, CallPermit, OverloadPolicy};
+use pipeline::Pipeline;
+use positions::ReaderPositions;
+use priority::{Priority, PriorityGate};
+use ratelimit::{RateLimitSettings, RateLimiter};
+use readahead::ReadAhead;
+use reopen::{ReopenSettings, ReopeningLog};
+use ring::AppendRing;
+use runtimes::{OwnedRuntime, RuntimeMode, RuntimeSettings};
+use scanstream::{ScanListener, ScanRange};
+use settings::{GarbageCollectorSettings, SettingsFile, SettingsOverlay, WriteBufferSettings};
+use skew::ClockSkew;
+use slowlog::SlowLog;
+use snapshots::Snapshots;
+use statsd::{Statsd, StatsdSettings};
+use tags::Tags;
+use tailcache::TailCache;
+use topics::{TopicCatalog, TopicEvent, TopicSettings, TOPIC_CATALOG_KEY};
+use tracecontext::TraceContext;
+use watch::Watches;
+use watchdog::{StuckCall, Watchdog, WatchdogSettings};
 
 /// Handle to a LogDb instance with its associated Tokio runtime.
 ///
@@ -81,14 +475,180 @@ use log::{
 /// SlateDB's compaction/GC tasks to prevent deadlock when the main runtime's
 /// threads are blocked in JNI calls.
 struct LogHandle {
-    /// The LogDb instance
-    log: LogDb,
+    /// The LogDb instance, shared weakly with readers committing offsets and
+    /// replaced after repeated storage failures if reopening is configured
+    log: Arc<ReopeningLog>,
+    /// Storage the log was opened with, for checkpoints
+    storage: StorageConfig,
     /// Handle to the runtime for async operations
     runtime_handle: Handle,
-    /// The main runtime (kept alive for the lifetime of the LogDb)
+    /// The main runtime (kept alive for the lifetime of the LogDb); None for
+    /// logs opened through a LogDbManager, which owns the shared runtimes
     runtime: Option<Runtime>,
-    /// Separate runtime for SlateDB compaction/GC tasks
+    /// Separate runtime for SlateDB compaction/GC tasks; None for managed logs
+    /// and when shared with the main runtime
     compaction_runtime: Option<Runtime>,
+    /// Latency histograms for appends and scans
+    latency: Arc<LatencyRecorder>,
+    /// Per-phase call timings (only recorded when profiling is enabled)
+    profiler: Profiler,
+    /// Java threads currently blocked in `block_on` on this handle
+    blocked_callers: BlockedCallers,
+    /// Warmup state and start of the current measurement period
+    measurement: Measurement,
+    /// Traffic totals at the end of the last interval stats call
+    intervals: Intervals,
+    /// Operations submitted without blocking, and their completions
+    completions: Arc<CompletionQueue>,
+    /// Bound on submitted appends in flight, if configured
+    write_queue: Option<Arc<WriteQueue>>,
+    /// Limit on blocking calls running at once, if configured
+    call_limiter: Option<CallLimiter>,
+    /// Produce and consume rate limits, if configured
+    rate_limiter: Option<Arc<RateLimiter>>,
+    /// Admits background appends and scans up to the configured concurrency
+    priorities: PriorityGate,
+    /// Recently appended entries served to scans, if configured
+    tail_cache: Option<Arc<TailCache>>,
+    /// Local disk cache of SlateDB object-store reads, if configured
+    disk_cache: Option<DiskCache>,
+    /// Bytes scans were served from the tail cache and from storage
+    served: Arc<ServedBytes>,
+    /// Skew of scanned entries carrying a receive time
+    skew: Arc<ClockSkew>,
+    /// Listeners notified of new entries of watched keys
+    watches: Arc<Watches>,
+    /// Per-key statistics of appends and scans, if configured
+    key_stats: Option<Arc<KeyStats>>,
+    /// Logs appends and scans exceeding the slow-operation threshold
+    slow_log: Arc<SlowLog>,
+    /// Native memory held by the handle, and its limit if configured
+    memory: Arc<HandleMemory>,
+    /// When the handle was opened
+    created_at: Instant,
+    /// Entry of the handle in the registry of live handles
+    registration: Registration,
+    /// Watchdog for stuck calls, if configured
+    watchdog: Option<Watchdog>,
+    /// Bridge threads awaiting appends, scans and flushes, if configured
+    bridge: Option<BridgePool>,
+    /// Injector of faults into the log's object store, if configured
+    faults: Option<Arc<FaultInjector>>,
+    /// Stand-in for storage on appends and scans with `StorageConfig.NoOp`
+    noop: Option<Arc<NoOpLog>>,
+    /// Merges concurrent blocking appends into shared batches, if configured
+    coalescer: Option<Arc<Coalescer>>,
+    /// Held shared by appends and exclusively by conditional appends
+    append_lock: Arc<tokio::sync::RwLock<()>>,
+    /// Producer dedup windows for idempotent appends
+    dedup: DedupWindows,
+    /// Latest committed offsets seen by this handle
+    offsets: OffsetCache,
+    /// Background task flushing buffered appends, if configured for a topic
+    /// or by a configuration update
+    flush_task: Mutex<Option<tokio::task::JoinHandle<()>>>,
+    /// Consumer-group membership seen by this handle
+    groups: GroupViews,
+    /// Payload size above which appended values are split into chunks; 0
+    /// leaves values whole
+    chunk_threshold: usize,
+    /// Whether appended values are copied with `GetPrimitiveArrayCritical`
+    critical_copy: bool,
+    /// Labels attached to the handle's stats, log lines and JFR events
+    tags: Arc<Tags>,
+    /// Clock stamping appended records, if not left their own timestamps
+    clock: Arc<Clock>,
+    /// Whether appended values record their receive time
+    receive_timestamps: bool,
+    /// Export of the handle's stats to a StatsD agent, if configured
+    statsd: Option<Statsd>,
+    /// Copy of the settings file storage is opened and reopened from, if
+    /// typed SlateDB settings are configured
+    _settings_file: Option<Arc<SettingsFile>>,
+}
+
+impl LogHandle {
+    /// Collects the name/value pairs reported by `nativeStats`.
+    fn stats(&self) -> Stats {
+        self.untagged_stats()
+            .into_iter()
+            .map(|(name, value)| (self.tags.stat_name(name), value))
+            .collect()
+    }
+
+    /// Collects the stats and latency summaries sent to a StatsD agent and
+    /// reported by `nativeMetricsSnapshot`, both of which receive the tags
+    /// separately.
+    fn exported_stats(&self) -> Stats {
+        let mut stats = self.untagged_stats();
+        self.latency.append_summary_stats(&mut stats);
+        stats
+    }
+
+    /// Returns the counts reported by `nativePendingOps`: Java threads in a
+    /// blocking call, bridged calls, submitted operations not completed,
+    /// completions not polled, record bytes of appends in flight and bytes of
+    /// scan results not polled.
+    fn pending_ops(&self) -> [u64; 6] {
+        [
+            self.blocked_callers.current(),
+            self.bridge.as_ref().map_or(0, BridgePool::pending),
+            self.completions.in_flight(),
+            self.completions.completed() as u64,
+            self.memory.in_flight_bytes() as u64,
+            self.completions.held_bytes() as u64,
+        ]
+    }
+
+    fn untagged_stats(&self) -> Stats {
+        let mut stats = Stats::new();
+        stats.push((
+            "uptime_nanos".to_string(),
+            self.created_at.elapsed().as_nanos() as i64,
+        ));
+        self.measurement.append_stats(&mut stats);
+        metrics::append_runtime_stats(&mut stats, "runtime", &self.runtime_handle);
+        metrics::append_blocked_caller_stats(&mut stats, "runtime", &self.blocked_callers);
+        self.completions.append_stats(&mut stats);
+        if let Some(write_queue) = &self.write_queue {
+            write_queue.append_stats(&mut stats);
+        }
+        if let Some(call_limiter) = &self.call_limiter {
+            call_limiter.append_stats(&mut stats);
+        }
+        if let Some(rate_limiter) = &self.rate_limiter {
+            rate_limiter.append_stats(&mut stats);
+        }
+        self.priorities.append_stats(&mut stats);
+        if let Some(tail_cache) = &self.tail_cache {
+            tail_cache.append_stats(&mut stats);
+        }
+        if let Some(disk_cache) = &self.disk_cache {
+            disk_cache.append_stats(&mut stats);
+        }
+        self.served.append_stats(&mut stats);
+        if let Some(key_stats) = &self.key_stats {
+            key_stats.append_stats(&mut stats);
+        }
+        self.skew.append_stats(&mut stats);
+        self.slow_log.append_stats(&mut stats);
+        self.log.append_stats(&mut stats);
+        attach::append_stats(&mut stats);
+        if let Some(rt) = &self.compaction_runtime {
+            metrics::append_runtime_stats(&mut stats, "compaction_runtime", rt.handle());
+        }
+        if let Some(faults) = &self.faults {
+            faults.append_stats(&mut stats);
+        }
+        if let Some(coalescer) = &self.coalescer {
+            coalescer.append_stats(&mut stats);
+        }
+        self.memory.append_stats(&mut stats);
+        if let Some(statsd) = &self.statsd {
+            statsd.append_stats(&mut stats);
+        }
+        stats
+    }
 }
 
 // =============================================================================
@@ -107,130 +667,768 @@ pub extern "system" fn Java_dev_opendata_LogDb_nativeCreate<'local>(
     mut env: JNIEnv<'local>,
     _class: JClass<'local>,
     config: JObject<'local>,
+    progress: JObject<'local>,
 ) -> jlong {
-    // Extract storage config from LogDbConfig
-    let storage_config = match extract_storage_config(&mut env, &config) {
-        Ok(c) => c,
+    attach::init(&mut env);
+    logging::init(&mut env);
+    jfr::init(&mut env);
+    let settings = match extract_log_settings(&mut env, &config) {
+        Ok(s) => s,
         Err(e) => {
             let _ = env.throw_new("java/lang/IllegalArgumentException", e);
             return 0;
         }
     };
-
-    let config = Config {
-        storage: storage_config,
-        ..Config::default()
+    let progress = match OpenProgress::new(&env, &progress) {
+        Ok(p) => p,
+        Err(e) => {
+            let _ = env.throw_new("dev/opendata/common/OpenDataNativeException", e);
+            return 0;
+        }
     };
+    create_log_handle(&mut env, settings, &progress)
+}
 
-    // Create a dedicated runtime for this LogDb instance (for user operations)
-    let runtime = match tokio::runtime::Builder::new_multi_thread()
-        .enable_all()
-        .thread_name("opendata-log")
-        .build()
-    {
-        Ok(rt) => rt,
+/// Creates a new LogDb instance configured by a TOML or YAML file.
+///
+/// See the `configfile` module for the file format.
+///
+/// # Arguments
+/// * `config_path` - Path of the config file
+///
+/// # Safety
+/// This is a JNI function - must be called from Java with valid JNIEnv.
+#[no_mangle]
+pub extern "system" fn Java_dev_opendata_LogDb_nativeCreateFromFile<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    config_path: JString<'local>,
+) -> jlong {
+    attach::init(&mut env);
+    logging::init(&mut env);
+    jfr::init(&mut env);
+    let path: String = match env.get_string(&config_path) {
+        Ok(p) => p.into(),
         Err(e) => {
-            let _ = env.throw_new("dev/opendata/common/OpenDataNativeException", e.to_string());
+            let _ = env.throw_new("java/lang/IllegalArgumentException", e.to_string());
+            return 0;
+        }
+    };
+    let settings = match configfile::load_log_settings(&path) {
+        Ok(s) => s,
+        Err(e) => {
+            let _ = env.throw_new("java/lang/IllegalArgumentException", e);
             return 0;
         }
     };
+    create_log_handle(&mut env, settings, &OpenProgress::default())
+}
 
-    // Create a SEPARATE runtime for SlateDB compaction/GC tasks.
-    // This prevents deadlock when the main runtime's threads are blocked in JNI calls
-    // while SlateDB's background tasks need to make progress.
-    let compaction_runtime = match tokio::runtime::Builder::new_multi_thread()
-        .enable_all()
-        .thread_name("opendata-compaction")
-        .build()
-    {
-        Ok(rt) => rt,
+/// Restores a backup into empty storage and opens a LogDb on it.
+///
+/// The backup is copied into the config's storage, after environment-variable
+/// overrides are applied, on a runtime of its own, and the log is then opened
+/// like `nativeCreate`. See `backup.rs`.
+///
+/// # Arguments
+/// * `config` - Java LogDbConfig object, whose storage must be empty
+/// * `backup` - Java `StorageConfig.SlateDb` of the backup
+///
+/// # Safety
+/// This is a JNI function - must be called from Java with valid JNIEnv.
+#[no_mangle]
+pub extern "system" fn Java_dev_opendata_LogDb_nativeRestore<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    config: JObject<'local>,
+    backup: JObject<'local>,
+) -> jlong {
+    attach::init(&mut env);
+    logging::init(&mut env);
+    jfr::init(&mut env);
+    let mut settings = match extract_log_settings(&mut env, &config) {
+        Ok(s) => s,
         Err(e) => {
-            let _ = env.throw_new("dev/opendata/common/OpenDataNativeException", e.to_string());
+            let _ = env.throw_new("java/lang/IllegalArgumentException", e);
+            return 0;
+        }
+    };
+    if let Err(e) = envconfig::apply_log(&mut settings) {
+        let _ = env.throw_new("java/lang/IllegalArgumentException", e);
+        return 0;
+    }
+    let backup = match extract_slatedb_config(&mut env, &backup) {
+        Ok(b) => b,
+        Err(e) => {
+            let _ = env.throw_new("java/lang/IllegalArgumentException", e);
             return 0;
         }
     };
 
-    // Open the LogDb using LogDbBuilder with separate compaction runtime
-    let result = runtime.block_on(async {
-        let storage_runtime =
-            StorageRuntime::new().with_compaction_runtime(compaction_runtime.handle().clone());
-        LogDbBuilder::new(config)
-            .with_storage_runtime(storage_runtime)
-            .build()
-            .await
-    });
+    let restored = checkpoints::slatedb_storage(&backup)
+        .and_then(|backup| {
+            let target = checkpoints::slatedb_storage(&settings.storage)?;
+            tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()
+                .map_err(|e| format!("Failed to create restore runtime: {}", e))?
+                .block_on(backup::restore(backup, target))
+        })
+        .and_then(|_| open_owned_log_handle(&settings, &OpenProgress::default()));
+    match restored {
+        Ok(handle) => handle,
+        Err(e) => {
+            let _ = env.throw_new("dev/opendata/common/OpenDataNativeException", e);
+            0
+        }
+    }
+}
 
-    match result {
-        Ok(log) => {
-            let handle = Box::new(LogHandle {
-                log,
-                runtime_handle: runtime.handle().clone(),
-                runtime: Some(runtime),
-                compaction_runtime: Some(compaction_runtime),
-            });
-            Box::into_raw(handle) as jlong
+/// Opens a LogDb without blocking the calling thread.
+///
+/// The config is extracted and environment-variable overrides are applied on
+/// the calling thread, so invalid settings still throw. The runtimes are then
+/// created and the log opened on a new `opendata-open` thread, which reports
+/// open phases to `progress`, if not null, and completes `future` with the
+/// handle as a `Long`, or exceptionally with an `OpenDataNativeException`.
+///
+/// # Safety
+/// This is a JNI function - must be called from Java with valid JNIEnv.
+#[no_mangle]
+pub extern "system" fn Java_dev_opendata_LogDb_nativeCreateAsync<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    config: JObject<'local>,
+    progress: JObject<'local>,
+    future: JObject<'local>,
+) {
+    attach::init(&mut env);
+    logging::init(&mut env);
+    jfr::init(&mut env);
+    let mut settings = match extract_log_settings(&mut env, &config) {
+        Ok(s) => s,
+        Err(e) => {
+            let _ = env.throw_new("java/lang/IllegalArgumentException", e);
+            return;
+        }
+    };
+    if let Err(e) = envconfig::apply_log(&mut settings) {
+        let _ = env.throw_new("java/lang/IllegalArgumentException", e);
+        return;
+    }
+    let progress = match OpenProgress::new(&env, &progress) {
+        Ok(p) => p,
+        Err(e) => {
+            let _ = env.throw_new("dev/opendata/common/OpenDataNativeException", e);
+            return;
         }
+    };
+    let future = match env.new_global_ref(&future) {
+        Ok(future) => future,
         Err(e) => {
             let _ = env.throw_new("dev/opendata/common/OpenDataNativeException", e.to_string());
+            return;
+        }
+    };
+
+    let spawned = std::thread::Builder::new()
+        .name("opendata-open".into())
+        .spawn(move || {
+            let opened = attach::with_env(|env| {
+                let result = open_owned_log_handle(&settings, &progress).and_then(|handle| {
+                    env.new_object("java/lang/Long", "(J)V", &[JValue::Long(handle)])
+                        .map_err(|e| e.to_string())
+                });
+                if complete_java_future(env, &future, result).is_err() {
+                    let _ = env.exception_clear();
+                }
+            });
+            if opened.is_none() {
+                logging::warn(LOG_TARGET, "Failed to attach the open thread to the JVM");
+            }
+            attach::detach_current_thread();
+        });
+    if let Err(e) = spawned {
+        let _ = env.throw_new("dev/opendata/common/OpenDataNativeException", e.to_string());
+    }
+}
+
+/// Opens a LogDb on dedicated runtimes, returning its handle or 0 after throwing.
+///
+/// Environment-variable overrides are applied to the settings first.
+fn create_log_handle(
+    env: &mut JNIEnv<'_>,
+    mut settings: LogSettings,
+    progress: &OpenProgress,
+) -> jlong {
+    if let Err(e) = envconfig::apply_log(&mut settings) {
+        let _ = env.throw_new("java/lang/IllegalArgumentException", e);
+        return 0;
+    }
+    match open_owned_log_handle(&settings, progress) {
+        Ok(handle) => handle,
+        Err(e) => {
+            let _ = env.throw_new("dev/opendata/common/OpenDataNativeException", e);
             0
         }
     }
 }
 
-// =============================================================================
-// Config Extraction Helpers
-// =============================================================================
+/// Opens a LogDb on runtimes owned by its handle, returning the handle.
+fn open_owned_log_handle(settings: &LogSettings, progress: &OpenProgress) -> Result<jlong, String> {
+    // Create a dedicated runtime for this LogDb instance (for user operations)
+    let runtime = settings.runtime.build(&settings.tags)?;
 
-/// Extracts StorageConfig from a Java LogDbConfig object.
-fn extract_storage_config(
-    env: &mut JNIEnv<'_>,
-    config: &JObject<'_>,
-) -> Result<StorageConfig, String> {
-    // Get the storage field from LogDbConfig
-    let storage_obj = env
-        .call_method(
-            config,
-            "storage",
-            "()Ldev/opendata/common/StorageConfig;",
-            &[],
-        )
-        .map_err(|e| format!("Failed to get storage: {}", e))?
-        .l()
-        .map_err(|e| format!("Failed to get storage object: {}", e))?;
+    // Create a SEPARATE runtime for SlateDB compaction/GC tasks.
+    // This prevents deadlock when the main runtime's threads are blocked in JNI calls
+    // while SlateDB's background tasks need to make progress.
+    let compaction_runtime = build_compaction_runtime(settings)?;
+    let compaction_handle = compaction_runtime
+        .as_ref()
+        .map_or_else(|| runtime.handle().clone(), |rt| rt.handle().clone());
+    progress.report(OpenPhase::RuntimesStarted);
+
+    let opened = open_log_handle(
+        settings,
+        settings.storage.clone(),
+        Arc::clone(&settings.tags),
+        runtime.handle(),
+        &compaction_handle,
+        progress,
+    );
+    let mut handle = match opened {
+        Ok(handle) => handle,
+        Err(e) => {
+            // A timed-out open may leave storage tasks stuck on the runtimes
+            runtime.shutdown_background();
+            if let Some(compaction_runtime) = compaction_runtime {
+                compaction_runtime.shutdown_background();
+            }
+            return Err(e);
+        }
+    };
+    handle.runtime = Some(runtime);
+    handle.compaction_runtime = compaction_runtime;
+    progress.report(OpenPhase::Ready);
+    Ok(publish_log_handle(handle))
+}
 
-    // Check which type of StorageConfig it is using instanceof
-    let in_memory_class = env
-        .find_class("dev/opendata/common/StorageConfig$InMemory")
-        .map_err(|e| format!("Failed to find InMemory class: {}", e))?;
+/// Moves an opened handle behind its pointer and starts its StatsD export,
+/// which reads the handle in place.
+fn publish_log_handle(handle: LogHandle) -> jlong {
+    let raw = Box::into_raw(Box::new(handle)) as jlong;
+    let log_handle = unsafe { &*(raw as *const LogHandle) };
+    if let Some(statsd) = &log_handle.statsd {
+        // Stopped by take_log_handle before the handle is freed
+        let started = statsd.start(move || unsafe { &*(raw as *const LogHandle) }.exported_stats());
+        if let Err(e) = started {
+            logging::warn(
+                statsd::TARGET,
+                &log_handle
+                    .tags
+                    .log_line(&format!("Failed to start the StatsD export: {}", e)),
+            );
+        }
+    }
+    raw
+}
 
-    let slatedb_class = env
-        .find_class("dev/opendata/common/StorageConfig$SlateDb")
-        .map_err(|e| format!("Failed to find SlateDb class: {}", e))?;
+/// Takes back ownership of a handle, once its StatsD export stopped.
+fn take_log_handle(handle: jlong) -> Box<LogHandle> {
+    let log_handle = unsafe { &*(handle as *const LogHandle) };
+    if let Some(statsd) = &log_handle.statsd {
+        statsd.stop();
+    }
+    unsafe { Box::from_raw(handle as *mut LogHandle) }
+}
 
-    if env
-        .is_instance_of(&storage_obj, &in_memory_class)
-        .map_err(|e| format!("instanceof check failed: {}", e))?
-    {
-        Ok(StorageConfig::InMemory)
-    } else if env
-        .is_instance_of(&storage_obj, &slatedb_class)
-        .map_err(|e| format!("instanceof check failed: {}", e))?
-    {
-        extract_slatedb_config(env, &storage_obj)
-    } else {
-        Err("Unknown StorageConfig type".to_string())
+/// Builds the compaction runtime, or returns None if it shares the runtime
+/// running calls.
+fn build_compaction_runtime(settings: &LogSettings) -> Result<Option<Runtime>, String> {
+    if !settings.compaction_runtime.shared {
+        return settings.compaction_runtime.build(&settings.tags).map(Some);
     }
+    if !matches!(settings.storage, StorageConfig::InMemory) {
+        return Err("The compaction runtime can only be shared with in-memory storage".to_string());
+    }
+    Ok(None)
 }
 
-/// Extracts SlateDbStorageConfig from a Java StorageConfig.SlateDb record.
-fn extract_slatedb_config(
-    env: &mut JNIEnv<'_>,
-    slatedb_obj: &JObject<'_>,
-) -> Result<StorageConfig, String> {
-    // Get path field
-    let path_obj = env
-        .call_method(slatedb_obj, "path", "()Ljava/lang/String;", &[])
-        .map_err(|e| format!("Failed to get path: {}", e))?
+/// Describes storage for error messages, naming its path and object store.
+fn describe_storage(storage: &StorageConfig) -> String {
+    let StorageConfig::SlateDb(slatedb) = storage else {
+        return "in-memory storage".to_string();
+    };
+    match &slatedb.object_store {
+        ObjectStoreConfig::InMemory => format!("SlateDB storage {} in memory", slatedb.path),
+        ObjectStoreConfig::Aws(aws) => format!(
+            "SlateDB storage {} in bucket {} ({})",
+            slatedb.path, aws.bucket, aws.region
+        ),
+        ObjectStoreConfig::Local(local) => {
+            format!("SlateDB storage {} under {}", slatedb.path, local.path)
+        }
+    }
+}
+
+/// Opens a LogDb using LogDbBuilder with a separate compaction runtime.
+///
+/// The log builds its object store from the storage config unless
+/// `object_store` is given.
+async fn open_log(
+    storage: StorageConfig,
+    object_store: Option<Arc<dyn ObjectStore>>,
+    compaction_runtime: &Handle,
+) -> Result<LogDb, log::Error> {
+    let config = Config {
+        storage,
+        ..Config::default()
+    };
+    let mut storage_runtime =
+        StorageRuntime::new().with_compaction_runtime(compaction_runtime.clone());
+    if let Some(object_store) = object_store {
+        storage_runtime = storage_runtime.with_object_store(object_store);
+    }
+    LogDbBuilder::new(config)
+        .with_storage_runtime(storage_runtime)
+        .build()
+        .await
+}
+
+/// Settings extracted from a Java LogDbConfig.
+struct LogSettings {
+    storage: StorageConfig,
+    profiling: bool,
+    watchdog: Option<WatchdogSettings>,
+    dedup_window: usize,
+    faults: Option<FaultSettings>,
+    /// Entry payload size when appends and scans bypass storage
+    noop_payload_size: Option<usize>,
+    /// Number of bridge threads; 0 waits in `block_on` on the calling thread
+    bridge_threads: usize,
+    /// Write coalescing window, if configured
+    coalesce: Option<CoalesceSettings>,
+    /// Bound on submitted appends, if configured
+    write_queue: Option<WriteQueueSettings>,
+    rate_limits: RateLimitSettings,
+    /// Background appends and scans running at once
+    background_concurrency: usize,
+    /// Keys with per-key statistics; 0 disables them
+    key_stats_capacity: usize,
+    /// Byte budget of the tail cache; 0 disables it
+    tail_cache_bytes: usize,
+    /// Local disk cache of SlateDB storage, if configured
+    disk_cache: Option<DiskCacheSettings>,
+    /// Garbage collection schedule of SlateDB storage, if configured
+    garbage_collector: Option<GarbageCollectorSettings>,
+    /// Write path settings of SlateDB storage
+    write_buffer: WriteBufferSettings,
+    /// Duration from which appends and scans are logged; zero disables logging
+    slow_operation_threshold: std::time::Duration,
+    /// How long opening the storage may take; zero waits indefinitely
+    open_timeout: std::time::Duration,
+    /// Automatic reopening after repeated storage failures, if configured
+    reopen: Option<ReopenSettings>,
+    /// Cap on native memory held by the handle, if configured
+    memory_limit: Option<MemoryLimitSettings>,
+    /// Limit on blocking calls running at once, if configured
+    call_limit: Option<CallLimitSettings>,
+    /// Thread names and cores of the runtime running calls
+    runtime: RuntimeSettings,
+    /// Thread names and cores of the runtime running SlateDB compaction
+    compaction_runtime: RuntimeSettings,
+    /// Payload size above which appended values are split into chunks; 0
+    /// leaves values whole
+    chunk_threshold: usize,
+    /// Whether appended values are copied with `GetPrimitiveArrayCritical`
+    critical_copy: bool,
+    /// Labels attached to the handle's stats, log lines and JFR events
+    tags: Arc<Tags>,
+    /// Kind of clock stamping appended records
+    clock: ClockSource,
+    /// Whether appended values record their receive time
+    receive_timestamps: bool,
+    /// StatsD agent the handle's stats are sent to, if configured
+    statsd: Option<StatsdSettings>,
+}
+
+/// Phases of an open, mirroring the Java `OpenPhase` enum.
+///
+/// The declaration order is part of the native contract.
+#[derive(Debug, Clone, Copy)]
+enum OpenPhase {
+    /// The handle's runtimes have started and the storage is being opened
+    RuntimesStarted,
+    /// The storage has loaded its manifest and replayed its WAL
+    StorageOpened,
+    /// The handle is ready for appends and scans
+    Ready,
+}
+
+/// Local references a reported open phase needs.
+const PROGRESS_FRAME_CAPACITY: i32 = 2;
+
+/// Reports open phases to a Java `IntConsumer`, if the caller passed one.
+#[derive(Default)]
+struct OpenProgress {
+    listener: Option<GlobalRef>,
+}
+
+impl OpenProgress {
+    /// Wraps a listener, which may be null.
+    fn new(env: &JNIEnv<'_>, listener: &JObject<'_>) -> Result<Self, String> {
+        if listener.is_null() {
+            return Ok(Self::default());
+        }
+        let listener = env.new_global_ref(listener).map_err(|e| e.to_string())?;
+        Ok(Self {
+            listener: Some(listener),
+        })
+    }
+
+    /// Reports a phase, ignoring exceptions thrown by the listener.
+    fn report(&self, phase: OpenPhase) {
+        let Some(listener) = &self.listener else {
+            return;
+        };
+        attach::call(PROGRESS_FRAME_CAPACITY, |env| {
+            env.call_method(listener, "accept", "(I)V", &[JValue::Int(phase as i32)])
+        });
+    }
+}
+
+/// Opens a LogDb on the given runtimes and wraps it in a handle.
+///
+/// The returned handle does not own its runtimes; callers that create
+/// dedicated runtimes store them in the handle afterwards.
+fn open_log_handle(
+    settings: &LogSettings,
+    mut storage: StorageConfig,
+    tags: Arc<Tags>,
+    runtime: &Handle,
+    compaction_runtime: &Handle,
+    progress: &OpenProgress,
+) -> Result<LogHandle, String> {
+    let settings_file = settings_overlay(
+        settings.disk_cache.as_ref(),
+        settings.garbage_collector,
+        &settings.write_buffer,
+    )
+    .apply(&mut storage)?;
+    let watchdog =
+        start_watchdog(settings.watchdog, "opendata-log", runtime).map_err(|e| e.to_string())?;
+    let bridge = start_bridge(settings.bridge_threads, runtime)?;
+    let statsd = settings
+        .statsd
+        .as_ref()
+        .map(|s| Statsd::connect(s, &tags))
+        .transpose()
+        .map_err(|e| format!("Failed to set up the StatsD export: {}", e))?;
+
+    let faults = settings.faults.map(|s| Arc::new(FaultInjector::new(s)));
+    let object_store = faults
+        .as_ref()
+        .map(|faults| fault::faulty_object_store(&storage, faults))
+        .transpose()?;
+
+    let description = describe_storage(&storage);
+    let reopen_storage = storage.clone();
+    let open = open_log(storage, object_store.clone(), compaction_runtime);
+    let log = if settings.open_timeout.is_zero() {
+        runtime.block_on(open)
+    } else {
+        runtime
+            .block_on(tokio::time::timeout(settings.open_timeout, open))
+            .map_err(|_| {
+                format!(
+                    "Timed out after {} ms opening {}; check that the object store is \
+                     reachable and its credentials are valid",
+                    settings.open_timeout.as_millis(),
+                    description
+                )
+            })?
+    }
+    .map_err(|e| e.to_string())?;
+    progress.report(OpenPhase::StorageOpened);
+    let tail_cache = (settings.tail_cache_bytes > 0)
+        .then(|| Arc::new(TailCache::new(settings.tail_cache_bytes)));
+    let key_stats = (settings.key_stats_capacity > 0)
+        .then(|| Arc::new(KeyStats::new(settings.key_stats_capacity)));
+    let watches = Arc::new(Watches::new(key_stats.clone()));
+    let completions: Arc<CompletionQueue> = Arc::default();
+    let memory = Arc::new(HandleMemory::new(
+        tail_cache.clone(),
+        Arc::clone(&completions),
+        settings.memory_limit,
+    ));
+    let log = Arc::new(ReopeningLog::new(
+        log,
+        settings.reopen,
+        reopen_storage.clone(),
+        object_store,
+        runtime,
+        compaction_runtime,
+        Arc::clone(&tags),
+    ));
+    let latency = Arc::new(LatencyRecorder::new());
+    let drain: Drain = {
+        let log = Arc::downgrade(&log);
+        let runtime = runtime.clone();
+        let completions = Arc::clone(&completions);
+        let tags = Arc::clone(&tags);
+        Arc::new(move |deadline| drain_log(&log, &runtime, &completions, &tags, deadline))
+    };
+    let registration = handles::register(
+        HandleKind::Log,
+        Arc::clone(&latency),
+        Arc::clone(&tags),
+        Some(drain),
+    );
+    let coalescer = settings
+        .coalesce
+        .map(|s| Arc::new(Coalescer::new(s, tail_cache.clone(), Arc::clone(&watches))));
+
+    Ok(LogHandle {
+        log,
+        storage: reopen_storage,
+        runtime_handle: runtime.clone(),
+        runtime: None,
+        compaction_runtime: None,
+        latency,
+        profiler: Profiler::new(settings.profiling),
+        blocked_callers: BlockedCallers::default(),
+        measurement: Measurement::new(),
+        intervals: Intervals::new(),
+        completions,
+        write_queue: settings.write_queue.map(|s| Arc::new(WriteQueue::new(s))),
+        call_limiter: settings.call_limit.map(CallLimiter::new),
+        rate_limiter: (!settings.rate_limits.is_unlimited())
+            .then(|| Arc::new(RateLimiter::new(settings.rate_limits))),
+        priorities: PriorityGate::new(settings.background_concurrency),
+        tail_cache,
+        disk_cache: settings.disk_cache.as_ref().map(DiskCache::new),
+        served: Arc::new(ServedBytes::new(key_stats.clone())),
+        skew: Arc::default(),
+        watches,
+        key_stats,
+        slow_log: Arc::new(SlowLog::new(
+            settings.slow_operation_threshold,
+            Arc::clone(&tags),
+        )),
+        memory,
+        created_at: Instant::now(),
+        registration,
+        watchdog,
+        bridge,
+        faults,
+        noop: settings
+            .noop_payload_size
+            .map(|size| Arc::new(NoOpLog::new(size))),
+        coalescer,
+        append_lock: Arc::new(tokio::sync::RwLock::new(())),
+        dedup: DedupWindows::new(settings.dedup_window),
+        offsets: OffsetCache::default(),
+        flush_task: Mutex::new(None),
+        groups: GroupViews::default(),
+        chunk_threshold: settings.chunk_threshold,
+        critical_copy: settings.critical_copy,
+        tags,
+        clock: Arc::new(Clock::start(settings.clock)),
+        receive_timestamps: settings.receive_timestamps,
+        statsd,
+        _settings_file: settings_file,
+    })
+}
+
+// =============================================================================
+// Config Extraction Helpers
+// =============================================================================
+
+/// Extracts the settings shared by LogDb and LogDbManager from a Java LogDbConfig.
+fn extract_log_settings(env: &mut JNIEnv<'_>, config: &JObject<'_>) -> Result<LogSettings, String> {
+    let noop_payload_size = extract_noop_payload_size(env, config)?;
+    let storage = match noop_payload_size {
+        // Offsets, groups and conditional appends still need a real log
+        Some(_) => StorageConfig::InMemory,
+        None => extract_storage_config(env, config)?,
+    };
+    Ok(LogSettings {
+        storage,
+        profiling: extract_bool(env, config, "profiling")?,
+        watchdog: extract_watchdog_settings(env, config)?,
+        dedup_window: extract_int(env, config, "dedupWindow")? as usize,
+        faults: extract_fault_settings(env, config)?,
+        noop_payload_size,
+        bridge_threads: extract_int(env, config, "bridgeThreads")? as usize,
+        coalesce: extract_coalesce_settings(env, config)?,
+        write_queue: extract_write_queue_settings(env, config)?,
+        rate_limits: extract_rate_limit_settings(env, config)?,
+        background_concurrency: extract_int(env, config, "backgroundConcurrency")?.max(1) as usize,
+        key_stats_capacity: extract_int(env, config, "keyStatsCapacity")?.max(0) as usize,
+        tail_cache_bytes: extract_long(env, config, "tailCacheBytes")? as usize,
+        disk_cache: extract_disk_cache_settings(env, config)?,
+        garbage_collector: extract_garbage_collector_settings(env, config)?,
+        write_buffer: extract_write_buffer_settings(env, config)?,
+        slow_operation_threshold: extract_slow_operation_threshold(env, config)?,
+        open_timeout: std::time::Duration::from_millis(
+            extract_long(env, config, "openTimeoutMs")?.max(0) as u64,
+        ),
+        reopen: extract_reopen_settings(env, config)?,
+        memory_limit: extract_memory_limit_settings(env, config)?,
+        call_limit: extract_call_limit_settings(env, config)?,
+        runtime: extract_runtime_settings(env, config, "runtime")?,
+        compaction_runtime: extract_runtime_settings(env, config, "compactionRuntime")?,
+        chunk_threshold: extract_long(env, config, "chunkThresholdBytes")? as usize,
+        critical_copy: extract_bool(env, config, "criticalValueCopy")?,
+        tags: Arc::new(extract_tags(env, config)?),
+        clock: extract_clock_source(env, config)?,
+        receive_timestamps: extract_bool(env, config, "receiveTimestamps")?,
+        statsd: extract_statsd_settings(env, config)?,
+    })
+}
+
+/// Extracts the tags of a Java LogDbConfig, a `Map<String, String>`.
+fn extract_tags(env: &mut JNIEnv<'_>, config: &JObject<'_>) -> Result<Tags, String> {
+    let map = env
+        .call_method(config, "tags", "()Ljava/util/Map;", &[])
+        .map_err(|e| format!("Failed to get tags: {}", e))?
+        .l()
+        .map_err(|e| format!("Failed to get tags object: {}", e))?;
+    let entries = env
+        .call_method(&map, "entrySet", "()Ljava/util/Set;", &[])
+        .map_err(|e| format!("Failed to get tag entries: {}", e))?
+        .l()
+        .map_err(|e| format!("Failed to get tag entries object: {}", e))?;
+    let iter = env
+        .call_method(&entries, "iterator", "()Ljava/util/Iterator;", &[])
+        .map_err(|e| format!("Failed to iterate tags: {}", e))?
+        .l()
+        .map_err(|e| format!("Failed to get tag iterator: {}", e))?;
+
+    let mut pairs = Vec::new();
+    while env
+        .call_method(&iter, "hasNext", "()Z", &[])
+        .map_err(|e| format!("Failed to iterate tags: {}", e))?
+        .z()
+        .map_err(|e| format!("Failed to iterate tags: {}", e))?
+    {
+        let entry = env
+            .call_method(&iter, "next", "()Ljava/lang/Object;", &[])
+            .map_err(|e| format!("Failed to get tag entry: {}", e))?
+            .l()
+            .map_err(|e| format!("Failed to get tag entry object: {}", e))?;
+        let mut pair = [String::new(), String::new()];
+        for (slot, getter) in pair.iter_mut().zip(["getKey", "getValue"]) {
+            let text: JString = env
+                .call_method(&entry, getter, "()Ljava/lang/Object;", &[])
+                .map_err(|e| format!("Failed to get tag: {}", e))?
+                .l()
+                .map_err(|e| format!("Failed to get tag object: {}", e))?
+                .into();
+            *slot = env
+                .get_string(&text)
+                .map_err(|e| format!("Failed to read tag: {}", e))?
+                .into();
+            let _ = env.delete_local_ref(text);
+        }
+        let _ = env.delete_local_ref(entry);
+        let [name, value] = pair;
+        pairs.push((name, value));
+    }
+    Ok(Tags::new(pairs))
+}
+
+/// Extracts the clock source of a Java LogDbConfig.
+fn extract_clock_source(env: &mut JNIEnv<'_>, config: &JObject<'_>) -> Result<ClockSource, String> {
+    let clock_obj = env
+        .call_method(config, "clock", "()Ldev/opendata/ClockSource;", &[])
+        .map_err(|e| format!("Failed to get clock: {}", e))?
+        .l()
+        .map_err(|e| format!("Failed to get clock object: {}", e))?;
+    let ordinal = extract_int(env, &clock_obj, "ordinal")?;
+    ClockSource::from_ordinal(ordinal)
+        .ok_or_else(|| format!("Unknown clock source ordinal: {}", ordinal))
+}
+
+/// Returns the entry payload size if a Java LogDbConfig uses `StorageConfig.NoOp`.
+fn extract_noop_payload_size(
+    env: &mut JNIEnv<'_>,
+    config: &JObject<'_>,
+) -> Result<Option<usize>, String> {
+    let storage_obj = env
+        .call_method(
+            config,
+            "storage",
+            "()Ldev/opendata/common/StorageConfig;",
+            &[],
+        )
+        .map_err(|e| format!("Failed to get storage: {}", e))?
+        .l()
+        .map_err(|e| format!("Failed to get storage object: {}", e))?;
+
+    let noop_class = env
+        .find_class("dev/opendata/common/StorageConfig$NoOp")
+        .map_err(|e| format!("Failed to find NoOp class: {}", e))?;
+
+    if !env
+        .is_instance_of(&storage_obj, &noop_class)
+        .map_err(|e| format!("instanceof check failed: {}", e))?
+    {
+        return Ok(None);
+    }
+    Ok(Some(extract_int(env, &storage_obj, "payloadSize")? as usize))
+}
+
+/// Extracts StorageConfig from a Java LogDbConfig object.
+fn extract_storage_config(
+    env: &mut JNIEnv<'_>,
+    config: &JObject<'_>,
+) -> Result<StorageConfig, String> {
+    // Get the storage field from LogDbConfig
+    let storage_obj = env
+        .call_method(
+            config,
+            "storage",
+            "()Ldev/opendata/common/StorageConfig;",
+            &[],
+        )
+        .map_err(|e| format!("Failed to get storage: {}", e))?
+        .l()
+        .map_err(|e| format!("Failed to get storage object: {}", e))?;
+
+    // Check which type of StorageConfig it is using instanceof
+    let in_memory_class = env
+        .find_class("dev/opendata/common/StorageConfig$InMemory")
+        .map_err(|e| format!("Failed to find InMemory class: {}", e))?;
+
+    let slatedb_class = env
+        .find_class("dev/opendata/common/StorageConfig$SlateDb")
+        .map_err(|e| format!("Failed to find SlateDb class: {}", e))?;
+
+    if env
+        .is_instance_of(&storage_obj, &in_memory_class)
+        .map_err(|e| format!("instanceof check failed: {}", e))?
+    {
+        Ok(StorageConfig::InMemory)
+    } else if env
+        .is_instance_of(&storage_obj, &slatedb_class)
+        .map_err(|e| format!("instanceof check failed: {}", e))?
+    {
+        extract_slatedb_config(env, &storage_obj)
+    } else {
+        Err("Unknown StorageConfig type".to_string())
+    }
+}
+
+/// Extracts SlateDbStorageConfig from a Java StorageConfig.SlateDb record.
+fn extract_slatedb_config(
+    env: &mut JNIEnv<'_>,
+    slatedb_obj: &JObject<'_>,
+) -> Result<StorageConfig, String> {
+    // Get path field
+    let path_obj = env
+        .call_method(slatedb_obj, "path", "()Ljava/lang/String;", &[])
+        .map_err(|e| format!("Failed to get path: {}", e))?
         .l()
         .map_err(|e| format!("Failed to get path object: {}", e))?;
     let path: String = env
@@ -274,6 +1472,169 @@ fn extract_slatedb_config(
     }))
 }
 
+/// Returns a Java config's storage if it is a `StorageConfig.SlateDb`.
+///
+/// Works on any config with a `storage()` component, LogDbConfig and
+/// LogDbReaderConfig alike.
+fn extract_slatedb_storage<'local>(
+    env: &mut JNIEnv<'local>,
+    config: &JObject<'_>,
+) -> Result<Option<JObject<'local>>, String> {
+    let storage_obj = env
+        .call_method(
+            config,
+            "storage",
+            "()Ldev/opendata/common/StorageConfig;",
+            &[],
+        )
+        .map_err(|e| format!("Failed to get storage: {}", e))?
+        .l()
+        .map_err(|e| format!("Failed to get storage object: {}", e))?;
+    let slatedb_class = env
+        .find_class("dev/opendata/common/StorageConfig$SlateDb")
+        .map_err(|e| format!("Failed to find SlateDb class: {}", e))?;
+    if !env
+        .is_instance_of(&storage_obj, &slatedb_class)
+        .map_err(|e| format!("instanceof check failed: {}", e))?
+    {
+        return Ok(None);
+    }
+    Ok(Some(storage_obj))
+}
+
+/// Extracts the disk cache of a Java config's SlateDb storage, if one is enabled.
+fn extract_disk_cache_settings(
+    env: &mut JNIEnv<'_>,
+    config: &JObject<'_>,
+) -> Result<Option<DiskCacheSettings>, String> {
+    let Some(storage_obj) = extract_slatedb_storage(env, config)? else {
+        return Ok(None);
+    };
+    let disk_cache_obj = env
+        .call_method(
+            &storage_obj,
+            "diskCache",
+            "()Ldev/opendata/common/DiskCacheConfig;",
+            &[],
+        )
+        .map_err(|e| format!("Failed to get diskCache: {}", e))?
+        .l()
+        .map_err(|e| format!("Failed to get diskCache object: {}", e))?;
+    let path_obj = env
+        .call_method(&disk_cache_obj, "path", "()Ljava/lang/String;", &[])
+        .map_err(|e| format!("Failed to get diskCache path: {}", e))?
+        .l()
+        .map_err(|e| format!("Failed to get diskCache path object: {}", e))?;
+    if path_obj.is_null() {
+        return Ok(None);
+    }
+    let root: String = env
+        .get_string((&path_obj).into())
+        .map_err(|e| format!("Failed to convert diskCache path: {}", e))?
+        .into();
+
+    Ok(Some(DiskCacheSettings {
+        root: root.into(),
+        max_bytes: extract_long(env, &disk_cache_obj, "maxBytes")? as u64,
+    }))
+}
+
+/// Extracts the garbage collection schedule of a LogDbConfig's SlateDb
+/// storage, if one is set.
+fn extract_garbage_collector_settings(
+    env: &mut JNIEnv<'_>,
+    config: &JObject<'_>,
+) -> Result<Option<GarbageCollectorSettings>, String> {
+    let Some(storage_obj) = extract_slatedb_storage(env, config)? else {
+        return Ok(None);
+    };
+    let gc_obj = env
+        .call_method(
+            &storage_obj,
+            "garbageCollector",
+            "()Ldev/opendata/common/GarbageCollectorConfig;",
+            &[],
+        )
+        .map_err(|e| format!("Failed to get garbageCollector: {}", e))?
+        .l()
+        .map_err(|e| format!("Failed to get garbageCollector object: {}", e))?;
+    let interval_ms = extract_long(env, &gc_obj, "intervalMs")?;
+    if interval_ms <= 0 {
+        return Ok(None);
+    }
+    Ok(Some(GarbageCollectorSettings {
+        interval: std::time::Duration::from_millis(interval_ms as u64),
+        min_age: std::time::Duration::from_millis(extract_long(env, &gc_obj, "minAgeMs")? as u64),
+    }))
+}
+
+/// Extracts the write path settings of a LogDbConfig's SlateDb storage.
+fn extract_write_buffer_settings(
+    env: &mut JNIEnv<'_>,
+    config: &JObject<'_>,
+) -> Result<WriteBufferSettings, String> {
+    let Some(storage_obj) = extract_slatedb_storage(env, config)? else {
+        return Ok(WriteBufferSettings::default());
+    };
+    let write_buffer_obj = env
+        .call_method(
+            &storage_obj,
+            "writeBuffer",
+            "()Ldev/opendata/common/WriteBufferConfig;",
+            &[],
+        )
+        .map_err(|e| format!("Failed to get writeBuffer: {}", e))?
+        .l()
+        .map_err(|e| format!("Failed to get writeBuffer object: {}", e))?;
+    let positive = |value: i64| (value > 0).then_some(value as u64);
+    let max_unflushed_bytes = positive(extract_long(env, &write_buffer_obj, "writeBufferBytes")?);
+    let l0_sst_size_bytes = positive(extract_long(env, &write_buffer_obj, "memtableBytes")?);
+    let flush_interval = positive(extract_long(env, &write_buffer_obj, "flushIntervalMs")?)
+        .map(std::time::Duration::from_millis);
+
+    let wal_obj = env
+        .call_method(
+            &write_buffer_obj,
+            "wal",
+            "()Ldev/opendata/common/WriteBufferConfig$Wal;",
+            &[],
+        )
+        .map_err(|e| format!("Failed to get wal: {}", e))?
+        .l()
+        .map_err(|e| format!("Failed to get wal object: {}", e))?;
+    // Ordinals of the Java enum: DEFAULT, ENABLED, DISABLED
+    let wal_enabled = match extract_int(env, &wal_obj, "ordinal")? {
+        0 => None,
+        1 => Some(true),
+        2 => Some(false),
+        other => return Err(format!("Unknown WAL ordinal: {}", other)),
+    };
+
+    Ok(WriteBufferSettings {
+        max_unflushed_bytes,
+        l0_sst_size_bytes,
+        flush_interval,
+        wal_enabled,
+    })
+}
+
+/// Collects the typed SlateDB settings to write over the settings file.
+fn settings_overlay(
+    disk_cache: Option<&DiskCacheSettings>,
+    garbage_collector: Option<GarbageCollectorSettings>,
+    write_buffer: &WriteBufferSettings,
+) -> SettingsOverlay {
+    let mut overlay = SettingsOverlay::default();
+    if let Some(disk_cache) = disk_cache {
+        disk_cache.overlay(&mut overlay);
+    }
+    if let Some(garbage_collector) = garbage_collector {
+        garbage_collector.overlay(&mut overlay);
+    }
+    write_buffer.overlay(&mut overlay);
+    overlay
+}
+
 /// Extracts ObjectStoreConfig from a Java ObjectStoreConfig object.
 fn extract_object_store_config(
     env: &mut JNIEnv<'_>,
@@ -351,6 +1712,10 @@ fn extract_reader_storage_config(
     env: &mut JNIEnv<'_>,
     config: &JObject<'_>,
 ) -> Result<StorageConfig, String> {
+    if extract_noop_payload_size(env, config)?.is_some() {
+        return Err("NoOp storage is not supported by LogDbReader".to_string());
+    }
+
     // Get the storage field from LogDbReaderConfig
     let storage_obj = env
         .call_method(
@@ -413,137 +1778,8453 @@ fn extract_refresh_interval(
     Ok(Some(std::time::Duration::from_millis(interval_ms as u64)))
 }
 
-/// Appends a batch of records to the log with timestamp headers.
-///
-/// Each value is stored as: `[8-byte timestamp (big-endian i64)] + [original payload]`
-/// The timestamp is read from each Java Record object (captured at submission time).
-///
-/// # Arguments
-/// * `handle` - Native LogDb pointer
-/// * `records` - Array of Java Record objects (each with key, value, timestampMs)
-///
-/// # Returns
-/// AppendResult jobject with start_sequence and timestamp of first record
-///
-/// # Safety
-/// JNI function - handle must be a valid pointer returned by nativeCreate.
-#[no_mangle]
-#[allow(clippy::not_unsafe_ptr_arg_deref)]
-pub extern "system" fn Java_dev_opendata_LogDb_nativeAppend<'local>(
-    mut env: JNIEnv<'local>,
-    _class: JClass<'local>,
-    handle: jlong,
-    records: jobjectArray,
-) -> jobject {
-    if handle == 0 {
-        let _ = env.throw_new("java/lang/NullPointerException", "LogDb handle is null");
-        return std::ptr::null_mut();
-    }
+/// Reads a boolean record component from a Java config object.
+fn extract_bool(env: &mut JNIEnv<'_>, obj: &JObject<'_>, name: &str) -> Result<bool, String> {
+    env.call_method(obj, name, "()Z", &[])
+        .map_err(|e| format!("Failed to get {}: {}", name, e))?
+        .z()
+        .map_err(|e| format!("Failed to get {} value: {}", name, e))
+}
 
-    let log_handle = unsafe { &*(handle as *const LogHandle) };
+/// Reads an int record component from a Java config object.
+fn extract_int(env: &mut JNIEnv<'_>, obj: &JObject<'_>, name: &str) -> Result<i32, String> {
+    env.call_method(obj, name, "()I", &[])
+        .map_err(|e| format!("Failed to get {}: {}", name, e))?
+        .i()
+        .map_err(|e| format!("Failed to get {} value: {}", name, e))
+}
 
-    // Convert Java Record[] to Rust Vec<Record>
-    let records_array = unsafe { JObjectArray::from_raw(records) };
-    let len = match env.get_array_length(&records_array) {
-        Ok(l) => l as usize,
-        Err(e) => {
-            let _ = env.throw_new("dev/opendata/common/OpenDataNativeException", e.to_string());
-            return std::ptr::null_mut();
-        }
-    };
+/// Reads a long record component from a Java config object.
+fn extract_long(env: &mut JNIEnv<'_>, obj: &JObject<'_>, name: &str) -> Result<i64, String> {
+    env.call_method(obj, name, "()J", &[])
+        .map_err(|e| format!("Failed to get {}: {}", name, e))?
+        .j()
+        .map_err(|e| format!("Failed to get {} value: {}", name, e))
+}
 
-    if len == 0 {
-        let _ = env.throw_new(
-            "java/lang/IllegalArgumentException",
-            "Records array is empty",
-        );
-        return std::ptr::null_mut();
-    }
+/// Reads a double record component from a Java config object.
+fn extract_double(env: &mut JNIEnv<'_>, obj: &JObject<'_>, name: &str) -> Result<f64, String> {
+    env.call_method(obj, name, "()D", &[])
+        .map_err(|e| format!("Failed to get {}: {}", name, e))?
+        .d()
+        .map_err(|e| format!("Failed to get {} value: {}", name, e))
+}
 
-    let mut rust_records = Vec::with_capacity(len);
-    let mut first_timestamp_ms: i64 = 0;
+/// Extracts the optional auto-commit group and interval from a Java LogDbReaderConfig.
+fn extract_auto_commit(
+    env: &mut JNIEnv<'_>,
+    config: &JObject<'_>,
+) -> Result<Option<(String, std::time::Duration)>, String> {
+    let auto_commit_obj = env
+        .call_method(
+            config,
+            "autoCommit",
+            "()Ldev/opendata/AutoCommitConfig;",
+            &[],
+        )
+        .map_err(|e| format!("Failed to get autoCommit: {}", e))?
+        .l()
+        .map_err(|e| format!("Failed to get autoCommit object: {}", e))?;
 
-    for i in 0..len {
-        let record_obj = match env.get_object_array_element(&records_array, i as i32) {
-            Ok(obj) => obj,
+    if auto_commit_obj.is_null() {
+        return Ok(None);
+    }
+
+    let group_obj: JString = env
+        .call_method(&auto_commit_obj, "group", "()Ljava/lang/String;", &[])
+        .map_err(|e| format!("Failed to get group: {}", e))?
+        .l()
+        .map_err(|e| format!("Failed to get group object: {}", e))?
+        .into();
+    let group: String = env
+        .get_string(&group_obj)
+        .map_err(|e| format!("Failed to get group string: {}", e))?
+        .into();
+    // Reject groups that cannot form an offset key up front
+    offsets::offset_key(&group, &[])?;
+
+    let interval_ms = env
+        .call_method(&auto_commit_obj, "intervalMs", "()J", &[])
+        .map_err(|e| format!("Failed to get intervalMs: {}", e))?
+        .j()
+        .map_err(|e| format!("Failed to get intervalMs value: {}", e))?;
+
+    Ok(Some((
+        group,
+        std::time::Duration::from_millis(interval_ms as u64),
+    )))
+}
+
+/// Extracts the payload size and workload settings from a Java BenchConfig.
+fn extract_bench_settings(
+    env: &mut JNIEnv<'_>,
+    config: &JObject<'_>,
+) -> Result<(usize, bench::BenchSettings), String> {
+    let payload_size = extract_int(env, config, "payloadSize")? as usize;
+    let settings = bench::BenchSettings {
+        batch_size: extract_int(env, config, "batchSize")? as usize,
+        batches: extract_int(env, config, "batches")? as usize,
+    };
+    Ok((payload_size, settings))
+}
+
+/// Extracts a nullable `String` accessor from a Java record.
+fn extract_optional_string(
+    env: &mut JNIEnv<'_>,
+    obj: &JObject<'_>,
+    name: &str,
+) -> Result<Option<String>, String> {
+    let value = env
+        .call_method(obj, name, "()Ljava/lang/String;", &[])
+        .map_err(|e| format!("Failed to get {}: {}", name, e))?
+        .l()
+        .map_err(|e| format!("Failed to get {} object: {}", name, e))?;
+    if value.is_null() {
+        return Ok(None);
+    }
+    let value: String = env
+        .get_string((&value).into())
+        .map_err(|e| format!("Failed to convert {}: {}", name, e))?
+        .into();
+    Ok(Some(value))
+}
+
+/// Extracts a nullable `Long` accessor from a Java record.
+fn extract_optional_long(
+    env: &mut JNIEnv<'_>,
+    obj: &JObject<'_>,
+    name: &str,
+) -> Result<Option<i64>, String> {
+    let value = env
+        .call_method(obj, name, "()Ljava/lang/Long;", &[])
+        .map_err(|e| format!("Failed to get {}: {}", name, e))?
+        .l()
+        .map_err(|e| format!("Failed to get {} object: {}", name, e))?;
+    if value.is_null() {
+        return Ok(None);
+    }
+    let value = env
+        .call_method(&value, "longValue", "()J", &[])
+        .map_err(|e| format!("Failed to unbox {}: {}", name, e))?
+        .j()
+        .map_err(|e| format!("Failed to get {} value: {}", name, e))?;
+    Ok(Some(value))
+}
+
+/// Extracts the scheduler of a reader's runtime from a Java LogDbReaderConfig.
+fn extract_runtime_mode(env: &mut JNIEnv<'_>, config: &JObject<'_>) -> Result<RuntimeMode, String> {
+    let mode_obj = env
+        .call_method(config, "runtimeMode", "()Ldev/opendata/RuntimeMode;", &[])
+        .map_err(|e| format!("Failed to get runtimeMode: {}", e))?
+        .l()
+        .map_err(|e| format!("Failed to get runtimeMode object: {}", e))?;
+    let ordinal = extract_int(env, &mode_obj, "ordinal")?;
+    RuntimeMode::from_ordinal(ordinal)
+        .ok_or_else(|| format!("Unknown runtime mode ordinal: {}", ordinal))
+}
+
+/// Extracts the slow-operation threshold from a Java config object.
+///
+/// Returns zero when slow operations are not logged.
+fn extract_slow_operation_threshold(
+    env: &mut JNIEnv<'_>,
+    config: &JObject<'_>,
+) -> Result<std::time::Duration, String> {
+    let threshold_ms = extract_long(env, config, "slowOperationThresholdMs")?;
+    Ok(std::time::Duration::from_millis(threshold_ms.max(0) as u64))
+}
+
+/// Extracts the optional watchdog settings from a Java config object.
+///
+/// Returns None when the watchdog is disabled (null `thresholdMs`).
+fn extract_watchdog_settings(
+    env: &mut JNIEnv<'_>,
+    config: &JObject<'_>,
+) -> Result<Option<WatchdogSettings>, String> {
+    let watchdog_obj = env
+        .call_method(config, "watchdog", "()Ldev/opendata/WatchdogConfig;", &[])
+        .map_err(|e| format!("Failed to get watchdog: {}", e))?
+        .l()
+        .map_err(|e| format!("Failed to get watchdog object: {}", e))?;
+
+    let threshold_obj = env
+        .call_method(&watchdog_obj, "thresholdMs", "()Ljava/lang/Long;", &[])
+        .map_err(|e| format!("Failed to get thresholdMs: {}", e))?
+        .l()
+        .map_err(|e| format!("Failed to get thresholdMs object: {}", e))?;
+
+    if threshold_obj.is_null() {
+        return Ok(None);
+    }
+
+    let threshold_ms = env
+        .call_method(&threshold_obj, "longValue", "()J", &[])
+        .map_err(|e| format!("Failed to unbox thresholdMs: {}", e))?
+        .j()
+        .map_err(|e| format!("Failed to get long value: {}", e))?;
+
+    let fail_stuck_calls = extract_bool(env, &watchdog_obj, "failStuckCalls")?;
+
+    Ok(Some(WatchdogSettings {
+        threshold: std::time::Duration::from_millis(threshold_ms as u64),
+        fail_stuck_calls,
+    }))
+}
+
+/// Extracts the write coalescing settings from a Java LogDbConfig.
+///
+/// Returns None when the config's window is zero.
+fn extract_coalesce_settings(
+    env: &mut JNIEnv<'_>,
+    config: &JObject<'_>,
+) -> Result<Option<CoalesceSettings>, String> {
+    let coalesce_obj = env
+        .call_method(config, "coalesce", "()Ldev/opendata/CoalesceConfig;", &[])
+        .map_err(|e| format!("Failed to get coalesce: {}", e))?
+        .l()
+        .map_err(|e| format!("Failed to get coalesce object: {}", e))?;
+
+    let window_micros = extract_long(env, &coalesce_obj, "windowMicros")?;
+    if window_micros == 0 {
+        return Ok(None);
+    }
+
+    Ok(Some(CoalesceSettings {
+        window: std::time::Duration::from_micros(window_micros as u64),
+        max_records: extract_int(env, &coalesce_obj, "maxRecords")? as usize,
+        max_bytes: extract_long(env, &coalesce_obj, "maxBytes")? as usize,
+    }))
+}
+
+/// Extracts the optional automatic reopening from a Java LogDbConfig.
+fn extract_reopen_settings(
+    env: &mut JNIEnv<'_>,
+    config: &JObject<'_>,
+) -> Result<Option<ReopenSettings>, String> {
+    let reopen_obj = env
+        .call_method(config, "reopen", "()Ldev/opendata/ReopenConfig;", &[])
+        .map_err(|e| format!("Failed to get reopen: {}", e))?
+        .l()
+        .map_err(|e| format!("Failed to get reopen object: {}", e))?;
+
+    let failure_threshold = extract_int(env, &reopen_obj, "failureThreshold")?;
+    if failure_threshold == 0 {
+        return Ok(None);
+    }
+
+    Ok(Some(ReopenSettings {
+        failure_threshold: failure_threshold as u32,
+        backoff: std::time::Duration::from_millis(
+            extract_long(env, &reopen_obj, "backoffMs")? as u64
+        ),
+    }))
+}
+
+/// Extracts the StatsD agent stats are sent to from a Java LogDbConfig.
+fn extract_statsd_settings(
+    env: &mut JNIEnv<'_>,
+    config: &JObject<'_>,
+) -> Result<Option<StatsdSettings>, String> {
+    let statsd_obj = env
+        .call_method(config, "statsd", "()Ldev/opendata/StatsdConfig;", &[])
+        .map_err(|e| format!("Failed to get statsd: {}", e))?
+        .l()
+        .map_err(|e| format!("Failed to get statsd object: {}", e))?;
+
+    let Some(host) = extract_optional_string(env, &statsd_obj, "host")? else {
+        return Ok(None);
+    };
+
+    Ok(Some(StatsdSettings {
+        host,
+        port: extract_int(env, &statsd_obj, "port")? as u16,
+        interval: std::time::Duration::from_millis(
+            extract_long(env, &statsd_obj, "intervalMs")?.max(1) as u64,
+        ),
+        prefix: extract_optional_string(env, &statsd_obj, "prefix")?.unwrap_or_default(),
+        dogstatsd: extract_bool(env, &statsd_obj, "dogStatsd")?,
+    }))
+}
+
+/// Extracts the thread names and cores of a runtime from a Java LogDbConfig.
+fn extract_runtime_settings(
+    env: &mut JNIEnv<'_>,
+    config: &JObject<'_>,
+    name: &str,
+) -> Result<RuntimeSettings, String> {
+    let runtime_obj = env
+        .call_method(config, name, "()Ldev/opendata/RuntimeConfig;", &[])
+        .map_err(|e| format!("Failed to get {}: {}", name, e))?
+        .l()
+        .map_err(|e| format!("Failed to get {} object: {}", name, e))?;
+
+    let thread_name_prefix = extract_optional_string(env, &runtime_obj, "threadNamePrefix")?
+        .ok_or_else(|| format!("{}.threadNamePrefix must not be null", name))?;
+    let cpus_obj = env
+        .call_method(&runtime_obj, "cpuArray", "()[I", &[])
+        .map_err(|e| format!("Failed to get cpuArray: {}", e))?
+        .l()
+        .map_err(|e| format!("Failed to get cpuArray object: {}", e))?;
+    let cpus_array = JIntArray::from(cpus_obj);
+    let len = env
+        .get_array_length(&cpus_array)
+        .map_err(|e| format!("Failed to get cpuArray length: {}", e))?;
+    let mut cpus = vec![0; len as usize];
+    env.get_int_array_region(&cpus_array, 0, &mut cpus)
+        .map_err(|e| format!("Failed to read cpuArray: {}", e))?;
+
+    Ok(RuntimeSettings {
+        thread_name_prefix,
+        cpus: cpus.into_iter().map(|cpu| cpu as usize).collect(),
+        worker_threads: extract_int(env, &runtime_obj, "workerThreads")? as usize,
+        shared: extract_bool(env, &runtime_obj, "shared")?,
+    })
+}
+
+/// Extracts the optional native memory limit from a Java LogDbConfig.
+fn extract_memory_limit_settings(
+    env: &mut JNIEnv<'_>,
+    config: &JObject<'_>,
+) -> Result<Option<MemoryLimitSettings>, String> {
+    let limit_obj = env
+        .call_method(
+            config,
+            "memoryLimit",
+            "()Ldev/opendata/MemoryLimitConfig;",
+            &[],
+        )
+        .map_err(|e| format!("Failed to get memoryLimit: {}", e))?
+        .l()
+        .map_err(|e| format!("Failed to get memoryLimit object: {}", e))?;
+
+    let max_bytes = extract_long(env, &limit_obj, "maxBytes")? as usize;
+    if max_bytes == 0 {
+        return Ok(None);
+    }
+
+    let policy_obj = env
+        .call_method(
+            &limit_obj,
+            "policy",
+            "()Ldev/opendata/BackpressurePolicy;",
+            &[],
+        )
+        .map_err(|e| format!("Failed to get policy: {}", e))?
+        .l()
+        .map_err(|e| format!("Failed to get policy object: {}", e))?;
+    let ordinal = extract_int(env, &policy_obj, "ordinal")?;
+    let policy = match BackpressurePolicy::from_ordinal(ordinal) {
+        Some(BackpressurePolicy::NotReady) => {
+            return Err("memoryLimit policy must be BLOCK or REJECT".to_string())
+        }
+        Some(policy) => policy,
+        None => return Err(format!("Unknown backpressure policy ordinal: {}", ordinal)),
+    };
+
+    Ok(Some(MemoryLimitSettings { max_bytes, policy }))
+}
+
+/// Extracts the optional limit on blocking calls from a Java LogDbConfig.
+fn extract_call_limit_settings(
+    env: &mut JNIEnv<'_>,
+    config: &JObject<'_>,
+) -> Result<Option<CallLimitSettings>, String> {
+    let limit_obj = env
+        .call_method(config, "callLimit", "()Ldev/opendata/CallLimitConfig;", &[])
+        .map_err(|e| format!("Failed to get callLimit: {}", e))?
+        .l()
+        .map_err(|e| format!("Failed to get callLimit object: {}", e))?;
+
+    let max_calls = extract_int(env, &limit_obj, "maxCalls")?;
+    if max_calls <= 0 {
+        return Ok(None);
+    }
+
+    let policy_obj = env
+        .call_method(&limit_obj, "policy", "()Ldev/opendata/OverloadPolicy;", &[])
+        .map_err(|e| format!("Failed to get policy: {}", e))?
+        .l()
+        .map_err(|e| format!("Failed to get policy object: {}", e))?;
+    let ordinal = extract_int(env, &policy_obj, "ordinal")?;
+    let policy = OverloadPolicy::from_ordinal(ordinal)
+        .ok_or_else(|| format!("Unknown overload policy ordinal: {}", ordinal))?;
+
+    Ok(Some(CallLimitSettings {
+        max_calls: max_calls as usize,
+        policy,
+        max_queued: extract_int(env, &limit_obj, "maxQueued")?.max(0) as usize,
+    }))
+}
+
+/// Extracts the optional write queue bound from a Java LogDbConfig.
+fn extract_write_queue_settings(
+    env: &mut JNIEnv<'_>,
+    config: &JObject<'_>,
+) -> Result<Option<WriteQueueSettings>, String> {
+    let queue_obj = env
+        .call_method(
+            config,
+            "writeQueue",
+            "()Ldev/opendata/WriteQueueConfig;",
+            &[],
+        )
+        .map_err(|e| format!("Failed to get writeQueue: {}", e))?
+        .l()
+        .map_err(|e| format!("Failed to get writeQueue object: {}", e))?;
+
+    let max_records = extract_int(env, &queue_obj, "maxRecords")? as usize;
+    let max_bytes = extract_long(env, &queue_obj, "maxBytes")? as usize;
+    if max_records == 0 && max_bytes == 0 {
+        return Ok(None);
+    }
+
+    let policy_obj = env
+        .call_method(
+            &queue_obj,
+            "policy",
+            "()Ldev/opendata/BackpressurePolicy;",
+            &[],
+        )
+        .map_err(|e| format!("Failed to get policy: {}", e))?
+        .l()
+        .map_err(|e| format!("Failed to get policy object: {}", e))?;
+    let ordinal = extract_int(env, &policy_obj, "ordinal")?;
+    let policy = BackpressurePolicy::from_ordinal(ordinal)
+        .ok_or_else(|| format!("Unknown backpressure policy ordinal: {}", ordinal))?;
+
+    Ok(Some(WriteQueueSettings {
+        max_records,
+        max_bytes,
+        policy,
+    }))
+}
+
+/// Extracts the produce and consume rate limits from a Java LogDbConfig.
+fn extract_rate_limit_settings(
+    env: &mut JNIEnv<'_>,
+    config: &JObject<'_>,
+) -> Result<RateLimitSettings, String> {
+    let limits_obj = env
+        .call_method(
+            config,
+            "rateLimits",
+            "()Ldev/opendata/RateLimitConfig;",
+            &[],
+        )
+        .map_err(|e| format!("Failed to get rateLimits: {}", e))?
+        .l()
+        .map_err(|e| format!("Failed to get rateLimits object: {}", e))?;
+    extract_rate_limits(env, &limits_obj)
+}
+
+/// Extracts the limits of a Java RateLimitConfig.
+fn extract_rate_limits(
+    env: &mut JNIEnv<'_>,
+    limits_obj: &JObject<'_>,
+) -> Result<RateLimitSettings, String> {
+    Ok(RateLimitSettings {
+        produce_records_per_sec: extract_long(env, limits_obj, "produceRecordsPerSec")? as u64,
+        produce_bytes_per_sec: extract_long(env, limits_obj, "produceBytesPerSec")? as u64,
+        consume_records_per_sec: extract_long(env, limits_obj, "consumeRecordsPerSec")? as u64,
+        consume_bytes_per_sec: extract_long(env, limits_obj, "consumeBytesPerSec")? as u64,
+    })
+}
+
+/// Extracts the optional fault injection settings from a Java LogDbConfig.
+fn extract_fault_settings(
+    env: &mut JNIEnv<'_>,
+    config: &JObject<'_>,
+) -> Result<Option<FaultSettings>, String> {
+    let faults_obj = env
+        .call_method(config, "faults", "()Ldev/opendata/FaultInjection;", &[])
+        .map_err(|e| format!("Failed to get faults: {}", e))?
+        .l()
+        .map_err(|e| format!("Failed to get faults object: {}", e))?;
+
+    let operations = extract_int(env, &faults_obj, "operationMask")? as u32;
+    if operations == 0 {
+        return Ok(None);
+    }
+
+    let error_probability = extract_double(env, &faults_obj, "errorProbability")?;
+    let throttle_probability = extract_double(env, &faults_obj, "throttleProbability")?;
+    let timeout_probability = extract_double(env, &faults_obj, "timeoutProbability")?;
+    let timeout_ms = extract_long(env, &faults_obj, "timeoutMs")?;
+    let min_latency_ms = extract_long(env, &faults_obj, "minLatencyMs")?;
+    let max_latency_ms = extract_long(env, &faults_obj, "maxLatencyMs")?;
+    let seed = extract_long(env, &faults_obj, "seed")?;
+
+    Ok(Some(FaultSettings {
+        error_probability,
+        throttle_probability,
+        timeout_probability,
+        timeout: std::time::Duration::from_millis(timeout_ms as u64),
+        min_latency: std::time::Duration::from_millis(min_latency_ms as u64),
+        max_latency: std::time::Duration::from_millis(max_latency_ms as u64),
+        operations,
+        seed: seed as u64,
+    }))
+}
+
+/// Appends a batch of records to the log with timestamp headers.
+///
+/// Each value is stored as: `[8-byte timestamp (big-endian i64)] + [original payload]`
+/// The timestamp is read from each Java Record object (captured at submission time).
+///
+/// # Arguments
+/// * `handle` - Native LogDb pointer
+/// * `records` - Array of Java Record objects (each with key, value, timestampMs)
+/// * `ack_mode` - Ordinal of the Java `AckMode` enum; `DURABLE` flushes before returning
+/// * `priority` - Ordinal of the Java `OperationPriority` enum, see `priority.rs`
+/// * `traceparent` - W3C traceparent of the call, or null, see `tracecontext.rs`
+///
+/// # Returns
+/// AppendResult jobject with start_sequence and timestamp of first record
+///
+/// # Safety
+/// JNI function - handle must be a valid pointer returned by nativeCreate.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "system" fn Java_dev_opendata_LogDb_nativeAppend<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    handle: jlong,
+    records: jobjectArray,
+    ack_mode: jint,
+    priority: jint,
+    traceparent: JString<'local>,
+) -> jobject {
+    let timer = CallTimer::start();
+
+    if handle == 0 {
+        let _ = env.throw_new("java/lang/NullPointerException", "LogDb handle is null");
+        return std::ptr::null_mut();
+    }
+
+    let Some(ack_mode) = AckMode::from_ordinal(ack_mode) else {
+        let _ = env.throw_new(
+            "java/lang/IllegalArgumentException",
+            format!("Unknown ack mode ordinal: {}", ack_mode),
+        );
+        return std::ptr::null_mut();
+    };
+
+    let Some(priority) = Priority::from_ordinal(priority) else {
+        let _ = env.throw_new(
+            "java/lang/IllegalArgumentException",
+            format!("Unknown priority ordinal: {}", priority),
+        );
+        return std::ptr::null_mut();
+    };
+
+    let trace = match extract_trace_context(&mut env, &traceparent) {
+        Ok(trace) => trace,
+        Err(e) => {
+            let _ = env.throw_new("java/lang/IllegalArgumentException", e);
+            return std::ptr::null_mut();
+        }
+    };
+
+    let log_handle = unsafe { &*(handle as *const LogHandle) };
+
+    // Convert Java Record[] to Rust Vec<Record>
+    let records_array = unsafe { JObjectArray::from_raw(records) };
+    let (rust_records, first_timestamp_ms) = match extract_records(
+        &mut env,
+        &records_array,
+        log_handle.critical_copy,
+        log_handle.clock.stamps(log_handle.receive_timestamps),
+    ) {
+        Ok(r) => r,
+        Err(e) => {
+            let _ = env.throw_new("dev/opendata/common/OpenDataNativeException", e.to_string());
+            return std::ptr::null_mut();
+        }
+    };
+
+    if rust_records.is_empty() {
+        let _ = env.throw_new(
+            "java/lang/IllegalArgumentException",
+            "Records array is empty",
+        );
+        return std::ptr::null_mut();
+    }
+
+    let _trace = tracecontext::enter(trace);
+    append_batch(
+        &mut env,
+        log_handle,
+        rust_records,
+        first_timestamp_ms,
+        ack_mode,
+        priority,
+        timer,
+    )
+}
+
+/// Appends a batch of records for any number of keys, grouped by key.
+///
+/// See `grouping.rs`.
+///
+/// # Arguments
+/// * `handle` - Native LogDb pointer
+/// * `records` - Array of Java Record objects (each with key, value, timestampMs)
+/// * `ack_mode` - Ordinal of the Java `AckMode` enum; `DURABLE` flushes before returning
+///
+/// # Returns
+/// KeyAppendResult[] with each key's first sequence and record count, in
+/// order of each key's first record
+///
+/// # Safety
+/// JNI function - handle must be a valid pointer returned by nativeCreate.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "system" fn Java_dev_opendata_LogDb_nativeAppendGrouped<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    handle: jlong,
+    records: jobjectArray,
+    ack_mode: jint,
+) -> jobjectArray {
+    let timer = CallTimer::start();
+
+    if handle == 0 {
+        let _ = env.throw_new("java/lang/NullPointerException", "LogDb handle is null");
+        return std::ptr::null_mut();
+    }
+
+    let Some(ack_mode) = AckMode::from_ordinal(ack_mode) else {
+        let _ = env.throw_new(
+            "java/lang/IllegalArgumentException",
+            format!("Unknown ack mode ordinal: {}", ack_mode),
+        );
+        return std::ptr::null_mut();
+    };
+
+    let log_handle = unsafe { &*(handle as *const LogHandle) };
+
+    let records_array = unsafe { JObjectArray::from_raw(records) };
+    let (rust_records, _) = match extract_records(
+        &mut env,
+        &records_array,
+        log_handle.critical_copy,
+        log_handle.clock.stamps(log_handle.receive_timestamps),
+    ) {
+        Ok(r) => r,
+        Err(e) => {
+            let _ = env.throw_new("dev/opendata/common/OpenDataNativeException", e.to_string());
+            return std::ptr::null_mut();
+        }
+    };
+
+    if rust_records.is_empty() {
+        let _ = env.throw_new(
+            "java/lang/IllegalArgumentException",
+            "Records array is empty",
+        );
+        return std::ptr::null_mut();
+    }
+
+    let (rust_records, groups) = grouping::group(rust_records, log_handle.chunk_threshold);
+    append_batch_with(
+        &mut env,
+        log_handle,
+        chunking::split(rust_records, log_handle.chunk_threshold),
+        ack_mode,
+        Priority::Foreground,
+        timer,
+        |env, start_sequence| create_key_append_result_array(env, start_sequence, &groups),
+    )
+}
+
+/// Appends a batch of records whose values are stored exactly as passed.
+///
+/// Unlike `nativeAppend`, no timestamp header is prepended and values are
+/// never split into chunks; each record's `timestampMs` is ignored.
+///
+/// # Arguments
+/// * `handle` - Native LogDb pointer
+/// * `records` - Array of Java Record objects
+/// * `ack_mode` - Ordinal of the Java `AckMode` enum; `DURABLE` flushes before returning
+///
+/// # Returns
+/// AppendResult jobject with the start sequence of the batch and a timestamp of 0
+///
+/// # Safety
+/// JNI function - handle must be a valid pointer returned by nativeCreate.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "system" fn Java_dev_opendata_LogDb_nativeAppendRaw<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    handle: jlong,
+    records: jobjectArray,
+    ack_mode: jint,
+) -> jobject {
+    let timer = CallTimer::start();
+
+    if handle == 0 {
+        let _ = env.throw_new("java/lang/NullPointerException", "LogDb handle is null");
+        return std::ptr::null_mut();
+    }
+
+    let Some(ack_mode) = AckMode::from_ordinal(ack_mode) else {
+        let _ = env.throw_new(
+            "java/lang/IllegalArgumentException",
+            format!("Unknown ack mode ordinal: {}", ack_mode),
+        );
+        return std::ptr::null_mut();
+    };
+
+    let log_handle = unsafe { &*(handle as *const LogHandle) };
+
+    let records_array = unsafe { JObjectArray::from_raw(records) };
+    let rust_records = match extract_raw_records(&mut env, &records_array) {
+        Ok(r) => r,
+        Err(e) => {
+            let _ = env.throw_new("dev/opendata/common/OpenDataNativeException", e.to_string());
+            return std::ptr::null_mut();
+        }
+    };
+
+    if rust_records.is_empty() {
+        let _ = env.throw_new(
+            "java/lang/IllegalArgumentException",
+            "Records array is empty",
+        );
+        return std::ptr::null_mut();
+    }
+
+    append_batch_with(
+        &mut env,
+        log_handle,
+        rust_records,
+        ack_mode,
+        Priority::Foreground,
+        timer,
+        |env, start_sequence| create_append_result(env, start_sequence, 0).map(JObject::into_raw),
+    )
+}
+
+/// Returns the partition a key is assigned to.
+///
+/// # Arguments
+/// * `key` - The routing key
+/// * `num_partitions` - Number of partitions, validated positive by Java
+///
+/// # Safety
+/// This is a JNI function - must be called from Java with valid JNIEnv.
+#[no_mangle]
+pub extern "system" fn Java_dev_opendata_LogDb_nativePartitionForKey<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    key: JByteArray<'local>,
+    num_partitions: jint,
+) -> jint {
+    match env.convert_byte_array(&key) {
+        Ok(key) => partition::partition_for_key(&key, num_partitions as u32) as jint,
+        Err(e) => {
+            let _ = env.throw_new("dev/opendata/common/OpenDataNativeException", e.to_string());
+            -1
+        }
+    }
+}
+
+/// Appends a batch of records, routing each to its topic partition's key.
+///
+/// Each record's key selects a partition with `nativePartitionForKey` and the
+/// record is stored under the key `<topic>-<partition>` instead.
+///
+/// # Arguments
+/// * `handle` - Native LogDb pointer
+/// * `topic` - Topic name prefixing the partition keys
+/// * `records` - Array of Java Record objects
+/// * `num_partitions` - Number of partitions, validated positive by Java
+/// * `ack_mode` - Ordinal of the Java `AckMode` enum
+///
+/// # Returns
+/// AppendResult jobject with start_sequence and timestamp of first record
+///
+/// # Safety
+/// JNI function - handle must be a valid pointer returned by nativeCreate.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "system" fn Java_dev_opendata_LogDb_nativeAppendPartitioned<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    handle: jlong,
+    topic: JString<'local>,
+    records: jobjectArray,
+    num_partitions: jint,
+    ack_mode: jint,
+) -> jobject {
+    let timer = CallTimer::start();
+
+    if handle == 0 {
+        let _ = env.throw_new("java/lang/NullPointerException", "LogDb handle is null");
+        return std::ptr::null_mut();
+    }
+
+    let Some(ack_mode) = AckMode::from_ordinal(ack_mode) else {
+        let _ = env.throw_new(
+            "java/lang/IllegalArgumentException",
+            format!("Unknown ack mode ordinal: {}", ack_mode),
+        );
+        return std::ptr::null_mut();
+    };
+
+    let log_handle = unsafe { &*(handle as *const LogHandle) };
+
+    let topic: String = match env.get_string(&topic) {
+        Ok(t) => t.into(),
+        Err(e) => {
+            let _ = env.throw_new("java/lang/IllegalArgumentException", e.to_string());
+            return std::ptr::null_mut();
+        }
+    };
+
+    let records_array = unsafe { JObjectArray::from_raw(records) };
+    let (mut rust_records, first_timestamp_ms) = match extract_records(
+        &mut env,
+        &records_array,
+        log_handle.critical_copy,
+        log_handle.clock.stamps(log_handle.receive_timestamps),
+    ) {
+        Ok(r) => r,
+        Err(e) => {
+            let _ = env.throw_new("dev/opendata/common/OpenDataNativeException", e.to_string());
+            return std::ptr::null_mut();
+        }
+    };
+
+    if rust_records.is_empty() {
+        let _ = env.throw_new(
+            "java/lang/IllegalArgumentException",
+            "Records array is empty",
+        );
+        return std::ptr::null_mut();
+    }
+
+    for record in &mut rust_records {
+        let partition = partition::partition_for_key(&record.key, num_partitions as u32);
+        record.key = partition::partition_key(&topic, partition);
+    }
+
+    append_batch(
+        &mut env,
+        log_handle,
+        rust_records,
+        first_timestamp_ms,
+        ack_mode,
+        Priority::Foreground,
+        timer,
+    )
+}
+
+/// Appends the records of a FlatBuffers `AppendBatch`.
+///
+/// The batch is verified and decoded natively; see `flatbatch.rs`. Records
+/// without a timestamp are stamped with the time of the call.
+///
+/// # Arguments
+/// * `handle` - Native LogDb pointer
+/// * `batch` - Bytes of an `AppendBatch`
+/// * `ack_mode` - Ordinal of the Java `AckMode` enum
+///
+/// # Returns
+/// AppendResult jobject with start_sequence and timestamp of first record
+///
+/// # Safety
+/// JNI function - handle must be a valid pointer returned by nativeCreate.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "system" fn Java_dev_opendata_LogDb_nativeAppendFlatBuffer<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    handle: jlong,
+    batch: JByteArray<'local>,
+    ack_mode: jint,
+) -> jobject {
+    let timer = CallTimer::start();
+
+    if handle == 0 {
+        let _ = env.throw_new("java/lang/NullPointerException", "LogDb handle is null");
+        return std::ptr::null_mut();
+    }
+
+    let Some(ack_mode) = AckMode::from_ordinal(ack_mode) else {
+        let _ = env.throw_new(
+            "java/lang/IllegalArgumentException",
+            format!("Unknown ack mode ordinal: {}", ack_mode),
+        );
+        return std::ptr::null_mut();
+    };
+
+    let log_handle = unsafe { &*(handle as *const LogHandle) };
+
+    let batch = match env.convert_byte_array(&batch) {
+        Ok(b) => b,
+        Err(e) => {
+            let _ = env.throw_new("dev/opendata/common/OpenDataNativeException", e.to_string());
+            return std::ptr::null_mut();
+        }
+    };
+    let (rust_records, first_timestamp_ms) = match flatbatch::decode_batch(
+        &batch,
+        current_timestamp_ms(),
+        log_handle.clock.stamps(log_handle.receive_timestamps),
+    ) {
+        Ok(r) => r,
+        Err(e) => {
+            let _ = env.throw_new("java/lang/IllegalArgumentException", e);
+            return std::ptr::null_mut();
+        }
+    };
+
+    if rust_records.is_empty() {
+        let _ = env.throw_new(
+            "java/lang/IllegalArgumentException",
+            "Records array is empty",
+        );
+        return std::ptr::null_mut();
+    }
+
+    append_batch(
+        &mut env,
+        log_handle,
+        rust_records,
+        first_timestamp_ms,
+        ack_mode,
+        Priority::Foreground,
+        timer,
+    )
+}
+
+/// Appends `count` records with payloads generated natively.
+///
+/// Only the key crosses JNI; one payload of `payload_size` bytes is generated
+/// per call and shared by every record in the batch, so the measured latency
+/// reflects the storage engine rather than Java-to-native copies. Values carry
+/// the usual timestamp header, taken when the call starts.
+///
+/// # Arguments
+/// * `handle` - Native LogDb pointer
+/// * `key` - The key to append under
+/// * `payload_size` - Size of each generated payload in bytes
+/// * `count` - Number of records to append, validated positive by Java
+/// * `ack_mode` - Ordinal of the Java `AckMode` enum
+///
+/// # Returns
+/// AppendResult jobject with start_sequence and the batch timestamp
+///
+/// # Safety
+/// JNI function - handle must be a valid pointer returned by nativeCreate.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "system" fn Java_dev_opendata_LogDb_nativeAppendSynthetic<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    handle: jlong,
+    key: JByteArray<'local>,
+    payload_size: jint,
+    count: jint,
+    ack_mode: jint,
+) -> jobject {
+    let timer = CallTimer::start();
+
+    if handle == 0 {
+        let _ = env.throw_new("java/lang/NullPointerException", "LogDb handle is null");
+        return std::ptr::null_mut();
+    }
+
+    let Some(ack_mode) = AckMode::from_ordinal(ack_mode) else {
+        let _ = env.throw_new(
+            "java/lang/IllegalArgumentException",
+            format!("Unknown ack mode ordinal: {}", ack_mode),
+        );
+        return std::ptr::null_mut();
+    };
+
+    let log_handle = unsafe { &*(handle as *const LogHandle) };
+
+    let key_bytes = match env.convert_byte_array(&key) {
+        Ok(b) => Bytes::from(b),
+        Err(e) => {
+            let _ = env.throw_new("dev/opendata/common/OpenDataNativeException", e.to_string());
+            return std::ptr::null_mut();
+        }
+    };
+
+    let timestamp_ms = log_handle.clock.now_ms();
+    let value = synthetic_value(timestamp_ms, payload_size as usize, timestamp_ms as u64);
+    let rust_records = (0..count)
+        .map(|_| Record {
+            key: key_bytes.clone(),
+            value: value.clone(),
+        })
+        .collect();
+
+    append_batch(
+        &mut env,
+        log_handle,
+        rust_records,
+        timestamp_ms,
+        ack_mode,
+        Priority::Foreground,
+        timer,
+    )
+}
+
+/// Runs the in-process loopback benchmark against this LogDb.
+///
+/// See the `bench` module. The benchmark writes to a fresh reserved key, so
+/// it does not disturb application keys. Like waiting for a sequence, a run
+/// is expected to be long and is not registered with the watchdog.
+///
+/// # Arguments
+/// * `handle` - Native LogDb pointer
+/// * `config` - Java BenchConfig object
+///
+/// # Returns
+/// BenchReport jobject
+///
+/// # Safety
+/// JNI function - handle must be a valid pointer returned by nativeCreate.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "system" fn Java_dev_opendata_LogDb_nativeBench<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    handle: jlong,
+    config: JObject<'local>,
+) -> jobject {
+    if handle == 0 {
+        let _ = env.throw_new("java/lang/NullPointerException", "LogDb handle is null");
+        return std::ptr::null_mut();
+    }
+
+    let log_handle = unsafe { &*(handle as *const LogHandle) };
+
+    let (payload_size, settings) = match extract_bench_settings(&mut env, &config) {
+        Ok(s) => s,
+        Err(e) => {
+            let _ = env.throw_new("java/lang/IllegalArgumentException", e);
+            return std::ptr::null_mut();
+        }
+    };
+
+    let timestamp_ms = current_timestamp_ms();
+    let run_id = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |d| d.as_nanos() as u64);
+    let key = bench::bench_key(run_id);
+    let value = synthetic_value(timestamp_ms, payload_size, timestamp_ms as u64);
+
+    let _blocked = log_handle.blocked_callers.enter();
+    let result = log_handle.runtime_handle.block_on(bench::run(
+        &log_handle.log.current(),
+        &log_handle.append_lock,
+        key,
+        value,
+        payload_size,
+        settings,
+    ));
+
+    let report = match result {
+        Ok(r) => r,
+        Err(e) => {
+            let _ = env.throw_new("dev/opendata/common/OpenDataNativeException", e.to_string());
+            return std::ptr::null_mut();
+        }
+    };
+
+    match create_bench_report(&mut env, &report) {
+        Ok(obj) => obj.into_raw(),
+        Err(e) => {
+            let _ = env.throw_new("dev/opendata/common/OpenDataNativeException", e);
+            std::ptr::null_mut()
+        }
+    }
+}
+
+/// Opens an append ring draining into this LogDb.
+///
+/// # Arguments
+/// * `handle` - Native LogDb pointer
+/// * `capacity` - Data capacity of the ring in bytes, a power of two
+///
+/// # Returns
+/// Native AppendRing pointer, or throws on failure
+///
+/// # Safety
+/// JNI function - handle must be a valid pointer returned by nativeCreate.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "system" fn Java_dev_opendata_LogDb_nativeOpenAppendRing<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    handle: jlong,
+    capacity: jint,
+) -> jlong {
+    if handle == 0 {
+        let _ = env.throw_new("java/lang/NullPointerException", "LogDb handle is null");
+        return 0;
+    }
+
+    let log_handle = unsafe { &*(handle as *const LogHandle) };
+
+    match AppendRing::start(
+        capacity as usize,
+        Arc::downgrade(&log_handle.log),
+        Arc::clone(&log_handle.append_lock),
+        log_handle.tail_cache.clone(),
+        Arc::clone(&log_handle.watches),
+        log_handle.runtime_handle.clone(),
+        &log_handle.memory,
+    ) {
+        Ok(ring) => Box::into_raw(Box::new(ring)) as jlong,
+        Err(e) => {
+            let _ = env.throw_new("java/lang/IllegalArgumentException", e);
+            0
+        }
+    }
+}
+
+/// Opens an append pipeline over this LogDb.
+///
+/// # Arguments
+/// * `handle` - Native LogDb pointer
+/// * `max_in_flight` - Batches the pipeline may keep in flight
+///
+/// # Returns
+/// Native AppendPipeline pointer, or throws on failure
+///
+/// # Safety
+/// JNI function - handle must be a valid pointer returned by nativeCreate.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "system" fn Java_dev_opendata_LogDb_nativeOpenPipeline<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    handle: jlong,
+    max_in_flight: jint,
+) -> jlong {
+    if handle == 0 {
+        let _ = env.throw_new("java/lang/NullPointerException", "LogDb handle is null");
+        return 0;
+    }
+
+    let log_handle = unsafe { &*(handle as *const LogHandle) };
+    match Pipeline::open(
+        max_in_flight.max(0) as usize,
+        Arc::downgrade(&log_handle.log),
+        Arc::clone(&log_handle.append_lock),
+        log_handle.tail_cache.clone(),
+        Arc::clone(&log_handle.watches),
+        Arc::clone(&log_handle.latency),
+        Arc::clone(&log_handle.slow_log),
+        Arc::clone(&log_handle.tags),
+        Arc::clone(&log_handle.memory),
+        log_handle.chunk_threshold,
+        log_handle.critical_copy,
+        Arc::clone(&log_handle.clock),
+        log_handle.receive_timestamps,
+        log_handle.runtime_handle.clone(),
+    ) {
+        Ok(pipeline) => Box::into_raw(Box::new(pipeline)) as jlong,
+        Err(e) => {
+            let _ = env.throw_new("java/lang/IllegalArgumentException", e);
+            0
+        }
+    }
+}
+
+/// A blocking call of a LogDb, counted among the blocked callers and holding
+/// a slot of the handle's call limit, if configured.
+struct BlockingCall<'a> {
+    _permit: Option<CallPermit<'a>>,
+    _blocked: BlockedCallerGuard<'a>,
+}
+
+/// Enters a blocking call, waiting for a slot of the handle's call limit.
+///
+/// Throws `OpenDataOverloadedException` and returns None when the limit's
+/// policy refuses the call; see `overload.rs`.
+fn enter_blocking_call<'a>(
+    env: &mut JNIEnv<'_>,
+    log_handle: &'a LogHandle,
+) -> Option<BlockingCall<'a>> {
+    let blocked = log_handle.blocked_callers.enter();
+    let permit = match &log_handle.call_limiter {
+        Some(limiter) => {
+            let Some(permit) = limiter.acquire() else {
+                let _ = env.throw_new(
+                    "dev/opendata/common/OpenDataOverloadedException",
+                    format!(
+                        "LogDb is overloaded: {} blocking calls are running",
+                        limiter.max_calls()
+                    ),
+                );
+                return None;
+            };
+            Some(permit)
+        }
+        None => None,
+    };
+    Some(BlockingCall {
+        _permit: permit,
+        _blocked: blocked,
+    })
+}
+
+/// Charges the records of an append against the handle's memory.
+///
+/// Blocks while the memory limit is reached under `BLOCK`; throws and
+/// returns None when the limit refuses the append.
+fn charge_append(
+    env: &mut JNIEnv<'_>,
+    memory: &HandleMemory,
+    records: &[Record],
+) -> Option<MemoryCharge> {
+    let charge = memory.charge_append(records);
+    if charge.is_none() {
+        let max_bytes = memory.limit().map_or(0, |limit| limit.max_bytes);
+        let _ = env.throw_new(
+            "dev/opendata/common/OpenDataBackpressureException",
+            format!("Native memory limit of {} bytes reached", max_bytes),
+        );
+    }
+    charge
+}
+
+/// Appends extracted records and converts the outcome into a Java AppendResult.
+///
+/// Shared by the plain and partitioned appends; throws and returns null on failure.
+fn append_batch(
+    env: &mut JNIEnv<'_>,
+    log_handle: &LogHandle,
+    rust_records: Vec<Record>,
+    first_timestamp_ms: i64,
+    ack_mode: AckMode,
+    priority: Priority,
+    timer: CallTimer,
+) -> jobject {
+    append_batch_with(
+        env,
+        log_handle,
+        chunking::split(rust_records, log_handle.chunk_threshold),
+        ack_mode,
+        priority,
+        timer,
+        |env, start_sequence| {
+            create_append_result(env, start_sequence, first_timestamp_ms).map(JObject::into_raw)
+        },
+    )
+}
+
+/// Appends records, already split into chunks if they are to be, and
+/// converts the start sequence of the batch into a Java result with
+/// `respond`; throws and returns null on failure.
+#[allow(clippy::too_many_arguments)]
+fn append_batch_with(
+    env: &mut JNIEnv<'_>,
+    log_handle: &LogHandle,
+    rust_records: Vec<Record>,
+    ack_mode: AckMode,
+    priority: Priority,
+    mut timer: CallTimer,
+    respond: impl FnOnce(&mut JNIEnv<'_>, u64) -> Result<jobject, jni::errors::Error>,
+) -> jobject {
+    let Some(_charge) = charge_append(env, &log_handle.memory, &rust_records) else {
+        return std::ptr::null_mut();
+    };
+    let summary = log_handle.slow_log.summarize(&rust_records);
+    let size = jfr::summarize(&log_handle.tags, &rust_records);
+    // Use block_on with separate compaction runtime to avoid deadlocks
+    timer.mark_submitted();
+    let Some(blocked) = enter_blocking_call(env, log_handle) else {
+        return std::ptr::null_mut();
+    };
+    let tracked = watchdog::track_call(log_handle.watchdog.as_ref(), "append");
+    let result = log_handle.runtime_handle.block_on(async {
+        if let Some(rate_limiter) = &log_handle.rate_limiter {
+            rate_limiter.admit_append(&rust_records).await;
+        }
+        let _permit = log_handle.priorities.admit(priority).await;
+        timer.mark_storage_started();
+        let result = watchdog::guard_call(log_handle.watchdog.as_ref(), "append", async {
+            if let Some(noop) = &log_handle.noop {
+                return Ok(noop.append(rust_records.len()));
+            }
+            if let Some(coalescer) = &log_handle.coalescer {
+                return coalescer
+                    .append(
+                        &log_handle.log.current(),
+                        &log_handle.append_lock,
+                        rust_records,
+                        ack_mode,
+                    )
+                    .await;
+            }
+            append_records(
+                &log_handle.log.current(),
+                &log_handle.append_lock,
+                log_handle.tail_cache.as_deref(),
+                &log_handle.watches,
+                rust_records,
+                ack_mode,
+            )
+            .await
+            .map_err(|e| e.to_string())
+        })
+        .await;
+        timer.mark_storage_finished();
+        result
+    });
+    drop(tracked);
+    drop(blocked);
+    if let Ok(result) = &result {
+        log_handle.log.observe(result);
+    }
+    log_handle
+        .latency
+        .record(Operation::Append, timer.storage_latency());
+
+    log_handle.slow_log.append(
+        timer.storage_latency(),
+        summary,
+        result.as_ref().ok().and_then(|r| r.as_ref().ok()).copied(),
+    );
+    jfr::append(timer.storage_latency(), size, matches!(result, Ok(Ok(_))));
+
+    let result = match result {
+        Ok(r) => r,
+        Err(stuck) => {
+            throw_stuck_call(env, &stuck);
+            return std::ptr::null_mut();
+        }
+    };
+
+    let java_result = match result {
+        Ok(start_sequence) => match respond(env, start_sequence) {
+            Ok(obj) => obj,
+            Err(e) => {
+                let _ = env.throw_new("dev/opendata/common/OpenDataNativeException", e.to_string());
+                std::ptr::null_mut()
+            }
+        },
+        Err(e) => {
+            let _ = env.throw_new("dev/opendata/common/OpenDataNativeException", e);
+            std::ptr::null_mut()
+        }
+    };
+    log_handle
+        .profiler
+        .record(Operation::Append, &timer.finish());
+    java_result
+}
+
+/// Appends a batch of records only if a key has not advanced past an expected sequence.
+///
+/// The append succeeds iff `key` has no entries with a sequence greater than
+/// `expected_tail_sequence` (-1 requires the key to have no entries at all).
+/// The check and append run under the handle's append lock, so they are atomic
+/// with respect to all other appends through this LogDb. Records may be for
+/// any key; only `key` is checked.
+///
+/// # Arguments
+/// * `handle` - Native LogDb pointer
+/// * `key` - Key whose tail is checked
+/// * `records` - Array of Java Record objects (each with key, value, timestampMs)
+/// * `expected_tail_sequence` - Last sequence the caller observed for `key`, or -1
+/// * `ack_mode` - Ordinal of the Java `AckMode` enum; `DURABLE` flushes before returning
+///
+/// # Returns
+/// AppendResult jobject, or throws `SequenceConflictException` on conflict
+///
+/// # Safety
+/// JNI function - handle must be a valid pointer returned by nativeCreate.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "system" fn Java_dev_opendata_LogDb_nativeAppendIf<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    handle: jlong,
+    key: JByteArray<'local>,
+    records: jobjectArray,
+    expected_tail_sequence: jlong,
+    ack_mode: jint,
+) -> jobject {
+    let mut timer = CallTimer::start();
+
+    if handle == 0 {
+        let _ = env.throw_new("java/lang/NullPointerException", "LogDb handle is null");
+        return std::ptr::null_mut();
+    }
+
+    let Some(ack_mode) = AckMode::from_ordinal(ack_mode) else {
+        let _ = env.throw_new(
+            "java/lang/IllegalArgumentException",
+            format!("Unknown ack mode ordinal: {}", ack_mode),
+        );
+        return std::ptr::null_mut();
+    };
+
+    if expected_tail_sequence < -1 {
+        let _ = env.throw_new(
+            "java/lang/IllegalArgumentException",
+            "expectedTailSequence must be >= -1",
+        );
+        return std::ptr::null_mut();
+    }
+
+    let log_handle = unsafe { &*(handle as *const LogHandle) };
+
+    let key_bytes = match env.convert_byte_array(&key) {
+        Ok(b) => Bytes::from(b),
+        Err(e) => {
+            let _ = env.throw_new("dev/opendata/common/OpenDataNativeException", e.to_string());
+            return std::ptr::null_mut();
+        }
+    };
+
+    let records_array = unsafe { JObjectArray::from_raw(records) };
+    let (rust_records, first_timestamp_ms) = match extract_records(
+        &mut env,
+        &records_array,
+        log_handle.critical_copy,
+        log_handle.clock.stamps(log_handle.receive_timestamps),
+    ) {
+        Ok(r) => r,
+        Err(e) => {
+            let _ = env.throw_new("dev/opendata/common/OpenDataNativeException", e.to_string());
+            return std::ptr::null_mut();
+        }
+    };
+
+    if rust_records.is_empty() {
+        let _ = env.throw_new(
+            "java/lang/IllegalArgumentException",
+            "Records array is empty",
+        );
+        return std::ptr::null_mut();
+    }
+
+    let rust_records = chunking::split(rust_records, log_handle.chunk_threshold);
+    let Some(_charge) = charge_append(&mut env, &log_handle.memory, &rust_records) else {
+        return std::ptr::null_mut();
+    };
+    let summary = log_handle.slow_log.summarize(&rust_records);
+    let size = jfr::summarize(&log_handle.tags, &rust_records);
+    timer.mark_submitted();
+    let Some(blocked) = enter_blocking_call(&mut env, log_handle) else {
+        return std::ptr::null_mut();
+    };
+    let tracked = watchdog::track_call(log_handle.watchdog.as_ref(), "append");
+    let result = log_handle.runtime_handle.block_on(async {
+        timer.mark_storage_started();
+        let result = watchdog::guard_call(log_handle.watchdog.as_ref(), "append", async {
+            // Exclusive: no other append may land between the check and the append
+            let _append = log_handle.append_lock.write().await;
+            let log = log_handle.log.current();
+            let start = (expected_tail_sequence + 1) as u64;
+            let mut iter = log
+                .scan(key_bytes, start..)
+                .await
+                .map_err(ConditionalAppendError::Storage)?;
+            if let Some(entry) = iter.next().await.map_err(ConditionalAppendError::Storage)? {
+                return Err(ConditionalAppendError::Conflict(entry.sequence));
+            }
+            let result = append_cached(
+                &log,
+                log_handle.tail_cache.as_deref(),
+                &log_handle.watches,
+                rust_records,
+            )
+            .await
+            .map_err(ConditionalAppendError::Storage)?;
+            if ack_mode == AckMode::Durable {
+                log.flush().await.map_err(ConditionalAppendError::Storage)?;
+            }
+            Ok::<AppendResult, ConditionalAppendError>(result)
+        })
+        .await;
+        timer.mark_storage_finished();
+        result
+    });
+    drop(tracked);
+    drop(blocked);
+    log_handle
+        .latency
+        .record(Operation::Append, timer.storage_latency());
+    log_handle.slow_log.append(
+        timer.storage_latency(),
+        summary,
+        result
+            .as_ref()
+            .ok()
+            .and_then(|r| r.as_ref().ok())
+            .map(|r| r.start_sequence),
+    );
+    jfr::append(timer.storage_latency(), size, matches!(result, Ok(Ok(_))));
+
+    let result = match result {
+        Ok(r) => r,
+        Err(stuck) => {
+            throw_stuck_call(&mut env, &stuck);
+            return std::ptr::null_mut();
+        }
+    };
+
+    let java_result = match result {
+        Ok(append_result) => {
+            match create_append_result(&mut env, append_result.start_sequence, first_timestamp_ms) {
+                Ok(obj) => obj.into_raw(),
+                Err(e) => {
+                    let _ =
+                        env.throw_new("dev/opendata/common/OpenDataNativeException", e.to_string());
+                    std::ptr::null_mut()
+                }
+            }
+        }
+        Err(ConditionalAppendError::Conflict(actual)) => {
+            throw_sequence_conflict(&mut env, expected_tail_sequence, actual);
+            std::ptr::null_mut()
+        }
+        Err(ConditionalAppendError::Storage(e)) => {
+            let _ = env.throw_new("dev/opendata/common/OpenDataNativeException", e.to_string());
+            std::ptr::null_mut()
+        }
+    };
+    log_handle
+        .profiler
+        .record(Operation::Append, &timer.finish());
+    java_result
+}
+
+/// Appends a batch of records at most once per producer batch sequence.
+///
+/// The batch is identified by `producer_id`, `batch_sequence`, and the key of
+/// its first record. If the same batch is still in the producer's dedup window
+/// it is not appended again, and the AppendResult of the original append is
+/// returned so retries after a timeout are transparent to the caller.
+///
+/// # Arguments
+/// * `handle` - Native LogDb pointer
+/// * `records` - Array of Java Record objects (each with key, value, timestampMs)
+/// * `producer_id` - Caller-chosen producer identity
+/// * `batch_sequence` - Per-producer batch number, increasing across batches
+/// * `ack_mode` - Ordinal of the Java `AckMode` enum; `DURABLE` flushes before returning
+///
+/// # Safety
+/// JNI function - handle must be a valid pointer returned by nativeCreate.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "system" fn Java_dev_opendata_LogDb_nativeAppendIdempotent<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    handle: jlong,
+    records: jobjectArray,
+    producer_id: jlong,
+    batch_sequence: jlong,
+    ack_mode: jint,
+) -> jobject {
+    let mut timer = CallTimer::start();
+
+    if handle == 0 {
+        let _ = env.throw_new("java/lang/NullPointerException", "LogDb handle is null");
+        return std::ptr::null_mut();
+    }
+
+    let Some(ack_mode) = AckMode::from_ordinal(ack_mode) else {
+        let _ = env.throw_new(
+            "java/lang/IllegalArgumentException",
+            format!("Unknown ack mode ordinal: {}", ack_mode),
+        );
+        return std::ptr::null_mut();
+    };
+
+    let log_handle = unsafe { &*(handle as *const LogHandle) };
+
+    let records_array = unsafe { JObjectArray::from_raw(records) };
+    let (rust_records, first_timestamp_ms) = match extract_records(
+        &mut env,
+        &records_array,
+        log_handle.critical_copy,
+        log_handle.clock.stamps(log_handle.receive_timestamps),
+    ) {
+        Ok(r) => r,
+        Err(e) => {
+            let _ = env.throw_new("dev/opendata/common/OpenDataNativeException", e.to_string());
+            return std::ptr::null_mut();
+        }
+    };
+
+    let Some(first_key) = rust_records.first().map(|r| r.key.clone()) else {
+        let _ = env.throw_new(
+            "java/lang/IllegalArgumentException",
+            "Records array is empty",
+        );
+        return std::ptr::null_mut();
+    };
+
+    let producer_id = producer_id as u64;
+    let batch_sequence = batch_sequence as u64;
+    let window = log_handle.dedup.window(producer_id, &first_key);
+
+    let rust_records = chunking::split(rust_records, log_handle.chunk_threshold);
+    let Some(_charge) = charge_append(&mut env, &log_handle.memory, &rust_records) else {
+        return std::ptr::null_mut();
+    };
+    let summary = log_handle.slow_log.summarize(&rust_records);
+    let size = jfr::summarize(&log_handle.tags, &rust_records);
+    timer.mark_submitted();
+    let Some(blocked) = enter_blocking_call(&mut env, log_handle) else {
+        return std::ptr::null_mut();
+    };
+    let tracked = watchdog::track_call(log_handle.watchdog.as_ref(), "append");
+    let result = log_handle.runtime_handle.block_on(async {
+        timer.mark_storage_started();
+        let result = watchdog::guard_call(log_handle.watchdog.as_ref(), "append", async {
+            // Held across check and append so concurrent retries cannot both land
+            let mut window = window.lock().await;
+            match DedupWindows::check(&window, batch_sequence) {
+                DedupCheck::Duplicate(start_sequence) => {
+                    return Ok(start_sequence);
+                }
+                DedupCheck::BeforeWindow { oldest } => {
+                    return Err(IdempotentAppendError::BeforeWindow { oldest });
+                }
+                DedupCheck::New => {}
+            }
+            let _append = log_handle.append_lock.read().await;
+            let log = log_handle.log.current();
+            let result = append_cached(
+                &log,
+                log_handle.tail_cache.as_deref(),
+                &log_handle.watches,
+                rust_records,
+            )
+            .await
+            .map_err(IdempotentAppendError::Storage)?;
+            if ack_mode == AckMode::Durable {
+                log.flush().await.map_err(IdempotentAppendError::Storage)?;
+            }
+            log_handle
+                .dedup
+                .record(&mut window, batch_sequence, result.start_sequence);
+            Ok::<u64, IdempotentAppendError>(result.start_sequence)
+        })
+        .await;
+        timer.mark_storage_finished();
+        result
+    });
+    drop(tracked);
+    drop(blocked);
+    log_handle
+        .latency
+        .record(Operation::Append, timer.storage_latency());
+    log_handle.slow_log.append(
+        timer.storage_latency(),
+        summary,
+        result.as_ref().ok().and_then(|r| r.as_ref().ok()).copied(),
+    );
+    jfr::append(timer.storage_latency(), size, matches!(result, Ok(Ok(_))));
+
+    let result = match result {
+        Ok(r) => r,
+        Err(stuck) => {
+            throw_stuck_call(&mut env, &stuck);
+            return std::ptr::null_mut();
+        }
+    };
+
+    let java_result = match result {
+        Ok(start_sequence) => {
+            match create_append_result(&mut env, start_sequence, first_timestamp_ms) {
+                Ok(obj) => obj.into_raw(),
+                Err(e) => {
+                    let _ =
+                        env.throw_new("dev/opendata/common/OpenDataNativeException", e.to_string());
+                    std::ptr::null_mut()
+                }
+            }
+        }
+        Err(IdempotentAppendError::BeforeWindow { oldest }) => {
+            let _ = env.throw_new(
+                "java/lang/IllegalStateException",
+                format!(
+                    "Batch sequence {} for producer {} is older than its dedup window (oldest {})",
+                    batch_sequence, producer_id, oldest
+                ),
+            );
+            std::ptr::null_mut()
+        }
+        Err(IdempotentAppendError::Storage(e)) => {
+            let _ = env.throw_new("dev/opendata/common/OpenDataNativeException", e.to_string());
+            std::ptr::null_mut()
+        }
+    };
+    log_handle
+        .profiler
+        .record(Operation::Append, &timer.finish());
+    java_result
+}
+
+/// Failure of an idempotent append.
+enum IdempotentAppendError {
+    /// The batch sequence precedes the producer's dedup window
+    BeforeWindow { oldest: u64 },
+    /// The underlying append or flush failed
+    Storage(log::Error),
+}
+
+/// Failure of a conditional append.
+enum ConditionalAppendError {
+    /// The key has an entry at this sequence, past the expected tail
+    Conflict(u64),
+    /// The underlying scan, append, or flush failed
+    Storage(log::Error),
+}
+
+/// Throws a Java SequenceConflictException for a failed conditional append.
+fn throw_sequence_conflict(env: &mut JNIEnv<'_>, expected: i64, actual: u64) {
+    let exception = env.new_object(
+        "dev/opendata/SequenceConflictException",
+        "(JJ)V",
+        &[JValue::Long(expected), JValue::Long(actual as i64)],
+    );
+    match exception {
+        Ok(obj) => {
+            let _ = env.throw(JThrowable::from(obj));
+        }
+        Err(e) => {
+            let _ = env.throw_new("dev/opendata/common/OpenDataNativeException", e.to_string());
+        }
+    }
+}
+
+/// Converts a Java Record[] into Rust records with timestamp headers.
+///
+/// Returns the records and the timestamp of the first record (0 if empty).
+/// With `critical`, values are copied out of the Java heap through
+/// `GetPrimitiveArrayCritical`, see `copy_value_with_timestamp`. With a
+/// timestamp in `stamps`, every record takes it instead of its
+/// `timestampMs`; with a receive time, every value records it.
+fn extract_records(
+    env: &mut JNIEnv<'_>,
+    records_array: &JObjectArray<'_>,
+    critical: bool,
+    stamps: Stamps,
+) -> Result<(Vec<Record>, i64), jni::errors::Error> {
+    let len = env.get_array_length(records_array)? as usize;
+    let mut rust_records = Vec::with_capacity(len);
+    let mut first_timestamp_ms: i64 = 0;
+
+    for i in 0..len {
+        let record_obj = env.get_object_array_element(records_array, i as i32)?;
+
+        // Extract key byte[] from Record
+        let key_array: JByteArray = env
+            .call_method(&record_obj, "key", "()[B", &[])?
+            .l()?
+            .into();
+        let key_bytes = Bytes::from(env.convert_byte_array(&key_array)?);
+
+        // Extract value byte[] from Record
+        let value_array: JByteArray = env
+            .call_method(&record_obj, "value", "()[B", &[])?
+            .l()?
+            .into();
+
+        // Extract timestampMs from Record, unless the clock stamps it
+        let timestamp_ms = match stamps.record_ms {
+            Some(stamp) => stamp,
+            None => env
+                .call_method(&record_obj, "timestampMs", "()J", &[])?
+                .j()?,
+        };
+
+        if i == 0 {
+            first_timestamp_ms = timestamp_ms;
+        }
+
+        // Convert value with timestamp header
+        let value_bytes = copy_value_with_timestamp(
+            env,
+            &value_array,
+            timestamp_ms,
+            stamps.received_ms,
+            critical,
+        )?;
+
+        rust_records.push(Record {
+            key: key_bytes,
+            value: value_bytes,
+        });
+
+        env.delete_local_ref(key_array)?;
+        env.delete_local_ref(value_array)?;
+        env.delete_local_ref(record_obj)?;
+    }
+
+    Ok((rust_records, first_timestamp_ms))
+}
+
+/// Converts a Java Record[] into Rust records holding the values as passed,
+/// without timestamp headers.
+fn extract_raw_records(
+    env: &mut JNIEnv<'_>,
+    records_array: &JObjectArray<'_>,
+) -> Result<Vec<Record>, jni::errors::Error> {
+    let len = env.get_array_length(records_array)? as usize;
+    let mut rust_records = Vec::with_capacity(len);
+    for i in 0..len {
+        let record_obj = env.get_object_array_element(records_array, i as i32)?;
+        let key_array: JByteArray = env
+            .call_method(&record_obj, "key", "()[B", &[])?
+            .l()?
+            .into();
+        let value_array: JByteArray = env
+            .call_method(&record_obj, "value", "()[B", &[])?
+            .l()?
+            .into();
+        rust_records.push(Record {
+            key: Bytes::from(env.convert_byte_array(&key_array)?),
+            value: Bytes::from(env.convert_byte_array(&value_array)?),
+        });
+        env.delete_local_ref(key_array)?;
+        env.delete_local_ref(value_array)?;
+        env.delete_local_ref(record_obj)?;
+    }
+    Ok(rust_records)
+}
+
+/// Copies a Java byte array into a Rust buffer with a prepended timestamp header.
+///
+/// This avoids an intermediate allocation by copying directly into the final buffer.
+/// With `received_ms`, the header also records the receive time, see `skew`.
+///
+/// With `critical`, the payload is read from the array's elements in place
+/// through `GetPrimitiveArrayCritical` instead of `GetByteArrayRegion`. The
+/// critical section spans only the copy into the buffer: no JNI call is made
+/// and nothing awaits storage while it is held, as the JVM may hold off
+/// garbage collection until it is released.
+fn copy_value_with_timestamp(
+    env: &mut JNIEnv<'_>,
+    value: &JByteArray<'_>,
+    timestamp_ms: i64,
+    received_ms: Option<i64>,
+    critical: bool,
+) -> Result<Bytes, jni::errors::Error> {
+    let payload_len = env.get_array_length(value)? as usize;
+    let header_size = skew::header_size(received_ms);
+
+    // Allocate final buffer: 8-byte header (16 with a receive time) + payload
+    let mut buffer = vec![0u8; header_size + payload_len];
+
+    // Write timestamp header (big-endian)
+    skew::write_header(&mut buffer, timestamp_ms, received_ms);
+
+    if payload_len > 0 && critical {
+        // Safety: the elements are only read, and released before the next JNI call
+        let elements = unsafe { env.get_array_elements_critical(value, ReleaseMode::NoCopyBack)? };
+        // Safety: i8 and u8 have the same layout
+        let source =
+            unsafe { std::slice::from_raw_parts(elements.as_ptr() as *const u8, elements.len()) };
+        buffer[header_size..].copy_from_slice(source);
+        drop(elements);
+    } else if payload_len > 0 {
+        // Copy payload directly from Java into buffer, avoiding intermediate Vec
+        // Safety: buffer[header_size..] has exactly payload_len bytes
+        // get_byte_array_region expects i8 slice, so we need to cast
+        let dest = &mut buffer[header_size..];
+        let dest_i8 =
+            unsafe { std::slice::from_raw_parts_mut(dest.as_mut_ptr() as *mut i8, payload_len) };
+        env.get_byte_array_region(value, 0, dest_i8)?;
+    }
+
+    Ok(Bytes::from(buffer))
+}
+
+/// Builds a timestamped value with a generated payload of `payload_size` bytes.
+///
+/// The payload is filled from an xorshift generator so it does not compress
+/// unrealistically well.
+fn synthetic_value(timestamp_ms: i64, payload_size: usize, seed: u64) -> Bytes {
+    let mut buffer = Vec::with_capacity(TIMESTAMP_HEADER_SIZE + payload_size);
+    buffer.extend_from_slice(&timestamp_ms.to_be_bytes());
+
+    // xorshift64 requires a non-zero state
+    let mut state = seed | 1;
+    while buffer.len() < TIMESTAMP_HEADER_SIZE + payload_size {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        let remaining = TIMESTAMP_HEADER_SIZE + payload_size - buffer.len();
+        buffer.extend_from_slice(&state.to_le_bytes()[..remaining.min(8)]);
+    }
+
+    Bytes::from(buffer)
+}
+
+/// Flushes all pending writes to durable storage.
+///
+/// # Safety
+/// JNI function - handle must be a valid pointer returned by nativeCreate.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "system" fn Java_dev_opendata_LogDb_nativeFlush<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    handle: jlong,
+) {
+    if handle == 0 {
+        let _ = env.throw_new("java/lang/NullPointerException", "LogDb handle is null");
+        return;
+    }
+
+    let log_handle = unsafe { &*(handle as *const LogHandle) };
+
+    let started = Instant::now();
+    let Some(_blocked) = enter_blocking_call(&mut env, log_handle) else {
+        return;
+    };
+    let _tracked = watchdog::track_call(log_handle.watchdog.as_ref(), "flush");
+    let result = log_handle.runtime_handle.block_on(async {
+        watchdog::guard_call(
+            log_handle.watchdog.as_ref(),
+            "flush",
+            log_handle.log.current().flush(),
+        )
+        .await
+    });
+    if let Ok(result) = &result {
+        log_handle.log.observe(result);
+    }
+    jfr::flush(
+        &log_handle.tags,
+        started.elapsed(),
+        matches!(result, Ok(Ok(()))),
+    );
+
+    match result {
+        Ok(Ok(())) => {}
+        Ok(Err(e)) => {
+            let _ = env.throw_new("dev/opendata/common/OpenDataNativeException", e.to_string());
+        }
+        Err(stuck) => throw_stuck_call(&mut env, &stuck),
+    }
+}
+
+/// Waits until every append acknowledged before the call is durable,
+/// returning the durable tail of each key.
+///
+/// See `durability.rs`.
+///
+/// # Returns
+/// KeyWatermark[] with the tail, one past the last durable sequence, of each
+/// key appended through the handle, ordered by key
+///
+/// # Safety
+/// JNI function - handle must be a valid pointer returned by nativeCreate.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "system" fn Java_dev_opendata_LogDb_nativeSync<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    handle: jlong,
+) -> jobjectArray {
+    if handle == 0 {
+        let _ = env.throw_new("java/lang/NullPointerException", "LogDb handle is null");
+        return std::ptr::null_mut();
+    }
+
+    let log_handle = unsafe { &*(handle as *const LogHandle) };
+
+    // Appends acknowledged from here on are not covered by the flush
+    let tails = log_handle.watches.appended().snapshot();
+    let started = Instant::now();
+    let Some(_blocked) = enter_blocking_call(&mut env, log_handle) else {
+        return std::ptr::null_mut();
+    };
+    let _tracked = watchdog::track_call(log_handle.watchdog.as_ref(), "sync");
+    let result = log_handle.runtime_handle.block_on(async {
+        watchdog::guard_call(
+            log_handle.watchdog.as_ref(),
+            "sync",
+            log_handle.log.current().flush(),
+        )
+        .await
+    });
+    if let Ok(result) = &result {
+        log_handle.log.observe(result);
+    }
+    jfr::flush(
+        &log_handle.tags,
+        started.elapsed(),
+        matches!(result, Ok(Ok(()))),
+    );
+
+    match result {
+        Ok(Ok(())) => match create_key_watermark_array(&mut env, &tails) {
+            Ok(arr) => arr,
+            Err(e) => {
+                let _ = env.throw_new("dev/opendata/common/OpenDataNativeException", e.to_string());
+                std::ptr::null_mut()
+            }
+        },
+        Ok(Err(e)) => {
+            let _ = env.throw_new("dev/opendata/common/OpenDataNativeException", e.to_string());
+            std::ptr::null_mut()
+        }
+        Err(stuck) => {
+            throw_stuck_call(&mut env, &stuck);
+            std::ptr::null_mut()
+        }
+    }
+}
+
+/// Drains every open log, then stops native threads from attaching to the
+/// JVM.
+///
+/// Called by the shutdown hook `NativeThreads` registers.
+#[no_mangle]
+pub extern "system" fn Java_dev_opendata_NativeThreads_nativeShutdown<'local>(
+    _env: JNIEnv<'local>,
+    _class: JClass<'local>,
+) {
+    handles::drain_all(SHUTDOWN_DRAIN_TIMEOUT);
+    attach::shutdown();
+}
+
+/// Drains every open log as the library is unloaded.
+///
+/// Only runs when the class loader that loaded the library is collected,
+/// which the JVM does not do at exit; the shutdown hook covers that case.
+#[no_mangle]
+pub extern "system" fn JNI_OnUnload(_vm: *mut jni::sys::JavaVM, _reserved: *mut std::ffi::c_void) {
+    handles::drain_all(SHUTDOWN_DRAIN_TIMEOUT);
+    attach::shutdown();
+}
+
+/// Waits for the submitted operations of every open log and flushes it.
+///
+/// # Arguments
+/// * `timeout_ms` - Longest time to wait for all logs, in milliseconds
+///
+/// # Returns
+/// Whether every log was flushed in time
+#[no_mangle]
+pub extern "system" fn Java_dev_opendata_LogDb_nativeDrainAll<'local>(
+    _env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    timeout_ms: jlong,
+) -> jboolean {
+    let timeout = std::time::Duration::from_millis(timeout_ms.max(0) as u64);
+    handles::drain_all(timeout) as jboolean
+}
+
+/// Records whether a JFR recording enables native operation events.
+///
+/// Called by `NativeEvents` whenever a recording starts or stops.
+#[no_mangle]
+pub extern "system" fn Java_dev_opendata_NativeEvents_nativeSetEnabled<'local>(
+    _env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    enabled: jboolean,
+) {
+    jfr::set_enabled(enabled != 0);
+}
+
+/// Commits a consumer group's offset for a key.
+///
+/// The offset is appended under a reserved key and flushed before returning,
+/// so it survives a restart of the JVM.
+///
+/// # Arguments
+/// * `handle` - Native LogDb pointer
+/// * `group` - Consumer group name
+/// * `key` - The consumed key
+/// * `sequence` - The sequence to commit
+///
+/// # Safety
+/// JNI function - handle must be a valid pointer returned by nativeCreate.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "system" fn Java_dev_opendata_LogDb_nativeCommitOffset<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    handle: jlong,
+    group: JString<'local>,
+    key: JByteArray<'local>,
+    sequence: jlong,
+) {
+    if handle == 0 {
+        let _ = env.throw_new("java/lang/NullPointerException", "LogDb handle is null");
+        return;
+    }
+
+    let log_handle = unsafe { &*(handle as *const LogHandle) };
+
+    let offset_key = match extract_offset_key(&mut env, &group, &key) {
+        Ok(k) => k,
+        Err(e) => {
+            let _ = env.throw_new("java/lang/IllegalArgumentException", e);
+            return;
+        }
+    };
+
+    let Some(_blocked) = enter_blocking_call(&mut env, log_handle) else {
+        return;
+    };
+    let _tracked = watchdog::track_call(log_handle.watchdog.as_ref(), "commitOffset");
+    let result = log_handle.runtime_handle.block_on(async {
+        let _append = log_handle.append_lock.read().await;
+        append_offset(
+            &log_handle.log.current(),
+            offset_key.clone(),
+            sequence as u64,
+        )
+        .await
+    });
+
+    match result {
+        Ok(entry_sequence) => log_handle.offsets.update(
+            offset_key,
+            CachedOffset {
+                entry_sequence,
+                offset: sequence as u64,
+            },
+        ),
+        Err(e) => {
+            let _ = env.throw_new("dev/opendata/common/OpenDataNativeException", e.to_string());
+        }
+    }
+}
+
+/// Fetches a consumer group's committed offset for a key.
+///
+/// # Returns
+/// The committed sequence, or -1 if the group has not committed for the key
+///
+/// # Safety
+/// JNI function - handle must be a valid pointer returned by nativeCreate.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "system" fn Java_dev_opendata_LogDb_nativeFetchOffset<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    handle: jlong,
+    group: JString<'local>,
+    key: JByteArray<'local>,
+) -> jlong {
+    if handle == 0 {
+        let _ = env.throw_new("java/lang/NullPointerException", "LogDb handle is null");
+        return -1;
+    }
+
+    let log_handle = unsafe { &*(handle as *const LogHandle) };
+
+    let offset_key = match extract_offset_key(&mut env, &group, &key) {
+        Ok(k) => k,
+        Err(e) => {
+            let _ = env.throw_new("java/lang/IllegalArgumentException", e);
+            return -1;
+        }
+    };
+
+    // Only scan offset entries committed since the last one this handle saw
+    let cached = log_handle.offsets.get(&offset_key);
+    let start = cached.map_or(0, |c| c.entry_sequence + 1);
+    let Some(_blocked) = enter_blocking_call(&mut env, log_handle) else {
+        return -1;
+    };
+    let _tracked = watchdog::track_call(log_handle.watchdog.as_ref(), "fetchOffset");
+    let result = log_handle.runtime_handle.block_on(async {
+        let log = log_handle.log.current();
+        let mut iter = log.scan(offset_key.clone(), start..).await?;
+        let mut latest = cached;
+        while let Some(entry) = iter.next().await? {
+            if let Some(offset) = offsets::decode_offset(&entry.value) {
+                latest = Some(CachedOffset {
+                    entry_sequence: entry.sequence,
+                    offset,
+                });
+            }
+        }
+        Ok::<Option<CachedOffset>, log::Error>(latest)
+    });
+
+    match result {
+        Ok(Some(latest)) => {
+            log_handle.offsets.update(offset_key, latest);
+            latest.offset as jlong
+        }
+        Ok(None) => -1,
+        Err(e) => {
+            let _ = env.throw_new("dev/opendata/common/OpenDataNativeException", e.to_string());
+            -1
+        }
+    }
+}
+
+/// Records a consumer-group heartbeat and returns the member's assigned keys.
+///
+/// Appends a heartbeat for `member`, replays the group's membership events
+/// since the last call on this handle, and assigns `keys` over the live
+/// members (see the `coordination` module).
+///
+/// # Arguments
+/// * `handle` - Native LogDb pointer
+/// * `group` - The consumer group
+/// * `member` - This member's id, unique within the group
+/// * `keys` - Java byte[][] of the keys shared by the group
+/// * `session_timeout_ms` - How long a member stays live without heartbeats
+///
+/// # Returns
+/// Java int[] of indexes into `keys` assigned to this member
+///
+/// # Safety
+/// JNI function - handle must be a valid pointer returned by nativeCreate.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "system" fn Java_dev_opendata_LogDb_nativeGroupHeartbeat<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    handle: jlong,
+    group: JString<'local>,
+    member: JString<'local>,
+    keys: JObjectArray<'local>,
+    session_timeout_ms: jlong,
+) -> jintArray {
+    if handle == 0 {
+        let _ = env.throw_new("java/lang/NullPointerException", "LogDb handle is null");
+        return std::ptr::null_mut();
+    }
+
+    let log_handle = unsafe { &*(handle as *const LogHandle) };
+
+    let names = extract_group_member(&mut env, &group, &member);
+    let (group, member) = match names {
+        Ok(n) => n,
+        Err(e) => {
+            let _ = env.throw_new("java/lang/IllegalArgumentException", e);
+            return std::ptr::null_mut();
+        }
+    };
+
+    let keys = match extract_byte_arrays(&mut env, &keys) {
+        Ok(k) => k,
+        Err(e) => {
+            let _ = env.throw_new("dev/opendata/common/OpenDataNativeException", e.to_string());
+            return std::ptr::null_mut();
+        }
+    };
+
+    let group_key = coordination::group_key(&group);
+    let now_ms = current_timestamp_ms();
+    let heartbeat = MemberEvent::Heartbeat {
+        member: member.clone(),
+        timestamp_ms: now_ms,
+    };
+
+    let Some(_blocked) = enter_blocking_call(&mut env, log_handle) else {
+        return std::ptr::null_mut();
+    };
+    let _tracked = watchdog::track_call(log_handle.watchdog.as_ref(), "groupHeartbeat");
+    let result = log_handle.runtime_handle.block_on(async {
+        let log = log_handle.log.current();
+        {
+            let _append = log_handle.append_lock.read().await;
+            log.append(vec![Record {
+                key: group_key.clone(),
+                value: heartbeat.encode(),
+            }])
+            .await?;
+        }
+        let mut view = log_handle.groups.get(&group_key);
+        let mut iter = log.scan(group_key.clone(), view.next_sequence()..).await?;
+        while let Some(entry) = iter.next().await? {
+            if let Some(event) = MemberEvent::decode(&entry.value) {
+                view.apply(entry.sequence, event);
+            }
+        }
+        Ok::<_, log::Error>(view)
+    });
+
+    let view = match result {
+        Ok(v) => v,
+        Err(e) => {
+            let _ = env.throw_new("dev/opendata/common/OpenDataNativeException", e.to_string());
+            return std::ptr::null_mut();
+        }
+    };
+
+    let live = view.live_members(now_ms, session_timeout_ms);
+    let assigned: Vec<jint> = coordination::assign(&keys, &live, &member)
+        .into_iter()
+        .map(|i| i as jint)
+        .collect();
+    log_handle.groups.update(group_key, view);
+
+    let array = match env.new_int_array(assigned.len() as i32) {
+        Ok(a) => a,
+        Err(e) => {
+            let _ = env.throw_new("dev/opendata/common/OpenDataNativeException", e.to_string());
+            return std::ptr::null_mut();
+        }
+    };
+    if let Err(e) = env.set_int_array_region(&array, 0, &assigned) {
+        let _ = env.throw_new("dev/opendata/common/OpenDataNativeException", e.to_string());
+        return std::ptr::null_mut();
+    }
+    array.into_raw()
+}
+
+/// Records that a member left its consumer group.
+///
+/// The member's keys are reassigned as soon as the other members next heartbeat.
+///
+/// # Safety
+/// JNI function - handle must be a valid pointer returned by nativeCreate.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "system" fn Java_dev_opendata_LogDb_nativeGroupLeave<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    handle: jlong,
+    group: JString<'local>,
+    member: JString<'local>,
+) {
+    if handle == 0 {
+        let _ = env.throw_new("java/lang/NullPointerException", "LogDb handle is null");
+        return;
+    }
+
+    let log_handle = unsafe { &*(handle as *const LogHandle) };
+
+    let names = extract_group_member(&mut env, &group, &member);
+    let (group, member) = match names {
+        Ok(n) => n,
+        Err(e) => {
+            let _ = env.throw_new("java/lang/IllegalArgumentException", e);
+            return;
+        }
+    };
+
+    let leave = MemberEvent::Leave {
+        member,
+        timestamp_ms: current_timestamp_ms(),
+    };
+
+    let Some(_blocked) = enter_blocking_call(&mut env, log_handle) else {
+        return;
+    };
+    let _tracked = watchdog::track_call(log_handle.watchdog.as_ref(), "groupLeave");
+    let result = log_handle.runtime_handle.block_on(async {
+        let _append = log_handle.append_lock.read().await;
+        log_handle
+            .log
+            .current()
+            .append(vec![Record {
+                key: coordination::group_key(&group),
+                value: leave.encode(),
+            }])
+            .await
+    });
+
+    if let Err(e) = result {
+        let _ = env.throw_new("dev/opendata/common/OpenDataNativeException", e.to_string());
+    }
+}
+
+/// Closes and frees a LogDb instance and its associated runtime.
+///
+/// # Safety
+/// JNI function - handle must be a valid pointer returned by nativeCreate.
+#[no_mangle]
+pub extern "system" fn Java_dev_opendata_LogDb_nativeClose<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    handle: jlong,
+) {
+    if handle != 0 {
+        let log_handle = take_log_handle(handle);
+        if let Err(e) = close_log_handle(*log_handle) {
+            let _ = env.throw_new("dev/opendata/common/OpenDataNativeException", e.to_string());
+        }
+    }
+}
+
+/// Closes and frees a LogDb instance on a close thread, without blocking.
+///
+/// Suited to `Cleaner` actions; see `closing.rs`. A failed close is logged.
+///
+/// # Returns
+/// Token passed to nativeAwaitClose, or 0 for a null handle
+///
+/// # Safety
+/// JNI function - handle must be a valid pointer returned by nativeCreate,
+/// and must not be used once this returns.
+#[no_mangle]
+pub extern "system" fn Java_dev_opendata_LogDb_nativeCloseAsync<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    handle: jlong,
+) -> jlong {
+    if handle == 0 {
+        return 0;
+    }
+    let spawned = closing::spawn(move || {
+        let log_handle = take_log_handle(handle);
+        let tags = Arc::clone(&log_handle.tags);
+        if let Err(e) = close_log_handle(*log_handle) {
+            logging::warn(
+                LOG_TARGET,
+                &tags.log_line(&format!("Failed to close abandoned LogDb: {}", e)),
+            );
+        }
+    });
+    match spawned {
+        Ok(token) => token as jlong,
+        Err(e) => {
+            let _ = env.throw_new("dev/opendata/common/OpenDataNativeException", e.to_string());
+            0
+        }
+    }
+}
+
+/// Waits for a close started by nativeCloseAsync.
+///
+/// # Arguments
+/// * `token` - Token returned by nativeCloseAsync
+/// * `timeout_ms` - Maximum time to wait
+///
+/// # Returns
+/// Whether the close finished within the timeout
+#[no_mangle]
+pub extern "system" fn Java_dev_opendata_LogDb_nativeAwaitClose<'local>(
+    _env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    token: jlong,
+    timeout_ms: jlong,
+) -> jboolean {
+    let timeout = std::time::Duration::from_millis(timeout_ms.max(0) as u64);
+    if closing::wait(token as u64, timeout) {
+        JNI_TRUE
+    } else {
+        JNI_FALSE
+    }
+}
+
+/// Records the hash of the Java stack that opened a LogDb, see `handles.rs`.
+///
+/// # Safety
+/// JNI function - handle must be a valid pointer returned by nativeCreate.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "system" fn Java_dev_opendata_LogDb_nativeSetOrigin<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    handle: jlong,
+    stack_hash: jint,
+) {
+    if handle == 0 {
+        let _ = env.throw_new("java/lang/NullPointerException", "LogDb handle is null");
+        return;
+    }
+
+    let log_handle = unsafe { &*(handle as *const LogHandle) };
+    log_handle.registration.set_stack_hash(stack_hash);
+}
+
+/// Lists the live LogDb and LogDbReader handles of the process.
+///
+/// # Returns
+/// HandleInfo[] of the live handles, oldest first
+#[no_mangle]
+pub extern "system" fn Java_dev_opendata_LogDb_nativeDumpHandles<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+) -> jobjectArray {
+    match create_handle_info_array(&mut env, &handles::live()) {
+        Ok(array) => array,
+        Err(e) => {
+            let _ = env.throw_new("dev/opendata/common/OpenDataNativeException", e.to_string());
+            std::ptr::null_mut()
+        }
+    }
+}
+
+/// SLF4J logger failed asynchronous opens and closes, drains, and periodic
+/// flushes are logged under.
+const LOG_TARGET: &str = "dev.opendata.LogDb";
+
+/// Longest time the shutdown hook waits for open logs to drain.
+const SHUTDOWN_DRAIN_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(30);
+
+/// Closes a LogDb, waiting for calls in flight, and stops its runtimes.
+fn close_log_handle(log_handle: LogHandle) -> Result<(), log::Error> {
+    // Destructure to take ownership of components
+    let LogHandle {
+        log,
+        runtime_handle,
+        runtime,
+        compaction_runtime,
+        watchdog,
+        bridge,
+        flush_task,
+        ..
+    } = log_handle;
+
+    // Stop the watchdog before closing so it cannot report the close itself
+    drop(watchdog);
+    // Bridged calls in flight hold the log; let them finish
+    drop(bridge);
+    if let Some(task) = flush_task
+        .into_inner()
+        .unwrap_or_else(PoisonError::into_inner)
+    {
+        task.abort();
+    }
+
+    // Close the log using block_on. Readers only hold weak references,
+    // upgraded for the duration of an offset commit, so wait those out.
+    let result = runtime_handle.block_on(async {
+        let log = reopen::unwrap_when_unused(log).await.into_current();
+        reopen::unwrap_when_unused(log).await.close().await
+    });
+
+    // Shutdown the runtimes
+    if let Some(rt) = compaction_runtime {
+        rt.shutdown_background();
+    }
+    if let Some(rt) = runtime {
+        rt.shutdown_background();
+    }
+    result
+}
+
+/// Waits for a log's submitted operations and flushes it, by `deadline`.
+///
+/// Returns whether the log was flushed, or was already closed.
+fn drain_log(
+    log: &Weak<ReopeningLog>,
+    runtime: &Handle,
+    completions: &CompletionQueue,
+    tags: &Tags,
+    deadline: Instant,
+) -> bool {
+    // Holding the log makes a concurrent close wait for the flush
+    let Some(log) = log.upgrade() else {
+        return true;
+    };
+    if !completions.wait_idle(deadline) {
+        logging::warn(
+            LOG_TARGET,
+            &tags.log_line("Submitted operations still in flight at shutdown"),
+        );
+    }
+    let remaining = deadline.saturating_duration_since(Instant::now());
+    let flushed = runtime.block_on(tokio::time::timeout(remaining, log.current().flush()));
+    match flushed {
+        Ok(Ok(())) => true,
+        Ok(Err(e)) => {
+            logging::warn(
+                LOG_TARGET,
+                &tags.log_line(&format!("Failed to flush LogDb at shutdown: {}", e)),
+            );
+            false
+        }
+        Err(_) => {
+            logging::warn(
+                LOG_TARGET,
+                &tags.log_line("Timed out flushing LogDb at shutdown"),
+            );
+            false
+        }
+    }
+}
+
+/// Scans a key on the calling thread, as `nativeScan` and `nativeScanInto` do.
+///
+/// Returns None when a stuck call was thrown.
+#[allow(clippy::too_many_arguments)]
+fn scan_blocking(
+    env: &mut JNIEnv<'_>,
+    log_handle: &LogHandle,
+    key_bytes: Bytes,
+    start_seq: u64,
+    max: usize,
+    filter: &ScanFilter,
+    priority: Priority,
+    timer: &mut CallTimer,
+) -> Option<Result<Vec<LogEntry>, log::Error>> {
+    // Scan entries using the LogDb (which implements LogRead)
+    timer.mark_submitted();
+    let Some(blocked) = enter_blocking_call(env, log_handle) else {
+        return None;
+    };
+    let tracked = watchdog::track_call(log_handle.watchdog.as_ref(), "scan");
+    let entries_result = log_handle.runtime_handle.block_on(async {
+        if let Some(rate_limiter) = &log_handle.rate_limiter {
+            rate_limiter.admit_scan().await;
+        }
+        let _permit = log_handle.priorities.admit(priority).await;
+        timer.mark_storage_started();
+        let result = watchdog::guard_call(log_handle.watchdog.as_ref(), "scan", async {
+            if let Some(noop) = &log_handle.noop {
+                let now_ms = current_timestamp_ms();
+                let mut entries = noop.scan(&key_bytes, start_seq, max, now_ms);
+                filter.retain(&mut entries);
+                return Ok(entries);
+            }
+            if let Some(tail_cache) = &log_handle.tail_cache {
+                if let Some(mut entries) = tail_cache.scan(&key_bytes, start_seq, max) {
+                    filter.retain(&mut entries);
+                    log_handle.served.record_cache(&entries);
+                    return Ok(entries);
+                }
+            }
+            let log = log_handle.log.current();
+            let entries = scan_entries(&log, key_bytes.clone(), start_seq, max, filter).await?;
+            log_handle.served.record_storage(&entries);
+            Ok(entries)
+        })
+        .await;
+        timer.mark_storage_finished();
+        if let (Some(rate_limiter), Ok(Ok(entries))) = (&log_handle.rate_limiter, &result) {
+            rate_limiter.charge_scan(entries);
+        }
+        result
+    });
+    drop(tracked);
+    drop(blocked);
+    if let Ok(result) = &entries_result {
+        log_handle.log.observe(result);
+    }
+    log_handle
+        .latency
+        .record(Operation::Scan, timer.storage_latency());
+    log_handle.slow_log.scan(
+        timer.storage_latency(),
+        &key_bytes,
+        start_seq,
+        entries_result
+            .as_ref()
+            .ok()
+            .and_then(|r| r.as_ref().ok())
+            .map(Vec::as_slice),
+    );
+    jfr::scan(
+        &log_handle.tags,
+        timer.storage_latency(),
+        entries_result
+            .as_ref()
+            .ok()
+            .and_then(|r| r.as_ref().ok())
+            .map(Vec::as_slice),
+    );
+    if let Ok(Ok(entries)) = &entries_result {
+        log_handle.skew.record(entries);
+    }
+
+    match entries_result {
+        Ok(result) => Some(result),
+        Err(stuck) => {
+            throw_stuck_call(env, &stuck);
+            None
+        }
+    }
+}
+
+/// Scans entries from the log for a given key.
+///
+/// Uses the LogDb (which implements LogRead) to scan entries. `priority` is
+/// the ordinal of the Java `OperationPriority` enum, see `priority.rs`, and
+/// `traceparent` the W3C traceparent of the call or null, see
+/// `tracecontext.rs`.
+///
+/// # Safety
+/// JNI function - handle must be a valid pointer returned by nativeCreate.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "system" fn Java_dev_opendata_LogDb_nativeScan<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    handle: jlong,
+    key: JByteArray<'local>,
+    start_sequence: jlong,
+    max_entries: jlong,
+    priority: jint,
+    traceparent: JString<'local>,
+) -> jobjectArray {
+    let mut timer = CallTimer::start();
+
+    if handle == 0 {
+        let _ = env.throw_new("java/lang/NullPointerException", "LogDb handle is null");
+        return std::ptr::null_mut();
+    }
+
+    let Some(priority) = Priority::from_ordinal(priority) else {
+        let _ = env.throw_new(
+            "java/lang/IllegalArgumentException",
+            format!("Unknown priority ordinal: {}", priority),
+        );
+        return std::ptr::null_mut();
+    };
+
+    let log_handle = unsafe { &*(handle as *const LogHandle) };
+
+    let key_bytes = match env.convert_byte_array(&key) {
+        Ok(b) => Bytes::from(b),
+        Err(e) => {
+            let _ = env.throw_new("dev/opendata/common/OpenDataNativeException", e.to_string());
+            return std::ptr::null_mut();
+        }
+    };
+
+    let trace = match extract_trace_context(&mut env, &traceparent) {
+        Ok(trace) => trace,
+        Err(e) => {
+            let _ = env.throw_new("java/lang/IllegalArgumentException", e);
+            return std::ptr::null_mut();
+        }
+    };
+
+    let max = max_entries as usize;
+    let start_seq = start_sequence as u64;
+
+    let _trace = tracecontext::enter(trace);
+    let Some(entries_result) = scan_blocking(
+        &mut env,
+        log_handle,
+        key_bytes,
+        start_seq,
+        max,
+        &ScanFilter::ALL,
+        priority,
+        &mut timer,
+    ) else {
+        return std::ptr::null_mut();
+    };
+
+    let java_result = match entries_result {
+        Ok(entries) => match create_log_entry_array(&mut env, &entries) {
+            Ok(arr) => arr,
+            Err(e) => {
+                let _ = env.throw_new("dev/opendata/common/OpenDataNativeException", e.to_string());
+                std::ptr::null_mut()
+            }
+        },
+        Err(e) => {
+            let _ = env.throw_new("dev/opendata/common/OpenDataNativeException", e.to_string());
+            std::ptr::null_mut()
+        }
+    };
+    log_handle.profiler.record(Operation::Scan, &timer.finish());
+    java_result
+}
+
+/// Scans entries for a key, returning only those passing a filter.
+///
+/// # Arguments
+/// * `filter` - Java ScanFilter the entries are tested against natively
+///
+/// # Safety
+/// JNI function - handle must be a valid pointer returned by nativeCreate.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "system" fn Java_dev_opendata_LogDb_nativeScanFiltered<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    handle: jlong,
+    key: JByteArray<'local>,
+    start_sequence: jlong,
+    max_entries: jlong,
+    filter: JObject<'local>,
+) -> jobjectArray {
+    let mut timer = CallTimer::start();
+
+    if handle == 0 {
+        let _ = env.throw_new("java/lang/NullPointerException", "LogDb handle is null");
+        return std::ptr::null_mut();
+    }
+
+    let log_handle = unsafe { &*(handle as *const LogHandle) };
+
+    let key_bytes = match env.convert_byte_array(&key) {
+        Ok(b) => Bytes::from(b),
+        Err(e) => {
+            let _ = env.throw_new("dev/opendata/common/OpenDataNativeException", e.to_string());
+            return std::ptr::null_mut();
+        }
+    };
+    let filter = match extract_scan_filter(&mut env, &filter) {
+        Ok(filter) => filter,
+        Err(e) => {
+            let _ = env.throw_new("java/lang/IllegalArgumentException", e);
+            return std::ptr::null_mut();
+        }
+    };
+
+    let Some(entries_result) = scan_blocking(
+        &mut env,
+        log_handle,
+        key_bytes,
+        start_sequence as u64,
+        max_entries as usize,
+        &filter,
+        Priority::Foreground,
+        &mut timer,
+    ) else {
+        return std::ptr::null_mut();
+    };
+
+    let java_result = match entries_result {
+        Ok(entries) => match create_log_entry_array(&mut env, &entries) {
+            Ok(arr) => arr,
+            Err(e) => {
+                let _ = env.throw_new("dev/opendata/common/OpenDataNativeException", e.to_string());
+                std::ptr::null_mut()
+            }
+        },
+        Err(e) => {
+            let _ = env.throw_new("dev/opendata/common/OpenDataNativeException", e.to_string());
+            std::ptr::null_mut()
+        }
+    };
+    log_handle.profiler.record(Operation::Scan, &timer.finish());
+    java_result
+}
+
+/// Scans entries for a key, returning only their sequence, timestamp and
+/// value length.
+///
+/// # Returns
+/// A long[] holding each entry's sequence, timestamp and value length in
+/// turn, or null after throwing
+///
+/// # Safety
+/// JNI function - handle must be a valid pointer returned by nativeCreate.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "system" fn Java_dev_opendata_LogDb_nativeScanMetadata<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    handle: jlong,
+    key: JByteArray<'local>,
+    start_sequence: jlong,
+    max_entries: jlong,
+) -> jlongArray {
+    let mut timer = CallTimer::start();
+
+    if handle == 0 {
+        let _ = env.throw_new("java/lang/NullPointerException", "LogDb handle is null");
+        return std::ptr::null_mut();
+    }
+
+    let log_handle = unsafe { &*(handle as *const LogHandle) };
+
+    let key_bytes = match env.convert_byte_array(&key) {
+        Ok(b) => Bytes::from(b),
+        Err(e) => {
+            let _ = env.throw_new("dev/opendata/common/OpenDataNativeException", e.to_string());
+            return std::ptr::null_mut();
+        }
+    };
+
+    let Some(entries_result) = scan_blocking(
+        &mut env,
+        log_handle,
+        key_bytes,
+        start_sequence as u64,
+        max_entries as usize,
+        &ScanFilter::ALL,
+        Priority::Foreground,
+        &mut timer,
+    ) else {
+        return std::ptr::null_mut();
+    };
+
+    let java_result = match entries_result {
+        Ok(entries) => match create_entry_metadata_array(&mut env, &entries) {
+            Ok(arr) => arr,
+            Err(e) => {
+                let _ = env.throw_new("dev/opendata/common/OpenDataNativeException", e.to_string());
+                std::ptr::null_mut()
+            }
+        },
+        Err(e) => {
+            let _ = env.throw_new("dev/opendata/common/OpenDataNativeException", e.to_string());
+            std::ptr::null_mut()
+        }
+    };
+    log_handle.profiler.record(Operation::Scan, &timer.finish());
+    java_result
+}
+
+/// Scans entries for a key, returning their values exactly as stored.
+///
+/// Unlike `nativeScan`, timestamp headers are not extracted and chunk runs
+/// are not reassembled: every stored entry is returned with a timestamp of
+/// 0. Scans go to storage, bypassing the tail cache and no-op storage.
+///
+/// # Safety
+/// JNI function - handle must be a valid pointer returned by nativeCreate.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "system" fn Java_dev_opendata_LogDb_nativeScanRaw<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    handle: jlong,
+    key: JByteArray<'local>,
+    start_sequence: jlong,
+    max_entries: jlong,
+) -> jobjectArray {
+    let mut timer = CallTimer::start();
+
+    if handle == 0 {
+        let _ = env.throw_new("java/lang/NullPointerException", "LogDb handle is null");
+        return std::ptr::null_mut();
+    }
+
+    let log_handle = unsafe { &*(handle as *const LogHandle) };
+
+    let key_bytes = match env.convert_byte_array(&key) {
+        Ok(b) => Bytes::from(b),
+        Err(e) => {
+            let _ = env.throw_new("dev/opendata/common/OpenDataNativeException", e.to_string());
+            return std::ptr::null_mut();
+        }
+    };
+
+    timer.mark_submitted();
+    let Some(blocked) = enter_blocking_call(&mut env, log_handle) else {
+        return std::ptr::null_mut();
+    };
+    let tracked = watchdog::track_call(log_handle.watchdog.as_ref(), "scan");
+    let result = log_handle.runtime_handle.block_on(async {
+        if let Some(rate_limiter) = &log_handle.rate_limiter {
+            rate_limiter.admit_scan().await;
+        }
+        timer.mark_storage_started();
+        let result = watchdog::guard_call(log_handle.watchdog.as_ref(), "scan", async {
+            let log = log_handle.log.current();
+            scan_stored(&log, key_bytes, start_sequence as u64, max_entries as usize).await
+        })
+        .await;
+        timer.mark_storage_finished();
+        if let (Some(rate_limiter), Ok(Ok(entries))) = (&log_handle.rate_limiter, &result) {
+            rate_limiter.charge_scan(entries);
+        }
+        result
+    });
+    drop(tracked);
+    drop(blocked);
+    if let Ok(result) = &result {
+        log_handle.log.observe(result);
+    }
+    log_handle
+        .latency
+        .record(Operation::Scan, timer.storage_latency());
+
+    let java_result = match result {
+        Ok(Ok(entries)) => {
+            log_handle.served.record_storage(&entries);
+            match create_raw_log_entry_array(&mut env, &entries) {
+                Ok(arr) => arr,
+                Err(e) => {
+                    let _ =
+                        env.throw_new("dev/opendata/common/OpenDataNativeException", e.to_string());
+                    std::ptr::null_mut()
+                }
+            }
+        }
+        Ok(Err(e)) => {
+            let _ = env.throw_new("dev/opendata/common/OpenDataNativeException", e.to_string());
+            std::ptr::null_mut()
+        }
+        Err(stuck) => {
+            throw_stuck_call(&mut env, &stuck);
+            std::ptr::null_mut()
+        }
+    };
+    log_handle.profiler.record(Operation::Scan, &timer.finish());
+    java_result
+}
+
+/// Scans entries for a key into a caller-owned direct buffer, returning the
+/// number of entries written.
+///
+/// Steady-state consumers reuse one buffer and one offsets array across
+/// fetches, so a fetch creates no Java objects. Entries are packed from the
+/// start of the buffer, ignoring its position and limit, as described by
+/// `pack_entries`. At most `offsets.length - 1` entries are scanned, and
+/// scanning stops at the first entry that does not fit.
+///
+/// # Arguments
+/// * `handle` - Native LogDb pointer
+/// * `key` - The key to scan
+/// * `start_sequence` - First sequence to scan
+/// * `dest` - Direct ByteBuffer the entries are written to
+/// * `offsets` - Receives the buffer offset of each entry written, followed
+///   by the end of the last
+///
+/// # Safety
+/// JNI function - handle must be a valid pointer returned by nativeCreate.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "system" fn Java_dev_opendata_LogDb_nativeScanInto<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    handle: jlong,
+    key: JByteArray<'local>,
+    start_sequence: jlong,
+    dest: JByteBuffer<'local>,
+    offsets: JIntArray<'local>,
+) -> jint {
+    let mut timer = CallTimer::start();
+
+    if handle == 0 {
+        let _ = env.throw_new("java/lang/NullPointerException", "LogDb handle is null");
+        return 0;
+    }
+
+    let log_handle = unsafe { &*(handle as *const LogHandle) };
+
+    let key_bytes = match env.convert_byte_array(&key) {
+        Ok(b) => Bytes::from(b),
+        Err(e) => {
+            let _ = env.throw_new("dev/opendata/common/OpenDataNativeException", e.to_string());
+            return 0;
+        }
+    };
+    let buffer = match (
+        env.get_direct_buffer_address(&dest),
+        env.get_direct_buffer_capacity(&dest),
+    ) {
+        // Safety: the buffer stays reachable from Java for the duration of the call
+        (Ok(address), Ok(capacity)) => unsafe { std::slice::from_raw_parts_mut(address, capacity) },
+        (Err(e), _) | (_, Err(e)) => {
+            let _ = env.throw_new("java/lang/IllegalArgumentException", e.to_string());
+            return 0;
+        }
+    };
+    let max = match env.get_array_length(&offsets) {
+        Ok(len) => (len as usize).saturating_sub(1),
+        Err(e) => {
+            let _ = env.throw_new("dev/opendata/common/OpenDataNativeException", e.to_string());
+            return 0;
+        }
+    };
+
+    let Some(entries_result) = scan_blocking(
+        &mut env,
+        log_handle,
+        key_bytes,
+        start_sequence as u64,
+        max,
+        &ScanFilter::ALL,
+        Priority::Foreground,
+        &mut timer,
+    ) else {
+        return 0;
+    };
+
+    let written = match entries_result {
+        Ok(entries) => {
+            let packed = pack_entries(&entries, buffer);
+            if let (Some(first), true) = (entries.first(), packed.len() == 1) {
+                let _ = env.throw_new(
+                    "java/lang/IllegalArgumentException",
+                    format!(
+                        "Entry at sequence {} needs {} bytes, dest has {}",
+                        first.sequence,
+                        PACKED_HEADER_SIZE + extract_timestamp_and_payload(&first.value).1.len(),
+                        buffer.len()
+                    ),
+                );
+                return 0;
+            }
+            match env.set_int_array_region(&offsets, 0, &packed) {
+                Ok(()) => (packed.len() - 1) as jint,
+                Err(e) => {
+                    let _ =
+                        env.throw_new("dev/opendata/common/OpenDataNativeException", e.to_string());
+                    0
+                }
+            }
+        }
+        Err(e) => {
+            let _ = env.throw_new("dev/opendata/common/OpenDataNativeException", e.to_string());
+            0
+        }
+    };
+    log_handle.profiler.record(Operation::Scan, &timer.finish());
+    written
+}
+
+/// Scans entries for a key into a FlatBuffers `ScanResult`.
+///
+/// See `flatbatch.rs` for the schema.
+///
+/// # Safety
+/// JNI function - handle must be a valid pointer returned by nativeCreate.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "system" fn Java_dev_opendata_LogDb_nativeScanFlatBuffer<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    handle: jlong,
+    key: JByteArray<'local>,
+    start_sequence: jlong,
+    max_entries: jlong,
+) -> jbyteArray {
+    let mut timer = CallTimer::start();
+
+    if handle == 0 {
+        let _ = env.throw_new("java/lang/NullPointerException", "LogDb handle is null");
+        return std::ptr::null_mut();
+    }
+
+    let log_handle = unsafe { &*(handle as *const LogHandle) };
+
+    let key_bytes = match env.convert_byte_array(&key) {
+        Ok(b) => Bytes::from(b),
+        Err(e) => {
+            let _ = env.throw_new("dev/opendata/common/OpenDataNativeException", e.to_string());
+            return std::ptr::null_mut();
+        }
+    };
+
+    let Some(entries_result) = scan_blocking(
+        &mut env,
+        log_handle,
+        key_bytes,
+        start_sequence as u64,
+        max_entries as usize,
+        &ScanFilter::ALL,
+        Priority::Foreground,
+        &mut timer,
+    ) else {
+        return std::ptr::null_mut();
+    };
+
+    let java_result = match entries_result {
+        Ok(entries) => match env.byte_array_from_slice(&flatbatch::encode_entries(&entries)) {
+            Ok(arr) => arr.into_raw(),
+            Err(e) => {
+                let _ = env.throw_new("dev/opendata/common/OpenDataNativeException", e.to_string());
+                std::ptr::null_mut()
+            }
+        },
+        Err(e) => {
+            let _ = env.throw_new("dev/opendata/common/OpenDataNativeException", e.to_string());
+            std::ptr::null_mut()
+        }
+    };
+    log_handle.profiler.record(Operation::Scan, &timer.finish());
+    java_result
+}
+
+/// Scans entries for a key into an Arrow record batch, returning the number
+/// of entries in it.
+///
+/// The batch is exported through the Arrow C Data Interface into structs the
+/// caller allocated; see `arrowbatch.rs`.
+///
+/// # Arguments
+/// * `handle` - Native LogDb pointer
+/// * `key` - The key to scan
+/// * `start_sequence` - First sequence to scan
+/// * `max_entries` - Most entries to scan
+/// * `array_address` - Address of an empty `ArrowArray` receiving the batch
+/// * `schema_address` - Address of an empty `ArrowSchema` receiving its type
+///
+/// # Safety
+/// JNI function - handle must be a valid pointer returned by nativeCreate, and
+/// the addresses must point to structs allocated by the caller.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "system" fn Java_dev_opendata_LogDb_nativeScanArrow<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    handle: jlong,
+    key: JByteArray<'local>,
+    start_sequence: jlong,
+    max_entries: jlong,
+    array_address: jlong,
+    schema_address: jlong,
+) -> jint {
+    let mut timer = CallTimer::start();
+
+    if handle == 0 {
+        let _ = env.throw_new("java/lang/NullPointerException", "LogDb handle is null");
+        return 0;
+    }
+
+    let log_handle = unsafe { &*(handle as *const LogHandle) };
+
+    let key_bytes = match env.convert_byte_array(&key) {
+        Ok(b) => Bytes::from(b),
+        Err(e) => {
+            let _ = env.throw_new("dev/opendata/common/OpenDataNativeException", e.to_string());
+            return 0;
+        }
+    };
+
+    let Some(entries_result) = scan_blocking(
+        &mut env,
+        log_handle,
+        key_bytes,
+        start_sequence as u64,
+        max_entries as usize,
+        &ScanFilter::ALL,
+        Priority::Foreground,
+        &mut timer,
+    ) else {
+        return 0;
+    };
+
+    let exported = entries_result
+        .map_err(|e| e.to_string())
+        .and_then(|entries| {
+            // Safety: Java checked the addresses are non-zero; the structs are the caller's
+            unsafe {
+                arrowbatch::export(&entries, array_address as *mut _, schema_address as *mut _)
+            }
+            .map(|()| entries.len() as jint)
+            .map_err(|e| e.to_string())
+        });
+    let count = match exported {
+        Ok(count) => count,
+        Err(e) => {
+            let _ = env.throw_new("dev/opendata/common/OpenDataNativeException", e);
+            0
+        }
+    };
+    log_handle.profiler.record(Operation::Scan, &timer.finish());
+    count
+}
+
+/// Writes a key's entries in a sequence range to a local file, returning
+/// the number written.
+///
+/// Entries are streamed from storage into the file without passing through
+/// the JVM; see `export.rs`.
+///
+/// # Arguments
+/// * `handle` - Native LogDb pointer
+/// * `key` - The key to export
+/// * `start_sequence` - First sequence to export
+/// * `end_sequence` - Sequence to stop before
+/// * `path` - File to write, replaced if it exists
+/// * `format` - Ordinal of the Java `ExportFormat` enum
+///
+/// # Safety
+/// JNI function - handle must be a valid pointer returned by nativeCreate.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "system" fn Java_dev_opendata_LogDb_nativeExport<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    handle: jlong,
+    key: JByteArray<'local>,
+    start_sequence: jlong,
+    end_sequence: jlong,
+    path: JString<'local>,
+    format: jint,
+) -> jlong {
+    if handle == 0 {
+        let _ = env.throw_new("java/lang/NullPointerException", "LogDb handle is null");
+        return 0;
+    }
+    let Some(format) = ExportFormat::from_ordinal(format) else {
+        let _ = env.throw_new(
+            "java/lang/IllegalArgumentException",
+            format!("Unknown export format ordinal: {}", format),
+        );
+        return 0;
+    };
+
+    let log_handle = unsafe { &*(handle as *const LogHandle) };
+
+    let key_bytes = match env.convert_byte_array(&key) {
+        Ok(b) => Bytes::from(b),
+        Err(e) => {
+            let _ = env.throw_new("dev/opendata/common/OpenDataNativeException", e.to_string());
+            return 0;
+        }
+    };
+    let path: String = match env.get_string(&path) {
+        Ok(p) => p.into(),
+        Err(e) => {
+            let _ = env.throw_new("dev/opendata/common/OpenDataNativeException", e.to_string());
+            return 0;
+        }
+    };
+    let range = start_sequence as u64..end_sequence as u64;
+
+    let Some(_blocked) = enter_blocking_call(&mut env, log_handle) else {
+        return 0;
+    };
+    let _tracked = watchdog::track_call(log_handle.watchdog.as_ref(), "export");
+    let result = log_handle.runtime_handle.block_on(async {
+        let log = log_handle.log.current();
+        watchdog::guard_call(
+            log_handle.watchdog.as_ref(),
+            "export",
+            export::export(&log, key_bytes, range, format, Path::new(&path)),
+        )
+        .await
+    });
+    if let Ok(result) = &result {
+        // Failures writing the file say nothing about the storage
+        if !matches!(result, Err(ExportError::Io(..))) {
+            log_handle.log.observe(result);
+        }
+    }
+
+    match result {
+        Ok(Ok(written)) => written as jlong,
+        Ok(Err(e)) => {
+            let _ = env.throw_new("dev/opendata/common/OpenDataNativeException", e.to_string());
+            0
+        }
+        Err(stuck) => {
+            throw_stuck_call(&mut env, &stuck);
+            0
+        }
+    }
+}
+
+/// Flushes the log and checkpoints its storage under a name.
+///
+/// See `checkpoints.rs`.
+///
+/// # Arguments
+/// * `name` - Name of the checkpoint
+/// * `lifetime_ms` - How long the checkpoint lives; 0 keeps it until deleted
+///
+/// # Returns
+/// The Checkpoint created
+///
+/// # Safety
+/// JNI function - handle must be a valid pointer returned by nativeCreate.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "system" fn Java_dev_opendata_LogDb_nativeCheckpoint<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    handle: jlong,
+    name: JString<'local>,
+    lifetime_ms: jlong,
+) -> jobject {
+    if handle == 0 {
+        let _ = env.throw_new("java/lang/NullPointerException", "LogDb handle is null");
+        return std::ptr::null_mut();
+    }
+
+    let log_handle = unsafe { &*(handle as *const LogHandle) };
+
+    let name: String = match env.get_string(&name) {
+        Ok(n) => n.into(),
+        Err(e) => {
+            let _ = env.throw_new("java/lang/IllegalArgumentException", e.to_string());
+            return std::ptr::null_mut();
+        }
+    };
+    let lifetime = (lifetime_ms > 0).then(|| std::time::Duration::from_millis(lifetime_ms as u64));
+
+    let Some(_blocked) = enter_blocking_call(&mut env, log_handle) else {
+        return std::ptr::null_mut();
+    };
+    let _tracked = watchdog::track_call(log_handle.watchdog.as_ref(), "checkpoint");
+    let result = log_handle.runtime_handle.block_on(async {
+        watchdog::guard_call(log_handle.watchdog.as_ref(), "checkpoint", async {
+            let log = log_handle.log.current();
+            let flushed = log.flush().await;
+            log_handle.log.observe(&flushed);
+            flushed.map_err(|e| e.to_string())?;
+            checkpoints::create(&log_handle.storage, &name, lifetime).await
+        })
+        .await
+    });
+
+    match result {
+        Ok(Ok(checkpoint)) => match create_checkpoint_object(&mut env, &checkpoint) {
+            Ok(obj) => obj.into_raw(),
+            Err(e) => {
+                let _ = env.throw_new("dev/opendata/common/OpenDataNativeException", e.to_string());
+                std::ptr::null_mut()
+            }
+        },
+        Ok(Err(e)) => {
+            let _ = env.throw_new("dev/opendata/common/OpenDataNativeException", e);
+            std::ptr::null_mut()
+        }
+        Err(stuck) => {
+            throw_stuck_call(&mut env, &stuck);
+            std::ptr::null_mut()
+        }
+    }
+}
+
+/// Lists the checkpoints of the log's storage.
+///
+/// # Returns
+/// Checkpoint[] oldest first
+///
+/// # Safety
+/// JNI function - handle must be a valid pointer returned by nativeCreate.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "system" fn Java_dev_opendata_LogDb_nativeListCheckpoints<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    handle: jlong,
+) -> jobjectArray {
+    if handle == 0 {
+        let _ = env.throw_new("java/lang/NullPointerException", "LogDb handle is null");
+        return std::ptr::null_mut();
+    }
+
+    let log_handle = unsafe { &*(handle as *const LogHandle) };
+
+    let Some(_blocked) = enter_blocking_call(&mut env, log_handle) else {
+        return std::ptr::null_mut();
+    };
+    let _tracked = watchdog::track_call(log_handle.watchdog.as_ref(), "listCheckpoints");
+    let result = log_handle.runtime_handle.block_on(async {
+        watchdog::guard_call(
+            log_handle.watchdog.as_ref(),
+            "listCheckpoints",
+            checkpoints::list(&log_handle.storage),
+        )
+        .await
+    });
+
+    match result {
+        Ok(Ok(checkpoints)) => match create_checkpoint_array(&mut env, &checkpoints) {
+            Ok(array) => array,
+            Err(e) => {
+                let _ = env.throw_new("dev/opendata/common/OpenDataNativeException", e.to_string());
+                std::ptr::null_mut()
+            }
+        },
+        Ok(Err(e)) => {
+            let _ = env.throw_new("dev/opendata/common/OpenDataNativeException", e);
+            std::ptr::null_mut()
+        }
+        Err(stuck) => {
+            throw_stuck_call(&mut env, &stuck);
+            std::ptr::null_mut()
+        }
+    }
+}
+
+/// Deletes a checkpoint of the log's storage.
+///
+/// # Arguments
+/// * `id` - Id of the checkpoint, as returned by nativeCheckpoint
+///
+/// # Safety
+/// JNI function - handle must be a valid pointer returned by nativeCreate.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "system" fn Java_dev_opendata_LogDb_nativeDeleteCheckpoint<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    handle: jlong,
+    id: JString<'local>,
+) {
+    if handle == 0 {
+        let _ = env.throw_new("java/lang/NullPointerException", "LogDb handle is null");
+        return;
+    }
+
+    let log_handle = unsafe { &*(handle as *const LogHandle) };
+
+    let id = match env
+        .get_string(&id)
+        .map_err(|e| e.to_string())
+        .and_then(|id| checkpoints::parse_id(&String::from(id)))
+    {
+        Ok(id) => id,
+        Err(e) => {
+            let _ = env.throw_new("java/lang/IllegalArgumentException", e);
+            return;
+        }
+    };
+
+    let Some(_blocked) = enter_blocking_call(&mut env, log_handle) else {
+        return;
+    };
+    let _tracked = watchdog::track_call(log_handle.watchdog.as_ref(), "deleteCheckpoint");
+    let result = log_handle.runtime_handle.block_on(async {
+        watchdog::guard_call(
+            log_handle.watchdog.as_ref(),
+            "deleteCheckpoint",
+            checkpoints::delete(&log_handle.storage, id),
+        )
+        .await
+    });
+
+    match result {
+        Ok(Ok(())) => {}
+        Ok(Err(e)) => {
+            let _ = env.throw_new("dev/opendata/common/OpenDataNativeException", e);
+        }
+        Err(stuck) => throw_stuck_call(&mut env, &stuck),
+    }
+}
+
+/// Flushes the log and clones its storage to a new path of the same object
+/// store.
+///
+/// See `checkpoints.rs`.
+///
+/// # Arguments
+/// * `path` - Path of the clone in the log's object store
+///
+/// # Safety
+/// JNI function - handle must be a valid pointer returned by nativeCreate.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "system" fn Java_dev_opendata_LogDb_nativeClone<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    handle: jlong,
+    path: JString<'local>,
+) {
+    if handle == 0 {
+        let _ = env.throw_new("java/lang/NullPointerException", "LogDb handle is null");
+        return;
+    }
+
+    let log_handle = unsafe { &*(handle as *const LogHandle) };
+
+    let path: String = match env.get_string(&path) {
+        Ok(p) => p.into(),
+        Err(e) => {
+            let _ = env.throw_new("java/lang/IllegalArgumentException", e.to_string());
+            return;
+        }
+    };
+
+    let Some(_blocked) = enter_blocking_call(&mut env, log_handle) else {
+        return;
+    };
+    let _tracked = watchdog::track_call(log_handle.watchdog.as_ref(), "clone");
+    let result = log_handle.runtime_handle.block_on(async {
+        watchdog::guard_call(log_handle.watchdog.as_ref(), "clone", async {
+            let log = log_handle.log.current();
+            let flushed = log.flush().await;
+            log_handle.log.observe(&flushed);
+            flushed.map_err(|e| e.to_string())?;
+            checkpoints::clone_to(&log_handle.storage, &path).await
+        })
+        .await
+    });
+
+    match result {
+        Ok(Ok(())) => {}
+        Ok(Err(e)) => {
+            let _ = env.throw_new("dev/opendata/common/OpenDataNativeException", e);
+        }
+        Err(stuck) => throw_stuck_call(&mut env, &stuck),
+    }
+}
+
+/// Copies the log's storage as of a checkpoint to another object store.
+///
+/// See `backup.rs`.
+///
+/// # Arguments
+/// * `checkpoint_id` - Id of the checkpoint to copy
+/// * `destination` - Java `StorageConfig.SlateDb` to copy into
+/// * `listener` - `BackupListener` told the progress after each object, or null
+///
+/// # Returns
+/// Number of objects copied
+///
+/// # Safety
+/// JNI function - handle must be a valid pointer returned by nativeCreate.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "system" fn Java_dev_opendata_LogDb_nativeBackup<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    handle: jlong,
+    checkpoint_id: JString<'local>,
+    destination: JObject<'local>,
+    listener: JObject<'local>,
+) -> jlong {
+    if handle == 0 {
+        let _ = env.throw_new("java/lang/NullPointerException", "LogDb handle is null");
+        return 0;
+    }
+
+    let log_handle = unsafe { &*(handle as *const LogHandle) };
+
+    let checkpoint_id = match env
+        .get_string(&checkpoint_id)
+        .map_err(|e| e.to_string())
+        .and_then(|id| checkpoints::parse_id(&String::from(id)))
+    {
+        Ok(id) => id,
+        Err(e) => {
+            let _ = env.throw_new("java/lang/IllegalArgumentException", e);
+            return 0;
+        }
+    };
+    let destination = match extract_slatedb_config(&mut env, &destination) {
+        Ok(d) => d,
+        Err(e) => {
+            let _ = env.throw_new("java/lang/IllegalArgumentException", e);
+            return 0;
+        }
+    };
+    let (source, destination) = match (
+        checkpoints::slatedb_storage(&log_handle.storage),
+        checkpoints::slatedb_storage(&destination),
+    ) {
+        (Ok(source), Ok(destination)) => (source, destination),
+        (Err(e), _) | (_, Err(e)) => {
+            let _ = env.throw_new("dev/opendata/common/OpenDataNativeException", e);
+            return 0;
+        }
+    };
+
+    let Some(_blocked) = enter_blocking_call(&mut env, log_handle) else {
+        return 0;
+    };
+    let result = log_handle.runtime_handle.block_on(async {
+        let checkpoint = checkpoints::find(&log_handle.storage, checkpoint_id).await?;
+        backup::backup(source, &checkpoint, destination, |progress| {
+            if listener.is_null() {
+                return;
+            }
+            let notified = env.call_method(
+                &listener,
+                "onProgress",
+                "(JJJ)V",
+                &[
+                    JValue::Long(progress.objects_copied as i64),
+                    JValue::Long(progress.objects_total as i64),
+                    JValue::Long(progress.bytes_copied as i64),
+                ],
+            );
+            if notified.is_err() {
+                let _ = env.exception_clear();
+            }
+        })
+        .await
+    });
+
+    match result {
+        Ok(progress) => progress.objects_copied as jlong,
+        Err(e) => {
+            let _ = env.throw_new("dev/opendata/common/OpenDataNativeException", e);
+            0
+        }
+    }
+}
+
+/// Scans a uniform sample of the entries of a key in a sequence range.
+///
+/// See `sample.rs`.
+///
+/// # Arguments
+/// * `start_sequence` - First sequence of the range
+/// * `end_sequence` - Sequence the range stops before
+/// * `rate` - Probability with which each entry is kept
+/// * `seed` - Seed of the draws
+///
+/// # Safety
+/// JNI function - handle must be a valid pointer returned by nativeCreate.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+#[allow(clippy::too_many_arguments)]
+pub extern "system" fn Java_dev_opendata_LogDb_nativeScanSample<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    handle: jlong,
+    key: JByteArray<'local>,
+    start_sequence: jlong,
+    end_sequence: jlong,
+    rate: jdouble,
+    seed: jlong,
+) -> jobjectArray {
+    if handle == 0 {
+        let _ = env.throw_new("java/lang/NullPointerException", "LogDb handle is null");
+        return std::ptr::null_mut();
+    }
+
+    let log_handle = unsafe { &*(handle as *const LogHandle) };
+
+    let key_bytes = match env.convert_byte_array(&key) {
+        Ok(b) => Bytes::from(b),
+        Err(e) => {
+            let _ = env.throw_new("dev/opendata/common/OpenDataNativeException", e.to_string());
+            return std::ptr::null_mut();
+        }
+    };
+    let range = start_sequence as u64..end_sequence as u64;
+
+    let Some(_blocked) = enter_blocking_call(&mut env, log_handle) else {
+        return std::ptr::null_mut();
+    };
+    let _tracked = watchdog::track_call(log_handle.watchdog.as_ref(), "sample");
+    let result = log_handle.runtime_handle.block_on(async {
+        let log = log_handle.log.current();
+        watchdog::guard_call(
+            log_handle.watchdog.as_ref(),
+            "sample",
+            sample::sample(&log, key_bytes, range, rate, seed as u64),
+        )
+        .await
+    });
+    if let Ok(result) = &result {
+        log_handle.log.observe(result);
+    }
+
+    match result {
+        Ok(Ok(entries)) => {
+            log_handle.served.record_storage(&entries);
+            match create_log_entry_array(&mut env, &entries) {
+                Ok(arr) => arr,
+                Err(e) => {
+                    let _ =
+                        env.throw_new("dev/opendata/common/OpenDataNativeException", e.to_string());
+                    std::ptr::null_mut()
+                }
+            }
+        }
+        Ok(Err(e)) => {
+            let _ = env.throw_new("dev/opendata/common/OpenDataNativeException", e.to_string());
+            std::ptr::null_mut()
+        }
+        Err(stuck) => {
+            throw_stuck_call(&mut env, &stuck);
+            std::ptr::null_mut()
+        }
+    }
+}
+
+/// Appends the records of an export file, returning the number appended.
+///
+/// The file is read and appended natively in batches, within the handle's
+/// memory and produce rate limits, and flushed once all are appended; see
+/// `export.rs`.
+///
+/// # Arguments
+/// * `handle` - Native LogDb pointer
+/// * `path` - File to read
+/// * `format` - Ordinal of the Java `ExportFormat` enum
+/// * `key` - Key to append every record under, or null to keep the file's
+///
+/// # Safety
+/// JNI function - handle must be a valid pointer returned by nativeCreate.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "system" fn Java_dev_opendata_LogDb_nativeImport<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    handle: jlong,
+    path: JString<'local>,
+    format: jint,
+    key: JByteArray<'local>,
+) -> jlong {
+    if handle == 0 {
+        let _ = env.throw_new("java/lang/NullPointerException", "LogDb handle is null");
+        return 0;
+    }
+    let Some(format) = ExportFormat::from_ordinal(format) else {
+        let _ = env.throw_new(
+            "java/lang/IllegalArgumentException",
+            format!("Unknown export format ordinal: {}", format),
+        );
+        return 0;
+    };
+
+    let log_handle = unsafe { &*(handle as *const LogHandle) };
+
+    let key = if key.is_null() {
+        None
+    } else {
+        match env.convert_byte_array(&key) {
+            Ok(b) => Some(Bytes::from(b)),
+            Err(e) => {
+                let _ = env.throw_new("dev/opendata/common/OpenDataNativeException", e.to_string());
+                return 0;
+            }
+        }
+    };
+    let path: String = match env.get_string(&path) {
+        Ok(p) => p.into(),
+        Err(e) => {
+            let _ = env.throw_new("dev/opendata/common/OpenDataNativeException", e.to_string());
+            return 0;
+        }
+    };
+    let mut importer = match Importer::open(Path::new(&path), format, key) {
+        Ok(importer) => importer,
+        Err(e) => {
+            let _ = env.throw_new("dev/opendata/common/OpenDataNativeException", e);
+            return 0;
+        }
+    };
+
+    let mut imported = 0;
+    loop {
+        let records = match importer.next_batch(current_timestamp_ms()) {
+            Ok(records) => records,
+            Err(e) => {
+                let _ = env.throw_new("dev/opendata/common/OpenDataNativeException", e);
+                return 0;
+            }
+        };
+        if records.is_empty() {
+            break;
+        }
+        let count = records.len() as jlong;
+        let records = chunking::split(records, log_handle.chunk_threshold);
+        let Some(_charge) = charge_append(&mut env, &log_handle.memory, &records) else {
+            return 0;
+        };
+        let Some(_blocked) = enter_blocking_call(&mut env, log_handle) else {
+            return 0;
+        };
+        let _tracked = watchdog::track_call(log_handle.watchdog.as_ref(), "import");
+        let result = log_handle.runtime_handle.block_on(async {
+            if let Some(rate_limiter) = &log_handle.rate_limiter {
+                rate_limiter.admit_append(&records).await;
+            }
+            watchdog::guard_call(
+                log_handle.watchdog.as_ref(),
+                "import",
+                append_records(
+                    &log_handle.log.current(),
+                    &log_handle.append_lock,
+                    log_handle.tail_cache.as_deref(),
+                    &log_handle.watches,
+                    records,
+                    AckMode::Buffered,
+                ),
+            )
+            .await
+        });
+        if let Ok(result) = &result {
+            log_handle.log.observe(result);
+        }
+        match result {
+            Ok(Ok(_)) => imported += count,
+            Ok(Err(e)) => {
+                let _ = env.throw_new("dev/opendata/common/OpenDataNativeException", e.to_string());
+                return 0;
+            }
+            Err(stuck) => {
+                throw_stuck_call(&mut env, &stuck);
+                return 0;
+            }
+        }
+    }
+
+    let result = log_handle
+        .runtime_handle
+        .block_on(log_handle.log.current().flush());
+    log_handle.log.observe(&result);
+    if let Err(e) = result {
+        let _ = env.throw_new("dev/opendata/common/OpenDataNativeException", e.to_string());
+        return 0;
+    }
+    imported
+}
+
+/// Verifies the entries stored for a key, returning a VerifyReport.
+///
+/// Entries are checked natively as they are scanned; see `verify.rs`. A
+/// failing scan ends the verification and is reported rather than thrown.
+///
+/// # Arguments
+/// * `handle` - Native LogDb pointer
+/// * `key` - The key to verify
+/// * `start_sequence` - First sequence to verify
+/// * `progress` - `LongConsumer` told the entries verified so far, or null
+///
+/// # Safety
+/// JNI function - handle must be a valid pointer returned by nativeCreate.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "system" fn Java_dev_opendata_LogDb_nativeVerify<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    handle: jlong,
+    key: JByteArray<'local>,
+    start_sequence: jlong,
+    progress: JObject<'local>,
+) -> jobject {
+    if handle == 0 {
+        let _ = env.throw_new("java/lang/NullPointerException", "LogDb handle is null");
+        return std::ptr::null_mut();
+    }
+
+    let log_handle = unsafe { &*(handle as *const LogHandle) };
+
+    let key_bytes = match env.convert_byte_array(&key) {
+        Ok(b) => Bytes::from(b),
+        Err(e) => {
+            let _ = env.throw_new("dev/opendata/common/OpenDataNativeException", e.to_string());
+            return std::ptr::null_mut();
+        }
+    };
+
+    let Some(_blocked) = enter_blocking_call(&mut env, log_handle) else {
+        return std::ptr::null_mut();
+    };
+    let verification = log_handle.runtime_handle.block_on(verify::verify(
+        &log_handle.log.current(),
+        key_bytes,
+        start_sequence as u64,
+        |entries| {
+            if progress.is_null() {
+                return;
+            }
+            let accepted =
+                env.call_method(&progress, "accept", "(J)V", &[JValue::Long(entries as i64)]);
+            if accepted.is_err() {
+                let _ = env.exception_clear();
+            }
+        },
+    ));
+
+    match create_verify_report(&mut env, &verification) {
+        Ok(report) => report.into_raw(),
+        Err(e) => {
+            let _ = env.throw_new("dev/opendata/common/OpenDataNativeException", e.to_string());
+            std::ptr::null_mut()
+        }
+    }
+}
+
+/// Analyzes a key's sequences for gaps and repeated payloads, returning a
+/// SequenceReport; see `verify.rs`.
+///
+/// # Arguments
+/// * `handle` - Native LogDb pointer
+/// * `key` - The key to analyze
+/// * `start_sequence` - First sequence to analyze
+///
+/// # Safety
+/// JNI function - handle must be a valid pointer returned by nativeCreate.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "system" fn Java_dev_opendata_LogDb_nativeAnalyzeSequences<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    handle: jlong,
+    key: JByteArray<'local>,
+    start_sequence: jlong,
+) -> jobject {
+    if handle == 0 {
+        let _ = env.throw_new("java/lang/NullPointerException", "LogDb handle is null");
+        return std::ptr::null_mut();
+    }
+
+    let log_handle = unsafe { &*(handle as *const LogHandle) };
+
+    let key_bytes = match env.convert_byte_array(&key) {
+        Ok(b) => Bytes::from(b),
+        Err(e) => {
+            let _ = env.throw_new("dev/opendata/common/OpenDataNativeException", e.to_string());
+            return std::ptr::null_mut();
+        }
+    };
+
+    let Some(_blocked) = enter_blocking_call(&mut env, log_handle) else {
+        return std::ptr::null_mut();
+    };
+    let analysis = log_handle.runtime_handle.block_on(verify::analyze(
+        &log_handle.log.current(),
+        key_bytes,
+        start_sequence as u64,
+    ));
+
+    match create_sequence_report(&mut env, &analysis) {
+        Ok(report) => report.into_raw(),
+        Err(e) => {
+            let _ = env.throw_new("dev/opendata/common/OpenDataNativeException", e.to_string());
+            std::ptr::null_mut()
+        }
+    }
+}
+
+/// Submits an append without waiting for it, returning its ticket.
+///
+/// The records are copied before returning; the append runs on the handle's
+/// runtime and its result is collected with `nativePollCompletions`.
+///
+/// # Arguments
+/// * `handle` - Native LogDb pointer
+/// * `records` - Array of Java Record objects (each with key, value, timestampMs)
+/// * `ack_mode` - Ordinal of the Java `AckMode` enum; `DURABLE` completes after a flush
+///
+/// # Safety
+/// JNI function - handle must be a valid pointer returned by nativeCreate.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "system" fn Java_dev_opendata_LogDb_nativeSubmitAppend<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    handle: jlong,
+    records: jobjectArray,
+    ack_mode: jint,
+) -> jlong {
+    if handle == 0 {
+        let _ = env.throw_new("java/lang/NullPointerException", "LogDb handle is null");
+        return 0;
+    }
+
+    let Some(ack_mode) = AckMode::from_ordinal(ack_mode) else {
+        let _ = env.throw_new(
+            "java/lang/IllegalArgumentException",
+            format!("Unknown ack mode ordinal: {}", ack_mode),
+        );
+        return 0;
+    };
+
+    let log_handle = unsafe { &*(handle as *const LogHandle) };
+
+    let records_array = unsafe { JObjectArray::from_raw(records) };
+    let (rust_records, first_timestamp_ms) = match extract_records(
+        &mut env,
+        &records_array,
+        log_handle.critical_copy,
+        log_handle.clock.stamps(log_handle.receive_timestamps),
+    ) {
+        Ok(r) => r,
+        Err(e) => {
+            let _ = env.throw_new("dev/opendata/common/OpenDataNativeException", e.to_string());
+            return 0;
+        }
+    };
+
+    if rust_records.is_empty() {
+        let _ = env.throw_new(
+            "java/lang/IllegalArgumentException",
+            "Records array is empty",
+        );
+        return 0;
+    }
+
+    let rust_records = chunking::split(rust_records, log_handle.chunk_threshold);
+    let reservation = match &log_handle.write_queue {
+        Some(write_queue) => {
+            let bytes = memory::record_bytes(&rust_records);
+            let reservation = {
+                let _blocked = log_handle.blocked_callers.enter();
+                write_queue.reserve(rust_records.len(), bytes)
+            };
+            match (reservation, write_queue.policy()) {
+                (Some(reservation), _) => Some(reservation),
+                (None, BackpressurePolicy::Reject) => {
+                    let _ = env.throw_new(
+                        "dev/opendata/common/OpenDataBackpressureException",
+                        "Write queue is full",
+                    );
+                    return 0;
+                }
+                // Ticket 0 is never issued and tells Java the queue was full
+                (None, _) => return 0,
+            }
+        }
+        None => None,
+    };
+    let Some(charge) = charge_append(&mut env, &log_handle.memory, &rust_records) else {
+        return 0;
+    };
+
+    let queue = Arc::clone(&log_handle.completions);
+    let ticket = queue.submit();
+    if let Some(noop) = &log_handle.noop {
+        let start_sequence = noop.append(rust_records.len());
+        queue.complete(
+            ticket,
+            Ok(Outcome::Append {
+                start_sequence,
+                timestamp_ms: first_timestamp_ms,
+            }),
+        );
+        return ticket as jlong;
+    }
+
+    let log = Arc::clone(&log_handle.log);
+    let append_lock = Arc::clone(&log_handle.append_lock);
+    let latency = Arc::clone(&log_handle.latency);
+    let rate_limiter = log_handle.rate_limiter.clone();
+    let tail_cache = log_handle.tail_cache.clone();
+    let watches = Arc::clone(&log_handle.watches);
+    let slow_log = Arc::clone(&log_handle.slow_log);
+    let tags = Arc::clone(&log_handle.tags);
+    log_handle.runtime_handle.spawn(async move {
+        let _charge = charge;
+        if let Some(rate_limiter) = &rate_limiter {
+            rate_limiter.admit_append(&rust_records).await;
+        }
+        let summary = slow_log.summarize(&rust_records);
+        let size = jfr::summarize(&tags, &rust_records);
+        let started = Instant::now();
+        let result = append_records(
+            &log.current(),
+            &append_lock,
+            tail_cache.as_deref(),
+            &watches,
+            rust_records,
+            ack_mode,
+        )
+        .await;
+        log.observe(&result);
+        let elapsed = started.elapsed();
+        latency.record(Operation::Append, elapsed);
+        slow_log.append(elapsed, summary, result.as_ref().ok().copied());
+        jfr::append(elapsed, size, result.is_ok());
+        let result = result
+            .map(|start_sequence| Outcome::Append {
+                start_sequence,
+                timestamp_ms: first_timestamp_ms,
+            })
+            .map_err(|e| e.to_string());
+        // Release first so a caller polling this completion finds the space
+        drop(reservation);
+        queue.complete(ticket, result);
+    });
+    ticket as jlong
+}
+
+/// Submits a scan without waiting for it, returning its ticket.
+///
+/// # Arguments
+/// * `handle` - Native LogDb pointer
+/// * `key` - Key to scan
+/// * `start_sequence` - First sequence to return
+/// * `max_entries` - Most entries to return
+///
+/// # Safety
+/// JNI function - handle must be a valid pointer returned by nativeCreate.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "system" fn Java_dev_opendata_LogDb_nativeSubmitScan<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    handle: jlong,
+    key: JByteArray<'local>,
+    start_sequence: jlong,
+    max_entries: jlong,
+) -> jlong {
+    if handle == 0 {
+        let _ = env.throw_new("java/lang/NullPointerException", "LogDb handle is null");
+        return 0;
+    }
+
+    let log_handle = unsafe { &*(handle as *const LogHandle) };
+
+    let key_bytes = match env.convert_byte_array(&key) {
+        Ok(b) => Bytes::from(b),
+        Err(e) => {
+            let _ = env.throw_new("dev/opendata/common/OpenDataNativeException", e.to_string());
+            return 0;
+        }
+    };
+
+    let max = max_entries as usize;
+    let start_seq = start_sequence as u64;
+    let queue = Arc::clone(&log_handle.completions);
+    let ticket = queue.submit();
+    if let Some(noop) = &log_handle.noop {
+        let entries = noop.scan(&key_bytes, start_seq, max, current_timestamp_ms());
+        queue.complete(ticket, Ok(Outcome::Scan(entries)));
+        return ticket as jlong;
+    }
+
+    let log = Arc::clone(&log_handle.log);
+    let latency = Arc::clone(&log_handle.latency);
+    let rate_limiter = log_handle.rate_limiter.clone();
+    let tail_cache = log_handle.tail_cache.clone();
+    let served = Arc::clone(&log_handle.served);
+    let skew = Arc::clone(&log_handle.skew);
+    let slow_log = Arc::clone(&log_handle.slow_log);
+    let tags = Arc::clone(&log_handle.tags);
+    log_handle.runtime_handle.spawn(async move {
+        let started = Instant::now();
+        let result = read_entries(
+            &log.current(),
+            rate_limiter.as_deref(),
+            tail_cache.as_deref(),
+            &served,
+            key_bytes.clone(),
+            start_seq,
+            max,
+        )
+        .await;
+        log.observe(&result);
+        let elapsed = started.elapsed();
+        latency.record(Operation::Scan, elapsed);
+        slow_log.scan(
+            elapsed,
+            &key_bytes,
+            start_seq,
+            result.as_ref().ok().map(Vec::as_slice),
+        );
+        jfr::scan(&tags, elapsed, result.as_ref().ok().map(Vec::as_slice));
+        if let Ok(entries) = &result {
+            skew.record(entries);
+        }
+        queue.complete(ticket, result.map(Outcome::Scan).map_err(|e| e.to_string()));
+    });
+    ticket as jlong
+}
+
+/// Returns up to `max` completed submissions as a Java Completion[].
+///
+/// Waits up to `timeout_ms` for the first completion and returns an empty
+/// array if none arrives. Does not wait at all with a zero timeout.
+///
+/// # Safety
+/// JNI function - handle must be a valid pointer returned by nativeCreate.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "system" fn Java_dev_opendata_LogDb_nativePollCompletions<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    handle: jlong,
+    max: jint,
+    timeout_ms: jlong,
+) -> jobjectArray {
+    if handle == 0 {
+        let _ = env.throw_new("java/lang/NullPointerException", "LogDb handle is null");
+        return std::ptr::null_mut();
+    }
+
+    let log_handle = unsafe { &*(handle as *const LogHandle) };
+
+    let completions = log_handle.completions.poll(
+        max as usize,
+        std::time::Duration::from_millis(timeout_ms as u64),
+    );
+
+    match create_completion_array(&mut env, completions) {
+        Ok(arr) => arr,
+        Err(e) => {
+            let _ = env.throw_new("dev/opendata/common/OpenDataNativeException", e.to_string());
+            std::ptr::null_mut()
+        }
+    }
+}
+
+/// Appends a batch of records on a bridge thread.
+///
+/// Returns once the records are copied and queued; `future` is completed with
+/// the AppendResult, or exceptionally, when the append finishes.
+///
+/// # Arguments
+/// * `handle` - Native LogDb pointer
+/// * `records` - Array of Java Record objects (each with key, value, timestampMs)
+/// * `ack_mode` - Ordinal of the Java `AckMode` enum; `DURABLE` completes after a flush
+/// * `future` - Java CompletableFuture to complete
+///
+/// # Safety
+/// JNI function - handle must be a valid pointer returned by nativeCreate.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "system" fn Java_dev_opendata_LogDb_nativeAppendBridged<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    handle: jlong,
+    records: jobjectArray,
+    ack_mode: jint,
+    future: JObject<'local>,
+) {
+    if handle == 0 {
+        let _ = env.throw_new("java/lang/NullPointerException", "LogDb handle is null");
+        return;
+    }
+
+    let Some(ack_mode) = AckMode::from_ordinal(ack_mode) else {
+        let _ = env.throw_new(
+            "java/lang/IllegalArgumentException",
+            format!("Unknown ack mode ordinal: {}", ack_mode),
+        );
+        return;
+    };
+
+    let log_handle = unsafe { &*(handle as *const LogHandle) };
+    let Some(bridge) = &log_handle.bridge else {
+        let _ = env.throw_new(
+            "java/lang/IllegalStateException",
+            "LogDb has no bridge threads",
+        );
+        return;
+    };
+
+    let records_array = unsafe { JObjectArray::from_raw(records) };
+    let (rust_records, first_timestamp_ms) = match extract_records(
+        &mut env,
+        &records_array,
+        log_handle.critical_copy,
+        log_handle.clock.stamps(log_handle.receive_timestamps),
+    ) {
+        Ok(r) => r,
+        Err(e) => {
+            let _ = env.throw_new("dev/opendata/common/OpenDataNativeException", e.to_string());
+            return;
+        }
+    };
+
+    if rust_records.is_empty() {
+        let _ = env.throw_new(
+            "java/lang/IllegalArgumentException",
+            "Records array is empty",
+        );
+        return;
+    }
+
+    let rust_records = chunking::split(rust_records, log_handle.chunk_threshold);
+    let future = match env.new_global_ref(&future) {
+        Ok(f) => f,
+        Err(e) => {
+            let _ = env.throw_new("dev/opendata/common/OpenDataNativeException", e.to_string());
+            return;
+        }
+    };
+
+    if let Some(noop) = &log_handle.noop {
+        let start_sequence = noop.append(rust_records.len());
+        let result = create_append_result(&mut env, start_sequence, first_timestamp_ms)
+            .map_err(|e| e.to_string());
+        complete_java_future_now(&mut env, &future, result);
+        return;
+    }
+
+    let log = Arc::clone(&log_handle.log);
+    let append_lock = Arc::clone(&log_handle.append_lock);
+    let latency = Arc::clone(&log_handle.latency);
+    let rate_limiter = log_handle.rate_limiter.clone();
+    let tail_cache = log_handle.tail_cache.clone();
+    let watches = Arc::clone(&log_handle.watches);
+    let slow_log = Arc::clone(&log_handle.slow_log);
+    let tags = Arc::clone(&log_handle.tags);
+    let Some(charge) = charge_append(&mut env, &log_handle.memory, &rust_records) else {
+        return;
+    };
+    submit_bridged(
+        &mut env,
+        bridge,
+        Box::pin(async move {
+            let _charge = charge;
+            if let Some(rate_limiter) = &rate_limiter {
+                rate_limiter.admit_append(&rust_records).await;
+            }
+            let summary = slow_log.summarize(&rust_records);
+            let size = jfr::summarize(&tags, &rust_records);
+            let started = Instant::now();
+            let result = append_records(
+                &log.current(),
+                &append_lock,
+                tail_cache.as_deref(),
+                &watches,
+                rust_records,
+                ack_mode,
+            )
+            .await;
+            log.observe(&result);
+            let elapsed = started.elapsed();
+            latency.record(Operation::Append, elapsed);
+            slow_log.append(elapsed, summary, result.as_ref().ok().copied());
+            jfr::append(elapsed, size, result.is_ok());
+            Box::new(move |env: &mut JNIEnv<'_>| {
+                let result = result
+                    .map_err(|e| e.to_string())
+                    .and_then(|start_sequence| {
+                        create_append_result(env, start_sequence, first_timestamp_ms)
+                            .map_err(|e| e.to_string())
+                    });
+                complete_java_future(env, &future, result)
+            }) as Deliver
+        }),
+    );
+}
+
+/// Scans entries for a key on a bridge thread.
+///
+/// Returns immediately; `future` is completed with a LogEntry[], or
+/// exceptionally, when the scan finishes.
+///
+/// # Safety
+/// JNI function - handle must be a valid pointer returned by nativeCreate.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "system" fn Java_dev_opendata_LogDb_nativeScanBridged<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    handle: jlong,
+    key: JByteArray<'local>,
+    start_sequence: jlong,
+    max_entries: jlong,
+    future: JObject<'local>,
+) {
+    if handle == 0 {
+        let _ = env.throw_new("java/lang/NullPointerException", "LogDb handle is null");
+        return;
+    }
+
+    let log_handle = unsafe { &*(handle as *const LogHandle) };
+    let Some(bridge) = &log_handle.bridge else {
+        let _ = env.throw_new(
+            "java/lang/IllegalStateException",
+            "LogDb has no bridge threads",
+        );
+        return;
+    };
+
+    let key_bytes = match env.convert_byte_array(&key) {
+        Ok(b) => Bytes::from(b),
+        Err(e) => {
+            let _ = env.throw_new("dev/opendata/common/OpenDataNativeException", e.to_string());
+            return;
+        }
+    };
+
+    let future = match env.new_global_ref(&future) {
+        Ok(f) => f,
+        Err(e) => {
+            let _ = env.throw_new("dev/opendata/common/OpenDataNativeException", e.to_string());
+            return;
+        }
+    };
+
+    let max = max_entries as usize;
+    let start_seq = start_sequence as u64;
+    if let Some(noop) = &log_handle.noop {
+        let entries = noop.scan(&key_bytes, start_seq, max, current_timestamp_ms());
+        let result = create_log_entry_array(&mut env, &entries)
+            .map(|arr| unsafe { JObject::from_raw(arr) })
+            .map_err(|e| e.to_string());
+        complete_java_future_now(&mut env, &future, result);
+        return;
+    }
+
+    let log = Arc::clone(&log_handle.log);
+    let latency = Arc::clone(&log_handle.latency);
+    let rate_limiter = log_handle.rate_limiter.clone();
+    let tail_cache = log_handle.tail_cache.clone();
+    let served = Arc::clone(&log_handle.served);
+    let skew = Arc::clone(&log_handle.skew);
+    let slow_log = Arc::clone(&log_handle.slow_log);
+    let tags = Arc::clone(&log_handle.tags);
+    submit_bridged(
+        &mut env,
+        bridge,
+        Box::pin(async move {
+            let started = Instant::now();
+            let result = read_entries(
+                &log.current(),
+                rate_limiter.as_deref(),
+                tail_cache.as_deref(),
+                &served,
+                key_bytes.clone(),
+                start_seq,
+                max,
+            )
+            .await;
+            log.observe(&result);
+            let elapsed = started.elapsed();
+            latency.record(Operation::Scan, elapsed);
+            slow_log.scan(
+                elapsed,
+                &key_bytes,
+                start_seq,
+                result.as_ref().ok().map(Vec::as_slice),
+            );
+            jfr::scan(&tags, elapsed, result.as_ref().ok().map(Vec::as_slice));
+            if let Ok(entries) = &result {
+                skew.record(entries);
+            }
+            Box::new(move |env: &mut JNIEnv<'_>| {
+                let result = result.map_err(|e| e.to_string()).and_then(|entries| {
+                    create_log_entry_array(env, &entries)
+                        .map(|arr| unsafe { JObject::from_raw(arr) })
+                        .map_err(|e| e.to_string())
+                });
+                complete_java_future(env, &future, result)
+            }) as Deliver
+        }),
+    );
+}
+
+/// Scans a range of a key's entries on the runtime, delivering them to a
+/// Java listener in batches.
+///
+/// Returns immediately; see `scanstream.rs` for how the listener is called.
+///
+/// # Arguments
+/// * `handle` - Native LogDb pointer
+/// * `key` - Key to scan
+/// * `start_sequence` - First sequence to deliver
+/// * `end_sequence` - Sequence to stop before
+/// * `batch_size` - Maximum number of entries per batch
+/// * `listener` - Java `ScanDelivery` receiving the batches
+///
+/// # Safety
+/// JNI function - handle must be a valid pointer returned by nativeCreate.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+#[allow(clippy::too_many_arguments)]
+pub extern "system" fn Java_dev_opendata_LogDb_nativeScanAsync<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    handle: jlong,
+    key: JByteArray<'local>,
+    start_sequence: jlong,
+    end_sequence: jlong,
+    batch_size: jint,
+    listener: JObject<'local>,
+) {
+    if handle == 0 {
+        let _ = env.throw_new("java/lang/NullPointerException", "LogDb handle is null");
+        return;
+    }
+
+    if start_sequence < 0 || end_sequence < start_sequence {
+        let _ = env.throw_new(
+            "java/lang/IllegalArgumentException",
+            "Scan range must satisfy 0 <= startSequence <= endSequence",
+        );
+        return;
+    }
+    if batch_size <= 0 {
+        let _ = env.throw_new(
+            "java/lang/IllegalArgumentException",
+            "batchSize must be positive",
+        );
+        return;
+    }
+
+    let log_handle = unsafe { &*(handle as *const LogHandle) };
+
+    let key_bytes = match env.convert_byte_array(&key) {
+        Ok(b) => Bytes::from(b),
+        Err(e) => {
+            let _ = env.throw_new("dev/opendata/common/OpenDataNativeException", e.to_string());
+            return;
+        }
+    };
+
+    let listener = match env.new_global_ref(&listener) {
+        Ok(l) => ScanListener::new(l),
+        Err(e) => {
+            let _ = env.throw_new("dev/opendata/common/OpenDataNativeException", e.to_string());
+            return;
+        }
+    };
+
+    let mut range = ScanRange::new(
+        start_sequence as u64,
+        end_sequence as u64,
+        batch_size as usize,
+    );
+    let noop = log_handle.noop.clone();
+    let log = Arc::clone(&log_handle.log);
+    let latency = Arc::clone(&log_handle.latency);
+    let rate_limiter = log_handle.rate_limiter.clone();
+    let tail_cache = log_handle.tail_cache.clone();
+    let served = Arc::clone(&log_handle.served);
+    let skew = Arc::clone(&log_handle.skew);
+    let slow_log = Arc::clone(&log_handle.slow_log);
+    let tags = Arc::clone(&log_handle.tags);
+    log_handle.runtime_handle.spawn(async move {
+        while range.remaining() {
+            let start_seq = range.next_sequence();
+            let result = match &noop {
+                Some(noop) => Ok(noop.scan(
+                    &key_bytes,
+                    start_seq,
+                    range.batch_size(),
+                    current_timestamp_ms(),
+                )),
+                None => {
+                    let started = Instant::now();
+                    let result = read_entries(
+                        &log.current(),
+                        rate_limiter.as_deref(),
+                        tail_cache.as_deref(),
+                        &served,
+                        key_bytes.clone(),
+                        start_seq,
+                        range.batch_size(),
+                    )
+                    .await;
+                    log.observe(&result);
+                    let elapsed = started.elapsed();
+                    latency.record(Operation::Scan, elapsed);
+                    slow_log.scan(
+                        elapsed,
+                        &key_bytes,
+                        start_seq,
+                        result.as_ref().ok().map(Vec::as_slice),
+                    );
+                    jfr::scan(&tags, elapsed, result.as_ref().ok().map(Vec::as_slice));
+                    if let Ok(entries) = &result {
+                        skew.record(entries);
+                    }
+                    result
+                }
+            };
+            let mut entries = match result {
+                Ok(entries) => entries,
+                Err(e) => {
+                    listener.fail(&e.to_string());
+                    return;
+                }
+            };
+            let more = range.advance(&mut entries);
+            if !entries.is_empty() && !listener.batch(&entries) {
+                break;
+            }
+            if !more {
+                break;
+            }
+        }
+        listener.complete(range.next_sequence());
+    });
+}
+
+/// Flushes pending writes on a bridge thread.
+///
+/// Returns immediately; `future` is completed with null, or exceptionally,
+/// when the flush finishes.
+///
+/// # Safety
+/// JNI function - handle must be a valid pointer returned by nativeCreate.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "system" fn Java_dev_opendata_LogDb_nativeFlushBridged<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    handle: jlong,
+    future: JObject<'local>,
+) {
+    if handle == 0 {
+        let _ = env.throw_new("java/lang/NullPointerException", "LogDb handle is null");
+        return;
+    }
+
+    let log_handle = unsafe { &*(handle as *const LogHandle) };
+    let Some(bridge) = &log_handle.bridge else {
+        let _ = env.throw_new(
+            "java/lang/IllegalStateException",
+            "LogDb has no bridge threads",
+        );
+        return;
+    };
+
+    let future = match env.new_global_ref(&future) {
+        Ok(f) => f,
+        Err(e) => {
+            let _ = env.throw_new("dev/opendata/common/OpenDataNativeException", e.to_string());
+            return;
+        }
+    };
+
+    let log = Arc::clone(&log_handle.log);
+    submit_bridged(
+        &mut env,
+        bridge,
+        Box::pin(async move {
+            let result = log.current().flush().await;
+            log.observe(&result);
+            Box::new(move |env: &mut JNIEnv<'_>| {
+                let result = result.map(|()| JObject::null()).map_err(|e| e.to_string());
+                complete_java_future(env, &future, result)
+            }) as Deliver
+        }),
+    );
+}
+
+/// Returns the build information of the native library.
+///
+/// The array holds, in order, the binding crate version, the upstream LogDb
+/// and SlateDB versions, the git commit and the Cargo profile, as recorded by
+/// `build.rs`, and the global allocator.
+///
+/// # Safety
+/// This is a JNI function - must be called from Java with valid JNIEnv.
+#[no_mangle]
+pub extern "system" fn Java_dev_opendata_LogDb_nativeVersionFields<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+) -> jobjectArray {
+    let fields = [
+        env!("CARGO_PKG_VERSION"),
+        env!("OPENDATA_LOGDB_VERSION"),
+        env!("OPENDATA_SLATEDB_VERSION"),
+        env!("OPENDATA_GIT_SHA"),
+        env!("OPENDATA_BUILD_PROFILE"),
+        alloc::NAME,
+    ]
+    .map(String::from);
+    match create_string_array(&mut env, &fields) {
+        Ok(array) => array,
+        Err(e) => {
+            let _ = env.throw_new("dev/opendata/common/OpenDataNativeException", e.to_string());
+            std::ptr::null_mut()
+        }
+    }
+}
+
+/// Features reported by `LogDb.nativeCapabilities()`.
+///
+/// The discriminants are the ordinals of the Java `NativeCapability` enum,
+/// so variants are only ever appended.
+#[derive(Clone, Copy)]
+enum Capability {
+    TimestampEnvelope,
+    SubmittedOperations,
+    BridgedCalls,
+    AppendPipeline,
+    AppendRing,
+    ConditionalAppend,
+    IdempotentAppend,
+    WaitForSequence,
+    TailCache,
+    DiskCache,
+    ConfigFile,
+    EnvironmentOverrides,
+    SlowOperationLog,
+    ReceiveTimestamps,
+    AppendWatch,
+    ScanResumeTokens,
+    MetadataScan,
+    ScanFilters,
+    ScanSample,
+    GroupedAppend,
+    DurabilityBarrier,
+    ConfigUpdates,
+    PriorityClasses,
+    ReadConsistency,
+    ReaderSnapshots,
+    RawEntries,
+    AsyncScans,
+    IntervalStats,
+    KeyStats,
+    TraceContext,
+    StatsdExport,
+    MetricsSnapshot,
+    PendingOps,
+    CallLimit,
+    RuntimeMode,
+    Checkpoints,
+    Backups,
+    Clones,
+}
+
+/// Capabilities supported by this build of the library.
+const CAPABILITIES: &[Capability] = &[
+    Capability::TimestampEnvelope,
+    Capability::SubmittedOperations,
+    Capability::BridgedCalls,
+    Capability::AppendPipeline,
+    Capability::AppendRing,
+    Capability::ConditionalAppend,
+    Capability::IdempotentAppend,
+    Capability::WaitForSequence,
+    Capability::TailCache,
+    Capability::DiskCache,
+    Capability::ConfigFile,
+    Capability::EnvironmentOverrides,
+    Capability::SlowOperationLog,
+    Capability::ReceiveTimestamps,
+    Capability::AppendWatch,
+    Capability::ScanResumeTokens,
+    Capability::MetadataScan,
+    Capability::ScanFilters,
+    Capability::ScanSample,
+    Capability::GroupedAppend,
+    Capability::DurabilityBarrier,
+    Capability::ConfigUpdates,
+    Capability::PriorityClasses,
+    Capability::ReadConsistency,
+    Capability::ReaderSnapshots,
+    Capability::RawEntries,
+    Capability::AsyncScans,
+    Capability::IntervalStats,
+    Capability::KeyStats,
+    Capability::TraceContext,
+    Capability::StatsdExport,
+    Capability::MetricsSnapshot,
+    Capability::PendingOps,
+    Capability::CallLimit,
+    Capability::RuntimeMode,
+    Capability::Checkpoints,
+    Capability::Backups,
+    Capability::Clones,
+];
+
+/// Returns the supported capabilities as a bit mask of `NativeCapability` ordinals.
+///
+/// # Safety
+/// This is a JNI function - must be called from Java with valid JNIEnv.
+#[no_mangle]
+pub extern "system" fn Java_dev_opendata_LogDb_nativeCapabilityMask<'local>(
+    _env: JNIEnv<'local>,
+    _class: JClass<'local>,
+) -> jlong {
+    CAPABILITIES
+        .iter()
+        .fold(0, |mask, &capability| mask | 1 << capability as u32)
+}
+
+/// Returns whether appends, scans and flushes should go through `*Bridged` calls.
+///
+/// # Safety
+/// JNI function - handle must be a valid pointer returned by nativeCreate.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "system" fn Java_dev_opendata_LogDb_nativeIsBridged<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    handle: jlong,
+) -> jboolean {
+    if handle == 0 {
+        let _ = env.throw_new("java/lang/NullPointerException", "LogDb handle is null");
+        return JNI_FALSE;
+    }
+
+    let log_handle = unsafe { &*(handle as *const LogHandle) };
+    if log_handle.bridge.is_some() {
+        JNI_TRUE
+    } else {
+        JNI_FALSE
+    }
+}
+
+/// Returns the HdrHistogram (V2 encoding, microseconds) of latencies recorded
+/// for the given operation since the LogDb was opened.
+///
+/// # Arguments
+/// * `handle` - Native LogDb pointer
+/// * `op` - Ordinal of the Java `Operation` enum
+///
+/// # Safety
+/// JNI function - handle must be a valid pointer returned by nativeCreate.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "system" fn Java_dev_opendata_LogDb_nativeGetLatencyHistogram<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    handle: jlong,
+    op: jint,
+) -> jbyteArray {
+    if handle == 0 {
+        let _ = env.throw_new("java/lang/NullPointerException", "LogDb handle is null");
+        return std::ptr::null_mut();
+    }
+
+    let log_handle = unsafe { &*(handle as *const LogHandle) };
+    encode_latency_histogram(&mut env, &log_handle.latency, op)
+}
+
+/// Returns the accumulated per-phase timing breakdown for the given operation.
+///
+/// The result is a `long[]` of `[calls, marshalInNanos, queueNanos,
+/// storageNanos, marshalOutNanos]`, all zero unless profiling is enabled.
+///
+/// # Safety
+/// JNI function - handle must be a valid pointer returned by nativeCreate.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "system" fn Java_dev_opendata_LogDb_nativeGetTimingBreakdown<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    handle: jlong,
+    op: jint,
+) -> jlongArray {
+    if handle == 0 {
+        let _ = env.throw_new("java/lang/NullPointerException", "LogDb handle is null");
+        return std::ptr::null_mut();
+    }
+
+    let log_handle = unsafe { &*(handle as *const LogHandle) };
+    timing_breakdown_array(&mut env, &log_handle.profiler, op)
+}
+
+/// Starts a new measurement period, discarding the latency histograms, phase
+/// timings and blocked caller peak recorded so far.
+///
+/// # Arguments
+/// * `handle` - Native LogDb pointer
+/// * `warming_up` - Whether the new period is a warmup
+///
+/// # Safety
+/// JNI function - handle must be a valid pointer returned by nativeCreate.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "system" fn Java_dev_opendata_LogDb_nativeMarkWarmup<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    handle: jlong,
+    warming_up: jboolean,
+) {
+    if handle == 0 {
+        let _ = env.throw_new("java/lang/NullPointerException", "LogDb handle is null");
+        return;
+    }
+
+    let log_handle = unsafe { &*(handle as *const LogHandle) };
+    restart_measurement(
+        &log_handle.latency,
+        &log_handle.profiler,
+        &log_handle.blocked_callers,
+        &log_handle.measurement,
+        warming_up == JNI_TRUE,
+    );
+}
+
+/// Settings changed by a Java `ConfigUpdate`; None leaves a setting as it is.
+struct ConfigUpdate {
+    tail_cache_bytes: Option<usize>,
+    rate_limits: Option<RateLimitSettings>,
+    /// `logging` level at or below which lines are logged
+    log_level: Option<i32>,
+    /// Interval of the background flush; Some(None) stops it
+    flush_interval: Option<Option<std::time::Duration>>,
+}
+
+/// Extracts a Java `ConfigUpdate`.
+fn extract_config_update(
+    env: &mut JNIEnv<'_>,
+    update: &JObject<'_>,
+) -> Result<ConfigUpdate, String> {
+    let tail_cache_bytes =
+        extract_optional_long(env, update, "tailCacheBytes")?.map(|bytes| bytes as usize);
+
+    let limits_obj = env
+        .call_method(
+            update,
+            "rateLimits",
+            "()Ldev/opendata/RateLimitConfig;",
+            &[],
+        )
+        .map_err(|e| format!("Failed to get rateLimits: {}", e))?
+        .l()
+        .map_err(|e| format!("Failed to get rateLimits object: {}", e))?;
+    let rate_limits = if limits_obj.is_null() {
+        None
+    } else {
+        Some(extract_rate_limits(env, &limits_obj)?)
+    };
+
+    let level_obj = env
+        .call_method(update, "logLevel", "()Ldev/opendata/NativeLogLevel;", &[])
+        .map_err(|e| format!("Failed to get logLevel: {}", e))?
+        .l()
+        .map_err(|e| format!("Failed to get logLevel object: {}", e))?;
+    // NativeLogLevel ordinals OFF, WARN and INFO are the levels themselves
+    let log_level = if level_obj.is_null() {
+        None
+    } else {
+        Some(extract_int(env, &level_obj, "ordinal")?)
+    };
+
+    let flush_interval = extract_optional_long(env, update, "flushIntervalMs")?
+        .map(|ms| (ms > 0).then(|| std::time::Duration::from_millis(ms as u64)));
+
+    Ok(ConfigUpdate {
+        tail_cache_bytes,
+        rate_limits,
+        log_level,
+        flush_interval,
+    })
+}
+
+/// Applies a configuration update to an open LogDb.
+///
+/// Every setting is checked before any is applied, so a rejected update
+/// changes nothing. The tail cache and rate limits can only be changed if
+/// they were enabled at open; the log level is shared by all handles.
+///
+/// # Arguments
+/// * `handle` - Native LogDb pointer
+/// * `update` - Java `ConfigUpdate` whose null fields are left unchanged
+///
+/// # Safety
+/// JNI function - handle must be a valid pointer returned by nativeCreate.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "system" fn Java_dev_opendata_LogDb_nativeUpdateConfig<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    handle: jlong,
+    update: JObject<'local>,
+) {
+    if handle == 0 {
+        let _ = env.throw_new("java/lang/NullPointerException", "LogDb handle is null");
+        return;
+    }
+
+    let log_handle = unsafe { &*(handle as *const LogHandle) };
+
+    let update = match extract_config_update(&mut env, &update) {
+        Ok(update) => update,
+        Err(e) => {
+            let _ = env.throw_new("dev/opendata/common/OpenDataNativeException", e);
+            return;
+        }
+    };
+    if update.tail_cache_bytes.is_some() && log_handle.tail_cache.is_none() {
+        let _ = env.throw_new(
+            "java/lang/IllegalStateException",
+            "LogDb was opened without a tail cache",
+        );
+        return;
+    }
+    if update.rate_limits.is_some() && log_handle.rate_limiter.is_none() {
+        let _ = env.throw_new(
+            "java/lang/IllegalStateException",
+            "LogDb was opened without rate limits",
+        );
+        return;
+    }
+
+    if let (Some(bytes), Some(tail_cache)) = (update.tail_cache_bytes, &log_handle.tail_cache) {
+        tail_cache.set_max_bytes(bytes);
+    }
+    if let (Some(limits), Some(rate_limiter)) = (update.rate_limits, &log_handle.rate_limiter) {
+        rate_limiter.update(limits);
+    }
+    if let Some(level) = update.log_level {
+        logging::set_max_level(level);
+    }
+    if let Some(interval) = update.flush_interval {
+        let mut flush_task = log_handle
+            .flush_task
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        if let Some(task) = flush_task.take() {
+            task.abort();
+        }
+        *flush_task = interval.map(|interval| {
+            spawn_periodic_flush(
+                &log_handle.runtime_handle,
+                interval,
+                Arc::downgrade(&log_handle.log),
+                Arc::clone(&log_handle.tags),
+            )
+        });
+    }
+}
+
+/// Sets the manual clock of a LogDb.
+///
+/// # Arguments
+/// * `handle` - Native LogDb pointer
+/// * `timestamp_ms` - Timestamp stamped on records appended from now on
+///
+/// # Safety
+/// JNI function - handle must be a valid pointer returned by nativeCreate.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "system" fn Java_dev_opendata_LogDb_nativeSetClock<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    handle: jlong,
+    timestamp_ms: jlong,
+) {
+    if handle == 0 {
+        let _ = env.throw_new("java/lang/NullPointerException", "LogDb handle is null");
+        return;
+    }
+
+    let log_handle = unsafe { &*(handle as *const LogHandle) };
+    if !log_handle.clock.set(timestamp_ms) {
+        let _ = env.throw_new(
+            "java/lang/IllegalStateException",
+            "LogDb was not opened with ClockSource.MANUAL",
+        );
+    }
+}
+
+/// Advances the manual clock of a LogDb.
+///
+/// # Arguments
+/// * `handle` - Native LogDb pointer
+/// * `delta_ms` - Milliseconds to move the clock by
+///
+/// # Returns
+/// The new time of the clock
+///
+/// # Safety
+/// JNI function - handle must be a valid pointer returned by nativeCreate.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "system" fn Java_dev_opendata_LogDb_nativeAdvanceClock<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    handle: jlong,
+    delta_ms: jlong,
+) -> jlong {
+    if handle == 0 {
+        let _ = env.throw_new("java/lang/NullPointerException", "LogDb handle is null");
+        return 0;
+    }
+
+    let log_handle = unsafe { &*(handle as *const LogHandle) };
+    match log_handle.clock.advance(delta_ms) {
+        Some(now_ms) => now_ms,
+        None => {
+            let _ = env.throw_new(
+                "java/lang/IllegalStateException",
+                "LogDb was not opened with ClockSource.MANUAL",
+            );
+            0
+        }
+    }
+}
+
+/// Registers a listener notified of new entries of a key, see `watch.rs`.
+///
+/// # Arguments
+/// * `handle` - Native LogDb pointer
+/// * `key` - Key to watch
+/// * `from_sequence` - First sequence whose appends notify the listener
+/// * `listener` - Java TailListener called with each new tail of the key
+///
+/// # Returns
+/// Id of the watch, passed to nativeUnwatch
+///
+/// # Safety
+/// JNI function - handle must be a valid pointer returned by nativeCreate.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "system" fn Java_dev_opendata_LogDb_nativeWatch<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    handle: jlong,
+    key: JByteArray<'local>,
+    from_sequence: jlong,
+    listener: JObject<'local>,
+) -> jlong {
+    if handle == 0 {
+        let _ = env.throw_new("java/lang/NullPointerException", "LogDb handle is null");
+        return 0;
+    }
+
+    if from_sequence < 0 {
+        let _ = env.throw_new(
+            "java/lang/IllegalArgumentException",
+            "fromSequence must be >= 0",
+        );
+        return 0;
+    }
+
+    let log_handle = unsafe { &*(handle as *const LogHandle) };
+
+    let key_bytes = match env.convert_byte_array(&key) {
+        Ok(b) => Bytes::from(b),
+        Err(e) => {
+            let _ = env.throw_new("dev/opendata/common/OpenDataNativeException", e.to_string());
+            return 0;
+        }
+    };
+
+    match log_handle
+        .watches
+        .watch(&env, key_bytes, from_sequence as u64, &listener)
+    {
+        Ok(id) => id as jlong,
+        Err(e) => {
+            let _ = env.throw_new("dev/opendata/common/OpenDataNativeException", e);
+            0
+        }
+    }
+}
+
+/// Removes a watch registered with nativeWatch.
+///
+/// # Arguments
+/// * `handle` - Native LogDb pointer
+/// * `id` - Id of the watch
+///
+/// # Returns
+/// Whether the watch was registered
+///
+/// # Safety
+/// JNI function - handle must be a valid pointer returned by nativeCreate.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "system" fn Java_dev_opendata_LogDb_nativeUnwatch<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    handle: jlong,
+    id: jlong,
+) -> jboolean {
+    if handle == 0 {
+        let _ = env.throw_new("java/lang/NullPointerException", "LogDb handle is null");
+        return JNI_FALSE;
+    }
+
+    let log_handle = unsafe { &*(handle as *const LogHandle) };
+    if log_handle.watches.unwatch(id as u64) {
+        JNI_TRUE
+    } else {
+        JNI_FALSE
+    }
+}
+
+/// Blocks until the rate of an operation is steady or the timeout elapses.
+///
+/// Not tracked by the watchdog: the call only sleeps and samples counters.
+///
+/// # Arguments
+/// * `handle` - Native LogDb pointer
+/// * `op` - Ordinal of the Java `Operation` enum
+/// * `config` - Java SteadyStateConfig object
+/// * `timeout_ms` - Maximum time to wait
+///
+/// # Safety
+/// JNI function - handle must be a valid pointer returned by nativeCreate.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "system" fn Java_dev_opendata_LogDb_nativeAwaitSteadyState<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    handle: jlong,
+    op: jint,
+    config: JObject<'local>,
+    timeout_ms: jlong,
+) -> jboolean {
+    if handle == 0 {
+        let _ = env.throw_new("java/lang/NullPointerException", "LogDb handle is null");
+        return JNI_FALSE;
+    }
+
+    let log_handle = unsafe { &*(handle as *const LogHandle) };
+    let _blocked = log_handle.blocked_callers.enter();
+    await_steady_state(
+        &mut env,
+        &log_handle.runtime_handle,
+        &log_handle.latency,
+        op,
+        &config,
+        timeout_ms,
+    )
+}
+
+/// Returns native statistics for a LogDb as a `java.util.Map<String, Long>`.
+///
+/// Includes Tokio runtime health for the operation runtime (`runtime.*`) and
+/// the compaction runtime (`compaction_runtime.*`).
+///
+/// # Safety
+/// JNI function - handle must be a valid pointer returned by nativeCreate.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "system" fn Java_dev_opendata_LogDb_nativeStats<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    handle: jlong,
+) -> jobject {
+    if handle == 0 {
+        let _ = env.throw_new("java/lang/NullPointerException", "LogDb handle is null");
+        return std::ptr::null_mut();
+    }
+
+    let log_handle = unsafe { &*(handle as *const LogHandle) };
+    match create_stats_map(&mut env, &log_handle.stats()) {
+        Ok(map) => map.into_raw(),
+        Err(e) => {
+            let _ = env.throw_new("dev/opendata/common/OpenDataNativeException", e.to_string());
+            std::ptr::null_mut()
+        }
+    }
+}
+
+/// Returns the native operations of a LogDb that are queued or in flight.
+///
+/// Reads a few counters without waiting for storage, so it is cheap enough
+/// to call before every submission.
+///
+/// # Returns
+/// long[] of `[blockingCalls, bridgedCalls, submittedOps,
+/// unpolledCompletions, inFlightAppendBytes, unpolledScanBytes]`
+///
+/// # Safety
+/// JNI function - handle must be a valid pointer returned by nativeCreate.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "system" fn Java_dev_opendata_LogDb_nativePendingOps<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    handle: jlong,
+) -> jlongArray {
+    if handle == 0 {
+        let _ = env.throw_new("java/lang/NullPointerException", "LogDb handle is null");
+        return std::ptr::null_mut();
+    }
+
+    let log_handle = unsafe { &*(handle as *const LogHandle) };
+    match create_sequence_array(&mut env, log_handle.pending_ops().into_iter()) {
+        Ok(array) => array.into_raw(),
+        Err(e) => {
+            let _ = env.throw_new("dev/opendata/common/OpenDataNativeException", e.to_string());
+            std::ptr::null_mut()
+        }
+    }
+}
+
+/// Returns the handle's stats and latency summaries with the kind of each,
+/// and its tags, for metrics binders polling on their own schedule.
+///
+/// See `metrics.rs`.
+///
+/// # Returns
+/// MetricsSnapshot with untagged names
+///
+/// # Safety
+/// JNI function - handle must be a valid pointer returned by nativeCreate.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "system" fn Java_dev_opendata_LogDb_nativeMetricsSnapshot<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    handle: jlong,
+) -> jobject {
+    if handle == 0 {
+        let _ = env.throw_new("java/lang/NullPointerException", "LogDb handle is null");
+        return std::ptr::null_mut();
+    }
+
+    let log_handle = unsafe { &*(handle as *const LogHandle) };
+    match create_metrics_snapshot(
+        &mut env,
+        log_handle.tags.pairs(),
+        &log_handle.exported_stats(),
+    ) {
+        Ok(snapshot) => snapshot,
+        Err(e) => {
+            let _ = env.throw_new("dev/opendata/common/OpenDataNativeException", e.to_string());
+            std::ptr::null_mut()
+        }
+    }
+}
+
+/// Returns the keys with the most appends and scans, most first.
+///
+/// See `keystats.rs`.
+///
+/// # Arguments
+/// * `handle` - Native LogDb pointer
+/// * `limit` - Maximum number of keys to return
+///
+/// # Returns
+/// KeyStats[] of up to `limit` keys
+///
+/// # Safety
+/// JNI function - handle must be a valid pointer returned by nativeCreate.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "system" fn Java_dev_opendata_LogDb_nativeHotKeys<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    handle: jlong,
+    limit: jint,
+) -> jobjectArray {
+    if handle == 0 {
+        let _ = env.throw_new("java/lang/NullPointerException", "LogDb handle is null");
+        return std::ptr::null_mut();
+    }
+
+    let log_handle = unsafe { &*(handle as *const LogHandle) };
+    let Some(key_stats) = &log_handle.key_stats else {
+        let _ = env.throw_new(
+            "java/lang/IllegalStateException",
+            "Key statistics are not enabled; see LogDbConfig.withKeyStatsCapacity",
+        );
+        return std::ptr::null_mut();
+    };
+
+    let keys = key_stats.hot_keys(limit.max(0) as usize);
+    match create_key_stats_array(&mut env, &keys) {
+        Ok(arr) => arr,
+        Err(e) => {
+            let _ = env.throw_new("dev/opendata/common/OpenDataNativeException", e.to_string());
+            std::ptr::null_mut()
+        }
+    }
+}
+
+/// Returns the traffic of a LogDb since the previous call, or since it
+/// opened, as a `java.util.Map<String, Long>`.
+///
+/// # Safety
+/// JNI function - handle must be a valid pointer returned by nativeCreate.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "system" fn Java_dev_opendata_LogDb_nativeIntervalStats<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    handle: jlong,
+) -> jobject {
+    if handle == 0 {
+        let _ = env.throw_new("java/lang/NullPointerException", "LogDb handle is null");
+        return std::ptr::null_mut();
+    }
+
+    let log_handle = unsafe { &*(handle as *const LogHandle) };
+    let stats = log_handle.intervals.next(|| {
+        Traffic::new(
+            log_handle.watches.volume().volume(),
+            log_handle.served.volume(),
+        )
+    });
+    match create_stats_map(&mut env, &stats) {
+        Ok(map) => map.into_raw(),
+        Err(e) => {
+            let _ = env.throw_new("dev/opendata/common/OpenDataNativeException", e.to_string());
+            std::ptr::null_mut()
+        }
+    }
+}
+
+// =============================================================================
+// AppendRing JNI Methods
+// =============================================================================
+
+/// Returns the ring memory, header included, as a direct ByteBuffer.
+///
+/// # Safety
+/// JNI function - ring must be a valid pointer returned by nativeOpenAppendRing.
+/// The buffer must not be used after nativeClose.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "system" fn Java_dev_opendata_AppendRing_nativeMemory<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    ring: jlong,
+) -> jobject {
+    if ring == 0 {
+        let _ = env.throw_new(
+            "java/lang/NullPointerException",
+            "AppendRing handle is null",
+        );
+        return std::ptr::null_mut();
+    }
+
+    let ring = unsafe { &*(ring as *const AppendRing) };
+    let (ptr, len) = ring.memory();
+
+    // SAFETY: the memory stays allocated until nativeClose
+    match unsafe { env.new_direct_byte_buffer(ptr, len) } {
+        Ok(buffer) => buffer.into_raw(),
+        Err(e) => {
+            let _ = env.throw_new("dev/opendata/common/OpenDataNativeException", e.to_string());
+            std::ptr::null_mut()
+        }
+    }
+}
+
+/// Wakes the ring's consumer thread after it announced it was waiting.
+///
+/// # Safety
+/// JNI function - ring must be a valid pointer returned by nativeOpenAppendRing.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "system" fn Java_dev_opendata_AppendRing_nativeDoorbell<'local>(
+    _env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    ring: jlong,
+) {
+    if ring != 0 {
+        let ring = unsafe { &*(ring as *const AppendRing) };
+        ring.doorbell();
+    }
+}
+
+/// Returns the message of the append that failed the ring, or null.
+///
+/// # Safety
+/// JNI function - ring must be a valid pointer returned by nativeOpenAppendRing.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "system" fn Java_dev_opendata_AppendRing_nativeError<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    ring: jlong,
+) -> jstring {
+    if ring == 0 {
+        let _ = env.throw_new(
+            "java/lang/NullPointerException",
+            "AppendRing handle is null",
+        );
+        return std::ptr::null_mut();
+    }
+
+    let ring = unsafe { &*(ring as *const AppendRing) };
+    error_to_jstring(&mut env, ring.error())
+}
+
+/// Drains the ring, stops its consumer and frees the memory.
+///
+/// # Returns
+/// The message of the append that failed the ring, or null
+///
+/// # Safety
+/// JNI function - ring must be a valid pointer returned by nativeOpenAppendRing.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "system" fn Java_dev_opendata_AppendRing_nativeClose<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    ring: jlong,
+) -> jstring {
+    if ring == 0 {
+        return std::ptr::null_mut();
+    }
+
+    let ring = unsafe { Box::from_raw(ring as *mut AppendRing) };
+    let error = ring.close();
+    error_to_jstring(&mut env, error)
+}
+
+// =============================================================================
+// AppendPipeline JNI Methods
+// =============================================================================
+
+/// Submits a batch of records to a pipeline, blocking while it is full.
+///
+/// `future` is completed with the AppendResult, or exceptionally, once this
+/// batch and every batch submitted before it have completed.
+///
+/// # Arguments
+/// * `pipeline` - Native AppendPipeline pointer
+/// * `records` - Array of Java Record objects (each with key, value, timestampMs)
+/// * `ack_mode` - Ordinal of the Java `AckMode` enum; `DURABLE` completes after a flush
+/// * `future` - Java CompletableFuture to complete
+///
+/// # Safety
+/// JNI function - pipeline must be a valid pointer returned by nativeOpenPipeline.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "system" fn Java_dev_opendata_AppendPipeline_nativeAppend<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    pipeline: jlong,
+    records: jobjectArray,
+    ack_mode: jint,
+    future: JObject<'local>,
+) {
+    if pipeline == 0 {
+        let _ = env.throw_new(
+            "java/lang/NullPointerException",
+            "AppendPipeline handle is null",
+        );
+        return;
+    }
+
+    let Some(ack_mode) = AckMode::from_ordinal(ack_mode) else {
+        let _ = env.throw_new(
+            "java/lang/IllegalArgumentException",
+            format!("Unknown ack mode ordinal: {}", ack_mode),
+        );
+        return;
+    };
+
+    let pipeline = unsafe { &*(pipeline as *const Pipeline) };
+
+    let records_array = unsafe { JObjectArray::from_raw(records) };
+    let (rust_records, first_timestamp_ms) = match extract_records(
+        &mut env,
+        &records_array,
+        pipeline.critical_copy(),
+        pipeline.clock().stamps(pipeline.receive_timestamps()),
+    ) {
+        Ok(r) => r,
+        Err(e) => {
+            let _ = env.throw_new("dev/opendata/common/OpenDataNativeException", e.to_string());
+            return;
+        }
+    };
+
+    if rust_records.is_empty() {
+        let _ = env.throw_new(
+            "java/lang/IllegalArgumentException",
+            "Records array is empty",
+        );
+        return;
+    }
+
+    let future = match env.new_global_ref(&future) {
+        Ok(f) => f,
+        Err(e) => {
+            let _ = env.throw_new("dev/opendata/common/OpenDataNativeException", e.to_string());
+            return;
+        }
+    };
+
+    let rust_records = chunking::split(rust_records, pipeline.chunk_threshold());
+    let Some(charge) = charge_append(&mut env, pipeline.memory(), &rust_records) else {
+        return;
+    };
+
+    let submitted = pipeline.submit(rust_records, charge, ack_mode, move |result| {
+        Box::new(move |env: &mut JNIEnv<'_>| {
+            let result = result.and_then(|start_sequence| {
+                create_append_result(env, start_sequence, first_timestamp_ms)
+                    .map_err(|e| e.to_string())
+            });
+            complete_java_future(env, &future, result)
+        })
+    });
+    if !submitted {
+        let _ = env.throw_new(
+            "java/lang/IllegalStateException",
+            "AppendPipeline is closing",
+        );
+    }
+}
+
+/// Waits for every batch in flight, completes their futures and frees the
+/// pipeline.
+///
+/// # Safety
+/// JNI function - pipeline must be a valid pointer returned by nativeOpenPipeline.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "system" fn Java_dev_opendata_AppendPipeline_nativeClose<'local>(
+    _env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    pipeline: jlong,
+) {
+    if pipeline != 0 {
+        drop(unsafe { Box::from_raw(pipeline as *mut Pipeline) });
+    }
+}
+
+/// Converts an optional ring failure into a Java string or null.
+fn error_to_jstring(env: &mut JNIEnv<'_>, error: Option<String>) -> jstring {
+    let Some(error) = error else {
+        return std::ptr::null_mut();
+    };
+    match env.new_string(error) {
+        Ok(s) => s.into_raw(),
+        Err(e) => {
+            let _ = env.throw_new("dev/opendata/common/OpenDataNativeException", e.to_string());
+            std::ptr::null_mut()
+        }
+    }
+}
+
+// =============================================================================
+// LogDbManager JNI Methods
+// =============================================================================
+
+/// Handle to a set of topics, each its own LogDb, sharing one pair of runtimes.
+///
+/// Each topic opened through the manager gets a regular `LogHandle` without
+/// runtimes of its own, so the LogDb JNI methods work on it unchanged. Topics
+/// are stored under the configured storage path at `topics/<name>/<generation>`
+/// and recorded in a catalog log at `catalog` (see the `topics` module).
+/// Object-store clients and caches are still created per log by the storage
+/// layer; only the runtimes are shared.
+struct LogManagerHandle {
+    /// Settings applied to every log opened through the manager
+    settings: LogSettings,
+    /// Per-topic overrides of the base settings
+    topic_settings: HashMap<String, TopicSettings>,
+    /// Handle to the shared runtime for async operations
+    runtime_handle: Handle,
+    /// The shared runtime (kept alive for the lifetime of the manager)
+    runtime: Option<Runtime>,
+    /// Handle to the runtime for SlateDB compaction/GC tasks, which is the
+    /// shared runtime if configured so for in-memory storage
+    compaction_handle: Handle,
+    /// Shared runtime for SlateDB compaction/GC tasks, unless shared with
+    /// the runtime for async operations
+    compaction_runtime: Option<Runtime>,
+    /// Log recording topic creates and deletes
+    catalog: Option<LogDb>,
+    /// Live topics and the ones currently open, guarded together so a topic
+    /// cannot be deleted while it is being opened
+    state: Mutex<ManagerState>,
+}
+
+/// Mutable state of a LogDbManager.
+struct ManagerState {
+    /// Live topics, replayed from the catalog log
+    topics: TopicCatalog,
+    /// Topics opened through the manager, by name
+    open_logs: HashMap<String, Weak<ReopeningLog>>,
+}
+
+impl LogManagerHandle {
+    fn state(&self) -> std::sync::MutexGuard<'_, ManagerState> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Appends a catalog event durably, returning its catalog sequence.
+    fn record_topic_event(&self, event: &TopicEvent) -> Result<u64, String> {
+        let catalog = self.catalog.as_ref().ok_or("LogDbManager is closed")?;
+        self.runtime_handle
+            .block_on(async {
+                let result = catalog
+                    .append(vec![Record {
+                        key: Bytes::from_static(TOPIC_CATALOG_KEY),
+                        value: event.encode(),
+                    }])
+                    .await?;
+                catalog.flush().await?;
+                Ok::<u64, log::Error>(result.start_sequence)
+            })
+            .map_err(|e| e.to_string())
+    }
+}
+
+/// Returns the storage config for a path under a manager's base storage.
+fn managed_storage(base: &StorageConfig, subpath: &str) -> StorageConfig {
+    match base {
+        StorageConfig::InMemory => StorageConfig::InMemory,
+        StorageConfig::SlateDb(slatedb) => StorageConfig::SlateDb(SlateDbStorageConfig {
+            path: format!("{}/{}", slatedb.path.trim_end_matches('/'), subpath),
+            object_store: slatedb.object_store.clone(),
+            settings_path: slatedb.settings_path.clone(),
+        }),
+    }
+}
+
+/// Extracts per-topic overrides from a Java `Map<String, TopicConfig>`.
+fn extract_topic_settings(
+    env: &mut JNIEnv<'_>,
+    map: &JObject<'_>,
+) -> Result<HashMap<String, TopicSettings>, String> {
+    let entries = env
+        .call_method(map, "entrySet", "()Ljava/util/Set;", &[])
+        .map_err(|e| format!("Failed to get topic config entries: {}", e))?
+        .l()
+        .map_err(|e| format!("Failed to get topic config entries object: {}", e))?;
+    let iter = env
+        .call_method(&entries, "iterator", "()Ljava/util/Iterator;", &[])
+        .map_err(|e| format!("Failed to iterate topic configs: {}", e))?
+        .l()
+        .map_err(|e| format!("Failed to get topic config iterator: {}", e))?;
+
+    let mut settings = HashMap::new();
+    while env
+        .call_method(&iter, "hasNext", "()Z", &[])
+        .map_err(|e| format!("Failed to iterate topic configs: {}", e))?
+        .z()
+        .map_err(|e| format!("Failed to iterate topic configs: {}", e))?
+    {
+        let entry = env
+            .call_method(&iter, "next", "()Ljava/lang/Object;", &[])
+            .map_err(|e| format!("Failed to get topic config entry: {}", e))?
+            .l()
+            .map_err(|e| format!("Failed to get topic config entry object: {}", e))?;
+        let name_obj: JString = env
+            .call_method(&entry, "getKey", "()Ljava/lang/Object;", &[])
+            .map_err(|e| format!("Failed to get topic name: {}", e))?
+            .l()
+            .map_err(|e| format!("Failed to get topic name object: {}", e))?
+            .into();
+        let name = extract_topic_name(env, &name_obj)?;
+        let config_obj = env
+            .call_method(&entry, "getValue", "()Ljava/lang/Object;", &[])
+            .map_err(|e| format!("Failed to get topic config: {}", e))?
+            .l()
+            .map_err(|e| format!("Failed to get topic config object: {}", e))?;
+
+        let settings_path = extract_optional_string(env, &config_obj, "settingsPath")?;
+        let flush_interval = extract_optional_long(env, &config_obj, "flushIntervalMs")?
+            .map(|ms| std::time::Duration::from_millis(ms as u64));
+
+        let _ = env.delete_local_ref(config_obj);
+        let _ = env.delete_local_ref(name_obj);
+        let _ = env.delete_local_ref(entry);
+
+        settings.insert(
+            name,
+            TopicSettings {
+                settings_path,
+                flush_interval,
+            },
+        );
+    }
+
+    Ok(settings)
+}
+
+/// Extracts a nullable traceparent argument, validated.
+fn extract_trace_context(
+    env: &mut JNIEnv<'_>,
+    traceparent: &JString<'_>,
+) -> Result<Option<TraceContext>, String> {
+    if traceparent.is_null() {
+        return Ok(None);
+    }
+    let value: String = env
+        .get_string(traceparent)
+        .map(String::from)
+        .map_err(|e| format!("Failed to convert traceparent: {}", e))?;
+    TraceContext::parse(&value).map(Some)
+}
+
+/// Extracts a topic name argument as a Rust string.
+fn extract_topic_name(env: &mut JNIEnv<'_>, name: &JString<'_>) -> Result<String, String> {
+    env.get_string(name)
+        .map(String::from)
+        .map_err(|e| format!("Failed to convert topic name: {}", e))
+}
+
+/// Creates a LogDbManager whose topics share one pair of runtimes.
+///
+/// Opens the catalog log and replays it to find the existing topics.
+///
+/// # Arguments
+/// * `config` - Java LogDbConfig applied to every topic opened through the manager
+/// * `topic_configs` - Java `Map<String, TopicConfig>` of per-topic overrides
+///
+/// # Safety
+/// This is a JNI function - must be called from Java with valid JNIEnv.
+#[no_mangle]
+pub extern "system" fn Java_dev_opendata_LogDbManager_nativeCreate<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    config: JObject<'local>,
+    topic_configs: JObject<'local>,
+) -> jlong {
+    attach::init(&mut env);
+    logging::init(&mut env);
+    jfr::init(&mut env);
+    let mut settings = match extract_log_settings(&mut env, &config) {
+        Ok(s) => s,
+        Err(e) => {
+            let _ = env.throw_new("java/lang/IllegalArgumentException", e);
+            return 0;
+        }
+    };
+    if let Err(e) = envconfig::apply_log(&mut settings) {
+        let _ = env.throw_new("java/lang/IllegalArgumentException", e);
+        return 0;
+    }
+
+    let topic_settings = match extract_topic_settings(&mut env, &topic_configs) {
+        Ok(s) => s,
+        Err(e) => {
+            let _ = env.throw_new("java/lang/IllegalArgumentException", e);
+            return 0;
+        }
+    };
+
+    let runtime = match settings.runtime.build(&settings.tags) {
+        Ok(rt) => rt,
+        Err(e) => {
+            let _ = env.throw_new("dev/opendata/common/OpenDataNativeException", e);
+            return 0;
+        }
+    };
+
+    let compaction_runtime = match build_compaction_runtime(&settings) {
+        Ok(rt) => rt,
+        Err(e) => {
+            let _ = env.throw_new("dev/opendata/common/OpenDataNativeException", e);
+            return 0;
+        }
+    };
+    let compaction_handle = compaction_runtime
+        .as_ref()
+        .map_or_else(|| runtime.handle().clone(), |rt| rt.handle().clone());
+
+    let catalog_storage = managed_storage(&settings.storage, "catalog");
+    let result = runtime.block_on(async {
+        let catalog = open_log(catalog_storage, None, &compaction_handle).await?;
+        let mut topics = TopicCatalog::default();
+        let mut iter = catalog
+            .scan(Bytes::from_static(TOPIC_CATALOG_KEY), 0..)
+            .await?;
+        while let Some(entry) = iter.next().await? {
+            if let Some(event) = TopicEvent::decode(&entry.value) {
+                topics.apply(entry.sequence, event);
+            }
+        }
+        drop(iter);
+        Ok::<_, log::Error>((catalog, topics))
+    });
+
+    match result {
+        Ok((catalog, topics)) => {
+            let handle = Box::new(LogManagerHandle {
+                settings,
+                topic_settings,
+                runtime_handle: runtime.handle().clone(),
+                runtime: Some(runtime),
+                compaction_handle,
+                compaction_runtime,
+                catalog: Some(catalog),
+                state: Mutex::new(ManagerState {
+                    topics,
+                    open_logs: HashMap::new(),
+                }),
+            });
+            Box::into_raw(handle) as jlong
+        }
+        Err(e) => {
+            let _ = env.throw_new("dev/opendata/common/OpenDataNativeException", e.to_string());
+            0
+        }
+    }
+}
+
+/// Creates a topic, recording it durably in the catalog.
+///
+/// # Safety
+/// JNI function - handle must be a valid pointer returned by nativeCreate.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "system" fn Java_dev_opendata_LogDbManager_nativeCreateTopic<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    handle: jlong,
+    name: JString<'local>,
+) {
+    if handle == 0 {
+        let _ = env.throw_new(
+            "java/lang/NullPointerException",
+            "LogDbManager handle is null",
+        );
+        return;
+    }
+
+    let manager = unsafe { &*(handle as *const LogManagerHandle) };
+
+    let name = match extract_topic_name(&mut env, &name) {
+        Ok(n) => n,
+        Err(e) => {
+            let _ = env.throw_new("java/lang/IllegalArgumentException", e);
+            return;
+        }
+    };
+
+    let mut state = manager.state();
+    if state.topics.generation(&name).is_some() {
+        let _ = env.throw_new(
+            "java/lang/IllegalArgumentException",
+            format!("Topic {} already exists", name),
+        );
+        return;
+    }
+
+    let event = TopicEvent::Created(name);
+    match manager.record_topic_event(&event) {
+        Ok(sequence) => state.topics.apply(sequence, event),
+        Err(e) => {
+            let _ = env.throw_new("dev/opendata/common/OpenDataNativeException", e);
+        }
+    }
+}
+
+/// Deletes a topic from the catalog.
+///
+/// The topic must not be open. Its data is left in storage, and creating a
+/// topic with the same name later starts a new, empty generation.
+///
+/// # Safety
+/// JNI function - handle must be a valid pointer returned by nativeCreate.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "system" fn Java_dev_opendata_LogDbManager_nativeDeleteTopic<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    handle: jlong,
+    name: JString<'local>,
+) {
+    if handle == 0 {
+        let _ = env.throw_new(
+            "java/lang/NullPointerException",
+            "LogDbManager handle is null",
+        );
+        return;
+    }
+
+    let manager = unsafe { &*(handle as *const LogManagerHandle) };
+
+    let name = match extract_topic_name(&mut env, &name) {
+        Ok(n) => n,
+        Err(e) => {
+            let _ = env.throw_new("java/lang/IllegalArgumentException", e);
+            return;
+        }
+    };
+
+    let mut state = manager.state();
+    if state.topics.generation(&name).is_none() {
+        let _ = env.throw_new(
+            "java/lang/IllegalArgumentException",
+            format!("Topic {} does not exist", name),
+        );
+        return;
+    }
+    state.open_logs.retain(|_, log| log.strong_count() > 0);
+    if state.open_logs.contains_key(&name) {
+        let _ = env.throw_new(
+            "java/lang/IllegalStateException",
+            format!("Topic {} is open", name),
+        );
+        return;
+    }
+
+    let event = TopicEvent::Deleted(name);
+    match manager.record_topic_event(&event) {
+        Ok(sequence) => state.topics.apply(sequence, event),
+        Err(e) => {
+            let _ = env.throw_new("dev/opendata/common/OpenDataNativeException", e);
+        }
+    }
+}
+
+/// Lists the manager's topics in sorted order.
+///
+/// # Returns
+/// Java String[] of topic names
+///
+/// # Safety
+/// JNI function - handle must be a valid pointer returned by nativeCreate.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "system" fn Java_dev_opendata_LogDbManager_nativeListTopics<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    handle: jlong,
+) -> jobjectArray {
+    if handle == 0 {
+        let _ = env.throw_new(
+            "java/lang/NullPointerException",
+            "LogDbManager handle is null",
+        );
+        return std::ptr::null_mut();
+    }
+
+    let manager = unsafe { &*(handle as *const LogManagerHandle) };
+    let names = manager.state().topics.names();
+
+    match create_string_array(&mut env, &names) {
+        Ok(arr) => arr,
+        Err(e) => {
+            let _ = env.throw_new("dev/opendata/common/OpenDataNativeException", e.to_string());
+            std::ptr::null_mut()
+        }
+    }
+}
+
+/// Opens a topic on the manager's shared runtimes.
+///
+/// # Returns
+/// A LogDb handle, closed with `LogDb.nativeClose` before the manager is closed
+///
+/// # Safety
+/// JNI function - handle must be a valid pointer returned by nativeCreate.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "system" fn Java_dev_opendata_LogDbManager_nativeOpenLog<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    handle: jlong,
+    name: JString<'local>,
+) -> jlong {
+    if handle == 0 {
+        let _ = env.throw_new(
+            "java/lang/NullPointerException",
+            "LogDbManager handle is null",
+        );
+        return 0;
+    }
+
+    let manager = unsafe { &*(handle as *const LogManagerHandle) };
+
+    let name = match extract_topic_name(&mut env, &name) {
+        Ok(n) => n,
+        Err(e) => {
+            let _ = env.throw_new("java/lang/IllegalArgumentException", e);
+            return 0;
+        }
+    };
+
+    // Held while opening so two callers cannot open the same topic concurrently
+    let mut state = manager.state();
+    let Some(generation) = state.topics.generation(&name) else {
+        let _ = env.throw_new(
+            "java/lang/IllegalArgumentException",
+            format!("Topic {} does not exist", name),
+        );
+        return 0;
+    };
+    state.open_logs.retain(|_, log| log.strong_count() > 0);
+    if state.open_logs.contains_key(&name) {
+        let _ = env.throw_new(
+            "java/lang/IllegalStateException",
+            format!("Topic {} is already open", name),
+        );
+        return 0;
+    }
+
+    let overrides = manager
+        .topic_settings
+        .get(&name)
+        .cloned()
+        .unwrap_or_default();
+    let mut storage = managed_storage(
+        &manager.settings.storage,
+        &format!("topics/{}/{}", name, generation),
+    );
+    if let (StorageConfig::SlateDb(slatedb), Some(path)) = (&mut storage, overrides.settings_path) {
+        slatedb.settings_path = Some(path);
+    }
+    match open_log_handle(
+        &manager.settings,
+        storage,
+        Arc::new(manager.settings.tags.with_default("topic", &name)),
+        &manager.runtime_handle,
+        &manager.compaction_handle,
+        &OpenProgress::default(),
+    ) {
+        Ok(mut log_handle) => {
+            log_handle.flush_task = Mutex::new(overrides.flush_interval.map(|interval| {
+                spawn_periodic_flush(
+                    &manager.runtime_handle,
+                    interval,
+                    Arc::downgrade(&log_handle.log),
+                    Arc::clone(&log_handle.tags),
+                )
+            }));
+            state
+                .open_logs
+                .insert(name, Arc::downgrade(&log_handle.log));
+            publish_log_handle(log_handle)
+        }
+        Err(e) => {
+            let _ = env.throw_new("dev/opendata/common/OpenDataNativeException", e);
+            0
+        }
+    }
+}
+
+/// Closes a LogDbManager's catalog and shuts down its shared runtimes.
+///
+/// Every topic opened through the manager must be closed first.
+///
+/// # Safety
+/// JNI function - handle must be a valid pointer returned by nativeCreate.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "system" fn Java_dev_opendata_LogDbManager_nativeClose<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    handle: jlong,
+) {
+    if handle != 0 {
+        let manager = *unsafe { Box::from_raw(handle as *mut LogManagerHandle) };
+
+        if let Some(catalog) = manager.catalog {
+            if let Err(e) = manager.runtime_handle.block_on(catalog.close()) {
+                let _ = env.throw_new("dev/opendata/common/OpenDataNativeException", e.to_string());
+            }
+        }
+
+        if let Some(rt) = manager.compaction_runtime {
+            rt.shutdown_background();
+        }
+        if let Some(rt) = manager.runtime {
+            rt.shutdown_background();
+        }
+    }
+}
+
+// =============================================================================
+// LogDbReader JNI Methods
+// =============================================================================
+
+/// Handle to a LogDbReader instance with its associated Tokio runtime.
+///
+/// Unlike LogReader which borrows from a parent LogDb, LogDbReader owns its
+/// own storage connection and runtime. This allows it to coexist with a
+/// separate LogDb writer for realistic end-to-end latency benchmarking.
+struct LogDbReaderHandle {
+    /// The current LogDbReader instance, replaced by `nativeRefresh` and
+    /// shared with the watch poller
+    reader: Arc<std::sync::RwLock<Arc<LogDbReader>>>,
+    /// Config the reader was opened with, reused when refreshing
+    config: ReaderConfig,
+    /// Handle to the runtime for async operations
+    runtime_handle: Handle,
+    /// The runtime (kept alive for the lifetime of the reader)
+    runtime: Option<OwnedRuntime>,
+    /// Latency histograms for scans
+    latency: Arc<LatencyRecorder>,
+    /// Per-phase call timings (only recorded when profiling is enabled)
+    profiler: Profiler,
+    /// Java threads currently blocked in `block_on` on this handle
+    blocked_callers: BlockedCallers,
+    /// Warmup state and start of the current measurement period
+    measurement: Measurement,
+    /// Traffic totals at the end of the last interval stats call
+    intervals: Intervals,
+    /// When the handle was opened
+    created_at: Instant,
+    /// Entry of the handle in the registry of live handles
+    registration: Registration,
+    /// Watchdog for stuck calls, if configured
+    watchdog: Option<Watchdog>,
+    /// Latest committed offsets seen by this handle
+    offsets: Arc<OffsetCache>,
+    /// Writer used to commit offsets, attached from Java
+    offset_writer: Arc<OffsetWriterSlot>,
+    /// Scan positions awaiting automatic commit, if auto-commit is enabled
+    auto_commit: Option<Arc<AutoCommitter>>,
+    /// Background task periodically committing scan positions
+    auto_commit_task: Option<tokio::task::JoinHandle<()>>,
+    /// Next sequence to poll for each key
+    positions: ReaderPositions,
+    /// Latest known tail (last sequence + 1) for each key, for lag queries
+    tails: ReaderPositions,
+    /// Effective interval at which the reader discovers new log data
+    refresh_interval: std::time::Duration,
+    /// Pages read ahead for sequential scans, if enabled
+    read_ahead: Option<ReadAhead>,
+    /// Tail cache of a writer in this process, attached from Java
+    tail_cache: Mutex<Option<Weak<TailCache>>>,
+    /// Appends of the writer whose writes read-your-writes scans observe,
+    /// paired from Java
+    paired_writer: Mutex<Option<Weak<Watches>>>,
+    /// Listeners notified of new entries of watched keys
+    watches: Arc<Watches>,
+    /// Background task polling the view for watched keys, started with the
+    /// first watch
+    watch_poller: Mutex<Option<tokio::task::JoinHandle<()>>>,
+    /// Local disk cache of SlateDB object-store reads, if configured
+    disk_cache: Option<DiskCache>,
+    /// Bytes scans were served from the attached tail cache and from storage
+    served: ServedBytes,
+    /// Skew of scanned entries carrying a receive time
+    skew: ClockSkew,
+    /// Logs scans exceeding the slow-operation threshold
+    slow_log: SlowLog,
+    /// Iterators of scans resumable by token
+    cursors: Cursors,
+    /// Readers pinned to the view of each open snapshot
+    snapshots: Snapshots<LogDbReader>,
+    /// Copy of the settings file the reader is opened and refreshed from, if
+    /// typed SlateDB settings are configured
+    _settings_file: Option<Arc<SettingsFile>>,
+}
+
+/// Weak reference to the writer a reader commits offsets through.
+type OffsetWriterSlot = Mutex<Option<Weak<ReopeningLog>>>;
+
+impl LogDbReaderHandle {
+    /// Returns the current reader.
+    ///
+    /// Calls hold the returned reader for their duration, so a concurrent
+    /// refresh does not affect scans already in progress.
+    fn reader(&self) -> Arc<LogDbReader> {
+        Arc::clone(&self.reader.read().unwrap_or_else(PoisonError::into_inner))
+    }
+
+    /// Returns the attached tail cache, unless its writer has been closed.
+    fn tail_cache(&self) -> Option<Arc<TailCache>> {
+        self.tail_cache
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .as_ref()
+            .and_then(Weak::upgrade)
+    }
+
+    /// Collects the name/value pairs reported by `nativeStats`.
+    fn stats(&self) -> Stats {
+        let mut stats = Stats::new();
+        stats.push((
+            "uptime_nanos".to_string(),
+            self.created_at.elapsed().as_nanos() as i64,
+        ));
+        self.measurement.append_stats(&mut stats);
+        metrics::append_runtime_stats(&mut stats, "runtime", &self.runtime_handle);
+        metrics::append_blocked_caller_stats(&mut stats, "runtime", &self.blocked_callers);
+        stats.push((
+            "refresh_interval_ms".to_string(),
+            self.refresh_interval.as_millis() as i64,
+        ));
+        if let Some(read_ahead) = &self.read_ahead {
+            read_ahead.append_stats(&mut stats);
+        }
+        if let Some(disk_cache) = &self.disk_cache {
+            disk_cache.append_stats(&mut stats);
+        }
+        self.served.append_stats(&mut stats);
+        self.skew.append_stats(&mut stats);
+        self.slow_log.append_stats(&mut stats);
+        self.cursors.append_stats(&mut stats);
+        stats.push(("snapshots.open".to_string(), self.snapshots.count() as i64));
+        attach::append_stats(&mut stats);
+        stats
+    }
+}
+
+/// Creates a new LogDbReader instance with the specified configuration.
+///
+/// # Arguments
+/// * `config` - Java LogDbReaderConfig object
+///
+/// # Safety
+/// This is a JNI function - must be called from Java with valid JNIEnv.
+#[no_mangle]
+pub extern "system" fn Java_dev_opendata_LogDbReader_nativeCreate<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    java_config: JObject<'local>,
+) -> jlong {
+    attach::init(&mut env);
+    logging::init(&mut env);
+    jfr::init(&mut env);
+    // Extract storage config from LogDbReaderConfig
+    let mut storage_config = match extract_reader_storage_config(&mut env, &java_config) {
+        Ok(c) => c,
+        Err(e) => {
+            let _ = env.throw_new("java/lang/IllegalArgumentException", e);
+            return 0;
+        }
+    };
+    let mut disk_cache = match extract_disk_cache_settings(&mut env, &java_config) {
+        Ok(d) => d,
+        Err(e) => {
+            let _ = env.throw_new("java/lang/IllegalArgumentException", e);
+            return 0;
+        }
+    };
+    if let Err(e) = envconfig::apply_storage(&mut storage_config, &mut disk_cache) {
+        let _ = env.throw_new("java/lang/IllegalArgumentException", e);
+        return 0;
+    }
+    // Readers never collect garbage, so only the disk cache applies
+    let settings_file =
+        match settings_overlay(disk_cache.as_ref(), None, &WriteBufferSettings::default())
+            .apply(&mut storage_config)
+        {
+            Ok(f) => f,
+            Err(e) => {
+                let _ = env.throw_new("java/lang/IllegalArgumentException", e);
+                return 0;
+            }
+        };
+
+    // Start with default config and override fields as needed
+    let mut config = ReaderConfig {
+        storage: storage_config,
+        ..ReaderConfig::default()
+    };
+
+    // Override refresh interval if explicitly provided
+    match extract_refresh_interval(&mut env, &java_config) {
+        Ok(Some(interval)) => config.refresh_interval = interval,
+        Ok(None) => {} // Use default from ReaderConfig
+        Err(e) => {
+            let _ = env.throw_new("java/lang/IllegalArgumentException", e);
+            return 0;
+        }
+    }
+
+    let profiling = match extract_bool(&mut env, &java_config, "profiling") {
+        Ok(p) => p,
+        Err(e) => {
+            let _ = env.throw_new("java/lang/IllegalArgumentException", e);
+            return 0;
+        }
+    };
+
+    let watchdog_settings = match extract_watchdog_settings(&mut env, &java_config) {
+        Ok(w) => w,
+        Err(e) => {
+            let _ = env.throw_new("java/lang/IllegalArgumentException", e);
+            return 0;
+        }
+    };
+
+    let auto_commit_settings = match extract_auto_commit(&mut env, &java_config) {
+        Ok(a) => a,
+        Err(e) => {
+            let _ = env.throw_new("java/lang/IllegalArgumentException", e);
+            return 0;
+        }
+    };
+
+    let read_ahead = match extract_bool(&mut env, &java_config, "readAhead") {
+        Ok(r) => r,
+        Err(e) => {
+            let _ = env.throw_new("java/lang/IllegalArgumentException", e);
+            return 0;
+        }
+    };
+
+    let slow_operation_threshold = match extract_slow_operation_threshold(&mut env, &java_config) {
+        Ok(t) => t,
+        Err(e) => {
+            let _ = env.throw_new("java/lang/IllegalArgumentException", e);
+            return 0;
+        }
+    };
+
+    let runtime_mode = match extract_runtime_mode(&mut env, &java_config) {
+        Ok(m) => m,
+        Err(e) => {
+            let _ = env.throw_new("java/lang/IllegalArgumentException", e);
+            return 0;
+        }
+    };
+
+    // Create a dedicated runtime for this LogDbReader instance
+    let runtime = match OwnedRuntime::build(runtime_mode, "opendata-reader") {
+        Ok(rt) => rt,
+        Err(e) => {
+            let _ = env.throw_new("dev/opendata/common/OpenDataNativeException", e.to_string());
+            return 0;
+        }
+    };
+
+    let watchdog = match start_watchdog(watchdog_settings, "opendata-reader", runtime.handle()) {
+        Ok(w) => w,
+        Err(e) => {
+            let _ = env.throw_new("dev/opendata/common/OpenDataNativeException", e.to_string());
+            return 0;
+        }
+    };
+
+    let offsets = Arc::new(OffsetCache::default());
+    let offset_writer = Arc::new(OffsetWriterSlot::default());
+    let (auto_commit, auto_commit_task) = match auto_commit_settings {
+        Some((group, interval)) => {
+            let committer = Arc::new(AutoCommitter::new(group));
+            let task = spawn_auto_commit(
+                runtime.handle(),
+                interval,
+                Arc::clone(&committer),
+                Arc::clone(&offset_writer),
+                Arc::clone(&offsets),
+            );
+            (Some(committer), Some(task))
+        }
+        None => (None, None),
+    };
+
+    // Open the LogDbReader
+    let refresh_interval = config.refresh_interval;
+    let result = runtime
+        .handle()
+        .block_on(async { LogDbReader::open(config.clone()).await });
+
+    match result {
+        Ok(reader) => {
+            let latency = Arc::new(LatencyRecorder::new());
+            let registration = handles::register(
+                HandleKind::Reader,
+                Arc::clone(&latency),
+                Arc::default(),
+                None,
+            );
+            let handle = Box::new(LogDbReaderHandle {
+                reader: Arc::new(std::sync::RwLock::new(Arc::new(reader))),
+                config,
+                runtime_handle: runtime.handle().clone(),
+                runtime: Some(runtime),
+                latency,
+                profiler: Profiler::new(profiling),
+                blocked_callers: BlockedCallers::default(),
+                measurement: Measurement::new(),
+                intervals: Intervals::new(),
+                created_at: Instant::now(),
+                registration,
+                watchdog,
+                offsets,
+                offset_writer,
+                auto_commit,
+                auto_commit_task,
+                positions: ReaderPositions::default(),
+                tails: ReaderPositions::default(),
+                refresh_interval,
+                read_ahead: read_ahead.then(ReadAhead::default),
+                tail_cache: Mutex::new(None),
+                paired_writer: Mutex::new(None),
+                watches: Arc::default(),
+                watch_poller: Mutex::new(None),
+                disk_cache: disk_cache.as_ref().map(DiskCache::new),
+                served: ServedBytes::default(),
+                skew: ClockSkew::default(),
+                slow_log: SlowLog::new(slow_operation_threshold, Arc::default()),
+                cursors: Cursors::default(),
+                snapshots: Snapshots::default(),
+                _settings_file: settings_file,
+            });
+            Box::into_raw(handle) as jlong
+        }
+        Err(e) => {
+            let _ = env.throw_new("dev/opendata/common/OpenDataNativeException", e.to_string());
+            0
+        }
+    }
+}
+
+/// Scans entries from the log for a given key using LogDbReader.
+///
+/// # Arguments
+/// * `handle` - Native LogDbReader pointer
+/// * `key` - The key to scan
+/// * `start_sequence` - The sequence to start from (inclusive)
+/// * `max_entries` - Maximum number of entries to return
+/// * `consistency` - Ordinal of the Java `ReadConsistency` enum;
+///   `READ_YOUR_WRITES` first waits for the paired writer's appends
+///
+/// # Safety
+/// JNI function - handle must be a valid pointer returned by nativeCreate.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "system" fn Java_dev_opendata_LogDbReader_nativeScan<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    handle: jlong,
+    key: JByteArray<'local>,
+    start_sequence: jlong,
+    max_entries: jlong,
+    consistency: jint,
+) -> jobjectArray {
+    let mut timer = CallTimer::start();
+
+    if handle == 0 {
+        let _ = env.throw_new(
+            "java/lang/NullPointerException",
+            "LogDbReader handle is null",
+        );
+        return std::ptr::null_mut();
+    }
+
+    let Some(consistency) = ReadConsistency::from_ordinal(consistency) else {
+        let _ = env.throw_new(
+            "java/lang/IllegalArgumentException",
+            format!("Unknown read consistency ordinal: {}", consistency),
+        );
+        return std::ptr::null_mut();
+    };
+
+    let reader_handle = unsafe { &*(handle as *const LogDbReaderHandle) };
+
+    let key_bytes = match env.convert_byte_array(&key) {
+        Ok(b) => Bytes::from(b),
+        Err(e) => {
+            let _ = env.throw_new("dev/opendata/common/OpenDataNativeException", e.to_string());
+            return std::ptr::null_mut();
+        }
+    };
+
+    if consistency == ReadConsistency::ReadYourWrites
+        && !await_paired_writes(&mut env, reader_handle, &key_bytes)
+    {
+        return std::ptr::null_mut();
+    }
+
+    let Some(entries) = scan_reader(
+        &mut env,
+        reader_handle,
+        key_bytes,
+        start_sequence as u64,
+        max_entries as usize,
+        None,
+        &ScanFilter::ALL,
+        &mut timer,
+    ) else {
+        return std::ptr::null_mut();
+    };
+
+    finish_reader_scan(&mut env, reader_handle, &entries, timer)
+}
+
+/// Scans entries for a key using LogDbReader, returning only those passing
+/// a filter, as `LogDb.nativeScanFiltered` does.
+///
+/// # Safety
+/// JNI function - handle must be a valid pointer returned by nativeCreate.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "system" fn Java_dev_opendata_LogDbReader_nativeScanFiltered<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    handle: jlong,
+    key: JByteArray<'local>,
+    start_sequence: jlong,
+    max_entries: jlong,
+    filter: JObject<'local>,
+) -> jobjectArray {
+    let mut timer = CallTimer::start();
+
+    if handle == 0 {
+        let _ = env.throw_new(
+            "java/lang/NullPointerException",
+            "LogDbReader handle is null",
+        );
+        return std::ptr::null_mut();
+    }
+
+    let reader_handle = unsafe { &*(handle as *const LogDbReaderHandle) };
+
+    let key_bytes = match env.convert_byte_array(&key) {
+        Ok(b) => Bytes::from(b),
+        Err(e) => {
+            let _ = env.throw_new("dev/opendata/common/OpenDataNativeException", e.to_string());
+            return std::ptr::null_mut();
+        }
+    };
+    let filter = match extract_scan_filter(&mut env, &filter) {
+        Ok(filter) => filter,
+        Err(e) => {
+            let _ = env.throw_new("java/lang/IllegalArgumentException", e);
+            return std::ptr::null_mut();
+        }
+    };
+
+    let Some(entries) = scan_reader(
+        &mut env,
+        reader_handle,
+        key_bytes,
+        start_sequence as u64,
+        max_entries as usize,
+        None,
+        &filter,
+        &mut timer,
+    ) else {
+        return std::ptr::null_mut();
+    };
+
+    finish_reader_scan(&mut env, reader_handle, &entries, timer)
+}
+
+/// Scans entries for a key using LogDbReader, returning only their
+/// sequence, timestamp and value length, as `LogDb.nativeScanMetadata` does.
+///
+/// # Safety
+/// JNI function - handle must be a valid pointer returned by nativeCreate.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "system" fn Java_dev_opendata_LogDbReader_nativeScanMetadata<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    handle: jlong,
+    key: JByteArray<'local>,
+    start_sequence: jlong,
+    max_entries: jlong,
+) -> jlongArray {
+    let mut timer = CallTimer::start();
+
+    if handle == 0 {
+        let _ = env.throw_new(
+            "java/lang/NullPointerException",
+            "LogDbReader handle is null",
+        );
+        return std::ptr::null_mut();
+    }
+
+    let reader_handle = unsafe { &*(handle as *const LogDbReaderHandle) };
+
+    let key_bytes = match env.convert_byte_array(&key) {
+        Ok(b) => Bytes::from(b),
+        Err(e) => {
+            let _ = env.throw_new("dev/opendata/common/OpenDataNativeException", e.to_string());
+            return std::ptr::null_mut();
+        }
+    };
+
+    let Some(entries) = scan_reader(
+        &mut env,
+        reader_handle,
+        key_bytes,
+        start_sequence as u64,
+        max_entries as usize,
+        None,
+        &ScanFilter::ALL,
+        &mut timer,
+    ) else {
+        return std::ptr::null_mut();
+    };
+
+    let java_result = match create_entry_metadata_array(&mut env, &entries) {
+        Ok(arr) => arr,
+        Err(e) => {
+            let _ = env.throw_new("dev/opendata/common/OpenDataNativeException", e.to_string());
+            std::ptr::null_mut()
+        }
+    };
+    reader_handle
+        .profiler
+        .record(Operation::Scan, &timer.finish());
+    java_result
+}
+
+/// Returns the next entries for a key from the reader's tracked position.
+///
+/// The position starts at 0, advances past the returned entries, and can be
+/// moved with `nativeSeek` / `nativeSeekToTimestamp`. Polls of one key should
+/// not run concurrently, as both would read from the same position.
+///
+/// # Safety
+/// JNI function - handle must be a valid pointer returned by nativeCreate.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "system" fn Java_dev_opendata_LogDbReader_nativePoll<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    handle: jlong,
+    key: JByteArray<'local>,
+    max_entries: jlong,
+) -> jobjectArray {
+    let mut timer = CallTimer::start();
+
+    if handle == 0 {
+        let _ = env.throw_new(
+            "java/lang/NullPointerException",
+            "LogDbReader handle is null",
+        );
+        return std::ptr::null_mut();
+    }
+
+    let reader_handle = unsafe { &*(handle as *const LogDbReaderHandle) };
+
+    let key_bytes = match env.convert_byte_array(&key) {
+        Ok(b) => Bytes::from(b),
+        Err(e) => {
+            let _ = env.throw_new("dev/opendata/common/OpenDataNativeException", e.to_string());
+            return std::ptr::null_mut();
+        }
+    };
+
+    let position = reader_handle.positions.get(&key_bytes);
+    let Some(entries) = scan_reader(
+        &mut env,
+        reader_handle,
+        key_bytes.clone(),
+        position,
+        max_entries as usize,
+        None,
+        &ScanFilter::ALL,
+        &mut timer,
+    ) else {
+        return std::ptr::null_mut();
+    };
+
+    if let Some(last) = entries.last() {
+        reader_handle
+            .positions
+            .advance(key_bytes, last.sequence + 1);
+    }
+
+    finish_reader_scan(&mut env, reader_handle, &entries, timer)
+}
+
+/// Scans a key for a page of entries resumable by token.
+///
+/// # Arguments
+/// * `token` - Token returned by the previous page, or null to start at
+///   `start_sequence`
+/// * `start_sequence` - Sequence to start at without a token
+/// * `next_token` - Array of `TOKEN_LEN` bytes filled with the token of the
+///   following page
+///
+/// # Safety
+/// JNI function - handle must be a valid pointer returned by nativeCreate.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+#[allow(clippy::too_many_arguments)]
+pub extern "system" fn Java_dev_opendata_LogDbReader_nativeScanResumable<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    handle: jlong,
+    key: JByteArray<'local>,
+    token: JByteArray<'local>,
+    start_sequence: jlong,
+    max_entries: jlong,
+    next_token: JByteArray<'local>,
+) -> jobjectArray {
+    let mut timer = CallTimer::start();
+
+    if handle == 0 {
+        let _ = env.throw_new(
+            "java/lang/NullPointerException",
+            "LogDbReader handle is null",
+        );
+        return std::ptr::null_mut();
+    }
+
+    let reader_handle = unsafe { &*(handle as *const LogDbReaderHandle) };
+
+    let key_bytes = match env.convert_byte_array(&key) {
+        Ok(b) => Bytes::from(b),
+        Err(e) => {
+            let _ = env.throw_new("dev/opendata/common/OpenDataNativeException", e.to_string());
+            return std::ptr::null_mut();
+        }
+    };
+    let mut resume = if token.is_null() {
+        ResumeToken::start(&key_bytes, start_sequence as u64)
+    } else {
+        let decoded = env
+            .convert_byte_array(&token)
+            .map_err(|e| e.to_string())
+            .and_then(|bytes| ResumeToken::decode(&bytes, &key_bytes));
+        match decoded {
+            Ok(resume) => resume,
             Err(e) => {
-                let _ = env.throw_new("dev/opendata/common/OpenDataNativeException", e.to_string());
+                let _ = env.throw_new("java/lang/IllegalArgumentException", e);
                 return std::ptr::null_mut();
             }
-        };
+        }
+    };
+
+    let Some(entries) = scan_reader(
+        &mut env,
+        reader_handle,
+        key_bytes,
+        resume.next_sequence,
+        max_entries as usize,
+        Some(&mut resume),
+        &ScanFilter::ALL,
+        &mut timer,
+    ) else {
+        return std::ptr::null_mut();
+    };
+
+    let encoded = resume.encode().map(|b| b as i8);
+    if env.set_byte_array_region(&next_token, 0, &encoded).is_err() {
+        return std::ptr::null_mut();
+    }
+
+    finish_reader_scan(&mut env, reader_handle, &entries, timer)
+}
+
+/// Moves the reader's position for a key to a sequence.
+///
+/// # Safety
+/// JNI function - handle must be a valid pointer returned by nativeCreate.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "system" fn Java_dev_opendata_LogDbReader_nativeSeek<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    handle: jlong,
+    key: JByteArray<'local>,
+    sequence: jlong,
+) {
+    if handle == 0 {
+        let _ = env.throw_new(
+            "java/lang/NullPointerException",
+            "LogDbReader handle is null",
+        );
+        return;
+    }
+
+    let reader_handle = unsafe { &*(handle as *const LogDbReaderHandle) };
+
+    match env.convert_byte_array(&key) {
+        Ok(b) => reader_handle
+            .positions
+            .seek(Bytes::from(b), sequence as u64),
+        Err(e) => {
+            let _ = env.throw_new("dev/opendata/common/OpenDataNativeException", e.to_string());
+        }
+    }
+}
+
+/// Moves the reader's position for a key to its first entry at or after a timestamp.
+///
+/// Timestamps are read from the value headers, scanning the key from the start.
+/// If no entry is that recent, the position moves past the key's last entry.
+///
+/// # Returns
+/// The new position
+///
+/// # Safety
+/// JNI function - handle must be a valid pointer returned by nativeCreate.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "system" fn Java_dev_opendata_LogDbReader_nativeSeekToTimestamp<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    handle: jlong,
+    key: JByteArray<'local>,
+    timestamp_ms: jlong,
+) -> jlong {
+    if handle == 0 {
+        let _ = env.throw_new(
+            "java/lang/NullPointerException",
+            "LogDbReader handle is null",
+        );
+        return -1;
+    }
+
+    let reader_handle = unsafe { &*(handle as *const LogDbReaderHandle) };
+
+    let key_bytes = match env.convert_byte_array(&key) {
+        Ok(b) => Bytes::from(b),
+        Err(e) => {
+            let _ = env.throw_new("dev/opendata/common/OpenDataNativeException", e.to_string());
+            return -1;
+        }
+    };
+
+    let _blocked = reader_handle.blocked_callers.enter();
+    let _tracked = watchdog::track_call(reader_handle.watchdog.as_ref(), "seekToTimestamp");
+    let result = reader_handle.runtime_handle.block_on(async {
+        let reader = reader_handle.reader();
+        let mut iter = reader.scan(key_bytes.clone(), 0..).await?;
+        let mut end = 0;
+        while let Some(entry) = iter.next().await? {
+            let (entry_timestamp_ms, _) = extract_timestamp_and_payload(&entry.value);
+            if entry_timestamp_ms >= timestamp_ms {
+                return Ok(entry.sequence);
+            }
+            end = entry.sequence + 1;
+        }
+        Ok::<u64, log::Error>(end)
+    });
+
+    match result {
+        Ok(position) => {
+            reader_handle.positions.seek(key_bytes, position);
+            position as jlong
+        }
+        Err(e) => {
+            let _ = env.throw_new("dev/opendata/common/OpenDataNativeException", e.to_string());
+            -1
+        }
+    }
+}
+
+/// Forces the reader to load the latest state of the log from storage.
+///
+/// The reader is reopened with its original config and swapped in once it is
+/// ready, so data written by other processes is visible without waiting for
+/// the next background refresh. Scans already in progress finish on the
+/// previous reader. Positions, offsets, and lag tails are kept.
+///
+/// # Safety
+/// JNI function - handle must be a valid pointer returned by nativeCreate.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "system" fn Java_dev_opendata_LogDbReader_nativeRefresh<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    handle: jlong,
+) {
+    if handle == 0 {
+        let _ = env.throw_new(
+            "java/lang/NullPointerException",
+            "LogDbReader handle is null",
+        );
+        return;
+    }
+
+    let reader_handle = unsafe { &*(handle as *const LogDbReaderHandle) };
+
+    let _blocked = reader_handle.blocked_callers.enter();
+    let _tracked = watchdog::track_call(reader_handle.watchdog.as_ref(), "refresh");
+    let result = reader_handle
+        .runtime_handle
+        .block_on(async { LogDbReader::open(reader_handle.config.clone()).await });
+
+    match result {
+        Ok(reader) => {
+            let previous = std::mem::replace(
+                &mut *reader_handle
+                    .reader
+                    .write()
+                    .unwrap_or_else(PoisonError::into_inner),
+                Arc::new(reader),
+            );
+            // Dropped outside the lock; in-flight scans may still hold it
+            drop(previous);
+        }
+        Err(e) => {
+            let _ = env.throw_new("dev/opendata/common/OpenDataNativeException", e.to_string());
+        }
+    }
+}
+
+/// Begins a snapshot pinning the reader's current view of the log.
+///
+/// See `snapshots.rs`.
+///
+/// # Returns
+/// Id of the snapshot, passed to `nativeScanSnapshot` and `nativeEndSnapshot`
+///
+/// # Safety
+/// JNI function - handle must be a valid pointer returned by nativeCreate.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "system" fn Java_dev_opendata_LogDbReader_nativeBeginSnapshot<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    handle: jlong,
+) -> jlong {
+    if handle == 0 {
+        let _ = env.throw_new(
+            "java/lang/NullPointerException",
+            "LogDbReader handle is null",
+        );
+        return 0;
+    }
+
+    let reader_handle = unsafe { &*(handle as *const LogDbReaderHandle) };
+
+    let _blocked = reader_handle.blocked_callers.enter();
+    let _tracked = watchdog::track_call(reader_handle.watchdog.as_ref(), "snapshot");
+    let result = reader_handle
+        .runtime_handle
+        .block_on(async { LogDbReader::open(snapshots::pinned(&reader_handle.config)).await });
+
+    match result {
+        Ok(reader) => reader_handle.snapshots.begin(reader) as jlong,
+        Err(e) => {
+            let _ = env.throw_new("dev/opendata/common/OpenDataNativeException", e.to_string());
+            0
+        }
+    }
+}
+
+/// Scans entries for a key as of an open snapshot.
+///
+/// # Arguments
+/// * `handle` - Native LogDbReader pointer
+/// * `snapshot` - Id returned by `nativeBeginSnapshot`
+/// * `key` - The key to scan
+/// * `start_sequence` - The sequence to start from (inclusive)
+/// * `max_entries` - Maximum number of entries to return
+///
+/// # Safety
+/// JNI function - handle must be a valid pointer returned by nativeCreate.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "system" fn Java_dev_opendata_LogDbReader_nativeScanSnapshot<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    handle: jlong,
+    snapshot: jlong,
+    key: JByteArray<'local>,
+    start_sequence: jlong,
+    max_entries: jlong,
+) -> jobjectArray {
+    let mut timer = CallTimer::start();
+
+    if handle == 0 {
+        let _ = env.throw_new(
+            "java/lang/NullPointerException",
+            "LogDbReader handle is null",
+        );
+        return std::ptr::null_mut();
+    }
+
+    let reader_handle = unsafe { &*(handle as *const LogDbReaderHandle) };
+
+    let Some(reader) = reader_handle.snapshots.get(snapshot as u64) else {
+        let _ = env.throw_new("java/lang/IllegalStateException", "Snapshot has ended");
+        return std::ptr::null_mut();
+    };
+
+    let key_bytes = match env.convert_byte_array(&key) {
+        Ok(b) => Bytes::from(b),
+        Err(e) => {
+            let _ = env.throw_new("dev/opendata/common/OpenDataNativeException", e.to_string());
+            return std::ptr::null_mut();
+        }
+    };
+
+    timer.mark_submitted();
+    let blocked = reader_handle.blocked_callers.enter();
+    let tracked = watchdog::track_call(reader_handle.watchdog.as_ref(), "scan");
+    let result = reader_handle.runtime_handle.block_on(async {
+        timer.mark_storage_started();
+        let result = watchdog::guard_call(reader_handle.watchdog.as_ref(), "scan", async {
+            scan_entries(
+                reader.as_ref(),
+                key_bytes,
+                start_sequence as u64,
+                max_entries as usize,
+                &ScanFilter::ALL,
+            )
+            .await
+        })
+        .await;
+        timer.mark_storage_finished();
+        result
+    });
+    drop(tracked);
+    drop(blocked);
+    reader_handle
+        .latency
+        .record(Operation::Scan, timer.storage_latency());
+
+    match result {
+        Ok(Ok(entries)) => {
+            reader_handle.served.record_storage(&entries);
+            finish_reader_scan(&mut env, reader_handle, &entries, timer)
+        }
+        Ok(Err(e)) => {
+            let _ = env.throw_new("dev/opendata/common/OpenDataNativeException", e.to_string());
+            std::ptr::null_mut()
+        }
+        Err(stuck) => {
+            throw_stuck_call(&mut env, &stuck);
+            std::ptr::null_mut()
+        }
+    }
+}
+
+/// Ends a snapshot, releasing its pinned view. Ending a snapshot twice does
+/// nothing.
+///
+/// # Safety
+/// JNI function - handle must be a valid pointer returned by nativeCreate.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "system" fn Java_dev_opendata_LogDbReader_nativeEndSnapshot<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    handle: jlong,
+    snapshot: jlong,
+) {
+    if handle == 0 {
+        let _ = env.throw_new(
+            "java/lang/NullPointerException",
+            "LogDbReader handle is null",
+        );
+        return;
+    }
+
+    let reader_handle = unsafe { &*(handle as *const LogDbReaderHandle) };
+    reader_handle.snapshots.end(snapshot as u64);
+}
+
+/// Returns the reader's position for a key: the next sequence `nativePoll` reads from.
+///
+/// # Safety
+/// JNI function - handle must be a valid pointer returned by nativeCreate.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "system" fn Java_dev_opendata_LogDbReader_nativePosition<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    handle: jlong,
+    key: JByteArray<'local>,
+) -> jlong {
+    if handle == 0 {
+        let _ = env.throw_new(
+            "java/lang/NullPointerException",
+            "LogDbReader handle is null",
+        );
+        return -1;
+    }
+
+    let reader_handle = unsafe { &*(handle as *const LogDbReaderHandle) };
+
+    match env.convert_byte_array(&key) {
+        Ok(b) => reader_handle.positions.get(&Bytes::from(b)) as jlong,
+        Err(e) => {
+            let _ = env.throw_new("dev/opendata/common/OpenDataNativeException", e.to_string());
+            -1
+        }
+    }
+}
+
+/// Registers a listener notified of new entries of a key as they become
+/// visible to the reader, see `watch.rs`.
+///
+/// The reader's watch poller starts with its first watch.
+///
+/// # Arguments
+/// * `handle` - Native LogDbReader pointer
+/// * `key` - Key to watch
+/// * `from_sequence` - First sequence whose entries notify the listener
+/// * `listener` - Java TailListener called with each new tail of the key
+///
+/// # Returns
+/// Id of the watch, passed to nativeUnwatch
+///
+/// # Safety
+/// JNI function - handle must be a valid pointer returned by nativeCreate.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "system" fn Java_dev_opendata_LogDbReader_nativeWatch<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    handle: jlong,
+    key: JByteArray<'local>,
+    from_sequence: jlong,
+    listener: JObject<'local>,
+) -> jlong {
+    if handle == 0 {
+        let _ = env.throw_new(
+            "java/lang/NullPointerException",
+            "LogDbReader handle is null",
+        );
+        return 0;
+    }
+
+    if from_sequence < 0 {
+        let _ = env.throw_new(
+            "java/lang/IllegalArgumentException",
+            "fromSequence must be >= 0",
+        );
+        return 0;
+    }
+
+    let reader_handle = unsafe { &*(handle as *const LogDbReaderHandle) };
+
+    let key_bytes = match env.convert_byte_array(&key) {
+        Ok(b) => Bytes::from(b),
+        Err(e) => {
+            let _ = env.throw_new("dev/opendata/common/OpenDataNativeException", e.to_string());
+            return 0;
+        }
+    };
+
+    let id = match reader_handle
+        .watches
+        .watch(&env, key_bytes, from_sequence as u64, &listener)
+    {
+        Ok(id) => id,
+        Err(e) => {
+            let _ = env.throw_new("dev/opendata/common/OpenDataNativeException", e);
+            return 0;
+        }
+    };
+    reader_handle
+        .watch_poller
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .get_or_insert_with(|| {
+            spawn_watch_poller(
+                &reader_handle.runtime_handle,
+                reader_handle.refresh_interval,
+                Arc::clone(&reader_handle.reader),
+                Arc::clone(&reader_handle.watches),
+            )
+        });
+    id as jlong
+}
+
+/// Removes a watch registered with nativeWatch.
+///
+/// # Arguments
+/// * `handle` - Native LogDbReader pointer
+/// * `id` - Id of the watch
+///
+/// # Returns
+/// Whether the watch was registered
+///
+/// # Safety
+/// JNI function - handle must be a valid pointer returned by nativeCreate.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "system" fn Java_dev_opendata_LogDbReader_nativeUnwatch<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    handle: jlong,
+    id: jlong,
+) -> jboolean {
+    if handle == 0 {
+        let _ = env.throw_new(
+            "java/lang/NullPointerException",
+            "LogDbReader handle is null",
+        );
+        return JNI_FALSE;
+    }
+
+    let reader_handle = unsafe { &*(handle as *const LogDbReaderHandle) };
+    if reader_handle.watches.unwatch(id as u64) {
+        JNI_TRUE
+    } else {
+        JNI_FALSE
+    }
+}
+
+/// Interval between checks while waiting for a sequence to become visible.
+const WAIT_POLL_INTERVAL: std::time::Duration = std::time::Duration::from_millis(1);
+
+/// Blocks until the reader's view includes an entry for a key at or after a sequence.
+///
+/// The wait is not registered with the watchdog, because waiting for data
+/// that has not been written yet is expected rather than a stuck call.
+///
+/// # Arguments
+/// * `handle` - Native LogDbReader pointer
+/// * `key` - The key to wait on
+/// * `sequence` - The sequence to wait for
+/// * `timeout_ms` - Maximum time to wait in milliseconds
+///
+/// # Returns
+/// true if the sequence became visible, false if the timeout elapsed first
+///
+/// # Safety
+/// JNI function - handle must be a valid pointer returned by nativeCreate.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "system" fn Java_dev_opendata_LogDbReader_nativeWaitForSequence<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    handle: jlong,
+    key: JByteArray<'local>,
+    sequence: jlong,
+    timeout_ms: jlong,
+) -> jboolean {
+    if handle == 0 {
+        let _ = env.throw_new(
+            "java/lang/NullPointerException",
+            "LogDbReader handle is null",
+        );
+        return JNI_FALSE;
+    }
+
+    let reader_handle = unsafe { &*(handle as *const LogDbReaderHandle) };
+
+    let key_bytes = match env.convert_byte_array(&key) {
+        Ok(b) => Bytes::from(b),
+        Err(e) => {
+            let _ = env.throw_new("dev/opendata/common/OpenDataNativeException", e.to_string());
+            return JNI_FALSE;
+        }
+    };
+
+    let deadline = Instant::now() + std::time::Duration::from_millis(timeout_ms as u64);
+    let _blocked = reader_handle.blocked_callers.enter();
+    let result = reader_handle.runtime_handle.block_on(wait_visible(
+        reader_handle,
+        &key_bytes,
+        sequence as u64,
+        deadline,
+    ));
+
+    match result {
+        Ok(true) => JNI_TRUE,
+        Ok(false) => JNI_FALSE,
+        Err(e) => {
+            let _ = env.throw_new("dev/opendata/common/OpenDataNativeException", e.to_string());
+            JNI_FALSE
+        }
+    }
+}
+
+/// Polls until the reader's view includes an entry for `key` at or after
+/// `sequence`, returning false once `deadline` passes first.
+async fn wait_visible(
+    reader_handle: &LogDbReaderHandle,
+    key: &Bytes,
+    sequence: u64,
+    deadline: Instant,
+) -> Result<bool, log::Error> {
+    loop {
+        let reader = reader_handle.reader();
+        let mut iter = reader.scan(key.clone(), sequence..).await?;
+        if iter.next().await?.is_some() {
+            return Ok(true);
+        }
+        if Instant::now() >= deadline {
+            return Ok(false);
+        }
+        tokio::time::sleep(WAIT_POLL_INTERVAL).await;
+    }
+}
+
+/// Longest time a read-your-writes scan waits for the paired writer's
+/// appends to become visible.
+const READ_YOUR_WRITES_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(30);
+
+/// Waits until the reader's view includes every entry of `key` appended
+/// through the paired writer so far.
+///
+/// Throws IllegalStateException if no writer is paired or it was closed, and
+/// OpenDataNativeException if the entries do not become visible within
+/// `READ_YOUR_WRITES_TIMEOUT`; returns false once thrown.
+fn await_paired_writes(
+    env: &mut JNIEnv<'_>,
+    reader_handle: &LogDbReaderHandle,
+    key: &Bytes,
+) -> bool {
+    let paired = reader_handle
+        .paired_writer
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .clone();
+    let Some(paired) = paired else {
+        let _ = env.throw_new(
+            "java/lang/IllegalStateException",
+            "No writer is paired with this LogDbReader",
+        );
+        return false;
+    };
+    let Some(watches) = paired.upgrade() else {
+        let _ = env.throw_new(
+            "java/lang/IllegalStateException",
+            "The writer paired with this LogDbReader has been closed",
+        );
+        return false;
+    };
+    let Some(tail) = watches.appended().tail(key) else {
+        return true;
+    };
+    drop(watches);
+
+    let deadline = Instant::now() + READ_YOUR_WRITES_TIMEOUT;
+    let _blocked = reader_handle.blocked_callers.enter();
+    let result =
+        reader_handle
+            .runtime_handle
+            .block_on(wait_visible(reader_handle, key, tail - 1, deadline));
+    match result {
+        Ok(true) => true,
+        Ok(false) => {
+            let _ = env.throw_new(
+                "dev/opendata/common/OpenDataNativeException",
+                format!(
+                    "Timed out after {} ms waiting for sequence {} to become visible",
+                    READ_YOUR_WRITES_TIMEOUT.as_millis(),
+                    tail - 1
+                ),
+            );
+            false
+        }
+        Err(e) => {
+            let _ = env.throw_new("dev/opendata/common/OpenDataNativeException", e.to_string());
+            false
+        }
+    }
+}
+
+/// Returns a key's lag: its tail sequence minus a consumed sequence.
+///
+/// The tail is one past the key's last entry in this reader's view. Each
+/// reader caches the tails it has seen, so repeated calls only scan entries
+/// appended since the previous call. Because sequences are global, the lag
+/// counts sequences rather than entries when keys are interleaved.
+///
+/// # Arguments
+/// * `handle` - Native LogDbReader pointer
+/// * `key` - The consumed key
+/// * `from_sequence` - The consumed sequence, or -1 for the reader's poll position
+///
+/// # Safety
+/// JNI function - handle must be a valid pointer returned by nativeCreate.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "system" fn Java_dev_opendata_LogDbReader_nativeGetLag<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    handle: jlong,
+    key: JByteArray<'local>,
+    from_sequence: jlong,
+) -> jlong {
+    if handle == 0 {
+        let _ = env.throw_new(
+            "java/lang/NullPointerException",
+            "LogDbReader handle is null",
+        );
+        return -1;
+    }
+
+    let reader_handle = unsafe { &*(handle as *const LogDbReaderHandle) };
+
+    let key_bytes = match env.convert_byte_array(&key) {
+        Ok(b) => Bytes::from(b),
+        Err(e) => {
+            let _ = env.throw_new("dev/opendata/common/OpenDataNativeException", e.to_string());
+            return -1;
+        }
+    };
+
+    let from = if from_sequence < 0 {
+        reader_handle.positions.get(&key_bytes)
+    } else {
+        from_sequence as u64
+    };
+    let known_tail = reader_handle.tails.get(&key_bytes);
+
+    let _blocked = reader_handle.blocked_callers.enter();
+    let _tracked = watchdog::track_call(reader_handle.watchdog.as_ref(), "lag");
+    let result = reader_handle.runtime_handle.block_on(async {
+        let reader = reader_handle.reader();
+        let mut iter = reader
+            .scan(key_bytes.clone(), from.max(known_tail)..)
+            .await?;
+        let mut tail = known_tail;
+        while let Some(entry) = iter.next().await? {
+            tail = entry.sequence + 1;
+        }
+        Ok::<u64, log::Error>(tail)
+    });
+
+    match result {
+        Ok(tail) => {
+            reader_handle.tails.advance(key_bytes, tail);
+            tail.saturating_sub(from) as jlong
+        }
+        Err(e) => {
+            let _ = env.throw_new("dev/opendata/common/OpenDataNativeException", e.to_string());
+            -1
+        }
+    }
+}
+
+/// Scans up to `max` entries for a key from `start_seq` using a LogDbReader.
+///
+/// With a resume token, reads through the token's cursor and moves the
+/// token past the entries instead of reading ahead; filtered scans do not
+/// read ahead either. Records the scan
+/// latency and feeds auto-commit. On failure, throws the Java exception and
+/// returns None.
+#[allow(clippy::too_many_arguments)]
+fn scan_reader(
+    env: &mut JNIEnv<'_>,
+    reader_handle: &LogDbReaderHandle,
+    key_bytes: Bytes,
+    start_seq: u64,
+    max: usize,
+    resume: Option<&mut ResumeToken>,
+    filter: &ScanFilter,
+    timer: &mut CallTimer,
+) -> Option<Vec<LogEntry>> {
+    let reads_ahead = resume.is_none() && *filter == ScanFilter::ALL;
+    // Scan entries using the LogDbReader
+    timer.mark_submitted();
+    let blocked = reader_handle.blocked_callers.enter();
+    let tracked = watchdog::track_call(reader_handle.watchdog.as_ref(), "scan");
+    let entries_result = reader_handle.runtime_handle.block_on(async {
+        timer.mark_storage_started();
+        let result = watchdog::guard_call(reader_handle.watchdog.as_ref(), "scan", async {
+            if let Some(token) = resume {
+                return read_resumed(reader_handle, &key_bytes, token, max).await;
+            }
+            if let Some(read_ahead) = reader_handle.read_ahead.as_ref().filter(|_| reads_ahead) {
+                if let Some(entries) = read_ahead.take(&key_bytes, start_seq, max).await {
+                    reader_handle.served.record_storage(&entries);
+                    return Ok(entries);
+                }
+            }
+            if let Some(tail_cache) = reader_handle.tail_cache() {
+                if let Some(mut entries) = tail_cache.scan(&key_bytes, start_seq, max) {
+                    filter.retain(&mut entries);
+                    reader_handle.served.record_cache(&entries);
+                    return Ok(entries);
+                }
+            }
+            let reader = reader_handle.reader();
+            let entries =
+                scan_entries(reader.as_ref(), key_bytes.clone(), start_seq, max, filter).await?;
+            reader_handle.served.record_storage(&entries);
+            Ok(entries)
+        })
+        .await;
+        timer.mark_storage_finished();
+        result
+    });
+    drop(tracked);
+    drop(blocked);
+    reader_handle
+        .latency
+        .record(Operation::Scan, timer.storage_latency());
+    reader_handle.slow_log.scan(
+        timer.storage_latency(),
+        &key_bytes,
+        start_seq,
+        entries_result
+            .as_ref()
+            .ok()
+            .and_then(|r| r.as_ref().ok())
+            .map(Vec::as_slice),
+    );
+    jfr::scan(
+        &Tags::default(),
+        timer.storage_latency(),
+        entries_result
+            .as_ref()
+            .ok()
+            .and_then(|r| r.as_ref().ok())
+            .map(Vec::as_slice),
+    );
+    if let Ok(Ok(entries)) = &entries_result {
+        reader_handle.skew.record(entries);
+    }
+
+    match entries_result {
+        Ok(Ok(entries)) => {
+            if let Some(read_ahead) = reader_handle.read_ahead.as_ref().filter(|_| reads_ahead) {
+                read_ahead.after_scan(
+                    reader_handle.reader(),
+                    &reader_handle.runtime_handle,
+                    key_bytes.clone(),
+                    &entries,
+                    max,
+                );
+            }
+            // Remember the next sequence to read for the next automatic commit
+            if let (Some(committer), Some(last)) = (&reader_handle.auto_commit, entries.last()) {
+                committer.observe(key_bytes, last.sequence + 1);
+            }
+            Some(entries)
+        }
+        Ok(Err(e)) => {
+            let _ = env.throw_new("dev/opendata/common/OpenDataNativeException", e.to_string());
+            reader_handle
+                .profiler
+                .record(Operation::Scan, &timer.finish());
+            None
+        }
+        Err(stuck) => {
+            throw_stuck_call(env, &stuck);
+            None
+        }
+    }
+}
+
+/// Reads the next page of a resumed scan through the token's cursor, or
+/// from the tail cache or a new cursor if it is gone, and moves the token
+/// past the page.
+async fn read_resumed(
+    reader_handle: &LogDbReaderHandle,
+    key: &Bytes,
+    token: &mut ResumeToken,
+    max: usize,
+) -> Result<Vec<LogEntry>, log::Error> {
+    if let Some(mut cursor) = reader_handle.cursors.take(token) {
+        if let Some(result) = cursor.read(max).await {
+            return park_cursor(reader_handle, cursor, token, max, result);
+        }
+    }
+    if let Some(tail_cache) = reader_handle.tail_cache() {
+        if let Some(entries) = tail_cache.scan(key, token.next_sequence, max) {
+            reader_handle.served.record_cache(&entries);
+            token.advance(0, &entries);
+            return Ok(entries);
+        }
+    }
+    let mut cursor = reader_handle.cursors.open(
+        reader_handle.reader(),
+        &reader_handle.runtime_handle,
+        key.clone(),
+        token.next_sequence,
+    );
+    match cursor.read(max).await {
+        Some(result) => park_cursor(reader_handle, cursor, token, max, result),
+        None => {
+            // The cursor's task failed before reading; scan without one
+            let reader = reader_handle.reader();
+            let entries = scan_entries(
+                reader.as_ref(),
+                key.clone(),
+                token.next_sequence,
+                max,
+                &ScanFilter::ALL,
+            )
+            .await?;
+            reader_handle.served.record_storage(&entries);
+            token.advance(0, &entries);
+            Ok(entries)
+        }
+    }
+}
+
+/// Parks a cursor after it read a full page, and moves the token onto it.
+fn park_cursor(
+    reader_handle: &LogDbReaderHandle,
+    cursor: Cursor,
+    token: &mut ResumeToken,
+    max: usize,
+    result: Result<Vec<LogEntry>, log::Error>,
+) -> Result<Vec<LogEntry>, log::Error> {
+    let entries = result?;
+    reader_handle.served.record_storage(&entries);
+    // A short page means the iterator reached the end of the key
+    let id = if entries.len() >= max {
+        reader_handle.cursors.park(cursor)
+    } else {
+        0
+    };
+    token.advance(id, &entries);
+    Ok(entries)
+}
+
+/// Converts scanned entries into a Java LogEntry[] and records the call's phases.
+fn finish_reader_scan(
+    env: &mut JNIEnv<'_>,
+    reader_handle: &LogDbReaderHandle,
+    entries: &[LogEntry],
+    timer: CallTimer,
+) -> jobjectArray {
+    let java_result = match create_log_entry_array(env, entries) {
+        Ok(arr) => arr,
+        Err(e) => {
+            let _ = env.throw_new("dev/opendata/common/OpenDataNativeException", e.to_string());
+            std::ptr::null_mut()
+        }
+    };
+    reader_handle
+        .profiler
+        .record(Operation::Scan, &timer.finish());
+    java_result
+}
+
+/// Returns the HdrHistogram (V2 encoding, microseconds) of scan latencies
+/// recorded since the LogDbReader was opened.
+///
+/// # Safety
+/// JNI function - handle must be a valid pointer returned by nativeCreate.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "system" fn Java_dev_opendata_LogDbReader_nativeGetLatencyHistogram<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    handle: jlong,
+    op: jint,
+) -> jbyteArray {
+    if handle == 0 {
+        let _ = env.throw_new(
+            "java/lang/NullPointerException",
+            "LogDbReader handle is null",
+        );
+        return std::ptr::null_mut();
+    }
+
+    let reader_handle = unsafe { &*(handle as *const LogDbReaderHandle) };
+    encode_latency_histogram(&mut env, &reader_handle.latency, op)
+}
+
+/// Returns the accumulated per-phase timing breakdown for the given operation.
+///
+/// See `Java_dev_opendata_LogDb_nativeGetTimingBreakdown` for the layout.
+///
+/// # Safety
+/// JNI function - handle must be a valid pointer returned by nativeCreate.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "system" fn Java_dev_opendata_LogDbReader_nativeGetTimingBreakdown<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    handle: jlong,
+    op: jint,
+) -> jlongArray {
+    if handle == 0 {
+        let _ = env.throw_new(
+            "java/lang/NullPointerException",
+            "LogDbReader handle is null",
+        );
+        return std::ptr::null_mut();
+    }
+
+    let reader_handle = unsafe { &*(handle as *const LogDbReaderHandle) };
+    timing_breakdown_array(&mut env, &reader_handle.profiler, op)
+}
+
+/// Records the hash of the Java stack that opened a LogDbReader, see
+/// `handles.rs`.
+///
+/// # Safety
+/// JNI function - handle must be a valid pointer returned by nativeCreate.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "system" fn Java_dev_opendata_LogDbReader_nativeSetOrigin<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    handle: jlong,
+    stack_hash: jint,
+) {
+    if handle == 0 {
+        let _ = env.throw_new(
+            "java/lang/NullPointerException",
+            "LogDbReader handle is null",
+        );
+        return;
+    }
+
+    let reader_handle = unsafe { &*(handle as *const LogDbReaderHandle) };
+    reader_handle.registration.set_stack_hash(stack_hash);
+}
+
+/// Starts a new measurement period, discarding the latency histograms, phase
+/// timings and blocked caller peak recorded so far.
+///
+/// # Arguments
+/// * `handle` - Native LogDbReader pointer
+/// * `warming_up` - Whether the new period is a warmup
+///
+/// # Safety
+/// JNI function - handle must be a valid pointer returned by nativeCreate.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "system" fn Java_dev_opendata_LogDbReader_nativeMarkWarmup<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    handle: jlong,
+    warming_up: jboolean,
+) {
+    if handle == 0 {
+        let _ = env.throw_new(
+            "java/lang/NullPointerException",
+            "LogDbReader handle is null",
+        );
+        return;
+    }
+
+    let reader_handle = unsafe { &*(handle as *const LogDbReaderHandle) };
+    restart_measurement(
+        &reader_handle.latency,
+        &reader_handle.profiler,
+        &reader_handle.blocked_callers,
+        &reader_handle.measurement,
+        warming_up == JNI_TRUE,
+    );
+}
+
+/// Blocks until the rate of an operation is steady or the timeout elapses.
+///
+/// Not tracked by the watchdog: the call only sleeps and samples counters.
+///
+/// # Arguments
+/// * `handle` - Native LogDbReader pointer
+/// * `op` - Ordinal of the Java `Operation` enum
+/// * `config` - Java SteadyStateConfig object
+/// * `timeout_ms` - Maximum time to wait
+///
+/// # Safety
+/// JNI function - handle must be a valid pointer returned by nativeCreate.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "system" fn Java_dev_opendata_LogDbReader_nativeAwaitSteadyState<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    handle: jlong,
+    op: jint,
+    config: JObject<'local>,
+    timeout_ms: jlong,
+) -> jboolean {
+    if handle == 0 {
+        let _ = env.throw_new(
+            "java/lang/NullPointerException",
+            "LogDbReader handle is null",
+        );
+        return JNI_FALSE;
+    }
+
+    let reader_handle = unsafe { &*(handle as *const LogDbReaderHandle) };
+    let _blocked = reader_handle.blocked_callers.enter();
+    await_steady_state(
+        &mut env,
+        &reader_handle.runtime_handle,
+        &reader_handle.latency,
+        op,
+        &config,
+        timeout_ms,
+    )
+}
+
+/// Returns native statistics for a LogDbReader as a `java.util.Map<String, Long>`.
+///
+/// # Safety
+/// JNI function - handle must be a valid pointer returned by nativeCreate.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "system" fn Java_dev_opendata_LogDbReader_nativeStats<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    handle: jlong,
+) -> jobject {
+    if handle == 0 {
+        let _ = env.throw_new(
+            "java/lang/NullPointerException",
+            "LogDbReader handle is null",
+        );
+        return std::ptr::null_mut();
+    }
+
+    let reader_handle = unsafe { &*(handle as *const LogDbReaderHandle) };
+    match create_stats_map(&mut env, &reader_handle.stats()) {
+        Ok(map) => map.into_raw(),
+        Err(e) => {
+            let _ = env.throw_new("dev/opendata/common/OpenDataNativeException", e.to_string());
+            std::ptr::null_mut()
+        }
+    }
+}
+
+/// Returns the reader's stats and latency summaries with the kind of each.
+///
+/// Readers have no tags, so the snapshot's tags are empty.
+///
+/// # Returns
+/// MetricsSnapshot of the reader
+///
+/// # Safety
+/// JNI function - handle must be a valid pointer returned by nativeCreate.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "system" fn Java_dev_opendata_LogDbReader_nativeMetricsSnapshot<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    handle: jlong,
+) -> jobject {
+    if handle == 0 {
+        let _ = env.throw_new(
+            "java/lang/NullPointerException",
+            "LogDbReader handle is null",
+        );
+        return std::ptr::null_mut();
+    }
 
-        // Extract key byte[] from Record
-        let key_obj = match env.call_method(&record_obj, "key", "()[B", &[]) {
-            Ok(v) => v.l().unwrap(),
-            Err(e) => {
-                let _ = env.throw_new("dev/opendata/common/OpenDataNativeException", e.to_string());
-                return std::ptr::null_mut();
-            }
-        };
-        let key_array: JByteArray = key_obj.into();
-        let key_bytes = match env.convert_byte_array(&key_array) {
-            Ok(b) => Bytes::from(b),
-            Err(e) => {
-                let _ = env.throw_new("dev/opendata/common/OpenDataNativeException", e.to_string());
-                return std::ptr::null_mut();
+    let reader_handle = unsafe { &*(handle as *const LogDbReaderHandle) };
+    let mut stats = reader_handle.stats();
+    reader_handle.latency.append_summary_stats(&mut stats);
+    match create_metrics_snapshot(&mut env, &[], &stats) {
+        Ok(snapshot) => snapshot,
+        Err(e) => {
+            let _ = env.throw_new("dev/opendata/common/OpenDataNativeException", e.to_string());
+            std::ptr::null_mut()
+        }
+    }
+}
+
+/// Returns the traffic of a LogDbReader since the previous call, or since it
+/// opened, as a `java.util.Map<String, Long>`. Readers never append, so
+/// their records and bytes in are always 0.
+///
+/// # Safety
+/// JNI function - handle must be a valid pointer returned by nativeCreate.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "system" fn Java_dev_opendata_LogDbReader_nativeIntervalStats<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    handle: jlong,
+) -> jobject {
+    if handle == 0 {
+        let _ = env.throw_new(
+            "java/lang/NullPointerException",
+            "LogDbReader handle is null",
+        );
+        return std::ptr::null_mut();
+    }
+
+    let reader_handle = unsafe { &*(handle as *const LogDbReaderHandle) };
+    let stats = reader_handle
+        .intervals
+        .next(|| Traffic::new((0, 0), reader_handle.served.volume()));
+    match create_stats_map(&mut env, &stats) {
+        Ok(map) => map.into_raw(),
+        Err(e) => {
+            let _ = env.throw_new("dev/opendata/common/OpenDataNativeException", e.to_string());
+            std::ptr::null_mut()
+        }
+    }
+}
+
+/// Attaches a LogDb writer through which a reader commits offsets.
+///
+/// The reader holds only a weak reference: once the writer is closed, commits
+/// through the reader fail.
+///
+/// # Safety
+/// JNI function - both handles must be valid pointers returned by nativeCreate.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "system" fn Java_dev_opendata_LogDbReader_nativeAttachOffsetWriter<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    handle: jlong,
+    writer_handle: jlong,
+) {
+    if handle == 0 {
+        let _ = env.throw_new(
+            "java/lang/NullPointerException",
+            "LogDbReader handle is null",
+        );
+        return;
+    }
+    if writer_handle == 0 {
+        let _ = env.throw_new("java/lang/NullPointerException", "LogDb handle is null");
+        return;
+    }
+
+    let reader_handle = unsafe { &*(handle as *const LogDbReaderHandle) };
+    let log_handle = unsafe { &*(writer_handle as *const LogHandle) };
+
+    *reader_handle
+        .offset_writer
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner) = Some(Arc::downgrade(&log_handle.log));
+}
+
+/// Attaches the tail cache of a LogDb writer, so that scans through the
+/// reader are answered from the writer's recently appended entries.
+///
+/// The reader holds only a weak reference: once the writer is closed, scans
+/// go to storage again. Throws IllegalArgumentException if the writer has no
+/// tail cache configured.
+///
+/// # Safety
+/// JNI function - both handles must be valid pointers returned by nativeCreate.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "system" fn Java_dev_opendata_LogDbReader_nativeAttachTailCache<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    handle: jlong,
+    writer_handle: jlong,
+) {
+    if handle == 0 {
+        let _ = env.throw_new(
+            "java/lang/NullPointerException",
+            "LogDbReader handle is null",
+        );
+        return;
+    }
+    if writer_handle == 0 {
+        let _ = env.throw_new("java/lang/NullPointerException", "LogDb handle is null");
+        return;
+    }
+
+    let reader_handle = unsafe { &*(handle as *const LogDbReaderHandle) };
+    let log_handle = unsafe { &*(writer_handle as *const LogHandle) };
+
+    let Some(tail_cache) = &log_handle.tail_cache else {
+        let _ = env.throw_new(
+            "java/lang/IllegalArgumentException",
+            "LogDb has no tail cache configured",
+        );
+        return;
+    };
+    *reader_handle
+        .tail_cache
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner) = Some(Arc::downgrade(tail_cache));
+}
+
+/// Pairs a LogDb writer with the reader, whose appends read-your-writes
+/// scans through the reader wait for.
+///
+/// The reader holds only a weak reference to the writer's appended tails.
+///
+/// # Safety
+/// JNI function - both handles must be valid pointers returned by nativeCreate.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "system" fn Java_dev_opendata_LogDbReader_nativePairWriter<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    handle: jlong,
+    writer_handle: jlong,
+) {
+    if handle == 0 {
+        let _ = env.throw_new(
+            "java/lang/NullPointerException",
+            "LogDbReader handle is null",
+        );
+        return;
+    }
+    if writer_handle == 0 {
+        let _ = env.throw_new("java/lang/NullPointerException", "LogDb handle is null");
+        return;
+    }
+
+    let reader_handle = unsafe { &*(handle as *const LogDbReaderHandle) };
+    let log_handle = unsafe { &*(writer_handle as *const LogHandle) };
+
+    *reader_handle
+        .paired_writer
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner) = Some(Arc::downgrade(&log_handle.watches));
+}
+
+/// Commits a consumer group's offset for a key through the attached writer.
+///
+/// # Safety
+/// JNI function - handle must be a valid pointer returned by nativeCreate.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "system" fn Java_dev_opendata_LogDbReader_nativeCommitOffset<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    handle: jlong,
+    group: JString<'local>,
+    key: JByteArray<'local>,
+    sequence: jlong,
+) {
+    if handle == 0 {
+        let _ = env.throw_new(
+            "java/lang/NullPointerException",
+            "LogDbReader handle is null",
+        );
+        return;
+    }
+
+    let reader_handle = unsafe { &*(handle as *const LogDbReaderHandle) };
+
+    let offset_key = match extract_offset_key(&mut env, &group, &key) {
+        Ok(k) => k,
+        Err(e) => {
+            let _ = env.throw_new("java/lang/IllegalArgumentException", e);
+            return;
+        }
+    };
+
+    let writer = reader_handle
+        .offset_writer
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .as_ref()
+        .map(|w| w.upgrade());
+    let writer = match writer {
+        Some(Some(writer)) => writer,
+        Some(None) => {
+            let _ = env.throw_new(
+                "java/lang/IllegalStateException",
+                "Offset writer attached to this reader is closed",
+            );
+            return;
+        }
+        None => {
+            let _ = env.throw_new(
+                "java/lang/IllegalStateException",
+                "No offset writer attached to this reader",
+            );
+            return;
+        }
+    };
+
+    let _blocked = reader_handle.blocked_callers.enter();
+    let _tracked = watchdog::track_call(reader_handle.watchdog.as_ref(), "commitOffset");
+    let result = reader_handle.runtime_handle.block_on(append_offset(
+        &writer.current(),
+        offset_key.clone(),
+        sequence as u64,
+    ));
+    // Release the strong reference promptly so the writer can close
+    drop(writer);
+
+    match result {
+        Ok(entry_sequence) => reader_handle.offsets.update(
+            offset_key,
+            CachedOffset {
+                entry_sequence,
+                offset: sequence as u64,
+            },
+        ),
+        Err(e) => {
+            let _ = env.throw_new("dev/opendata/common/OpenDataNativeException", e.to_string());
+        }
+    }
+}
+
+/// Fetches a consumer group's committed offset for a key.
+///
+/// Reads the reserved offset key from this reader's view of the log, which
+/// reflects commits made through other processes once the reader refreshes.
+///
+/// # Returns
+/// The committed sequence, or -1 if the group has not committed for the key
+///
+/// # Safety
+/// JNI function - handle must be a valid pointer returned by nativeCreate.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "system" fn Java_dev_opendata_LogDbReader_nativeFetchOffset<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    handle: jlong,
+    group: JString<'local>,
+    key: JByteArray<'local>,
+) -> jlong {
+    if handle == 0 {
+        let _ = env.throw_new(
+            "java/lang/NullPointerException",
+            "LogDbReader handle is null",
+        );
+        return -1;
+    }
+
+    let reader_handle = unsafe { &*(handle as *const LogDbReaderHandle) };
+
+    let offset_key = match extract_offset_key(&mut env, &group, &key) {
+        Ok(k) => k,
+        Err(e) => {
+            let _ = env.throw_new("java/lang/IllegalArgumentException", e);
+            return -1;
+        }
+    };
+
+    // Only scan offset entries committed since the last one this handle saw
+    let cached = reader_handle.offsets.get(&offset_key);
+    let start = cached.map_or(0, |c| c.entry_sequence + 1);
+    let _blocked = reader_handle.blocked_callers.enter();
+    let _tracked = watchdog::track_call(reader_handle.watchdog.as_ref(), "fetchOffset");
+    let result = reader_handle.runtime_handle.block_on(async {
+        let reader = reader_handle.reader();
+        let mut iter = reader.scan(offset_key.clone(), start..).await?;
+        let mut latest = cached;
+        while let Some(entry) = iter.next().await? {
+            if let Some(offset) = offsets::decode_offset(&entry.value) {
+                latest = Some(CachedOffset {
+                    entry_sequence: entry.sequence,
+                    offset,
+                });
             }
-        };
+        }
+        Ok::<Option<CachedOffset>, log::Error>(latest)
+    });
 
-        // Extract value byte[] from Record
-        let value_obj = match env.call_method(&record_obj, "value", "()[B", &[]) {
-            Ok(v) => v.l().unwrap(),
-            Err(e) => {
-                let _ = env.throw_new("dev/opendata/common/OpenDataNativeException", e.to_string());
-                return std::ptr::null_mut();
+    match result {
+        Ok(Some(latest)) => {
+            reader_handle.offsets.update(offset_key, latest);
+            latest.offset as jlong
+        }
+        Ok(None) => -1,
+        Err(e) => {
+            let _ = env.throw_new("dev/opendata/common/OpenDataNativeException", e.to_string());
+            -1
+        }
+    }
+}
+
+/// Closes and frees a LogDbReader instance.
+///
+/// # Safety
+/// JNI function - handle must be a valid pointer returned by nativeCreate.
+#[no_mangle]
+pub extern "system" fn Java_dev_opendata_LogDbReader_nativeClose<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    handle: jlong,
+) {
+    if handle != 0 {
+        let reader_handle = unsafe { Box::from_raw(handle as *mut LogDbReaderHandle) };
+
+        // Stop the watchdog before the runtime it reports on
+        drop(reader_handle.watchdog);
+
+        // Stop polling for watches, whose listeners the poller may call
+        if let Some(task) = reader_handle
+            .watch_poller
+            .into_inner()
+            .unwrap_or_else(PoisonError::into_inner)
+        {
+            task.abort();
+        }
+
+        // Stop periodic commits and commit the final scan positions
+        if let Some(task) = reader_handle.auto_commit_task {
+            task.abort();
+        }
+        if let Some(committer) = &reader_handle.auto_commit {
+            let result = reader_handle.runtime_handle.block_on(commit_pending(
+                committer,
+                &reader_handle.offset_writer,
+                &reader_handle.offsets,
+            ));
+            if let Err(e) = result {
+                let _ = env.throw_new("dev/opendata/common/OpenDataNativeException", e);
             }
-        };
-        let value_array: JByteArray = value_obj.into();
+        }
+
+        // Shutdown the runtime
+        if let Some(rt) = reader_handle.runtime {
+            rt.shutdown_background();
+        }
+        // LogDbReader drops automatically
+    }
+}
+
+// =============================================================================
+// Helper Functions
+// =============================================================================
+
+/// Creates a Java AppendResult object for a batch starting at `start_sequence`.
+fn create_append_result<'local>(
+    env: &mut JNIEnv<'local>,
+    start_sequence: u64,
+    timestamp_ms: i64,
+) -> Result<JObject<'local>, jni::errors::Error> {
+    let class = env.find_class("dev/opendata/AppendResult")?;
+
+    // AppendResult is a record with (long sequence, long timestamp)
+    let obj = env.new_object(
+        class,
+        "(JJ)V",
+        &[
+            JValue::Long(start_sequence as i64),
+            JValue::Long(timestamp_ms),
+        ],
+    )?;
+
+    Ok(obj)
+}
 
-        // Extract timestampMs from Record
-        let timestamp_ms = match env.call_method(&record_obj, "timestampMs", "()J", &[]) {
-            Ok(v) => v.j().unwrap(),
-            Err(e) => {
-                let _ = env.throw_new("dev/opendata/common/OpenDataNativeException", e.to_string());
-                return std::ptr::null_mut();
-            }
-        };
+/// Creates a Java KeyAppendResult[] for the groups of a batch appended from
+/// `start_sequence`.
+fn create_key_append_result_array(
+    env: &mut JNIEnv<'_>,
+    start_sequence: u64,
+    groups: &[grouping::Group],
+) -> Result<jobjectArray, jni::errors::Error> {
+    let class = env.find_class("dev/opendata/KeyAppendResult")?;
+    let array = env.new_object_array(groups.len() as i32, &class, JObject::null())?;
+    for (i, group) in groups.iter().enumerate() {
+        let key = env.byte_array_from_slice(&group.key)?;
+        // KeyAppendResult is a record with (byte[] key, long sequence, int count, long timestamp)
+        let result = env.new_object(
+            &class,
+            "([BJIJ)V",
+            &[
+                JValue::Object(&key),
+                JValue::Long((start_sequence + group.offset) as i64),
+                JValue::Int(group.count as i32),
+                JValue::Long(group.timestamp_ms),
+            ],
+        )?;
+        env.set_object_array_element(&array, i as i32, &result)?;
+        env.delete_local_ref(result)?;
+        env.delete_local_ref(key)?;
+    }
+    Ok(array.into_raw())
+}
 
-        if i == 0 {
-            first_timestamp_ms = timestamp_ms;
-        }
+/// Creates a Java KeyWatermark[] from per-key tails.
+fn create_key_watermark_array(
+    env: &mut JNIEnv<'_>,
+    tails: &[(Bytes, u64)],
+) -> Result<jobjectArray, jni::errors::Error> {
+    let class = env.find_class("dev/opendata/KeyWatermark")?;
+    let array = env.new_object_array(tails.len() as i32, &class, JObject::null())?;
+    for (i, (key, tail)) in tails.iter().enumerate() {
+        let key = env.byte_array_from_slice(key)?;
+        // KeyWatermark is a record with (byte[] key, long tail)
+        let watermark = env.new_object(
+            &class,
+            "([BJ)V",
+            &[JValue::Object(&key), JValue::Long(*tail as i64)],
+        )?;
+        env.set_object_array_element(&array, i as i32, &watermark)?;
+        env.delete_local_ref(watermark)?;
+        env.delete_local_ref(key)?;
+    }
+    Ok(array.into_raw())
+}
 
-        // Convert value with timestamp header
-        let value_bytes = match copy_value_with_timestamp(&mut env, &value_array, timestamp_ms) {
-            Ok(b) => b,
-            Err(e) => {
-                let _ = env.throw_new("dev/opendata/common/OpenDataNativeException", e.to_string());
-                return std::ptr::null_mut();
-            }
-        };
+/// Creates a Java Checkpoint.
+fn create_checkpoint_object<'local>(
+    env: &mut JNIEnv<'local>,
+    checkpoint: &CheckpointInfo,
+) -> Result<JObject<'local>, jni::errors::Error> {
+    let id = env.new_string(checkpoint.id.to_string())?;
+    let name = match &checkpoint.name {
+        Some(name) => JObject::from(env.new_string(name)?),
+        None => JObject::null(),
+    };
+    // Checkpoint is a record with (String id, String name, long manifestId,
+    // long createdAtMs, long expiresAtMs)
+    let obj = env.new_object(
+        "dev/opendata/Checkpoint",
+        "(Ljava/lang/String;Ljava/lang/String;JJJ)V",
+        &[
+            JValue::Object(&id),
+            JValue::Object(&name),
+            JValue::Long(checkpoint.manifest_id as i64),
+            JValue::Long(checkpoint.create_time_ms),
+            JValue::Long(checkpoint.expire_time_ms.unwrap_or(0)),
+        ],
+    )?;
+    env.delete_local_ref(name)?;
+    env.delete_local_ref(id)?;
+    Ok(obj)
+}
 
-        rust_records.push(Record {
-            key: key_bytes,
-            value: value_bytes,
-        });
+/// Creates a Java Checkpoint[].
+fn create_checkpoint_array(
+    env: &mut JNIEnv<'_>,
+    checkpoints: &[CheckpointInfo],
+) -> Result<jobjectArray, jni::errors::Error> {
+    let class = env.find_class("dev/opendata/Checkpoint")?;
+    let array = env.new_object_array(checkpoints.len() as i32, &class, JObject::null())?;
+    for (i, checkpoint) in checkpoints.iter().enumerate() {
+        let obj = create_checkpoint_object(env, checkpoint)?;
+        env.set_object_array_element(&array, i as i32, &obj)?;
+        env.delete_local_ref(obj)?;
     }
+    Ok(array.into_raw())
+}
 
-    // Use block_on with separate compaction runtime to avoid deadlocks
-    let result = log_handle
-        .runtime_handle
-        .block_on(async { log_handle.log.append(rust_records).await });
+/// Creates a Java KeyStats[] from per-key counts.
+fn create_key_stats_array(
+    env: &mut JNIEnv<'_>,
+    keys: &[(Bytes, KeyCounts)],
+) -> Result<jobjectArray, jni::errors::Error> {
+    let class = env.find_class("dev/opendata/KeyStats")?;
+    let array = env.new_object_array(keys.len() as i32, &class, JObject::null())?;
+    for (i, (key, counts)) in keys.iter().enumerate() {
+        let key = env.byte_array_from_slice(key)?;
+        // KeyStats is a record with (byte[] key, long recordsAppended,
+        // long bytesAppended, long scans, long entriesScanned,
+        // long bytesScanned, long lastAppendMs, long lastScanMs)
+        let stats = env.new_object(
+            &class,
+            "([BJJJJJJJ)V",
+            &[
+                JValue::Object(&key),
+                JValue::Long(counts.records_appended as i64),
+                JValue::Long(counts.bytes_appended as i64),
+                JValue::Long(counts.scans as i64),
+                JValue::Long(counts.entries_scanned as i64),
+                JValue::Long(counts.bytes_scanned as i64),
+                JValue::Long(counts.last_append_ms),
+                JValue::Long(counts.last_scan_ms),
+            ],
+        )?;
+        env.set_object_array_element(&array, i as i32, &stats)?;
+        env.delete_local_ref(stats)?;
+        env.delete_local_ref(key)?;
+    }
+    Ok(array.into_raw())
+}
 
-    match result {
-        Ok(append_result) => {
-            // Create Java AppendResult object with first record's timestamp
-            match create_append_result(&mut env, &append_result, first_timestamp_ms) {
-                Ok(obj) => obj.into_raw(),
-                Err(e) => {
-                    let _ =
-                        env.throw_new("dev/opendata/common/OpenDataNativeException", e.to_string());
-                    std::ptr::null_mut()
-                }
-            }
+/// Encodes a latency histogram into a Java byte[], throwing on failure.
+fn encode_latency_histogram(
+    env: &mut JNIEnv<'_>,
+    recorder: &LatencyRecorder,
+    op: jint,
+) -> jbyteArray {
+    let Some(op) = Operation::from_ordinal(op) else {
+        let _ = env.throw_new(
+            "java/lang/IllegalArgumentException",
+            format!("Unknown operation ordinal: {}", op),
+        );
+        return std::ptr::null_mut();
+    };
+
+    let encoded = match recorder.encode(op) {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            let _ = env.throw_new("dev/opendata/common/OpenDataNativeException", e);
+            return std::ptr::null_mut();
         }
+    };
+
+    match env.byte_array_from_slice(&encoded) {
+        Ok(arr) => arr.into_raw(),
         Err(e) => {
             let _ = env.throw_new("dev/opendata/common/OpenDataNativeException", e.to_string());
             std::ptr::null_mut()
@@ -551,357 +10232,764 @@ pub extern "system" fn Java_dev_opendata_LogDb_nativeAppend<'local>(
     }
 }
 
-/// Copies a Java byte array into a Rust buffer with a prepended timestamp header.
-///
-/// This avoids an intermediate allocation by copying directly into the final buffer.
-fn copy_value_with_timestamp(
-    env: &mut JNIEnv<'_>,
-    value: &JByteArray<'_>,
-    timestamp_ms: i64,
-) -> Result<Bytes, jni::errors::Error> {
-    let payload_len = env.get_array_length(value)? as usize;
+/// Discards a handle's recorded metrics and starts a new measurement period.
+fn restart_measurement(
+    latency: &LatencyRecorder,
+    profiler: &Profiler,
+    blocked_callers: &BlockedCallers,
+    measurement: &Measurement,
+    warming_up: bool,
+) {
+    latency.reset();
+    profiler.reset();
+    blocked_callers.reset_peak();
+    measurement.restart(warming_up);
+}
 
-    // Allocate final buffer: 8-byte header + payload
-    let mut buffer = vec![0u8; TIMESTAMP_HEADER_SIZE + payload_len];
+/// Waits for steady state of an operation, throwing on invalid arguments.
+fn await_steady_state(
+    env: &mut JNIEnv<'_>,
+    runtime_handle: &Handle,
+    latency: &LatencyRecorder,
+    op: jint,
+    config: &JObject<'_>,
+    timeout_ms: jlong,
+) -> jboolean {
+    let Some(op) = Operation::from_ordinal(op) else {
+        let _ = env.throw_new(
+            "java/lang/IllegalArgumentException",
+            format!("Unknown operation ordinal: {}", op),
+        );
+        return JNI_FALSE;
+    };
 
-    // Write timestamp header (big-endian)
-    buffer[..TIMESTAMP_HEADER_SIZE].copy_from_slice(&timestamp_ms.to_be_bytes());
+    let settings = match extract_steady_state_settings(env, config) {
+        Ok(s) => s,
+        Err(e) => {
+            let _ = env.throw_new("java/lang/IllegalArgumentException", e);
+            return JNI_FALSE;
+        }
+    };
 
-    // Copy payload directly from Java into buffer, avoiding intermediate Vec
-    if payload_len > 0 {
-        // Safety: buffer[TIMESTAMP_HEADER_SIZE..] has exactly payload_len bytes
-        // get_byte_array_region expects i8 slice, so we need to cast
-        let dest = &mut buffer[TIMESTAMP_HEADER_SIZE..];
-        let dest_i8 =
-            unsafe { std::slice::from_raw_parts_mut(dest.as_mut_ptr() as *mut i8, payload_len) };
-        env.get_byte_array_region(value, 0, dest_i8)?;
+    let timeout = std::time::Duration::from_millis(timeout_ms as u64);
+    if runtime_handle.block_on(metrics::await_steady_state(latency, op, settings, timeout)) {
+        JNI_TRUE
+    } else {
+        JNI_FALSE
     }
+}
 
-    Ok(Bytes::from(buffer))
+/// Extracts steady-state detection settings from a Java SteadyStateConfig.
+fn extract_steady_state_settings(
+    env: &mut JNIEnv<'_>,
+    config: &JObject<'_>,
+) -> Result<SteadyStateSettings, String> {
+    let window_ms = extract_long(env, config, "windowMs")?;
+    let windows = extract_int(env, config, "windows")?;
+    let max_coefficient_of_variation = extract_double(env, config, "maxCoefficientOfVariation")?;
+    Ok(SteadyStateSettings {
+        window: std::time::Duration::from_millis(window_ms as u64),
+        windows: windows as usize,
+        max_coefficient_of_variation,
+    })
 }
 
-/// Flushes all pending writes to durable storage.
-///
-/// # Safety
-/// JNI function - handle must be a valid pointer returned by nativeCreate.
-#[no_mangle]
-#[allow(clippy::not_unsafe_ptr_arg_deref)]
-pub extern "system" fn Java_dev_opendata_LogDb_nativeFlush<'local>(
-    mut env: JNIEnv<'local>,
-    _class: JClass<'local>,
-    handle: jlong,
-) {
-    if handle == 0 {
-        let _ = env.throw_new("java/lang/NullPointerException", "LogDb handle is null");
-        return;
+/// Extracts the bounds of a Java ScanFilter.
+fn extract_scan_filter(env: &mut JNIEnv<'_>, filter: &JObject<'_>) -> Result<ScanFilter, String> {
+    if filter.is_null() {
+        return Err("filter must not be null".to_string());
     }
+    Ok(ScanFilter {
+        min_value_length: extract_long(env, filter, "minValueLength")?.max(0) as u64,
+        max_value_length: extract_long(env, filter, "maxValueLength")?.max(0) as u64,
+        min_timestamp_ms: extract_long(env, filter, "minTimestamp")?,
+        max_timestamp_ms: extract_long(env, filter, "maxTimestamp")?,
+    })
+}
 
-    let log_handle = unsafe { &*(handle as *const LogHandle) };
-
-    let result = log_handle
-        .runtime_handle
-        .block_on(async { log_handle.log.flush().await });
+/// Copies a profiler snapshot into a Java long[], throwing on failure.
+fn timing_breakdown_array(env: &mut JNIEnv<'_>, profiler: &Profiler, op: jint) -> jlongArray {
+    let Some(op) = Operation::from_ordinal(op) else {
+        let _ = env.throw_new(
+            "java/lang/IllegalArgumentException",
+            format!("Unknown operation ordinal: {}", op),
+        );
+        return std::ptr::null_mut();
+    };
 
-    if let Err(e) = result {
+    let values = profiler.snapshot(op).map(|v| v as i64);
+    let array = match env.new_long_array(values.len() as i32) {
+        Ok(arr) => arr,
+        Err(e) => {
+            let _ = env.throw_new("dev/opendata/common/OpenDataNativeException", e.to_string());
+            return std::ptr::null_mut();
+        }
+    };
+    if let Err(e) = env.set_long_array_region(&array, 0, &values) {
         let _ = env.throw_new("dev/opendata/common/OpenDataNativeException", e.to_string());
+        return std::ptr::null_mut();
     }
+    array.into_raw()
 }
 
-/// Closes and frees a LogDb instance and its associated runtime.
-///
-/// # Safety
-/// JNI function - handle must be a valid pointer returned by nativeCreate.
-#[no_mangle]
-pub extern "system" fn Java_dev_opendata_LogDb_nativeClose<'local>(
-    mut env: JNIEnv<'local>,
-    _class: JClass<'local>,
-    handle: jlong,
-) {
-    if handle != 0 {
-        let log_handle = unsafe { Box::from_raw(handle as *mut LogHandle) };
-
-        // Destructure to take ownership of components
-        let LogHandle {
-            log,
-            runtime_handle,
-            runtime,
-            compaction_runtime,
-        } = *log_handle;
+/// Starts a watchdog for a handle if one is configured.
+fn start_watchdog(
+    settings: Option<WatchdogSettings>,
+    name: &'static str,
+    runtime: &Handle,
+) -> std::io::Result<Option<Watchdog>> {
+    settings
+        .map(|s| Watchdog::start(s, name, runtime.clone()))
+        .transpose()
+}
 
-        // Close the log using block_on
-        let result = runtime_handle.block_on(async { log.close().await });
+/// Appends records under the shared append lock, flushing if durable.
+async fn append_records(
+    log: &LogDb,
+    append_lock: &tokio::sync::RwLock<()>,
+    tail_cache: Option<&TailCache>,
+    watches: &Watches,
+    records: Vec<Record>,
+    ack_mode: AckMode,
+) -> Result<u64, log::Error> {
+    // Shared with other appends; excludes conditional appends mid-check
+    let _append = append_lock.read().await;
+    let result = append_cached(log, tail_cache, watches, records).await?;
+    if ack_mode == AckMode::Durable {
+        log.flush().await?;
+    }
+    Ok(result.start_sequence)
+}
 
-        if let Err(e) = result {
-            let _ = env.throw_new("dev/opendata/common/OpenDataNativeException", e.to_string());
+/// Appends records to storage, caching their entries if the handle has a
+/// tail cache, recording the tails of their keys and notifying the watches
+/// of their keys.
+///
+/// Every append of a handle must go through here, or the tail cache could
+/// serve a key's entries with one missing and watches could miss entries.
+pub(crate) async fn append_cached(
+    log: &LogDb,
+    tail_cache: Option<&TailCache>,
+    watches: &Watches,
+    records: Vec<Record>,
+) -> Result<AppendResult, log::Error> {
+    let tails = watches.capture(&records);
+    let offsets = AppendedTails::capture(&records);
+    let key_counts = watches.key_stats().map(|_| KeyStats::capture(&records));
+    let count = records.len();
+    let bytes = records
+        .iter()
+        .map(|r| (r.key.len() + r.value.len()) as u64)
+        .sum();
+    let result = match tail_cache {
+        None => log.append(records).await?,
+        Some(tail_cache) => {
+            let pending = tail_cache.begin(&records);
+            let result = log.append(records.clone()).await?;
+            pending.complete(result.start_sequence, records);
+            result
         }
+    };
+    watches.appended().record(result.start_sequence, offsets);
+    watches.volume().record(count, bytes);
+    if let (Some(key_stats), Some(key_counts)) = (watches.key_stats(), key_counts) {
+        key_stats.record_append(key_counts, current_timestamp_ms());
+    }
+    if let Some(tails) = tails {
+        watches.notify(result.start_sequence, tails);
+    }
+    Ok(result)
+}
 
-        // Shutdown the runtimes
-        if let Some(rt) = compaction_runtime {
-            rt.shutdown_background();
+/// Reads up to `max_entries` entries for a key from `start_sequence`, from
+/// the tail cache if it can answer, within the handle's consume rate limit if
+/// one is configured.
+async fn read_entries(
+    log: &LogDb,
+    rate_limiter: Option<&RateLimiter>,
+    tail_cache: Option<&TailCache>,
+    served: &ServedBytes,
+    key: Bytes,
+    start_sequence: u64,
+    max_entries: usize,
+) -> Result<Vec<LogEntry>, log::Error> {
+    if let Some(rate_limiter) = rate_limiter {
+        rate_limiter.admit_scan().await;
+    }
+    let cached = tail_cache.and_then(|c| c.scan(&key, start_sequence, max_entries));
+    let entries = match cached {
+        Some(entries) => {
+            served.record_cache(&entries);
+            entries
         }
-        if let Some(rt) = runtime {
-            rt.shutdown_background();
+        None => {
+            let entries =
+                scan_entries(log, key, start_sequence, max_entries, &ScanFilter::ALL).await?;
+            served.record_storage(&entries);
+            entries
         }
+    };
+    if let Some(rate_limiter) = rate_limiter {
+        rate_limiter.charge_scan(&entries);
     }
+    Ok(entries)
 }
 
-/// Scans entries from the log for a given key.
-///
-/// Uses the LogDb (which implements LogRead) to scan entries.
-///
-/// # Safety
-/// JNI function - handle must be a valid pointer returned by nativeCreate.
-#[no_mangle]
-#[allow(clippy::not_unsafe_ptr_arg_deref)]
-pub extern "system" fn Java_dev_opendata_LogDb_nativeScan<'local>(
-    mut env: JNIEnv<'local>,
-    _class: JClass<'local>,
-    handle: jlong,
-    key: JByteArray<'local>,
-    start_sequence: jlong,
-    max_entries: jlong,
-) -> jobjectArray {
-    if handle == 0 {
-        let _ = env.throw_new("java/lang/NullPointerException", "LogDb handle is null");
-        return std::ptr::null_mut();
+/// Reads up to `max_entries` entries passing `filter` for a key from
+/// `start_sequence` from storage.
+async fn scan_entries<R: LogRead>(
+    log: &R,
+    key: Bytes,
+    start_sequence: u64,
+    max_entries: usize,
+    filter: &ScanFilter,
+) -> Result<Vec<LogEntry>, log::Error> {
+    let mut iter = log.scan(key, start_sequence..).await?;
+    let mut entries = Assembler::default();
+    while entries.len() < max_entries {
+        match iter.next().await? {
+            Some(entry) => {
+                let complete = entries.len();
+                entries.push(entry);
+                if entries.len() > complete {
+                    entries.keep_newest_if(|entry| filter.matches(entry));
+                }
+            }
+            None => break,
+        }
     }
+    Ok(entries.finish())
+}
 
-    let log_handle = unsafe { &*(handle as *const LogHandle) };
-
-    let key_bytes = match env.convert_byte_array(&key) {
-        Ok(b) => Bytes::from(b),
-        Err(e) => {
-            let _ = env.throw_new("dev/opendata/common/OpenDataNativeException", e.to_string());
-            return std::ptr::null_mut();
+/// Scans up to `max_entries` entries of `key` exactly as stored, neither
+/// reassembling chunks nor filtering.
+async fn scan_stored<R: LogRead>(
+    log: &R,
+    key: Bytes,
+    start_sequence: u64,
+    max_entries: usize,
+) -> Result<Vec<LogEntry>, log::Error> {
+    let mut iter = log.scan(key, start_sequence..).await?;
+    let mut entries = Vec::new();
+    while entries.len() < max_entries {
+        match iter.next().await? {
+            Some(entry) => entries.push(entry),
+            None => break,
         }
-    };
+    }
+    Ok(entries)
+}
 
-    let max = max_entries as usize;
-    let start_seq = start_sequence as u64;
+/// Queues a job on a bridge pool, throwing if the pool is shutting down.
+fn submit_bridged(env: &mut JNIEnv<'_>, bridge: &BridgePool, job: bridge::Job) {
+    if !bridge.submit(job) {
+        let _ = env.throw_new("java/lang/IllegalStateException", "LogDb is closing");
+    }
+}
 
-    // Scan entries using the LogDb (which implements LogRead)
-    let entries_result = log_handle.runtime_handle.block_on(async {
-        let mut iter = log_handle.log.scan(key_bytes, start_seq..).await?;
-        let mut entries = Vec::with_capacity(max);
-        while entries.len() < max {
-            match iter.next().await? {
-                Some(entry) => entries.push(entry),
-                None => break,
-            }
+/// Completes a Java CompletableFuture with a value, or exceptionally with an
+/// OpenDataNativeException carrying the error message.
+fn complete_java_future<'local>(
+    env: &mut JNIEnv<'local>,
+    future: &GlobalRef,
+    result: Result<JObject<'local>, String>,
+) -> jni::errors::Result<()> {
+    match result {
+        Ok(value) => {
+            env.call_method(
+                future,
+                "complete",
+                "(Ljava/lang/Object;)Z",
+                &[JValue::Object(&value)],
+            )?;
         }
-        Ok::<Vec<LogEntry>, log::Error>(entries)
-    });
-
-    match entries_result {
-        Ok(entries) => match create_log_entry_array(&mut env, &entries) {
-            Ok(arr) => arr,
-            Err(e) => {
-                let _ = env.throw_new("dev/opendata/common/OpenDataNativeException", e.to_string());
-                std::ptr::null_mut()
-            }
-        },
-        Err(e) => {
-            let _ = env.throw_new("dev/opendata/common/OpenDataNativeException", e.to_string());
-            std::ptr::null_mut()
+        Err(message) => {
+            let message = env.new_string(message)?;
+            let error = env.new_object(
+                "dev/opendata/common/OpenDataNativeException",
+                "(Ljava/lang/String;)V",
+                &[JValue::Object(&message)],
+            )?;
+            env.call_method(
+                future,
+                "completeExceptionally",
+                "(Ljava/lang/Throwable;)Z",
+                &[JValue::Object(&error)],
+            )?;
         }
     }
+    Ok(())
 }
 
-// =============================================================================
-// LogDbReader JNI Methods
-// =============================================================================
+/// Completes a Java future on the calling thread, throwing if that fails.
+fn complete_java_future_now<'local>(
+    env: &mut JNIEnv<'local>,
+    future: &GlobalRef,
+    result: Result<JObject<'local>, String>,
+) {
+    if let Err(e) = complete_java_future(env, future, result) {
+        let _ = env.throw_new("dev/opendata/common/OpenDataNativeException", e.to_string());
+    }
+}
 
-/// Handle to a LogDbReader instance with its associated Tokio runtime.
-///
-/// Unlike LogReader which borrows from a parent LogDb, LogDbReader owns its
-/// own storage connection and runtime. This allows it to coexist with a
-/// separate LogDb writer for realistic end-to-end latency benchmarking.
-struct LogDbReaderHandle {
-    /// The LogDbReader instance
-    reader: LogDbReader,
-    /// Handle to the runtime for async operations
-    runtime_handle: Handle,
-    /// The runtime (kept alive for the lifetime of the reader)
-    runtime: Option<Runtime>,
+/// Starts the bridge pool for a handle, unless `threads` is 0.
+fn start_bridge(threads: usize, runtime: &Handle) -> Result<Option<BridgePool>, String> {
+    if threads == 0 {
+        return Ok(None);
+    }
+    BridgePool::start(threads, runtime.clone())
+        .map(Some)
+        .map_err(|e| e.to_string())
+}
+
+/// Throws the Java exception for a call aborted by the watchdog.
+fn throw_stuck_call(env: &mut JNIEnv<'_>, stuck: &StuckCall) {
+    let _ = env.throw_new(
+        "dev/opendata/common/OpenDataTimeoutException",
+        stuck.to_string(),
+    );
+}
+
+/// Extracts a Java group name and member id as Rust strings.
+fn extract_group_member(
+    env: &mut JNIEnv<'_>,
+    group: &JString<'_>,
+    member: &JString<'_>,
+) -> Result<(String, String), String> {
+    let group: String = env
+        .get_string(group)
+        .map_err(|e| format!("Failed to convert group: {}", e))?
+        .into();
+    let member: String = env
+        .get_string(member)
+        .map_err(|e| format!("Failed to convert member: {}", e))?
+        .into();
+    Ok((group, member))
+}
+
+/// Converts a Java byte[][] into byte buffers.
+fn extract_byte_arrays(
+    env: &mut JNIEnv<'_>,
+    arrays: &JObjectArray<'_>,
+) -> Result<Vec<Bytes>, jni::errors::Error> {
+    let len = env.get_array_length(arrays)?;
+    let mut result = Vec::with_capacity(len as usize);
+    for i in 0..len {
+        let array: JByteArray = env.get_object_array_element(arrays, i)?.into();
+        result.push(Bytes::from(env.convert_byte_array(&array)?));
+        env.delete_local_ref(array)?;
+    }
+    Ok(result)
+}
+
+/// Builds the reserved offset key for a Java group name and key.
+fn extract_offset_key(
+    env: &mut JNIEnv<'_>,
+    group: &JString<'_>,
+    key: &JByteArray<'_>,
+) -> Result<Bytes, String> {
+    let group: String = env
+        .get_string(group)
+        .map_err(|e| format!("Failed to get group: {}", e))?
+        .into();
+    let key = env
+        .convert_byte_array(key)
+        .map_err(|e| format!("Failed to get key: {}", e))?;
+    offsets::offset_key(&group, &key)
 }
 
-/// Creates a new LogDbReader instance with the specified configuration.
-///
-/// # Arguments
-/// * `config` - Java LogDbReaderConfig object
-///
-/// # Safety
-/// This is a JNI function - must be called from Java with valid JNIEnv.
-#[no_mangle]
-pub extern "system" fn Java_dev_opendata_LogDbReader_nativeCreate<'local>(
-    mut env: JNIEnv<'local>,
-    _class: JClass<'local>,
-    java_config: JObject<'local>,
-) -> jlong {
-    // Extract storage config from LogDbReaderConfig
-    let storage_config = match extract_reader_storage_config(&mut env, &java_config) {
-        Ok(c) => c,
-        Err(e) => {
-            let _ = env.throw_new("java/lang/IllegalArgumentException", e);
-            return 0;
-        }
-    };
+/// Appends and flushes an offset entry, returning the entry's log sequence.
+async fn append_offset(log: &LogDb, key: Bytes, sequence: u64) -> Result<u64, log::Error> {
+    let result = log
+        .append(vec![Record {
+            key,
+            value: offsets::encode_offset(sequence),
+        }])
+        .await?;
+    log.flush().await?;
+    Ok(result.start_sequence)
+}
 
-    // Start with default config and override fields as needed
-    let mut config = ReaderConfig {
-        storage: storage_config,
-        ..ReaderConfig::default()
-    };
+/// SLF4J logger failures of a reader's background tasks are logged under.
+const READER_TARGET: &str = "dev.opendata.LogDbReader";
 
-    // Override refresh interval if explicitly provided
-    match extract_refresh_interval(&mut env, &java_config) {
-        Ok(Some(interval)) => config.refresh_interval = interval,
-        Ok(None) => {} // Use default from ReaderConfig
-        Err(e) => {
-            let _ = env.throw_new("java/lang/IllegalArgumentException", e);
-            return 0;
+/// Starts the background task notifying a reader's watches of entries that
+/// became visible in its view, polled once per refresh interval.
+///
+/// The view only moves when the reader refreshes, so polling more often
+/// would find nothing new. Failed polls are logged and retried at the next
+/// interval.
+fn spawn_watch_poller(
+    runtime: &Handle,
+    interval: std::time::Duration,
+    reader: Arc<std::sync::RwLock<Arc<LogDbReader>>>,
+    watches: Arc<Watches>,
+) -> tokio::task::JoinHandle<()> {
+    runtime.spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        loop {
+            // The first tick completes immediately, reporting stored entries
+            ticker.tick().await;
+            let view = Arc::clone(&reader.read().unwrap_or_else(PoisonError::into_inner));
+            for (key, from_sequence) in watches.watched() {
+                match watch::visible_tail(&*view, key.clone(), from_sequence).await {
+                    Ok(Some(tail)) => watches.notify_tail(&key, tail),
+                    Ok(None) => {}
+                    Err(e) => logging::warn(
+                        READER_TARGET,
+                        &format!("Polling watched key {:?} failed: {}", key, e),
+                    ),
+                }
+            }
         }
-    }
+    })
+}
 
-    // Create a dedicated runtime for this LogDbReader instance
-    let runtime = match tokio::runtime::Builder::new_multi_thread()
-        .enable_all()
-        .thread_name("opendata-reader")
-        .build()
-    {
-        Ok(rt) => rt,
-        Err(e) => {
-            let _ = env.throw_new("dev/opendata/common/OpenDataNativeException", e.to_string());
-            return 0;
+/// Starts the background task committing a reader's scan positions.
+fn spawn_auto_commit(
+    runtime: &Handle,
+    interval: std::time::Duration,
+    committer: Arc<AutoCommitter>,
+    writer: Arc<OffsetWriterSlot>,
+    cache: Arc<OffsetCache>,
+) -> tokio::task::JoinHandle<()> {
+    runtime.spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        // The first tick completes immediately
+        ticker.tick().await;
+        loop {
+            ticker.tick().await;
+            if let Err(e) = commit_pending(&committer, &writer, &cache).await {
+                logging::warn(
+                    READER_TARGET,
+                    &format!("Auto-commit for group {} failed: {}", committer.group(), e),
+                );
+            }
         }
-    };
-
-    // Open the LogDbReader
-    let result = runtime.block_on(async { LogDbReader::open(config).await });
+    })
+}
 
-    match result {
-        Ok(reader) => {
-            let handle = Box::new(LogDbReaderHandle {
-                reader,
-                runtime_handle: runtime.handle().clone(),
-                runtime: Some(runtime),
-            });
-            Box::into_raw(handle) as jlong
-        }
-        Err(e) => {
-            let _ = env.throw_new("dev/opendata/common/OpenDataNativeException", e.to_string());
-            0
+/// Starts the background task flushing a topic's buffered appends.
+///
+/// The task holds the log weakly and stops once the log is closed. Failures
+/// are logged with `tags`.
+fn spawn_periodic_flush(
+    runtime: &Handle,
+    interval: std::time::Duration,
+    log: Weak<ReopeningLog>,
+    tags: Arc<Tags>,
+) -> tokio::task::JoinHandle<()> {
+    runtime.spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        // The first tick completes immediately
+        ticker.tick().await;
+        loop {
+            ticker.tick().await;
+            let Some(log) = log.upgrade() else {
+                return;
+            };
+            let result = log.current().flush().await;
+            log.observe(&result);
+            if let Err(e) = result {
+                logging::warn(
+                    LOG_TARGET,
+                    &tags.log_line(&format!("Periodic flush failed: {}", e)),
+                );
+            }
         }
-    }
+    })
 }
 
-/// Scans entries from the log for a given key using LogDbReader.
+/// Commits all pending scan positions in a single append.
 ///
-/// # Safety
-/// JNI function - handle must be a valid pointer returned by nativeCreate.
-#[no_mangle]
-#[allow(clippy::not_unsafe_ptr_arg_deref)]
-pub extern "system" fn Java_dev_opendata_LogDbReader_nativeScan<'local>(
-    mut env: JNIEnv<'local>,
-    _class: JClass<'local>,
-    handle: jlong,
-    key: JByteArray<'local>,
-    start_sequence: jlong,
-    max_entries: jlong,
-) -> jobjectArray {
-    if handle == 0 {
-        let _ = env.throw_new(
-            "java/lang/NullPointerException",
-            "LogDbReader handle is null",
-        );
-        return std::ptr::null_mut();
+/// Positions are restored on failure so the next attempt retries them.
+async fn commit_pending(
+    committer: &AutoCommitter,
+    writer: &OffsetWriterSlot,
+    cache: &OffsetCache,
+) -> Result<(), String> {
+    let pending = committer.take_pending();
+    if pending.is_empty() {
+        return Ok(());
     }
 
-    let reader_handle = unsafe { &*(handle as *const LogDbReaderHandle) };
+    let writer = writer
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .as_ref()
+        .and_then(Weak::upgrade)
+        .map(|writer| writer.current());
+    let Some(writer) = writer else {
+        committer.restore(pending);
+        return Err("No open offset writer attached to this reader".to_string());
+    };
 
-    let key_bytes = match env.convert_byte_array(&key) {
-        Ok(b) => Bytes::from(b),
+    let offset_keys: Result<Vec<(Bytes, u64)>, String> = pending
+        .iter()
+        .map(|(key, next)| offsets::offset_key(committer.group(), key).map(|k| (k, *next)))
+        .collect();
+    let offset_keys = match offset_keys {
+        Ok(k) => k,
         Err(e) => {
-            let _ = env.throw_new("dev/opendata/common/OpenDataNativeException", e.to_string());
-            return std::ptr::null_mut();
+            committer.restore(pending);
+            return Err(e);
         }
     };
 
-    let max = max_entries as usize;
-    let start_seq = start_sequence as u64;
+    let records = offset_keys
+        .iter()
+        .map(|(key, next)| Record {
+            key: key.clone(),
+            value: offsets::encode_offset(*next),
+        })
+        .collect();
+    let result = async {
+        let result = writer.append(records).await?;
+        writer.flush().await?;
+        Ok::<u64, log::Error>(result.start_sequence)
+    }
+    .await;
 
-    // Scan entries using the LogDbReader
-    let entries_result = reader_handle.runtime_handle.block_on(async {
-        let mut iter = reader_handle.reader.scan(key_bytes, start_seq..).await?;
-        let mut entries = Vec::with_capacity(max);
-        while entries.len() < max {
-            match iter.next().await? {
-                Some(entry) => entries.push(entry),
-                None => break,
+    match result {
+        Ok(start_sequence) => {
+            // Sequences within a batch are contiguous
+            for (i, (key, offset)) in offset_keys.into_iter().enumerate() {
+                cache.update(
+                    key,
+                    CachedOffset {
+                        entry_sequence: start_sequence + i as u64,
+                        offset,
+                    },
+                );
             }
+            Ok(())
         }
-        Ok::<Vec<LogEntry>, log::Error>(entries)
-    });
-
-    match entries_result {
-        Ok(entries) => match create_log_entry_array(&mut env, &entries) {
-            Ok(arr) => arr,
-            Err(e) => {
-                let _ = env.throw_new("dev/opendata/common/OpenDataNativeException", e.to_string());
-                std::ptr::null_mut()
-            }
-        },
         Err(e) => {
-            let _ = env.throw_new("dev/opendata/common/OpenDataNativeException", e.to_string());
-            std::ptr::null_mut()
+            committer.restore(pending);
+            Err(e.to_string())
         }
     }
 }
 
-/// Closes and frees a LogDbReader instance.
-///
-/// # Safety
-/// JNI function - handle must be a valid pointer returned by nativeCreate.
-#[no_mangle]
-pub extern "system" fn Java_dev_opendata_LogDbReader_nativeClose<'local>(
-    _env: JNIEnv<'local>,
-    _class: JClass<'local>,
-    handle: jlong,
-) {
-    if handle != 0 {
-        let reader_handle = unsafe { Box::from_raw(handle as *mut LogDbReaderHandle) };
+/// Creates a Java BenchReport object from a benchmark outcome.
+fn create_bench_report<'local>(
+    env: &mut JNIEnv<'local>,
+    report: &bench::BenchReport,
+) -> Result<JObject<'local>, String> {
+    let append = metrics::encode_histogram(&report.append)?;
+    let end_to_end = metrics::encode_histogram(&report.end_to_end)?;
+
+    let build = |env: &mut JNIEnv<'local>| -> Result<JObject<'local>, jni::errors::Error> {
+        let append_arr = env.byte_array_from_slice(&append)?;
+        let end_to_end_arr = env.byte_array_from_slice(&end_to_end)?;
+
+        // BenchReport is a record with
+        // (long records, long bytes, long elapsedNanos, byte[] appendLatencies, byte[] endToEndLatencies)
+        env.new_object(
+            "dev/opendata/BenchReport",
+            "(JJJ[B[B)V",
+            &[
+                JValue::Long(report.records as i64),
+                JValue::Long(report.bytes as i64),
+                JValue::Long(report.elapsed.as_nanos() as i64),
+                JValue::Object(&append_arr.into()),
+                JValue::Object(&end_to_end_arr.into()),
+            ],
+        )
+    };
+    build(env).map_err(|e| e.to_string())
+}
 
-        // Shutdown the runtime
-        if let Some(rt) = reader_handle.runtime {
-            rt.shutdown_background();
-        }
-        // LogDbReader drops automatically
+/// Creates a Java VerifyReport object from a verification.
+fn create_verify_report<'local>(
+    env: &mut JNIEnv<'local>,
+    verification: &verify::Verification,
+) -> Result<JObject<'local>, jni::errors::Error> {
+    let sequences_arr = create_sequence_array(env, verification.corrupt_sequences.iter().copied())?;
+    let error = create_optional_string(env, verification.error.as_deref())?;
+
+    // VerifyReport is a record with (long entries, long bytes, long corrupt,
+    // long[] corruptSequences, long lastSequence, String error)
+    env.new_object(
+        "dev/opendata/VerifyReport",
+        "(JJJ[JJLjava/lang/String;)V",
+        &[
+            JValue::Long(verification.entries as i64),
+            JValue::Long(verification.bytes as i64),
+            JValue::Long(verification.corrupt as i64),
+            JValue::Object(&sequences_arr.into()),
+            JValue::Long(verification.last_sequence.map_or(-1, |s| s as i64)),
+            JValue::Object(&error),
+        ],
+    )
+}
+
+/// Creates a Java SequenceReport object from a sequence analysis.
+fn create_sequence_report<'local>(
+    env: &mut JNIEnv<'local>,
+    analysis: &verify::Analysis,
+) -> Result<JObject<'local>, jni::errors::Error> {
+    let gap_starts = create_sequence_array(env, analysis.gap_ranges.iter().map(|gap| gap.0))?;
+    let gap_ends = create_sequence_array(env, analysis.gap_ranges.iter().map(|gap| gap.1))?;
+    let duplicates =
+        create_sequence_array(env, analysis.duplicate_pairs.iter().map(|pair| pair.0))?;
+    let originals = create_sequence_array(env, analysis.duplicate_pairs.iter().map(|pair| pair.1))?;
+    let error = create_optional_string(env, analysis.error.as_deref())?;
+
+    // SequenceReport is a record with (long entries, long firstSequence,
+    // long lastSequence, long gaps, long missing, long[] gapStarts,
+    // long[] gapEnds, long duplicates, long[] duplicateSequences,
+    // long[] originalSequences, String error)
+    env.new_object(
+        "dev/opendata/SequenceReport",
+        "(JJJJJ[J[JJ[J[JLjava/lang/String;)V",
+        &[
+            JValue::Long(analysis.entries as i64),
+            JValue::Long(analysis.first_sequence.map_or(-1, |s| s as i64)),
+            JValue::Long(analysis.last_sequence.map_or(-1, |s| s as i64)),
+            JValue::Long(analysis.gaps as i64),
+            JValue::Long(analysis.missing as i64),
+            JValue::Object(&gap_starts.into()),
+            JValue::Object(&gap_ends.into()),
+            JValue::Long(analysis.duplicates as i64),
+            JValue::Object(&duplicates.into()),
+            JValue::Object(&originals.into()),
+            JValue::Object(&error),
+        ],
+    )
+}
+
+/// Creates a Java long[] from sequences or other unsigned counts.
+fn create_sequence_array<'local>(
+    env: &mut JNIEnv<'local>,
+    sequences: impl Iterator<Item = u64>,
+) -> Result<JLongArray<'local>, jni::errors::Error> {
+    let values: Vec<i64> = sequences.map(|sequence| sequence as i64).collect();
+    let array = env.new_long_array(values.len() as i32)?;
+    env.set_long_array_region(&array, 0, &values)?;
+    Ok(array)
+}
+
+/// Creates a Java String, or null for None.
+fn create_optional_string<'local>(
+    env: &mut JNIEnv<'local>,
+    value: Option<&str>,
+) -> Result<JObject<'local>, jni::errors::Error> {
+    match value {
+        Some(value) => Ok(env.new_string(value)?.into()),
+        None => Ok(JObject::null()),
     }
 }
 
-// =============================================================================
-// Helper Functions
-// =============================================================================
+/// Creates a Java String[] from native strings.
+fn create_string_array<'local>(
+    env: &mut JNIEnv<'local>,
+    strings: &[String],
+) -> Result<jobjectArray, jni::errors::Error> {
+    let array = env.new_object_array(strings.len() as i32, "java/lang/String", JObject::null())?;
 
-/// Creates a Java AppendResult object from a Rust AppendResult.
-fn create_append_result<'local>(
+    for (i, s) in strings.iter().enumerate() {
+        let string = env.new_string(s)?;
+        env.set_object_array_element(&array, i as i32, &string)?;
+        env.delete_local_ref(string)?;
+    }
+
+    Ok(array.into_raw())
+}
+
+/// Creates a Java `HashMap<String, Long>` from native stats.
+fn create_stats_map<'local>(
     env: &mut JNIEnv<'local>,
-    result: &AppendResult,
-    timestamp_ms: i64,
+    stats: &Stats,
 ) -> Result<JObject<'local>, jni::errors::Error> {
-    let class = env.find_class("dev/opendata/AppendResult")?;
+    let map = env.new_object("java/util/HashMap", "()V", &[])?;
+    for (name, value) in stats {
+        let name = env.new_string(name)?;
+        let value = env.new_object("java/lang/Long", "(J)V", &[JValue::Long(*value)])?;
+        env.call_method(
+            &map,
+            "put",
+            "(Ljava/lang/Object;Ljava/lang/Object;)Ljava/lang/Object;",
+            &[JValue::Object(&name), JValue::Object(&value)],
+        )?;
+        env.delete_local_ref(name)?;
+        env.delete_local_ref(value)?;
+    }
+    Ok(map)
+}
 
-    // AppendResult is a record with (long sequence, long timestamp)
-    let obj = env.new_object(
-        class,
-        "(JJ)V",
-        &[
-            JValue::Long(result.start_sequence as i64),
-            JValue::Long(timestamp_ms),
-        ],
+/// Creates a Java MetricsSnapshot from a handle's tags and untagged stats.
+fn create_metrics_snapshot(
+    env: &mut JNIEnv<'_>,
+    tags: &[(String, String)],
+    stats: &Stats,
+) -> Result<jobject, jni::errors::Error> {
+    let pairs: Vec<String> = tags
+        .iter()
+        .flat_map(|(name, value)| [name.clone(), value.clone()])
+        .collect();
+    let pairs = unsafe { JObject::from_raw(create_string_array(env, &pairs)?) };
+    let class = env.find_class("dev/opendata/NativeMetric")?;
+    let metrics = env.new_object_array(stats.len() as i32, &class, JObject::null())?;
+    for (i, (name, value)) in stats.iter().enumerate() {
+        let kind = metrics::metric_kind(name);
+        let name = env.new_string(name)?;
+        // NativeMetric has a package-private (String name, int type, long value)
+        // constructor taking the MetricType ordinal
+        let metric = env.new_object(
+            &class,
+            "(Ljava/lang/String;IJ)V",
+            &[
+                JValue::Object(&name),
+                JValue::Int(kind.ordinal()),
+                JValue::Long(*value),
+            ],
+        )?;
+        env.set_object_array_element(&metrics, i as i32, &metric)?;
+        env.delete_local_ref(metric)?;
+        env.delete_local_ref(name)?;
+    }
+    // MetricsSnapshot has a package-private (String[] tagPairs,
+    // NativeMetric[] metrics) constructor
+    let snapshot = env.new_object(
+        "dev/opendata/MetricsSnapshot",
+        "([Ljava/lang/String;[Ldev/opendata/NativeMetric;)V",
+        &[JValue::Object(&pairs), JValue::Object(&metrics)],
     )?;
+    Ok(snapshot.into_raw())
+}
 
-    Ok(obj)
+/// Creates a Java HandleInfo[] from summaries of live handles.
+fn create_handle_info_array<'local>(
+    env: &mut JNIEnv<'local>,
+    live: &[handles::HandleSummary],
+) -> Result<jobjectArray, jni::errors::Error> {
+    let class = env.find_class("dev/opendata/HandleInfo")?;
+    let array = env.new_object_array(live.len() as i32, &class, JObject::null())?;
+    for (i, handle) in live.iter().enumerate() {
+        let type_name = env.new_string(handle.kind.class_name())?;
+        let tags = env.new_string(&handle.tags)?;
+        let info = env.new_object(
+            &class,
+            "(Ljava/lang/String;IJJJLjava/lang/String;)V",
+            &[
+                JValue::Object(&type_name),
+                JValue::Int(handle.stack_hash),
+                JValue::Long(handle.age.as_millis().min(i64::MAX as u128) as i64),
+                JValue::Long(handle.appends as i64),
+                JValue::Long(handle.scans as i64),
+                JValue::Object(&tags),
+            ],
+        )?;
+        env.set_object_array_element(&array, i as i32, &info)?;
+        env.delete_local_ref(info)?;
+        env.delete_local_ref(type_name)?;
+        env.delete_local_ref(tags)?;
+    }
+    Ok(array.into_raw())
 }
 
 /// Creates a Java LogEntry[] array from Rust LogEntry vector.
@@ -941,6 +11029,126 @@ fn create_log_entry_array<'local>(
     Ok(array.into_raw())
 }
 
+/// Creates a Java LogEntry[] array of entries with their values as stored
+/// and a timestamp of 0.
+fn create_raw_log_entry_array(
+    env: &mut JNIEnv<'_>,
+    entries: &[LogEntry],
+) -> Result<jobjectArray, jni::errors::Error> {
+    let class = env.find_class("dev/opendata/LogEntry")?;
+    let array = env.new_object_array(entries.len() as i32, &class, JObject::null())?;
+    for (i, entry) in entries.iter().enumerate() {
+        let key_arr = env.byte_array_from_slice(&entry.key)?;
+        let value_arr = env.byte_array_from_slice(&entry.value)?;
+        let obj = env.new_object(
+            &class,
+            "(JJ[B[B)V",
+            &[
+                JValue::Long(entry.sequence as i64),
+                JValue::Long(0),
+                JValue::Object(&key_arr),
+                JValue::Object(&value_arr),
+            ],
+        )?;
+        env.set_object_array_element(&array, i as i32, &obj)?;
+        env.delete_local_ref(obj)?;
+        env.delete_local_ref(key_arr)?;
+        env.delete_local_ref(value_arr)?;
+    }
+    Ok(array.into_raw())
+}
+
+/// Converts scanned entries into a Java long[] of their sequence, timestamp
+/// and value length, copying neither keys nor values.
+fn create_entry_metadata_array(
+    env: &mut JNIEnv<'_>,
+    entries: &[LogEntry],
+) -> Result<jlongArray, jni::errors::Error> {
+    let fields: Vec<i64> = entries
+        .iter()
+        .flat_map(|entry| {
+            let (timestamp_ms, payload) = extract_timestamp_and_payload(&entry.value);
+            [entry.sequence as i64, timestamp_ms, payload.len() as i64]
+        })
+        .collect();
+    let array = env.new_long_array(fields.len() as i32)?;
+    env.set_long_array_region(&array, 0, &fields)?;
+    Ok(array.into_raw())
+}
+
+/// Bytes preceding the payload of a packed entry.
+const PACKED_HEADER_SIZE: usize = 16;
+
+/// Packs as many entries as fit into `dest`, returning the offset of each
+/// entry packed followed by the end of the last.
+///
+/// Each entry is its sequence and timestamp as big-endian longs followed by
+/// its payload, whose length is the distance to the next offset.
+fn pack_entries(entries: &[LogEntry], dest: &mut [u8]) -> Vec<i32> {
+    let mut offsets = Vec::with_capacity(entries.len() + 1);
+    let mut at = 0;
+    offsets.push(0);
+    for entry in entries {
+        let (timestamp_ms, payload) = extract_timestamp_and_payload(&entry.value);
+        let end = at + PACKED_HEADER_SIZE + payload.len();
+        if end > dest.len() || end > i32::MAX as usize {
+            break;
+        }
+        dest[at..at + 8].copy_from_slice(&entry.sequence.to_be_bytes());
+        dest[at + 8..at + 16].copy_from_slice(&timestamp_ms.to_be_bytes());
+        dest[at + PACKED_HEADER_SIZE..end].copy_from_slice(payload);
+        offsets.push(end as i32);
+        at = end;
+    }
+    offsets
+}
+
+/// Creates a Java Completion[] array from finished submissions.
+fn create_completion_array(
+    env: &mut JNIEnv<'_>,
+    completions: Vec<Completion>,
+) -> Result<jobjectArray, jni::errors::Error> {
+    let class = env.find_class("dev/opendata/Completion")?;
+
+    let array = env.new_object_array(completions.len() as i32, &class, JObject::null())?;
+
+    for (i, completion) in completions.into_iter().enumerate() {
+        let mut append_result = JObject::null();
+        let mut entries = JObject::null();
+        let mut error = JObject::null();
+        match completion.result {
+            Ok(Outcome::Append {
+                start_sequence,
+                timestamp_ms,
+            }) => append_result = create_append_result(env, start_sequence, timestamp_ms)?,
+            Ok(Outcome::Scan(scanned)) => {
+                entries = unsafe { JObject::from_raw(create_log_entry_array(env, &scanned)?) }
+            }
+            Err(e) => error = env.new_string(e)?.into(),
+        }
+
+        // Completion(long ticket, AppendResult appendResult, LogEntry[] entries, String error)
+        let obj = env.new_object(
+            &class,
+            "(JLdev/opendata/AppendResult;[Ldev/opendata/LogEntry;Ljava/lang/String;)V",
+            &[
+                JValue::Long(completion.ticket as i64),
+                JValue::Object(&append_result),
+                JValue::Object(&entries),
+                JValue::Object(&error),
+            ],
+        )?;
+
+        env.set_object_array_element(&array, i as i32, &obj)?;
+        env.delete_local_ref(obj)?;
+        env.delete_local_ref(append_result)?;
+        env.delete_local_ref(entries)?;
+        env.delete_local_ref(error)?;
+    }
+
+    Ok(array.into_raw())
+}
+
 /// Extracts the timestamp header and original payload from a stored value.
 ///
 /// Returns (timestamp_ms, payload_slice). If the value is too short to contain
@@ -955,13 +11163,16 @@ fn extract_timestamp_and_payload(value: &[u8]) -> (i64, &[u8]) {
         .try_into()
         .expect("slice is exactly 8 bytes");
     let timestamp_ms = i64::from_be_bytes(timestamp_bytes);
+
+    if let Some(received) = skew::split_received(value) {
+        return received;
+    }
     let payload = &value[TIMESTAMP_HEADER_SIZE..];
 
     (timestamp_ms, payload)
 }
 
-/// Returns current wall-clock time as milliseconds since Unix epoch (for testing).
-#[cfg(test)]
+/// Returns current wall-clock time as milliseconds since Unix epoch.
 fn current_timestamp_ms() -> i64 {
     use std::time::{SystemTime, UNIX_EPOCH};
     SystemTime::now()
@@ -1166,6 +11377,21 @@ mod tests {
         assert_eq!(extracted_payload, payload);
     }
 
+    #[test]
+    fn should_generate_synthetic_value_with_header() {
+        // given
+        let timestamp: i64 = 1_700_000_000_000;
+
+        // when
+        let value = synthetic_value(timestamp, 1_001, 42);
+        let (extracted_ts, payload) = extract_timestamp_and_payload(&value);
+
+        // then
+        assert_eq!(extracted_ts, timestamp);
+        assert_eq!(payload.len(), 1_001);
+        assert!(payload.iter().any(|&b| b != payload[0]));
+    }
+
     #[test]
     fn should_roundtrip_max_timestamp() {
         // given
@@ -1180,4 +11406,27 @@ mod tests {
         assert_eq!(extracted_ts, timestamp);
         assert_eq!(extracted_payload, payload);
     }
+
+    #[test]
+    fn should_pack_entries_that_fit() {
+        // given
+        let entries: Vec<LogEntry> = [(4, b"ab".as_slice()), (5, b"cdef".as_slice())]
+            .into_iter()
+            .map(|(sequence, payload)| LogEntry {
+                key: Bytes::from_static(b"key"),
+                sequence,
+                value: Bytes::from(create_timestamped_value(1000, payload)),
+            })
+            .collect();
+        let mut dest = vec![0u8; 2 * PACKED_HEADER_SIZE + 3];
+
+        // when
+        let offsets = pack_entries(&entries, &mut dest);
+
+        // then
+        assert_eq!(offsets, vec![0, 18]);
+        assert_eq!(dest[..8], 4u64.to_be_bytes());
+        assert_eq!(dest[8..16], 1000i64.to_be_bytes());
+        assert_eq!(&dest[16..18], b"ab");
+    }
 }
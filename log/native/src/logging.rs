@@ -0,0 +1,105 @@
+//! Native log lines forwarded to SLF4J.
+//!
+//! Native code has no logger of its own. Once a handle has been opened, log
+//! lines are handed to the Java `NativeLogging` class, which passes them to
+//! the SLF4J logger named by their target, so they reach whatever backend
+//! and configuration the application uses. The class is resolved on the Java
+//! thread that opens the first handle, since native threads attached later
+//! cannot see application classes; until then, and if a call into Java
+//! fails, lines go to stderr.
+//!
+//! Forwarding attaches the logging thread to the JVM, see `attach.rs`, so a
+//! line logged from a runtime worker costs a JNI upcall. Lines above the
+//! library's maximum level are dropped before any upcall; the level is
+//! shared by every handle and can be lowered while they are open.
+
+use std::sync::atomic::{AtomicI32, Ordering};
+use std::sync::OnceLock;
+
+use jni::objects::{GlobalRef, JClass, JValue};
+use jni::JNIEnv;
+
+use crate::attach;
+
+/// Local references a forwarded line needs.
+const LOCAL_FRAME_CAPACITY: i32 = 4;
+
+/// Level arguments of `NativeLogging.log`.
+pub(crate) const WARN: i32 = 1;
+pub(crate) const INFO: i32 = 2;
+
+/// Level at or below which lines are logged; below `WARN` logs nothing.
+static MAX_LEVEL: AtomicI32 = AtomicI32::new(INFO);
+
+/// Sets the level at or below which lines are logged.
+pub(crate) fn set_max_level(level: i32) {
+    MAX_LEVEL.store(level, Ordering::Relaxed);
+}
+
+struct Bridge {
+    class: GlobalRef,
+}
+
+static BRIDGE: OnceLock<Bridge> = OnceLock::new();
+
+/// Resolves the Java `NativeLogging` class, if not done yet.
+///
+/// Must be called from a Java thread. Failures leave logging on stderr.
+pub(crate) fn init(env: &mut JNIEnv<'_>) {
+    if BRIDGE.get().is_some() {
+        return;
+    }
+    let bridge = env
+        .find_class("dev/opendata/NativeLogging")
+        .and_then(|class| env.new_global_ref(class))
+        .map(|class| Bridge { class });
+    match bridge {
+        Ok(bridge) => {
+            let _ = BRIDGE.set(bridge);
+        }
+        Err(_) => {
+            let _ = env.exception_clear();
+        }
+    }
+}
+
+/// Logs `message` as a warning under the SLF4J logger named `target`.
+pub(crate) fn warn(target: &str, message: &str) {
+    log(WARN, target, message);
+}
+
+/// Logs `message` at info level under the SLF4J logger named `target`.
+pub(crate) fn info(target: &str, message: &str) {
+    log(INFO, target, message);
+}
+
+fn log(level: i32, target: &str, message: &str) {
+    if level > MAX_LEVEL.load(Ordering::Relaxed) {
+        return;
+    }
+    let forwarded = BRIDGE
+        .get()
+        .is_some_and(|bridge| forward(bridge, level, target, message).is_some());
+    if !forwarded {
+        eprintln!("{}: {}", target, message);
+    }
+}
+
+fn forward(bridge: &Bridge, level: i32, target: &str, message: &str) -> Option<()> {
+    attach::call(LOCAL_FRAME_CAPACITY, |env| {
+        let target = env.new_string(target)?;
+        let message = env.new_string(message)?;
+        let class: &JClass<'_> = bridge.class.as_obj().into();
+        env.call_static_method(
+            class,
+            "log",
+            "(ILjava/lang/String;Ljava/lang/String;)V",
+            &[
+                JValue::Int(level),
+                JValue::Object(&target),
+                JValue::Object(&message),
+            ],
+        )?;
+        Ok(())
+    })
+}
@@ -0,0 +1,330 @@
+//! Accounting of native memory held by a handle.
+//!
+//! Memory the binding allocates outside the JVM heap shows up in the
+//! process RSS but in no JVM metric. Each component of a handle therefore
+//! reports the key and value bytes it holds, and `LogHandle::stats` adds
+//! them up as `memory.*` stats: the tail cache, records of appends in flight
+//! through blocking, bridged, submitted and pipelined calls (including those
+//! held by the write queue or an open coalescing window), scan results
+//! waiting to be polled, and the shared memory of open append rings. Figures
+//! leave out allocator overhead and per-entry bookkeeping, and SlateDB's own
+//! memtables and block cache, which the binding cannot see.
+//!
+//! With a memory limit configured, an append is admitted only while that
+//! total plus its own records stays within the limit; otherwise it waits for
+//! memory to be released or is refused, as the policy says. The check and the
+//! charge are not atomic, so concurrent appends may overshoot the limit by
+//! their own size. An append is always admitted when no other append is in
+//! flight, so that a batch larger than the limit, or caches and rings already
+//! filling it, cannot stall a producer forever. Rings count toward the limit
+//! but opening one is never refused.
+
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Condvar, Mutex, PoisonError};
+use std::time::Duration;
+
+use log::{LogEntry, Record};
+
+use crate::backpressure::BackpressurePolicy;
+use crate::completion::CompletionQueue;
+use crate::metrics::Stats;
+use crate::tailcache::TailCache;
+
+/// How often a blocked append rechecks memory it is not notified about,
+/// such as scan results being polled.
+const RECHECK_INTERVAL: Duration = Duration::from_millis(10);
+
+/// Memory limit settings extracted from a Java `MemoryLimitConfig`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct MemoryLimitSettings {
+    /// Native bytes a handle may hold before appends are held back
+    pub(crate) max_bytes: usize,
+    /// `Block` or `Reject`
+    pub(crate) policy: BackpressurePolicy,
+}
+
+/// Bytes currently charged by calls or resources of a handle.
+#[derive(Debug, Default)]
+pub(crate) struct MemoryGauge {
+    bytes: AtomicU64,
+    /// Threads waiting for a release, so releases skip the lock otherwise
+    waiters: AtomicUsize,
+    lock: Mutex<()>,
+    released: Condvar,
+}
+
+impl MemoryGauge {
+    /// Charges `bytes` until the returned charge is dropped.
+    pub(crate) fn charge(self: &Arc<Self>, bytes: usize) -> MemoryCharge {
+        self.bytes.fetch_add(bytes as u64, Ordering::Relaxed);
+        MemoryCharge {
+            gauge: Arc::clone(self),
+            bytes: bytes as u64,
+        }
+    }
+
+    /// Returns the bytes currently charged.
+    pub(crate) fn bytes(&self) -> usize {
+        self.bytes.load(Ordering::SeqCst) as usize
+    }
+
+    /// Blocks until `ready` holds, rechecking whenever a charge is released.
+    fn wait_until(&self, ready: impl Fn() -> bool) {
+        self.waiters.fetch_add(1, Ordering::SeqCst);
+        let mut guard = self.lock.lock().unwrap_or_else(PoisonError::into_inner);
+        while !ready() {
+            guard = self
+                .released
+                .wait_timeout(guard, RECHECK_INTERVAL)
+                .unwrap_or_else(PoisonError::into_inner)
+                .0;
+        }
+        drop(guard);
+        self.waiters.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+/// Bytes held against a gauge, released on drop.
+#[derive(Debug)]
+pub(crate) struct MemoryCharge {
+    gauge: Arc<MemoryGauge>,
+    bytes: u64,
+}
+
+impl Drop for MemoryCharge {
+    fn drop(&mut self) {
+        let gauge = &self.gauge;
+        gauge.bytes.fetch_sub(self.bytes, Ordering::SeqCst);
+        if gauge.waiters.load(Ordering::SeqCst) > 0 {
+            // Taking the lock ensures a waiter past its check is waiting
+            drop(gauge.lock.lock().unwrap_or_else(PoisonError::into_inner));
+            gauge.released.notify_all();
+        }
+    }
+}
+
+/// Native memory held by one handle, and its limit if configured.
+pub(crate) struct HandleMemory {
+    tail_cache: Option<Arc<TailCache>>,
+    completions: Arc<CompletionQueue>,
+    /// Record bytes of appends in flight, however they were made
+    in_flight: Arc<MemoryGauge>,
+    /// Shared memory of open append rings
+    rings: Arc<MemoryGauge>,
+    limit: Option<MemoryLimitSettings>,
+    refused: AtomicU64,
+}
+
+impl HandleMemory {
+    pub(crate) fn new(
+        tail_cache: Option<Arc<TailCache>>,
+        completions: Arc<CompletionQueue>,
+        limit: Option<MemoryLimitSettings>,
+    ) -> Self {
+        Self {
+            tail_cache,
+            completions,
+            in_flight: Arc::default(),
+            rings: Arc::default(),
+            limit,
+            refused: AtomicU64::new(0),
+        }
+    }
+
+    /// Returns the configured limit, if any.
+    pub(crate) fn limit(&self) -> Option<MemoryLimitSettings> {
+        self.limit
+    }
+
+    /// Charges the records of an append until the charge is dropped,
+    /// applying the limit's policy if they do not fit.
+    ///
+    /// Blocks under `Block`; returns None under `Reject`.
+    pub(crate) fn charge_append(&self, records: &[Record]) -> Option<MemoryCharge> {
+        let bytes = record_bytes(records);
+        if let Some(limit) = &self.limit {
+            let fits = || self.in_flight.bytes() == 0 || self.held() + bytes <= limit.max_bytes;
+            if !fits() {
+                if limit.policy != BackpressurePolicy::Block {
+                    self.refused.fetch_add(1, Ordering::Relaxed);
+                    return None;
+                }
+                self.in_flight.wait_until(fits);
+            }
+        }
+        Some(self.in_flight.charge(bytes))
+    }
+
+    /// Returns the record bytes of appends in flight.
+    pub(crate) fn in_flight_bytes(&self) -> usize {
+        self.in_flight.bytes()
+    }
+
+    /// Charges the shared memory of an append ring.
+    pub(crate) fn charge_ring(&self, bytes: usize) -> MemoryCharge {
+        self.rings.charge(bytes)
+    }
+
+    /// Returns the bytes held across all parts.
+    fn held(&self) -> usize {
+        self.parts().iter().map(|(_, bytes)| bytes).sum()
+    }
+
+    fn parts(&self) -> [(&'static str, usize); 4] {
+        [
+            (
+                "tail_cache",
+                self.tail_cache.as_ref().map_or(0, |cache| cache.bytes()),
+            ),
+            ("in_flight", self.in_flight.bytes()),
+            ("completions", self.completions.held_bytes()),
+            ("rings", self.rings.bytes()),
+        ]
+    }
+
+    /// Appends the `memory.*` stats, with the limit and refused appends if
+    /// a limit is configured.
+    pub(crate) fn append_stats(&self, stats: &mut Stats) {
+        append_stats(stats, &self.parts());
+        if let Some(limit) = &self.limit {
+            stats.push(("memory.limit_bytes".to_string(), limit.max_bytes as i64));
+            stats.push((
+                "memory.refused".to_string(),
+                self.refused.load(Ordering::Relaxed) as i64,
+            ));
+        }
+    }
+}
+
+/// Key and value bytes of records.
+pub(crate) fn record_bytes(records: &[Record]) -> usize {
+    records
+        .iter()
+        .map(|record| record.key.len() + record.value.len())
+        .sum()
+}
+
+/// Key and value bytes of entries.
+pub(crate) fn entry_bytes(entries: &[LogEntry]) -> usize {
+    entries
+        .iter()
+        .map(|entry| entry.key.len() + entry.value.len())
+        .sum()
+}
+
+/// Appends a `memory.<part>_bytes` stat for each part and their total.
+fn append_stats(stats: &mut Stats, parts: &[(&str, usize)]) {
+    for (part, bytes) in parts {
+        stats.push((format!("memory.{}_bytes", part), *bytes as i64));
+    }
+    let total: usize = parts.iter().map(|(_, bytes)| bytes).sum();
+    stats.push(("memory.total_bytes".to_string(), total as i64));
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+
+    use super::*;
+
+    #[test]
+    fn should_release_charge_on_drop() {
+        // given
+        let gauge = Arc::new(MemoryGauge::default());
+        let first = gauge.charge(100);
+        let second = gauge.charge(20);
+
+        // when
+        drop(first);
+
+        // then
+        assert_eq!(gauge.bytes(), 20);
+        drop(second);
+        assert_eq!(gauge.bytes(), 0);
+    }
+
+    fn limited(max_bytes: usize, policy: BackpressurePolicy) -> HandleMemory {
+        HandleMemory::new(
+            None,
+            Arc::default(),
+            Some(MemoryLimitSettings { max_bytes, policy }),
+        )
+    }
+
+    fn records(value_bytes: usize) -> Vec<Record> {
+        vec![Record {
+            key: Bytes::from_static(b"k"),
+            value: Bytes::from(vec![0; value_bytes]),
+        }]
+    }
+
+    #[test]
+    fn should_refuse_append_beyond_limit_until_memory_is_released() {
+        // given
+        let memory = limited(100, BackpressurePolicy::Reject);
+        let first = memory.charge_append(&records(79)).unwrap();
+
+        // when
+        let refused = memory.charge_append(&records(29));
+        drop(first);
+        let admitted = memory.charge_append(&records(29));
+
+        // then
+        assert!(refused.is_none());
+        assert!(admitted.is_some());
+        let mut stats = Stats::new();
+        memory.append_stats(&mut stats);
+        assert!(stats.contains(&("memory.in_flight_bytes".to_string(), 30)));
+        assert!(stats.contains(&("memory.refused".to_string(), 1)));
+    }
+
+    #[test]
+    fn should_admit_oversized_append_when_nothing_is_in_flight() {
+        // given
+        let memory = limited(10, BackpressurePolicy::Reject);
+        let _ring = memory.charge_ring(1024);
+
+        // when
+        let charge = memory.charge_append(&records(100));
+
+        // then
+        assert!(charge.is_some());
+    }
+
+    #[test]
+    fn should_block_until_memory_is_released() {
+        // given
+        let memory = Arc::new(limited(100, BackpressurePolicy::Block));
+        let first = memory.charge_append(&records(99)).unwrap();
+        let blocked = Arc::clone(&memory);
+
+        // when
+        let waiter = std::thread::spawn(move || blocked.charge_append(&records(9)).is_some());
+        std::thread::sleep(Duration::from_millis(50));
+        let finished_early = waiter.is_finished();
+        drop(first);
+
+        // then
+        assert!(!finished_early);
+        assert!(waiter.join().unwrap());
+    }
+
+    #[test]
+    fn should_total_parts() {
+        // given
+        let mut stats = Stats::new();
+
+        // when
+        append_stats(&mut stats, &[("tail_cache", 64), ("ring", 1024)]);
+
+        // then
+        assert_eq!(
+            stats,
+            vec![
+                ("memory.tail_cache_bytes".to_string(), 64),
+                ("memory.ring_bytes".to_string(), 1024),
+                ("memory.total_bytes".to_string(), 1088),
+            ]
+        );
+    }
+}
@@ -0,0 +1,1075 @@
+//! Native latency recording.
+//!
+//! Append and scan latencies are recorded into HdrHistograms inside the native
+//! layer so that benchmark harnesses can pull a complete latency distribution
+//! with a single JNI call instead of timing every operation from Java.
+//!
+//! Histograms are exported using the HdrHistogram V2 encoding, which the Java
+//! `org.HdrHistogram.Histogram.decodeFromByteBuffer` method reads directly.
+//! Values are recorded in microseconds.
+//!
+//! When profiling is enabled, each call is additionally split into phases
+//! (JNI marshalling in, runtime queueing, the storage future, and marshalling
+//! out) whose totals are accumulated per operation.
+//!
+//! Runtime health (worker utilization, injection queue depth, and the number
+//! of Java threads blocked in `block_on`) is reported as flat name/value
+//! pairs through the stats API, as are the bytes scans were served from
+//! in-memory caches and from storage.
+//!
+//! Records and bytes appended and served are also totalled for interval
+//! stats, which report the traffic since the previous call along with its
+//! per-second rates, so a harness printing throughput every few seconds
+//! needs one JNI call per report rather than counting every operation.
+//!
+//! Metrics snapshots report the same stats with the kind of each, so a
+//! metrics binder polling on its own schedule knows which values only grow
+//! and which go up and down. Counters are totals since the handle opened,
+//! except the latency counts, which restart with the histograms at warmup
+//! marks; every stat not listed as a counter is a gauge.
+//!
+//! Benchmark harnesses mark the start and end of warmup to discard the
+//! histograms and counters recorded so far, and can wait for steady state:
+//! the operation rate is sampled over fixed windows until the coefficient of
+//! variation (standard deviation over mean) of the most recent windows drops
+//! below a threshold.
+
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::{Duration, Instant};
+
+use hdrhistogram::serialization::{Serializer, V2Serializer};
+use hdrhistogram::Histogram;
+use log::LogEntry;
+use tokio::runtime::Handle;
+
+use crate::current_timestamp_ms;
+use crate::keystats::KeyStats;
+
+/// Flat name/value pairs reported through the stats API.
+pub(crate) type Stats = Vec<(String, i64)>;
+
+/// Kind of a stat in a metrics snapshot.
+///
+/// The ordinals must match the declaration order of the Java
+/// `dev.opendata.MetricType` enum.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum MetricKind {
+    /// Total that only grows, except latency counts at warmup marks
+    Counter,
+    /// Current value that may go up and down
+    Gauge,
+}
+
+impl MetricKind {
+    /// Returns the ordinal of the matching Java `MetricType` constant.
+    pub(crate) fn ordinal(self) -> i32 {
+        match self {
+            MetricKind::Counter => 0,
+            MetricKind::Gauge => 1,
+        }
+    }
+}
+
+/// Untagged names of the stats that are counters.
+const COUNTERS: &[&str] = &[
+    "clock_skew.samples",
+    "call_limit.rejected",
+    "coalesce.batches",
+    "coalesce.records",
+    "cursors.opened",
+    "cursors.resumed",
+    "disk_cache.parts_added",
+    "faults.injected_errors",
+    "faults.injected_latency_nanos",
+    "faults.injected_throttles",
+    "faults.injected_timeouts",
+    "key_stats.evicted",
+    "memory.refused",
+    "priority.background_wait_nanos",
+    "priority.background_waits",
+    "rate_limit.consume_throttled_nanos",
+    "rate_limit.produce_throttled_nanos",
+    "read_ahead.hits",
+    "read_ahead.misses",
+    "read_ahead.prefetches",
+    "reopen.count",
+    "reopen.failed",
+    "scan.cache_bytes",
+    "scan.storage_bytes",
+    "slow_log.logged",
+    "statsd.datagrams",
+    "statsd.send_errors",
+    "tail_cache.hits",
+    "tail_cache.misses",
+    "write_queue.refused",
+];
+
+/// Last components of counter names reported under a prefix, such as
+/// `runtime.busy_nanos` or `append.latency.count`.
+const COUNTER_SUFFIXES: &[&str] = &["busy_nanos", "park_count", "latency.count"];
+
+/// Returns the kind of the stat with the given untagged name.
+pub(crate) fn metric_kind(name: &str) -> MetricKind {
+    let suffixed = COUNTER_SUFFIXES.iter().any(|suffix| {
+        name.strip_suffix(suffix)
+            .is_some_and(|prefix| prefix.ends_with('.'))
+    });
+    if suffixed || COUNTERS.contains(&name) {
+        MetricKind::Counter
+    } else {
+        MetricKind::Gauge
+    }
+}
+
+/// Highest latency tracked by the histograms, in microseconds (one minute).
+///
+/// Larger values are clamped to this bound rather than dropped.
+const MAX_TRACKABLE_LATENCY_MICROS: u64 = 60_000_000;
+
+/// Number of significant decimal digits maintained by the histograms.
+const SIGNIFICANT_DIGITS: u8 = 3;
+
+/// Operations whose latency is recorded natively.
+///
+/// The ordinals must match the declaration order of the Java
+/// `dev.opendata.Operation` enum.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Operation {
+    Append,
+    Scan,
+}
+
+impl Operation {
+    /// Converts a Java `Operation.ordinal()` value into an operation.
+    pub(crate) fn from_ordinal(ordinal: i32) -> Option<Self> {
+        match ordinal {
+            0 => Some(Operation::Append),
+            1 => Some(Operation::Scan),
+            _ => None,
+        }
+    }
+}
+
+/// Per-handle latency histograms for each recorded operation.
+pub(crate) struct LatencyRecorder {
+    append: Mutex<Histogram<u64>>,
+    scan: Mutex<Histogram<u64>>,
+}
+
+impl LatencyRecorder {
+    pub(crate) fn new() -> Self {
+        Self {
+            append: Mutex::new(new_histogram()),
+            scan: Mutex::new(new_histogram()),
+        }
+    }
+
+    /// Records the latency of a completed operation.
+    pub(crate) fn record(&self, op: Operation, elapsed: Duration) {
+        record_latency(
+            &mut self
+                .histogram(op)
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner),
+            elapsed,
+        );
+    }
+
+    /// Returns the number of operations recorded since the last reset.
+    pub(crate) fn count(&self, op: Operation) -> u64 {
+        self.histogram(op)
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .len()
+    }
+
+    /// Discards all recorded latencies.
+    pub(crate) fn reset(&self) {
+        for op in [Operation::Append, Operation::Scan] {
+            self.histogram(op)
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .reset();
+        }
+    }
+
+    /// Appends the count, median, 99th percentile and maximum of each
+    /// operation's latencies, in microseconds, such as
+    /// `append.latency.p99_micros`.
+    pub(crate) fn append_summary_stats(&self, stats: &mut Stats) {
+        for (op, name) in [(Operation::Append, "append"), (Operation::Scan, "scan")] {
+            let histogram = self
+                .histogram(op)
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner);
+            stats.push((format!("{}.latency.count", name), histogram.len() as i64));
+            stats.push((
+                format!("{}.latency.p50_micros", name),
+                histogram.value_at_quantile(0.5) as i64,
+            ));
+            stats.push((
+                format!("{}.latency.p99_micros", name),
+                histogram.value_at_quantile(0.99) as i64,
+            ));
+            stats.push((
+                format!("{}.latency.max_micros", name),
+                histogram.max() as i64,
+            ));
+        }
+    }
+
+    /// Encodes the histogram for an operation using the HdrHistogram V2 format.
+    pub(crate) fn encode(&self, op: Operation) -> Result<Vec<u8>, String> {
+        let histogram = self
+            .histogram(op)
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        encode_histogram(&histogram)
+    }
+
+    fn histogram(&self, op: Operation) -> &Mutex<Histogram<u64>> {
+        match op {
+            Operation::Append => &self.append,
+            Operation::Scan => &self.scan,
+        }
+    }
+}
+
+/// Creates an empty latency histogram with the standard bounds.
+pub(crate) fn new_histogram() -> Histogram<u64> {
+    Histogram::new_with_bounds(1, MAX_TRACKABLE_LATENCY_MICROS, SIGNIFICANT_DIGITS)
+        .expect("histogram bounds are valid")
+}
+
+/// Records a latency in microseconds, clamped to the histogram's bounds.
+pub(crate) fn record_latency(histogram: &mut Histogram<u64>, elapsed: Duration) {
+    let micros = u64::try_from(elapsed.as_micros()).unwrap_or(u64::MAX);
+    histogram.saturating_record(micros.max(1));
+}
+
+/// Encodes a histogram using the HdrHistogram V2 format.
+pub(crate) fn encode_histogram(histogram: &Histogram<u64>) -> Result<Vec<u8>, String> {
+    let mut buffer = Vec::new();
+    V2Serializer::new()
+        .serialize(histogram, &mut buffer)
+        .map_err(|e| format!("Failed to encode histogram: {:?}", e))?;
+    Ok(buffer)
+}
+
+/// Time spent in each phase of a single native call.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) struct PhaseTimings {
+    /// Converting Java arguments into Rust values
+    pub(crate) marshal_in: Duration,
+    /// From calling `block_on` until the storage future is first polled
+    pub(crate) queue: Duration,
+    /// Awaiting the underlying LogDb future
+    pub(crate) storage: Duration,
+    /// Converting the Rust result into Java objects
+    pub(crate) marshal_out: Duration,
+}
+
+/// Captures the phase boundaries of a native call as it progresses.
+pub(crate) struct CallTimer {
+    started: Instant,
+    submitted: Instant,
+    storage_started: Instant,
+    storage_finished: Instant,
+}
+
+impl CallTimer {
+    /// Starts timing a call; should be invoked on entry to the JNI function.
+    pub(crate) fn start() -> Self {
+        let now = Instant::now();
+        Self {
+            started: now,
+            submitted: now,
+            storage_started: now,
+            storage_finished: now,
+        }
+    }
+
+    /// Marks the end of argument marshalling, just before `block_on`.
+    pub(crate) fn mark_submitted(&mut self) {
+        self.submitted = Instant::now();
+    }
+
+    /// Marks the first poll of the storage future on the runtime.
+    pub(crate) fn mark_storage_started(&mut self) {
+        self.storage_started = Instant::now();
+    }
+
+    /// Marks completion of the storage future.
+    pub(crate) fn mark_storage_finished(&mut self) {
+        self.storage_finished = Instant::now();
+    }
+
+    /// Time spent inside `block_on`, including runtime queueing.
+    pub(crate) fn storage_latency(&self) -> Duration {
+        self.storage_finished
+            .saturating_duration_since(self.submitted)
+    }
+
+    /// Completes the call, attributing the time since storage finished to
+    /// result marshalling.
+    pub(crate) fn finish(&self) -> PhaseTimings {
+        PhaseTimings {
+            marshal_in: self.submitted.saturating_duration_since(self.started),
+            queue: self
+                .storage_started
+                .saturating_duration_since(self.submitted),
+            storage: self
+                .storage_finished
+                .saturating_duration_since(self.storage_started),
+            marshal_out: self.storage_finished.elapsed(),
+        }
+    }
+}
+
+/// Accumulated phase totals for one operation.
+#[derive(Default)]
+struct PhaseTotals {
+    calls: AtomicU64,
+    marshal_in_nanos: AtomicU64,
+    queue_nanos: AtomicU64,
+    storage_nanos: AtomicU64,
+    marshal_out_nanos: AtomicU64,
+}
+
+impl PhaseTotals {
+    fn add(&self, timings: &PhaseTimings) {
+        self.calls.fetch_add(1, Ordering::Relaxed);
+        self.marshal_in_nanos
+            .fetch_add(duration_nanos(timings.marshal_in), Ordering::Relaxed);
+        self.queue_nanos
+            .fetch_add(duration_nanos(timings.queue), Ordering::Relaxed);
+        self.storage_nanos
+            .fetch_add(duration_nanos(timings.storage), Ordering::Relaxed);
+        self.marshal_out_nanos
+            .fetch_add(duration_nanos(timings.marshal_out), Ordering::Relaxed);
+    }
+
+    fn reset(&self) {
+        self.calls.store(0, Ordering::Relaxed);
+        self.marshal_in_nanos.store(0, Ordering::Relaxed);
+        self.queue_nanos.store(0, Ordering::Relaxed);
+        self.storage_nanos.store(0, Ordering::Relaxed);
+        self.marshal_out_nanos.store(0, Ordering::Relaxed);
+    }
+
+    fn snapshot(&self) -> [u64; 5] {
+        [
+            self.calls.load(Ordering::Relaxed),
+            self.marshal_in_nanos.load(Ordering::Relaxed),
+            self.queue_nanos.load(Ordering::Relaxed),
+            self.storage_nanos.load(Ordering::Relaxed),
+            self.marshal_out_nanos.load(Ordering::Relaxed),
+        ]
+    }
+}
+
+/// Per-handle accumulator of per-phase call timings.
+///
+/// Recording is a no-op unless profiling was enabled in the handle's config.
+pub(crate) struct Profiler {
+    enabled: bool,
+    append: PhaseTotals,
+    scan: PhaseTotals,
+}
+
+impl Profiler {
+    pub(crate) fn new(enabled: bool) -> Self {
+        Self {
+            enabled,
+            append: PhaseTotals::default(),
+            scan: PhaseTotals::default(),
+        }
+    }
+
+    /// Accumulates the phase timings of a completed call.
+    pub(crate) fn record(&self, op: Operation, timings: &PhaseTimings) {
+        if self.enabled {
+            self.totals(op).add(timings);
+        }
+    }
+
+    /// Returns `[calls, marshalInNanos, queueNanos, storageNanos, marshalOutNanos]`
+    /// accumulated for an operation, in the field order of the Java
+    /// `TimingBreakdown` record.
+    pub(crate) fn snapshot(&self, op: Operation) -> [u64; 5] {
+        self.totals(op).snapshot()
+    }
+
+    /// Discards all accumulated timings.
+    pub(crate) fn reset(&self) {
+        self.append.reset();
+        self.scan.reset();
+    }
+
+    fn totals(&self, op: Operation) -> &PhaseTotals {
+        match op {
+            Operation::Append => &self.append,
+            Operation::Scan => &self.scan,
+        }
+    }
+}
+
+fn duration_nanos(duration: Duration) -> u64 {
+    u64::try_from(duration.as_nanos()).unwrap_or(u64::MAX)
+}
+
+/// Tracks how many caller threads are currently blocked in `block_on`.
+///
+/// A steadily high value relative to the runtime's worker count indicates that
+/// Java callers are queueing behind the scheduler rather than storage.
+#[derive(Default)]
+pub(crate) struct BlockedCallers {
+    current: AtomicU64,
+    peak: AtomicU64,
+}
+
+impl BlockedCallers {
+    /// Registers a caller entering `block_on`; the caller is counted until the
+    /// returned guard is dropped.
+    pub(crate) fn enter(&self) -> BlockedCallerGuard<'_> {
+        let current = self.current.fetch_add(1, Ordering::Relaxed) + 1;
+        self.peak.fetch_max(current, Ordering::Relaxed);
+        BlockedCallerGuard { gauge: self }
+    }
+
+    pub(crate) fn current(&self) -> u64 {
+        self.current.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn peak(&self) -> u64 {
+        self.peak.load(Ordering::Relaxed)
+    }
+
+    /// Restarts peak tracking from the current number of blocked callers.
+    pub(crate) fn reset_peak(&self) {
+        self.peak.store(self.current(), Ordering::Relaxed);
+    }
+}
+
+/// Decrements the blocked caller count when dropped.
+pub(crate) struct BlockedCallerGuard<'a> {
+    gauge: &'a BlockedCallers,
+}
+
+impl Drop for BlockedCallerGuard<'_> {
+    fn drop(&mut self) {
+        self.gauge.current.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+/// Key and value bytes of scanned entries, by where they were served from.
+///
+/// Storage bytes include entries SlateDB served from its block cache, which
+/// the binding cannot tell apart from object-store reads, and pages read
+/// ahead, which were read from storage in the background.
+#[derive(Default)]
+pub(crate) struct ServedBytes {
+    cache: AtomicU64,
+    storage: AtomicU64,
+    /// Entries served from either, for interval stats
+    entries: AtomicU64,
+    /// Per-key statistics scans are recorded into, if configured
+    key_stats: Option<Arc<KeyStats>>,
+}
+
+impl ServedBytes {
+    /// Creates the counters of a handle recording scans into `key_stats`.
+    pub(crate) fn new(key_stats: Option<Arc<KeyStats>>) -> Self {
+        Self {
+            key_stats,
+            ..Self::default()
+        }
+    }
+
+    /// Records entries served from the tail cache.
+    pub(crate) fn record_cache(&self, entries: &[LogEntry]) {
+        self.cache
+            .fetch_add(entry_bytes(entries), Ordering::Relaxed);
+        self.record_served(entries);
+    }
+
+    /// Records entries read through storage.
+    pub(crate) fn record_storage(&self, entries: &[LogEntry]) {
+        self.storage
+            .fetch_add(entry_bytes(entries), Ordering::Relaxed);
+        self.record_served(entries);
+    }
+
+    fn record_served(&self, entries: &[LogEntry]) {
+        self.entries
+            .fetch_add(entries.len() as u64, Ordering::Relaxed);
+        if let Some(key_stats) = &self.key_stats {
+            key_stats.record_scan(entries, current_timestamp_ms());
+        }
+    }
+
+    /// Returns the entries and bytes served so far.
+    pub(crate) fn volume(&self) -> (u64, u64) {
+        let bytes = self.cache.load(Ordering::Relaxed) + self.storage.load(Ordering::Relaxed);
+        (self.entries.load(Ordering::Relaxed), bytes)
+    }
+
+    /// Appends the bytes served from cache and from storage.
+    pub(crate) fn append_stats(&self, stats: &mut Stats) {
+        stats.push((
+            "scan.cache_bytes".to_string(),
+            self.cache.load(Ordering::Relaxed) as i64,
+        ));
+        stats.push((
+            "scan.storage_bytes".to_string(),
+            self.storage.load(Ordering::Relaxed) as i64,
+        ));
+    }
+}
+
+fn entry_bytes(entries: &[LogEntry]) -> u64 {
+    entries
+        .iter()
+        .map(|e| (e.key.len() + e.value.len()) as u64)
+        .sum()
+}
+
+/// Records and bytes appended through a handle, for interval stats.
+#[derive(Default)]
+pub(crate) struct AppendedVolume {
+    records: AtomicU64,
+    bytes: AtomicU64,
+}
+
+impl AppendedVolume {
+    /// Records an appended batch of `records` records of `bytes` key and
+    /// value bytes.
+    pub(crate) fn record(&self, records: usize, bytes: u64) {
+        self.records.fetch_add(records as u64, Ordering::Relaxed);
+        self.bytes.fetch_add(bytes, Ordering::Relaxed);
+    }
+
+    /// Returns the records and bytes appended so far.
+    pub(crate) fn volume(&self) -> (u64, u64) {
+        (
+            self.records.load(Ordering::Relaxed),
+            self.bytes.load(Ordering::Relaxed),
+        )
+    }
+}
+
+/// Records and bytes into and out of a handle since it opened.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) struct Traffic {
+    pub(crate) records_in: u64,
+    pub(crate) bytes_in: u64,
+    pub(crate) records_out: u64,
+    pub(crate) bytes_out: u64,
+}
+
+impl Traffic {
+    /// Combines the totals appended and served by a handle.
+    pub(crate) fn new(appended: (u64, u64), served: (u64, u64)) -> Self {
+        Self {
+            records_in: appended.0,
+            bytes_in: appended.1,
+            records_out: served.0,
+            bytes_out: served.1,
+        }
+    }
+}
+
+/// Traffic totals at the end of a handle's last interval.
+pub(crate) struct Intervals {
+    last: Mutex<(Instant, Traffic)>,
+}
+
+impl Intervals {
+    pub(crate) fn new() -> Self {
+        Self {
+            last: Mutex::new((Instant::now(), Traffic::default())),
+        }
+    }
+
+    /// Ends the current interval, returning its length, the traffic in it
+    /// and the traffic's per-second rates.
+    ///
+    /// `totals` is read under the lock, so concurrent calls see them in
+    /// order.
+    pub(crate) fn next(&self, totals: impl FnOnce() -> Traffic) -> Stats {
+        let mut last = self.last.lock().unwrap_or_else(PoisonError::into_inner);
+        end_interval(&mut last, totals(), Instant::now())
+    }
+}
+
+/// Ends the interval started at `last` with `totals` at `now`.
+fn end_interval(last: &mut (Instant, Traffic), totals: Traffic, now: Instant) -> Stats {
+    let (started, previous) = std::mem::replace(last, (now, totals));
+    let nanos = duration_nanos(now.saturating_duration_since(started));
+    let deltas = [
+        ("records_in", totals.records_in - previous.records_in),
+        ("bytes_in", totals.bytes_in - previous.bytes_in),
+        ("records_out", totals.records_out - previous.records_out),
+        ("bytes_out", totals.bytes_out - previous.bytes_out),
+    ];
+    let mut stats = vec![("interval_nanos".to_string(), nanos as i64)];
+    for (name, delta) in deltas {
+        stats.push((name.to_string(), delta as i64));
+        let per_sec = if nanos == 0 {
+            0
+        } else {
+            (delta as u128 * 1_000_000_000 / nanos as u128) as i64
+        };
+        stats.push((format!("{}_per_sec", name), per_sec));
+    }
+    stats
+}
+
+/// Returns `hits` as parts per million of `hits + misses`, or 0 before any
+/// lookup, so that hit rates fit the integer stats map.
+pub(crate) fn hit_rate_ppm(hits: u64, misses: u64) -> i64 {
+    let total = hits as u128 + misses as u128;
+    if total == 0 {
+        return 0;
+    }
+    (hits as u128 * 1_000_000 / total) as i64
+}
+
+/// Warmup state of a handle and the start of its current measurement period.
+pub(crate) struct Measurement {
+    warming_up: AtomicBool,
+    started: Mutex<Instant>,
+}
+
+impl Measurement {
+    pub(crate) fn new() -> Self {
+        Self {
+            warming_up: AtomicBool::new(false),
+            started: Mutex::new(Instant::now()),
+        }
+    }
+
+    /// Starts a new measurement period, during warmup or after it.
+    ///
+    /// Callers reset the handle's histograms and counters alongside.
+    pub(crate) fn restart(&self, warming_up: bool) {
+        *self.started.lock().unwrap_or_else(PoisonError::into_inner) = Instant::now();
+        self.warming_up.store(warming_up, Ordering::Relaxed);
+    }
+
+    /// Appends `warming_up` and `measurement_nanos`, the time since the
+    /// current measurement period started.
+    pub(crate) fn append_stats(&self, stats: &mut Stats) {
+        let started = *self.started.lock().unwrap_or_else(PoisonError::into_inner);
+        stats.push((
+            "warming_up".to_string(),
+            self.warming_up.load(Ordering::Relaxed) as i64,
+        ));
+        stats.push((
+            "measurement_nanos".to_string(),
+            duration_nanos(started.elapsed()) as i64,
+        ));
+    }
+}
+
+/// Settings for detecting a steady operation rate.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct SteadyStateSettings {
+    /// Length of each sampling window
+    pub(crate) window: Duration,
+    /// Number of most recent windows compared
+    pub(crate) windows: usize,
+    /// Highest coefficient of variation considered steady
+    pub(crate) max_coefficient_of_variation: f64,
+}
+
+/// Detects when the rate of an operation stops varying between windows.
+pub(crate) struct SteadyStateDetector {
+    settings: SteadyStateSettings,
+    counts: VecDeque<u64>,
+}
+
+impl SteadyStateDetector {
+    pub(crate) fn new(settings: SteadyStateSettings) -> Self {
+        Self {
+            settings,
+            counts: VecDeque::with_capacity(settings.windows),
+        }
+    }
+
+    /// Records the number of operations in a completed window and returns
+    /// whether the most recent windows are steady.
+    ///
+    /// Windows without operations are never steady, so an idle handle does
+    /// not report steady state.
+    pub(crate) fn observe(&mut self, count: u64) -> bool {
+        if self.counts.len() == self.settings.windows {
+            self.counts.pop_front();
+        }
+        self.counts.push_back(count);
+        if self.counts.len() < self.settings.windows || self.counts.contains(&0) {
+            return false;
+        }
+        let n = self.counts.len() as f64;
+        let mean = self.counts.iter().map(|&c| c as f64).sum::<f64>() / n;
+        let variance = self
+            .counts
+            .iter()
+            .map(|&c| (c as f64 - mean).powi(2))
+            .sum::<f64>()
+            / n;
+        variance.sqrt() / mean <= self.settings.max_coefficient_of_variation
+    }
+
+    /// Forgets all observed windows, e.g. after the counters were reset.
+    pub(crate) fn clear(&mut self) {
+        self.counts.clear();
+    }
+}
+
+/// Samples an operation's rate until it is steady or the timeout elapses.
+///
+/// Returns whether steady state was reached. Counts come from the latency
+/// histograms, so a warmup mark while waiting restarts detection.
+pub(crate) async fn await_steady_state(
+    latency: &LatencyRecorder,
+    op: Operation,
+    settings: SteadyStateSettings,
+    timeout: Duration,
+) -> bool {
+    let deadline = Instant::now() + timeout;
+    let mut detector = SteadyStateDetector::new(settings);
+    let mut previous = latency.count(op);
+    while Instant::now() + settings.window <= deadline {
+        tokio::time::sleep(settings.window).await;
+        let current = latency.count(op);
+        if current < previous {
+            detector.clear();
+        } else if detector.observe(current - previous) {
+            return true;
+        }
+        previous = current;
+    }
+    false
+}
+
+/// Appends health metrics for a Tokio runtime under the given name prefix.
+///
+/// Busy time is cumulative since the runtime started; callers compute worker
+/// utilization by diffing `busy_nanos` against wall-clock time multiplied by
+/// `workers`.
+pub(crate) fn append_runtime_stats(stats: &mut Stats, prefix: &str, handle: &Handle) {
+    let metrics = handle.metrics();
+    let workers = metrics.num_workers();
+    let busy_nanos: u64 = (0..workers)
+        .map(|worker| duration_nanos(metrics.worker_total_busy_duration(worker)))
+        .sum();
+    let park_count: u64 = (0..workers)
+        .map(|worker| metrics.worker_park_count(worker))
+        .sum();
+
+    stats.push((format!("{}.workers", prefix), workers as i64));
+    stats.push((
+        format!("{}.alive_tasks", prefix),
+        metrics.num_alive_tasks() as i64,
+    ));
+    stats.push((
+        format!("{}.injection_queue_depth", prefix),
+        metrics.global_queue_depth() as i64,
+    ));
+    stats.push((format!("{}.busy_nanos", prefix), busy_nanos as i64));
+    stats.push((format!("{}.park_count", prefix), park_count as i64));
+}
+
+/// Appends the blocked caller gauge under the given name prefix.
+pub(crate) fn append_blocked_caller_stats(
+    stats: &mut Stats,
+    prefix: &str,
+    blocked: &BlockedCallers,
+) {
+    stats.push((
+        format!("{}.blocked_callers", prefix),
+        blocked.current() as i64,
+    ));
+    stats.push((
+        format!("{}.blocked_callers_peak", prefix),
+        blocked.peak() as i64,
+    ));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hdrhistogram::serialization::Deserializer;
+    use std::io::Cursor;
+
+    fn decode(bytes: &[u8]) -> Histogram<u64> {
+        Deserializer::new()
+            .deserialize(&mut Cursor::new(bytes))
+            .expect("histogram should decode")
+    }
+
+    #[test]
+    fn should_report_traffic_since_previous_interval() {
+        // given
+        let start = Instant::now();
+        let mut last = (start, Traffic::new((10, 1_000), (4, 400)));
+
+        // when
+        let stats = end_interval(
+            &mut last,
+            Traffic::new((30, 3_000), (4, 400)),
+            start + Duration::from_secs(2),
+        );
+
+        // then
+        let stat = |name: &str| stats.iter().find(|(n, _)| n == name).map(|(_, v)| *v);
+        assert_eq!(stat("interval_nanos"), Some(2_000_000_000));
+        assert_eq!(stat("records_in"), Some(20));
+        assert_eq!(stat("bytes_in_per_sec"), Some(1_000));
+        assert_eq!(stat("records_out"), Some(0));
+    }
+
+    #[test]
+    fn should_map_java_ordinals_to_operations() {
+        // given
+        let ordinals = [0, 1, 2, -1];
+
+        // when
+        let ops: Vec<Option<Operation>> = ordinals
+            .iter()
+            .map(|o| Operation::from_ordinal(*o))
+            .collect();
+
+        // then
+        assert_eq!(
+            ops,
+            vec![Some(Operation::Append), Some(Operation::Scan), None, None]
+        );
+    }
+
+    #[test]
+    fn should_record_latencies_in_microseconds() {
+        // given
+        let recorder = LatencyRecorder::new();
+
+        // when
+        recorder.record(Operation::Append, Duration::from_micros(250));
+        recorder.record(Operation::Append, Duration::from_millis(2));
+        let histogram = decode(&recorder.encode(Operation::Append).unwrap());
+
+        // then
+        assert_eq!(histogram.len(), 2);
+        assert!(histogram.equivalent(histogram.min(), 250));
+        assert!(histogram.equivalent(histogram.max(), 2_000));
+    }
+
+    #[test]
+    fn should_keep_operations_separate() {
+        // given
+        let recorder = LatencyRecorder::new();
+
+        // when
+        recorder.record(Operation::Scan, Duration::from_micros(10));
+        let appends = decode(&recorder.encode(Operation::Append).unwrap());
+        let scans = decode(&recorder.encode(Operation::Scan).unwrap());
+
+        // then
+        assert_eq!(appends.len(), 0);
+        assert_eq!(scans.len(), 1);
+    }
+
+    #[test]
+    fn should_clamp_latencies_beyond_trackable_range() {
+        // given
+        let recorder = LatencyRecorder::new();
+
+        // when
+        recorder.record(Operation::Append, Duration::from_secs(3600));
+        let histogram = decode(&recorder.encode(Operation::Append).unwrap());
+
+        // then
+        assert_eq!(histogram.len(), 1);
+        assert!(histogram.equivalent(histogram.max(), MAX_TRACKABLE_LATENCY_MICROS));
+    }
+
+    #[test]
+    fn should_accumulate_phase_timings_when_enabled() {
+        // given
+        let profiler = Profiler::new(true);
+        let timings = PhaseTimings {
+            marshal_in: Duration::from_nanos(10),
+            queue: Duration::from_nanos(20),
+            storage: Duration::from_nanos(30),
+            marshal_out: Duration::from_nanos(40),
+        };
+
+        // when
+        profiler.record(Operation::Append, &timings);
+        profiler.record(Operation::Append, &timings);
+
+        // then
+        assert_eq!(profiler.snapshot(Operation::Append), [2, 20, 40, 60, 80]);
+        assert_eq!(profiler.snapshot(Operation::Scan), [0, 0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn should_ignore_phase_timings_when_disabled() {
+        // given
+        let profiler = Profiler::new(false);
+
+        // when
+        profiler.record(Operation::Scan, &PhaseTimings::default());
+
+        // then
+        assert_eq!(profiler.snapshot(Operation::Scan), [0, 0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn should_split_call_into_ordered_phases() {
+        // given
+        let mut timer = CallTimer::start();
+
+        // when
+        timer.mark_submitted();
+        timer.mark_storage_started();
+        std::thread::sleep(Duration::from_millis(2));
+        timer.mark_storage_finished();
+        let timings = timer.finish();
+
+        // then
+        assert!(timings.storage >= Duration::from_millis(2));
+        assert!(timer.storage_latency() >= timings.storage);
+    }
+
+    #[test]
+    fn should_discard_latencies_and_timings_on_reset() {
+        // given
+        let recorder = LatencyRecorder::new();
+        let profiler = Profiler::new(true);
+        recorder.record(Operation::Append, Duration::from_micros(10));
+        profiler.record(Operation::Scan, &PhaseTimings::default());
+
+        // when
+        recorder.reset();
+        profiler.reset();
+
+        // then
+        assert_eq!(recorder.count(Operation::Append), 0);
+        assert_eq!(profiler.snapshot(Operation::Scan), [0; 5]);
+    }
+
+    #[test]
+    fn should_detect_steady_state_once_windows_stop_varying() {
+        // given
+        let mut detector = SteadyStateDetector::new(SteadyStateSettings {
+            window: Duration::from_secs(1),
+            windows: 3,
+            max_coefficient_of_variation: 0.05,
+        });
+
+        // when
+        let ramping: Vec<bool> = [100, 400, 1_000]
+            .iter()
+            .map(|&c| detector.observe(c))
+            .collect();
+        let settling: Vec<bool> = [1_010, 990].iter().map(|&c| detector.observe(c)).collect();
+
+        // then
+        assert_eq!(ramping, vec![false, false, false]);
+        assert_eq!(settling, vec![false, true]);
+    }
+
+    #[test]
+    fn should_not_report_idle_windows_as_steady() {
+        // given
+        let mut detector = SteadyStateDetector::new(SteadyStateSettings {
+            window: Duration::from_secs(1),
+            windows: 2,
+            max_coefficient_of_variation: 0.5,
+        });
+
+        // when
+        detector.observe(0);
+        let steady = detector.observe(0);
+
+        // then
+        assert!(!steady);
+    }
+
+    #[test]
+    fn should_track_current_and_peak_blocked_callers() {
+        // given
+        let blocked = BlockedCallers::default();
+
+        // when
+        let first = blocked.enter();
+        let second = blocked.enter();
+        drop(first);
+
+        // then
+        assert_eq!(blocked.current(), 1);
+        assert_eq!(blocked.peak(), 2);
+        drop(second);
+        assert_eq!(blocked.current(), 0);
+    }
+
+    #[test]
+    fn should_report_runtime_stats_under_prefix() {
+        // given
+        let runtime = tokio::runtime::Builder::new_multi_thread()
+            .worker_threads(2)
+            .build()
+            .unwrap();
+        let mut stats = Stats::new();
+
+        // when
+        append_runtime_stats(&mut stats, "runtime", runtime.handle());
+
+        // then
+        assert!(stats.contains(&("runtime.workers".to_string(), 2)));
+        assert!(stats
+            .iter()
+            .any(|(name, _)| name == "runtime.injection_queue_depth"));
+        assert!(stats.iter().all(|(name, _)| name.starts_with("runtime.")));
+    }
+
+    #[test]
+    fn should_tell_counters_from_gauges() {
+        // given
+        let names = [
+            "tail_cache.hits",
+            "compaction_runtime.busy_nanos",
+            "scan.latency.count",
+            "tail_cache.hit_rate_ppm",
+            "cursors.parked",
+            "busy_nanos",
+        ];
+
+        // when
+        let kinds: Vec<MetricKind> = names.iter().map(|name| metric_kind(name)).collect();
+
+        // then
+        assert_eq!(
+            kinds,
+            vec![
+                MetricKind::Counter,
+                MetricKind::Counter,
+                MetricKind::Counter,
+                MetricKind::Gauge,
+                MetricKind::Gauge,
+                MetricKind::Gauge,
+            ]
+        );
+    }
+
+    #[test]
+    fn should_report_hit_rate_in_parts_per_million() {
+        // when / then
+        assert_eq!(hit_rate_ppm(0, 0), 0);
+        assert_eq!(hit_rate_ppm(3, 1), 750_000);
+        assert_eq!(hit_rate_ppm(u64::MAX, 0), 1_000_000);
+    }
+}
@@ -0,0 +1,101 @@
+//! No-op storage for measuring the overhead of the bindings alone.
+//!
+//! Appends to a no-op log only allocate sequences, and scans return
+//! synthetic entries for sequences that were allocated, so a benchmark
+//! against it measures JNI marshalling and the runtime round trip without any
+//! storage work. Synthetic values carry a timestamp header with the time of
+//! the scan followed by a zero-filled payload of the configured size.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use bytes::{BufMut, Bytes, BytesMut};
+use log::LogEntry;
+
+use crate::TIMESTAMP_HEADER_SIZE;
+
+/// Sequence allocator standing in for a LogDb.
+pub(crate) struct NoOpLog {
+    next_sequence: AtomicU64,
+    payload_size: usize,
+}
+
+impl NoOpLog {
+    pub(crate) fn new(payload_size: usize) -> Self {
+        Self {
+            next_sequence: AtomicU64::new(0),
+            payload_size,
+        }
+    }
+
+    /// Accepts a batch of `count` records, returning its start sequence.
+    pub(crate) fn append(&self, count: usize) -> u64 {
+        self.next_sequence
+            .fetch_add(count as u64, Ordering::Relaxed)
+    }
+
+    /// Returns up to `max_entries` synthetic entries from `start_sequence`.
+    ///
+    /// Every allocated sequence is reported under the scanned key, whichever
+    /// key it was appended with.
+    pub(crate) fn scan(
+        &self,
+        key: &Bytes,
+        start_sequence: u64,
+        max_entries: usize,
+        timestamp_ms: i64,
+    ) -> Vec<LogEntry> {
+        let end = self
+            .next_sequence
+            .load(Ordering::Relaxed)
+            .min(start_sequence.saturating_add(max_entries as u64));
+        if start_sequence >= end {
+            return Vec::new();
+        }
+        let mut value = BytesMut::zeroed(TIMESTAMP_HEADER_SIZE + self.payload_size);
+        (&mut value[..TIMESTAMP_HEADER_SIZE]).put_i64(timestamp_ms);
+        let value = value.freeze();
+        (start_sequence..end)
+            .map(|sequence| LogEntry {
+                key: key.clone(),
+                sequence,
+                value: value.clone(),
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_allocate_contiguous_sequences() {
+        // given
+        let log = NoOpLog::new(0);
+
+        // when
+        let first = log.append(3);
+        let second = log.append(2);
+
+        // then
+        assert_eq!((first, second), (0, 3));
+    }
+
+    #[test]
+    fn should_scan_only_allocated_sequences() {
+        // given
+        let log = NoOpLog::new(16);
+        log.append(5);
+        let key = Bytes::from_static(b"key");
+
+        // when
+        let entries = log.scan(&key, 3, 10, 1_234);
+
+        // then
+        let sequences: Vec<u64> = entries.iter().map(|e| e.sequence).collect();
+        assert_eq!(sequences, vec![3, 4]);
+        assert_eq!(entries[0].value.len(), TIMESTAMP_HEADER_SIZE + 16);
+        assert_eq!(&entries[0].value[..8], &1_234i64.to_be_bytes());
+        assert!(log.scan(&key, 5, 10, 0).is_empty());
+    }
+}
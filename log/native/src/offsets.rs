@@ -0,0 +1,229 @@
+//! Consumer offsets stored inside the log.
+//!
+//! Each committed offset is appended as an entry under a reserved key derived
+//! from the consumer group and the consumed key:
+//!
+//! ```text
+//! ┌──────────────────────────┬─────────────────┬──────────────┬─────────────┐
+//! │ OFFSET_KEY_PREFIX        │ group_len (2B)  │ group (UTF-8)│ key         │
+//! │                          │ big-endian u16  │              │             │
+//! └──────────────────────────┴─────────────────┴──────────────┴─────────────┘
+//! ```
+//!
+//! The entry value is the committed sequence as a big-endian u64. The latest
+//! entry for a reserved key is the current offset. Each handle caches the
+//! latest offset it has seen along with the log sequence of its entry, so
+//! repeated fetches only scan entries committed since the previous fetch.
+//!
+//! Readers with auto-commit enabled track the next sequence to read for every
+//! key they have scanned and periodically commit the positions that changed.
+
+use std::collections::HashMap;
+use std::sync::{Mutex, PoisonError};
+
+use bytes::{BufMut, Bytes, BytesMut};
+
+/// Prefix of all reserved offset keys; application keys must not start with it.
+pub(crate) const OFFSET_KEY_PREFIX: &[u8] = b"\xff__opendata/offsets/";
+
+/// Size of an encoded offset value.
+const OFFSET_VALUE_SIZE: usize = 8;
+
+/// Builds the reserved key holding a group's offset for a key.
+pub(crate) fn offset_key(group: &str, key: &[u8]) -> Result<Bytes, String> {
+    let group_len =
+        u16::try_from(group.len()).map_err(|_| format!("group name exceeds {} bytes", u16::MAX))?;
+    let mut buffer = BytesMut::with_capacity(OFFSET_KEY_PREFIX.len() + 2 + group.len() + key.len());
+    buffer.put_slice(OFFSET_KEY_PREFIX);
+    buffer.put_u16(group_len);
+    buffer.put_slice(group.as_bytes());
+    buffer.put_slice(key);
+    Ok(buffer.freeze())
+}
+
+/// Encodes a committed sequence as an entry value.
+pub(crate) fn encode_offset(sequence: u64) -> Bytes {
+    Bytes::copy_from_slice(&sequence.to_be_bytes())
+}
+
+/// Decodes an entry value, returning None if it is not a valid offset.
+pub(crate) fn decode_offset(value: &[u8]) -> Option<u64> {
+    let bytes: [u8; OFFSET_VALUE_SIZE] = value.try_into().ok()?;
+    Some(u64::from_be_bytes(bytes))
+}
+
+/// Latest offset seen for a reserved key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct CachedOffset {
+    /// Log sequence of the entry holding the offset
+    pub(crate) entry_sequence: u64,
+    /// The committed sequence
+    pub(crate) offset: u64,
+}
+
+/// Per-handle cache of the latest offset entry for each reserved key.
+#[derive(Default)]
+pub(crate) struct OffsetCache {
+    entries: Mutex<HashMap<Bytes, CachedOffset>>,
+}
+
+impl OffsetCache {
+    /// Returns the cached offset for a reserved key.
+    pub(crate) fn get(&self, key: &Bytes) -> Option<CachedOffset> {
+        self.lock().get(key).copied()
+    }
+
+    /// Caches an offset entry unless a later entry is already cached.
+    pub(crate) fn update(&self, key: Bytes, cached: CachedOffset) {
+        let mut entries = self.lock();
+        let current = entries.entry(key).or_insert(cached);
+        if cached.entry_sequence > current.entry_sequence {
+            *current = cached;
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<Bytes, CachedOffset>> {
+        self.entries.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// Scan positions awaiting an automatic commit for one consumer group.
+pub(crate) struct AutoCommitter {
+    group: String,
+    pending: Mutex<HashMap<Bytes, u64>>,
+}
+
+impl AutoCommitter {
+    pub(crate) fn new(group: String) -> Self {
+        Self {
+            group,
+            pending: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Returns the consumer group positions are committed for.
+    pub(crate) fn group(&self) -> &str {
+        &self.group
+    }
+
+    /// Records that a scan of `key` returned entries up to `next_sequence - 1`.
+    pub(crate) fn observe(&self, key: Bytes, next_sequence: u64) {
+        let mut pending = self.lock();
+        let position = pending.entry(key).or_insert(next_sequence);
+        *position = (*position).max(next_sequence);
+    }
+
+    /// Takes all positions observed since the last commit.
+    pub(crate) fn take_pending(&self) -> HashMap<Bytes, u64> {
+        std::mem::take(&mut *self.lock())
+    }
+
+    /// Returns positions whose commit failed so the next attempt retries them.
+    ///
+    /// Positions observed in the meantime take precedence if they are later.
+    pub(crate) fn restore(&self, positions: HashMap<Bytes, u64>) {
+        for (key, next_sequence) in positions {
+            self.observe(key, next_sequence);
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<Bytes, u64>> {
+        self.pending.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_build_distinct_keys_per_group() {
+        // given
+        let key = b"orders";
+
+        // when
+        let a = offset_key("group-a", key).unwrap();
+        let b = offset_key("group-b", key).unwrap();
+
+        // then
+        assert!(a.starts_with(OFFSET_KEY_PREFIX));
+        assert_ne!(a, b);
+    }
+
+    #[test]
+    fn should_not_confuse_group_and_key_boundaries() {
+        // given / when
+        let a = offset_key("ab", b"c").unwrap();
+        let b = offset_key("a", b"bc").unwrap();
+
+        // then
+        assert_ne!(a, b);
+    }
+
+    #[test]
+    fn should_round_trip_offset_value() {
+        // given
+        let value = encode_offset(123_456);
+
+        // when
+        let decoded = decode_offset(&value);
+
+        // then
+        assert_eq!(decoded, Some(123_456));
+        assert_eq!(decode_offset(b"bad"), None);
+    }
+
+    #[test]
+    fn should_keep_latest_cached_offset() {
+        // given
+        let cache = OffsetCache::default();
+        let key = Bytes::from_static(b"k");
+        let newer = CachedOffset {
+            entry_sequence: 10,
+            offset: 5,
+        };
+        let older = CachedOffset {
+            entry_sequence: 3,
+            offset: 2,
+        };
+
+        // when
+        cache.update(key.clone(), newer);
+        cache.update(key.clone(), older);
+
+        // then
+        assert_eq!(cache.get(&key), Some(newer));
+    }
+
+    #[test]
+    fn should_track_highest_position_per_key() {
+        // given
+        let committer = AutoCommitter::new("group".to_string());
+        let key = Bytes::from_static(b"k");
+
+        // when
+        committer.observe(key.clone(), 5);
+        committer.observe(key.clone(), 3);
+
+        // then
+        let pending = committer.take_pending();
+        assert_eq!(pending.get(&key), Some(&5));
+        assert!(committer.take_pending().is_empty());
+    }
+
+    #[test]
+    fn should_keep_later_position_when_restoring() {
+        // given
+        let committer = AutoCommitter::new("group".to_string());
+        let key = Bytes::from_static(b"k");
+        committer.observe(key.clone(), 5);
+        let failed = committer.take_pending();
+        committer.observe(key.clone(), 8);
+
+        // when
+        committer.restore(failed);
+
+        // then
+        assert_eq!(committer.take_pending().get(&key), Some(&8));
+    }
+}
@@ -0,0 +1,256 @@
+//! Limit on the blocking calls a handle runs at once.
+//!
+//! A blocking JNI call parks its Java thread in `block_on` until storage
+//! answers, and every parked caller adds a future to the runtime. When
+//! hundreds of Java threads call one handle at once, nothing bounds how many
+//! calls pile into the runtime and its storage connections. With a call
+//! limit configured, at most `max_calls` blocking calls of the handle run at
+//! once, and a call arriving beyond them is handled by the configured policy:
+//! `Queue` waits in arrival order while fewer than `max_queued` callers wait
+//! and refuses the rest, `Block` waits in arrival order without bound, and
+//! `Reject` refuses at once. Refused calls throw `OpenDataOverloadedException`
+//! before touching storage.
+//!
+//! Submitted and bridged calls return without holding a Java thread in
+//! native code and are not limited; the write queue bounds submitted appends.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Condvar, Mutex, MutexGuard, PoisonError};
+
+use jni::sys::jint;
+
+use crate::metrics::Stats;
+
+/// What a blocking call does when the handle's call limit is reached.
+///
+/// Mirrors the Java `OverloadPolicy` enum by ordinal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum OverloadPolicy {
+    /// Wait in arrival order, refusing calls beyond `max_queued` waiting
+    Queue,
+    /// Wait in arrival order without bound
+    Block,
+    /// Refuse the call at once
+    Reject,
+}
+
+impl OverloadPolicy {
+    /// Converts a Java `OverloadPolicy.ordinal()` value into a policy.
+    pub(crate) fn from_ordinal(ordinal: jint) -> Option<Self> {
+        match ordinal {
+            0 => Some(Self::Queue),
+            1 => Some(Self::Block),
+            2 => Some(Self::Reject),
+            _ => None,
+        }
+    }
+}
+
+/// Call limit settings extracted from a Java `CallLimitConfig`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct CallLimitSettings {
+    /// Blocking calls running at once
+    pub(crate) max_calls: usize,
+    pub(crate) policy: OverloadPolicy,
+    /// Callers waiting for a slot under `Queue`
+    pub(crate) max_queued: usize,
+}
+
+/// Slots of the running calls and tickets of the waiting ones.
+#[derive(Default)]
+struct Slots {
+    running: usize,
+    /// Ticket the next caller to arrive takes
+    next_ticket: u64,
+    /// Ticket of the next caller to be admitted
+    next_admitted: u64,
+}
+
+impl Slots {
+    fn queued(&self) -> u64 {
+        self.next_ticket - self.next_admitted
+    }
+}
+
+/// Per-handle limit on blocking calls running at once.
+pub(crate) struct CallLimiter {
+    settings: CallLimitSettings,
+    slots: Mutex<Slots>,
+    released: Condvar,
+    rejected: AtomicU64,
+}
+
+/// Slot of a running call, released when dropped.
+pub(crate) struct CallPermit<'a> {
+    limiter: &'a CallLimiter,
+}
+
+impl CallLimiter {
+    pub(crate) fn new(settings: CallLimitSettings) -> Self {
+        Self {
+            settings,
+            slots: Mutex::default(),
+            released: Condvar::new(),
+            rejected: AtomicU64::new(0),
+        }
+    }
+
+    /// Takes a slot for a call, waiting for one if the policy allows.
+    ///
+    /// Returns None if the call is refused.
+    pub(crate) fn acquire(&self) -> Option<CallPermit<'_>> {
+        let mut slots = self.lock();
+        let free = slots.queued() == 0 && slots.running < self.settings.max_calls;
+        let refused = !free
+            && match self.settings.policy {
+                OverloadPolicy::Queue => slots.queued() >= self.settings.max_queued as u64,
+                OverloadPolicy::Block => false,
+                OverloadPolicy::Reject => true,
+            };
+        if refused {
+            self.rejected.fetch_add(1, Ordering::Relaxed);
+            return None;
+        }
+        let ticket = slots.next_ticket;
+        slots.next_ticket += 1;
+        while slots.next_admitted != ticket || slots.running >= self.settings.max_calls {
+            slots = self
+                .released
+                .wait(slots)
+                .unwrap_or_else(PoisonError::into_inner);
+        }
+        slots.next_admitted += 1;
+        slots.running += 1;
+        drop(slots);
+        // The next caller in line may fit in another free slot
+        self.released.notify_all();
+        Some(CallPermit { limiter: self })
+    }
+
+    /// Returns the configured limit.
+    pub(crate) fn max_calls(&self) -> usize {
+        self.settings.max_calls
+    }
+
+    /// Appends the running and waiting calls and the calls refused.
+    pub(crate) fn append_stats(&self, stats: &mut Stats) {
+        let slots = self.lock();
+        stats.push((
+            "call_limit.max_calls".to_string(),
+            self.settings.max_calls as i64,
+        ));
+        stats.push(("call_limit.running".to_string(), slots.running as i64));
+        stats.push(("call_limit.queued".to_string(), slots.queued() as i64));
+        stats.push((
+            "call_limit.rejected".to_string(),
+            self.rejected.load(Ordering::Relaxed) as i64,
+        ));
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Slots> {
+        self.slots.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl Drop for CallPermit<'_> {
+    fn drop(&mut self) {
+        self.limiter.lock().running -= 1;
+        self.limiter.released.notify_all();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use std::thread;
+    use std::time::Duration;
+
+    use super::*;
+
+    fn limiter(max_calls: usize, policy: OverloadPolicy, max_queued: usize) -> Arc<CallLimiter> {
+        Arc::new(CallLimiter::new(CallLimitSettings {
+            max_calls,
+            policy,
+            max_queued,
+        }))
+    }
+
+    fn stat(limiter: &CallLimiter, name: &str) -> i64 {
+        let mut stats = Stats::new();
+        limiter.append_stats(&mut stats);
+        stats.into_iter().find(|(n, _)| n == name).unwrap().1
+    }
+
+    fn wait_for_queued(limiter: &CallLimiter, queued: i64) {
+        while stat(limiter, "call_limit.queued") < queued {
+            thread::sleep(Duration::from_millis(1));
+        }
+    }
+
+    #[test]
+    fn should_reject_calls_beyond_limit() {
+        // given
+        let limiter = limiter(2, OverloadPolicy::Reject, 0);
+        let _first = limiter.acquire().unwrap();
+        let second = limiter.acquire().unwrap();
+
+        // when
+        let refused = limiter.acquire().is_none();
+        drop(second);
+        let admitted = limiter.acquire().is_some();
+
+        // then
+        assert!(refused);
+        assert!(admitted);
+        assert_eq!(stat(&limiter, "call_limit.rejected"), 1);
+    }
+
+    #[test]
+    fn should_refuse_calls_beyond_queue() {
+        // given
+        let limiter = limiter(1, OverloadPolicy::Queue, 1);
+        let running = limiter.acquire().unwrap();
+        let waiter = {
+            let limiter = Arc::clone(&limiter);
+            thread::spawn(move || limiter.acquire().is_some())
+        };
+        wait_for_queued(&limiter, 1);
+
+        // when
+        let refused = limiter.acquire().is_none();
+        drop(running);
+
+        // then
+        assert!(refused);
+        assert!(waiter.join().unwrap());
+        assert_eq!(stat(&limiter, "call_limit.running"), 0);
+    }
+
+    #[test]
+    fn should_admit_waiting_calls_in_arrival_order() {
+        // given
+        let limiter = limiter(1, OverloadPolicy::Block, 0);
+        let running = limiter.acquire().unwrap();
+        let admitted = Arc::new(Mutex::new(Vec::new()));
+        let mut waiters = Vec::new();
+        for i in 0..3 {
+            let limiter = Arc::clone(&limiter);
+            let admitted = Arc::clone(&admitted);
+            waiters.push(thread::spawn(move || {
+                let _permit = limiter.acquire().unwrap();
+                admitted.lock().unwrap().push(i);
+            }));
+            wait_for_queued(&limiter, i + 1);
+        }
+
+        // when
+        drop(running);
+        for waiter in waiters {
+            waiter.join().unwrap();
+        }
+
+        // then
+        assert_eq!(*admitted.lock().unwrap(), vec![0, 1, 2]);
+        assert_eq!(stat(&limiter, "call_limit.queued"), 0);
+    }
+}
@@ -0,0 +1,92 @@
+//! Key-to-partition assignment shared by Java and native callers.
+//!
+//! Partitions are chosen with the murmur2 hash used by Kafka's default
+//! partitioner, so a key lands on the same partition number here as it would
+//! for a Kafka producer with the same partition count. Partitioned appends
+//! store each record under the key `<topic>-<partition>`.
+
+use bytes::Bytes;
+
+/// Seed of Kafka's murmur2 hash.
+const MURMUR2_SEED: u32 = 0x9747_b28c;
+
+/// Computes the 32-bit murmur2 hash of a key, as implemented by Kafka.
+pub(crate) fn murmur2(data: &[u8]) -> i32 {
+    const M: u32 = 0x5bd1_e995;
+    const R: u32 = 24;
+
+    let mut h = MURMUR2_SEED ^ data.len() as u32;
+    let mut chunks = data.chunks_exact(4);
+    for chunk in &mut chunks {
+        let mut k = u32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+        k = k.wrapping_mul(M);
+        k ^= k >> R;
+        k = k.wrapping_mul(M);
+        h = h.wrapping_mul(M);
+        h ^= k;
+    }
+
+    let tail = chunks.remainder();
+    if tail.len() >= 3 {
+        h ^= (tail[2] as u32) << 16;
+    }
+    if tail.len() >= 2 {
+        h ^= (tail[1] as u32) << 8;
+    }
+    if !tail.is_empty() {
+        h ^= tail[0] as u32;
+        h = h.wrapping_mul(M);
+    }
+
+    h ^= h >> 13;
+    h = h.wrapping_mul(M);
+    h ^= h >> 15;
+    h as i32
+}
+
+/// Returns the partition a key is assigned to; `num_partitions` must be positive.
+pub(crate) fn partition_for_key(key: &[u8], num_partitions: u32) -> u32 {
+    // Kafka clears the sign bit rather than taking the absolute value
+    (murmur2(key) & 0x7fff_ffff) as u32 % num_partitions
+}
+
+/// Returns the log key holding a topic's partition.
+pub(crate) fn partition_key(topic: &str, partition: u32) -> Bytes {
+    Bytes::from(format!("{}-{}", topic, partition))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_match_kafka_murmur2() {
+        // given / when / then (reference values from Kafka's Utils.murmur2)
+        assert_eq!(murmur2(b"21"), -973_932_308);
+        assert_eq!(murmur2(b"foobar"), -790_332_482);
+        assert_eq!(murmur2(b"a-little-bit-long-string"), -985_981_536);
+        assert_eq!(murmur2(b""), 275_646_681);
+    }
+
+    #[test]
+    fn should_assign_partition_within_range() {
+        // given
+        let keys: [&[u8]; 4] = [b"", b"a", b"orders", b"a-little-bit-long-string"];
+
+        // when / then
+        for key in keys {
+            let partition = partition_for_key(key, 7);
+            assert!(partition < 7);
+            assert_eq!(partition, partition_for_key(key, 7));
+        }
+    }
+
+    #[test]
+    fn should_build_partition_key() {
+        // given / when
+        let key = partition_key("orders", 3);
+
+        // then
+        assert_eq!(key, Bytes::from_static(b"orders-3"));
+    }
+}
@@ -0,0 +1,235 @@
+//! Pipelined appends with ordered acknowledgements.
+//!
+//! A pipeline lets a producer keep several append batches in flight while
+//! still observing them complete in sequence order. Batches are appended in
+//! submission order: each waits for its predecessor to be appended, but not
+//! for its predecessor's flush, so a durable batch's flush overlaps with the
+//! next batch's append and with the producer preparing further batches.
+//!
+//! Completions are delivered by a single bridge thread that awaits the
+//! batches in submission order, so Java futures complete in sequence order
+//! even when a later batch finishes first. A failed batch fails every batch
+//! submitted after it, so a producer retrying the failed records can never
+//! see them land behind records submitted later.
+
+use std::sync::{Arc, Mutex, PoisonError, Weak};
+use std::time::Instant;
+
+use log::Record;
+use tokio::runtime::Handle;
+use tokio::sync::{oneshot, RwLock, Semaphore};
+
+use crate::bridge::{BridgePool, Deliver};
+use crate::clock::Clock;
+use crate::jfr;
+use crate::memory::{HandleMemory, MemoryCharge};
+use crate::metrics::{LatencyRecorder, Operation};
+use crate::reopen::ReopeningLog;
+use crate::slowlog::SlowLog;
+use crate::tags::Tags;
+use crate::tailcache::TailCache;
+use crate::watch::Watches;
+use crate::AckMode;
+
+/// Most batches a pipeline may keep in flight.
+pub(crate) const MAX_IN_FLIGHT: usize = 64;
+
+/// Error reported for batches submitted after a failed one.
+const PREDECESSOR_FAILED: &str = "An earlier pipelined append failed";
+
+/// Ordered append pipeline over one LogDb.
+pub(crate) struct Pipeline {
+    log: Weak<ReopeningLog>,
+    append_lock: Arc<RwLock<()>>,
+    tail_cache: Option<Arc<TailCache>>,
+    watches: Arc<Watches>,
+    latency: Arc<LatencyRecorder>,
+    slow_log: Arc<SlowLog>,
+    /// Tags of the handle, carried by its JFR events
+    tags: Arc<Tags>,
+    /// Memory of the handle, charged with the records of batches in flight
+    memory: Arc<HandleMemory>,
+    /// Payload size above which the handle splits values into chunks
+    chunk_threshold: usize,
+    /// Whether the handle copies values with `GetPrimitiveArrayCritical`
+    critical_copy: bool,
+    /// Clock of the handle, stamping submitted records if it assigns timestamps
+    clock: Arc<Clock>,
+    /// Whether the handle records the receive time of appended values
+    receive_timestamps: bool,
+    runtime: Handle,
+    permits: Arc<Semaphore>,
+    /// Resolves once the latest batch was appended, with whether it succeeded;
+    /// also serializes submissions so the bridge sees them in order
+    tail: Mutex<Option<oneshot::Receiver<bool>>>,
+    bridge: BridgePool,
+}
+
+impl Pipeline {
+    /// Opens a pipeline allowing `max_in_flight` batches in flight.
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn open(
+        max_in_flight: usize,
+        log: Weak<ReopeningLog>,
+        append_lock: Arc<RwLock<()>>,
+        tail_cache: Option<Arc<TailCache>>,
+        watches: Arc<Watches>,
+        latency: Arc<LatencyRecorder>,
+        slow_log: Arc<SlowLog>,
+        tags: Arc<Tags>,
+        memory: Arc<HandleMemory>,
+        chunk_threshold: usize,
+        critical_copy: bool,
+        clock: Arc<Clock>,
+        receive_timestamps: bool,
+        runtime: Handle,
+    ) -> Result<Self, String> {
+        if max_in_flight == 0 || max_in_flight > MAX_IN_FLIGHT {
+            return Err(format!(
+                "maxInFlight must be between 1 and {}, got {}",
+                MAX_IN_FLIGHT, max_in_flight
+            ));
+        }
+        let bridge = BridgePool::start(1, runtime.clone()).map_err(|e| e.to_string())?;
+        Ok(Self {
+            log,
+            append_lock,
+            tail_cache,
+            watches,
+            latency,
+            slow_log,
+            tags,
+            memory,
+            chunk_threshold,
+            critical_copy,
+            clock,
+            receive_timestamps,
+            runtime,
+            permits: Arc::new(Semaphore::new(max_in_flight)),
+            tail: Mutex::new(None),
+            bridge,
+        })
+    }
+
+    /// Returns the memory of the handle the pipeline appends to.
+    pub(crate) fn memory(&self) -> &HandleMemory {
+        &self.memory
+    }
+
+    /// Returns the payload size above which the handle splits values into
+    /// chunks; 0 leaves values whole.
+    pub(crate) fn chunk_threshold(&self) -> usize {
+        self.chunk_threshold
+    }
+
+    /// Returns whether the handle copies appended values with
+    /// `GetPrimitiveArrayCritical`.
+    pub(crate) fn critical_copy(&self) -> bool {
+        self.critical_copy
+    }
+
+    /// Returns the clock of the handle.
+    pub(crate) fn clock(&self) -> &Clock {
+        &self.clock
+    }
+
+    /// Returns whether the handle records the receive time of appended
+    /// values.
+    pub(crate) fn receive_timestamps(&self) -> bool {
+        self.receive_timestamps
+    }
+
+    /// Submits a batch, blocking while the pipeline is full.
+    ///
+    /// `charge` is the batch's charge against the handle's memory, held until
+    /// it is appended. `deliver` turns the batch's outcome, the sequence of
+    /// its first record or an error, into the Java completion. Returns false
+    /// if the pipeline is shutting down and the batch was dropped.
+    pub(crate) fn submit<F>(
+        &self,
+        records: Vec<Record>,
+        charge: MemoryCharge,
+        ack_mode: AckMode,
+        deliver: F,
+    ) -> bool
+    where
+        F: FnOnce(Result<u64, String>) -> Deliver + Send + 'static,
+    {
+        let Ok(permit) = self
+            .runtime
+            .block_on(Arc::clone(&self.permits).acquire_owned())
+        else {
+            return false;
+        };
+
+        let mut tail = self.tail.lock().unwrap_or_else(PoisonError::into_inner);
+        let (appended, next) = oneshot::channel();
+        let previous = tail.replace(next);
+        let log = self.log.clone();
+        let append_lock = Arc::clone(&self.append_lock);
+        let tail_cache = self.tail_cache.clone();
+        let watches = Arc::clone(&self.watches);
+        let latency = Arc::clone(&self.latency);
+        let slow_log = Arc::clone(&self.slow_log);
+        let tags = Arc::clone(&self.tags);
+        let batch = self.runtime.spawn(async move {
+            let _charge = charge;
+            let predecessor_ok = match previous {
+                Some(previous) => previous.await.unwrap_or(false),
+                None => true,
+            };
+            if !predecessor_ok {
+                let _ = appended.send(false);
+                return Err(PREDECESSOR_FAILED.to_string());
+            }
+            let Some(shared) = log.upgrade() else {
+                let _ = appended.send(false);
+                return Err("LogDb is closed".to_string());
+            };
+            let log = shared.current();
+
+            let summary = slow_log.summarize(&records);
+            let size = jfr::summarize(&tags, &records);
+            let started = Instant::now();
+            let result = {
+                let _append = append_lock.read().await;
+                crate::append_cached(&log, tail_cache.as_deref(), &watches, records).await
+            };
+            let result = match result {
+                Ok(result) => {
+                    // Successors may append while this batch flushes
+                    let _ = appended.send(true);
+                    if ack_mode == AckMode::Durable {
+                        log.flush().await.map(|()| result.start_sequence)
+                    } else {
+                        Ok(result.start_sequence)
+                    }
+                }
+                Err(e) => {
+                    let _ = appended.send(false);
+                    Err(e)
+                }
+            };
+            shared.observe(&result);
+            let elapsed = started.elapsed();
+            latency.record(Operation::Append, elapsed);
+            slow_log.append(elapsed, summary, result.as_ref().ok().copied());
+            jfr::append(elapsed, size, result.is_ok());
+            result.map_err(|e| e.to_string())
+        });
+
+        self.bridge.submit(Box::pin(async move {
+            let result = batch.await.unwrap_or_else(|e| Err(e.to_string()));
+            // Free the slot first so completion callbacks may submit again
+            drop(permit);
+            deliver(result)
+        }))
+    }
+}
+
+impl Drop for Pipeline {
+    /// Waits for every batch in flight and delivers its completion.
+    fn drop(&mut self) {
+        self.permits.close();
+    }
+}
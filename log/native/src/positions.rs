@@ -0,0 +1,89 @@
+//! Per-key read positions for stateful readers.
+//!
+//! A reader's position for a key is the next sequence `poll` will return
+//! entries from. Positions start at 0 and are moved by polling past entries or
+//! by an explicit seek. Positions are held in memory only; use committed
+//! offsets to resume across restarts.
+
+use std::collections::HashMap;
+use std::sync::{Mutex, PoisonError};
+
+use bytes::Bytes;
+
+/// Read positions of one reader handle, keyed by log key.
+#[derive(Default)]
+pub(crate) struct ReaderPositions {
+    positions: Mutex<HashMap<Bytes, u64>>,
+}
+
+impl ReaderPositions {
+    /// Returns the next sequence to read for a key.
+    pub(crate) fn get(&self, key: &Bytes) -> u64 {
+        self.lock().get(key).copied().unwrap_or(0)
+    }
+
+    /// Moves the position for a key to `sequence`, forwards or backwards.
+    pub(crate) fn seek(&self, key: Bytes, sequence: u64) {
+        self.lock().insert(key, sequence);
+    }
+
+    /// Moves the position for a key forward after a poll returned entries.
+    ///
+    /// A concurrent seek to a later position is kept.
+    pub(crate) fn advance(&self, key: Bytes, next_sequence: u64) {
+        let mut positions = self.lock();
+        let position = positions.entry(key).or_insert(next_sequence);
+        *position = (*position).max(next_sequence);
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<Bytes, u64>> {
+        self.positions
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_start_at_zero() {
+        // given
+        let positions = ReaderPositions::default();
+
+        // when
+        let position = positions.get(&Bytes::from_static(b"k"));
+
+        // then
+        assert_eq!(position, 0);
+    }
+
+    #[test]
+    fn should_seek_backwards() {
+        // given
+        let positions = ReaderPositions::default();
+        let key = Bytes::from_static(b"k");
+        positions.advance(key.clone(), 10);
+
+        // when
+        positions.seek(key.clone(), 2);
+
+        // then
+        assert_eq!(positions.get(&key), 2);
+    }
+
+    #[test]
+    fn should_only_advance_forwards() {
+        // given
+        let positions = ReaderPositions::default();
+        let key = Bytes::from_static(b"k");
+        positions.seek(key.clone(), 10);
+
+        // when
+        positions.advance(key.clone(), 4);
+
+        // then
+        assert_eq!(positions.get(&key), 10);
+    }
+}
@@ -0,0 +1,133 @@
+//! Priority classes of appends and scans.
+//!
+//! Administrative work on a benchmarked log, such as a full scan verifying a
+//! key or a backfill, competes with the measured workload for the runtime,
+//! the append lock and storage reads, adding to its tail latency. Calls of the
+//! background class hold a permit of a semaphore for their storage access, so
+//! at most the configured number of them run at once and the rest queue
+//! natively, on the runtime, rather than piling onto storage. Foreground
+//! calls, the default, take no permit and never wait on background ones.
+//!
+//! Permits are taken after rate limits admitted the call, so a throttled
+//! background call does not hold one while it sleeps.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Instant;
+
+use tokio::sync::{Semaphore, SemaphorePermit};
+
+use crate::metrics::Stats;
+
+/// Priority of a call, mirroring the Java `OperationPriority` enum.
+///
+/// The declaration order is part of the native contract.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Priority {
+    /// Part of the workload; never queued behind background calls
+    Foreground,
+    /// Administrative; limited to the handle's background concurrency
+    Background,
+}
+
+impl Priority {
+    pub(crate) fn from_ordinal(ordinal: i32) -> Option<Self> {
+        match ordinal {
+            0 => Some(Self::Foreground),
+            1 => Some(Self::Background),
+            _ => None,
+        }
+    }
+}
+
+/// Admits calls of each priority class of a handle.
+pub(crate) struct PriorityGate {
+    background: Semaphore,
+    concurrency: usize,
+    waits: AtomicU64,
+    wait_nanos: AtomicU64,
+}
+
+impl PriorityGate {
+    /// Creates a gate running at most `background_concurrency` background
+    /// calls at once; at least one always runs.
+    pub(crate) fn new(background_concurrency: usize) -> Self {
+        let concurrency = background_concurrency.max(1);
+        Self {
+            background: Semaphore::new(concurrency),
+            concurrency,
+            waits: AtomicU64::new(0),
+            wait_nanos: AtomicU64::new(0),
+        }
+    }
+
+    /// Waits until a call of `priority` may access storage, returning the
+    /// permit a background call holds until it finishes.
+    pub(crate) async fn admit(&self, priority: Priority) -> Option<SemaphorePermit<'_>> {
+        if priority == Priority::Foreground {
+            return None;
+        }
+        if let Ok(permit) = self.background.try_acquire() {
+            return Some(permit);
+        }
+        let started = Instant::now();
+        // The semaphore is never closed
+        let permit = self.background.acquire().await.ok();
+        self.waits.fetch_add(1, Ordering::Relaxed);
+        self.wait_nanos
+            .fetch_add(started.elapsed().as_nanos() as u64, Ordering::Relaxed);
+        permit
+    }
+
+    /// Appends the background calls running and the waits for a permit.
+    pub(crate) fn append_stats(&self, stats: &mut Stats) {
+        stats.push((
+            "priority.background_in_flight".to_string(),
+            (self.concurrency - self.background.available_permits()) as i64,
+        ));
+        stats.push((
+            "priority.background_waits".to_string(),
+            self.waits.load(Ordering::Relaxed) as i64,
+        ));
+        stats.push((
+            "priority.background_wait_nanos".to_string(),
+            self.wait_nanos.load(Ordering::Relaxed) as i64,
+        ));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    fn runtime() -> tokio::runtime::Runtime {
+        tokio::runtime::Builder::new_multi_thread()
+            .worker_threads(1)
+            .enable_all()
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn should_queue_background_calls_past_concurrency() {
+        // given
+        let runtime = runtime();
+        let gate = PriorityGate::new(1);
+        let held = runtime.block_on(gate.admit(Priority::Background));
+
+        // when
+        let foreground = runtime.block_on(gate.admit(Priority::Foreground));
+        let queued = runtime.block_on(tokio::time::timeout(
+            Duration::from_millis(20),
+            gate.admit(Priority::Background),
+        ));
+
+        // then
+        assert!(held.is_some());
+        assert!(foreground.is_none());
+        assert!(queued.is_err());
+        drop(held);
+        assert!(runtime.block_on(gate.admit(Priority::Background)).is_some());
+    }
+}
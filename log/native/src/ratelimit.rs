@@ -0,0 +1,300 @@
+//! Token-bucket rate limits on appends and scans.
+//!
+//! Each configured limit is a token bucket refilled at its rate and holding
+//! at most one second of tokens. A call reserves its tokens up front and
+//! sleeps until the bucket has paid off the debt left by earlier calls, so a
+//! batch larger than the bucket is admitted rather than stalled forever and
+//! the long-run rate still converges on the limit. Scans do not know their
+//! size in advance: they wait for earlier debt, then charge what they read.
+//!
+//! Waiting happens natively on the runtime, on a timer, so callers throttled
+//! by the handle don't need a Java rate limiter with its own scheduling
+//! jitter.
+//!
+//! Limits can be replaced while the handle is open. New buckets start full,
+//! so debt left under the old limits is forgiven.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, PoisonError, RwLock, RwLockReadGuard};
+use std::time::{Duration, Instant};
+
+use log::{LogEntry, Record};
+
+use crate::metrics::Stats;
+
+/// Rate limits extracted from a Java `RateLimitConfig`; 0 means no limit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub(crate) struct RateLimitSettings {
+    pub(crate) produce_records_per_sec: u64,
+    pub(crate) produce_bytes_per_sec: u64,
+    pub(crate) consume_records_per_sec: u64,
+    pub(crate) consume_bytes_per_sec: u64,
+}
+
+impl RateLimitSettings {
+    pub(crate) fn is_unlimited(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+struct BucketState {
+    /// Negative while earlier reservations are still being paid off
+    tokens: f64,
+    refilled: Instant,
+}
+
+/// A token bucket allowing debt.
+struct TokenBucket {
+    rate: f64,
+    burst: f64,
+    state: Mutex<BucketState>,
+}
+
+impl TokenBucket {
+    fn new(rate: u64, now: Instant) -> Self {
+        let rate = rate as f64;
+        Self {
+            rate,
+            burst: rate,
+            state: Mutex::new(BucketState {
+                tokens: rate,
+                refilled: now,
+            }),
+        }
+    }
+
+    /// Takes `amount` tokens, returning how long the caller must wait for
+    /// the debt of earlier callers to be paid off.
+    fn reserve(&self, amount: f64, now: Instant) -> Duration {
+        let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+        let elapsed = now.saturating_duration_since(state.refilled).as_secs_f64();
+        state.tokens = (state.tokens + elapsed * self.rate).min(self.burst);
+        state.refilled = state.refilled.max(now);
+        let wait = if state.tokens >= 0.0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64(-state.tokens / self.rate)
+        };
+        state.tokens -= amount;
+        wait
+    }
+}
+
+/// A pair of record and byte buckets for one direction.
+struct Limit {
+    records: Option<TokenBucket>,
+    bytes: Option<TokenBucket>,
+    /// Total time callers were made to wait
+    throttled_nanos: AtomicU64,
+}
+
+impl Limit {
+    fn new(records_per_sec: u64, bytes_per_sec: u64, now: Instant) -> Self {
+        let bucket = |rate| (rate > 0).then(|| TokenBucket::new(rate, now));
+        Self {
+            records: bucket(records_per_sec),
+            bytes: bucket(bytes_per_sec),
+            throttled_nanos: AtomicU64::new(0),
+        }
+    }
+
+    /// Reserves tokens from both buckets, returning the longer wait.
+    fn charge(&self, records: usize, bytes: usize, now: Instant) -> Duration {
+        let wait = |bucket: &Option<TokenBucket>, amount: usize| {
+            bucket
+                .as_ref()
+                .map_or(Duration::ZERO, |b| b.reserve(amount as f64, now))
+        };
+        wait(&self.records, records).max(wait(&self.bytes, bytes))
+    }
+
+    /// Replaces the buckets, keeping the time callers were throttled.
+    fn replace(&mut self, records_per_sec: u64, bytes_per_sec: u64, now: Instant) {
+        let throttled_nanos = self.throttled_nanos.load(Ordering::Relaxed);
+        *self = Self::new(records_per_sec, bytes_per_sec, now);
+        self.throttled_nanos = AtomicU64::new(throttled_nanos);
+    }
+
+    /// Like [`Limit::charge`], for a caller that then waits.
+    fn reserve(&self, records: usize, bytes: usize, now: Instant) -> Duration {
+        let wait = self.charge(records, bytes, now);
+        self.throttled_nanos
+            .fetch_add(wait.as_nanos() as u64, Ordering::Relaxed);
+        wait
+    }
+}
+
+/// Per-handle produce and consume rate limits.
+pub(crate) struct RateLimiter {
+    produce: RwLock<Limit>,
+    consume: RwLock<Limit>,
+}
+
+impl RateLimiter {
+    pub(crate) fn new(settings: RateLimitSettings) -> Self {
+        let now = Instant::now();
+        Self {
+            produce: RwLock::new(Limit::new(
+                settings.produce_records_per_sec,
+                settings.produce_bytes_per_sec,
+                now,
+            )),
+            consume: RwLock::new(Limit::new(
+                settings.consume_records_per_sec,
+                settings.consume_bytes_per_sec,
+                now,
+            )),
+        }
+    }
+
+    /// Replaces the limits of both directions.
+    pub(crate) fn update(&self, settings: RateLimitSettings) {
+        let now = Instant::now();
+        self.produce
+            .write()
+            .unwrap_or_else(PoisonError::into_inner)
+            .replace(
+                settings.produce_records_per_sec,
+                settings.produce_bytes_per_sec,
+                now,
+            );
+        self.consume
+            .write()
+            .unwrap_or_else(PoisonError::into_inner)
+            .replace(
+                settings.consume_records_per_sec,
+                settings.consume_bytes_per_sec,
+                now,
+            );
+    }
+
+    /// Waits until an append of `records` may proceed.
+    pub(crate) async fn admit_append(&self, records: &[Record]) {
+        let bytes = records.iter().map(|r| r.key.len() + r.value.len()).sum();
+        let wait = read(&self.produce).reserve(records.len(), bytes, Instant::now());
+        sleep(wait).await;
+    }
+
+    /// Waits until a scan may proceed; its size is charged afterwards with
+    /// [`RateLimiter::charge_scan`].
+    pub(crate) async fn admit_scan(&self) {
+        let wait = read(&self.consume).reserve(0, 0, Instant::now());
+        sleep(wait).await;
+    }
+
+    /// Charges the entries a scan returned against the consume limits.
+    pub(crate) fn charge_scan(&self, entries: &[LogEntry]) {
+        let bytes = entries.iter().map(|e| e.key.len() + e.value.len()).sum();
+        // The debt this leaves is paid off by the next scan's wait
+        read(&self.consume).charge(entries.len(), bytes, Instant::now());
+    }
+
+    /// Appends the total time appends and scans were throttled.
+    pub(crate) fn append_stats(&self, stats: &mut Stats) {
+        stats.push((
+            "rate_limit.produce_throttled_nanos".to_string(),
+            read(&self.produce).throttled_nanos.load(Ordering::Relaxed) as i64,
+        ));
+        stats.push((
+            "rate_limit.consume_throttled_nanos".to_string(),
+            read(&self.consume).throttled_nanos.load(Ordering::Relaxed) as i64,
+        ));
+    }
+}
+
+fn read(limit: &RwLock<Limit>) -> RwLockReadGuard<'_, Limit> {
+    limit.read().unwrap_or_else(PoisonError::into_inner)
+}
+
+async fn sleep(wait: Duration) {
+    if !wait.is_zero() {
+        tokio::time::sleep(wait).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_admit_burst_then_pace_at_rate() {
+        // given
+        let now = Instant::now();
+        let bucket = TokenBucket::new(100, now);
+
+        // when
+        let first = bucket.reserve(100.0, now);
+        let second = bucket.reserve(50.0, now);
+        let third = bucket.reserve(1.0, now);
+
+        // then
+        assert_eq!(first, Duration::ZERO);
+        assert_eq!(second, Duration::ZERO);
+        assert_eq!(third, Duration::from_millis(500));
+    }
+
+    #[test]
+    fn should_refill_up_to_one_second_of_tokens() {
+        // given
+        let now = Instant::now();
+        let bucket = TokenBucket::new(100, now);
+        bucket.reserve(150.0, now);
+
+        // when
+        let after_debt = bucket.reserve(100.0, now + Duration::from_millis(500));
+        let after_idle = bucket.reserve(100.0, now + Duration::from_secs(60));
+        let over_burst = bucket.reserve(1.0, now + Duration::from_secs(60));
+
+        // then
+        assert_eq!(after_debt, Duration::ZERO);
+        assert_eq!(after_idle, Duration::ZERO);
+        assert_eq!(over_burst, Duration::ZERO);
+        assert_eq!(
+            bucket.reserve(0.0, now + Duration::from_secs(60)),
+            Duration::from_millis(10)
+        );
+    }
+
+    #[test]
+    fn should_wait_for_slowest_limit() {
+        // given
+        let now = Instant::now();
+        let limit = Limit::new(1_000, 100, now);
+        limit.reserve(1, 200, now);
+
+        // when
+        let wait = limit.reserve(1, 1, now);
+
+        // then
+        assert_eq!(wait, Duration::from_secs(1));
+    }
+
+    #[test]
+    fn should_replace_limits_and_forgive_debt() {
+        // given
+        let limiter = RateLimiter::new(RateLimitSettings {
+            produce_records_per_sec: 10,
+            ..RateLimitSettings::default()
+        });
+        let now = Instant::now();
+        read(&limiter.produce).reserve(20, 0, now);
+        let throttled = read(&limiter.produce).reserve(1, 0, now);
+
+        // when
+        limiter.update(RateLimitSettings {
+            produce_records_per_sec: 1_000,
+            ..RateLimitSettings::default()
+        });
+        let wait = read(&limiter.produce).reserve(1, 0, Instant::now());
+
+        // then
+        assert_eq!(throttled, Duration::from_secs(1));
+        assert_eq!(wait, Duration::ZERO);
+        assert_eq!(
+            read(&limiter.produce)
+                .throttled_nanos
+                .load(Ordering::Relaxed),
+            throttled.as_nanos() as u64
+        );
+    }
+}
@@ -0,0 +1,138 @@
+//! Read-ahead for sequential reader scans.
+//!
+//! A consumer typically scans a key page by page, starting each scan right
+//! after the last entry of the previous one. With read-ahead enabled, a scan
+//! that fills its page starts reading the following page in the background
+//! on the reader's runtime. When the next scan for the key starts exactly
+//! there and asks for no more than a page, it takes the prefetched entries,
+//! awaiting the prefetch if it is still running, instead of waiting for a cold
+//! object-store read. Any other scan discards the prefetch.
+//!
+//! Efficiency is reported as the share of prefetches a scan took: a prefetch
+//! that is discarded, replaced by a newer one, or fails counts as a miss.
+//!
+//! Prefetched pages are only served when full: entries are immutable once
+//! written, so a full page is never stale, while a short one may predate
+//! entries appended since.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, PoisonError};
+
+use bytes::Bytes;
+use log::{LogDbReader, LogEntry, LogRead};
+use tokio::runtime::Handle;
+use tokio::task::JoinHandle;
+
+use crate::chunking::Assembler;
+use crate::metrics::{self, Stats};
+
+/// Keys with a prefetch in flight or waiting before no more are started.
+const MAX_PREFETCHED_KEYS: usize = 1024;
+
+/// A page being read ahead for one key.
+struct Prefetch {
+    start: u64,
+    max: usize,
+    task: JoinHandle<Result<Vec<LogEntry>, log::Error>>,
+}
+
+/// Per-handle read-ahead state.
+#[derive(Default)]
+pub(crate) struct ReadAhead {
+    prefetches: Mutex<HashMap<Bytes, Prefetch>>,
+    /// Prefetches started
+    started: AtomicU64,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+impl ReadAhead {
+    /// Takes the page prefetched for `key`, if it starts at `start` and
+    /// covers `max` entries.
+    pub(crate) async fn take(&self, key: &Bytes, start: u64, max: usize) -> Option<Vec<LogEntry>> {
+        let prefetch = self.lock().remove(key)?;
+        if prefetch.start != start || prefetch.max < max {
+            prefetch.task.abort();
+            self.misses.fetch_add(1, Ordering::Relaxed);
+            return None;
+        }
+        match prefetch.task.await {
+            Ok(Ok(mut entries)) if entries.len() >= max => {
+                entries.truncate(max);
+                self.hits.fetch_add(1, Ordering::Relaxed);
+                Some(entries)
+            }
+            _ => {
+                self.misses.fetch_add(1, Ordering::Relaxed);
+                None
+            }
+        }
+    }
+
+    /// Starts reading the page after `entries` if they filled a page of `max`.
+    pub(crate) fn after_scan(
+        &self,
+        reader: Arc<LogDbReader>,
+        runtime: &Handle,
+        key: Bytes,
+        entries: &[LogEntry],
+        max: usize,
+    ) {
+        // A short page reached the tail; there is nothing to read ahead yet
+        let Some(last) = entries.last().filter(|_| entries.len() >= max) else {
+            return;
+        };
+        let start = last.sequence + 1;
+        let mut prefetches = self.lock();
+        if prefetches.len() >= MAX_PREFETCHED_KEYS && !prefetches.contains_key(&key) {
+            return;
+        }
+        let task = runtime.spawn(read_page(reader, key.clone(), start, max));
+        self.started.fetch_add(1, Ordering::Relaxed);
+        if let Some(replaced) = prefetches.insert(key, Prefetch { start, max, task }) {
+            replaced.task.abort();
+            self.misses.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    /// Appends the prefetches started, taken by a scan and wasted, with the
+    /// resulting hit rate.
+    pub(crate) fn append_stats(&self, stats: &mut Stats) {
+        let hits = self.hits.load(Ordering::Relaxed);
+        let misses = self.misses.load(Ordering::Relaxed);
+        stats.push((
+            "read_ahead.prefetches".to_string(),
+            self.started.load(Ordering::Relaxed) as i64,
+        ));
+        stats.push(("read_ahead.hits".to_string(), hits as i64));
+        stats.push(("read_ahead.misses".to_string(), misses as i64));
+        stats.push((
+            "read_ahead.hit_rate_ppm".to_string(),
+            metrics::hit_rate_ppm(hits, misses),
+        ));
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<Bytes, Prefetch>> {
+        self.prefetches
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+async fn read_page(
+    reader: Arc<LogDbReader>,
+    key: Bytes,
+    start: u64,
+    max: usize,
+) -> Result<Vec<LogEntry>, log::Error> {
+    let mut iter = reader.scan(key, start..).await?;
+    let mut entries = Assembler::default();
+    while entries.len() < max {
+        match iter.next().await? {
+            Some(entry) => entries.push(entry),
+            None => break,
+        }
+    }
+    Ok(entries.finish())
+}
@@ -0,0 +1,290 @@
+//! Automatic reopening of a LogDb after repeated storage failures.
+//!
+//! A handle reaches its LogDb through a [`ReopeningLog`], which hands out the
+//! current instance. With reopening configured, appends, scans and flushes
+//! report their outcome, and once `failure_threshold` of them in a row have
+//! failed the instance is presumed poisoned: SlateDB keeps failing every call
+//! after, for example, an object-store error fenced its writer, while the
+//! upstream error does not tell fatal failures from transient ones. The
+//! storage is then opened again on the handle's runtimes in the background
+//! and the new instance swapped in. Calls made until the swap still reach the
+//! old instance and fail; later calls, including those of pipelines, rings
+//! and readers committing offsets, resume on the new one. A failed reopen is
+//! retried after the backoff. The old instance is closed once its in-flight
+//! calls have finished.
+//!
+//! Entries buffered but not yet flushed by the old instance are lost, as they
+//! would be had the process restarted. Every reopen is logged as a warning
+//! and counted in the `reopen.*` stats.
+
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicU64, Ordering};
+use std::sync::{Arc, PoisonError, RwLock, Weak};
+use std::time::Duration;
+
+use common::storage::config::StorageConfig;
+use log::LogDb;
+use object_store::ObjectStore;
+use tokio::runtime::Handle;
+
+use crate::logging;
+use crate::metrics::Stats;
+use crate::tags::Tags;
+
+/// SLF4J logger reopens are logged under.
+const TARGET: &str = "dev.opendata.Reopen";
+
+/// Reopen settings extracted from a Java `ReopenConfig`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct ReopenSettings {
+    /// Consecutive failed storage calls that trigger a reopen
+    pub(crate) failure_threshold: u32,
+    /// Delay before retrying a failed reopen
+    pub(crate) backoff: Duration,
+}
+
+/// Counts consecutive failures against a threshold.
+struct Failures {
+    threshold: u32,
+    consecutive: AtomicU32,
+}
+
+impl Failures {
+    /// Records an outcome, returning true when it reaches the threshold.
+    fn record(&self, succeeded: bool) -> bool {
+        if succeeded {
+            self.consecutive.store(0, Ordering::Relaxed);
+            return false;
+        }
+        self.consecutive.fetch_add(1, Ordering::Relaxed) + 1 >= self.threshold
+    }
+}
+
+/// What a handle needs to open its storage again.
+struct Reopener {
+    settings: ReopenSettings,
+    storage: StorageConfig,
+    /// Object store the log was opened on, if the handle built it
+    object_store: Option<Arc<dyn ObjectStore>>,
+    runtime: Handle,
+    compaction_runtime: Handle,
+    /// Tags starting the handle's log lines
+    tags: Arc<Tags>,
+    failures: Failures,
+    /// Whether a reopen task is running
+    reopening: AtomicBool,
+    reopens: AtomicU64,
+    failed_reopens: AtomicU64,
+}
+
+/// The LogDb of a handle, replaced by a reopened instance if configured.
+pub(crate) struct ReopeningLog {
+    current: RwLock<Arc<LogDb>>,
+    /// None unless reopening is configured
+    reopener: Option<Reopener>,
+}
+
+impl ReopeningLog {
+    /// Wraps an opened log; `storage` and `object_store` are what it was
+    /// opened with.
+    pub(crate) fn new(
+        log: LogDb,
+        settings: Option<ReopenSettings>,
+        storage: StorageConfig,
+        object_store: Option<Arc<dyn ObjectStore>>,
+        runtime: &Handle,
+        compaction_runtime: &Handle,
+        tags: Arc<Tags>,
+    ) -> Self {
+        Self {
+            current: RwLock::new(Arc::new(log)),
+            reopener: settings.map(|settings| Reopener {
+                settings,
+                storage,
+                object_store,
+                runtime: runtime.clone(),
+                compaction_runtime: compaction_runtime.clone(),
+                tags,
+                failures: Failures {
+                    threshold: settings.failure_threshold,
+                    consecutive: AtomicU32::new(0),
+                },
+                reopening: AtomicBool::new(false),
+                reopens: AtomicU64::new(0),
+                failed_reopens: AtomicU64::new(0),
+            }),
+        }
+    }
+
+    /// Returns the current instance.
+    pub(crate) fn current(&self) -> Arc<LogDb> {
+        Arc::clone(&self.current.read().unwrap_or_else(PoisonError::into_inner))
+    }
+
+    /// Records the outcome of a storage call, starting a reopen once enough
+    /// calls in a row have failed.
+    pub(crate) fn observe<T, E>(self: &Arc<Self>, result: &Result<T, E>) {
+        let Some(reopener) = &self.reopener else {
+            return;
+        };
+        if !reopener.failures.record(result.is_ok()) {
+            return;
+        }
+        if reopener.reopening.swap(true, Ordering::AcqRel) {
+            return;
+        }
+        logging::warn(
+            TARGET,
+            &reopener.tags.log_line(&format!(
+                "Reopening LogDb after {} consecutive storage failures",
+                reopener.settings.failure_threshold
+            )),
+        );
+        reopener.runtime.spawn(reopen(Arc::downgrade(self)));
+    }
+
+    /// Returns the current instance, consuming the wrapper.
+    pub(crate) fn into_current(self) -> Arc<LogDb> {
+        self.current
+            .into_inner()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Appends the `reopen.*` stats, if reopening is configured.
+    pub(crate) fn append_stats(&self, stats: &mut Stats) {
+        let Some(reopener) = &self.reopener else {
+            return;
+        };
+        let consecutive = reopener.failures.consecutive.load(Ordering::Relaxed);
+        stats.push(("reopen.count".to_string(), load(&reopener.reopens)));
+        stats.push(("reopen.failed".to_string(), load(&reopener.failed_reopens)));
+        stats.push((
+            "reopen.consecutive_failures".to_string(),
+            consecutive as i64,
+        ));
+        stats.push((
+            "reopen.in_progress".to_string(),
+            reopener.reopening.load(Ordering::Relaxed) as i64,
+        ));
+    }
+}
+
+fn load(counter: &AtomicU64) -> i64 {
+    counter.load(Ordering::Relaxed) as i64
+}
+
+/// Opens the storage again until it succeeds, then swaps the new instance in.
+///
+/// Holds the log weakly while opening, so closing the handle is not delayed.
+async fn reopen(shared: Weak<ReopeningLog>) {
+    loop {
+        let Some((storage, object_store, compaction_runtime, backoff, tags)) =
+            shared.upgrade().and_then(|log| {
+                let reopener = log.reopener.as_ref()?;
+                Some((
+                    reopener.storage.clone(),
+                    reopener.object_store.clone(),
+                    reopener.compaction_runtime.clone(),
+                    reopener.settings.backoff,
+                    Arc::clone(&reopener.tags),
+                ))
+            })
+        else {
+            return;
+        };
+        let error = match crate::open_log(storage, object_store, &compaction_runtime).await {
+            Ok(log) => {
+                let Some(shared) = shared.upgrade() else {
+                    let _ = log.close().await;
+                    return;
+                };
+                let old = std::mem::replace(
+                    &mut *shared
+                        .current
+                        .write()
+                        .unwrap_or_else(PoisonError::into_inner),
+                    Arc::new(log),
+                );
+                if let Some(reopener) = &shared.reopener {
+                    reopener.failures.record(true);
+                    reopener.reopens.fetch_add(1, Ordering::Relaxed);
+                    reopener.reopening.store(false, Ordering::Release);
+                }
+                drop(shared);
+                logging::warn(
+                    TARGET,
+                    &tags.log_line("Reopened LogDb; calls resume on the new instance"),
+                );
+                // The old instance is presumed broken, so its close may fail too
+                let _ = unwrap_when_unused(old).await.close().await;
+                return;
+            }
+            Err(e) => e,
+        };
+        if let Some(reopener) = shared.upgrade().as_ref().and_then(|s| s.reopener.as_ref()) {
+            reopener.failed_reopens.fetch_add(1, Ordering::Relaxed);
+        }
+        logging::warn(
+            TARGET,
+            &tags.log_line(&format!(
+                "Failed to reopen LogDb, retrying in {} ms: {}",
+                backoff.as_millis(),
+                error
+            )),
+        );
+        tokio::time::sleep(backoff).await;
+    }
+}
+
+/// Waits until `shared` is the last reference, then takes its value.
+///
+/// Other references are only held for the duration of a call, so this waits
+/// out calls in flight.
+pub(crate) async fn unwrap_when_unused<T>(mut shared: Arc<T>) -> T {
+    loop {
+        match Arc::try_unwrap(shared) {
+            Ok(value) => return value,
+            Err(still_shared) => {
+                shared = still_shared;
+                tokio::time::sleep(Duration::from_millis(1)).await;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn failures(threshold: u32) -> Failures {
+        Failures {
+            threshold,
+            consecutive: AtomicU32::new(0),
+        }
+    }
+
+    #[test]
+    fn should_reach_threshold_after_consecutive_failures() {
+        // given
+        let failures = failures(3);
+
+        // when
+        let reached: Vec<bool> = (0..3).map(|_| failures.record(false)).collect();
+
+        // then
+        assert_eq!(reached, vec![false, false, true]);
+    }
+
+    #[test]
+    fn should_reset_consecutive_failures_on_success() {
+        // given
+        let failures = failures(2);
+        failures.record(false);
+
+        // when
+        failures.record(true);
+
+        // then
+        assert!(!failures.record(false));
+        assert!(failures.record(false));
+    }
+}
@@ -0,0 +1,522 @@
+//! Shared-memory ring buffer transport for appends.
+//!
+//! An append ring is a natively allocated buffer that Java sees as a direct
+//! `ByteBuffer`. Java producers write record frames into it and advance the
+//! tail; a dedicated native consumer thread drains frames from the head and
+//! appends them to the log in batches, so producing a record costs no JNI call
+//! at all. When the consumer finds the ring empty it raises a waiting flag and
+//! parks; the producer that next publishes a frame clears the flag and rings
+//! the doorbell (one JNI call) to wake it.
+//!
+//! Layout, with all integers in native byte order:
+//!
+//! ```text
+//! offset 0    head    u64  next byte the consumer reads (written by the consumer)
+//! offset 64   tail    u64  next byte producers write (written by producers)
+//! offset 128  waiting u32  1 while the consumer is parked on an empty ring
+//! offset 192  failed  u32  1 once an append failed; later frames are discarded
+//! offset 256  data    capacity bytes, capacity a power of two
+//! ```
+//!
+//! Head and tail are byte positions that only grow; a position maps to
+//! `data[position & (capacity - 1)]`. Each counter sits on its own cache line so
+//! producers and the consumer do not contend on the same line. Frames are
+//! 8-byte aligned and never wrap: a producer that would run past the end of the
+//! data region writes a padding frame covering the remainder and starts its
+//! frame at offset 0.
+//!
+//! ```text
+//! ┌───────────────┬─────────────┬───────────────┬──────────┬──────────────────┬─────┬───────┬─────┐
+//! │ frame_len u32 │ key_len u32 │ value_len u32 │ 0 u32    │ timestamp_ms i64 │ key │ value │ pad │
+//! └───────────────┴─────────────┴───────────────┴──────────┴──────────────────┴─────┴───────┴─────┘
+//! ```
+//!
+//! A padding frame has `key_len == u32::MAX` and only its first two fields are
+//! meaningful. The consumer publishes the head only after the drained records
+//! were appended, so a producer waiting for the head to reach its tail knows
+//! its records are in the log.
+
+use std::alloc::{self, Layout};
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, PoisonError, Weak};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use bytes::{BufMut, Bytes, BytesMut};
+use log::Record;
+use tokio::runtime::Handle;
+use tokio::sync::RwLock;
+
+use crate::memory::{HandleMemory, MemoryCharge};
+use crate::reopen::ReopeningLog;
+use crate::tailcache::TailCache;
+use crate::watch::Watches;
+use crate::TIMESTAMP_HEADER_SIZE;
+
+const HEAD_OFFSET: usize = 0;
+const TAIL_OFFSET: usize = 64;
+const WAITING_OFFSET: usize = 128;
+const FAILED_OFFSET: usize = 192;
+
+/// Size of the header preceding the data region.
+pub(crate) const HEADER_SIZE: usize = 256;
+
+/// Size of the fixed part of a frame.
+pub(crate) const FRAME_HEADER_SIZE: usize = 24;
+
+/// `key_len` marking a padding frame.
+const PADDING_KEY_LEN: u32 = u32::MAX;
+
+/// Smallest accepted data capacity.
+pub(crate) const MIN_CAPACITY: usize = 4096;
+
+/// Most records appended in one batch.
+const MAX_BATCH_RECORDS: usize = 4096;
+
+/// How long the consumer parks before rechecking an empty ring.
+///
+/// The doorbell normally wakes it; the timeout only bounds a missed wakeup.
+const IDLE_PARK_TIMEOUT: Duration = Duration::from_millis(100);
+
+/// Ring memory shared between Java producers and the consumer thread.
+pub(crate) struct RingMemory {
+    ptr: *mut u8,
+    layout: Layout,
+    capacity: usize,
+}
+
+// The memory is only accessed through atomics and the published [head, tail) range.
+unsafe impl Send for RingMemory {}
+unsafe impl Sync for RingMemory {}
+
+impl RingMemory {
+    /// Allocates a zeroed ring with `capacity` data bytes.
+    pub(crate) fn new(capacity: usize) -> Result<Self, String> {
+        if capacity < MIN_CAPACITY || !capacity.is_power_of_two() {
+            return Err(format!(
+                "Ring capacity must be a power of two of at least {} bytes, got {}",
+                MIN_CAPACITY, capacity
+            ));
+        }
+        let layout = Layout::from_size_align(HEADER_SIZE + capacity, 64)
+            .map_err(|e| format!("Invalid ring capacity {}: {}", capacity, e))?;
+        // SAFETY: the layout has a non-zero size
+        let ptr = unsafe { alloc::alloc_zeroed(layout) };
+        if ptr.is_null() {
+            return Err(format!("Failed to allocate a ring of {} bytes", capacity));
+        }
+        Ok(Self {
+            ptr,
+            layout,
+            capacity,
+        })
+    }
+
+    /// Returns the start and length of the whole ring, header included.
+    pub(crate) fn as_raw(&self) -> (*mut u8, usize) {
+        (self.ptr, self.layout.size())
+    }
+
+    fn head(&self) -> &AtomicU64 {
+        // SAFETY: in bounds and 64-byte aligned; only accessed atomically
+        unsafe { &*(self.ptr.add(HEAD_OFFSET) as *const AtomicU64) }
+    }
+
+    fn tail(&self) -> &AtomicU64 {
+        // SAFETY: as for head
+        unsafe { &*(self.ptr.add(TAIL_OFFSET) as *const AtomicU64) }
+    }
+
+    fn waiting(&self) -> &AtomicU32 {
+        // SAFETY: as for head
+        unsafe { &*(self.ptr.add(WAITING_OFFSET) as *const AtomicU32) }
+    }
+
+    fn failed(&self) -> &AtomicU32 {
+        // SAFETY: as for head
+        unsafe { &*(self.ptr.add(FAILED_OFFSET) as *const AtomicU32) }
+    }
+
+    /// Returns `len` data bytes starting at data offset `offset`.
+    fn read(&self, offset: usize, len: usize) -> &[u8] {
+        debug_assert!(offset + len <= self.capacity);
+        // SAFETY: in bounds; callers only read frames producers have published
+        unsafe { std::slice::from_raw_parts(self.ptr.add(HEADER_SIZE + offset), len) }
+    }
+
+    fn read_u32(&self, offset: usize) -> u32 {
+        u32::from_ne_bytes(self.read(offset, 4).try_into().expect("4 bytes"))
+    }
+
+    fn read_i64(&self, offset: usize) -> i64 {
+        i64::from_ne_bytes(self.read(offset, 8).try_into().expect("8 bytes"))
+    }
+}
+
+impl Drop for RingMemory {
+    fn drop(&mut self) {
+        // SAFETY: allocated in `new` with the same layout
+        unsafe { alloc::dealloc(self.ptr, self.layout) };
+    }
+}
+
+/// Decodes the frames published in `[head, tail)`, up to `max_records`.
+///
+/// Returns the records, with timestamp headers prepended to their values, and
+/// the position after the last decoded frame.
+fn decode_frames(
+    memory: &RingMemory,
+    mut head: u64,
+    tail: u64,
+    max_records: usize,
+) -> Result<(Vec<Record>, u64), String> {
+    let mask = memory.capacity as u64 - 1;
+    let mut records = Vec::new();
+    while head < tail && records.len() < max_records {
+        let offset = (head & mask) as usize;
+        let available = (tail - head).min((memory.capacity - offset) as u64) as usize;
+        if available < 8 {
+            return Err(format!("Truncated ring frame at position {}", head));
+        }
+        let frame_len = memory.read_u32(offset) as usize;
+        let key_len = memory.read_u32(offset + 4);
+        if frame_len == 0 || frame_len % 8 != 0 || frame_len > available {
+            return Err(format!(
+                "Invalid ring frame length {} at position {}",
+                frame_len, head
+            ));
+        }
+        if key_len != PADDING_KEY_LEN {
+            let key_len = key_len as usize;
+            let value_len = memory.read_u32(offset + 8) as usize;
+            if FRAME_HEADER_SIZE + key_len + value_len > frame_len {
+                return Err(format!(
+                    "Ring frame at position {} overflows its length {}",
+                    head, frame_len
+                ));
+            }
+            let timestamp_ms = memory.read_i64(offset + 16);
+            let key_start = offset + FRAME_HEADER_SIZE;
+            let mut value = BytesMut::with_capacity(TIMESTAMP_HEADER_SIZE + value_len);
+            value.put_i64(timestamp_ms);
+            value.put_slice(memory.read(key_start + key_len, value_len));
+            records.push(Record {
+                key: Bytes::copy_from_slice(memory.read(key_start, key_len)),
+                value: value.freeze(),
+            });
+        }
+        head += frame_len as u64;
+    }
+    Ok((records, head))
+}
+
+/// State shared between the ring handle and its consumer thread.
+#[derive(Default)]
+struct Consumer {
+    stop: AtomicBool,
+    error: Mutex<Option<String>>,
+}
+
+impl Consumer {
+    fn fail(&self, memory: &RingMemory, error: String) {
+        let mut slot = self.error.lock().unwrap_or_else(PoisonError::into_inner);
+        slot.get_or_insert(error);
+        memory.failed().store(1, Ordering::Release);
+    }
+}
+
+/// An append ring and the thread draining it into a log.
+pub(crate) struct AppendRing {
+    memory: Arc<RingMemory>,
+    consumer: Arc<Consumer>,
+    thread: Option<JoinHandle<()>>,
+    /// Charge of the ring memory against its handle
+    _charge: MemoryCharge,
+}
+
+impl AppendRing {
+    /// Allocates a ring and starts its consumer thread.
+    ///
+    /// The consumer holds the log weakly so that it never keeps a closed log
+    /// alive, and holds `append_lock` shared around each append, like any
+    /// other append through the handle.
+    pub(crate) fn start(
+        capacity: usize,
+        log: Weak<ReopeningLog>,
+        append_lock: Arc<RwLock<()>>,
+        tail_cache: Option<Arc<TailCache>>,
+        watches: Arc<Watches>,
+        runtime: Handle,
+        handle_memory: &HandleMemory,
+    ) -> Result<Self, String> {
+        let memory = Arc::new(RingMemory::new(capacity)?);
+        let charge = handle_memory.charge_ring(memory.as_raw().1);
+        let consumer = Arc::new(Consumer::default());
+        let thread_memory = Arc::clone(&memory);
+        let thread_consumer = Arc::clone(&consumer);
+        let thread = thread::Builder::new()
+            .name("opendata-append-ring".to_string())
+            .spawn(move || {
+                consume(
+                    &thread_memory,
+                    &thread_consumer,
+                    &log,
+                    &append_lock,
+                    tail_cache.as_deref(),
+                    &watches,
+                    &runtime,
+                )
+            })
+            .map_err(|e| e.to_string())?;
+        Ok(Self {
+            memory,
+            consumer,
+            thread: Some(thread),
+            _charge: charge,
+        })
+    }
+
+    /// Returns the start and length of the ring memory.
+    pub(crate) fn memory(&self) -> (*mut u8, usize) {
+        self.memory.as_raw()
+    }
+
+    /// Wakes the consumer thread.
+    pub(crate) fn doorbell(&self) {
+        if let Some(thread) = &self.thread {
+            thread.thread().unpark();
+        }
+    }
+
+    /// Returns the first append failure, if any.
+    pub(crate) fn error(&self) -> Option<String> {
+        self.consumer
+            .error
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    /// Drains every published frame, stops the consumer and returns the first failure.
+    pub(crate) fn close(mut self) -> Option<String> {
+        self.stop();
+        self.error()
+    }
+
+    fn stop(&mut self) {
+        self.consumer.stop.store(true, Ordering::SeqCst);
+        if let Some(thread) = self.thread.take() {
+            thread.thread().unpark();
+            let _ = thread.join();
+        }
+    }
+}
+
+impl Drop for AppendRing {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+/// Consumer loop: drains the ring until stopped and empty.
+fn consume(
+    memory: &RingMemory,
+    consumer: &Consumer,
+    log: &Weak<ReopeningLog>,
+    append_lock: &RwLock<()>,
+    tail_cache: Option<&TailCache>,
+    watches: &Watches,
+    runtime: &Handle,
+) {
+    let mut head = memory.head().load(Ordering::Relaxed);
+    loop {
+        let tail = memory.tail().load(Ordering::Acquire);
+        if head == tail {
+            if consumer.stop.load(Ordering::SeqCst) {
+                return;
+            }
+            // Announce the wait before the final check, pairing with the
+            // producer's tail store followed by its load of the flag.
+            memory.waiting().store(1, Ordering::SeqCst);
+            if memory.tail().load(Ordering::SeqCst) == head && !consumer.stop.load(Ordering::SeqCst)
+            {
+                thread::park_timeout(IDLE_PARK_TIMEOUT);
+            }
+            memory.waiting().store(0, Ordering::SeqCst);
+            continue;
+        }
+
+        head = match decode_frames(memory, head, tail, MAX_BATCH_RECORDS) {
+            Ok((records, next)) => {
+                if !records.is_empty() && memory.failed().load(Ordering::Acquire) == 0 {
+                    if let Err(e) = append(log, append_lock, tail_cache, watches, runtime, records)
+                    {
+                        consumer.fail(memory, e);
+                    }
+                }
+                next
+            }
+            Err(e) => {
+                // Frames after a corrupt one cannot be located; discard them all
+                consumer.fail(memory, e);
+                tail
+            }
+        };
+        memory.head().store(head, Ordering::Release);
+    }
+}
+
+fn append(
+    log: &Weak<ReopeningLog>,
+    append_lock: &RwLock<()>,
+    tail_cache: Option<&TailCache>,
+    watches: &Watches,
+    runtime: &Handle,
+    records: Vec<Record>,
+) -> Result<(), String> {
+    let log = log.upgrade().ok_or_else(|| "LogDb is closed".to_string())?;
+    let result = runtime.block_on(async {
+        let _append = append_lock.read().await;
+        crate::append_cached(&log.current(), tail_cache, watches, records).await
+    });
+    log.observe(&result);
+    result.map(|_| ()).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Writes frames the way the Java producer does.
+    struct Producer<'a> {
+        memory: &'a RingMemory,
+        tail: u64,
+    }
+
+    impl Producer<'_> {
+        fn write(&mut self, offset: usize, bytes: &[u8]) {
+            // SAFETY: test-only single-threaded access within bounds
+            unsafe {
+                std::ptr::copy_nonoverlapping(
+                    bytes.as_ptr(),
+                    self.memory.ptr.add(HEADER_SIZE + offset),
+                    bytes.len(),
+                )
+            };
+        }
+
+        fn push(&mut self, key: &[u8], value: &[u8], timestamp_ms: i64) {
+            let capacity = self.memory.capacity;
+            let frame_len = (FRAME_HEADER_SIZE + key.len() + value.len()).next_multiple_of(8);
+            let mut offset = (self.tail as usize) & (capacity - 1);
+            if capacity - offset < frame_len {
+                let padding = capacity - offset;
+                self.write(offset, &(padding as u32).to_ne_bytes());
+                self.write(offset + 4, &PADDING_KEY_LEN.to_ne_bytes());
+                self.tail += padding as u64;
+                offset = 0;
+            }
+            self.write(offset, &(frame_len as u32).to_ne_bytes());
+            self.write(offset + 4, &(key.len() as u32).to_ne_bytes());
+            self.write(offset + 8, &(value.len() as u32).to_ne_bytes());
+            self.write(offset + 16, &timestamp_ms.to_ne_bytes());
+            self.write(offset + FRAME_HEADER_SIZE, key);
+            self.write(offset + FRAME_HEADER_SIZE + key.len(), value);
+            self.tail += frame_len as u64;
+        }
+    }
+
+    #[test]
+    fn should_reject_capacity_that_is_not_a_power_of_two() {
+        // when
+        let result = RingMemory::new(MIN_CAPACITY + 8);
+
+        // then
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn should_decode_frames_with_timestamp_headers() {
+        // given
+        let memory = RingMemory::new(MIN_CAPACITY).unwrap();
+        let mut producer = Producer {
+            memory: &memory,
+            tail: 0,
+        };
+        producer.push(b"key", b"value", 1_234);
+        producer.push(b"other", b"", 5_678);
+
+        // when
+        let (records, head) = decode_frames(&memory, 0, producer.tail, 10).unwrap();
+
+        // then
+        assert_eq!(head, producer.tail);
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].key.as_ref(), b"key");
+        assert_eq!(&records[0].value[..8], &1_234i64.to_be_bytes());
+        assert_eq!(&records[0].value[8..], b"value");
+        assert_eq!(records[1].key.as_ref(), b"other");
+        assert_eq!(records[1].value.len(), TIMESTAMP_HEADER_SIZE);
+    }
+
+    #[test]
+    fn should_skip_padding_when_frames_wrap() {
+        // given
+        let memory = RingMemory::new(MIN_CAPACITY).unwrap();
+        let mut producer = Producer {
+            memory: &memory,
+            tail: 0,
+        };
+        let value = vec![7u8; 1_000];
+        for _ in 0..3 {
+            producer.push(b"key", &value, 0);
+        }
+        let (_, head) = decode_frames(&memory, 0, producer.tail, 10).unwrap();
+
+        // when
+        producer.push(b"key", &value, 0);
+        producer.push(b"key", &value, 0);
+        let (records, next) = decode_frames(&memory, head, producer.tail, 10).unwrap();
+
+        // then
+        assert_eq!(records.len(), 2);
+        assert_eq!(next, producer.tail);
+        assert!(records.iter().all(|r| r.value[8..] == value[..]));
+    }
+
+    #[test]
+    fn should_stop_at_max_records() {
+        // given
+        let memory = RingMemory::new(MIN_CAPACITY).unwrap();
+        let mut producer = Producer {
+            memory: &memory,
+            tail: 0,
+        };
+        producer.push(b"a", b"1", 0);
+        producer.push(b"b", b"2", 0);
+
+        // when
+        let (records, head) = decode_frames(&memory, 0, producer.tail, 1).unwrap();
+
+        // then
+        assert_eq!(records.len(), 1);
+        assert_eq!(head, 32);
+    }
+
+    #[test]
+    fn should_reject_corrupt_frame_length() {
+        // given
+        let memory = RingMemory::new(MIN_CAPACITY).unwrap();
+        let mut producer = Producer {
+            memory: &memory,
+            tail: 0,
+        };
+        producer.write(0, &12u32.to_ne_bytes());
+
+        // when
+        let result = decode_frames(&memory, 0, 16, 10);
+
+        // then
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,334 @@
+//! Thread names and CPU affinity of a handle's runtimes.
+//!
+//! Every LogDb runs calls on one Tokio runtime and SlateDB compaction on
+//! another. Their threads are named after a configurable prefix followed by
+//! a per-runtime counter, so profiles can attribute native CPU to either.
+//! With cores configured, the runtime starts one worker per core and every
+//! thread it starts, workers and blocking threads alike, pins itself to that
+//! core set, so benchmark runs can keep native work off the cores the JVM's
+//! GC threads use. Pinning uses `sched_setaffinity` and is only supported on
+//! Linux; cores outside the process's own affinity mask fail the open.
+//!
+//! The worker count defaults to one per pinned core, or per CPU without
+//! pinning. With in-memory storage there is no SlateDB compaction to keep
+//! apart from calls, so the compaction runtime can be shared with the
+//! runtime running calls instead of starting a second set of threads.
+//!
+//! Readers run on a multi-threaded runtime of their own by default. Hosts
+//! running many benchmark workers open many readers, each mostly idle,
+//! and every one of them starts a worker per CPU. In current-thread mode a
+//! reader's runtime has a single scheduler thread instead: one driver thread
+//! owns the runtime and runs its tasks, timers and IO, while JNI callers
+//! wait on their calls through the runtime's handle as they do in
+//! multi-threaded mode. Blocking threads still start on demand.
+
+use std::io;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{mpsc, Arc};
+use std::thread::{self, JoinHandle};
+
+use jni::sys::jint;
+use tokio::runtime::{Builder, Handle, Runtime};
+use tokio::sync::oneshot;
+
+use crate::attach;
+use crate::logging;
+use crate::tags::Tags;
+
+/// SLF4J logger failed pinning is logged under.
+const TARGET: &str = "dev.opendata.Runtime";
+
+/// Runtime settings extracted from a Java `RuntimeConfig`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct RuntimeSettings {
+    /// Prefix of thread names, followed by `-<n>`
+    pub(crate) thread_name_prefix: String,
+    /// Cores the threads are pinned to, ascending; empty leaves them unpinned
+    pub(crate) cpus: Vec<usize>,
+    /// Worker threads; 0 starts one per pinned core, or per CPU
+    pub(crate) worker_threads: usize,
+    /// Whether the compaction runtime is the runtime running calls
+    pub(crate) shared: bool,
+}
+
+impl RuntimeSettings {
+    /// Default settings of the runtime running calls.
+    pub(crate) fn operation() -> Self {
+        Self {
+            thread_name_prefix: "opendata-log".to_string(),
+            cpus: Vec::new(),
+            worker_threads: 0,
+            shared: false,
+        }
+    }
+
+    /// Default settings of the runtime running SlateDB compaction.
+    pub(crate) fn compaction() -> Self {
+        Self {
+            thread_name_prefix: "opendata-compaction".to_string(),
+            cpus: Vec::new(),
+            worker_threads: 0,
+            shared: false,
+        }
+    }
+
+    /// Builds a multi-threaded runtime with these settings, starting its log
+    /// lines with `tags`.
+    pub(crate) fn build(&self, tags: &Arc<Tags>) -> Result<Runtime, String> {
+        let mut builder = Builder::new_multi_thread();
+        builder.enable_all();
+        let prefix = self.thread_name_prefix.clone();
+        let next = AtomicUsize::new(0);
+        builder
+            .thread_name_fn(move || format!("{}-{}", prefix, next.fetch_add(1, Ordering::Relaxed)))
+            .on_thread_stop(attach::detach_current_thread);
+        if self.worker_threads > 0 {
+            builder.worker_threads(self.worker_threads);
+        }
+        if !self.cpus.is_empty() {
+            check_available(&self.cpus)?;
+            if self.worker_threads == 0 {
+                builder.worker_threads(self.cpus.len());
+            }
+            let cpus: Arc<[usize]> = self.cpus.as_slice().into();
+            let tags = Arc::clone(tags);
+            builder.on_thread_start(move || {
+                if let Err(e) = pin_current_thread(&cpus) {
+                    logging::warn(
+                        TARGET,
+                        &tags.log_line(&format!(
+                            "Failed to pin runtime thread to cores {:?}: {}",
+                            cpus, e
+                        )),
+                    );
+                }
+            });
+        }
+        builder.build().map_err(|e| e.to_string())
+    }
+}
+
+/// Scheduler of a reader's runtime.
+///
+/// Mirrors the Java `RuntimeMode` enum by ordinal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum RuntimeMode {
+    /// A worker thread per CPU
+    MultiThread,
+    /// One driver thread running every task
+    CurrentThread,
+}
+
+impl RuntimeMode {
+    /// Converts a Java `RuntimeMode.ordinal()` value into a mode.
+    pub(crate) fn from_ordinal(ordinal: jint) -> Option<Self> {
+        match ordinal {
+            0 => Some(Self::MultiThread),
+            1 => Some(Self::CurrentThread),
+            _ => None,
+        }
+    }
+}
+
+/// A runtime owned by a handle, built in either mode.
+pub(crate) enum OwnedRuntime {
+    MultiThread(Runtime),
+    CurrentThread(Driver),
+}
+
+/// Thread owning a current-thread runtime and driving its tasks.
+pub(crate) struct Driver {
+    handle: Handle,
+    stop: oneshot::Sender<()>,
+    thread: JoinHandle<()>,
+}
+
+impl OwnedRuntime {
+    /// Builds a runtime in `mode` whose threads are named `thread_name`.
+    pub(crate) fn build(mode: RuntimeMode, thread_name: &str) -> io::Result<Self> {
+        match mode {
+            RuntimeMode::MultiThread => Builder::new_multi_thread()
+                .enable_all()
+                .thread_name(thread_name)
+                .on_thread_stop(attach::detach_current_thread)
+                .build()
+                .map(Self::MultiThread),
+            RuntimeMode::CurrentThread => Driver::start(thread_name).map(Self::CurrentThread),
+        }
+    }
+
+    /// Returns the handle calls and spawned tasks run through.
+    pub(crate) fn handle(&self) -> &Handle {
+        match self {
+            Self::MultiThread(runtime) => runtime.handle(),
+            Self::CurrentThread(driver) => &driver.handle,
+        }
+    }
+
+    /// Shuts the runtime down without waiting for its blocking threads.
+    pub(crate) fn shutdown_background(self) {
+        match self {
+            Self::MultiThread(runtime) => runtime.shutdown_background(),
+            Self::CurrentThread(driver) => driver.shutdown(),
+        }
+    }
+}
+
+impl Driver {
+    /// Starts the driver thread and waits for its runtime to be built.
+    fn start(thread_name: &str) -> io::Result<Self> {
+        let (built, handle) = mpsc::channel();
+        let (stop, stopped) = oneshot::channel::<()>();
+        let name = thread_name.to_string();
+        let thread = thread::Builder::new()
+            .name(thread_name.to_string())
+            .spawn(move || {
+                let runtime = match Builder::new_current_thread()
+                    .enable_all()
+                    .thread_name(name)
+                    .on_thread_stop(attach::detach_current_thread)
+                    .build()
+                {
+                    Ok(runtime) => runtime,
+                    Err(e) => {
+                        let _ = built.send(Err(e));
+                        return;
+                    }
+                };
+                let _ = built.send(Ok(runtime.handle().clone()));
+                // Runs spawned tasks until stopped or the driver is dropped
+                let _ = runtime.block_on(stopped);
+                runtime.shutdown_background();
+                attach::detach_current_thread();
+            })?;
+        let handle = handle
+            .recv()
+            .map_err(|_| io::Error::other("Runtime driver thread exited"))??;
+        Ok(Self {
+            handle,
+            stop,
+            thread,
+        })
+    }
+
+    /// Stops running tasks and waits for the driver thread to exit.
+    fn shutdown(self) {
+        let _ = self.stop.send(());
+        let _ = self.thread.join();
+    }
+}
+
+/// Checks that the process may run on every core in `cpus`.
+#[cfg(target_os = "linux")]
+fn check_available(cpus: &[usize]) -> Result<(), String> {
+    // SAFETY: cpu_set_t is plain data, and zeroed it is the empty set
+    let mut allowed: libc::cpu_set_t = unsafe { std::mem::zeroed() };
+    // SAFETY: the set is valid for writes of its own size
+    let result =
+        unsafe { libc::sched_getaffinity(0, std::mem::size_of::<libc::cpu_set_t>(), &mut allowed) };
+    if result != 0 {
+        return Err(format!(
+            "Failed to read CPU affinity: {}",
+            std::io::Error::last_os_error()
+        ));
+    }
+    for &cpu in cpus {
+        // SAFETY: the index is checked against CPU_SETSIZE first
+        if cpu >= libc::CPU_SETSIZE as usize || !unsafe { libc::CPU_ISSET(cpu, &allowed) } {
+            return Err(format!("CPU {} is not available to this process", cpu));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(not(target_os = "linux"))]
+fn check_available(_cpus: &[usize]) -> Result<(), String> {
+    Err("CPU affinity is only supported on Linux".to_string())
+}
+
+/// Restricts the calling thread to the cores in `cpus`.
+#[cfg(target_os = "linux")]
+fn pin_current_thread(cpus: &[usize]) -> std::io::Result<()> {
+    // SAFETY: cpu_set_t is plain data, and zeroed it is the empty set
+    let mut set: libc::cpu_set_t = unsafe { std::mem::zeroed() };
+    for &cpu in cpus {
+        // SAFETY: cores were checked against CPU_SETSIZE when the runtime was built
+        unsafe { libc::CPU_SET(cpu, &mut set) };
+    }
+    // SAFETY: the set is valid for reads of its own size
+    let result =
+        unsafe { libc::sched_setaffinity(0, std::mem::size_of::<libc::cpu_set_t>(), &set) };
+    if result == 0 {
+        Ok(())
+    } else {
+        Err(std::io::Error::last_os_error())
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+fn pin_current_thread(_cpus: &[usize]) -> std::io::Result<()> {
+    Err(std::io::ErrorKind::Unsupported.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_name_threads_after_prefix() {
+        // given
+        let settings = RuntimeSettings {
+            thread_name_prefix: "bench-ops".to_string(),
+            worker_threads: 1,
+            ..RuntimeSettings::operation()
+        };
+
+        // when
+        let runtime = settings.build(&Arc::default()).unwrap();
+        let name = runtime
+            .block_on(runtime.spawn(async { std::thread::current().name().map(String::from) }))
+            .unwrap();
+
+        // then
+        assert_eq!(name.as_deref(), Some("bench-ops-0"));
+    }
+
+    #[test]
+    fn should_run_current_thread_tasks_on_driver_thread() {
+        // given
+        let runtime = OwnedRuntime::build(RuntimeMode::CurrentThread, "bench-reader").unwrap();
+
+        // when
+        let handle = runtime.handle().clone();
+        let name = handle
+            .block_on(async {
+                tokio::time::sleep(std::time::Duration::from_millis(1)).await;
+                handle
+                    .spawn(async { std::thread::current().name().map(String::from) })
+                    .await
+            })
+            .unwrap();
+        let workers = runtime.handle().metrics().num_workers();
+        runtime.shutdown_background();
+
+        // then
+        assert_eq!(name.as_deref(), Some("bench-reader"));
+        assert_eq!(workers, 1);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn should_reject_core_outside_affinity_mask() {
+        // given
+        let settings = RuntimeSettings {
+            cpus: vec![libc::CPU_SETSIZE as usize],
+            ..RuntimeSettings::operation()
+        };
+
+        // when
+        let result = settings.build(&Arc::default());
+
+        // then
+        assert!(result.unwrap_err().contains("not available"));
+    }
+}
@@ -0,0 +1,132 @@
+//! Uniform samples of the entries of a key.
+//!
+//! Spot-checking the payloads of a backlog of millions of entries does not
+//! need all of them. A sample keeps each entry of a sequence range with
+//! probability `rate`, drawn from a seeded xorshift generator, so a seed
+//! reproduces a sample of unchanged data.
+//!
+//! Dense samples stream the range and draw per entry. Sparse ones, below
+//! `SEEK_RATE`, instead skip ahead by a geometrically distributed number of
+//! sequences and seek there, reading only the entries kept and whatever
+//! storage reads around them; this draws per sequence, which is the same
+//! while a key's sequences are consecutive. A seek landing inside a chunk
+//! run takes the entry after the run.
+
+use std::ops::Range;
+
+use bytes::Bytes;
+use log::{LogEntry, LogRead};
+
+use crate::chunking::Assembler;
+use crate::fault::{next_random, unit_interval};
+
+/// Rate below which samples seek to each entry rather than stream the range.
+const SEEK_RATE: f64 = 1.0 / 64.0;
+
+/// Samples the entries of `key` in `range`, keeping each with probability
+/// `rate`.
+pub(crate) async fn sample<R: LogRead>(
+    log: &R,
+    key: Bytes,
+    range: Range<u64>,
+    rate: f64,
+    seed: u64,
+) -> Result<Vec<LogEntry>, log::Error> {
+    // xorshift never leaves the all-zero state
+    let mut state = seed.max(1);
+    if rate <= 0.0 || range.is_empty() {
+        return Ok(Vec::new());
+    }
+    if rate >= SEEK_RATE {
+        return stream(log, key, range, rate, &mut state).await;
+    }
+    let mut sampled = Vec::new();
+    let mut next = range.start;
+    loop {
+        let skip = geometric(rate, next_random(&mut state));
+        let Some(target) = next.checked_add(skip).filter(|t| *t < range.end) else {
+            break;
+        };
+        let Some(entry) = first_entry(log, key.clone(), target..range.end).await? else {
+            break;
+        };
+        next = entry.sequence + 1;
+        sampled.push(entry);
+    }
+    Ok(sampled)
+}
+
+/// Streams the range, keeping each entry with probability `rate`.
+async fn stream<R: LogRead>(
+    log: &R,
+    key: Bytes,
+    range: Range<u64>,
+    rate: f64,
+    state: &mut u64,
+) -> Result<Vec<LogEntry>, log::Error> {
+    let mut iter = log.scan(key, range).await?;
+    let mut entries = Assembler::default();
+    let mut sampled = Vec::new();
+    while let Some(entry) = iter.next().await? {
+        entries.push(entry);
+        for entry in entries.take() {
+            if unit_interval(next_random(state)) < rate {
+                sampled.push(entry);
+            }
+        }
+    }
+    Ok(sampled)
+}
+
+/// Reads the first complete entry in `range`.
+async fn first_entry<R: LogRead>(
+    log: &R,
+    key: Bytes,
+    range: Range<u64>,
+) -> Result<Option<LogEntry>, log::Error> {
+    let mut iter = log.scan(key, range).await?;
+    let mut entries = Assembler::default();
+    while let Some(entry) = iter.next().await? {
+        entries.push(entry);
+        if let Some(entry) = entries.take().pop() {
+            return Ok(Some(entry));
+        }
+    }
+    Ok(None)
+}
+
+/// Maps a random value to the number of sequences skipped before the next
+/// one kept with probability `rate`.
+fn geometric(rate: f64, value: u64) -> u64 {
+    // 1 - u lies in (0, 1], so the logarithm is finite
+    let u = 1.0 - unit_interval(value);
+    (u.ln() / (1.0 - rate).ln()).floor() as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_skip_nothing_for_smallest_draw() {
+        assert_eq!(geometric(0.01, 0), 0);
+    }
+
+    #[test]
+    fn should_skip_about_inverse_rate_on_average() {
+        // given
+        let rate = 0.01;
+        let mut state = 42;
+
+        // when
+        let draws = 10_000;
+        let total: u64 = (0..draws)
+            .map(|_| geometric(rate, next_random(&mut state)))
+            .sum();
+
+        // then
+        let mean = total as f64 / draws as f64;
+        // The mean skip of a geometric distribution is (1 - rate) / rate
+        assert!((mean - 99.0).abs() < 5.0, "mean skip {}", mean);
+    }
+}
@@ -0,0 +1,201 @@
+//! Scans delivering batches to Java listeners.
+//!
+//! The bridged and completion-queue scans still hand a caller one page per
+//! call, so a consumer reading a long range either blocks a thread per page
+//! or resubmits after every completion. An async scan instead runs as a task
+//! on the handle's runtime, reading the range in batches and calling a Java
+//! `ScanListener` with each one, then once with the sequence the scan
+//! stopped at, without any Java thread waiting on it.
+//!
+//! Listeners are called on the runtime worker running the scan, attached to
+//! the JVM as in `attach.rs`, one call at a time per scan. The next batch is
+//! read only after the listener returned, so a slow listener slows down its
+//! scan rather than buffering entries natively. A listener returning false,
+//! or throwing, stops the scan. A scan still running when its handle closes
+//! is dropped with the runtime, and its listener receives an error.
+
+use jni::objects::{GlobalRef, JObject, JValue};
+use log::LogEntry;
+
+use crate::{attach, create_log_entry_array};
+
+/// Local references a callback may create.
+const LOCAL_FRAME_CAPACITY: i32 = 16;
+
+/// Error reported to the listener of a scan dropped before it finished.
+const CANCELLED: &str = "Scan was cancelled because the LogDb was closed";
+
+/// Progress of an async scan through its range of sequences.
+pub(crate) struct ScanRange {
+    next_sequence: u64,
+    end_sequence: u64,
+    batch_size: usize,
+}
+
+impl ScanRange {
+    /// Creates the range from `start_sequence` up to, excluding,
+    /// `end_sequence`, read `batch_size` entries at a time.
+    pub(crate) fn new(start_sequence: u64, end_sequence: u64, batch_size: usize) -> Self {
+        Self {
+            next_sequence: start_sequence,
+            end_sequence,
+            batch_size: batch_size.max(1),
+        }
+    }
+
+    /// Returns the sequence the next batch is read from.
+    pub(crate) fn next_sequence(&self) -> u64 {
+        self.next_sequence
+    }
+
+    /// Returns the number of entries to read per batch.
+    pub(crate) fn batch_size(&self) -> usize {
+        self.batch_size
+    }
+
+    /// Returns whether sequences remain to be read.
+    pub(crate) fn remaining(&self) -> bool {
+        self.next_sequence < self.end_sequence
+    }
+
+    /// Drops the entries of a read batch past the range and moves past the
+    /// rest, returning whether another batch may follow.
+    ///
+    /// A short batch means the scan reached the end of the key.
+    pub(crate) fn advance(&mut self, entries: &mut Vec<LogEntry>) -> bool {
+        let full = entries.len() >= self.batch_size;
+        let end = self.end_sequence;
+        entries.retain(|entry| entry.sequence < end);
+        if let Some(last) = entries.last() {
+            self.next_sequence = last.sequence + 1;
+        }
+        full && self.remaining()
+    }
+}
+
+/// Java `ScanListener` of an async scan, told of an error if the scan is
+/// dropped before it finished.
+pub(crate) struct ScanListener {
+    listener: GlobalRef,
+    finished: bool,
+}
+
+impl ScanListener {
+    pub(crate) fn new(listener: GlobalRef) -> Self {
+        Self {
+            listener,
+            finished: false,
+        }
+    }
+
+    /// Delivers a batch, returning whether the listener wants more.
+    pub(crate) fn batch(&self, entries: &[LogEntry]) -> bool {
+        attach::call(LOCAL_FRAME_CAPACITY, |env| {
+            let batch = unsafe { JObject::from_raw(create_log_entry_array(env, entries)?) };
+            env.call_method(
+                &self.listener,
+                "onBatch",
+                "([Ldev/opendata/LogEntry;)Z",
+                &[JValue::Object(&batch)],
+            )?
+            .z()
+        })
+        .unwrap_or(false)
+    }
+
+    /// Reports the end of the scan, with the sequence a later scan would
+    /// continue from.
+    pub(crate) fn complete(mut self, next_sequence: u64) {
+        self.finished = true;
+        attach::call(LOCAL_FRAME_CAPACITY, |env| {
+            env.call_method(
+                &self.listener,
+                "onComplete",
+                "(J)V",
+                &[JValue::Long(next_sequence as i64)],
+            )
+            .map(drop)
+        });
+    }
+
+    /// Reports the error that stopped the scan.
+    pub(crate) fn fail(mut self, message: &str) {
+        self.finished = true;
+        deliver_error(&self.listener, message);
+    }
+}
+
+impl Drop for ScanListener {
+    fn drop(&mut self) {
+        if !self.finished {
+            deliver_error(&self.listener, CANCELLED);
+        }
+    }
+}
+
+fn deliver_error(listener: &GlobalRef, message: &str) {
+    attach::call(LOCAL_FRAME_CAPACITY, |env| {
+        let message = env.new_string(message)?;
+        let error = env.new_object(
+            "dev/opendata/common/OpenDataNativeException",
+            "(Ljava/lang/String;)V",
+            &[JValue::Object(&message)],
+        )?;
+        env.call_method(
+            listener,
+            "onError",
+            "(Ldev/opendata/common/OpenDataNativeException;)V",
+            &[JValue::Object(&error)],
+        )
+        .map(drop)
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+
+    use super::*;
+
+    fn entries(sequences: std::ops::Range<u64>) -> Vec<LogEntry> {
+        sequences
+            .map(|sequence| LogEntry {
+                key: Bytes::from_static(b"key"),
+                sequence,
+                value: Bytes::new(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn should_trim_last_batch_to_end_of_range() {
+        // given
+        let mut range = ScanRange::new(0, 5, 3);
+        let mut first = entries(0..3);
+        let mut second = entries(3..6);
+
+        // when
+        let more_after_first = range.advance(&mut first);
+        let more_after_second = range.advance(&mut second);
+
+        // then
+        assert!(more_after_first);
+        assert!(!more_after_second);
+        assert_eq!(second.len(), 2);
+        assert_eq!(range.next_sequence(), 5);
+    }
+
+    #[test]
+    fn should_stop_after_short_batch() {
+        // given
+        let mut range = ScanRange::new(10, u64::MAX, 4);
+        let mut batch = entries(10..12);
+
+        // when
+        let more = range.advance(&mut batch);
+
+        // then
+        assert!(!more);
+        assert_eq!(range.next_sequence(), 12);
+    }
+}
@@ -0,0 +1,415 @@
+//! Typed SlateDB settings layered over the settings file.
+//!
+//! `SlateDbStorageConfig` carries SlateDB settings only as the path of a
+//! settings file. Settings the Java config exposes as typed fields are
+//! therefore written into a copy of that file: the overlay parses the
+//! configured file, or starts from an empty one, sets each typed value at its
+//! dotted key, and points the storage config at the result.
+//!
+//! Copies are written into a directory of the process's own under the
+//! temporary directory, created with a random name and readable only by the
+//! process's user, and each file is created fresh, so no other user can plant
+//! settings for a handle to load. Handles opened with the same settings share
+//! one copy while its content is unchanged. A copy is held by the handles
+//! using it, which reopen storage from it, and deleted when the last of them
+//! closes; the directory goes with the last copy.
+//!
+//! SlateDB's garbage collector is scheduled the same way. Collecting on
+//! demand is not possible: the collector runs inside the upstream log, which
+//! offers no call to trigger it. So is the write path: the write buffer and
+//! memtable sizes, the flush interval and the WAL toggle.
+//!
+//! Only TOML settings files can be overlaid; YAML and JSON settings files
+//! are still read by SlateDB as long as no typed setting is configured.
+
+use std::collections::hash_map::RandomState;
+use std::collections::HashMap;
+use std::fs::{self, DirBuilder, OpenOptions};
+use std::hash::BuildHasher;
+use std::io::{self, Write};
+#[cfg(unix)]
+use std::os::unix::fs::{DirBuilderExt, OpenOptionsExt};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError, Weak};
+use std::time::Duration;
+
+use common::storage::config::StorageConfig;
+use toml::{Table, Value};
+
+/// Typed SlateDB settings to apply over a settings file.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct SettingsOverlay {
+    /// Dotted keys, such as `object_store_cache_options.root_folder`, and values
+    values: Vec<(&'static str, Value)>,
+}
+
+impl SettingsOverlay {
+    /// Sets `key` to `value`, replacing what the settings file holds there.
+    pub(crate) fn set(&mut self, key: &'static str, value: impl Into<Value>) {
+        self.values.push((key, value.into()));
+    }
+
+    /// Points a SlateDB storage config at a copy of its settings file with
+    /// the overlay applied. Other storage is left unchanged.
+    ///
+    /// Returns the copy, which the caller holds for as long as the storage
+    /// config may be opened, or None if nothing was written.
+    pub(crate) fn apply(
+        &self,
+        storage: &mut StorageConfig,
+    ) -> Result<Option<Arc<SettingsFile>>, String> {
+        let StorageConfig::SlateDb(slatedb) = storage else {
+            return Ok(None);
+        };
+        if self.values.is_empty() {
+            return Ok(None);
+        }
+        let mut table = match &slatedb.settings_path {
+            Some(path) => load_toml(path)?,
+            None => Table::new(),
+        };
+        self.apply_to(&mut table)?;
+        let file = write_settings(&table)?;
+        slatedb.settings_path = Some(file.path.to_string_lossy().into_owned());
+        Ok(Some(file))
+    }
+
+    fn apply_to(&self, table: &mut Table) -> Result<(), String> {
+        for (key, value) in &self.values {
+            let mut parts: Vec<&str> = key.split('.').collect();
+            let Some(last) = parts.pop() else {
+                continue;
+            };
+            let mut section = &mut *table;
+            for part in parts {
+                let entry = section
+                    .entry(part)
+                    .or_insert_with(|| Value::Table(Table::new()));
+                section = entry
+                    .as_table_mut()
+                    .ok_or_else(|| format!("SlateDB setting {} is not a table", part))?;
+            }
+            section.insert(last.to_string(), value.clone());
+        }
+        Ok(())
+    }
+}
+
+/// Interval and minimum age keys of each kind of object SlateDB's garbage
+/// collector deletes: manifests, WAL files and compacted SSTs.
+const GC_KEYS: [(&str, &str); 3] = [
+    (
+        "garbage_collector_options.manifest_options.interval",
+        "garbage_collector_options.manifest_options.min_age",
+    ),
+    (
+        "garbage_collector_options.wal_options.interval",
+        "garbage_collector_options.wal_options.min_age",
+    ),
+    (
+        "garbage_collector_options.compacted_options.interval",
+        "garbage_collector_options.compacted_options.min_age",
+    ),
+];
+
+/// Garbage collection schedule extracted from a Java `GarbageCollectorConfig`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct GarbageCollectorSettings {
+    /// How often the collector runs
+    pub(crate) interval: Duration,
+    /// Age an unreferenced object must reach before it is deleted
+    pub(crate) min_age: Duration,
+}
+
+impl GarbageCollectorSettings {
+    /// Adds the SlateDB settings applying the schedule to every kind of object.
+    pub(crate) fn overlay(&self, overlay: &mut SettingsOverlay) {
+        for (interval, min_age) in GC_KEYS {
+            overlay.set(interval, format!("{}ms", self.interval.as_millis()));
+            overlay.set(min_age, format!("{}ms", self.min_age.as_millis()));
+        }
+    }
+}
+
+/// Write path settings extracted from a Java `WriteBufferConfig`; None keeps
+/// the settings file's value.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) struct WriteBufferSettings {
+    /// Bytes of unflushed writes before writers block
+    pub(crate) max_unflushed_bytes: Option<u64>,
+    /// Size at which the memtable is flushed as an L0 SST
+    pub(crate) l0_sst_size_bytes: Option<u64>,
+    /// How often buffered writes are flushed to the WAL
+    pub(crate) flush_interval: Option<Duration>,
+    /// Whether writes go through the WAL
+    pub(crate) wal_enabled: Option<bool>,
+}
+
+impl WriteBufferSettings {
+    /// Adds the SlateDB settings that are set.
+    pub(crate) fn overlay(&self, overlay: &mut SettingsOverlay) {
+        if let Some(bytes) = self.max_unflushed_bytes {
+            overlay.set("max_unflushed_bytes", bytes as i64);
+        }
+        if let Some(bytes) = self.l0_sst_size_bytes {
+            overlay.set("l0_sst_size_bytes", bytes as i64);
+        }
+        if let Some(interval) = self.flush_interval {
+            overlay.set("flush_interval", format!("{}ms", interval.as_millis()));
+        }
+        if let Some(enabled) = self.wal_enabled {
+            overlay.set("wal_enabled", enabled);
+        }
+    }
+}
+
+fn load_toml(path: &str) -> Result<Table, String> {
+    if Path::new(path).extension().and_then(|e| e.to_str()) != Some("toml") {
+        return Err(format!(
+            "Typed SlateDB settings (diskCache, garbageCollector, writeBuffer) can only \
+             be written over a TOML settings file, got {}; convert it to TOML or move \
+             the typed settings into it",
+            path
+        ));
+    }
+    let text = fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read settings file {}: {}", path, e))?;
+    text.parse()
+        .map_err(|e| format!("Failed to parse settings file {}: {}", path, e))
+}
+
+/// Copy of a settings file with an overlay applied, deleted when dropped.
+#[derive(Debug)]
+pub(crate) struct SettingsFile {
+    path: PathBuf,
+    text: String,
+}
+
+/// Copies written by this process, and the private directory holding them.
+#[derive(Default)]
+struct Copies {
+    directory: Option<PathBuf>,
+    files: HashMap<String, Weak<SettingsFile>>,
+    next_file: u64,
+}
+
+static COPIES: Mutex<Option<Copies>> = Mutex::new(None);
+
+fn copies() -> MutexGuard<'static, Option<Copies>> {
+    COPIES.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Returns a copy holding the settings, reusing a live copy whose file still
+/// holds them.
+fn write_settings(table: &Table) -> Result<Arc<SettingsFile>, String> {
+    let text = toml::to_string(table).map_err(|e| format!("Failed to write settings: {}", e))?;
+    let mut guard = copies();
+    let copies = guard.get_or_insert_with(Copies::default);
+    if let Some(file) = copies.files.get(&text).and_then(Weak::upgrade) {
+        if fs::read_to_string(&file.path).is_ok_and(|on_disk| on_disk == text) {
+            return Ok(file);
+        }
+    }
+    let directory = match &copies.directory {
+        Some(directory) => directory.clone(),
+        None => {
+            let directory = create_private_directory()
+                .map_err(|e| format!("Failed to create settings directory: {}", e))?;
+            copies.directory = Some(directory.clone());
+            directory
+        }
+    };
+    let path = directory.join(format!("settings-{}.toml", copies.next_file));
+    copies.next_file += 1;
+    create_private_file(&path, &text)
+        .map_err(|e| format!("Failed to write settings file {}: {}", path.display(), e))?;
+    let file = Arc::new(SettingsFile {
+        path,
+        text: text.clone(),
+    });
+    copies.files.insert(text, Arc::downgrade(&file));
+    Ok(file)
+}
+
+impl Drop for SettingsFile {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+        let mut guard = copies();
+        let Some(copies) = guard.as_mut() else {
+            return;
+        };
+        // A newer copy of the same settings may have replaced this one
+        if copies
+            .files
+            .get(&self.text)
+            .is_some_and(|file| file.strong_count() == 0)
+        {
+            copies.files.remove(&self.text);
+        }
+        if copies.files.values().all(|file| file.strong_count() == 0) {
+            if let Some(directory) = copies.directory.take() {
+                let _ = fs::remove_dir(directory);
+            }
+            copies.files.clear();
+        }
+    }
+}
+
+/// Creates a directory with a random name in the temporary directory,
+/// accessible only to the process's user.
+fn create_private_directory() -> io::Result<PathBuf> {
+    let mut builder = DirBuilder::new();
+    #[cfg(unix)]
+    builder.mode(0o700);
+    loop {
+        let suffix = RandomState::new().hash_one(std::process::id());
+        let directory = std::env::temp_dir().join(format!(
+            "opendata-slatedb-{}-{:016x}",
+            std::process::id(),
+            suffix
+        ));
+        match builder.create(&directory) {
+            Ok(()) => return Ok(directory),
+            Err(e) if e.kind() == io::ErrorKind::AlreadyExists => continue,
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+/// Writes a file that must not exist yet, readable only by the process's user.
+fn create_private_file(path: &Path, text: &str) -> io::Result<()> {
+    let mut options = OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    options.mode(0o600);
+    options.open(path)?.write_all(text.as_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(unix)]
+    use std::os::unix::fs::PermissionsExt;
+
+    use super::*;
+
+    #[test]
+    fn should_set_nested_keys_over_base_settings() {
+        // given
+        let mut table: Table =
+            "flush_interval = \"100ms\"\n\n[object_store_cache_options]\npart_size_bytes = 4096\n"
+                .parse()
+                .unwrap();
+        let mut overlay = SettingsOverlay::default();
+        overlay.set("object_store_cache_options.root_folder", "/cache");
+        overlay.set("flush_interval", "10ms");
+
+        // when
+        overlay.apply_to(&mut table).unwrap();
+
+        // then
+        let cache = table["object_store_cache_options"].as_table().unwrap();
+        assert_eq!(cache["root_folder"].as_str(), Some("/cache"));
+        assert_eq!(cache["part_size_bytes"].as_integer(), Some(4096));
+        assert_eq!(table["flush_interval"].as_str(), Some("10ms"));
+    }
+
+    #[test]
+    fn should_schedule_every_kind_of_garbage() {
+        // given
+        let mut table: Table = "[garbage_collector_options.wal_options]\ninterval = \"1s\"\n"
+            .parse()
+            .unwrap();
+        let mut overlay = SettingsOverlay::default();
+        GarbageCollectorSettings {
+            interval: Duration::from_secs(60),
+            min_age: Duration::from_millis(1500),
+        }
+        .overlay(&mut overlay);
+
+        // when
+        overlay.apply_to(&mut table).unwrap();
+
+        // then
+        let gc = table["garbage_collector_options"].as_table().unwrap();
+        for directory in ["manifest_options", "wal_options", "compacted_options"] {
+            let options = gc[directory].as_table().unwrap();
+            assert_eq!(options["interval"].as_str(), Some("60000ms"));
+            assert_eq!(options["min_age"].as_str(), Some("1500ms"));
+        }
+    }
+
+    #[test]
+    fn should_set_only_configured_write_settings() {
+        // given
+        let mut table: Table = "max_unflushed_bytes = 1024\nl0_sst_size_bytes = 4096\n"
+            .parse()
+            .unwrap();
+        let mut overlay = SettingsOverlay::default();
+        WriteBufferSettings {
+            max_unflushed_bytes: Some(1 << 28),
+            flush_interval: Some(Duration::from_millis(5)),
+            wal_enabled: Some(false),
+            ..WriteBufferSettings::default()
+        }
+        .overlay(&mut overlay);
+
+        // when
+        overlay.apply_to(&mut table).unwrap();
+
+        // then
+        assert_eq!(table["max_unflushed_bytes"].as_integer(), Some(1 << 28));
+        assert_eq!(table["l0_sst_size_bytes"].as_integer(), Some(4096));
+        assert_eq!(table["flush_interval"].as_str(), Some("5ms"));
+        assert_eq!(table["wal_enabled"].as_bool(), Some(false));
+    }
+
+    #[test]
+    fn should_share_copies_until_last_holder_drops_them() {
+        // given
+        let table: Table = "l0_sst_size_bytes = 12345\n".parse().unwrap();
+        let first = write_settings(&table).unwrap();
+
+        // when
+        let second = write_settings(&table).unwrap();
+        fs::write(&first.path, "l0_sst_size_bytes = 1\n").unwrap();
+        let rewritten = write_settings(&table).unwrap();
+
+        // then
+        assert_eq!(first.path, second.path);
+        assert_ne!(rewritten.path, first.path);
+        assert_eq!(fs::read_to_string(&rewritten.path).unwrap(), rewritten.text);
+        let path = rewritten.path.clone();
+        drop(rewritten);
+        assert!(!path.exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn should_write_copies_readable_only_by_owner() {
+        // given
+        let table: Table = "l0_sst_size_bytes = 54321\n".parse().unwrap();
+
+        // when
+        let file = write_settings(&table).unwrap();
+
+        // then
+        let mode = fs::metadata(&file.path).unwrap().permissions().mode();
+        let directory = fs::metadata(file.path.parent().unwrap()).unwrap();
+        assert_eq!(mode & 0o777, 0o600);
+        assert_eq!(directory.permissions().mode() & 0o777, 0o700);
+    }
+
+    #[test]
+    fn should_reject_key_below_non_table_setting() {
+        // given
+        let mut table: Table = "object_store_cache_options = 1\n".parse().unwrap();
+        let mut overlay = SettingsOverlay::default();
+        overlay.set("object_store_cache_options.root_folder", "/cache");
+
+        // when
+        let result = overlay.apply_to(&mut table);
+
+        // then
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,226 @@
+//! Receive times of appended values, and the clock skew they reveal.
+//!
+//! OMB measures end-to-end latency as the reader's clock minus the
+//! producer's timestamp, which across hosts includes the drift between the
+//! two clocks. With receive timestamps configured, a handle also records in
+//! each value the time its clock read when the batch reached the native
+//! layer, in an extended envelope of its own version:
+//!
+//! ```text
+//! ┌────────────┬─────────────┬───────────────────┬──────────────────┬─────────┐
+//! │ magic (7B) │ version (1B)│ timestamp_ms (8B) │ received_ms (8B) │ payload │
+//! └────────────┴─────────────┴───────────────────┴──────────────────┴─────────┘
+//! ```
+//!
+//! As for chunks, the timestamp is stored as is, so plain values of any
+//! timestamp read as before, short of the few `ENVELOPE_MAGIC` describes.
+//! Every reader of the envelope strips its fields, so scans keep returning the
+//! producer timestamp and the payload, and readers that do not record
+//! receive times read such values too.
+//!
+//! Scans sample the skew of each entry carrying a receive time, its receive
+//! time minus its producer timestamp, and report its count, minimum, maximum
+//! and mean as `clock_skew.*` stats. Samples include the time from the
+//! producer to the native layer, so on one host they stay near 0, and across
+//! hosts their floor estimates the producer's clock offset. Values split
+//! into chunks, generated by synthetic appends, appended through an
+//! `AppendRing` or the C ABI, or imported, carry no receive time.
+
+use std::sync::atomic::{AtomicI64, AtomicU64, Ordering};
+
+use log::LogEntry;
+
+use crate::metrics::Stats;
+use crate::{envelope_prefix, envelope_version, ENVELOPE_PREFIX_SIZE, TIMESTAMP_HEADER_SIZE};
+
+/// Version of the extended envelope of a value carrying a receive time.
+const RECEIVED_VERSION: u8 = 2;
+
+/// Offset of the timestamp header of a value carrying a receive time.
+const RECEIVED_TIMESTAMP_OFFSET: usize = ENVELOPE_PREFIX_SIZE;
+
+/// Offset of the receive time of a value carrying one.
+const RECEIVED_MS_OFFSET: usize = RECEIVED_TIMESTAMP_OFFSET + TIMESTAMP_HEADER_SIZE;
+
+/// Size of the envelope of a value carrying a receive time.
+const RECEIVED_HEADER_SIZE: usize = RECEIVED_MS_OFFSET + 8;
+
+/// Returns the size of the envelope of a value, with a receive time if given.
+pub(crate) fn header_size(received_ms: Option<i64>) -> usize {
+    match received_ms {
+        Some(_) => RECEIVED_HEADER_SIZE,
+        None => TIMESTAMP_HEADER_SIZE,
+    }
+}
+
+/// Writes the envelope of a value into the start of `buffer`, which must
+/// hold `header_size(received_ms)` bytes.
+pub(crate) fn write_header(buffer: &mut [u8], timestamp_ms: i64, received_ms: Option<i64>) {
+    match received_ms {
+        Some(received_ms) => {
+            buffer[..ENVELOPE_PREFIX_SIZE].copy_from_slice(&envelope_prefix(RECEIVED_VERSION));
+            buffer[RECEIVED_TIMESTAMP_OFFSET..RECEIVED_MS_OFFSET]
+                .copy_from_slice(&timestamp_ms.to_be_bytes());
+            buffer[RECEIVED_MS_OFFSET..RECEIVED_HEADER_SIZE]
+                .copy_from_slice(&received_ms.to_be_bytes());
+        }
+        None => buffer[..TIMESTAMP_HEADER_SIZE].copy_from_slice(&timestamp_ms.to_be_bytes()),
+    }
+}
+
+/// Returns the producer timestamp and the payload of a stored value
+/// carrying a receive time, or None for other values.
+pub(crate) fn split_received(value: &[u8]) -> Option<(i64, &[u8])> {
+    if value.len() < RECEIVED_HEADER_SIZE || envelope_version(value) != Some(RECEIVED_VERSION) {
+        return None;
+    }
+    let bytes = value[RECEIVED_TIMESTAMP_OFFSET..RECEIVED_MS_OFFSET]
+        .try_into()
+        .expect("8 bytes");
+    Some((i64::from_be_bytes(bytes), &value[RECEIVED_HEADER_SIZE..]))
+}
+
+/// Returns the receive time of a stored value, if it carries one; chunks
+/// never do.
+pub(crate) fn received_ms(value: &[u8]) -> Option<i64> {
+    split_received(value)?;
+    let bytes = value[RECEIVED_MS_OFFSET..RECEIVED_HEADER_SIZE]
+        .try_into()
+        .expect("8 bytes");
+    Some(i64::from_be_bytes(bytes))
+}
+
+/// Skew of the scanned entries carrying a receive time.
+pub(crate) struct ClockSkew {
+    samples: AtomicU64,
+    sum_ms: AtomicI64,
+    min_ms: AtomicI64,
+    max_ms: AtomicI64,
+}
+
+impl Default for ClockSkew {
+    fn default() -> Self {
+        Self {
+            samples: AtomicU64::new(0),
+            sum_ms: AtomicI64::new(0),
+            min_ms: AtomicI64::new(i64::MAX),
+            max_ms: AtomicI64::new(i64::MIN),
+        }
+    }
+}
+
+impl ClockSkew {
+    /// Samples the skew of scanned entries.
+    pub(crate) fn record(&self, entries: &[LogEntry]) {
+        for entry in entries {
+            let Some(received_ms) = received_ms(&entry.value) else {
+                continue;
+            };
+            let (timestamp_ms, _) = crate::extract_timestamp_and_payload(&entry.value);
+            let skew_ms = received_ms.saturating_sub(timestamp_ms);
+            self.samples.fetch_add(1, Ordering::Relaxed);
+            self.sum_ms.fetch_add(skew_ms, Ordering::Relaxed);
+            self.min_ms.fetch_min(skew_ms, Ordering::Relaxed);
+            self.max_ms.fetch_max(skew_ms, Ordering::Relaxed);
+        }
+    }
+
+    /// Appends the sample count and the minimum, maximum and mean skew; the
+    /// skews are 0 before any sample.
+    pub(crate) fn append_stats(&self, stats: &mut Stats) {
+        let samples = self.samples.load(Ordering::Relaxed);
+        let (min_ms, max_ms, mean_ms) = match samples {
+            0 => (0, 0, 0),
+            _ => (
+                self.min_ms.load(Ordering::Relaxed),
+                self.max_ms.load(Ordering::Relaxed),
+                self.sum_ms.load(Ordering::Relaxed) / samples as i64,
+            ),
+        };
+        stats.push(("clock_skew.samples".to_string(), samples as i64));
+        stats.push(("clock_skew.min_ms".to_string(), min_ms));
+        stats.push(("clock_skew.max_ms".to_string(), max_ms));
+        stats.push(("clock_skew.mean_ms".to_string(), mean_ms));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+
+    use super::*;
+
+    fn entry(timestamp_ms: i64, received_ms: Option<i64>) -> LogEntry {
+        let mut value = vec![0; header_size(received_ms) + 3];
+        write_header(&mut value, timestamp_ms, received_ms);
+        value[header_size(received_ms)..].copy_from_slice(b"abc");
+        LogEntry {
+            key: Bytes::from_static(b"key"),
+            sequence: 0,
+            value: Bytes::from(value),
+        }
+    }
+
+    #[test]
+    fn should_strip_receive_time_from_envelope() {
+        // given
+        let entry = entry(1_000, Some(1_250));
+
+        // when
+        let (timestamp_ms, payload) = crate::extract_timestamp_and_payload(&entry.value);
+
+        // then
+        assert_eq!(timestamp_ms, 1_000);
+        assert_eq!(payload, b"abc");
+        assert_eq!(received_ms(&entry.value), Some(1_250));
+    }
+
+    #[test]
+    fn should_read_plain_values_with_high_timestamp_bits_as_plain() {
+        // given
+        let timestamps = [1 << 62, i64::MAX, -1, i64::MIN];
+        let payload = b"long enough for a receive time";
+
+        // when
+        let plain: Vec<_> = timestamps
+            .iter()
+            .map(|&t| crate::create_timestamped_value(t, payload))
+            .collect();
+
+        // then
+        for (value, &timestamp_ms) in plain.iter().zip(&timestamps) {
+            assert_eq!(received_ms(value), None);
+            assert_eq!(
+                crate::extract_timestamp_and_payload(value),
+                (timestamp_ms, payload.as_slice())
+            );
+        }
+    }
+
+    #[test]
+    fn should_sample_skew_of_entries_with_receive_time() {
+        // given
+        let skew = ClockSkew::default();
+        let entries = [
+            entry(1_000, Some(1_250)),
+            entry(1_000, None),
+            entry(2_000, Some(1_950)),
+        ];
+
+        // when
+        skew.record(&entries);
+        let mut stats = Vec::new();
+        skew.append_stats(&mut stats);
+
+        // then
+        assert_eq!(
+            stats,
+            vec![
+                ("clock_skew.samples".to_string(), 2),
+                ("clock_skew.min_ms".to_string(), -50),
+                ("clock_skew.max_ms".to_string(), 250),
+                ("clock_skew.mean_ms".to_string(), 100),
+            ]
+        );
+    }
+}
@@ -0,0 +1,232 @@
+//! Logging of appends and scans slower than a threshold.
+//!
+//! With a slow-operation threshold configured, every append and scan of the
+//! handle whose storage call takes at least that long is logged as a warning
+//! through the SLF4J bridge, naming the key and the sequences involved, so a
+//! latency outlier in the stats can be traced back to the call that caused
+//! it. Durations are measured the same way as the latency histograms.
+//! Lines start with the handle's tags, if any, and end with the traceparent
+//! of the call, if it passed one.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use bytes::Bytes;
+use log::{LogEntry, Record};
+
+use crate::logging;
+use crate::metrics::Stats;
+use crate::tags::Tags;
+use crate::tracecontext;
+
+/// SLF4J logger slow operations are logged under.
+const TARGET: &str = "dev.opendata.SlowOperations";
+
+/// Keys longer than this are truncated in log lines.
+const MAX_KEY_BYTES: usize = 64;
+
+/// What a slow append log line describes, captured before the records move.
+pub(crate) struct AppendSummary {
+    key: Bytes,
+    mixed_keys: bool,
+    records: usize,
+}
+
+/// Logs appends and scans exceeding a threshold.
+pub(crate) struct SlowLog {
+    /// None when slow operations are not logged
+    threshold: Option<Duration>,
+    logged: AtomicU64,
+    tags: Arc<Tags>,
+}
+
+impl SlowLog {
+    /// Creates a slow log starting lines with `tags`; a zero threshold
+    /// disables it.
+    pub(crate) fn new(threshold: Duration, tags: Arc<Tags>) -> Self {
+        Self {
+            threshold: (!threshold.is_zero()).then_some(threshold),
+            logged: AtomicU64::new(0),
+            tags,
+        }
+    }
+
+    /// Captures what an append log line needs, or None when disabled.
+    pub(crate) fn summarize(&self, records: &[Record]) -> Option<AppendSummary> {
+        self.threshold?;
+        let key = records.first()?.key.clone();
+        Some(AppendSummary {
+            mixed_keys: records.iter().any(|record| record.key != key),
+            key,
+            records: records.len(),
+        })
+    }
+
+    /// Logs an append that took at least the threshold.
+    ///
+    /// `start_sequence` is None if the append failed.
+    pub(crate) fn append(
+        &self,
+        elapsed: Duration,
+        summary: Option<AppendSummary>,
+        start_sequence: Option<u64>,
+    ) {
+        let Some(summary) = summary else {
+            return;
+        };
+        if !self.exceeded(elapsed) {
+            return;
+        }
+        let keys = if summary.mixed_keys {
+            format!("keys starting with {}", describe_key(&summary.key))
+        } else {
+            format!("key {}", describe_key(&summary.key))
+        };
+        let outcome = match start_sequence {
+            Some(start) => format!(
+                "sequences {}..{}",
+                start,
+                start + summary.records as u64 - 1
+            ),
+            None => "failed".to_string(),
+        };
+        self.log(format!(
+            "Slow append of {} records to {} ({}) took {} ms",
+            summary.records,
+            keys,
+            outcome,
+            elapsed.as_millis()
+        ));
+    }
+
+    /// Logs a scan that took at least the threshold.
+    ///
+    /// `entries` is None if the scan failed.
+    pub(crate) fn scan(
+        &self,
+        elapsed: Duration,
+        key: &[u8],
+        start_sequence: u64,
+        entries: Option<&[LogEntry]>,
+    ) {
+        if !self.exceeded(elapsed) {
+            return;
+        }
+        let outcome = match entries {
+            Some([]) => "no entries".to_string(),
+            Some(entries) => format!(
+                "{} entries, sequences {}..{}",
+                entries.len(),
+                entries[0].sequence,
+                entries[entries.len() - 1].sequence
+            ),
+            None => "failed".to_string(),
+        };
+        self.log(format!(
+            "Slow scan of key {} from sequence {} ({}) took {} ms",
+            describe_key(key),
+            start_sequence,
+            outcome,
+            elapsed.as_millis()
+        ));
+    }
+
+    /// Appends the number of operations logged, if enabled.
+    pub(crate) fn append_stats(&self, stats: &mut Stats) {
+        if self.threshold.is_some() {
+            stats.push((
+                "slow_log.logged".to_string(),
+                self.logged.load(Ordering::Relaxed) as i64,
+            ));
+        }
+    }
+
+    fn exceeded(&self, elapsed: Duration) -> bool {
+        self.threshold.is_some_and(|threshold| elapsed >= threshold)
+    }
+
+    fn log(&self, mut message: String) {
+        self.logged.fetch_add(1, Ordering::Relaxed);
+        if let Some(traceparent) = tracecontext::current() {
+            message.push_str(&format!(" (traceparent {})", traceparent));
+        }
+        logging::warn(TARGET, &self.tags.log_line(&message));
+    }
+}
+
+/// Quotes a key for a log line, escaping non-printable bytes.
+fn describe_key(key: &[u8]) -> String {
+    let shown = &key[..key.len().min(MAX_KEY_BYTES)];
+    let mut described = format!("'{}'", shown.escape_ascii());
+    if key.len() > MAX_KEY_BYTES {
+        described.push_str(&format!(" ({} bytes)", key.len()));
+    }
+    described
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(key: &'static [u8]) -> Record {
+        Record {
+            key: Bytes::from_static(key),
+            value: Bytes::new(),
+        }
+    }
+
+    #[test]
+    fn should_log_only_operations_reaching_threshold() {
+        // given
+        let slow_log = SlowLog::new(Duration::from_millis(100), Arc::default());
+        let records = [record(b"orders"), record(b"orders")];
+
+        // when
+        slow_log.append(
+            Duration::from_millis(99),
+            slow_log.summarize(&records),
+            Some(10),
+        );
+        slow_log.append(
+            Duration::from_millis(100),
+            slow_log.summarize(&records),
+            Some(12),
+        );
+        slow_log.scan(Duration::from_millis(250), b"orders", 10, None);
+
+        // then
+        let mut stats = Stats::new();
+        slow_log.append_stats(&mut stats);
+        assert_eq!(stats, vec![("slow_log.logged".to_string(), 2)]);
+    }
+
+    #[test]
+    fn should_not_summarize_when_disabled() {
+        // given
+        let slow_log = SlowLog::new(Duration::ZERO, Arc::default());
+
+        // when
+        let summary = slow_log.summarize(&[record(b"orders")]);
+
+        // then
+        assert!(summary.is_none());
+        let mut stats = Stats::new();
+        slow_log.append_stats(&mut stats);
+        assert!(stats.is_empty());
+    }
+
+    #[test]
+    fn should_escape_and_truncate_keys() {
+        // given
+        let long_key = [b'k'; MAX_KEY_BYTES + 1];
+
+        // when
+        let short = describe_key(b"a\nb");
+        let long = describe_key(&long_key);
+
+        // then
+        assert_eq!(short, "'a\\nb'");
+        assert!(long.ends_with(&format!("' ({} bytes)", MAX_KEY_BYTES + 1)));
+    }
+}
@@ -0,0 +1,99 @@
+//! Snapshot-isolated reader sessions.
+//!
+//! Verifying several streams after a run compares scans of different keys,
+//! which only agree if they observe the same cut of the log; a reader's view
+//! moves on at its refresh interval, between one scan and the next. A
+//! snapshot opens a second reader on the same storage whose view is never
+//! refreshed, so every scan through it sees the log as of the moment the
+//! snapshot began, across keys, until the snapshot ends.
+//!
+//! Each snapshot holds its own reader, with the memory and storage reads of
+//! loading a manifest. Snapshot scans go to storage only: the read-ahead and
+//! any attached tail cache of the reader hold entries past the cut.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::time::Duration;
+
+use log::ReaderConfig;
+
+/// Refresh interval of snapshot readers, so long that their view never
+/// moves while a session lasts.
+const PINNED_REFRESH_INTERVAL: Duration = Duration::from_secs(365 * 24 * 60 * 60);
+
+/// Returns the config of a reader pinned to the view it opens with.
+pub(crate) fn pinned(config: &ReaderConfig) -> ReaderConfig {
+    let mut config = config.clone();
+    config.refresh_interval = PINNED_REFRESH_INTERVAL;
+    config
+}
+
+/// Open snapshots of a reader handle, by id.
+pub(crate) struct Snapshots<R> {
+    next_id: AtomicU64,
+    open: Mutex<HashMap<u64, Arc<R>>>,
+}
+
+impl<R> Default for Snapshots<R> {
+    fn default() -> Self {
+        Self {
+            // Ids start at 1 so Java can treat 0 as no snapshot
+            next_id: AtomicU64::new(1),
+            open: Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+impl<R> Snapshots<R> {
+    /// Registers the reader of a new snapshot, returning its id.
+    pub(crate) fn begin(&self, reader: R) -> u64 {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        self.lock().insert(id, Arc::new(reader));
+        id
+    }
+
+    /// Returns the reader of an open snapshot.
+    pub(crate) fn get(&self, id: u64) -> Option<Arc<R>> {
+        self.lock().get(&id).cloned()
+    }
+
+    /// Releases a snapshot, returning whether it was open. Scans in flight
+    /// keep its reader until they finish.
+    pub(crate) fn end(&self, id: u64) -> bool {
+        self.lock().remove(&id).is_some()
+    }
+
+    /// Returns the number of open snapshots.
+    pub(crate) fn count(&self) -> usize {
+        self.lock().len()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, HashMap<u64, Arc<R>>> {
+        self.open.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_release_snapshot_once() {
+        // given
+        let snapshots = Snapshots::default();
+        let first = snapshots.begin("first");
+        let second = snapshots.begin("second");
+
+        // when
+        let ended = snapshots.end(first);
+        let ended_again = snapshots.end(first);
+
+        // then
+        assert!(ended);
+        assert!(!ended_again);
+        assert!(snapshots.get(first).is_none());
+        assert_eq!(snapshots.get(second).as_deref(), Some(&"second"));
+        assert_eq!(snapshots.count(), 1);
+    }
+}
@@ -0,0 +1,333 @@
+//! Export of a handle's stats to a StatsD or DogStatsD agent.
+//!
+//! Deployments without a Prometheus scraper often run a StatsD agent on
+//! each host. With a StatsD address configured, a thread per handle sends
+//! the handle's stats every interval as UDP datagrams, along with summaries
+//! of its append and scan latency histograms, so the native counters reach
+//! the same dashboards as the rest of the application without any Java code
+//! polling them.
+//!
+//! Every value is sent as a gauge. The native counters are totals since the
+//! handle opened or since the last warmup mark, and the latency summaries
+//! are already aggregated natively, so a StatsD counter or timer would
+//! aggregate them a second time. Negative values are sent as a zero first,
+//! as StatsD reads a signed gauge value as a change to the previous one.
+//!
+//! Names are prefixed with the configured prefix. DogStatsD agents receive
+//! the handle's tags as tags of each line; for plain StatsD, which has no
+//! tags, their values are inserted after the prefix instead, so topics of a
+//! `LogDbManager` report under separate names. Sending never blocks calls
+//! of the handle: the thread takes a stats snapshot like `nativeStats` and
+//! counts failed sends rather than retrying them.
+
+use std::io;
+use std::net::{SocketAddr, ToSocketAddrs, UdpSocket};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use crate::metrics::Stats;
+use crate::tags::Tags;
+
+/// SLF4J logger failures to start the export are logged under.
+pub(crate) const TARGET: &str = "dev.opendata.StatsD";
+
+/// Largest datagram sent, fitting the payload of one Ethernet frame.
+const MAX_DATAGRAM_BYTES: usize = 1432;
+
+/// Where and how often to send a handle's stats.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct StatsdSettings {
+    pub(crate) host: String,
+    pub(crate) port: u16,
+    pub(crate) interval: Duration,
+    /// Prepended to every name, followed by a dot unless empty
+    pub(crate) prefix: String,
+    /// Whether tags are sent in the DogStatsD format
+    pub(crate) dogstatsd: bool,
+}
+
+/// StatsD export of one handle.
+pub(crate) struct Statsd {
+    sink: Arc<Sink>,
+    interval: Duration,
+    exporter: Mutex<Option<Exporter>>,
+}
+
+/// Socket sending to the agent, and the names of the lines sent.
+struct Sink {
+    socket: UdpSocket,
+    /// Name prefix, with plain StatsD also the handle's tag values
+    prefix: String,
+    /// DogStatsD tag suffix of each line, empty for plain StatsD
+    tag_suffix: String,
+    datagrams: AtomicU64,
+    send_errors: AtomicU64,
+}
+
+/// Thread sending the stats every interval.
+struct Exporter {
+    stop: mpsc::Sender<()>,
+    thread: JoinHandle<()>,
+}
+
+impl Statsd {
+    /// Resolves the agent's address and binds the socket sending to it.
+    ///
+    /// Nothing is sent until the export is started.
+    pub(crate) fn connect(settings: &StatsdSettings, tags: &Tags) -> io::Result<Self> {
+        let address = (settings.host.as_str(), settings.port)
+            .to_socket_addrs()?
+            .next()
+            .ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::NotFound,
+                    format!("StatsD host {} has no address", settings.host),
+                )
+            })?;
+        let local: SocketAddr = if address.is_ipv4() {
+            ([0, 0, 0, 0], 0).into()
+        } else {
+            ([0u16; 8], 0).into()
+        };
+        let socket = UdpSocket::bind(local)?;
+        socket.connect(address)?;
+        let (prefix, tag_suffix) = render_names(settings, tags);
+        Ok(Self {
+            sink: Arc::new(Sink {
+                socket,
+                prefix,
+                tag_suffix,
+                datagrams: AtomicU64::new(0),
+                send_errors: AtomicU64::new(0),
+            }),
+            interval: settings.interval,
+            exporter: Mutex::new(None),
+        })
+    }
+
+    /// Starts the thread sending the snapshots `stats` takes every interval.
+    ///
+    /// `stats` is called on that thread until `stop` returns.
+    pub(crate) fn start<F>(&self, stats: F) -> io::Result<()>
+    where
+        F: Fn() -> Stats + Send + 'static,
+    {
+        let (stop, stopped) = mpsc::channel();
+        let interval = self.interval;
+        let sink = Arc::clone(&self.sink);
+        let thread = thread::Builder::new()
+            .name("opendata-statsd".to_string())
+            .spawn(move || loop {
+                match stopped.recv_timeout(interval) {
+                    Err(RecvTimeoutError::Timeout) => sink.send(&stats()),
+                    // Sender dropped or stop requested
+                    _ => return,
+                }
+            })?;
+        *self.lock() = Some(Exporter { stop, thread });
+        Ok(())
+    }
+
+    /// Stops the export, waiting for a send in progress.
+    pub(crate) fn stop(&self) {
+        let exporter = self.lock().take();
+        if let Some(Exporter { stop, thread }) = exporter {
+            drop(stop);
+            let _ = thread.join();
+        }
+    }
+
+    /// Appends the datagrams sent and the sends that failed.
+    pub(crate) fn append_stats(&self, stats: &mut Stats) {
+        stats.push((
+            "statsd.datagrams".to_string(),
+            self.sink.datagrams.load(Ordering::Relaxed) as i64,
+        ));
+        stats.push((
+            "statsd.send_errors".to_string(),
+            self.sink.send_errors.load(Ordering::Relaxed) as i64,
+        ));
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Option<Exporter>> {
+        self.exporter.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl Sink {
+    fn send(&self, stats: &Stats) {
+        for datagram in datagrams(stats, &self.prefix, &self.tag_suffix) {
+            match self.socket.send(datagram.as_bytes()) {
+                Ok(_) => self.datagrams.fetch_add(1, Ordering::Relaxed),
+                Err(_) => self.send_errors.fetch_add(1, Ordering::Relaxed),
+            };
+        }
+    }
+}
+
+/// Returns the name prefix and the tag suffix of every line.
+fn render_names(settings: &StatsdSettings, tags: &Tags) -> (String, String) {
+    let mut prefix: Vec<String> = Vec::new();
+    if !settings.prefix.is_empty() {
+        prefix.push(sanitize(&settings.prefix, ":|@#,\n"));
+    }
+    let mut tag_suffix = String::new();
+    if settings.dogstatsd {
+        if !tags.pairs().is_empty() {
+            let rendered: Vec<String> = tags
+                .pairs()
+                .iter()
+                .map(|(name, value)| format!("{}:{}", name, sanitize(value, "|#,\n")))
+                .collect();
+            tag_suffix = format!("|#{}", rendered.join(","));
+        }
+    } else {
+        prefix.extend(
+            tags.pairs()
+                .iter()
+                .map(|(_, value)| sanitize(value, ":|@#,.\n")),
+        );
+    }
+    let mut prefix = prefix.join(".");
+    if !prefix.is_empty() {
+        prefix.push('.');
+    }
+    (prefix, tag_suffix)
+}
+
+/// Renders stats as gauge lines, packed into datagrams.
+fn datagrams(stats: &Stats, prefix: &str, tag_suffix: &str) -> Vec<String> {
+    let mut datagrams = Vec::new();
+    let mut current = String::new();
+    for (name, value) in stats {
+        let name = sanitize(name, ":|@#,\n");
+        let mut lines = Vec::with_capacity(2);
+        if *value < 0 {
+            lines.push(format!("{}{}:0|g{}", prefix, name, tag_suffix));
+        }
+        lines.push(format!("{}{}:{}|g{}", prefix, name, value, tag_suffix));
+        for line in lines {
+            if !current.is_empty() && current.len() + 1 + line.len() > MAX_DATAGRAM_BYTES {
+                datagrams.push(std::mem::take(&mut current));
+            }
+            if !current.is_empty() {
+                current.push('\n');
+            }
+            current.push_str(&line);
+        }
+    }
+    if !current.is_empty() {
+        datagrams.push(current);
+    }
+    datagrams
+}
+
+/// Replaces characters StatsD reserves with underscores.
+fn sanitize(value: &str, reserved: &str) -> String {
+    value
+        .chars()
+        .map(|c| if reserved.contains(c) { '_' } else { c })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settings(dogstatsd: bool) -> StatsdSettings {
+        StatsdSettings {
+            host: "localhost".to_string(),
+            port: 8125,
+            interval: Duration::from_secs(10),
+            prefix: "opendata".to_string(),
+            dogstatsd,
+        }
+    }
+
+    #[test]
+    fn should_render_tags_for_each_flavour() {
+        // given
+        let tags = Tags::new(vec![
+            ("topic".to_string(), "orders".to_string()),
+            ("region".to_string(), "eu,west".to_string()),
+        ]);
+
+        // when
+        let plain = render_names(&settings(false), &tags);
+        let dogstatsd = render_names(&settings(true), &tags);
+
+        // then
+        assert_eq!(
+            plain,
+            ("opendata.eu_west.orders.".to_string(), String::new())
+        );
+        assert_eq!(
+            dogstatsd,
+            (
+                "opendata.".to_string(),
+                "|#region:eu_west,topic:orders".to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn should_send_negative_gauges_from_zero() {
+        // given
+        let stats = vec![
+            ("skew.min_nanos".to_string(), -5),
+            ("uptime_nanos".to_string(), 7),
+        ];
+
+        // when
+        let datagrams = datagrams(&stats, "p.", "|#topic:a");
+
+        // then
+        assert_eq!(
+            datagrams,
+            vec![
+                "p.skew.min_nanos:0|g|#topic:a\np.skew.min_nanos:-5|g|#topic:a\n\
+                 p.uptime_nanos:7|g|#topic:a"
+                    .to_string()
+            ]
+        );
+    }
+
+    #[test]
+    fn should_split_datagrams_at_size_limit() {
+        // given
+        let stats: Stats = (0..200).map(|i| (format!("stat_{}", i), i)).collect();
+
+        // when
+        let datagrams = datagrams(&stats, "opendata.", "");
+
+        // then
+        assert!(datagrams.len() > 1);
+        assert!(datagrams.iter().all(|d| d.len() <= MAX_DATAGRAM_BYTES));
+        let lines: usize = datagrams.iter().map(|d| d.lines().count()).sum();
+        assert_eq!(lines, 200);
+    }
+
+    #[test]
+    fn should_send_stats_to_agent() {
+        // given
+        let agent = UdpSocket::bind("127.0.0.1:0").unwrap();
+        let mut settings = settings(false);
+        settings.port = agent.local_addr().unwrap().port();
+        settings.host = "127.0.0.1".to_string();
+        let statsd = Statsd::connect(&settings, &Tags::default()).unwrap();
+
+        // when
+        statsd.sink.send(&vec![("records_in".to_string(), 3)]);
+
+        // then
+        let mut buf = [0u8; MAX_DATAGRAM_BYTES];
+        let len = agent.recv(&mut buf).unwrap();
+        assert_eq!(&buf[..len], b"opendata.records_in:3|g");
+        let mut stats = Stats::new();
+        statsd.append_stats(&mut stats);
+        assert_eq!(stats[0], ("statsd.datagrams".to_string(), 1));
+    }
+}
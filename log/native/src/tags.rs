@@ -0,0 +1,154 @@
+//! Labels identifying a handle in its stats, log lines and JFR events.
+//!
+//! A benchmark worker often opens several handles in one JVM, and several
+//! workers report into one dashboard. Tags configured on a handle, such as
+//! the topic, worker id or region, are attached to everything the handle
+//! reports, so its telemetry can be told apart once merged. They are
+//! rendered once, sorted by name, as `name="value"` pairs separated by
+//! commas, with backslashes, quotes and newlines in values escaped as in the
+//! Prometheus text format:
+//!
+//! - stat names carry them in braces, `slow_log.logged{topic="orders"}`;
+//! - log lines of the handle start with them in brackets;
+//! - JFR events carry them in their `tags` field.
+//!
+//! Without tags, names and lines are unchanged. Handles of a `LogDbManager`
+//! are tagged with their topic unless a `topic` tag is configured. Lines not
+//! tied to a handle, such as applied environment overrides, are not tagged.
+
+/// The tags of a handle, and their rendering.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct Tags {
+    /// Name and value of each tag, sorted by name
+    pairs: Vec<(String, String)>,
+    rendered: String,
+}
+
+impl Tags {
+    /// Creates tags from name/value pairs, in any order.
+    pub(crate) fn new(mut pairs: Vec<(String, String)>) -> Self {
+        pairs.sort();
+        pairs.dedup_by(|a, b| a.0 == b.0);
+        let rendered = pairs
+            .iter()
+            .map(|(name, value)| format!("{}=\"{}\"", name, escape(value)))
+            .collect::<Vec<_>>()
+            .join(",");
+        Self { pairs, rendered }
+    }
+
+    /// Returns these tags with `name` set to `value`, unless already set.
+    pub(crate) fn with_default(&self, name: &str, value: &str) -> Self {
+        if self.pairs.iter().any(|(n, _)| n == name) {
+            return self.clone();
+        }
+        let mut pairs = self.pairs.clone();
+        pairs.push((name.to_string(), value.to_string()));
+        Self::new(pairs)
+    }
+
+    /// Returns the name/value pairs, sorted by name.
+    pub(crate) fn pairs(&self) -> &[(String, String)] {
+        &self.pairs
+    }
+
+    /// Returns the rendered tags, empty without tags.
+    pub(crate) fn as_str(&self) -> &str {
+        &self.rendered
+    }
+
+    /// Returns a stat name with the tags appended.
+    pub(crate) fn stat_name(&self, name: String) -> String {
+        if self.rendered.is_empty() {
+            return name;
+        }
+        format!("{}{{{}}}", name, self.rendered)
+    }
+
+    /// Returns a log line with the tags prepended.
+    pub(crate) fn log_line(&self, message: &str) -> String {
+        if self.rendered.is_empty() {
+            return message.to_string();
+        }
+        format!("[{}] {}", self.rendered, message)
+    }
+}
+
+/// Whether `name` may name a tag: a letter or underscore, then letters,
+/// digits and underscores, as in Prometheus label names.
+pub(crate) fn is_valid_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+fn escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tags(pairs: &[(&str, &str)]) -> Tags {
+        Tags::new(
+            pairs
+                .iter()
+                .map(|(name, value)| (name.to_string(), value.to_string()))
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn should_render_tags_sorted_and_escaped() {
+        // given
+        let tags = tags(&[("workerId", "3"), ("region", "us \"east\"")]);
+
+        // when
+        let name = tags.stat_name("slow_log.logged".to_string());
+        let line = tags.log_line("Slow scan");
+
+        // then
+        assert_eq!(
+            name,
+            "slow_log.logged{region=\"us \\\"east\\\"\",workerId=\"3\"}"
+        );
+        assert_eq!(
+            line,
+            "[region=\"us \\\"east\\\"\",workerId=\"3\"] Slow scan"
+        );
+    }
+
+    #[test]
+    fn should_leave_names_and_lines_unchanged_without_tags() {
+        // given
+        let tags = Tags::default();
+
+        // when
+        let name = tags.stat_name("uptime_nanos".to_string());
+        let line = tags.log_line("Reopened LogDb");
+
+        // then
+        assert_eq!(name, "uptime_nanos");
+        assert_eq!(line, "Reopened LogDb");
+    }
+
+    #[test]
+    fn should_keep_configured_tag_over_default() {
+        // given
+        let configured = tags(&[("topic", "orders")]);
+
+        // when
+        let kept = configured.with_default("topic", "payments");
+        let added = Tags::default().with_default("topic", "payments");
+
+        // then
+        assert_eq!(kept.as_str(), "topic=\"orders\"");
+        assert_eq!(added.as_str(), "topic=\"payments\"");
+    }
+}
@@ -0,0 +1,381 @@
+//! In-memory cache of recently appended entries.
+//!
+//! A tail-following consumer in the same process as the writer mostly asks
+//! for entries it could have been handed at append time. With a tail cache
+//! configured, every append made through the handle also records its entries
+//! here, and scans of the handle, or of readers attached to it, starting at or
+//! after the oldest entry cached for a key are answered from memory, the way
+//! Kafka consumers at the tail are served from the page cache.
+//!
+//! A key's cached entries are only served while they are known to be
+//! complete: from the sequence the key was first appended at through this
+//! handle, or the one after the last entry evicted, every entry of the key is
+//! cached. Scans issued while an append of the key is in flight go to
+//! storage, since that append could still take a sequence below ones already
+//! cached, and an append that fails or is abandoned resets the key once its
+//! in-flight appends have finished. When the cache exceeds its byte budget,
+//! the oldest entries of the least recently used key are evicted first;
+//! the budget can be changed while the handle is open, evicting down to it.
+
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Mutex, PoisonError};
+
+use bytes::Bytes;
+use log::{LogEntry, Record};
+
+use crate::chunking;
+use crate::metrics::{self, Stats};
+
+#[derive(Default)]
+struct Tail {
+    /// Cached entries in sequence order
+    entries: VecDeque<LogEntry>,
+    /// Every entry of the key from this sequence on is cached
+    covered_from: Option<u64>,
+    /// Appends of the key in flight
+    pending: usize,
+    /// An append failed while others were in flight; reset once they finish
+    poisoned: bool,
+    bytes: usize,
+    /// Tick of the last append or scan of the key
+    used: u64,
+}
+
+#[derive(Default)]
+struct Inner {
+    tails: HashMap<Bytes, Tail>,
+    /// Keys by the tick they were last used at, oldest first
+    lru: BTreeMap<u64, Bytes>,
+    clock: u64,
+    bytes: usize,
+}
+
+impl Inner {
+    fn touch(&mut self, key: &Bytes) -> &mut Tail {
+        self.clock += 1;
+        let clock = self.clock;
+        let tail = self.tails.entry(key.clone()).or_default();
+        if tail.used != 0 {
+            self.lru.remove(&tail.used);
+        }
+        tail.used = clock;
+        self.lru.insert(clock, key.clone());
+        tail
+    }
+
+    /// Evicts the oldest entries of the least recently used keys until the
+    /// cache fits `max_bytes`.
+    fn evict(&mut self, max_bytes: usize) {
+        while self.bytes > max_bytes {
+            let Some((&used, key)) = self.lru.iter().next() else {
+                return;
+            };
+            let key = key.clone();
+            let Some(tail) = self.tails.get_mut(&key) else {
+                self.lru.remove(&used);
+                continue;
+            };
+            match tail.entries.pop_front() {
+                Some(entry) => {
+                    let size = entry_size(&entry);
+                    tail.bytes -= size;
+                    self.bytes -= size;
+                    tail.covered_from = Some(entry.sequence + 1);
+                }
+                None => {
+                    self.lru.remove(&used);
+                    if tail.pending == 0 {
+                        self.tails.remove(&key);
+                    } else {
+                        tail.used = 0;
+                    }
+                }
+            }
+        }
+    }
+
+    /// Forgets the cached entries of a key.
+    fn reset(&mut self, key: &Bytes) {
+        if let Some(tail) = self.tails.get_mut(key) {
+            self.bytes -= tail.bytes;
+            tail.bytes = 0;
+            tail.entries.clear();
+            tail.covered_from = None;
+            tail.poisoned = false;
+        }
+    }
+}
+
+/// Per-handle cache of recently appended entries.
+pub(crate) struct TailCache {
+    max_bytes: AtomicUsize,
+    inner: Mutex<Inner>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+/// An append of some keys in flight, registered with [`TailCache::begin`].
+///
+/// Dropping it without calling [`PendingAppend::complete`] treats the
+/// append as failed.
+pub(crate) struct PendingAppend<'a> {
+    cache: &'a TailCache,
+    keys: Vec<Bytes>,
+}
+
+impl TailCache {
+    pub(crate) fn new(max_bytes: usize) -> Self {
+        Self {
+            max_bytes: AtomicUsize::new(max_bytes),
+            inner: Mutex::new(Inner::default()),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+        }
+    }
+
+    /// Registers an append of `records` before it is issued to storage.
+    pub(crate) fn begin(&self, records: &[Record]) -> PendingAppend<'_> {
+        let mut keys: Vec<Bytes> = records.iter().map(|r| r.key.clone()).collect();
+        keys.sort_unstable();
+        keys.dedup();
+        let mut inner = self.lock();
+        for key in &keys {
+            inner.touch(key).pending += 1;
+        }
+        PendingAppend { cache: self, keys }
+    }
+
+    /// Returns up to `max_entries` cached entries of `key` from
+    /// `start_sequence`, or None if the cache cannot answer the scan.
+    pub(crate) fn scan(
+        &self,
+        key: &Bytes,
+        start_sequence: u64,
+        max_entries: usize,
+    ) -> Option<Vec<LogEntry>> {
+        let mut inner = self.lock();
+        let answerable = inner.tails.get(key).is_some_and(|tail| {
+            tail.pending == 0 && tail.covered_from.is_some_and(|c| start_sequence >= c)
+        });
+        if !answerable {
+            drop(inner);
+            self.misses.fetch_add(1, Ordering::Relaxed);
+            return None;
+        }
+        let tail = inner.touch(key);
+        let first = tail
+            .entries
+            .partition_point(|e| e.sequence < start_sequence);
+        let entries = chunking::assemble(tail.entries.range(first..).cloned(), max_entries);
+        drop(inner);
+        self.hits.fetch_add(1, Ordering::Relaxed);
+        Some(entries)
+    }
+
+    /// Returns the key and value bytes cached.
+    pub(crate) fn bytes(&self) -> usize {
+        self.lock().bytes
+    }
+
+    /// Changes the byte budget, evicting entries down to it.
+    pub(crate) fn set_max_bytes(&self, max_bytes: usize) {
+        self.max_bytes.store(max_bytes, Ordering::Relaxed);
+        self.lock().evict(max_bytes);
+    }
+
+    /// Appends the cached bytes, keys, and scans served from and missing the
+    /// cache, with the resulting hit rate.
+    pub(crate) fn append_stats(&self, stats: &mut Stats) {
+        let inner = self.lock();
+        stats.push(("tail_cache.bytes".to_string(), inner.bytes as i64));
+        stats.push(("tail_cache.keys".to_string(), inner.tails.len() as i64));
+        drop(inner);
+        let hits = self.hits.load(Ordering::Relaxed);
+        let misses = self.misses.load(Ordering::Relaxed);
+        stats.push(("tail_cache.hits".to_string(), hits as i64));
+        stats.push(("tail_cache.misses".to_string(), misses as i64));
+        stats.push((
+            "tail_cache.hit_rate_ppm".to_string(),
+            metrics::hit_rate_ppm(hits, misses),
+        ));
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Inner> {
+        self.inner.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl PendingAppend<'_> {
+    /// Caches the entries of an append that was assigned `start_sequence`.
+    pub(crate) fn complete(mut self, start_sequence: u64, records: Vec<Record>) {
+        let keys = std::mem::take(&mut self.keys);
+        let mut inner = self.cache.lock();
+        for (offset, record) in records.into_iter().enumerate() {
+            let entry = LogEntry {
+                key: record.key,
+                sequence: start_sequence + offset as u64,
+                value: record.value,
+            };
+            let size = entry_size(&entry);
+            let Some(tail) = inner.tails.get_mut(&entry.key) else {
+                continue;
+            };
+            if tail.poisoned {
+                continue;
+            }
+            tail.covered_from = Some(
+                tail.covered_from
+                    .map_or(entry.sequence, |c| c.min(entry.sequence)),
+            );
+            let at = tail
+                .entries
+                .partition_point(|e| e.sequence < entry.sequence);
+            tail.entries.insert(at, entry);
+            tail.bytes += size;
+            inner.bytes += size;
+        }
+        finish(&mut inner, &keys, false);
+        inner.evict(self.cache.max_bytes.load(Ordering::Relaxed));
+    }
+}
+
+impl Drop for PendingAppend<'_> {
+    fn drop(&mut self) {
+        if !self.keys.is_empty() {
+            let keys = std::mem::take(&mut self.keys);
+            finish(&mut self.cache.lock(), &keys, true);
+        }
+    }
+}
+
+/// Ends an append of `keys`, resetting keys whose appends failed once no
+/// other append of them is in flight.
+fn finish(inner: &mut Inner, keys: &[Bytes], failed: bool) {
+    for key in keys {
+        let Some(tail) = inner.tails.get_mut(key) else {
+            continue;
+        };
+        tail.pending -= 1;
+        tail.poisoned |= failed;
+        if tail.poisoned && tail.pending == 0 {
+            inner.reset(key);
+        }
+    }
+}
+
+fn entry_size(entry: &LogEntry) -> usize {
+    entry.key.len() + entry.value.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(key: &'static [u8], value: &'static [u8]) -> Record {
+        Record {
+            key: Bytes::from_static(key),
+            value: Bytes::from_static(value),
+        }
+    }
+
+    fn append(cache: &TailCache, start_sequence: u64, records: Vec<Record>) {
+        cache.begin(&records).complete(start_sequence, records);
+    }
+
+    fn sequences(entries: Option<Vec<LogEntry>>) -> Option<Vec<u64>> {
+        entries.map(|entries| entries.iter().map(|e| e.sequence).collect())
+    }
+
+    #[test]
+    fn should_serve_scans_from_first_cached_sequence() {
+        // given
+        let cache = TailCache::new(usize::MAX);
+        append(&cache, 10, vec![record(b"a", b"1"), record(b"b", b"2")]);
+        append(&cache, 12, vec![record(b"a", b"3")]);
+        let key = Bytes::from_static(b"a");
+
+        // when / then
+        assert_eq!(sequences(cache.scan(&key, 10, 10)), Some(vec![10, 12]));
+        assert_eq!(sequences(cache.scan(&key, 11, 1)), Some(vec![12]));
+        assert_eq!(sequences(cache.scan(&key, 13, 10)), Some(vec![]));
+        assert_eq!(sequences(cache.scan(&key, 9, 10)), None);
+        assert_eq!(
+            sequences(cache.scan(&Bytes::from_static(b"c"), 0, 10)),
+            None
+        );
+    }
+
+    #[test]
+    fn should_not_serve_key_with_append_in_flight() {
+        // given
+        let cache = TailCache::new(usize::MAX);
+        append(&cache, 0, vec![record(b"a", b"1")]);
+        let in_flight = vec![record(b"a", b"2")];
+        let pending = cache.begin(&in_flight);
+        let key = Bytes::from_static(b"a");
+
+        // when
+        let during = cache.scan(&key, 0, 10);
+        pending.complete(1, in_flight);
+        let after = cache.scan(&key, 0, 10);
+
+        // then
+        assert_eq!(sequences(during), None);
+        assert_eq!(sequences(after), Some(vec![0, 1]));
+    }
+
+    #[test]
+    fn should_reset_key_after_failed_append() {
+        // given
+        let cache = TailCache::new(usize::MAX);
+        append(&cache, 0, vec![record(b"a", b"1")]);
+        let failed = vec![record(b"a", b"2")];
+
+        // when
+        drop(cache.begin(&failed));
+        append(&cache, 5, vec![record(b"a", b"3")]);
+
+        // then
+        let key = Bytes::from_static(b"a");
+        assert_eq!(sequences(cache.scan(&key, 0, 10)), None);
+        assert_eq!(sequences(cache.scan(&key, 5, 10)), Some(vec![5]));
+    }
+
+    #[test]
+    fn should_evict_oldest_entries_of_least_recently_used_key() {
+        // given
+        let cache = TailCache::new(8);
+        append(&cache, 0, vec![record(b"a", b"111"), record(b"a", b"222")]);
+        append(&cache, 2, vec![record(b"b", b"333")]);
+
+        // when
+        let a = cache.scan(&Bytes::from_static(b"a"), 1, 10);
+        let evicted = cache.scan(&Bytes::from_static(b"a"), 0, 10);
+
+        // then
+        assert_eq!(sequences(a), Some(vec![1]));
+        assert_eq!(sequences(evicted), None);
+        assert_eq!(
+            sequences(cache.scan(&Bytes::from_static(b"b"), 2, 10)),
+            Some(vec![2])
+        );
+    }
+
+    #[test]
+    fn should_evict_down_to_reduced_budget() {
+        // given
+        let cache = TailCache::new(usize::MAX);
+        append(&cache, 0, vec![record(b"a", b"111"), record(b"a", b"222")]);
+        let full = cache.bytes();
+
+        // when
+        cache.set_max_bytes(full - 1);
+
+        // then
+        let key = Bytes::from_static(b"a");
+        assert!(cache.bytes() < full);
+        assert_eq!(sequences(cache.scan(&key, 0, 10)), None);
+        assert_eq!(sequences(cache.scan(&key, 1, 10)), Some(vec![1]));
+    }
+}
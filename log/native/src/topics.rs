@@ -0,0 +1,149 @@
+//! Topic catalog for logs opened through a LogDbManager.
+//!
+//! The manager keeps a small catalog log next to its topics. Every create and
+//! delete is appended as an event under a single reserved key:
+//!
+//! ```text
+//! ┌──────────────┬─────────────────────┐
+//! │ kind (1B)    │ topic name (UTF-8)  │
+//! │ 0=create     │                     │
+//! │ 1=delete     │                     │
+//! └──────────────┴─────────────────────┘
+//! ```
+//!
+//! Replaying the events in order yields the live topics. A topic's generation
+//! is the catalog sequence of its create event and names the directory its
+//! data is stored in, so a topic that is deleted and created again starts
+//! empty. Deleting a topic only removes it from the catalog; its data is left
+//! in place for the object store's lifecycle rules to reclaim.
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use bytes::{BufMut, Bytes, BytesMut};
+
+/// Reserved key holding all catalog events.
+pub(crate) const TOPIC_CATALOG_KEY: &[u8] = b"\xff__opendata/topics";
+
+const CREATE_EVENT: u8 = 0;
+const DELETE_EVENT: u8 = 1;
+
+/// Per-topic settings extracted from a Java `TopicConfig`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct TopicSettings {
+    /// SlateDB settings file replacing the base storage's
+    pub(crate) settings_path: Option<String>,
+    /// Interval at which buffered appends are flushed in the background
+    pub(crate) flush_interval: Option<Duration>,
+}
+
+/// A change to the set of topics.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum TopicEvent {
+    Created(String),
+    Deleted(String),
+}
+
+impl TopicEvent {
+    /// Encodes the event as a catalog entry value.
+    pub(crate) fn encode(&self) -> Bytes {
+        let (kind, name) = match self {
+            TopicEvent::Created(name) => (CREATE_EVENT, name),
+            TopicEvent::Deleted(name) => (DELETE_EVENT, name),
+        };
+        let mut buffer = BytesMut::with_capacity(1 + name.len());
+        buffer.put_u8(kind);
+        buffer.put_slice(name.as_bytes());
+        buffer.freeze()
+    }
+
+    /// Decodes a catalog entry value, returning None if it is not a valid event.
+    pub(crate) fn decode(value: &[u8]) -> Option<Self> {
+        let (&kind, name) = value.split_first()?;
+        let name = std::str::from_utf8(name).ok()?.to_string();
+        match kind {
+            CREATE_EVENT => Some(TopicEvent::Created(name)),
+            DELETE_EVENT => Some(TopicEvent::Deleted(name)),
+            _ => None,
+        }
+    }
+}
+
+/// Live topics and their generations, rebuilt from catalog events.
+#[derive(Debug, Default)]
+pub(crate) struct TopicCatalog {
+    topics: HashMap<String, u64>,
+}
+
+impl TopicCatalog {
+    /// Applies the event stored at catalog sequence `sequence`.
+    pub(crate) fn apply(&mut self, sequence: u64, event: TopicEvent) {
+        match event {
+            TopicEvent::Created(name) => {
+                self.topics.entry(name).or_insert(sequence);
+            }
+            TopicEvent::Deleted(name) => {
+                self.topics.remove(&name);
+            }
+        }
+    }
+
+    /// Returns the generation of a live topic.
+    pub(crate) fn generation(&self, name: &str) -> Option<u64> {
+        self.topics.get(name).copied()
+    }
+
+    /// Returns the names of all live topics in sorted order.
+    pub(crate) fn names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.topics.keys().cloned().collect();
+        names.sort();
+        names
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_round_trip_topic_events() {
+        // given
+        let created = TopicEvent::Created("orders".to_string());
+        let deleted = TopicEvent::Deleted("orders".to_string());
+
+        // when / then
+        assert_eq!(TopicEvent::decode(&created.encode()), Some(created));
+        assert_eq!(TopicEvent::decode(&deleted.encode()), Some(deleted));
+        assert_eq!(TopicEvent::decode(b""), None);
+        assert_eq!(TopicEvent::decode(b"\x07orders"), None);
+    }
+
+    #[test]
+    fn should_list_live_topics_in_order() {
+        // given
+        let mut catalog = TopicCatalog::default();
+
+        // when
+        catalog.apply(0, TopicEvent::Created("payments".to_string()));
+        catalog.apply(1, TopicEvent::Created("orders".to_string()));
+        catalog.apply(2, TopicEvent::Created("audit".to_string()));
+        catalog.apply(3, TopicEvent::Deleted("audit".to_string()));
+
+        // then
+        assert_eq!(catalog.names(), vec!["orders", "payments"]);
+    }
+
+    #[test]
+    fn should_start_new_generation_when_recreated() {
+        // given
+        let mut catalog = TopicCatalog::default();
+        catalog.apply(4, TopicEvent::Created("orders".to_string()));
+        catalog.apply(5, TopicEvent::Deleted("orders".to_string()));
+
+        // when
+        catalog.apply(9, TopicEvent::Created("orders".to_string()));
+
+        // then
+        assert_eq!(catalog.generation("orders"), Some(9));
+    }
+}
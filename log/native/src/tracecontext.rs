@@ -0,0 +1,164 @@
+//! W3C trace context of Java calls.
+//!
+//! A distributed trace through a Java application otherwise stops at the
+//! JNI boundary: nothing native says which request an append or scan served.
+//! Appends and scans may pass the caller's `traceparent` header, which is
+//! validated against the W3C Trace Context format here and entered as the
+//! trace scope of the calling thread for the length of the call, the way a
+//! tracing span is entered. Everything the call reports on that thread, its
+//! JFR event and any slow-operation log line, then carries the traceparent,
+//! so the native time of a traced request can be found from its trace id.
+//!
+//! The binding has no tracer of its own, and the storage layer below it is
+//! not instrumented, so no spans are started natively. The scope is thread
+//! local: work the call hands to other threads, such as coalesced group
+//! commits or bridged calls, reports without it.
+
+use std::cell::RefCell;
+
+/// Length of a version 00 traceparent.
+const TRACEPARENT_LEN: usize = 55;
+
+/// A validated `traceparent` header.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct TraceContext {
+    traceparent: String,
+}
+
+impl TraceContext {
+    /// Parses a `traceparent` header, such as
+    /// `00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01`.
+    pub(crate) fn parse(value: &str) -> Result<Self, String> {
+        let invalid = |reason: &str| format!("Invalid traceparent '{}': {}", value, reason);
+        if value.len() < TRACEPARENT_LEN || !value.is_ascii() {
+            return Err(invalid("expected version-traceid-parentid-flags"));
+        }
+        let (known, rest) = value.split_at(TRACEPARENT_LEN);
+        let fields: Vec<&str> = known.split('-').collect();
+        let [version, trace_id, parent_id, flags] = fields[..] else {
+            return Err(invalid("expected version-traceid-parentid-flags"));
+        };
+        if version.len() != 2 || trace_id.len() != 32 || parent_id.len() != 16 || flags.len() != 2 {
+            return Err(invalid("expected version-traceid-parentid-flags"));
+        }
+        if ![version, trace_id, parent_id, flags].iter().all(|field| {
+            field
+                .bytes()
+                .all(|b| b.is_ascii_digit() || (b'a'..=b'f').contains(&b))
+        }) {
+            return Err(invalid("fields must be lowercase hex"));
+        }
+        if version == "ff" {
+            return Err(invalid("version ff is invalid"));
+        }
+        // Later versions may append fields, which are ignored
+        if !rest.is_empty() && (version == "00" || !rest.starts_with('-')) {
+            return Err(invalid("unexpected trailing characters"));
+        }
+        if trace_id.bytes().all(|b| b == b'0') {
+            return Err(invalid("trace id must not be all zeros"));
+        }
+        if parent_id.bytes().all(|b| b == b'0') {
+            return Err(invalid("parent id must not be all zeros"));
+        }
+        Ok(Self {
+            traceparent: known.to_string(),
+        })
+    }
+
+    /// Returns the header, without any fields of later versions.
+    pub(crate) fn as_str(&self) -> &str {
+        &self.traceparent
+    }
+}
+
+thread_local! {
+    /// Trace context of the call running on this thread
+    static CURRENT: RefCell<Option<TraceContext>> = const { RefCell::new(None) };
+}
+
+/// Trace scope of a call, restoring the enclosing one when dropped.
+pub(crate) struct Scope {
+    previous: Option<TraceContext>,
+}
+
+impl Drop for Scope {
+    fn drop(&mut self) {
+        let previous = self.previous.take();
+        CURRENT.with(|current| *current.borrow_mut() = previous);
+    }
+}
+
+/// Enters `context` as the trace scope of the calling thread until the
+/// returned scope is dropped.
+pub(crate) fn enter(context: Option<TraceContext>) -> Scope {
+    let previous = CURRENT.with(|current| current.replace(context));
+    Scope { previous }
+}
+
+/// Returns the traceparent of the call running on this thread, if traced.
+pub(crate) fn current() -> Option<String> {
+    CURRENT.with(|current| {
+        current
+            .borrow()
+            .as_ref()
+            .map(|context| context.as_str().to_string())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TRACEPARENT: &str = "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01";
+
+    #[test]
+    fn should_parse_valid_traceparent() {
+        // given
+        let later_version = "01-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01-extra";
+
+        // when
+        let parsed = TraceContext::parse(TRACEPARENT);
+        let later = TraceContext::parse(later_version);
+
+        // then
+        assert_eq!(parsed.unwrap().as_str(), TRACEPARENT);
+        assert_eq!(later.unwrap().as_str(), &later_version[..TRACEPARENT_LEN]);
+    }
+
+    #[test]
+    fn should_reject_malformed_traceparent() {
+        // given
+        let malformed = [
+            "",
+            "00-4BF92F3577B34DA6A3CE929D0E0E4736-00f067aa0ba902b7-01",
+            "00-00000000000000000000000000000000-00f067aa0ba902b7-01",
+            "00-4bf92f3577b34da6a3ce929d0e0e4736-0000000000000000-01",
+            "ff-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01",
+            "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01-extra",
+        ];
+
+        // when
+        let parsed: Vec<_> = malformed.iter().map(|v| TraceContext::parse(v)).collect();
+
+        // then
+        assert!(parsed.iter().all(Result::is_err));
+    }
+
+    #[test]
+    fn should_restore_enclosing_scope() {
+        // given
+        let outer = enter(TraceContext::parse(TRACEPARENT).ok());
+
+        // when
+        let inner = enter(None);
+        let during = current();
+        drop(inner);
+
+        // then
+        assert_eq!(during, None);
+        assert_eq!(current().as_deref(), Some(TRACEPARENT));
+        drop(outer);
+        assert_eq!(current(), None);
+    }
+}
@@ -0,0 +1,255 @@
+//! Verification of the entries stored for a key.
+//!
+//! After an incident, forensics needs to know which stored entries can
+//! still be trusted. Verification scans a key's entries natively and checks
+//! what the binding itself wrote: that each value carries the timestamp
+//! header with a non-negative timestamp, that the entry belongs to the key
+//! scanned, and that sequences strictly increase. Checksums of the stored
+//! blocks are SlateDB's to check; a block failing its checksum fails the
+//! scan, which ends the verification with the error and the last sequence
+//! verified instead of throwing away what was found so far. Chunked values
+//! are checked once reassembled.
+//!
+//! Sequence analysis walks a key the same way to check a benchmark's
+//! results: it reports gaps between consecutive sequences of the key, and
+//! entries whose payload repeats an earlier one's by hash. Sequences are
+//! assigned across all keys of a log, so gaps only mean lost entries for a
+//! key written alone. Finding duplicates keeps a 64-bit hash and a sequence
+//! per distinct payload, 16 bytes per entry plus map overhead.
+
+use std::collections::hash_map::{DefaultHasher, Entry};
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+
+use bytes::Bytes;
+use log::{LogEntry, LogRead};
+
+use crate::chunking::Assembler;
+use crate::{extract_timestamp_and_payload, TIMESTAMP_HEADER_SIZE};
+
+/// Most corrupt sequences a report lists; later ones are only counted.
+const MAX_REPORTED: usize = 10_000;
+
+/// Entries verified between progress reports.
+const PROGRESS_INTERVAL: u64 = 10_000;
+
+/// Outcome of verifying a key.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub(crate) struct Verification {
+    /// Entries read
+    pub(crate) entries: u64,
+    /// Value bytes read, headers included
+    pub(crate) bytes: u64,
+    /// Entries failing a check
+    pub(crate) corrupt: u64,
+    /// Sequences of the first corrupt entries, in the order read
+    pub(crate) corrupt_sequences: Vec<u64>,
+    /// Sequence of the last entry read, if any
+    pub(crate) last_sequence: Option<u64>,
+    /// Why the scan ended early, if it did
+    pub(crate) error: Option<String>,
+}
+
+impl Verification {
+    fn check(&mut self, key: &Bytes, entry: &LogEntry) {
+        let valid = entry.key == *key
+            && entry.value.len() >= TIMESTAMP_HEADER_SIZE
+            && extract_timestamp_and_payload(&entry.value).0 >= 0
+            && self
+                .last_sequence
+                .map_or(true, |last| entry.sequence > last);
+        self.entries += 1;
+        self.bytes += entry.value.len() as u64;
+        if !valid {
+            self.corrupt += 1;
+            if self.corrupt_sequences.len() < MAX_REPORTED {
+                self.corrupt_sequences.push(entry.sequence);
+            }
+        }
+        self.last_sequence = Some(
+            self.last_sequence
+                .map_or(entry.sequence, |last| last.max(entry.sequence)),
+        );
+    }
+}
+
+/// Gaps and duplicate payloads among a key's sequences.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub(crate) struct Analysis {
+    /// Entries read
+    pub(crate) entries: u64,
+    /// Sequence of the first entry read, if any
+    pub(crate) first_sequence: Option<u64>,
+    /// Sequence of the last entry read, if any
+    pub(crate) last_sequence: Option<u64>,
+    /// Gaps between consecutive entries
+    pub(crate) gaps: u64,
+    /// Sequences inside gaps
+    pub(crate) missing: u64,
+    /// First and last missing sequence of the first gaps
+    pub(crate) gap_ranges: Vec<(u64, u64)>,
+    /// Entries repeating an earlier payload
+    pub(crate) duplicates: u64,
+    /// Sequence of the first duplicates and of the entry each repeats
+    pub(crate) duplicate_pairs: Vec<(u64, u64)>,
+    /// Why the scan ended early, if it did
+    pub(crate) error: Option<String>,
+    /// First sequence of each payload hash seen
+    seen: HashMap<u64, u64>,
+}
+
+impl Analysis {
+    fn check(&mut self, entry: &LogEntry) {
+        if let Some(last) = self.last_sequence {
+            if entry.sequence > last + 1 {
+                self.gaps += 1;
+                self.missing += entry.sequence - last - 1;
+                if self.gap_ranges.len() < MAX_REPORTED {
+                    self.gap_ranges.push((last + 1, entry.sequence - 1));
+                }
+            }
+        }
+        let (_, payload) = extract_timestamp_and_payload(&entry.value);
+        let mut hasher = DefaultHasher::new();
+        payload.hash(&mut hasher);
+        match self.seen.entry(hasher.finish()) {
+            Entry::Occupied(first) => {
+                self.duplicates += 1;
+                if self.duplicate_pairs.len() < MAX_REPORTED {
+                    self.duplicate_pairs.push((entry.sequence, *first.get()));
+                }
+            }
+            Entry::Vacant(slot) => {
+                slot.insert(entry.sequence);
+            }
+        }
+        self.entries += 1;
+        self.first_sequence.get_or_insert(entry.sequence);
+        self.last_sequence = Some(entry.sequence);
+    }
+}
+
+/// Verifies the entries of `key` from `start_sequence` on, calling
+/// `progress` with the entries read every `PROGRESS_INTERVAL` entries.
+pub(crate) async fn verify<R: LogRead>(
+    log: &R,
+    key: Bytes,
+    start_sequence: u64,
+    mut progress: impl FnMut(u64),
+) -> Verification {
+    let mut verification = Verification::default();
+    let scanned = key.clone();
+    let error = walk(log, key, start_sequence, |entry| {
+        verification.check(&scanned, entry);
+        if verification.entries % PROGRESS_INTERVAL == 0 {
+            progress(verification.entries);
+        }
+    })
+    .await;
+    verification.error = error;
+    verification
+}
+
+/// Analyzes the sequences and payloads of `key` from `start_sequence` on.
+pub(crate) async fn analyze<R: LogRead>(log: &R, key: Bytes, start_sequence: u64) -> Analysis {
+    let mut analysis = Analysis::default();
+    let error = walk(log, key, start_sequence, |entry| analysis.check(entry)).await;
+    analysis.error = error;
+    analysis.seen = HashMap::new();
+    analysis
+}
+
+/// Passes each entry of `key` from `start_sequence` on to `visit`,
+/// returning why the scan ended early, if it did.
+async fn walk<R: LogRead>(
+    log: &R,
+    key: Bytes,
+    start_sequence: u64,
+    mut visit: impl FnMut(&LogEntry),
+) -> Option<String> {
+    let mut iter = match log.scan(key, start_sequence..).await {
+        Ok(iter) => iter,
+        Err(e) => return Some(e.to_string()),
+    };
+    let mut entries = Assembler::default();
+    loop {
+        match iter.next().await {
+            Ok(Some(entry)) => {
+                entries.push(entry);
+                entries.take().iter().for_each(&mut visit);
+            }
+            Ok(None) => return None,
+            Err(e) => return Some(e.to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(key: &'static [u8], sequence: u64, value: Vec<u8>) -> LogEntry {
+        LogEntry {
+            key: Bytes::from_static(key),
+            sequence,
+            value: Bytes::from(value),
+        }
+    }
+
+    fn stamped(timestamp_ms: i64) -> Vec<u8> {
+        let mut value = timestamp_ms.to_be_bytes().to_vec();
+        value.extend_from_slice(b"payload");
+        value
+    }
+
+    #[test]
+    fn should_flag_entries_failing_checks() {
+        // given
+        let key = Bytes::from_static(b"key");
+        let entries = [
+            entry(b"key", 0, stamped(1000)),
+            entry(b"key", 1, vec![0; 3]),
+            entry(b"key", 2, stamped(-1)),
+            entry(b"other", 3, stamped(1000)),
+            entry(b"key", 3, stamped(1000)),
+            entry(b"key", 4, stamped(1001)),
+        ];
+        let mut verification = Verification::default();
+
+        // when
+        for entry in &entries {
+            verification.check(&key, entry);
+        }
+
+        // then
+        assert_eq!(verification.entries, 6);
+        assert_eq!(verification.corrupt, 4);
+        assert_eq!(verification.corrupt_sequences, vec![1, 2, 3, 3]);
+        assert_eq!(verification.last_sequence, Some(4));
+    }
+
+    #[test]
+    fn should_report_gaps_and_repeated_payloads() {
+        // given
+        let entries = [
+            entry(b"key", 3, stamped(1000)),
+            entry(b"key", 4, b"\0\0\0\0\0\0\0\x01other".to_vec()),
+            entry(b"key", 7, stamped(2000)),
+            entry(b"key", 10, b"\0\0\0\0\0\0\0\x02other".to_vec()),
+        ];
+        let mut analysis = Analysis::default();
+
+        // when
+        for entry in &entries {
+            analysis.check(entry);
+        }
+
+        // then
+        assert_eq!(analysis.first_sequence, Some(3));
+        assert_eq!(analysis.gaps, 2);
+        assert_eq!(analysis.missing, 4);
+        assert_eq!(analysis.gap_ranges, vec![(5, 6), (8, 9)]);
+        assert_eq!(analysis.duplicates, 2);
+        assert_eq!(analysis.duplicate_pairs, vec![(7, 3), (10, 4)]);
+    }
+}
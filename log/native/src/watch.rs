@@ -0,0 +1,373 @@
+//! Notifications of new entries of watched keys.
+//!
+//! A consumer following a key otherwise finds new entries by polling scans
+//! or blocking in `waitForSequence`. A watch registers a Java `TailListener`
+//! for a key instead, which is called with the key's new tail, one past its
+//! last sequence, whenever entries of the key at or after the watch's start
+//! sequence become visible. What makes them visible depends on the handle:
+//!
+//! - On a writer, appends report their keys here once storage accepted them,
+//!   the point where scans of the handle can read them, so no watch polls
+//!   storage. A log has a single writer, so these are all of its appends
+//!   while the handle is open; entries appended before the watch was
+//!   registered are reported with the next append of the key.
+//! - On a reader, entries appended by a writer in any process only become
+//!   visible when the reader next refreshes its view of the manifest, and
+//!   the upstream log has no hook for that. A poller of the reader instead
+//!   scans each watched key from its last notified tail once per refresh
+//!   interval, so a watch is notified at most one refresh interval after
+//!   its entries became readable, starting with those already stored when
+//!   it was registered. Each poll reads only the entries past that tail.
+//!
+//! Listeners are called on one notifier thread per handle, started with its
+//! first watch and attached to the JVM as in `attach.rs`, so a listener may
+//! call back into the log without blocking an append. Notifications of a
+//! watch are coalesced: a listener that falls behind is called once with the
+//! latest tail, and tails only ever grow. Exceptions thrown by a listener
+//! are ignored.
+//!
+//! Listeners are held through weak global references, so a forgotten watch
+//! never pins its listener, nor the class loader of an application that
+//! registered it. The Java `AppendWatch` holds the listener strongly until it
+//! is closed; once the listener is collected, the notifier removes the
+//! watch the next time it is due a notification.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::thread;
+
+use bytes::Bytes;
+use jni::objects::{JObject, JValue, WeakRef};
+use jni::JNIEnv;
+use log::{LogRead, Record};
+
+use crate::attach;
+use crate::durability::AppendedTails;
+use crate::keystats::KeyStats;
+use crate::metrics::AppendedVolume;
+
+/// Local references a notification may create.
+const LOCAL_FRAME_CAPACITY: i32 = 4;
+
+struct Watch {
+    key: Bytes,
+    listener: Arc<WeakRef>,
+    /// Highest tail handed to the notifier, or the start sequence
+    notified: u64,
+    /// Tail awaiting delivery, if the watch is queued
+    pending: Option<u64>,
+}
+
+#[derive(Default)]
+struct Registry {
+    next_id: u64,
+    watches: HashMap<u64, Watch>,
+    /// Ids of the watches of each key
+    by_key: HashMap<Bytes, Vec<u64>>,
+}
+
+impl Registry {
+    /// Raises the watches of `key` behind `tail` to it, adding those not
+    /// already queued to `queued`.
+    fn advance(&mut self, key: &Bytes, tail: u64, queued: &mut Vec<u64>) {
+        for id in self.by_key.get(key).into_iter().flatten() {
+            let Some(watch) = self.watches.get_mut(id) else {
+                continue;
+            };
+            if tail <= watch.notified {
+                continue;
+            }
+            watch.notified = tail;
+            if watch.pending.replace(tail).is_none() {
+                queued.push(*id);
+            }
+        }
+    }
+
+    fn remove(&mut self, id: u64) -> bool {
+        let Some(watch) = self.watches.remove(&id) else {
+            return false;
+        };
+        if let Some(ids) = self.by_key.get_mut(&watch.key) {
+            ids.retain(|&other| other != id);
+            if ids.is_empty() {
+                self.by_key.remove(&watch.key);
+            }
+        }
+        true
+    }
+}
+
+/// Registry shared with the notifier thread.
+#[derive(Default)]
+struct Shared {
+    /// Registered watches, read on every append without taking the lock
+    count: AtomicUsize,
+    registry: Mutex<Registry>,
+}
+
+impl Shared {
+    fn lock(&self) -> MutexGuard<'_, Registry> {
+        self.registry.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn remove(&self, id: u64) -> bool {
+        let removed = self.lock().remove(id);
+        if removed {
+            self.count.fetch_sub(1, Ordering::Relaxed);
+        }
+        removed
+    }
+}
+
+/// Watches registered on a handle.
+#[derive(Default)]
+pub(crate) struct Watches {
+    shared: Arc<Shared>,
+    /// Queue of watches with a pending tail, once the notifier started
+    notifier: Mutex<Option<Sender<u64>>>,
+    /// Tails of every key appended to, reported by syncs
+    appended: AppendedTails,
+    /// Records and bytes appended, reported by interval stats
+    volume: AppendedVolume,
+    /// Per-key statistics, if configured
+    key_stats: Option<Arc<KeyStats>>,
+}
+
+/// Offset past the last record of each watched key of a batch, captured
+/// before the batch is appended.
+pub(crate) struct BatchTails(Vec<(Bytes, u64)>);
+
+impl Watches {
+    /// Creates the watches of a handle recording appends into `key_stats`.
+    pub(crate) fn new(key_stats: Option<Arc<KeyStats>>) -> Self {
+        Self {
+            key_stats,
+            ..Self::default()
+        }
+    }
+
+    /// Registers a listener for entries of `key` at or after
+    /// `from_sequence`, returning the watch's id.
+    pub(crate) fn watch(
+        &self,
+        env: &JNIEnv<'_>,
+        key: Bytes,
+        from_sequence: u64,
+        listener: &JObject<'_>,
+    ) -> Result<u64, String> {
+        let listener = env
+            .new_weak_ref(listener)
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| "listener must not be null".to_string())?;
+        self.start_notifier()?;
+        let mut registry = self.shared.lock();
+        registry.next_id += 1;
+        let id = registry.next_id;
+        registry.by_key.entry(key.clone()).or_default().push(id);
+        registry.watches.insert(
+            id,
+            Watch {
+                key,
+                listener: Arc::new(listener),
+                notified: from_sequence,
+                pending: None,
+            },
+        );
+        self.shared.count.fetch_add(1, Ordering::Relaxed);
+        Ok(id)
+    }
+
+    /// Returns the tails of every key appended through the handle, which are
+    /// recorded alongside notifications; see `durability.rs`.
+    pub(crate) fn appended(&self) -> &AppendedTails {
+        &self.appended
+    }
+
+    /// Returns the records and bytes appended through the handle, which are
+    /// recorded alongside notifications.
+    pub(crate) fn volume(&self) -> &AppendedVolume {
+        &self.volume
+    }
+
+    /// Returns the per-key statistics appends are recorded into, if
+    /// configured.
+    pub(crate) fn key_stats(&self) -> Option<&KeyStats> {
+        self.key_stats.as_deref()
+    }
+
+    /// Removes a watch, returning false if it was already removed.
+    pub(crate) fn unwatch(&self, id: u64) -> bool {
+        self.shared.remove(id)
+    }
+
+    /// Captures the watched keys of a batch, or None if none is watched.
+    pub(crate) fn capture(&self, records: &[Record]) -> Option<BatchTails> {
+        if self.shared.count.load(Ordering::Relaxed) == 0 {
+            return None;
+        }
+        let registry = self.shared.lock();
+        let tails = batch_tails(records)
+            .into_iter()
+            .filter(|(key, _)| registry.by_key.contains_key(key))
+            .collect::<Vec<_>>();
+        (!tails.is_empty()).then_some(BatchTails(tails))
+    }
+
+    /// Queues notifications for the watched keys of a batch appended at
+    /// `start_sequence`.
+    pub(crate) fn notify(&self, start_sequence: u64, tails: BatchTails) {
+        let mut queued = Vec::new();
+        {
+            let mut registry = self.shared.lock();
+            for (key, offset) in tails.0 {
+                registry.advance(&key, start_sequence + offset, &mut queued);
+            }
+        }
+        self.queue(queued);
+    }
+
+    /// Returns each watched key with the lowest tail its watches were told
+    /// of, where a poll for new entries of the key starts.
+    pub(crate) fn watched(&self) -> Vec<(Bytes, u64)> {
+        let registry = self.shared.lock();
+        registry
+            .by_key
+            .iter()
+            .filter_map(|(key, ids)| {
+                let from = ids
+                    .iter()
+                    .filter_map(|id| registry.watches.get(id))
+                    .map(|watch| watch.notified)
+                    .min()?;
+                Some((key.clone(), from))
+            })
+            .collect()
+    }
+
+    /// Queues notifications for the watches of `key` not yet told of `tail`.
+    pub(crate) fn notify_tail(&self, key: &Bytes, tail: u64) {
+        let mut queued = Vec::new();
+        self.shared.lock().advance(key, tail, &mut queued);
+        self.queue(queued);
+    }
+
+    fn queue(&self, queued: Vec<u64>) {
+        if queued.is_empty() {
+            return;
+        }
+        let notifier = self.notifier.lock().unwrap_or_else(PoisonError::into_inner);
+        if let Some(notifier) = notifier.as_ref() {
+            for id in queued {
+                let _ = notifier.send(id);
+            }
+        }
+    }
+
+    fn start_notifier(&self) -> Result<(), String> {
+        let mut notifier = self.notifier.lock().unwrap_or_else(PoisonError::into_inner);
+        if notifier.is_some() {
+            return Ok(());
+        }
+        let shared = Arc::clone(&self.shared);
+        let (sender, receiver) = mpsc::channel();
+        thread::Builder::new()
+            .name("opendata-watch".to_string())
+            .spawn(move || {
+                run(&shared, receiver);
+                attach::detach_current_thread();
+            })
+            .map_err(|e| format!("Failed to start watch notifier: {}", e))?;
+        *notifier = Some(sender);
+        Ok(())
+    }
+}
+
+/// Returns the tail of `key` in a view of the log, if the view holds
+/// entries of the key at or after `from_sequence`.
+pub(crate) async fn visible_tail<R: LogRead>(
+    view: &R,
+    key: Bytes,
+    from_sequence: u64,
+) -> Result<Option<u64>, log::Error> {
+    let mut iter = view.scan(key, from_sequence..).await?;
+    let mut tail = None;
+    while let Some(entry) = iter.next().await? {
+        tail = Some(entry.sequence + 1);
+    }
+    Ok(tail)
+}
+
+/// Offset past the last record of each key of a batch.
+fn batch_tails(records: &[Record]) -> HashMap<Bytes, u64> {
+    let mut tails = HashMap::new();
+    for (i, record) in records.iter().enumerate() {
+        tails.insert(record.key.clone(), i as u64 + 1);
+    }
+    tails
+}
+
+/// Notifier thread loop: calls the listeners of queued watches until the
+/// handle's watches are dropped, removing watches whose listener was
+/// collected.
+fn run(shared: &Shared, queued: Receiver<u64>) {
+    for id in queued {
+        let notification = shared
+            .lock()
+            .watches
+            .get_mut(&id)
+            .and_then(|w| Some((Arc::clone(&w.listener), w.pending.take()?)));
+        let Some((listener, tail)) = notification else {
+            continue;
+        };
+        let delivered = attach::call(LOCAL_FRAME_CAPACITY, |env| {
+            let Some(listener) = listener.upgrade_local(env)? else {
+                return Ok(false);
+            };
+            env.call_method(&listener, "onTail", "(J)V", &[JValue::Long(tail as i64)])?;
+            Ok(true)
+        });
+        if delivered == Some(false) {
+            shared.remove(id);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(key: &'static [u8]) -> Record {
+        Record {
+            key: Bytes::from_static(key),
+            value: Bytes::new(),
+        }
+    }
+
+    #[test]
+    fn should_find_end_of_each_key_in_batch() {
+        // given
+        let records = [record(b"a"), record(b"b"), record(b"a")];
+
+        // when
+        let tails = batch_tails(&records);
+
+        // then
+        assert_eq!(tails.get(&Bytes::from_static(b"a")), Some(&3));
+        assert_eq!(tails.get(&Bytes::from_static(b"b")), Some(&2));
+    }
+
+    #[test]
+    fn should_capture_nothing_without_watches() {
+        // given
+        let watches = Watches::default();
+
+        // when
+        let captured = watches.capture(&[record(b"a")]);
+
+        // then
+        assert!(captured.is_none());
+    }
+}
@@ -0,0 +1,358 @@
+//! Watchdog for native calls stuck in `block_on`.
+//!
+//! Every append, scan, and flush on a handle with a watchdog is registered as
+//! an in-flight call for its duration. A background thread periodically checks
+//! the registry and, for each call exceeding the configured threshold, logs the
+//! call together with a snapshot of the runtime (worker and queue metrics plus
+//! every other in-flight call on the handle). Each stuck call is reported once,
+//! as a warning forwarded to SLF4J.
+//!
+//! Tokio's own task dumps require building with `tokio_unstable`, so the
+//! snapshot is assembled from the stable runtime metrics instead.
+//!
+//! When `fail_stuck_calls` is set, the storage future is additionally bounded
+//! by the threshold and the blocked Java call fails with a timeout instead of
+//! waiting indefinitely.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::thread::{self, JoinHandle, ThreadId};
+use std::time::{Duration, Instant};
+
+use tokio::runtime::Handle;
+
+use crate::metrics::{self, Stats};
+use crate::{attach, logging};
+
+/// SLF4J logger stuck-call reports are logged under.
+const TARGET: &str = "dev.opendata.Watchdog";
+
+/// Shortest interval between watchdog checks.
+const MIN_POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// Longest interval between watchdog checks.
+const MAX_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Watchdog settings extracted from the Java `WatchdogConfig`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct WatchdogSettings {
+    /// How long a call may run before it is reported
+    pub(crate) threshold: Duration,
+    /// Whether calls exceeding the threshold fail instead of blocking
+    pub(crate) fail_stuck_calls: bool,
+}
+
+/// A native call currently executing on a handle.
+struct InFlightCall {
+    operation: &'static str,
+    started: Instant,
+    thread: ThreadId,
+    reported: bool,
+}
+
+/// In-flight calls keyed by registration id.
+#[derive(Default)]
+struct Registry {
+    next_id: AtomicU64,
+    calls: Mutex<HashMap<u64, InFlightCall>>,
+}
+
+impl Registry {
+    fn calls(&self) -> std::sync::MutexGuard<'_, HashMap<u64, InFlightCall>> {
+        self.calls.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// Error returned when a call is aborted for exceeding the watchdog threshold.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct StuckCall {
+    operation: &'static str,
+    threshold: Duration,
+}
+
+impl fmt::Display for StuckCall {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} exceeded the watchdog threshold of {}ms",
+            self.operation,
+            self.threshold.as_millis()
+        )
+    }
+}
+
+/// Background thread monitoring in-flight calls on one handle.
+pub(crate) struct Watchdog {
+    settings: WatchdogSettings,
+    registry: Arc<Registry>,
+    stop: Option<mpsc::Sender<()>>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl Watchdog {
+    /// Starts the watchdog thread for a handle.
+    ///
+    /// `name` identifies the handle in log output and `runtime` is the runtime
+    /// whose metrics are included in stuck-call reports.
+    pub(crate) fn start(
+        settings: WatchdogSettings,
+        name: &'static str,
+        runtime: Handle,
+    ) -> std::io::Result<Self> {
+        let registry = Arc::new(Registry::default());
+        let (stop, stopped) = mpsc::channel();
+        let poll_interval = (settings.threshold / 4).clamp(MIN_POLL_INTERVAL, MAX_POLL_INTERVAL);
+
+        let thread_registry = Arc::clone(&registry);
+        let thread = thread::Builder::new()
+            .name(format!("{}-watchdog", name))
+            .spawn(move || {
+                while let Err(RecvTimeoutError::Timeout) = stopped.recv_timeout(poll_interval) {
+                    for report in check(&thread_registry, settings.threshold, name, &runtime) {
+                        logging::warn(TARGET, &report);
+                    }
+                }
+                // Sender dropped or stop requested
+                attach::detach_current_thread();
+            })?;
+
+        Ok(Self {
+            settings,
+            registry,
+            stop: Some(stop),
+            thread: Some(thread),
+        })
+    }
+
+    /// Registers a call as in flight until the returned guard is dropped.
+    pub(crate) fn track(&self, operation: &'static str) -> TrackedCall<'_> {
+        track(&self.registry, operation)
+    }
+
+    /// Runs a future, bounding it by the threshold if stuck calls should fail.
+    pub(crate) async fn guard<F: Future>(
+        &self,
+        operation: &'static str,
+        future: F,
+    ) -> Result<F::Output, StuckCall> {
+        if !self.settings.fail_stuck_calls {
+            return Ok(future.await);
+        }
+        tokio::time::timeout(self.settings.threshold, future)
+            .await
+            .map_err(|_| StuckCall {
+                operation,
+                threshold: self.settings.threshold,
+            })
+    }
+}
+
+impl Drop for Watchdog {
+    fn drop(&mut self) {
+        drop(self.stop.take());
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+/// Removes a call from the in-flight registry when dropped.
+pub(crate) struct TrackedCall<'a> {
+    registry: &'a Registry,
+    id: u64,
+}
+
+impl Drop for TrackedCall<'_> {
+    fn drop(&mut self) {
+        self.registry.calls().remove(&self.id);
+    }
+}
+
+/// Registers a call, returning a guard that unregisters it.
+fn track<'a>(registry: &'a Registry, operation: &'static str) -> TrackedCall<'a> {
+    let id = registry.next_id.fetch_add(1, Ordering::Relaxed);
+    registry.calls().insert(
+        id,
+        InFlightCall {
+            operation,
+            started: Instant::now(),
+            thread: thread::current().id(),
+            reported: false,
+        },
+    );
+    TrackedCall { registry, id }
+}
+
+/// Optional watchdog tracking for a call on a handle.
+pub(crate) fn track_call<'a>(
+    watchdog: Option<&'a Watchdog>,
+    operation: &'static str,
+) -> Option<TrackedCall<'a>> {
+    watchdog.map(|w| w.track(operation))
+}
+
+/// Runs a future under the handle's watchdog, if any.
+pub(crate) async fn guard_call<F: Future>(
+    watchdog: Option<&Watchdog>,
+    operation: &'static str,
+    future: F,
+) -> Result<F::Output, StuckCall> {
+    match watchdog {
+        Some(w) => w.guard(operation, future).await,
+        None => Ok(future.await),
+    }
+}
+
+/// Returns a report for every call that newly exceeded the threshold.
+fn check(registry: &Registry, threshold: Duration, name: &str, runtime: &Handle) -> Vec<String> {
+    let now = Instant::now();
+    let mut calls = registry.calls();
+    let mut stuck = Vec::new();
+    for call in calls.values_mut() {
+        let elapsed = now.duration_since(call.started);
+        if !call.reported && elapsed >= threshold {
+            call.reported = true;
+            stuck.push(format!(
+                "{} watchdog: {} on {:?} has been running for {}ms (threshold {}ms)",
+                name,
+                call.operation,
+                call.thread,
+                elapsed.as_millis(),
+                threshold.as_millis()
+            ));
+        }
+    }
+    if stuck.is_empty() {
+        return stuck;
+    }
+
+    let in_flight: Vec<String> = calls
+        .values()
+        .map(|call| {
+            format!(
+                "{} on {:?} for {}ms",
+                call.operation,
+                call.thread,
+                now.duration_since(call.started).as_millis()
+            )
+        })
+        .collect();
+    drop(calls);
+
+    let mut runtime_stats = Stats::new();
+    metrics::append_runtime_stats(&mut runtime_stats, "runtime", runtime);
+    let runtime_stats: Vec<String> = runtime_stats
+        .iter()
+        .map(|(stat, value)| format!("{}={}", stat, value))
+        .collect();
+
+    stuck.push(format!(
+        "{} watchdog: {}; in-flight calls: [{}]",
+        name,
+        runtime_stats.join(" "),
+        in_flight.join(", ")
+    ));
+    stuck
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn runtime() -> tokio::runtime::Runtime {
+        tokio::runtime::Builder::new_multi_thread()
+            .worker_threads(1)
+            .enable_all()
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn should_report_stuck_call_once() {
+        // given
+        let runtime = runtime();
+        let registry = Registry::default();
+        let _call = track(&registry, "append");
+
+        // when
+        let first = check(&registry, Duration::ZERO, "log", runtime.handle());
+        let second = check(&registry, Duration::ZERO, "log", runtime.handle());
+
+        // then
+        assert_eq!(first.len(), 2);
+        assert!(first[0].contains("append"));
+        assert!(first[1].contains("runtime.workers=1"));
+        assert!(second.is_empty());
+    }
+
+    #[test]
+    fn should_not_report_calls_under_threshold() {
+        // given
+        let runtime = runtime();
+        let registry = Registry::default();
+        let _call = track(&registry, "scan");
+
+        // when
+        let reports = check(&registry, Duration::from_secs(60), "log", runtime.handle());
+
+        // then
+        assert!(reports.is_empty());
+    }
+
+    #[test]
+    fn should_unregister_call_when_guard_dropped() {
+        // given
+        let registry = Registry::default();
+        let call = track(&registry, "flush");
+
+        // when
+        drop(call);
+
+        // then
+        assert!(registry.calls().is_empty());
+    }
+
+    #[test]
+    fn should_fail_stuck_call_when_configured() {
+        // given
+        let runtime = runtime();
+        let settings = WatchdogSettings {
+            threshold: Duration::from_millis(10),
+            fail_stuck_calls: true,
+        };
+        let watchdog = Watchdog::start(settings, "log", runtime.handle().clone()).unwrap();
+
+        // when
+        let result = runtime.block_on(watchdog.guard("append", std::future::pending::<()>()));
+
+        // then
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            "append exceeded the watchdog threshold of 10ms"
+        );
+    }
+
+    #[test]
+    fn should_not_bound_calls_when_only_logging() {
+        // given
+        let runtime = runtime();
+        let settings = WatchdogSettings {
+            threshold: Duration::from_millis(1),
+            fail_stuck_calls: false,
+        };
+        let watchdog = Watchdog::start(settings, "log", runtime.handle().clone()).unwrap();
+
+        // when
+        let result = runtime.block_on(watchdog.guard("append", async {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            7
+        }));
+
+        // then
+        assert_eq!(result, Ok(7));
+    }
+}
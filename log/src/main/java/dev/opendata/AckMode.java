@@ -0,0 +1,21 @@
+package dev.opendata;
+
+/**
+ * When an append is acknowledged to the caller, mirroring Kafka's {@code acks}.
+ *
+ * <p>The declaration order is part of the native contract: the native layer
+ * identifies modes by {@link #ordinal()}.
+ */
+public enum AckMode {
+    /**
+     * Acknowledged once the storage layer has accepted the batch. Records may
+     * still be buffered in memory and are lost if the process crashes before
+     * the next flush.
+     */
+    BUFFERED,
+    /**
+     * Acknowledged only after the batch has been flushed to durable storage,
+     * equivalent to an append followed by {@link LogDb#flush()}.
+     */
+    DURABLE
+}
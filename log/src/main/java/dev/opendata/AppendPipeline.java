@@ -0,0 +1,114 @@
+package dev.opendata;
+
+import java.io.Closeable;
+import java.util.concurrent.CompletableFuture;
+
+/**
+ * Pipelined appends to a {@link LogDb} with ordered acknowledgements.
+ *
+ * <p>A pipeline keeps up to {@code maxInFlight} append batches in flight, so a
+ * producer need not wait for one batch, or its flush, before submitting the
+ * next. Batches are appended in submission order, and their futures complete
+ * in that order too: a batch's future completes only after the futures of all
+ * batches submitted before it, so its sequences are never acknowledged ahead
+ * of earlier ones.
+ *
+ * <p>Once a batch fails, every batch submitted after it fails as well, so
+ * retried records can never land behind records submitted later; open a new
+ * pipeline to continue. Futures complete on a native thread, so dependent
+ * actions should not block. Pipelined appends bypass no-op storage.
+ *
+ * <p>Open pipelines are closed by {@link LogDb#close()}.
+ */
+public final class AppendPipeline implements Closeable {
+
+    static {
+        System.loadLibrary("opendata_log_jni");
+    }
+
+    /**
+     * Largest accepted number of batches in flight.
+     */
+    public static final int MAX_IN_FLIGHT = 64;
+
+    private final LogDb log;
+    private final long handle;
+    private final int maxInFlight;
+    private boolean closed = false;
+
+    AppendPipeline(LogDb log, long handle, int maxInFlight) {
+        this.log = log;
+        this.handle = handle;
+        this.maxInFlight = maxInFlight;
+    }
+
+    /**
+     * Returns the number of batches the pipeline keeps in flight.
+     *
+     * @return the maximum number of batches in flight
+     */
+    public int maxInFlight() {
+        return maxInFlight;
+    }
+
+    /**
+     * Submits a batch with {@link AckMode#BUFFERED} acknowledgement.
+     *
+     * @param records the records to append
+     * @return a future completed with the result of the batch
+     * @see #append(Record[], AckMode)
+     */
+    public CompletableFuture<AppendResult> append(Record[] records) {
+        return append(records, AckMode.BUFFERED);
+    }
+
+    /**
+     * Submits a batch, blocking while {@link #maxInFlight()} batches are in
+     * flight.
+     *
+     * @param records the records to append
+     * @param ackMode when the batch is acknowledged
+     * @return a future completed with the result of the batch, after the
+     *         futures of every batch submitted before it
+     */
+    public synchronized CompletableFuture<AppendResult> append(Record[] records, AckMode ackMode) {
+        if (records == null || records.length == 0) {
+            throw new IllegalArgumentException("records must not be empty");
+        }
+        if (ackMode == null) {
+            throw new IllegalArgumentException("ackMode must not be null");
+        }
+        checkNotClosed();
+        CompletableFuture<AppendResult> result = new CompletableFuture<>();
+        nativeAppend(handle, records, ackMode.ordinal(), result);
+        return result;
+    }
+
+    /**
+     * Waits for every batch in flight, completing their futures, and frees
+     * the pipeline.
+     */
+    @Override
+    public void close() {
+        synchronized (this) {
+            if (closed) {
+                return;
+            }
+            closed = true;
+        }
+        // Outside the lock: completing futures may run actions that submit
+        log.unregisterPipeline(this);
+        nativeClose(handle);
+    }
+
+    private void checkNotClosed() {
+        if (closed) {
+            throw new IllegalStateException("AppendPipeline is closed");
+        }
+    }
+
+    // Native methods
+    private static native void nativeAppend(
+            long handle, Record[] records, int ackMode, CompletableFuture<AppendResult> result);
+    private static native void nativeClose(long handle);
+}
@@ -0,0 +1,220 @@
+package dev.opendata;
+
+import dev.opendata.common.OpenDataNativeException;
+
+import java.io.Closeable;
+import java.lang.invoke.MethodHandles;
+import java.lang.invoke.VarHandle;
+import java.nio.ByteBuffer;
+import java.nio.ByteOrder;
+import java.util.concurrent.locks.LockSupport;
+
+/**
+ * Shared-memory transport for appends to a {@link LogDb}.
+ *
+ * <p>Records offered to the ring are written straight into native memory and
+ * appended to the log in batches by a native consumer thread, so an offer
+ * makes no JNI call unless the consumer is parked on an empty ring and has to
+ * be woken. Offers are fire-and-forget: they return no sequence, and a failed
+ * append is reported by the next {@link #offer}, {@link #flush()} or
+ * {@link #close()} call, after which the ring discards further records.
+ *
+ * <p>Any number of threads may offer concurrently. An offer blocks while the
+ * ring is full. Records are appended in offer order and timestamped when
+ * offered. Ring appends bypass native latency recording.
+ *
+ * <p>Open rings are closed by {@link LogDb#close()}.
+ */
+public final class AppendRing implements Closeable {
+
+    static {
+        System.loadLibrary("opendata_log_jni");
+    }
+
+    /**
+     * Smallest accepted capacity in bytes.
+     */
+    public static final int MIN_CAPACITY = 4096;
+
+    // Layout shared with ring.rs
+    private static final int HEAD_OFFSET = 0;
+    private static final int TAIL_OFFSET = 64;
+    private static final int WAITING_OFFSET = 128;
+    private static final int FAILED_OFFSET = 192;
+    private static final int DATA_OFFSET = 256;
+    private static final int FRAME_HEADER_SIZE = 24;
+    private static final int PADDING_KEY_LEN = -1;
+
+    private static final VarHandle LONGS =
+            MethodHandles.byteBufferViewVarHandle(long[].class, ByteOrder.nativeOrder());
+    private static final VarHandle INTS =
+            MethodHandles.byteBufferViewVarHandle(int[].class, ByteOrder.nativeOrder());
+
+    private final LogDb log;
+    private final long handle;
+    private final ByteBuffer memory;
+    private final int capacity;
+    private long tail = 0;
+    private long cachedHead = 0;
+    private volatile boolean closed = false;
+
+    AppendRing(LogDb log, long handle, int capacity) {
+        this.log = log;
+        this.handle = handle;
+        this.memory = nativeMemory(handle).order(ByteOrder.nativeOrder());
+        this.capacity = capacity;
+    }
+
+    /**
+     * Returns the data capacity of the ring in bytes.
+     *
+     * @return the capacity
+     */
+    public int capacity() {
+        return capacity;
+    }
+
+    /**
+     * Offers a record, blocking while the ring is full.
+     *
+     * @param key   the record key
+     * @param value the record value
+     * @throws IllegalArgumentException if the record does not fit in the ring
+     * @throws OpenDataNativeException  if an earlier append from the ring failed
+     */
+    public synchronized void offer(byte[] key, byte[] value) {
+        if (key == null) {
+            throw new IllegalArgumentException("key must not be null");
+        }
+        if (value == null) {
+            throw new IllegalArgumentException("value must not be null");
+        }
+        long frameSize = align((long) FRAME_HEADER_SIZE + key.length + value.length);
+        if (frameSize > capacity) {
+            throw new IllegalArgumentException(
+                    "record of " + (key.length + value.length) + " bytes does not fit in a ring of "
+                            + capacity + " bytes");
+        }
+        checkNotClosed();
+        checkNotFailed();
+
+        int offset = (int) (tail & (capacity - 1));
+        if (capacity - offset < frameSize) {
+            // Frames never wrap; pad out the end of the data region
+            int padding = capacity - offset;
+            awaitSpace(padding);
+            memory.putInt(DATA_OFFSET + offset, padding);
+            memory.putInt(DATA_OFFSET + offset + 4, PADDING_KEY_LEN);
+            tail += padding;
+            publish();
+            offset = 0;
+        }
+        awaitSpace(frameSize);
+
+        int base = DATA_OFFSET + offset;
+        memory.putInt(base, (int) frameSize);
+        memory.putInt(base + 4, key.length);
+        memory.putInt(base + 8, value.length);
+        memory.putInt(base + 12, 0);
+        memory.putLong(base + 16, System.currentTimeMillis());
+        memory.put(base + FRAME_HEADER_SIZE, key);
+        memory.put(base + FRAME_HEADER_SIZE + key.length, value);
+        tail += frameSize;
+        publish();
+    }
+
+    /**
+     * Blocks until every record offered so far has been appended to the log.
+     *
+     * <p>The records are appended but not necessarily durable; call
+     * {@link LogDb#flush()} afterwards to make them durable. Offers from other
+     * threads wait for the flush to finish.
+     *
+     * @throws OpenDataNativeException if an append from the ring failed
+     */
+    public synchronized void flush() {
+        checkNotClosed();
+        int spins = 0;
+        while ((long) LONGS.getAcquire(memory, HEAD_OFFSET) < tail) {
+            checkNotFailed();
+            spins = backOff(spins);
+        }
+        checkNotFailed();
+    }
+
+    /**
+     * Appends every record offered so far, stops the consumer and frees the ring.
+     *
+     * @throws OpenDataNativeException if an append from the ring failed
+     */
+    @Override
+    public void close() {
+        String error;
+        synchronized (this) {
+            if (closed) {
+                return;
+            }
+            closed = true;
+            log.unregisterRing(this);
+            error = nativeClose(handle);
+        }
+        if (error != null) {
+            throw new OpenDataNativeException(error);
+        }
+    }
+
+    /**
+     * Publishes the tail, then wakes the consumer if it parked on an empty ring.
+     */
+    private void publish() {
+        LONGS.setVolatile(memory, TAIL_OFFSET, tail);
+        if ((int) INTS.getVolatile(memory, WAITING_OFFSET) != 0
+                && INTS.compareAndSet(memory, WAITING_OFFSET, 1, 0)) {
+            nativeDoorbell(handle);
+        }
+    }
+
+    private void awaitSpace(long size) {
+        int spins = 0;
+        while (tail + size - cachedHead > capacity) {
+            cachedHead = (long) LONGS.getAcquire(memory, HEAD_OFFSET);
+            if (tail + size - cachedHead <= capacity) {
+                return;
+            }
+            checkNotFailed();
+            spins = backOff(spins);
+        }
+    }
+
+    private static int backOff(int spins) {
+        if (spins < 100) {
+            Thread.onSpinWait();
+        } else {
+            LockSupport.parkNanos(10_000);
+        }
+        return spins + 1;
+    }
+
+    private static long align(long size) {
+        return (size + 7) & ~7L;
+    }
+
+    private void checkNotFailed() {
+        if ((int) INTS.getAcquire(memory, FAILED_OFFSET) != 0) {
+            String error = nativeError(handle);
+            throw new OpenDataNativeException(error != null ? error : "append ring failed");
+        }
+    }
+
+    private void checkNotClosed() {
+        if (closed) {
+            throw new IllegalStateException("AppendRing is closed");
+        }
+    }
+
+    // Native methods
+    private static native ByteBuffer nativeMemory(long handle);
+    private static native void nativeDoorbell(long handle);
+    private static native String nativeError(long handle);
+    private static native String nativeClose(long handle);
+}
@@ -0,0 +1,39 @@
+package dev.opendata;
+
+import java.util.function.LongConsumer;
+
+/**
+ * Registration of a {@link TailListener} for new entries of a key, returned
+ * by {@link LogDb#watchAppends(byte[], long, TailListener)} and
+ * {@link LogDbReader#watchAppends(byte[], long, TailListener)}.
+ *
+ * <p>Closing the watch stops notifications, except for one already being
+ * delivered. Watches are removed when their log or reader closes.
+ *
+ * <p>The native layer holds the listener only weakly; the watch holds it
+ * strongly. A watch that is no longer reachable stops once its listener is
+ * garbage collected, so a forgotten watch does not pin the listener or its
+ * class loader, but when it stops is up to the collector: close watches to
+ * stop them deterministically.
+ */
+public final class AppendWatch implements AutoCloseable {
+
+    private final LongConsumer unwatch;
+    private final long id;
+    // Keeps the listener reachable while the watch is
+    private final TailListener listener;
+
+    AppendWatch(LongConsumer unwatch, long id, TailListener listener) {
+        this.unwatch = unwatch;
+        this.id = id;
+        this.listener = listener;
+    }
+
+    /**
+     * Stops notifying the listener. Closing a watch twice has no effect.
+     */
+    @Override
+    public void close() {
+        unwatch.accept(id);
+    }
+}
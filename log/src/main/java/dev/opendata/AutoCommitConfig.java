@@ -0,0 +1,41 @@
+package dev.opendata;
+
+/**
+ * Configuration for automatically committing a {@link LogDbReader}'s offsets.
+ *
+ * <p>When enabled, the reader tracks the next sequence to read for every key
+ * it scans and a native background task periodically commits the positions
+ * that changed for the consumer group, mirroring Kafka's
+ * {@code enable.auto.commit}. Pending positions are also committed when the
+ * reader is closed. Commits go through the writer attached with
+ * {@link LogDbReader#attachOffsetWriter(LogDb)}.
+ *
+ * @param group      the consumer group to commit offsets for
+ * @param intervalMs interval in milliseconds between automatic commits
+ */
+public record AutoCommitConfig(String group, long intervalMs) {
+
+    /**
+     * Default interval between automatic commits, matching Kafka's
+     * {@code auto.commit.interval.ms}.
+     */
+    public static final long DEFAULT_INTERVAL_MS = 5_000;
+
+    /**
+     * Creates an auto-commit config with the default interval.
+     *
+     * @param group the consumer group to commit offsets for
+     */
+    public AutoCommitConfig(String group) {
+        this(group, DEFAULT_INTERVAL_MS);
+    }
+
+    public AutoCommitConfig {
+        if (group == null || group.isEmpty()) {
+            throw new IllegalArgumentException("group must not be null or empty");
+        }
+        if (intervalMs <= 0) {
+            throw new IllegalArgumentException("intervalMs must be positive");
+        }
+    }
+}
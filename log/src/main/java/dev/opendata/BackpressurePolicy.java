@@ -0,0 +1,26 @@
+package dev.opendata;
+
+/**
+ * What {@link LogDb#submitAppend(Record[], AckMode)} does when the native
+ * write queue is full, see {@link WriteQueueConfig}.
+ *
+ * <p>The declaration order is part of the native contract: the native layer
+ * identifies policies by {@link #ordinal()}.
+ */
+public enum BackpressurePolicy {
+    /**
+     * Block the submitting thread until earlier appends complete and free
+     * enough space.
+     */
+    BLOCK,
+    /**
+     * Throw {@link dev.opendata.common.OpenDataBackpressureException} without
+     * submitting the append.
+     */
+    REJECT,
+    /**
+     * Return {@link LogDb#NOT_READY} instead of a ticket without submitting the
+     * append, so the caller can poll completions and retry.
+     */
+    NOT_READY
+}
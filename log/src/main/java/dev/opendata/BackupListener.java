@@ -0,0 +1,24 @@
+package dev.opendata;
+
+/**
+ * Callback notified of the progress of a backup started with
+ * {@link LogDb#backup(String, dev.opendata.common.StorageConfig.SlateDb, BackupListener)}.
+ *
+ * <p>Callbacks run on the thread calling {@code backup}, once before the
+ * first object is copied and after each object. Exceptions thrown by the
+ * listener are ignored.
+ */
+@FunctionalInterface
+public interface BackupListener {
+
+    /**
+     * Called as the backup copies objects.
+     *
+     * @param objectsCopied objects copied so far
+     * @param objectsTotal  objects the backup copies in all; it shrinks when
+     *                      an object the checkpoint does not need vanishes
+     *                      from the source before it is copied
+     * @param bytesCopied   bytes copied so far
+     */
+    void onProgress(long objectsCopied, long objectsTotal, long bytesCopied);
+}
@@ -0,0 +1,26 @@
+package dev.opendata;
+
+/**
+ * Workload for {@link LogDb#bench(BenchConfig)}.
+ *
+ * @param payloadSize size of each record's payload in bytes
+ * @param batchSize   number of records per append
+ * @param batches     number of appends
+ */
+public record BenchConfig(int payloadSize, int batchSize, int batches) {
+
+    public BenchConfig {
+        if (payloadSize < 0) {
+            throw new IllegalArgumentException("payloadSize must not be negative");
+        }
+        if (batchSize <= 0) {
+            throw new IllegalArgumentException("batchSize must be positive");
+        }
+        if (batches <= 0) {
+            throw new IllegalArgumentException("batches must be positive");
+        }
+        if ((long) batchSize * batches > Integer.MAX_VALUE) {
+            throw new IllegalArgumentException("batchSize * batches must fit in an int");
+        }
+    }
+}
@@ -0,0 +1,41 @@
+package dev.opendata;
+
+/**
+ * Result of {@link LogDb#bench(BenchConfig)}.
+ *
+ * <p>Latency histograms use the same encoding as
+ * {@link LogDb#latencyHistogram(Operation)}.
+ *
+ * @param records           number of records produced and consumed
+ * @param bytes             payload bytes produced
+ * @param elapsedNanos      time from the first append until the last record was read
+ * @param appendLatencies   encoded histogram of append call latencies, in microseconds
+ * @param endToEndLatencies encoded histogram of per-record latencies from append
+ *                          until read, in microseconds
+ */
+public record BenchReport(
+        long records,
+        long bytes,
+        long elapsedNanos,
+        byte[] appendLatencies,
+        byte[] endToEndLatencies
+) {
+
+    /**
+     * Returns the throughput in records per second.
+     *
+     * @return records per second
+     */
+    public double recordsPerSecond() {
+        return elapsedNanos > 0 ? records * 1e9 / elapsedNanos : 0;
+    }
+
+    /**
+     * Returns the throughput in payload bytes per second.
+     *
+     * @return bytes per second
+     */
+    public double bytesPerSecond() {
+        return elapsedNanos > 0 ? bytes * 1e9 / elapsedNanos : 0;
+    }
+}
@@ -0,0 +1,85 @@
+package dev.opendata;
+
+/**
+ * Limit on the blocking calls one {@link LogDb} handle runs at once.
+ *
+ * <p>Each blocking call, such as an append, scan, flush or offset commit,
+ * holds its Java thread in native code until storage answers. When hundreds
+ * of threads call one handle at once, the limit lets at most
+ * {@code maxCalls} of them into the native runtime; a call beyond them is
+ * handled according to {@code policy}, and a refused call throws
+ * {@link dev.opendata.common.OpenDataOverloadedException} without reaching
+ * storage. Submitted appends and scans, bridged calls and append rings do
+ * not hold a Java thread in native code and are not limited; see
+ * {@link WriteQueueConfig} for bounding submitted appends.
+ * {@link LogDb#stats()} reports the limit as {@code call_limit.max_calls},
+ * the calls running and waiting as {@code call_limit.running} and
+ * {@code call_limit.queued}, and counts refused calls as
+ * {@code call_limit.rejected}.
+ *
+ * @param maxCalls  blocking calls running at once, or 0 for no limit
+ * @param policy    what a call does when the limit is reached
+ * @param maxQueued callers waiting for a running call to return under
+ *                  {@link OverloadPolicy#QUEUE}; with 0 every call beyond
+ *                  the limit is refused
+ */
+public record CallLimitConfig(int maxCalls, OverloadPolicy policy, int maxQueued) {
+
+    /**
+     * Configuration without a call limit.
+     */
+    public static final CallLimitConfig UNLIMITED = new CallLimitConfig(0, OverloadPolicy.BLOCK, 0);
+
+    public CallLimitConfig {
+        if (maxCalls < 0) {
+            throw new IllegalArgumentException("maxCalls must not be negative");
+        }
+        if (policy == null) {
+            throw new IllegalArgumentException("policy must not be null");
+        }
+        if (maxQueued < 0) {
+            throw new IllegalArgumentException("maxQueued must not be negative");
+        }
+    }
+
+    /**
+     * Creates a config limiting blocking calls, blocking callers beyond the
+     * limit until a running call returns.
+     *
+     * @param maxCalls blocking calls running at once
+     * @return a new CallLimitConfig
+     */
+    public static CallLimitConfig of(int maxCalls) {
+        return new CallLimitConfig(maxCalls, OverloadPolicy.BLOCK, 0);
+    }
+
+    /**
+     * Returns a copy of this config with the given policy.
+     *
+     * @param policy what a call does when the limit is reached
+     * @return a new CallLimitConfig
+     */
+    public CallLimitConfig withPolicy(OverloadPolicy policy) {
+        return new CallLimitConfig(maxCalls, policy, maxQueued);
+    }
+
+    /**
+     * Returns a copy of this config letting the given number of callers wait
+     * under {@link OverloadPolicy#QUEUE}.
+     *
+     * @param maxQueued callers waiting for a running call to return
+     * @return a new CallLimitConfig
+     */
+    public CallLimitConfig withMaxQueued(int maxQueued) {
+        return new CallLimitConfig(maxCalls, policy, maxQueued);
+    }
+
+    /**
+     * Returns whether blocking calls are limited.
+     *
+     * @return true if the limit is positive
+     */
+    public boolean enabled() {
+        return maxCalls > 0;
+    }
+}
@@ -0,0 +1,19 @@
+package dev.opendata;
+
+/**
+ * A named, consistent checkpoint of a log's SlateDB storage, as returned by
+ * {@link LogDb#checkpoint(String)}.
+ *
+ * <p>A checkpoint pins the manifest current when it was taken, and the data
+ * that manifest references, so the log can be read, backed up or cloned as
+ * it stood then, whatever compaction and garbage collection do meanwhile.
+ *
+ * @param id          unique id of the checkpoint
+ * @param name        name the checkpoint was taken under, or null
+ * @param manifestId  id of the SlateDB manifest the checkpoint pins
+ * @param createdAtMs when the checkpoint was taken, in epoch milliseconds
+ * @param expiresAtMs when the checkpoint expires, in epoch milliseconds, or 0
+ *                    if it lives until deleted
+ */
+public record Checkpoint(String id, String name, long manifestId, long createdAtMs, long expiresAtMs) {
+}
@@ -0,0 +1,37 @@
+package dev.opendata;
+
+/**
+ * Source of the timestamps stored with appended records, set with
+ * {@link LogDbConfig#withClock(ClockSource)}.
+ *
+ * <p>Except for {@link #RECORD}, the native layer stamps every record of a
+ * batch with one timestamp, taken as the batch is copied out of Java, and
+ * ignores the records' {@link Record#timestampMs()}, as with Kafka's
+ * {@code LogAppendTime}. {@link AppendResult#timestamp()} reports the stamp.
+ * Records appended through an {@link AppendRing} or the C ABI, and imported
+ * entries, keep their own timestamps.
+ *
+ * <p>The declaration order is part of the native contract: the native layer
+ * identifies sources by {@link #ordinal()}.
+ */
+public enum ClockSource {
+    /**
+     * Records keep the timestamp they were created with, which measures
+     * end-to-end latency but trusts every producer's clock.
+     */
+    RECORD,
+    /**
+     * The system time at each append.
+     */
+    WALL,
+    /**
+     * The system time when the log opened, advanced by monotonic time, so
+     * timestamps never go backwards when the system time is stepped.
+     */
+    MONOTONIC,
+    /**
+     * A time starting at 0 that only moves through {@link LogDb#setClock(long)}
+     * and {@link LogDb#advanceClock(long)}, for deterministic tests.
+     */
+    MANUAL
+}
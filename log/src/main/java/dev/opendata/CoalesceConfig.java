@@ -0,0 +1,91 @@
+package dev.opendata;
+
+/**
+ * Configuration for coalescing concurrent appends into shared batches.
+ *
+ * <p>When enabled, blocking appends from different threads that arrive within
+ * a window of each other are merged natively into a single storage append,
+ * which raises throughput for many small producers at the cost of up to one
+ * window of added latency. The window opens with its first append and closes
+ * after {@code windowMicros}, or earlier once it holds {@code maxRecords}
+ * records or {@code maxBytes} bytes of keys and values. Each caller still
+ * receives the sequence of its own first record.
+ *
+ * @param windowMicros how long in microseconds a window stays open, or 0 to
+ *                     disable coalescing
+ * @param maxRecords   number of records that closes a window early
+ * @param maxBytes     number of key and value bytes that closes a window early
+ */
+public record CoalesceConfig(long windowMicros, int maxRecords, long maxBytes) {
+
+    /**
+     * Default number of records that closes a window.
+     */
+    public static final int DEFAULT_MAX_RECORDS = 10_000;
+
+    /**
+     * Default number of bytes that closes a window, 1 MiB.
+     */
+    public static final long DEFAULT_MAX_BYTES = 1L << 20;
+
+    /**
+     * Configuration with coalescing disabled.
+     */
+    public static final CoalesceConfig DISABLED =
+            new CoalesceConfig(0, DEFAULT_MAX_RECORDS, DEFAULT_MAX_BYTES);
+
+    public CoalesceConfig {
+        if (windowMicros < 0) {
+            throw new IllegalArgumentException("windowMicros must not be negative");
+        }
+        if (maxRecords <= 0) {
+            throw new IllegalArgumentException("maxRecords must be positive");
+        }
+        if (maxBytes <= 0) {
+            throw new IllegalArgumentException("maxBytes must be positive");
+        }
+    }
+
+    /**
+     * Creates a config coalescing appends within the given window and the
+     * default size limits.
+     *
+     * @param windowMicros window in microseconds
+     * @return a new CoalesceConfig
+     */
+    public static CoalesceConfig within(long windowMicros) {
+        if (windowMicros <= 0) {
+            throw new IllegalArgumentException("windowMicros must be positive");
+        }
+        return new CoalesceConfig(windowMicros, DEFAULT_MAX_RECORDS, DEFAULT_MAX_BYTES);
+    }
+
+    /**
+     * Returns a copy of this config with the given record limit.
+     *
+     * @param maxRecords number of records that closes a window early
+     * @return a new CoalesceConfig
+     */
+    public CoalesceConfig withMaxRecords(int maxRecords) {
+        return new CoalesceConfig(windowMicros, maxRecords, maxBytes);
+    }
+
+    /**
+     * Returns a copy of this config with the given byte limit.
+     *
+     * @param maxBytes number of key and value bytes that closes a window early
+     * @return a new CoalesceConfig
+     */
+    public CoalesceConfig withMaxBytes(long maxBytes) {
+        return new CoalesceConfig(windowMicros, maxRecords, maxBytes);
+    }
+
+    /**
+     * Returns whether coalescing is enabled.
+     *
+     * @return true if {@code windowMicros} is positive
+     */
+    public boolean enabled() {
+        return windowMicros > 0;
+    }
+}
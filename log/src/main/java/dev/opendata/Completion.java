@@ -0,0 +1,39 @@
+package dev.opendata;
+
+import dev.opendata.common.OpenDataNativeException;
+
+import java.util.List;
+
+/**
+ * Outcome of an operation submitted with {@link LogDb#submitAppend} or
+ * {@link LogDb#submitScan}, returned by {@link LogDb#pollCompletions}.
+ *
+ * <p>Exactly one of {@code appendResult}, {@code entries} and {@code error} is
+ * non-null: the append result of a successful append, the entries of a
+ * successful scan, or the error of a failed operation.
+ *
+ * @param ticket       the ticket returned when the operation was submitted
+ * @param appendResult the result of a successful append, or null
+ * @param entries      the entries read by a successful scan, or null
+ * @param error        the failure of the operation, or null
+ */
+public record Completion(long ticket, AppendResult appendResult, List<LogEntry> entries,
+                         OpenDataNativeException error) {
+
+    /**
+     * Creates a completion from the values reported by the native layer.
+     */
+    Completion(long ticket, AppendResult appendResult, LogEntry[] entries, String error) {
+        this(ticket, appendResult, entries != null ? List.of(entries) : null,
+                error != null ? new OpenDataNativeException(error) : null);
+    }
+
+    /**
+     * Returns whether the operation failed.
+     *
+     * @return true if {@link #error()} is set
+     */
+    public boolean failed() {
+        return error != null;
+    }
+}
@@ -0,0 +1,81 @@
+package dev.opendata;
+
+/**
+ * Settings changed on an open {@link LogDb} by {@link LogDb#updateConfig(ConfigUpdate)}.
+ *
+ * <p>Null fields leave the setting as it is. Long soak tests can thereby
+ * sweep these settings mid-run without reopening the log; settings that
+ * shape the storage, such as the write buffer, still require a reopen.
+ *
+ * @param tailCacheBytes  byte budget of the tail cache, which must have been
+ *                        enabled at open; shrinking it evicts entries
+ * @param rateLimits      rate limits replacing the current ones, which must
+ *                        have been configured at open; debt left under the
+ *                        old limits is forgiven
+ * @param logLevel        most verbose level of native log lines, shared by
+ *                        every handle
+ * @param flushIntervalMs interval in milliseconds at which buffered appends
+ *                        are flushed in the background, or 0 to stop
+ *                        background flushes
+ */
+public record ConfigUpdate(
+        Long tailCacheBytes,
+        RateLimitConfig rateLimits,
+        NativeLogLevel logLevel,
+        Long flushIntervalMs
+) {
+
+    /**
+     * Update that changes nothing.
+     */
+    public static final ConfigUpdate NONE = new ConfigUpdate(null, null, null, null);
+
+    public ConfigUpdate {
+        if (tailCacheBytes != null && tailCacheBytes < 0) {
+            throw new IllegalArgumentException("tailCacheBytes must not be negative");
+        }
+        if (flushIntervalMs != null && flushIntervalMs < 0) {
+            throw new IllegalArgumentException("flushIntervalMs must not be negative");
+        }
+    }
+
+    /**
+     * Returns a copy of this update with the given tail cache budget.
+     *
+     * @param tailCacheBytes byte budget of the tail cache
+     * @return a new ConfigUpdate
+     */
+    public ConfigUpdate withTailCacheBytes(long tailCacheBytes) {
+        return new ConfigUpdate(tailCacheBytes, rateLimits, logLevel, flushIntervalMs);
+    }
+
+    /**
+     * Returns a copy of this update with the given rate limits.
+     *
+     * @param rateLimits rate limits replacing the current ones
+     * @return a new ConfigUpdate
+     */
+    public ConfigUpdate withRateLimits(RateLimitConfig rateLimits) {
+        return new ConfigUpdate(tailCacheBytes, rateLimits, logLevel, flushIntervalMs);
+    }
+
+    /**
+     * Returns a copy of this update with the given native log level.
+     *
+     * @param logLevel most verbose level of native log lines
+     * @return a new ConfigUpdate
+     */
+    public ConfigUpdate withLogLevel(NativeLogLevel logLevel) {
+        return new ConfigUpdate(tailCacheBytes, rateLimits, logLevel, flushIntervalMs);
+    }
+
+    /**
+     * Returns a copy of this update with the given background flush interval.
+     *
+     * @param flushIntervalMs flush interval in milliseconds, or 0 to stop
+     * @return a new ConfigUpdate
+     */
+    public ConfigUpdate withFlushIntervalMs(long flushIntervalMs) {
+        return new ConfigUpdate(tailCacheBytes, rateLimits, logLevel, flushIntervalMs);
+    }
+}
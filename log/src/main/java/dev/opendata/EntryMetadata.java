@@ -0,0 +1,26 @@
+package dev.opendata;
+
+import java.util.ArrayList;
+import java.util.List;
+
+/**
+ * An entry read from the log without its key or value, as returned by
+ * {@link LogRead#scanMetadata(byte[], long, int)}.
+ *
+ * @param sequence    the sequence number of the entry
+ * @param timestamp   the timestamp (epoch millis) when the entry was appended
+ * @param valueLength the length in bytes of the entry's value
+ */
+public record EntryMetadata(long sequence, long timestamp, int valueLength) {
+
+    static List<EntryMetadata> fromNative(long[] fields) {
+        if (fields == null) {
+            return List.of();
+        }
+        List<EntryMetadata> entries = new ArrayList<>(fields.length / 3);
+        for (int i = 0; i + 2 < fields.length; i += 3) {
+            entries.add(new EntryMetadata(fields[i], fields[i + 1], (int) fields[i + 2]));
+        }
+        return List.copyOf(entries);
+    }
+}
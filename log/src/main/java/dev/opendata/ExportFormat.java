@@ -0,0 +1,21 @@
+package dev.opendata;
+
+/**
+ * Layout of a file written by {@link LogDb#export(byte[], long, long, java.nio.file.Path, ExportFormat)}.
+ *
+ * <p>The declaration order is part of the native contract: the native layer
+ * identifies formats by {@link #ordinal()}.
+ */
+public enum ExportFormat {
+    /**
+     * One JSON object per line, with the entry's {@code sequence}, its
+     * {@code timestamp_ms}, and its {@code key} and {@code value} in base64.
+     * The value is the payload as appended, without the timestamp header.
+     */
+    NDJSON,
+    /**
+     * The payloads alone, each as a 4-byte big-endian length followed by its
+     * bytes, for comparing against the payloads a benchmark produced.
+     */
+    RAW
+}
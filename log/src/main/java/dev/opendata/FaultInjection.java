@@ -0,0 +1,200 @@
+package dev.opendata;
+
+import java.util.EnumSet;
+import java.util.Set;
+
+/**
+ * Object-store fault injection for testing retry and error handling.
+ *
+ * <p>Faults are injected into the requests the log's SlateDB instance makes
+ * of its object store, which the native layer wraps. Each targeted request
+ * is first delayed by a latency drawn uniformly from
+ * {@code [minLatencyMs, maxLatencyMs]} and then fails with one of three
+ * faults, at independent rates:
+ * <ul>
+ *   <li>a storage error, with probability {@code errorProbability}</li>
+ *   <li>a rejection as if throttled with an S3 {@code 503 SlowDown}, with
+ *       probability {@code throttleProbability}</li>
+ *   <li>a transient timeout that hangs for {@code timeoutMs} before failing,
+ *       with probability {@code timeoutProbability}</li>
+ * </ul>
+ *
+ * <p>Requests are targeted by the operation they serve: writes of WAL SSTs,
+ * compacted SSTs and manifests, and deletes, by {@link Operation#APPEND};
+ * reads and lists by {@link Operation#SCAN}. Requests of flushes, compaction
+ * and manifest polling are targeted alike.
+ *
+ * <p>SlateDB retries failed requests with backoff, as it does those of a real
+ * object store, so a fault shows up in Java as latency of the call it held
+ * up, such as a {@link AckMode#DURABLE} append, a flush or a scan reading from
+ * object storage. Appends acknowledged from memory do not wait on the object
+ * store. The number of injected faults of each kind and the total injected
+ * latency are reported as {@code faults.injected_errors},
+ * {@code faults.injected_throttles}, {@code faults.injected_timeouts} and
+ * {@code faults.injected_latency_nanos} in {@link LogDb#stats()}.
+ *
+ * <p>Fault injection needs {@link dev.opendata.common.StorageConfig.SlateDb}
+ * storage, on any object store; opening in-memory storage with faults fails.
+ *
+ * @param errorProbability    probability that a targeted request fails with a storage error
+ * @param throttleProbability probability that a targeted request is rejected as throttled
+ * @param timeoutProbability  probability that a targeted request times out
+ * @param timeoutMs           how long a timed-out request hangs before failing
+ * @param minLatencyMs        lower bound of the latency added to targeted requests
+ * @param maxLatencyMs        upper bound of the latency added to targeted requests
+ * @param operations          operations to inject faults into; empty disables injection
+ * @param seed                seed of the native random generator, so runs can be repeated
+ */
+public record FaultInjection(
+        double errorProbability,
+        double throttleProbability,
+        double timeoutProbability,
+        long timeoutMs,
+        long minLatencyMs,
+        long maxLatencyMs,
+        Set<Operation> operations,
+        long seed
+) {
+
+    /**
+     * Fault injection disabled.
+     */
+    public static final FaultInjection DISABLED = new FaultInjection(0, 0, 0, 0, 0, 0, Set.of(), 0);
+
+    public FaultInjection {
+        requireProbability("errorProbability", errorProbability);
+        requireProbability("throttleProbability", throttleProbability);
+        requireProbability("timeoutProbability", timeoutProbability);
+        if (errorProbability + throttleProbability + timeoutProbability > 1) {
+            throw new IllegalArgumentException("fault probabilities must not sum to more than 1");
+        }
+        if (timeoutMs < 0) {
+            throw new IllegalArgumentException("timeoutMs must not be negative");
+        }
+        if (minLatencyMs < 0) {
+            throw new IllegalArgumentException("minLatencyMs must not be negative");
+        }
+        if (maxLatencyMs < minLatencyMs) {
+            throw new IllegalArgumentException("maxLatencyMs must not be less than minLatencyMs");
+        }
+        if (operations == null) {
+            throw new IllegalArgumentException("operations must not be null");
+        }
+        operations = Set.copyOf(operations);
+    }
+
+    /**
+     * Creates a config failing object-store requests with the given probability.
+     *
+     * @param errorProbability probability in {@code [0, 1]} that a request fails
+     * @return a new FaultInjection
+     */
+    public static FaultInjection errors(double errorProbability) {
+        return new FaultInjection(errorProbability, 0, 0, 0, 0, 0, EnumSet.allOf(Operation.class), 0);
+    }
+
+    /**
+     * Creates a config delaying object-store requests by a uniformly distributed latency.
+     *
+     * @param minLatencyMs lower bound of the added latency
+     * @param maxLatencyMs upper bound of the added latency
+     * @return a new FaultInjection
+     */
+    public static FaultInjection latency(long minLatencyMs, long maxLatencyMs) {
+        return new FaultInjection(0, 0, 0, 0, minLatencyMs, maxLatencyMs, EnumSet.allOf(Operation.class), 0);
+    }
+
+    /**
+     * Creates a chaos config failing object-store requests as if the object store throttled them.
+     *
+     * <p>SlateDB backs off and retries the rejected and timed-out requests,
+     * so calls waiting on them slow down rather than fail.
+     *
+     * @param throttleProbability probability that a request is rejected with {@code 503 SlowDown}
+     * @param timeoutProbability  probability that a request times out
+     * @param timeoutMs           how long a timed-out request hangs before failing
+     * @return a new FaultInjection
+     */
+    public static FaultInjection throttling(double throttleProbability, double timeoutProbability, long timeoutMs) {
+        return new FaultInjection(0, throttleProbability, timeoutProbability, timeoutMs, 0, 0,
+                EnumSet.allOf(Operation.class), 0);
+    }
+
+    /**
+     * Returns a copy of this config with the given error probability.
+     *
+     * @param errorProbability probability in {@code [0, 1]} that a targeted request fails
+     * @return a new FaultInjection
+     */
+    public FaultInjection withErrorProbability(double errorProbability) {
+        return new FaultInjection(errorProbability, throttleProbability, timeoutProbability, timeoutMs,
+                minLatencyMs, maxLatencyMs, operations, seed);
+    }
+
+    /**
+     * Returns a copy of this config with the given throttling and timeout rates.
+     *
+     * @param throttleProbability probability that a targeted request is rejected as throttled
+     * @param timeoutProbability  probability that a targeted request times out
+     * @param timeoutMs           how long a timed-out request hangs before failing
+     * @return a new FaultInjection
+     */
+    public FaultInjection withThrottling(double throttleProbability, double timeoutProbability, long timeoutMs) {
+        return new FaultInjection(errorProbability, throttleProbability, timeoutProbability, timeoutMs,
+                minLatencyMs, maxLatencyMs, operations, seed);
+    }
+
+    /**
+     * Returns a copy of this config with the given added latency range.
+     *
+     * @param minLatencyMs lower bound of the added latency
+     * @param maxLatencyMs upper bound of the added latency
+     * @return a new FaultInjection
+     */
+    public FaultInjection withLatencyMs(long minLatencyMs, long maxLatencyMs) {
+        return new FaultInjection(errorProbability, throttleProbability, timeoutProbability, timeoutMs,
+                minLatencyMs, maxLatencyMs, operations, seed);
+    }
+
+    /**
+     * Returns a copy of this config targeting only the given operations.
+     *
+     * @param first the first targeted operation
+     * @param rest  further targeted operations
+     * @return a new FaultInjection
+     */
+    public FaultInjection withOperations(Operation first, Operation... rest) {
+        return new FaultInjection(errorProbability, throttleProbability, timeoutProbability, timeoutMs,
+                minLatencyMs, maxLatencyMs, EnumSet.of(first, rest), seed);
+    }
+
+    /**
+     * Returns a copy of this config with the given random seed.
+     *
+     * @param seed seed of the native random generator
+     * @return a new FaultInjection
+     */
+    public FaultInjection withSeed(long seed) {
+        return new FaultInjection(errorProbability, throttleProbability, timeoutProbability, timeoutMs,
+                minLatencyMs, maxLatencyMs, operations, seed);
+    }
+
+    private static void requireProbability(String name, double probability) {
+        if (!(probability >= 0 && probability <= 1)) {
+            throw new IllegalArgumentException(name + " must be between 0 and 1");
+        }
+    }
+
+    /**
+     * Returns the targeted operations as a bit mask of ordinals, as read by the native layer.
+     *
+     * @return the operation mask
+     */
+    int operationMask() {
+        int mask = 0;
+        for (Operation operation : operations) {
+            mask |= 1 << operation.ordinal();
+        }
+        return mask;
+    }
+}
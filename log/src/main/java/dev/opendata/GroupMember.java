@@ -0,0 +1,172 @@
+package dev.opendata;
+
+import java.io.Closeable;
+import java.nio.ByteBuffer;
+import java.util.ArrayList;
+import java.util.LinkedHashMap;
+import java.util.List;
+import java.util.Map;
+
+/**
+ * Membership of one consumer in a group that shares a set of keys.
+ *
+ * <p>Members of a group heartbeat through a reserved key in the log and
+ * divide the group's keys among the live members, so several consumers can
+ * share keys (for example the partition keys of a topic) without external
+ * coordination. Every member must pass the same keys. A member that stops
+ * calling {@link #poll()} for longer than the session timeout is considered
+ * gone and its keys move to the remaining members.
+ *
+ * <p>All members of a group must use the same {@link LogDb}, since the log has
+ * a single writer. Assignment is eventually consistent: while members catch up
+ * on each other's heartbeats, a key may briefly be assigned to two members, so
+ * consumers should commit offsets in {@link RebalanceListener#onKeysRevoked}.
+ */
+public class GroupMember implements Closeable {
+
+    /**
+     * Default session timeout, matching Kafka's former {@code session.timeout.ms}.
+     */
+    public static final long DEFAULT_SESSION_TIMEOUT_MS = 10_000;
+
+    private final LogDb log;
+    private final String group;
+    private final String memberId;
+    private final byte[][] keys;
+    private final long sessionTimeoutMs;
+    private final RebalanceListener listener;
+    private Map<ByteBuffer, byte[]> assignment = new LinkedHashMap<>();
+    private boolean closed = false;
+
+    private GroupMember(LogDb log, String group, String memberId, byte[][] keys,
+                        long sessionTimeoutMs, RebalanceListener listener) {
+        this.log = log;
+        this.group = group;
+        this.memberId = memberId;
+        this.keys = keys;
+        this.sessionTimeoutMs = sessionTimeoutMs;
+        this.listener = listener;
+    }
+
+    /**
+     * Joins a consumer group with the default session timeout.
+     *
+     * @param log      the log coordinating the group
+     * @param group    the consumer group
+     * @param memberId this member's id, unique within the group
+     * @param keys     the keys shared by the group
+     * @param listener callback for assignment changes
+     * @return the joined member, with its initial assignment
+     */
+    public static GroupMember join(LogDb log, String group, String memberId, List<byte[]> keys,
+                                   RebalanceListener listener) {
+        return join(log, group, memberId, keys, DEFAULT_SESSION_TIMEOUT_MS, listener);
+    }
+
+    /**
+     * Joins a consumer group.
+     *
+     * @param log              the log coordinating the group
+     * @param group            the consumer group
+     * @param memberId         this member's id, unique within the group
+     * @param keys             the keys shared by the group
+     * @param sessionTimeoutMs how long this member stays live without polling
+     * @param listener         callback for assignment changes
+     * @return the joined member, with its initial assignment
+     */
+    public static GroupMember join(LogDb log, String group, String memberId, List<byte[]> keys,
+                                   long sessionTimeoutMs, RebalanceListener listener) {
+        if (log == null) {
+            throw new IllegalArgumentException("log must not be null");
+        }
+        if (group == null || group.isEmpty()) {
+            throw new IllegalArgumentException("group must not be null or empty");
+        }
+        if (memberId == null || memberId.isEmpty()) {
+            throw new IllegalArgumentException("memberId must not be null or empty");
+        }
+        if (keys == null || keys.contains(null)) {
+            throw new IllegalArgumentException("keys must not be null or contain null");
+        }
+        if (sessionTimeoutMs <= 0) {
+            throw new IllegalArgumentException("sessionTimeoutMs must be positive");
+        }
+        if (listener == null) {
+            throw new IllegalArgumentException("listener must not be null");
+        }
+        var member = new GroupMember(log, group, memberId, keys.toArray(new byte[0][]),
+                sessionTimeoutMs, listener);
+        member.poll();
+        return member;
+    }
+
+    /**
+     * Heartbeats and updates this member's assignment.
+     *
+     * <p>Call at least once per session timeout. If the assignment changed,
+     * the listener is notified of revoked keys first, then of assigned keys.
+     *
+     * @return the keys currently assigned to this member
+     */
+    public synchronized List<byte[]> poll() {
+        checkNotClosed();
+        int[] indexes = log.groupHeartbeat(group, memberId, keys, sessionTimeoutMs);
+        Map<ByteBuffer, byte[]> next = new LinkedHashMap<>();
+        for (int index : indexes) {
+            next.put(ByteBuffer.wrap(keys[index]), keys[index]);
+        }
+
+        List<byte[]> revoked = difference(assignment, next);
+        List<byte[]> assigned = difference(next, assignment);
+        assignment = next;
+        if (!revoked.isEmpty()) {
+            listener.onKeysRevoked(revoked);
+        }
+        if (!assigned.isEmpty()) {
+            listener.onKeysAssigned(assigned);
+        }
+        return assignment();
+    }
+
+    /**
+     * Returns the keys assigned to this member as of the last poll.
+     *
+     * @return the assigned keys
+     */
+    public synchronized List<byte[]> assignment() {
+        return List.copyOf(assignment.values());
+    }
+
+    /**
+     * Leaves the group, revoking this member's keys so other members can take
+     * them over on their next poll.
+     */
+    @Override
+    public synchronized void close() {
+        if (!closed) {
+            closed = true;
+            List<byte[]> revoked = assignment();
+            assignment = new LinkedHashMap<>();
+            if (!revoked.isEmpty()) {
+                listener.onKeysRevoked(revoked);
+            }
+            log.groupLeave(group, memberId);
+        }
+    }
+
+    private static List<byte[]> difference(Map<ByteBuffer, byte[]> a, Map<ByteBuffer, byte[]> b) {
+        List<byte[]> result = new ArrayList<>();
+        a.forEach((key, value) -> {
+            if (!b.containsKey(key)) {
+                result.add(value);
+            }
+        });
+        return result;
+    }
+
+    private void checkNotClosed() {
+        if (closed) {
+            throw new IllegalStateException("GroupMember is closed");
+        }
+    }
+}
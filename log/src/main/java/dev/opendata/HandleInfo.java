@@ -0,0 +1,26 @@
+package dev.opendata;
+
+/**
+ * A live native handle, as listed by {@link LogDb#dumpHandles()}.
+ *
+ * <p>Handles opened from the same call site share a
+ * {@code creationStackHash}, so grouping a dump by it shows which call site
+ * opens handles that are never closed.
+ *
+ * @param type              {@code "LogDb"} or {@code "LogDbReader"}
+ * @param creationStackHash hash of the Java stack that opened the handle, or
+ *                          0 if it was not recorded yet
+ * @param ageMs             milliseconds since the handle was opened
+ * @param appends           appends recorded since the last warmup mark
+ * @param scans             scans recorded since the last warmup mark
+ * @param tags              rendered tags of the handle, empty without tags
+ */
+public record HandleInfo(
+        String type,
+        int creationStackHash,
+        long ageMs,
+        long appends,
+        long scans,
+        String tags
+) {
+}
@@ -0,0 +1,16 @@
+package dev.opendata;
+
+/**
+ * Result of appending the records of one key within a grouped append.
+ *
+ * <p>The key's records take consecutive sequences, in the order they were
+ * passed, from {@code sequence}.
+ *
+ * @param key       the key the records were appended under
+ * @param sequence  the sequence assigned to the key's first record
+ * @param count     the number of records appended under the key
+ * @param timestamp the timestamp (epoch millis) of the key's first record
+ * @see LogDb#appendGrouped(Record[], AckMode)
+ */
+public record KeyAppendResult(byte[] key, long sequence, int count, long timestamp) {
+}
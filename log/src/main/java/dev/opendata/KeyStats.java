@@ -0,0 +1,41 @@
+package dev.opendata;
+
+/**
+ * Appends and scans of a key, as returned by {@link LogDb#hotKeys(int)}.
+ *
+ * <p>Counts start when the key was last taken into the statistics, see
+ * {@link LogDbConfig#withKeyStatsCapacity(int)}. Bytes are key and value
+ * bytes as stored, with timestamp headers and chunks.
+ *
+ * @param key             the key
+ * @param recordsAppended records appended, each chunk counted as a record
+ * @param bytesAppended   bytes of the records appended
+ * @param scans           scans that returned entries of the key
+ * @param entriesScanned  entries those scans returned
+ * @param bytesScanned    bytes of the entries scanned
+ * @param lastAppendMs    wall-clock time of the last append in milliseconds,
+ *                        or 0 if none was counted
+ * @param lastScanMs      wall-clock time of the last scan in milliseconds, or
+ *                        0 if none was counted
+ */
+public record KeyStats(
+        byte[] key,
+        long recordsAppended,
+        long bytesAppended,
+        long scans,
+        long entriesScanned,
+        long bytesScanned,
+        long lastAppendMs,
+        long lastScanMs
+) {
+
+    /**
+     * Returns the records appended plus the scans, by which
+     * {@link LogDb#hotKeys(int)} ranks keys.
+     *
+     * @return the key's operations
+     */
+    public long operations() {
+        return recordsAppended + scans;
+    }
+}
@@ -0,0 +1,10 @@
+package dev.opendata;
+
+/**
+ * How far a key is durable, as returned by {@link LogDb#sync()}.
+ *
+ * @param key  the key
+ * @param tail one past the key's last durable sequence
+ */
+public record KeyWatermark(byte[] key, long tail) {
+}
@@ -1,7 +1,24 @@
 package dev.opendata;
 
+import dev.opendata.common.StorageConfig;
 import java.io.Closeable;
+import java.lang.ref.Cleaner;
+import java.lang.ref.Reference;
+import java.nio.ByteBuffer;
+import java.nio.file.Path;
+import java.util.Collections;
+import java.util.EnumSet;
 import java.util.List;
+import java.util.Map;
+import java.util.OptionalLong;
+import java.util.Set;
+import java.util.TreeMap;
+import java.util.concurrent.CompletableFuture;
+import java.util.concurrent.CompletionException;
+import java.util.concurrent.ConcurrentHashMap;
+import java.util.function.Consumer;
+import java.util.function.IntConsumer;
+import java.util.function.LongConsumer;
 
 /**
  * Java binding for the OpenData LogDb trait.
@@ -12,6 +29,12 @@ import java.util.List;
  *
  * <p>Implements {@link LogRead} for read operations. For read-only access without
  * write capabilities, use {@link LogDbReader} instead.
+ *
+ * <p>A log that becomes unreachable without being closed is closed by a
+ * {@link Cleaner}, through {@link #closeAsync()}'s native close, so a leaked
+ * instance eventually releases its runtimes and native memory. Rings and
+ * pipelines of such a log are not reclaimed; close logs explicitly whenever
+ * possible.
  */
 public class LogDb implements Closeable, LogRead {
 
@@ -19,11 +42,32 @@ public class LogDb implements Closeable, LogRead {
         System.loadLibrary("opendata_log_jni");
     }
 
+    /**
+     * Returned by {@link #submitAppend(Record[], AckMode)} instead of a ticket
+     * when the write queue is full under {@link BackpressurePolicy#NOT_READY}.
+     */
+    public static final long NOT_READY = 0;
+
+    private static final Cleaner CLEANER = Cleaner.create();
+
     private final long handle;
+    private final boolean bridged;
+    private final Set<AppendRing> rings = ConcurrentHashMap.newKeySet();
+    private final Set<AppendPipeline> pipelines = ConcurrentHashMap.newKeySet();
+    private final Cleanup cleanup;
+    private final Cleaner.Cleanable cleanable;
     private volatile boolean closed = false;
 
-    private LogDb(long handle) {
+    LogDb(long handle) {
+        this(handle, NativeHandles.callerStackHash());
+    }
+
+    LogDb(long handle, int creationStackHash) {
         this.handle = handle;
+        this.bridged = nativeIsBridged(handle);
+        this.cleanup = new Cleanup(handle);
+        this.cleanable = CLEANER.register(this, cleanup);
+        nativeSetOrigin(handle, creationStackHash);
     }
 
     /**
@@ -36,13 +80,210 @@ public class LogDb implements Closeable, LogRead {
         if (config == null) {
             throw new IllegalArgumentException("config must not be null");
         }
-        long handle = nativeCreate(config);
+        long handle = nativeCreate(config, null);
+        if (handle == 0) {
+            throw new RuntimeException("Failed to create LogDb instance");
+        }
+        return new LogDb(handle);
+    }
+
+    /**
+     * Opens a LogDb instance, reporting the phases of the open.
+     *
+     * <p>The listener is called on the opening thread as each
+     * {@link OpenPhase} completes, so startup logs can show where a slow open
+     * spends its time. Exceptions thrown by the listener are ignored. Combine
+     * with {@link LogDbConfig#withOpenTimeout(long)} to fail opens that stall.
+     *
+     * @param config   the log configuration
+     * @param progress listener for the phases of the open
+     * @return a new LogDb instance
+     */
+    public static LogDb open(LogDbConfig config, Consumer<OpenPhase> progress) {
+        if (config == null) {
+            throw new IllegalArgumentException("config must not be null");
+        }
+        if (progress == null) {
+            throw new IllegalArgumentException("progress must not be null");
+        }
+        long handle = nativeCreate(config, phaseListener(progress));
+        if (handle == 0) {
+            throw new RuntimeException("Failed to create LogDb instance");
+        }
+        return new LogDb(handle);
+    }
+
+    /**
+     * Opens a LogDb instance without blocking the calling thread.
+     *
+     * <p>Opening SlateDB storage over an object store can take seconds; this
+     * lets several instances, for example one per benchmark worker, open
+     * concurrently. The config is validated before returning, and the open
+     * itself runs on a native thread that completes the returned future. If
+     * the future is cancelled, or completed by the caller, before the open
+     * finishes, the opened instance is closed.
+     *
+     * @param config the log configuration
+     * @return a future completed with the new LogDb instance, or exceptionally
+     *         with an {@link dev.opendata.common.OpenDataNativeException} if
+     *         the open fails
+     * @throws IllegalArgumentException if the config holds invalid settings
+     */
+    public static CompletableFuture<LogDb> openAsync(LogDbConfig config) {
+        if (config == null) {
+            throw new IllegalArgumentException("config must not be null");
+        }
+        return startOpen(config, null);
+    }
+
+    /**
+     * Opens a LogDb instance without blocking, reporting the phases of the open.
+     *
+     * <p>The listener is called on the native thread running the open.
+     *
+     * @param config   the log configuration
+     * @param progress listener for the phases of the open
+     * @return a future completed with the new LogDb instance
+     * @see #openAsync(LogDbConfig)
+     * @see #open(LogDbConfig, Consumer)
+     */
+    public static CompletableFuture<LogDb> openAsync(LogDbConfig config, Consumer<OpenPhase> progress) {
+        if (config == null) {
+            throw new IllegalArgumentException("config must not be null");
+        }
+        if (progress == null) {
+            throw new IllegalArgumentException("progress must not be null");
+        }
+        return startOpen(config, phaseListener(progress));
+    }
+
+    private static CompletableFuture<LogDb> startOpen(LogDbConfig config, IntConsumer progress) {
+        int creationStackHash = NativeHandles.callerStackHash();
+        CompletableFuture<Long> opened = new CompletableFuture<>();
+        nativeCreateAsync(config, progress, opened);
+        CompletableFuture<LogDb> result = new CompletableFuture<>();
+        opened.whenComplete((handle, error) -> {
+            if (error != null) {
+                result.completeExceptionally(error);
+                return;
+            }
+            LogDb log = new LogDb(handle, creationStackHash);
+            if (!result.complete(log)) {
+                log.close();
+            }
+        });
+        return result;
+    }
+
+    /**
+     * Opens a LogDb instance configured by a TOML or YAML file.
+     *
+     * <p>The file is parsed natively and holds the settings of a
+     * {@link LogDbConfig}, named in snake_case with nested records as tables,
+     * for example {@code bridge_threads = 4} or a {@code [storage]} table with
+     * {@code type = "slatedb"}, a {@code path} and an {@code object_store}.
+     * Omitted settings take the defaults of {@link LogDbConfig}, and unknown
+     * settings are rejected. Editing the file is enough to sweep a benchmark
+     * over settings without building config objects in Java. The format is
+     * chosen by the file's {@code .toml}, {@code .yaml} or {@code .yml}
+     * extension.
+     *
+     * @param configPath path of the config file
+     * @return a new LogDb instance
+     * @throws IllegalArgumentException if the file cannot be read or holds
+     *                                  invalid settings
+     */
+    public static LogDb open(Path configPath) {
+        if (configPath == null) {
+            throw new IllegalArgumentException("configPath must not be null");
+        }
+        long handle = nativeCreateFromFile(configPath.toString());
         if (handle == 0) {
             throw new RuntimeException("Failed to create LogDb instance");
         }
         return new LogDb(handle);
     }
 
+    /**
+     * Restores a backup into empty storage and opens a LogDb on it.
+     *
+     * <p>Every object of a backup written by
+     * {@link #backup(String, StorageConfig.SlateDb, BackupListener)} is copied
+     * into the storage of {@code config}, which must not hold any data yet,
+     * and the log is then opened there as by {@link #open(LogDbConfig)}. The
+     * backup is left as it was, so one backup can seed any number of
+     * identical logs, for example a benchmark dataset per run.
+     *
+     * @param config the configuration of the restored log; its storage must
+     *               be an empty {@link StorageConfig.SlateDb} path on a local
+     *               or S3 object store
+     * @param backup where the backup was written
+     * @return a new LogDb instance
+     * @throws dev.opendata.common.OpenDataNativeException if the storage
+     *               already holds data, there is no backup at {@code backup},
+     *               or copying or opening fails
+     */
+    public static LogDb restore(LogDbConfig config, StorageConfig.SlateDb backup) {
+        if (config == null) {
+            throw new IllegalArgumentException("config must not be null");
+        }
+        if (backup == null) {
+            throw new IllegalArgumentException("backup must not be null");
+        }
+        long handle = nativeRestore(config, backup);
+        if (handle == 0) {
+            throw new RuntimeException("Failed to restore LogDb instance");
+        }
+        return new LogDb(handle);
+    }
+
+    private static IntConsumer phaseListener(Consumer<OpenPhase> progress) {
+        OpenPhase[] phases = OpenPhase.values();
+        return ordinal -> progress.accept(phases[ordinal]);
+    }
+
+    /**
+     * Returns the build information of the loaded native library.
+     *
+     * <p>Compare {@link NativeVersion#binding()} against the JAR version, or
+     * call {@link NativeVersion#matchesJar()}, at startup to detect a shared
+     * library left over from another build.
+     *
+     * @return the native library's versions, commit, build profile and allocator
+     */
+    public static NativeVersion nativeVersion() {
+        String[] fields = nativeVersionFields();
+        return new NativeVersion(fields[0], fields[1], fields[2], fields[3], fields[4], fields[5]);
+    }
+
+    /**
+     * Returns the features supported by the loaded native library.
+     *
+     * <p>Lets callers degrade gracefully against an older library, for
+     * example by falling back to blocking appends without
+     * {@link NativeCapability#SUBMITTED_OPERATIONS}. A library that predates
+     * capability detection reports no capabilities, and capabilities of a
+     * newer library unknown to this JAR are left out. The library does not
+     * compress values, so no codecs are reported.
+     *
+     * @return an unmodifiable set of the supported capabilities
+     */
+    public static Set<NativeCapability> nativeCapabilities() {
+        long mask;
+        try {
+            mask = nativeCapabilityMask();
+        } catch (UnsatisfiedLinkError e) {
+            return Collections.emptySet();
+        }
+        Set<NativeCapability> capabilities = EnumSet.noneOf(NativeCapability.class);
+        for (NativeCapability capability : NativeCapability.values()) {
+            if ((mask & (1L << capability.ordinal())) != 0) {
+                capabilities.add(capability);
+            }
+        }
+        return Collections.unmodifiableSet(capabilities);
+    }
+
     /**
      * Opens a LogDb instance with in-memory storage (for testing).
      *
@@ -55,15 +296,150 @@ public class LogDb implements Closeable, LogRead {
     /**
      * Appends a batch of records to the log.
      *
-     * <p>This is a blocking call that returns when all records have been persisted.
-     * For better throughput, batch multiple records into a single call.
+     * <p>This is a blocking call that returns once the storage layer has accepted
+     * the records ({@link AckMode#BUFFERED}). For better throughput, batch
+     * multiple records into a single call.
      *
      * @param records the records to append
      * @return the result of the append operation (sequence of first record)
      */
     public AppendResult append(Record[] records) {
+        return append(records, AckMode.BUFFERED);
+    }
+
+    /**
+     * Appends a batch of records to the log with the given acknowledgement mode.
+     *
+     * <p>With {@link AckMode#DURABLE}, the call returns only after the records
+     * have been flushed to durable storage, and the recorded append latency
+     * includes the flush.
+     *
+     * <p>With a {@link LogDbConfig#memoryLimit() memory limit} configured,
+     * this and every other append blocks or throws while the handle holds
+     * the limit in native memory, according to its policy.
+     *
+     * @param records the records to append
+     * @param ackMode when the append is acknowledged
+     * @return the result of the append operation (sequence of first record)
+     * @throws dev.opendata.common.OpenDataBackpressureException if the memory
+     *         limit is reached under {@link BackpressurePolicy#REJECT}
+     */
+    public AppendResult append(Record[] records, AckMode ackMode) {
+        return append(records, ackMode, OperationPriority.FOREGROUND);
+    }
+
+    /**
+     * Appends a batch of records to the log with the given acknowledgement
+     * mode and priority.
+     *
+     * <p>A {@link OperationPriority#BACKGROUND} append, such as a backfill,
+     * waits natively while {@link LogDbConfig#backgroundConcurrency()} other
+     * background calls access storage. It always waits in the native call,
+     * even when {@link LogDbConfig#bridgeThreads() bridge threads} are
+     * configured.
+     *
+     * @param records  the records to append
+     * @param ackMode  when the append is acknowledged
+     * @param priority priority class of the append
+     * @return the result of the append operation (sequence of first record)
+     */
+    public AppendResult append(Record[] records, AckMode ackMode, OperationPriority priority) {
+        return append(records, ackMode, priority, null);
+    }
+
+    /**
+     * Appends a batch of records as part of a distributed trace.
+     *
+     * <p>The {@code traceparent} header of the calling request, in the W3C
+     * Trace Context format, is validated natively and attached to the JFR
+     * event and any slow-operation log line of the append, so the native
+     * time of a traced request can be found from its trace id. A traced
+     * append always runs in the native call, even when
+     * {@link LogDbConfig#bridgeThreads() bridge threads} are configured.
+     *
+     * @param records     the records to append
+     * @param ackMode     when the append is acknowledged
+     * @param priority    priority class of the append
+     * @param traceparent W3C traceparent of the call, or null if untraced
+     * @return the result of the append operation (sequence of first record)
+     * @throws IllegalArgumentException if {@code traceparent} is malformed
+     */
+    public AppendResult append(Record[] records, AckMode ackMode, OperationPriority priority, String traceparent) {
+        if (ackMode == null) {
+            throw new IllegalArgumentException("ackMode must not be null");
+        }
+        if (priority == null) {
+            throw new IllegalArgumentException("priority must not be null");
+        }
+        checkNotClosed();
+        try {
+            if (bridged && priority == OperationPriority.FOREGROUND && traceparent == null) {
+                CompletableFuture<AppendResult> result = new CompletableFuture<>();
+                nativeAppendBridged(handle, records, ackMode.ordinal(), result);
+                return awaitBridged(result);
+            }
+            return nativeAppend(handle, records, ackMode.ordinal(), priority.ordinal(), traceparent);
+        } finally {
+            Reference.reachabilityFence(this);
+        }
+    }
+
+    /**
+     * Appends a batch of records for any number of keys, grouped by key.
+     *
+     * <p>Unlike {@link #append(Record[], AckMode)}, which stores records in
+     * the order passed, the records are grouped natively so that each key's
+     * records, kept in the order passed, take consecutive sequences. A
+     * producer writing small batches to many keys can thereby append all of
+     * them in one call. The groups are written as one storage batch.
+     *
+     * @param records the records to append, for any number of keys
+     * @param ackMode when the append is acknowledged
+     * @return the result for each key, in order of each key's first record
+     */
+    public List<KeyAppendResult> appendGrouped(Record[] records, AckMode ackMode) {
+        if (records == null) {
+            throw new IllegalArgumentException("records must not be null");
+        }
+        if (ackMode == null) {
+            throw new IllegalArgumentException("ackMode must not be null");
+        }
+        checkNotClosed();
+        try {
+            return List.of(nativeAppendGrouped(handle, records, ackMode.ordinal()));
+        } finally {
+            Reference.reachabilityFence(this);
+        }
+    }
+
+    /**
+     * Appends a batch of records whose values are stored exactly as passed.
+     *
+     * <p>Unlike {@link #append(Record[], AckMode)}, no timestamp header is
+     * prepended and values are never stored as chunks, so data migrated from
+     * another store keeps its bytes and the cost of the envelope can be
+     * measured against it. {@link Record#timestampMs()} is ignored, and the
+     * result's timestamp is 0. Read such values back with
+     * {@link #scanRaw(byte[], long, int)}: other scans treat their first
+     * bytes as a timestamp header.
+     *
+     * @param records the records to append
+     * @param ackMode when the append is acknowledged
+     * @return the result of the append operation (sequence of first record)
+     */
+    public AppendResult appendRaw(Record[] records, AckMode ackMode) {
+        if (records == null) {
+            throw new IllegalArgumentException("records must not be null");
+        }
+        if (ackMode == null) {
+            throw new IllegalArgumentException("ackMode must not be null");
+        }
         checkNotClosed();
-        return nativeAppend(handle, records);
+        try {
+            return nativeAppendRaw(handle, records, ackMode.ordinal());
+        } finally {
+            Reference.reachabilityFence(this);
+        }
     }
 
     /**
@@ -80,47 +456,1804 @@ public class LogDb implements Closeable, LogRead {
         return append(new Record[]{new Record(key, value)});
     }
 
-    @Override
-    public List<LogEntry> scan(byte[] key, long startSequence, int maxEntries) {
+    /**
+     * Appends a single record to the log with the given acknowledgement mode.
+     *
+     * @param key     the key to append under
+     * @param value   the value to append
+     * @param ackMode when the append is acknowledged
+     * @return the result of the append operation
+     */
+    public AppendResult append(byte[] key, byte[] value, AckMode ackMode) {
+        return append(new Record[]{new Record(key, value)}, ackMode);
+    }
+
+    /**
+     * Appends the records of a FlatBuffers {@code AppendBatch}.
+     *
+     * <p>Tooling in other languages, and callers of the native library's C
+     * ABI, build batches from the same schema,
+     * {@code log/native/include/opendata_log_batch.fbs}, so a batch written
+     * once can be submitted through either. The batch is verified and decoded
+     * natively without creating a {@link Record} per entry. Records without a
+     * timestamp are stamped when the call starts.
+     *
+     * @param batch   the bytes of an {@code AppendBatch}
+     * @param ackMode when the append is acknowledged
+     * @return the result of the append operation (sequence of first record)
+     * @throws IllegalArgumentException if the batch is malformed or empty
+     */
+    public AppendResult appendFlatBuffer(byte[] batch, AckMode ackMode) {
+        if (batch == null) {
+            throw new IllegalArgumentException("batch must not be null");
+        }
+        if (ackMode == null) {
+            throw new IllegalArgumentException("ackMode must not be null");
+        }
         checkNotClosed();
-        LogEntry[] entries = nativeScan(handle, key, startSequence, maxEntries);
-        return entries != null ? List.of(entries) : List.of();
+        try {
+            return nativeAppendFlatBuffer(handle, batch, ackMode.ordinal());
+        } finally {
+            Reference.reachabilityFence(this);
+        }
     }
 
     /**
-     * Flushes all pending writes to durable storage.
+     * Appends records whose payloads are generated by the native layer.
      *
-     * <p>This ensures that all writes that have been acknowledged are persisted
-     * to durable storage. For SlateDB-backed storage, this flushes the memtable
-     * to the WAL and object store.
+     * <p>Benchmark mode: only the key and sizes cross JNI, so the call measures
+     * the storage engine's ceiling independent of Java-to-native copy cost.
+     * Every record in the batch shares one pseudo-random payload, and the
+     * timestamp is taken natively when the call starts.
+     *
+     * @param key         the key to append under
+     * @param payloadSize size of each payload in bytes
+     * @param count       number of records to append
+     * @return the result of the append operation (sequence of first record)
      */
-    public void flush() {
+    public AppendResult appendSynthetic(byte[] key, int payloadSize, int count) {
+        return appendSynthetic(key, payloadSize, count, AckMode.BUFFERED);
+    }
+
+    /**
+     * Appends natively generated records with the given acknowledgement mode.
+     *
+     * @param key         the key to append under
+     * @param payloadSize size of each payload in bytes
+     * @param count       number of records to append
+     * @param ackMode     when the append is acknowledged
+     * @return the result of the append operation (sequence of first record)
+     * @see #appendSynthetic(byte[], int, int)
+     */
+    public AppendResult appendSynthetic(byte[] key, int payloadSize, int count, AckMode ackMode) {
+        if (key == null) {
+            throw new IllegalArgumentException("key must not be null");
+        }
+        if (payloadSize < 0) {
+            throw new IllegalArgumentException("payloadSize must not be negative");
+        }
+        if (count <= 0) {
+            throw new IllegalArgumentException("count must be positive");
+        }
+        if (ackMode == null) {
+            throw new IllegalArgumentException("ackMode must not be null");
+        }
         checkNotClosed();
-        nativeFlush(handle);
+        try {
+            return nativeAppendSynthetic(handle, key, payloadSize, count, ackMode.ordinal());
+        } finally {
+            Reference.reachabilityFence(this);
+        }
     }
 
-    @Override
-    public void close() {
-        if (!closed) {
-            closed = true;
-            nativeClose(handle);
+    /**
+     * Runs a produce/consume benchmark entirely inside the native layer.
+     *
+     * <p>Records are produced and consumed by Rust code against this log, with
+     * no JNI calls per record. Comparing the report with the same workload
+     * driven from Java isolates the overhead of the binding. The benchmark
+     * writes to a fresh reserved key and blocks until every record has been
+     * read back.
+     *
+     * @param config the workload
+     * @return throughput and latency results
+     */
+    public BenchReport bench(BenchConfig config) {
+        if (config == null) {
+            throw new IllegalArgumentException("config must not be null");
+        }
+        checkNotClosed();
+        try {
+            return nativeBench(handle, config);
+        } finally {
+            Reference.reachabilityFence(this);
         }
     }
 
-    private void checkNotClosed() {
-        if (closed) {
-            throw new IllegalStateException("LogDb is closed");
+    /**
+     * Opens a shared-memory ring whose records are appended to this log.
+     *
+     * <p>Offering a record to the ring makes no JNI call in the common case;
+     * see {@link AppendRing}. The ring is closed, appending any records still
+     * in it, when this log is closed.
+     *
+     * @param capacityBytes data capacity of the ring, a power of two of at
+     *                      least {@link AppendRing#MIN_CAPACITY}
+     * @return the open ring
+     */
+    public AppendRing openAppendRing(int capacityBytes) {
+        if (capacityBytes < AppendRing.MIN_CAPACITY || Integer.bitCount(capacityBytes) != 1) {
+            throw new IllegalArgumentException(
+                    "capacityBytes must be a power of two of at least " + AppendRing.MIN_CAPACITY);
+        }
+        checkNotClosed();
+        try {
+            AppendRing ring = new AppendRing(this, nativeOpenAppendRing(handle, capacityBytes), capacityBytes);
+            rings.add(ring);
+            return ring;
+        } finally {
+            Reference.reachabilityFence(this);
         }
     }
 
-    long getHandle() {
-        return handle;
+    void unregisterRing(AppendRing ring) {
+        rings.remove(ring);
     }
 
-    // Native methods
-    private static native long nativeCreate(LogDbConfig config);
-    private static native AppendResult nativeAppend(long handle, Record[] records);
-    private static native LogEntry[] nativeScan(long handle, byte[] key, long startSequence, long maxEntries);
-    private static native void nativeFlush(long handle);
-    private static native void nativeClose(long handle);
+    /**
+     * Opens a pipeline keeping up to {@code maxInFlight} append batches in
+     * flight while acknowledging them in sequence order.
+     *
+     * <p>See {@link AppendPipeline}. The pipeline is closed, completing the
+     * batches still in flight, when this log is closed.
+     *
+     * @param maxInFlight batches the pipeline may keep in flight, between 1
+     *                    and {@link AppendPipeline#MAX_IN_FLIGHT}
+     * @return the open pipeline
+     */
+    public AppendPipeline openPipeline(int maxInFlight) {
+        if (maxInFlight < 1 || maxInFlight > AppendPipeline.MAX_IN_FLIGHT) {
+            throw new IllegalArgumentException(
+                    "maxInFlight must be between 1 and " + AppendPipeline.MAX_IN_FLIGHT);
+        }
+        checkNotClosed();
+        try {
+            AppendPipeline pipeline = new AppendPipeline(this, nativeOpenPipeline(handle, maxInFlight), maxInFlight);
+            pipelines.add(pipeline);
+            return pipeline;
+        } finally {
+            Reference.reachabilityFence(this);
+        }
+    }
+
+    void unregisterPipeline(AppendPipeline pipeline) {
+        pipelines.remove(pipeline);
+    }
+
+    /**
+     * Returns the partition a key is assigned to.
+     *
+     * <p>Uses the murmur2 hash of Kafka's default partitioner, so keys map to
+     * the same partition numbers as for a Kafka producer. This is the
+     * assignment {@link #appendPartitioned(String, Record[], int)} applies.
+     *
+     * @param key           the routing key
+     * @param numPartitions the number of partitions
+     * @return the partition, between 0 and {@code numPartitions - 1}
+     */
+    public static int partitionForKey(byte[] key, int numPartitions) {
+        if (key == null) {
+            throw new IllegalArgumentException("key must not be null");
+        }
+        if (numPartitions <= 0) {
+            throw new IllegalArgumentException("numPartitions must be positive");
+        }
+        return nativePartitionForKey(key, numPartitions);
+    }
+
+    /**
+     * Appends a batch of records, routing each to a partition of a topic.
+     *
+     * <p>Each record's key selects a partition with
+     * {@link #partitionForKey(byte[], int)}, and the record is stored under the
+     * key {@code <topic>-<partition>} (UTF-8) instead of its own key.
+     *
+     * @param topic         the topic prefixing the partition keys
+     * @param records       the records to append
+     * @param numPartitions the number of partitions
+     * @return the result of the append operation (sequence of first record)
+     */
+    public AppendResult appendPartitioned(String topic, Record[] records, int numPartitions) {
+        return appendPartitioned(topic, records, numPartitions, AckMode.BUFFERED);
+    }
+
+    /**
+     * Appends a batch of records routed to topic partitions with the given
+     * acknowledgement mode.
+     *
+     * @param topic         the topic prefixing the partition keys
+     * @param records       the records to append
+     * @param numPartitions the number of partitions
+     * @param ackMode       when the append is acknowledged
+     * @return the result of the append operation (sequence of first record)
+     * @see #appendPartitioned(String, Record[], int)
+     */
+    public AppendResult appendPartitioned(String topic, Record[] records, int numPartitions, AckMode ackMode) {
+        if (topic == null || topic.isEmpty()) {
+            throw new IllegalArgumentException("topic must not be null or empty");
+        }
+        if (numPartitions <= 0) {
+            throw new IllegalArgumentException("numPartitions must be positive");
+        }
+        if (ackMode == null) {
+            throw new IllegalArgumentException("ackMode must not be null");
+        }
+        checkNotClosed();
+        try {
+            return nativeAppendPartitioned(handle, topic, records, numPartitions, ackMode.ordinal());
+        } finally {
+            Reference.reachabilityFence(this);
+        }
+    }
+
+    /**
+     * Appends a batch of records at most once per producer batch.
+     *
+     * <p>The batch is identified by the producer ID, the batch sequence, and the
+     * key of its first record. If the same batch was appended recently (within
+     * {@link LogDbConfig#dedupWindow()} batches for that producer and key), it is
+     * not appended again and the result of the original append is returned, so
+     * retrying after a timeout does not create duplicates. Batch sequences should
+     * increase for each new batch a producer sends.
+     *
+     * <p>Dedup state is held in memory and does not survive closing the LogDb.
+     *
+     * @param records       the records to append
+     * @param producerId    identity of the producer sending the batch
+     * @param batchSequence the producer's sequence number for this batch
+     * @return the result of the (original) append operation
+     * @throws IllegalStateException if the batch sequence is older than the dedup window
+     */
+    public AppendResult appendIdempotent(Record[] records, long producerId, long batchSequence) {
+        return appendIdempotent(records, producerId, batchSequence, AckMode.BUFFERED);
+    }
+
+    /**
+     * Appends a batch of records at most once per producer batch with the given
+     * acknowledgement mode.
+     *
+     * @param records       the records to append
+     * @param producerId    identity of the producer sending the batch
+     * @param batchSequence the producer's sequence number for this batch
+     * @param ackMode       when the append is acknowledged
+     * @return the result of the (original) append operation
+     * @see #appendIdempotent(Record[], long, long)
+     */
+    public AppendResult appendIdempotent(Record[] records, long producerId, long batchSequence, AckMode ackMode) {
+        if (batchSequence < 0) {
+            throw new IllegalArgumentException("batchSequence must not be negative");
+        }
+        if (ackMode == null) {
+            throw new IllegalArgumentException("ackMode must not be null");
+        }
+        checkNotClosed();
+        try {
+            return nativeAppendIdempotent(handle, records, producerId, batchSequence, ackMode.ordinal());
+        } finally {
+            Reference.reachabilityFence(this);
+        }
+    }
+
+    /**
+     * Appends a batch of records only if a key has not advanced past an expected sequence.
+     *
+     * <p>The append succeeds only if {@code key} has no entries with a sequence
+     * greater than {@code expectedTailSequence}; pass -1 to require that the key
+     * has no entries at all. The check and the append are atomic with respect to
+     * all other appends through this LogDb, enabling optimistic concurrency for
+     * coordination built on the log. The records themselves may use any key.
+     *
+     * @param key                  the key whose tail is checked
+     * @param records              the records to append
+     * @param expectedTailSequence the last sequence observed for the key, or -1
+     * @return the result of the append operation (sequence of first record)
+     * @throws SequenceConflictException if the key has an entry past the expected tail
+     */
+    public AppendResult appendIf(byte[] key, Record[] records, long expectedTailSequence) {
+        return appendIf(key, records, expectedTailSequence, AckMode.BUFFERED);
+    }
+
+    /**
+     * Conditionally appends a batch of records with the given acknowledgement mode.
+     *
+     * @param key                  the key whose tail is checked
+     * @param records              the records to append
+     * @param expectedTailSequence the last sequence observed for the key, or -1
+     * @param ackMode              when the append is acknowledged
+     * @return the result of the append operation (sequence of first record)
+     * @throws SequenceConflictException if the key has an entry past the expected tail
+     * @see #appendIf(byte[], Record[], long)
+     */
+    public AppendResult appendIf(byte[] key, Record[] records, long expectedTailSequence, AckMode ackMode) {
+        if (key == null) {
+            throw new IllegalArgumentException("key must not be null");
+        }
+        if (expectedTailSequence < -1) {
+            throw new IllegalArgumentException("expectedTailSequence must be >= -1");
+        }
+        if (ackMode == null) {
+            throw new IllegalArgumentException("ackMode must not be null");
+        }
+        checkNotClosed();
+        try {
+            return nativeAppendIf(handle, key, records, expectedTailSequence, ackMode.ordinal());
+        } finally {
+            Reference.reachabilityFence(this);
+        }
+    }
+
+    @Override
+    public List<LogEntry> scan(byte[] key, long startSequence, int maxEntries) {
+        return scan(key, startSequence, maxEntries, OperationPriority.FOREGROUND);
+    }
+
+    /**
+     * Scans entries for a key with the given priority.
+     *
+     * <p>A {@link OperationPriority#BACKGROUND} scan, such as a verification
+     * pass over a key, waits natively while
+     * {@link LogDbConfig#backgroundConcurrency()} other background calls
+     * access storage. It always waits in the native call, even when
+     * {@link LogDbConfig#bridgeThreads() bridge threads} are configured.
+     *
+     * @param key           the key to scan
+     * @param startSequence the sequence to start from (inclusive)
+     * @param maxEntries    maximum number of entries to return
+     * @param priority      priority class of the scan
+     * @return the entries scanned
+     */
+    public List<LogEntry> scan(byte[] key, long startSequence, int maxEntries, OperationPriority priority) {
+        return scan(key, startSequence, maxEntries, priority, null);
+    }
+
+    /**
+     * Scans entries for a key as part of a distributed trace.
+     *
+     * <p>As {@link #append(Record[], AckMode, OperationPriority, String)},
+     * the traceparent is attached to the JFR event and any slow-operation
+     * log line of the scan, which always runs in the native call.
+     *
+     * @param key           the key to scan
+     * @param startSequence the sequence to start from (inclusive)
+     * @param maxEntries    maximum number of entries to return
+     * @param priority      priority class of the scan
+     * @param traceparent   W3C traceparent of the call, or null if untraced
+     * @return the entries scanned
+     * @throws IllegalArgumentException if {@code traceparent} is malformed
+     */
+    public List<LogEntry> scan(
+            byte[] key, long startSequence, int maxEntries, OperationPriority priority, String traceparent) {
+        if (priority == null) {
+            throw new IllegalArgumentException("priority must not be null");
+        }
+        checkNotClosed();
+        try {
+            LogEntry[] entries;
+            if (bridged && priority == OperationPriority.FOREGROUND && traceparent == null) {
+                CompletableFuture<LogEntry[]> result = new CompletableFuture<>();
+                nativeScanBridged(handle, key, startSequence, maxEntries, result);
+                entries = awaitBridged(result);
+            } else {
+                entries = nativeScan(handle, key, startSequence, maxEntries, priority.ordinal(), traceparent);
+            }
+            return entries != null ? List.of(entries) : List.of();
+        } finally {
+            Reference.reachabilityFence(this);
+        }
+    }
+
+    @Override
+    public List<LogEntry> scan(byte[] key, long startSequence, int maxEntries, ScanFilter filter) {
+        if (key == null) {
+            throw new IllegalArgumentException("key must not be null");
+        }
+        if (filter == null) {
+            throw new IllegalArgumentException("filter must not be null");
+        }
+        checkNotClosed();
+        try {
+            LogEntry[] entries = nativeScanFiltered(handle, key, startSequence, maxEntries, filter);
+            return entries != null ? List.of(entries) : List.of();
+        } finally {
+            Reference.reachabilityFence(this);
+        }
+    }
+
+    @Override
+    public List<EntryMetadata> scanMetadata(byte[] key, long startSequence, int maxEntries) {
+        if (key == null) {
+            throw new IllegalArgumentException("key must not be null");
+        }
+        checkNotClosed();
+        try {
+            return EntryMetadata.fromNative(nativeScanMetadata(handle, key, startSequence, maxEntries));
+        } finally {
+            Reference.reachabilityFence(this);
+        }
+    }
+
+    /**
+     * Scans entries for a key, returning their values exactly as stored.
+     *
+     * <p>Unlike {@link #scan(byte[], long, int)}, timestamp headers are not
+     * stripped and values stored as chunks are returned chunk by chunk.
+     * Every entry's timestamp is 0. Raw scans always read storage, bypassing
+     * the tail cache.
+     *
+     * @param key           the key to scan
+     * @param startSequence the sequence to start from (inclusive)
+     * @param maxEntries    maximum number of entries to return
+     * @return the entries as stored
+     */
+    public List<LogEntry> scanRaw(byte[] key, long startSequence, int maxEntries) {
+        if (key == null) {
+            throw new IllegalArgumentException("key must not be null");
+        }
+        checkNotClosed();
+        try {
+            LogEntry[] entries = nativeScanRaw(handle, key, startSequence, maxEntries);
+            return entries != null ? List.of(entries) : List.of();
+        } finally {
+            Reference.reachabilityFence(this);
+        }
+    }
+
+    /**
+     * Scans entries for a key into caller-owned buffers.
+     *
+     * <p>Unlike {@link #scan(byte[], long, int)}, no objects are created per
+     * entry, so a consumer reusing the same buffer and offsets array across
+     * fetches generates no garbage in steady state. Entries are written from
+     * the start of {@code dest}, which is cleared and then limited to the
+     * bytes written. Each entry is its sequence and timestamp as big-endian
+     * longs followed by its payload; entry {@code i} starts at
+     * {@code offsets[i]} and ends at {@code offsets[i + 1]}, so its payload
+     * is {@code offsets[i + 1] - offsets[i] - 16} bytes. Keys are not
+     * written.
+     *
+     * <p>At most {@code offsets.length - 1} entries are scanned, and the scan
+     * stops at the first entry not fitting in {@code dest}; continue from
+     * the sequence after the last entry returned.
+     *
+     * @param key           the key to scan
+     * @param startSequence the sequence to start from
+     * @param dest          a direct buffer receiving the entries
+     * @param offsets       receives the offset of each entry in {@code dest},
+     *                      followed by the end of the last; at least 2 elements long
+     * @return the number of entries written
+     * @throws IllegalArgumentException if the first entry found does not fit
+     *                      in {@code dest}
+     */
+    public int scanInto(byte[] key, long startSequence, ByteBuffer dest, int[] offsets) {
+        if (key == null) {
+            throw new IllegalArgumentException("key must not be null");
+        }
+        if (dest == null || !dest.isDirect()) {
+            throw new IllegalArgumentException("dest must be a direct buffer");
+        }
+        if (offsets == null || offsets.length < 2) {
+            throw new IllegalArgumentException("offsets must hold at least 2 elements");
+        }
+        checkNotClosed();
+        try {
+            int count = nativeScanInto(handle, key, startSequence, dest, offsets);
+            dest.clear().limit(count > 0 ? offsets[count] : 0);
+            return count;
+        } finally {
+            Reference.reachabilityFence(this);
+        }
+    }
+
+    /**
+     * Scans entries for a key into a FlatBuffers {@code ScanResult}.
+     *
+     * <p>The result follows the schema
+     * {@code log/native/include/opendata_log_batch.fbs} shared with the
+     * native library's C ABI; values are returned without the timestamp
+     * header, which is carried in each entry's {@code timestamp_ms}.
+     *
+     * @param key           the key to scan
+     * @param startSequence the sequence to start from
+     * @param maxEntries    the most entries to scan
+     * @return the bytes of a {@code ScanResult}
+     */
+    public byte[] scanFlatBuffer(byte[] key, long startSequence, int maxEntries) {
+        if (key == null) {
+            throw new IllegalArgumentException("key must not be null");
+        }
+        checkNotClosed();
+        try {
+            return nativeScanFlatBuffer(handle, key, startSequence, maxEntries);
+        } finally {
+            Reference.reachabilityFence(this);
+        }
+    }
+
+    /**
+     * Scans entries for a key into an Arrow record batch.
+     *
+     * <p>The batch is built natively and exported through the Arrow C Data
+     * Interface, so Arrow Java imports it without copying the entries again.
+     * It is a struct array with the columns {@code sequence} (int64),
+     * {@code timestamp} (timestamp in milliseconds, UTC), {@code key}
+     * (binary) and {@code value} (large binary). This binding does not
+     * depend on Arrow; allocate the structs with {@code arrow-c-data} and
+     * import the batch once this returns:
+     *
+     * <pre>{@code
+     * try (ArrowArray array = ArrowArray.allocateNew(allocator);
+     *      ArrowSchema schema = ArrowSchema.allocateNew(allocator)) {
+     *     log.scanArrow(key, start, 1000, array.memoryAddress(), schema.memoryAddress());
+     *     try (VectorSchemaRoot root = Data.importVectorSchemaRoot(allocator, array, schema, null)) {
+     *         ...
+     *     }
+     * }
+     * }</pre>
+     *
+     * <p>The native buffers are freed when the imported vectors are closed.
+     *
+     * @param key           the key to scan
+     * @param startSequence the sequence to start from
+     * @param maxEntries    the most entries to scan
+     * @param arrayAddress  address of an empty {@code ArrowArray} struct
+     * @param schemaAddress address of an empty {@code ArrowSchema} struct
+     * @return the number of entries in the batch
+     */
+    public int scanArrow(byte[] key, long startSequence, int maxEntries, long arrayAddress, long schemaAddress) {
+        if (key == null) {
+            throw new IllegalArgumentException("key must not be null");
+        }
+        if (arrayAddress == 0 || schemaAddress == 0) {
+            throw new IllegalArgumentException("arrayAddress and schemaAddress must not be 0");
+        }
+        checkNotClosed();
+        try {
+            return nativeScanArrow(handle, key, startSequence, maxEntries, arrayAddress, schemaAddress);
+        } finally {
+            Reference.reachabilityFence(this);
+        }
+    }
+
+    /**
+     * Returns a uniform sample of the entries of a key in a sequence range.
+     *
+     * <p>Each entry is kept with probability {@code rate}, so payload
+     * integrity can be spot-checked across a large backlog without scanning
+     * all of it; sparse samples seek to the entries kept rather than read
+     * the whole range. The same seed reproduces the same sample of
+     * unchanged entries.
+     *
+     * @param key           the key to sample
+     * @param startSequence the first sequence to sample
+     * @param endSequence   the sequence to stop before, or {@link Long#MAX_VALUE}
+     *                      for the rest of the key
+     * @param rate          the probability of keeping each entry, in [0, 1]
+     * @param seed          the seed of the random draws
+     * @return the sampled entries in sequence order
+     */
+    public List<LogEntry> scanSample(byte[] key, long startSequence, long endSequence, double rate, long seed) {
+        if (key == null) {
+            throw new IllegalArgumentException("key must not be null");
+        }
+        if (startSequence < 0) {
+            throw new IllegalArgumentException("startSequence must be >= 0");
+        }
+        if (endSequence < startSequence) {
+            throw new IllegalArgumentException("endSequence must be >= startSequence");
+        }
+        if (!(rate >= 0.0 && rate <= 1.0)) {
+            throw new IllegalArgumentException("rate must be in [0, 1]");
+        }
+        checkNotClosed();
+        try {
+            LogEntry[] entries = nativeScanSample(handle, key, startSequence, endSequence, rate, seed);
+            return entries != null ? List.of(entries) : List.of();
+        } finally {
+            Reference.reachabilityFence(this);
+        }
+    }
+
+    /**
+     * Returns a uniform sample of the entries of a key in a sequence range,
+     * drawn with a fresh seed.
+     *
+     * @see #scanSample(byte[], long, long, double, long)
+     */
+    public List<LogEntry> scanSample(byte[] key, long startSequence, long endSequence, double rate) {
+        return scanSample(key, startSequence, endSequence, rate, System.nanoTime());
+    }
+
+    /**
+     * Writes the entries of a key in a sequence range to a local file.
+     *
+     * <p>Entries are streamed from storage into the file natively, without
+     * being copied into the JVM, so whole keys can be dumped for debugging or
+     * to verify benchmark payloads. The file is written under a temporary
+     * name and replaces {@code path} only once complete.
+     *
+     * @param key           the key to export
+     * @param startSequence the first sequence to export
+     * @param endSequence   the sequence to stop before, or {@link Long#MAX_VALUE}
+     *                      for the rest of the key
+     * @param path          the file to write
+     * @param format        the layout of the file
+     * @return the number of entries written
+     * @throws dev.opendata.common.OpenDataNativeException if scanning or
+     *                      writing fails
+     */
+    public long export(byte[] key, long startSequence, long endSequence, Path path, ExportFormat format) {
+        if (key == null) {
+            throw new IllegalArgumentException("key must not be null");
+        }
+        if (startSequence < 0) {
+            throw new IllegalArgumentException("startSequence must be >= 0");
+        }
+        if (endSequence < startSequence) {
+            throw new IllegalArgumentException("endSequence must be >= startSequence");
+        }
+        if (path == null) {
+            throw new IllegalArgumentException("path must not be null");
+        }
+        if (format == null) {
+            throw new IllegalArgumentException("format must not be null");
+        }
+        checkNotClosed();
+        try {
+            return nativeExport(handle, key, startSequence, endSequence,
+                    path.toAbsolutePath().toString(), format.ordinal());
+        } finally {
+            Reference.reachabilityFence(this);
+        }
+    }
+
+    /**
+     * Appends the entries of a file written by
+     * {@link #export(byte[], long, long, Path, ExportFormat)}, keeping the
+     * keys the file records.
+     *
+     * @param path   the file to read
+     * @param format the layout of the file; must be {@link ExportFormat#NDJSON}
+     * @return the number of entries appended
+     * @see #importFile(Path, ExportFormat, byte[])
+     */
+    public long importFile(Path path, ExportFormat format) {
+        return importFile(path, format, null);
+    }
+
+    /**
+     * Appends the entries of a file written by
+     * {@link #export(byte[], long, long, Path, ExportFormat)}.
+     *
+     * <p>The file is read and appended natively in batches, without
+     * marshalling records through Java, so backlogs can be pre-populated
+     * quickly. Entries get new sequences. NDJSON entries keep their
+     * timestamps, and their keys unless {@code key} is given; raw payloads
+     * carry neither, so they are appended under {@code key} with the time of
+     * the import. Batches are appended within the memory and produce rate
+     * limits of this log, and flushed to durable storage before returning. A
+     * failure partway leaves the batches already appended in place.
+     *
+     * @param path   the file to read
+     * @param format the layout of the file
+     * @param key    the key to append every entry under, or null to keep the
+     *               keys of an NDJSON file
+     * @return the number of entries appended
+     * @throws dev.opendata.common.OpenDataNativeException if reading,
+     *               appending or flushing fails
+     */
+    public long importFile(Path path, ExportFormat format, byte[] key) {
+        if (path == null) {
+            throw new IllegalArgumentException("path must not be null");
+        }
+        if (format == null) {
+            throw new IllegalArgumentException("format must not be null");
+        }
+        if (format == ExportFormat.RAW && key == null) {
+            throw new IllegalArgumentException("key must not be null for raw payloads");
+        }
+        checkNotClosed();
+        try {
+            return nativeImport(handle, path.toAbsolutePath().toString(), format.ordinal(), key);
+        } finally {
+            Reference.reachabilityFence(this);
+        }
+    }
+
+    /**
+     * Checkpoints the log's storage under a name, until the checkpoint is
+     * deleted.
+     *
+     * @param name the name of the checkpoint
+     * @return the checkpoint
+     * @see #checkpoint(String, long)
+     */
+    public Checkpoint checkpoint(String name) {
+        return checkpoint(name, 0);
+    }
+
+    /**
+     * Checkpoints the log's storage under a name.
+     *
+     * <p>Pending writes are flushed first, so the checkpoint holds every
+     * append acknowledged before the call. The checkpoint is written to the
+     * SlateDB manifest in the object store, next to the log's data, and pins
+     * that data against compaction and garbage collection until it expires
+     * or is {@link #deleteCheckpoint(String) deleted}. A log opened or read
+     * from it sees the log as it stood at the checkpoint, which makes
+     * point-in-time recovery repeatable.
+     *
+     * <p>Requires {@link dev.opendata.common.StorageConfig.SlateDb} storage on
+     * a local or S3 object store; an in-memory object store cannot be reached
+     * outside the log.
+     *
+     * @param name       the name of the checkpoint
+     * @param lifetimeMs how long the checkpoint lives, in milliseconds, or 0 to
+     *                   keep it until deleted
+     * @return the checkpoint
+     * @throws dev.opendata.common.OpenDataNativeException if flushing or
+     *                   writing the checkpoint fails, or the storage does not
+     *                   support checkpoints
+     */
+    public Checkpoint checkpoint(String name, long lifetimeMs) {
+        if (name == null || name.isBlank()) {
+            throw new IllegalArgumentException("name must not be null or blank");
+        }
+        if (lifetimeMs < 0) {
+            throw new IllegalArgumentException("lifetimeMs must not be negative");
+        }
+        checkNotClosed();
+        try {
+            return nativeCheckpoint(handle, name, lifetimeMs);
+        } finally {
+            Reference.reachabilityFence(this);
+        }
+    }
+
+    /**
+     * Lists the checkpoints of the log's storage that have not expired.
+     *
+     * <p>Checkpoints taken by other processes on the same storage, and by
+     * SlateDB itself for readers and clones, are listed too.
+     *
+     * @return the checkpoints, oldest first
+     * @throws dev.opendata.common.OpenDataNativeException if reading the
+     *         manifest fails, or the storage does not support checkpoints
+     */
+    public List<Checkpoint> listCheckpoints() {
+        checkNotClosed();
+        try {
+            return List.of(nativeListCheckpoints(handle));
+        } finally {
+            Reference.reachabilityFence(this);
+        }
+    }
+
+    /**
+     * Deletes a checkpoint of the log's storage, releasing the data it pins
+     * to garbage collection.
+     *
+     * @param id the {@link Checkpoint#id() id} of the checkpoint
+     * @throws dev.opendata.common.OpenDataNativeException if updating the
+     *           manifest fails, or the storage does not support checkpoints
+     */
+    public void deleteCheckpoint(String id) {
+        if (id == null) {
+            throw new IllegalArgumentException("id must not be null");
+        }
+        checkNotClosed();
+        try {
+            nativeDeleteCheckpoint(handle, id);
+        } finally {
+            Reference.reachabilityFence(this);
+        }
+    }
+
+    /**
+     * Copies the log's storage as of a checkpoint to another object store.
+     *
+     * @param checkpointId the {@link Checkpoint#id() id} of the checkpoint
+     * @param destination  where to copy the storage
+     * @return the number of objects copied
+     * @see #backup(String, StorageConfig.SlateDb, BackupListener)
+     */
+    public long backup(String checkpointId, StorageConfig.SlateDb destination) {
+        return backup(checkpointId, destination, null);
+    }
+
+    /**
+     * Copies the log's storage as of a checkpoint to another object store,
+     * such as a bucket in a second region, for disaster-recovery drills.
+     *
+     * <p>The objects of the checkpoint are copied one by one to
+     * {@code destination}'s path in its object store, while the log stays
+     * open: the checkpoint keeps them from being compacted away meanwhile.
+     * Data written after the checkpoint is left out, so the copy opens, with
+     * {@link #restore(LogDbConfig, StorageConfig.SlateDb)} or directly, as
+     * the log stood at the checkpoint. Only the path and object store of
+     * {@code destination} are used; its path should be empty. A failure
+     * partway leaves the objects already copied in place.
+     *
+     * @param checkpointId the {@link Checkpoint#id() id} of the checkpoint
+     * @param destination  where to copy the storage
+     * @param listener     told the progress of the copy, or null
+     * @return the number of objects copied
+     * @throws dev.opendata.common.OpenDataNativeException if either storage
+     *                     does not support backups, the checkpoint does not
+     *                     exist or copying fails
+     */
+    public long backup(String checkpointId, StorageConfig.SlateDb destination, BackupListener listener) {
+        if (checkpointId == null) {
+            throw new IllegalArgumentException("checkpointId must not be null");
+        }
+        if (destination == null) {
+            throw new IllegalArgumentException("destination must not be null");
+        }
+        checkNotClosed();
+        try {
+            return nativeBackup(handle, checkpointId, destination, listener);
+        } finally {
+            Reference.reachabilityFence(this);
+        }
+    }
+
+    /**
+     * Clones the log's storage to a new path of the same object store.
+     *
+     * <p>Pending writes are flushed first, so the clone holds every append
+     * acknowledged before the call. The clone is made at the metadata level:
+     * SlateDB writes a manifest at {@code path} that refers to the data of
+     * this log through a checkpoint, so cloning takes the same time however
+     * much the log holds. Open the clone with this log's
+     * {@link StorageConfig.SlateDb} config and {@code path} in place of its
+     * path; writes to either log are not seen by the other, which lets
+     * destructive tests run against a fork while this log stays a golden
+     * dataset. The clone needs this log's data, so keep this log's storage
+     * for as long as the clone is used.
+     *
+     * @param path the path of the clone in the log's object store; must not
+     *             hold a log yet
+     * @throws dev.opendata.common.OpenDataNativeException if flushing or
+     *             cloning fails, or the storage does not support clones
+     */
+    public void cloneTo(String path) {
+        if (path == null || path.isBlank()) {
+            throw new IllegalArgumentException("path must not be null or blank");
+        }
+        checkNotClosed();
+        try {
+            nativeClone(handle, path);
+        } finally {
+            Reference.reachabilityFence(this);
+        }
+    }
+
+    /**
+     * Verifies the entries stored for a key.
+     *
+     * @param key the key to verify
+     * @return the verification report
+     * @see #verify(byte[], long, LongConsumer)
+     */
+    public VerifyReport verify(byte[] key) {
+        return verify(key, 0, null);
+    }
+
+    /**
+     * Verifies the entries stored for a key, for forensics after an incident.
+     *
+     * <p>Entries are scanned and checked natively, without being copied into
+     * the JVM; {@link VerifyReport} lists the checks. Checksums of stored
+     * blocks are verified by SlateDB as they are read, and a read that fails
+     * ends the verification with {@link VerifyReport#error()} set rather than
+     * throwing, so the entries verified up to that point are still reported.
+     * The call blocks until the key has been read; run it on a thread of its
+     * own to verify in the background.
+     *
+     * @param key           the key to verify
+     * @param startSequence the first sequence to verify
+     * @param progress      told the number of entries verified so far, every
+     *                      10,000 entries, on the calling thread; may be null
+     * @return the verification report
+     */
+    public VerifyReport verify(byte[] key, long startSequence, LongConsumer progress) {
+        if (key == null) {
+            throw new IllegalArgumentException("key must not be null");
+        }
+        if (startSequence < 0) {
+            throw new IllegalArgumentException("startSequence must be >= 0");
+        }
+        checkNotClosed();
+        try {
+            return nativeVerify(handle, key, startSequence, progress);
+        } finally {
+            Reference.reachabilityFence(this);
+        }
+    }
+
+    /**
+     * Reports gaps and repeated payloads among the entries of a key.
+     *
+     * <p>The key is scanned natively and only the report crosses into the
+     * JVM, so benchmark results can be validated without fetching every
+     * entry. Finding duplicates keeps a hash per distinct payload in native
+     * memory, about 16 bytes per entry plus map overhead, until the call
+     * returns. As with {@link #verify(byte[], long, LongConsumer)}, a read
+     * that fails ends the analysis with {@link SequenceReport#error()} set.
+     *
+     * @param key           the key to analyze
+     * @param startSequence the first sequence to analyze
+     * @return the sequence report
+     */
+    public SequenceReport analyzeSequences(byte[] key, long startSequence) {
+        if (key == null) {
+            throw new IllegalArgumentException("key must not be null");
+        }
+        if (startSequence < 0) {
+            throw new IllegalArgumentException("startSequence must be >= 0");
+        }
+        checkNotClosed();
+        try {
+            return nativeAnalyzeSequences(handle, key, startSequence);
+        } finally {
+            Reference.reachabilityFence(this);
+        }
+    }
+
+    /**
+     * Submits an append without waiting for it to complete.
+     *
+     * <p>The records are copied before this method returns. The outcome is
+     * collected with {@link #pollCompletions}, under the returned ticket, so a
+     * single thread can keep many appends in flight. Submitted appends bypass
+     * the watchdog.
+     *
+     * <p>With a {@link LogDbConfig#writeQueue() write queue} configured, a
+     * submission that finds the queue full blocks, throws, or returns
+     * {@link #NOT_READY} according to its {@link BackpressurePolicy}.
+     *
+     * @param records the records to append
+     * @param ackMode when the append completes
+     * @return the ticket identifying the append's completion, or
+     *         {@link #NOT_READY} if the append was not submitted
+     * @throws dev.opendata.common.OpenDataBackpressureException if the write
+     *         queue is full, or the memory limit reached, under
+     *         {@link BackpressurePolicy#REJECT}
+     */
+    public long submitAppend(Record[] records, AckMode ackMode) {
+        if (records == null) {
+            throw new IllegalArgumentException("records must not be null");
+        }
+        if (ackMode == null) {
+            throw new IllegalArgumentException("ackMode must not be null");
+        }
+        checkNotClosed();
+        try {
+            return nativeSubmitAppend(handle, records, ackMode.ordinal());
+        } finally {
+            Reference.reachabilityFence(this);
+        }
+    }
+
+    /**
+     * Submits a scan without waiting for it to complete.
+     *
+     * @param key           the key to scan
+     * @param startSequence the first sequence to return
+     * @param maxEntries    the maximum number of entries to return
+     * @return the ticket identifying the scan's completion
+     * @see #submitAppend(Record[], AckMode)
+     */
+    public long submitScan(byte[] key, long startSequence, int maxEntries) {
+        if (key == null) {
+            throw new IllegalArgumentException("key must not be null");
+        }
+        if (maxEntries <= 0) {
+            throw new IllegalArgumentException("maxEntries must be positive");
+        }
+        checkNotClosed();
+        try {
+            return nativeSubmitScan(handle, key, startSequence, maxEntries);
+        } finally {
+            Reference.reachabilityFence(this);
+        }
+    }
+
+    /**
+     * Scans a range of a key's entries without blocking the calling thread.
+     *
+     * <p>The scan runs on the log's native runtime, which reads the range in
+     * batches of up to {@code batchSize} entries and passes each to the
+     * listener before reading the next, see {@link ScanListener} for its
+     * threading. The scan ends at {@code endSequence}, at the end of the key,
+     * or when the listener stops it. Closing the log ends scans still
+     * running with {@link ScanListener#onError}.
+     *
+     * @param key           the key to scan
+     * @param startSequence the first sequence to deliver
+     * @param endSequence   the sequence to stop before, or {@link Long#MAX_VALUE}
+     * @param batchSize     the maximum number of entries per batch
+     * @param listener      the listener receiving the batches
+     */
+    public void scanAsync(byte[] key, long startSequence, long endSequence, int batchSize, ScanListener listener) {
+        if (key == null) {
+            throw new IllegalArgumentException("key must not be null");
+        }
+        if (startSequence < 0) {
+            throw new IllegalArgumentException("startSequence must be >= 0");
+        }
+        if (endSequence < startSequence) {
+            throw new IllegalArgumentException("endSequence must be >= startSequence");
+        }
+        if (batchSize <= 0) {
+            throw new IllegalArgumentException("batchSize must be positive");
+        }
+        if (listener == null) {
+            throw new IllegalArgumentException("listener must not be null");
+        }
+        checkNotClosed();
+        try {
+            nativeScanAsync(handle, key, startSequence, endSequence, batchSize, new ScanDelivery(listener));
+        } finally {
+            Reference.reachabilityFence(this);
+        }
+    }
+
+    /**
+     * Returns completed submissions, waiting for at least one.
+     *
+     * <p>Completions are returned in the order their operations finished, and
+     * each is returned once. Operations still in flight when the log is closed
+     * finish before it closes, but their completions are discarded.
+     *
+     * @param max       the maximum number of completions to return
+     * @param timeoutMs how long to wait for the first completion; 0 returns
+     *                  immediately
+     * @return the completions, empty if none arrived within the timeout
+     */
+    public List<Completion> pollCompletions(int max, long timeoutMs) {
+        if (max <= 0) {
+            throw new IllegalArgumentException("max must be positive");
+        }
+        if (timeoutMs < 0) {
+            throw new IllegalArgumentException("timeoutMs must not be negative");
+        }
+        checkNotClosed();
+        try {
+            return List.of(nativePollCompletions(handle, max, timeoutMs));
+        } finally {
+            Reference.reachabilityFence(this);
+        }
+    }
+
+    /**
+     * Flushes all pending writes to durable storage.
+     *
+     * <p>This ensures that all writes that have been acknowledged are persisted
+     * to durable storage. For SlateDB-backed storage, this flushes the memtable
+     * to the WAL and object store.
+     */
+    public void flush() {
+        checkNotClosed();
+        try {
+            if (bridged) {
+                CompletableFuture<Void> result = new CompletableFuture<>();
+                nativeFlushBridged(handle, result);
+                awaitBridged(result);
+                return;
+            }
+            nativeFlush(handle);
+        } finally {
+            Reference.reachabilityFence(this);
+        }
+    }
+
+    /**
+     * Waits until every append acknowledged before the call is durable and
+     * returns how far each key is durable.
+     *
+     * <p>Each key ever appended to through this handle is reported with its
+     * tail, one past its last sequence, as of the start of the call; once
+     * this method returns, every entry of the key before the tail is in the
+     * object store. Crash-recovery tests can compare the watermarks with
+     * what a fresh handle reads back. Appends through other handles or
+     * processes are not reported.
+     *
+     * @return the durable watermark of each key, ordered by key
+     */
+    public List<KeyWatermark> sync() {
+        checkNotClosed();
+        try {
+            return List.of(nativeSync(handle));
+        } finally {
+            Reference.reachabilityFence(this);
+        }
+    }
+
+    /**
+     * Commits a consumer group's offset for a key.
+     *
+     * <p>The offset is stored in the log itself under a reserved key and flushed
+     * before this method returns, so consumers can resume from it after a JVM
+     * restart via {@link #fetchOffset(String, byte[])} or
+     * {@link LogDbReader#fetchOffset(String, byte[])}. Every commit appends a
+     * small entry; application keys must not start with the reserved prefix
+     * {@code 0xFF "__opendata/offsets/"}.
+     *
+     * @param group    the consumer group
+     * @param key      the consumed key
+     * @param sequence the sequence to commit, typically the next sequence to read
+     */
+    public void commitOffset(String group, byte[] key, long sequence) {
+        validateOffsetArgs(group, key);
+        if (sequence < 0) {
+            throw new IllegalArgumentException("sequence must not be negative");
+        }
+        checkNotClosed();
+        try {
+            nativeCommitOffset(handle, group, key, sequence);
+        } finally {
+            Reference.reachabilityFence(this);
+        }
+    }
+
+    /**
+     * Fetches a consumer group's committed offset for a key.
+     *
+     * @param group the consumer group
+     * @param key   the consumed key
+     * @return the committed sequence, or empty if the group never committed for the key
+     */
+    public OptionalLong fetchOffset(String group, byte[] key) {
+        validateOffsetArgs(group, key);
+        checkNotClosed();
+        try {
+            long offset = nativeFetchOffset(handle, group, key);
+            return offset >= 0 ? OptionalLong.of(offset) : OptionalLong.empty();
+        } finally {
+            Reference.reachabilityFence(this);
+        }
+    }
+
+    int[] groupHeartbeat(String group, String memberId, byte[][] keys, long sessionTimeoutMs) {
+        checkNotClosed();
+        try {
+            return nativeGroupHeartbeat(handle, group, memberId, keys, sessionTimeoutMs);
+        } finally {
+            Reference.reachabilityFence(this);
+        }
+    }
+
+    void groupLeave(String group, String memberId) {
+        checkNotClosed();
+        try {
+            nativeGroupLeave(handle, group, memberId);
+        } finally {
+            Reference.reachabilityFence(this);
+        }
+    }
+
+    static void validateOffsetArgs(String group, byte[] key) {
+        if (group == null || group.isEmpty()) {
+            throw new IllegalArgumentException("group must not be null or empty");
+        }
+        if (key == null) {
+            throw new IllegalArgumentException("key must not be null");
+        }
+    }
+
+    /**
+     * Returns the native latency histogram for the given operation.
+     *
+     * <p>Latencies are recorded in microseconds for every call since the log was
+     * opened and cover the storage round trip, excluding JNI marshalling. The
+     * result uses the HdrHistogram V2 encoding and can be decoded with
+     * {@code org.HdrHistogram.Histogram.decodeFromByteBuffer(ByteBuffer.wrap(bytes), 0)}.
+     *
+     * @param operation the operation to return latencies for
+     * @return the encoded histogram
+     */
+    public byte[] latencyHistogram(Operation operation) {
+        if (operation == null) {
+            throw new IllegalArgumentException("operation must not be null");
+        }
+        checkNotClosed();
+        try {
+            return nativeGetLatencyHistogram(handle, operation.ordinal());
+        } finally {
+            Reference.reachabilityFence(this);
+        }
+    }
+
+    /**
+     * Returns the accumulated per-phase timings of native calls for an operation.
+     *
+     * <p>Timings are only accumulated when profiling is enabled in the config;
+     * otherwise all values are zero.
+     *
+     * @param operation the operation to return timings for
+     * @return the accumulated timing breakdown
+     */
+    public TimingBreakdown timingBreakdown(Operation operation) {
+        if (operation == null) {
+            throw new IllegalArgumentException("operation must not be null");
+        }
+        checkNotClosed();
+        try {
+            return TimingBreakdown.fromNative(nativeGetTimingBreakdown(handle, operation.ordinal()));
+        } finally {
+            Reference.reachabilityFence(this);
+        }
+    }
+
+    /**
+     * Marks the start of a benchmark warmup.
+     *
+     * <p>Discards the latency histograms, timing breakdowns and blocked caller
+     * peak recorded so far, and reports {@code warming_up} as 1 in
+     * {@link #stats()} until {@link #markWarmupEnd()} is called.
+     */
+    public void markWarmupStart() {
+        checkNotClosed();
+        try {
+            nativeMarkWarmup(handle, true);
+        } finally {
+            Reference.reachabilityFence(this);
+        }
+    }
+
+    /**
+     * Marks the end of a benchmark warmup.
+     *
+     * <p>Discards everything recorded during warmup, so that histograms and
+     * timing breakdowns only cover the measured run. {@code measurement_nanos}
+     * in {@link #stats()} is the time since the last warmup mark.
+     */
+    public void markWarmupEnd() {
+        checkNotClosed();
+        try {
+            nativeMarkWarmup(handle, false);
+        } finally {
+            Reference.reachabilityFence(this);
+        }
+    }
+
+    /**
+     * Changes tunable settings of this log without reopening it.
+     *
+     * <p>Every setting of the update is checked before any is applied, so a
+     * rejected update changes nothing.
+     *
+     * @param update the settings to change
+     * @throws IllegalStateException if the update changes the tail cache or
+     *         rate limits of a log opened without them
+     */
+    public void updateConfig(ConfigUpdate update) {
+        if (update == null) {
+            throw new IllegalArgumentException("update must not be null");
+        }
+        checkNotClosed();
+        try {
+            nativeUpdateConfig(handle, update);
+        } finally {
+            Reference.reachabilityFence(this);
+        }
+    }
+
+    /**
+     * Sets the clock of a log opened with {@link ClockSource#MANUAL}.
+     *
+     * <p>Records appended from now on are stamped with the given timestamp,
+     * until the clock is set or advanced again.
+     *
+     * @param timestampMs the new time of the clock, in milliseconds since the epoch
+     * @throws IllegalStateException if the log does not use a manual clock
+     */
+    public void setClock(long timestampMs) {
+        checkNotClosed();
+        try {
+            nativeSetClock(handle, timestampMs);
+        } finally {
+            Reference.reachabilityFence(this);
+        }
+    }
+
+    /**
+     * Advances the clock of a log opened with {@link ClockSource#MANUAL}.
+     *
+     * @param deltaMs milliseconds to move the clock by; may be negative
+     * @return the new time of the clock
+     * @throws IllegalStateException if the log does not use a manual clock
+     */
+    public long advanceClock(long deltaMs) {
+        checkNotClosed();
+        try {
+            return nativeAdvanceClock(handle, deltaMs);
+        } finally {
+            Reference.reachabilityFence(this);
+        }
+    }
+
+    /**
+     * Watches a key for entries appended through this log.
+     *
+     * <p>The listener is called with the key's new tail whenever an append
+     * through this log makes entries of the key at or after
+     * {@code fromSequence} visible, without polling storage. A log has a
+     * single writer, so while it is open every append is reported; entries
+     * already stored when the watch is registered are reported with the
+     * key's next append. Storage offers no notification of its own, so a
+     * process without the writer watches through
+     * {@link LogDbReader#watchAppends(byte[], long, TailListener)}, which
+     * polls instead.
+     *
+     * @param key          the key to watch
+     * @param fromSequence the first sequence whose appends notify the listener
+     * @param listener     the listener, see {@link TailListener} for its threading
+     * @return the watch, closed to stop notifications; the listener is only
+     *         notified while the watch is reachable, see {@link AppendWatch}
+     */
+    public AppendWatch watchAppends(byte[] key, long fromSequence, TailListener listener) {
+        if (key == null) {
+            throw new IllegalArgumentException("key must not be null");
+        }
+        if (fromSequence < 0) {
+            throw new IllegalArgumentException("fromSequence must be >= 0");
+        }
+        if (listener == null) {
+            throw new IllegalArgumentException("listener must not be null");
+        }
+        checkNotClosed();
+        try {
+            return new AppendWatch(this::unwatch, nativeWatch(handle, key, fromSequence, listener), listener);
+        } finally {
+            Reference.reachabilityFence(this);
+        }
+    }
+
+    void unwatch(long id) {
+        try {
+            if (!closed) {
+                nativeUnwatch(handle, id);
+            }
+        } finally {
+            Reference.reachabilityFence(this);
+        }
+    }
+
+    /**
+     * Blocks until the rate of an operation on this log is steady.
+     *
+     * <p>Operations from all threads using this log are counted, so the
+     * workload must run on other threads while this method waits. Marking
+     * warmup while waiting restarts detection.
+     *
+     * @param operation the operation whose rate is sampled
+     * @param config    the sampling windows and variation threshold
+     * @param timeoutMs maximum time to wait in milliseconds
+     * @return true if steady state was reached, false if the timeout elapsed first
+     */
+    public boolean awaitSteadyState(Operation operation, SteadyStateConfig config, long timeoutMs) {
+        if (operation == null) {
+            throw new IllegalArgumentException("operation must not be null");
+        }
+        if (config == null) {
+            throw new IllegalArgumentException("config must not be null");
+        }
+        if (timeoutMs < 0) {
+            throw new IllegalArgumentException("timeoutMs must not be negative");
+        }
+        checkNotClosed();
+        try {
+            return nativeAwaitSteadyState(handle, operation.ordinal(), config, timeoutMs);
+        } finally {
+            Reference.reachabilityFence(this);
+        }
+    }
+
+    /**
+     * Returns a snapshot of native statistics, keyed by dotted metric name.
+     *
+     * <p>Runtime health is reported per Tokio runtime:
+     * <ul>
+     *   <li>{@code runtime.*} - the runtime serving Java calls</li>
+     *   <li>{@code compaction_runtime.*} - the runtime running SlateDB compaction and GC</li>
+     * </ul>
+     *
+     * <p>Each runtime reports {@code workers}, {@code alive_tasks},
+     * {@code injection_queue_depth}, {@code busy_nanos} (cumulative across
+     * workers; diff two snapshots against elapsed time to get utilization) and
+     * {@code park_count}. {@code runtime.blocked_callers} and
+     * {@code runtime.blocked_callers_peak} count Java threads blocked waiting on
+     * native calls. {@code uptime_nanos} is the time since the handle was opened;
+     * {@code warming_up} and {@code measurement_nanos} track warmup marks.
+     * {@code async.in_flight} counts submitted operations that have not
+     * finished and {@code async.completed} finished ones not yet polled. With
+     * write coalescing, {@code coalesce.batches} counts storage appends made
+     * for coalesced windows and {@code coalesce.records} the records they held.
+     * With a bounded write queue, {@code write_queue.records} and
+     * {@code write_queue.bytes} report what submitted appends hold in it and
+     * {@code write_queue.refused} counts submissions rejected or not ready.
+     * With rate limits, {@code rate_limit.produce_throttled_nanos} and
+     * {@code rate_limit.consume_throttled_nanos} total the time calls waited.
+     * With a tail cache, {@code tail_cache.bytes} and {@code tail_cache.keys}
+     * report what it holds, and {@code tail_cache.hits} and
+     * {@code tail_cache.misses} count scans, including those of attached
+     * readers, served from and missing it, and {@code tail_cache.hit_rate_ppm}
+     * gives their ratio in parts per million. With a disk cache,
+     * {@code disk_cache.bytes} and {@code disk_cache.parts} report what the
+     * cache directory holds and {@code disk_cache.parts_added} counts parts
+     * fetched into it since the log opened, each a read that missed the cache.
+     * {@code scan.cache_bytes} and {@code scan.storage_bytes} total the key and
+     * value bytes scans of this handle returned from the tail cache and through
+     * storage; SlateDB's block cache is not visible to the binding, so its hits
+     * count as storage bytes. With a slow-operation threshold,
+     * {@code slow_log.logged} counts the appends and scans logged as slow.
+     * With automatic reopening, {@code reopen.count} and {@code reopen.failed}
+     * count reopens that succeeded and failed,
+     * {@code reopen.consecutive_failures} the storage calls failed in a row
+     * and {@code reopen.in_progress} is 1 while a reopen runs.
+     * {@code memory.tail_cache_bytes}, {@code memory.in_flight_bytes},
+     * {@code memory.completions_bytes} and {@code memory.rings_bytes} report
+     * the native memory, outside the JVM heap, held by the tail cache, appends
+     * in flight, scan results not yet polled and open append rings, and
+     * {@code memory.total_bytes} their sum; SlateDB's memtables and block
+     * cache are not included. With a memory limit, {@code memory.limit_bytes}
+     * reports it and {@code memory.refused} counts appends it rejected.
+     * With a call limit, see {@link LogDbConfig#withCallLimit(CallLimitConfig)},
+     * {@code call_limit.max_calls} reports it, {@code call_limit.running} and
+     * {@code call_limit.queued} the blocking calls running and waiting, and
+     * {@code call_limit.rejected} counts the calls it refused.
+     * With tags, see {@link LogDbConfig#withTags(Map)}, every name is
+     * followed by the tags in braces.
+     *
+     * @return an unmodifiable, name-ordered map of statistics
+     */
+    public Map<String, Long> stats() {
+        checkNotClosed();
+        try {
+            return Collections.unmodifiableMap(new TreeMap<>(nativeStats(handle)));
+        } finally {
+            Reference.reachabilityFence(this);
+        }
+    }
+
+    /**
+     * Returns the native operations of this log that are queued or in flight.
+     *
+     * <p>Only a few native counters are read, without waiting for storage, so
+     * callers may check it before each submission, for example to grow
+     * batches while many operations are in flight, or to stop submitting
+     * when {@link PendingOps#heldBytes()} grows without bound.
+     *
+     * @return the pending operations
+     */
+    public PendingOps pendingOps() {
+        checkNotClosed();
+        try {
+            return PendingOps.fromNative(nativePendingOps(handle));
+        } finally {
+            Reference.reachabilityFence(this);
+        }
+    }
+
+    /**
+     * Returns the native metrics of this log, typed and without tags.
+     *
+     * <p>The snapshot holds the stats of {@link #stats()} and summaries of the
+     * append and scan latencies, each marked as a counter or a gauge, and
+     * this log's tags, so a Micrometer binder can register them as function
+     * counters and gauges and poll a snapshot whenever its registry reads
+     * them. Unlike {@link #intervalStats()}, taking a snapshot resets
+     * nothing, so several readers may poll independently.
+     *
+     * @return a snapshot of the native metrics
+     */
+    public MetricsSnapshot metricsSnapshot() {
+        checkNotClosed();
+        try {
+            return nativeMetricsSnapshot(handle);
+        } finally {
+            Reference.reachabilityFence(this);
+        }
+    }
+
+    /**
+     * Returns the keys with the most appends and scans.
+     *
+     * <p>Keys are ranked by {@link KeyStats#operations()}, most first, ties
+     * ordered by key. Only the keys in the statistics are considered, see
+     * {@link LogDbConfig#withKeyStatsCapacity(int)}.
+     *
+     * @param limit the maximum number of keys to return
+     * @return up to {@code limit} of the hottest keys
+     * @throws IllegalStateException if the log was opened without key statistics
+     */
+    public List<KeyStats> hotKeys(int limit) {
+        if (limit < 0) {
+            throw new IllegalArgumentException("limit must be >= 0");
+        }
+        checkNotClosed();
+        try {
+            return List.of(nativeHotKeys(handle, limit));
+        } finally {
+            Reference.reachabilityFence(this);
+        }
+    }
+
+    /**
+     * Returns the traffic of this log since the previous call, or since it
+     * opened.
+     *
+     * <p>{@code interval_nanos} is the length of the interval.
+     * {@code records_in} and {@code bytes_in} count the records appended in it
+     * and their key and value bytes, and {@code records_out} and
+     * {@code bytes_out} the entries scans returned; bytes are counted as
+     * stored, with timestamp headers and chunks. Each count is followed by its
+     * per-second rate over the interval, such as {@code records_in_per_sec}.
+     * Calls from several threads split the traffic between them, so a single
+     * reporter should call this method.
+     *
+     * @return an unmodifiable, name-ordered map of the interval's traffic
+     */
+    public Map<String, Long> intervalStats() {
+        checkNotClosed();
+        try {
+            return Collections.unmodifiableMap(new TreeMap<>(nativeIntervalStats(handle)));
+        } finally {
+            Reference.reachabilityFence(this);
+        }
+    }
+
+    @Override
+    public void close() {
+        if (!closed) {
+            closed = true;
+            closeAttached();
+            nativeClose(handle);
+        }
+    }
+
+    /**
+     * Closes this log without waiting for storage to close.
+     *
+     * <p>Open rings and pipelines are closed first, on the calling thread.
+     * The log itself is closed on a native close thread, which waits for
+     * calls in flight and flushes like {@link #close()}; a failed close is
+     * logged rather than thrown.
+     *
+     * @return a token for {@link #awaitClose(long, long)}, or 0 if the log
+     *         was already closed
+     */
+    public long closeAsync() {
+        if (closed) {
+            return 0;
+        }
+        closed = true;
+        closeAttached();
+        return nativeCloseAsync(handle);
+    }
+
+    /**
+     * Lists the native LogDb and LogDbReader handles of this process that are
+     * still open, oldest first, to track down handles that are never closed.
+     *
+     * <p>Handles of logs closed by their {@link Cleaner} stay listed until
+     * their asynchronous close finishes.
+     *
+     * @return the live handles
+     */
+    public static List<HandleInfo> dumpHandles() {
+        return List.of(nativeDumpHandles());
+    }
+
+    /**
+     * Waits for the submitted operations of every open log to complete and
+     * flushes each log, so that nothing is left buffered or half-uploaded.
+     *
+     * <p>The shutdown hook of the native library does this for up to 30
+     * seconds once the JVM starts shutting down. Applications that order
+     * their own shutdown can call this from their hook first, with their own
+     * timeout. Logs stay open; appends made after the drain are not covered.
+     *
+     * @param timeoutMs maximum time to wait in milliseconds
+     * @return true if every log was flushed within the timeout
+     */
+    public static boolean drainAll(long timeoutMs) {
+        return nativeDrainAll(timeoutMs);
+    }
+
+    /**
+     * Waits for a close started by {@link #closeAsync()}.
+     *
+     * @param token     the token returned by {@link #closeAsync()}
+     * @param timeoutMs maximum time to wait in milliseconds
+     * @return true if the close finished within the timeout
+     */
+    public static boolean awaitClose(long token, long timeoutMs) {
+        return nativeAwaitClose(token, timeoutMs);
+    }
+
+    /**
+     * Closes the rings and pipelines of this log and disarms its cleanup,
+     * before the handle is closed.
+     */
+    private void closeAttached() {
+        // Rings append through this handle, so drain them first
+        for (AppendRing ring : List.copyOf(rings)) {
+            try {
+                ring.close();
+            } catch (RuntimeException e) {
+                // A failed ring must not keep the log open
+            }
+        }
+        for (AppendPipeline pipeline : List.copyOf(pipelines)) {
+            pipeline.close();
+        }
+        cleanup.disarm();
+        cleanable.clean();
+    }
+
+    /**
+     * Closes the handle of a log that became unreachable while open.
+     *
+     * <p>Holds only the handle, never the log, so that the log can become
+     * unreachable. A log is unreachable as soon as a method has read its
+     * handle, so every method calling into native code fences the log until
+     * the call returns; otherwise this could free the handle under it.
+     */
+    private static final class Cleanup implements Runnable {
+        private final long handle;
+        private volatile boolean armed = true;
+
+        Cleanup(long handle) {
+            this.handle = handle;
+        }
+
+        void disarm() {
+            armed = false;
+        }
+
+        @Override
+        public void run() {
+            if (armed) {
+                nativeCloseAsync(handle);
+            }
+        }
+    }
+
+    private void checkNotClosed() {
+        if (closed) {
+            throw new IllegalStateException("LogDb is closed");
+        }
+    }
+
+    /**
+     * Waits in Java for a call handed to the native bridge threads.
+     */
+    private static <T> T awaitBridged(CompletableFuture<T> result) {
+        try {
+            return result.join();
+        } catch (CompletionException e) {
+            if (e.getCause() instanceof RuntimeException cause) {
+                throw cause;
+            }
+            throw e;
+        }
+    }
+
+    long getHandle() {
+        return handle;
+    }
+
+    // Native methods
+    private static native long nativeCreate(LogDbConfig config, IntConsumer progress);
+
+    private static native long nativeCreateFromFile(String configPath);
+    private static native long nativeRestore(LogDbConfig config, StorageConfig.SlateDb backup);
+    private static native void nativeCreateAsync(
+            LogDbConfig config, IntConsumer progress, CompletableFuture<Long> result);
+
+    private static native String[] nativeVersionFields();
+
+    private static native long nativeCapabilityMask();
+    private static native AppendResult nativeAppend(
+            long handle, Record[] records, int ackMode, int priority, String traceparent);
+    private static native AppendResult nativeAppendRaw(long handle, Record[] records, int ackMode);
+    private static native KeyAppendResult[] nativeAppendGrouped(long handle, Record[] records, int ackMode);
+    private static native AppendResult nativeAppendFlatBuffer(long handle, byte[] batch, int ackMode);
+
+    private static native AppendResult nativeAppendSynthetic(
+            long handle, byte[] key, int payloadSize, int count, int ackMode);
+    private static native BenchReport nativeBench(long handle, BenchConfig config);
+    private static native long nativeOpenAppendRing(long handle, int capacity);
+    private static native long nativeOpenPipeline(long handle, int maxInFlight);
+    private static native int nativePartitionForKey(byte[] key, int numPartitions);
+    private static native AppendResult nativeAppendPartitioned(
+            long handle, String topic, Record[] records, int numPartitions, int ackMode);
+    private static native AppendResult nativeAppendIdempotent(
+            long handle, Record[] records, long producerId, long batchSequence, int ackMode);
+    private static native AppendResult nativeAppendIf(
+            long handle, byte[] key, Record[] records, long expectedTailSequence, int ackMode);
+    private static native LogEntry[] nativeScan(
+            long handle, byte[] key, long startSequence, long maxEntries, int priority, String traceparent);
+
+    private static native int nativeScanInto(
+            long handle, byte[] key, long startSequence, ByteBuffer dest, int[] offsets);
+
+    private static native byte[] nativeScanFlatBuffer(long handle, byte[] key, long startSequence, long maxEntries);
+    private static native LogEntry[] nativeScanRaw(long handle, byte[] key, long startSequence, long maxEntries);
+    private static native long[] nativeScanMetadata(long handle, byte[] key, long startSequence, long maxEntries);
+    private static native LogEntry[] nativeScanFiltered(
+            long handle, byte[] key, long startSequence, long maxEntries, ScanFilter filter);
+
+    private static native int nativeScanArrow(
+            long handle, byte[] key, long startSequence, long maxEntries, long arrayAddress, long schemaAddress);
+    private static native void nativeFlush(long handle);
+    private static native KeyWatermark[] nativeSync(long handle);
+    private static native LogEntry[] nativeScanSample(
+            long handle, byte[] key, long startSequence, long endSequence, double rate, long seed);
+    private static native long nativeExport(
+            long handle, byte[] key, long startSequence, long endSequence, String path, int format);
+    private static native long nativeImport(long handle, String path, int format, byte[] key);
+    private static native Checkpoint nativeCheckpoint(long handle, String name, long lifetimeMs);
+    private static native Checkpoint[] nativeListCheckpoints(long handle);
+    private static native void nativeDeleteCheckpoint(long handle, String id);
+    private static native void nativeClone(long handle, String path);
+    private static native long nativeBackup(
+            long handle, String checkpointId, StorageConfig.SlateDb destination, BackupListener listener);
+    private static native VerifyReport nativeVerify(
+            long handle, byte[] key, long startSequence, LongConsumer progress);
+    private static native SequenceReport nativeAnalyzeSequences(long handle, byte[] key, long startSequence);
+    private static native void nativeAppendBridged(
+            long handle, Record[] records, int ackMode, CompletableFuture<AppendResult> result);
+    private static native void nativeScanBridged(
+            long handle, byte[] key, long startSequence, long maxEntries, CompletableFuture<LogEntry[]> result);
+    private static native void nativeFlushBridged(long handle, CompletableFuture<Void> result);
+    private static native boolean nativeIsBridged(long handle);
+    private static native long nativeSubmitAppend(long handle, Record[] records, int ackMode);
+    private static native long nativeSubmitScan(long handle, byte[] key, long startSequence, long maxEntries);
+    private static native void nativeScanAsync(
+            long handle, byte[] key, long startSequence, long endSequence, int batchSize, ScanDelivery listener);
+    private static native Completion[] nativePollCompletions(long handle, int max, long timeoutMs);
+    private static native int[] nativeGroupHeartbeat(
+            long handle, String group, String memberId, byte[][] keys, long sessionTimeoutMs);
+    private static native void nativeGroupLeave(long handle, String group, String memberId);
+    private static native void nativeCommitOffset(long handle, String group, byte[] key, long sequence);
+    private static native long nativeFetchOffset(long handle, String group, byte[] key);
+    private static native byte[] nativeGetLatencyHistogram(long handle, int operation);
+    private static native long[] nativeGetTimingBreakdown(long handle, int operation);
+    private static native Map<String, Long> nativeStats(long handle);
+    private static native MetricsSnapshot nativeMetricsSnapshot(long handle);
+    private static native long[] nativePendingOps(long handle);
+    private static native Map<String, Long> nativeIntervalStats(long handle);
+    private static native KeyStats[] nativeHotKeys(long handle, int limit);
+    private static native void nativeMarkWarmup(long handle, boolean warmingUp);
+    private static native void nativeUpdateConfig(long handle, ConfigUpdate update);
+    private static native void nativeSetClock(long handle, long timestampMs);
+    private static native long nativeAdvanceClock(long handle, long deltaMs);
+    private static native long nativeWatch(long handle, byte[] key, long fromSequence, TailListener listener);
+    private static native boolean nativeUnwatch(long handle, long id);
+    private static native boolean nativeAwaitSteadyState(
+            long handle, int operation, SteadyStateConfig config, long timeoutMs);
+    private static native void nativeClose(long handle);
+
+    private static native long nativeCloseAsync(long handle);
+
+    private static native boolean nativeAwaitClose(long token, long timeoutMs);
+
+    private static native void nativeSetOrigin(long handle, int creationStackHash);
+
+    private static native HandleInfo[] nativeDumpHandles();
+
+    private static native boolean nativeDrainAll(long timeoutMs);
 }
@@ -2,20 +2,123 @@ package dev.opendata;
 
 import dev.opendata.common.StorageConfig;
 
+import java.util.Map;
+import java.util.regex.Pattern;
+
 /**
  * Configuration for opening a {@link LogDb}.
  *
  * <p>This record holds all the settings needed to initialize a log instance,
  * including storage backend configuration and segmentation settings.
  *
+ * <p>When the log opens, {@code OPENDATA_*} environment variables override
+ * individual settings of the config, so a deployment can inject per-process
+ * values without changing code. A variable is named after the setting in a
+ * config file read by {@link LogDb#open(java.nio.file.Path)}, upper-cased
+ * with dots as underscores, for example {@code OPENDATA_BRIDGE_THREADS},
+ * {@code OPENDATA_TAIL_CACHE_BYTES} or
+ * {@code OPENDATA_STORAGE_OBJECT_STORE_BUCKET}. Overrides apply to the
+ * storage path, settings path, object store bucket, region and path, disk
+ * cache path and size, profiling, dedup window, bridge threads, tail cache,
+ * slow-operation threshold, open timeout and rate limits; an invalid value
+ * fails the open.
+ *
  * @param storage      storage backend configuration
  * @param segmentation segmentation configuration
+ * @param profiling    whether to accumulate per-phase native call timings,
+ *                     see {@link LogDb#timingBreakdown(Operation)}
+ * @param watchdog     watchdog configuration for stuck native calls
+ * @param dedupWindow  number of recent batches remembered per producer and key
+ *                     for {@link LogDb#appendIdempotent(Record[], long, long)}
+ * @param faults       fault injection into the object store, for resilience testing
+ * @param bridgeThreads number of native bridge threads awaiting appends, scans
+ *                     and flushes, so that callers wait in Java rather than in
+ *                     a native call; 0 waits in the native call, see
+ *                     {@link #withBridgeThreads(int)}
+ * @param coalesce     coalescing of concurrent appends into shared batches
+ * @param writeQueue   bound on appends queued by
+ *                     {@link LogDb#submitAppend(Record[], AckMode)}
+ * @param rateLimits   native produce and consume rate limits
+ * @param tailCacheBytes bytes of recently appended entries kept in native
+ *                     memory to answer tail scans; 0 disables the cache, see
+ *                     {@link #withTailCacheBytes(long)}
+ * @param slowOperationThresholdMs duration in milliseconds from which appends
+ *                     and scans are logged as slow; 0 disables the log, see
+ *                     {@link #withSlowOperationThreshold(long)}
+ * @param openTimeoutMs how long in milliseconds opening the storage may take
+ *                     before the open fails; 0 waits indefinitely, see
+ *                     {@link #withOpenTimeout(long)}
+ * @param reopen       reopening of the storage after repeated failures
+ * @param memoryLimit  cap on native memory held by the handle
+ * @param runtime      thread names and cores of the runtime running calls
+ * @param compactionRuntime thread names and cores of the runtime running
+ *                     SlateDB compaction
+ * @param chunkThresholdBytes payload size above which appended values are
+ *                     stored as chunks; 0 stores values whole, see
+ *                     {@link #withChunkThresholdBytes(long)}
+ * @param criticalValueCopy whether appended values are copied out of the Java
+ *                     heap with {@code GetPrimitiveArrayCritical}, see
+ *                     {@link #withCriticalValueCopy(boolean)}
+ * @param tags         labels attached to the handle's stats, log lines and
+ *                     JFR events, see {@link #withTags(Map)}
+ * @param clock        source of the timestamps stored with appended records,
+ *                     see {@link #withClock(ClockSource)}
+ * @param receiveTimestamps whether appended values also record when they
+ *                     reached the native layer, see
+ *                     {@link #withReceiveTimestamps(boolean)}
+ * @param backgroundConcurrency number of {@link OperationPriority#BACKGROUND}
+ *                     appends and scans accessing storage at once, see
+ *                     {@link #withBackgroundConcurrency(int)}
+ * @param keyStatsCapacity number of keys with per-key statistics; 0 disables
+ *                     them, see {@link #withKeyStatsCapacity(int)}
+ * @param statsd       export of the native stats to a StatsD agent, see
+ *                     {@link #withStatsd(StatsdConfig)}
+ * @param callLimit    limit on the blocking calls running at once, see
+ *                     {@link #withCallLimit(CallLimitConfig)}
  */
 public record LogDbConfig(
         StorageConfig storage,
-        SegmentConfig segmentation
+        SegmentConfig segmentation,
+        boolean profiling,
+        WatchdogConfig watchdog,
+        int dedupWindow,
+        FaultInjection faults,
+        int bridgeThreads,
+        CoalesceConfig coalesce,
+        WriteQueueConfig writeQueue,
+        RateLimitConfig rateLimits,
+        long tailCacheBytes,
+        long slowOperationThresholdMs,
+        long openTimeoutMs,
+        ReopenConfig reopen,
+        MemoryLimitConfig memoryLimit,
+        RuntimeConfig runtime,
+        RuntimeConfig compactionRuntime,
+        long chunkThresholdBytes,
+        boolean criticalValueCopy,
+        Map<String, String> tags,
+        ClockSource clock,
+        boolean receiveTimestamps,
+        int backgroundConcurrency,
+        int keyStatsCapacity,
+        StatsdConfig statsd,
+        CallLimitConfig callLimit
 ) {
 
+    /**
+     * Default number of batches remembered per producer and key, matching
+     * Kafka's limit of five in-flight requests per idempotent producer.
+     */
+    public static final int DEFAULT_DEDUP_WINDOW = 5;
+
+    /**
+     * Default number of background appends and scans accessing storage at
+     * once.
+     */
+    public static final int DEFAULT_BACKGROUND_CONCURRENCY = 1;
+
+    private static final Pattern TAG_NAME = Pattern.compile("[A-Za-z_][A-Za-z0-9_]*");
+
     /**
      * Creates a config with the specified storage and default segmentation.
      *
@@ -25,6 +128,21 @@ public record LogDbConfig(
         this(storage, SegmentConfig.DEFAULT);
     }
 
+    /**
+     * Creates a config with the specified storage and segmentation.
+     *
+     * @param storage      storage backend configuration
+     * @param segmentation segmentation configuration
+     */
+    public LogDbConfig(StorageConfig storage, SegmentConfig segmentation) {
+        this(storage, segmentation, false, WatchdogConfig.DISABLED, DEFAULT_DEDUP_WINDOW,
+                FaultInjection.DISABLED, 0, CoalesceConfig.DISABLED, WriteQueueConfig.UNBOUNDED,
+                RateLimitConfig.UNLIMITED, 0, 0, 0, ReopenConfig.DISABLED,
+                MemoryLimitConfig.UNLIMITED, RuntimeConfig.OPERATION, RuntimeConfig.COMPACTION, 0,
+                false, Map.of(), ClockSource.RECORD, false, DEFAULT_BACKGROUND_CONCURRENCY, 0,
+                StatsdConfig.DISABLED, CallLimitConfig.UNLIMITED);
+    }
+
     public LogDbConfig {
         if (storage == null) {
             throw new IllegalArgumentException("storage must not be null");
@@ -32,6 +150,530 @@ public record LogDbConfig(
         if (segmentation == null) {
             throw new IllegalArgumentException("segmentation must not be null");
         }
+        if (watchdog == null) {
+            throw new IllegalArgumentException("watchdog must not be null");
+        }
+        if (dedupWindow <= 0) {
+            throw new IllegalArgumentException("dedupWindow must be positive");
+        }
+        if (faults == null) {
+            throw new IllegalArgumentException("faults must not be null");
+        }
+        if (bridgeThreads < 0) {
+            throw new IllegalArgumentException("bridgeThreads must not be negative");
+        }
+        if (coalesce == null) {
+            throw new IllegalArgumentException("coalesce must not be null");
+        }
+        if (writeQueue == null) {
+            throw new IllegalArgumentException("writeQueue must not be null");
+        }
+        if (rateLimits == null) {
+            throw new IllegalArgumentException("rateLimits must not be null");
+        }
+        if (tailCacheBytes < 0) {
+            throw new IllegalArgumentException("tailCacheBytes must not be negative");
+        }
+        if (slowOperationThresholdMs < 0) {
+            throw new IllegalArgumentException("slowOperationThresholdMs must not be negative");
+        }
+        if (openTimeoutMs < 0) {
+            throw new IllegalArgumentException("openTimeoutMs must not be negative");
+        }
+        if (reopen == null) {
+            throw new IllegalArgumentException("reopen must not be null");
+        }
+        if (memoryLimit == null) {
+            throw new IllegalArgumentException("memoryLimit must not be null");
+        }
+        if (runtime == null) {
+            throw new IllegalArgumentException("runtime must not be null");
+        }
+        if (compactionRuntime == null) {
+            throw new IllegalArgumentException("compactionRuntime must not be null");
+        }
+        if (runtime.shared()) {
+            throw new IllegalArgumentException("runtime cannot be shared; share compactionRuntime");
+        }
+        if (compactionRuntime.shared()
+                && !(storage instanceof StorageConfig.InMemory || storage instanceof StorageConfig.NoOp)) {
+            throw new IllegalArgumentException("compactionRuntime can only be shared with in-memory storage");
+        }
+        if (chunkThresholdBytes < 0) {
+            throw new IllegalArgumentException("chunkThresholdBytes must not be negative");
+        }
+        if (tags == null) {
+            throw new IllegalArgumentException("tags must not be null");
+        }
+        for (Map.Entry<String, String> tag : tags.entrySet()) {
+            if (tag.getKey() == null || !TAG_NAME.matcher(tag.getKey()).matches()) {
+                throw new IllegalArgumentException("Invalid tag name: " + tag.getKey());
+            }
+            if (tag.getValue() == null) {
+                throw new IllegalArgumentException("Tag " + tag.getKey() + " must have a value");
+            }
+        }
+        tags = Map.copyOf(tags);
+        if (clock == null) {
+            throw new IllegalArgumentException("clock must not be null");
+        }
+        if (backgroundConcurrency <= 0) {
+            throw new IllegalArgumentException("backgroundConcurrency must be positive");
+        }
+        if (keyStatsCapacity < 0) {
+            throw new IllegalArgumentException("keyStatsCapacity must be >= 0");
+        }
+        if (statsd == null) {
+            throw new IllegalArgumentException("statsd must not be null");
+        }
+        if (callLimit == null) {
+            throw new IllegalArgumentException("callLimit must not be null");
+        }
+    }
+
+    /**
+     * Returns a copy of this config with profiling enabled or disabled.
+     *
+     * @param profiling whether to accumulate per-phase native call timings
+     * @return a new LogDbConfig
+     */
+    public LogDbConfig withProfiling(boolean profiling) {
+        return new LogDbConfig(storage, segmentation, profiling, watchdog, dedupWindow, faults,
+                bridgeThreads, coalesce, writeQueue, rateLimits, tailCacheBytes,
+                slowOperationThresholdMs, openTimeoutMs, reopen, memoryLimit, runtime,
+                compactionRuntime, chunkThresholdBytes, criticalValueCopy, tags, clock,
+                receiveTimestamps, backgroundConcurrency, keyStatsCapacity, statsd, callLimit);
+    }
+
+    /**
+     * Returns a copy of this config with the given watchdog configuration.
+     *
+     * @param watchdog watchdog configuration for stuck native calls
+     * @return a new LogDbConfig
+     */
+    public LogDbConfig withWatchdog(WatchdogConfig watchdog) {
+        return new LogDbConfig(storage, segmentation, profiling, watchdog, dedupWindow, faults,
+                bridgeThreads, coalesce, writeQueue, rateLimits, tailCacheBytes,
+                slowOperationThresholdMs, openTimeoutMs, reopen, memoryLimit, runtime,
+                compactionRuntime, chunkThresholdBytes, criticalValueCopy, tags, clock,
+                receiveTimestamps, backgroundConcurrency, keyStatsCapacity, statsd, callLimit);
+    }
+
+    /**
+     * Returns a copy of this config with the given dedup window size.
+     *
+     * @param dedupWindow number of recent batches remembered per producer and key
+     * @return a new LogDbConfig
+     */
+    public LogDbConfig withDedupWindow(int dedupWindow) {
+        return new LogDbConfig(storage, segmentation, profiling, watchdog, dedupWindow, faults,
+                bridgeThreads, coalesce, writeQueue, rateLimits, tailCacheBytes,
+                slowOperationThresholdMs, openTimeoutMs, reopen, memoryLimit, runtime,
+                compactionRuntime, chunkThresholdBytes, criticalValueCopy, tags, clock,
+                receiveTimestamps, backgroundConcurrency, keyStatsCapacity, statsd, callLimit);
+    }
+
+    /**
+     * Returns a copy of this config with the given fault injection.
+     *
+     * @param faults fault injection into the object store, for resilience testing
+     * @return a new LogDbConfig
+     */
+    public LogDbConfig withFaults(FaultInjection faults) {
+        return new LogDbConfig(storage, segmentation, profiling, watchdog, dedupWindow, faults,
+                bridgeThreads, coalesce, writeQueue, rateLimits, tailCacheBytes,
+                slowOperationThresholdMs, openTimeoutMs, reopen, memoryLimit, runtime,
+                compactionRuntime, chunkThresholdBytes, criticalValueCopy, tags, clock,
+                receiveTimestamps, backgroundConcurrency, keyStatsCapacity, statsd, callLimit);
+    }
+
+    /**
+     * Returns a copy of this config with the given number of bridge threads.
+     *
+     * <p>With bridge threads, {@link LogDb#append}, {@link LogDb#scan} and
+     * {@link LogDb#flush()} hand their storage call to a bounded pool of native
+     * threads and wait for its result in Java instead of inside a native call.
+     * A virtual thread therefore unmounts while waiting instead of pinning its
+     * carrier for the whole storage round trip. At most {@code bridgeThreads}
+     * storage calls run at once; further calls queue.
+     *
+     * @param bridgeThreads number of bridge threads, or 0 to wait in the native call
+     * @return a new LogDbConfig
+     */
+    public LogDbConfig withBridgeThreads(int bridgeThreads) {
+        return new LogDbConfig(storage, segmentation, profiling, watchdog, dedupWindow, faults,
+                bridgeThreads, coalesce, writeQueue, rateLimits, tailCacheBytes,
+                slowOperationThresholdMs, openTimeoutMs, reopen, memoryLimit, runtime,
+                compactionRuntime, chunkThresholdBytes, criticalValueCopy, tags, clock,
+                receiveTimestamps, backgroundConcurrency, keyStatsCapacity, statsd, callLimit);
+    }
+
+    /**
+     * Returns a copy of this config with the given write coalescing.
+     *
+     * @param coalesce coalescing of concurrent appends into shared batches
+     * @return a new LogDbConfig
+     */
+    public LogDbConfig withCoalesce(CoalesceConfig coalesce) {
+        return new LogDbConfig(storage, segmentation, profiling, watchdog, dedupWindow, faults,
+                bridgeThreads, coalesce, writeQueue, rateLimits, tailCacheBytes,
+                slowOperationThresholdMs, openTimeoutMs, reopen, memoryLimit, runtime,
+                compactionRuntime, chunkThresholdBytes, criticalValueCopy, tags, clock,
+                receiveTimestamps, backgroundConcurrency, keyStatsCapacity, statsd, callLimit);
+    }
+
+    /**
+     * Returns a copy of this config with the given write queue bound.
+     *
+     * @param writeQueue bound on appends queued by
+     *                   {@link LogDb#submitAppend(Record[], AckMode)}
+     * @return a new LogDbConfig
+     */
+    public LogDbConfig withWriteQueue(WriteQueueConfig writeQueue) {
+        return new LogDbConfig(storage, segmentation, profiling, watchdog, dedupWindow, faults,
+                bridgeThreads, coalesce, writeQueue, rateLimits, tailCacheBytes,
+                slowOperationThresholdMs, openTimeoutMs, reopen, memoryLimit, runtime,
+                compactionRuntime, chunkThresholdBytes, criticalValueCopy, tags, clock,
+                receiveTimestamps, backgroundConcurrency, keyStatsCapacity, statsd, callLimit);
+    }
+
+    /**
+     * Returns a copy of this config with the given rate limits.
+     *
+     * @param rateLimits native produce and consume rate limits
+     * @return a new LogDbConfig
+     */
+    public LogDbConfig withRateLimits(RateLimitConfig rateLimits) {
+        return new LogDbConfig(storage, segmentation, profiling, watchdog, dedupWindow, faults,
+                bridgeThreads, coalesce, writeQueue, rateLimits, tailCacheBytes,
+                slowOperationThresholdMs, openTimeoutMs, reopen, memoryLimit, runtime,
+                compactionRuntime, chunkThresholdBytes, criticalValueCopy, tags, clock,
+                receiveTimestamps, backgroundConcurrency, keyStatsCapacity, statsd, callLimit);
+    }
+
+    /**
+     * Returns a copy of this config with the given tail cache budget.
+     *
+     * <p>With a tail cache, every append also keeps its entries in native
+     * memory, and scans starting within a key's cached entries are answered
+     * without reading storage. Readers in the same process can share the
+     * cache through {@link LogDbReader#attachTailCache(LogDb)}. Once the
+     * cache exceeds its budget, the oldest entries of the least recently used
+     * key are evicted first.
+     *
+     * @param tailCacheBytes key and value bytes to cache, or 0 to disable the cache
+     * @return a new LogDbConfig
+     */
+    public LogDbConfig withTailCacheBytes(long tailCacheBytes) {
+        return new LogDbConfig(storage, segmentation, profiling, watchdog, dedupWindow, faults,
+                bridgeThreads, coalesce, writeQueue, rateLimits, tailCacheBytes,
+                slowOperationThresholdMs, openTimeoutMs, reopen, memoryLimit, runtime,
+                compactionRuntime, chunkThresholdBytes, criticalValueCopy, tags, clock,
+                receiveTimestamps, backgroundConcurrency, keyStatsCapacity, statsd, callLimit);
+    }
+
+    /**
+     * Returns a copy of this config with the given slow-operation threshold.
+     *
+     * <p>Every append and scan whose storage call takes at least the threshold
+     * is logged as a warning to the SLF4J logger
+     * {@code dev.opendata.SlowOperations}, with its key and the sequences it
+     * appended or returned. The number of operations logged is reported as
+     * {@code slow_log.logged} in {@link LogDb#stats()}.
+     *
+     * @param slowOperationThresholdMs threshold in milliseconds, or 0 to log nothing
+     * @return a new LogDbConfig
+     */
+    public LogDbConfig withSlowOperationThreshold(long slowOperationThresholdMs) {
+        return new LogDbConfig(storage, segmentation, profiling, watchdog, dedupWindow, faults,
+                bridgeThreads, coalesce, writeQueue, rateLimits, tailCacheBytes,
+                slowOperationThresholdMs, openTimeoutMs, reopen, memoryLimit, runtime,
+                compactionRuntime, chunkThresholdBytes, criticalValueCopy, tags, clock,
+                receiveTimestamps, backgroundConcurrency, keyStatsCapacity, statsd, callLimit);
+    }
+
+    /**
+     * Returns a copy of this config with the given open timeout.
+     *
+     * <p>Opening SlateDB storage loads its manifest and replays its WAL from
+     * the object store, which against a misconfigured or unreachable bucket
+     * can stall without failing. With a timeout, an open that has not
+     * finished in time fails with an
+     * {@link dev.opendata.common.OpenDataNativeException} naming the storage
+     * instead of blocking the opening thread indefinitely.
+     *
+     * @param openTimeoutMs timeout in milliseconds, or 0 to wait indefinitely
+     * @return a new LogDbConfig
+     */
+    public LogDbConfig withOpenTimeout(long openTimeoutMs) {
+        return new LogDbConfig(storage, segmentation, profiling, watchdog, dedupWindow, faults,
+                bridgeThreads, coalesce, writeQueue, rateLimits, tailCacheBytes,
+                slowOperationThresholdMs, openTimeoutMs, reopen, memoryLimit, runtime,
+                compactionRuntime, chunkThresholdBytes, criticalValueCopy, tags, clock,
+                receiveTimestamps, backgroundConcurrency, keyStatsCapacity, statsd, callLimit);
+    }
+
+    /**
+     * Returns a copy of this config with the given automatic reopening.
+     *
+     * @param reopen reopening of the storage after repeated failures
+     * @return a new LogDbConfig
+     */
+    public LogDbConfig withReopen(ReopenConfig reopen) {
+        return new LogDbConfig(storage, segmentation, profiling, watchdog, dedupWindow, faults,
+                bridgeThreads, coalesce, writeQueue, rateLimits, tailCacheBytes,
+                slowOperationThresholdMs, openTimeoutMs, reopen, memoryLimit, runtime,
+                compactionRuntime, chunkThresholdBytes, criticalValueCopy, tags, clock,
+                receiveTimestamps, backgroundConcurrency, keyStatsCapacity, statsd, callLimit);
+    }
+
+    /**
+     * Returns a copy of this config with the given native memory limit.
+     *
+     * @param memoryLimit cap on native memory held by the handle
+     * @return a new LogDbConfig
+     */
+    public LogDbConfig withMemoryLimit(MemoryLimitConfig memoryLimit) {
+        return new LogDbConfig(storage, segmentation, profiling, watchdog, dedupWindow, faults,
+                bridgeThreads, coalesce, writeQueue, rateLimits, tailCacheBytes,
+                slowOperationThresholdMs, openTimeoutMs, reopen, memoryLimit, runtime,
+                compactionRuntime, chunkThresholdBytes, criticalValueCopy, tags, clock,
+                receiveTimestamps, backgroundConcurrency, keyStatsCapacity, statsd, callLimit);
+    }
+
+    /**
+     * Returns a copy of this config with the given settings of the runtime
+     * running calls.
+     *
+     * @param runtime thread names and cores of the runtime running calls
+     * @return a new LogDbConfig
+     */
+    public LogDbConfig withRuntime(RuntimeConfig runtime) {
+        return new LogDbConfig(storage, segmentation, profiling, watchdog, dedupWindow, faults,
+                bridgeThreads, coalesce, writeQueue, rateLimits, tailCacheBytes,
+                slowOperationThresholdMs, openTimeoutMs, reopen, memoryLimit, runtime,
+                compactionRuntime, chunkThresholdBytes, criticalValueCopy, tags, clock,
+                receiveTimestamps, backgroundConcurrency, keyStatsCapacity, statsd, callLimit);
+    }
+
+    /**
+     * Returns a copy of this config with the given settings of the runtime
+     * running SlateDB compaction.
+     *
+     * @param compactionRuntime thread names and cores of the compaction runtime
+     * @return a new LogDbConfig
+     */
+    public LogDbConfig withCompactionRuntime(RuntimeConfig compactionRuntime) {
+        return new LogDbConfig(storage, segmentation, profiling, watchdog, dedupWindow, faults,
+                bridgeThreads, coalesce, writeQueue, rateLimits, tailCacheBytes,
+                slowOperationThresholdMs, openTimeoutMs, reopen, memoryLimit, runtime,
+                compactionRuntime, chunkThresholdBytes, criticalValueCopy, tags, clock,
+                receiveTimestamps, backgroundConcurrency, keyStatsCapacity, statsd, callLimit);
+    }
+
+    /**
+     * Returns a copy of this config with the given chunk threshold.
+     *
+     * <p>An appended value whose payload exceeds the threshold is stored as
+     * several entries of at most the threshold's payload each, keeping
+     * memtables, SSTs and object-store puts small for values of hundreds of
+     * megabytes. Scans, readers and exports reassemble the chunks, returning
+     * one entry with the sequence of its first chunk, so they read chunked
+     * values whatever their own threshold. Chunks take sequences of their
+     * own: the records of a batch following a chunked value no longer have
+     * the batch's start sequence plus their index. Values still cross JNI
+     * whole, and records appended through an {@link AppendRing} are not
+     * chunked.
+     *
+     * @param chunkThresholdBytes payload bytes per chunk, or 0 to store values whole
+     * @return a new LogDbConfig
+     */
+    public LogDbConfig withChunkThresholdBytes(long chunkThresholdBytes) {
+        return new LogDbConfig(storage, segmentation, profiling, watchdog, dedupWindow, faults,
+                bridgeThreads, coalesce, writeQueue, rateLimits, tailCacheBytes,
+                slowOperationThresholdMs, openTimeoutMs, reopen, memoryLimit, runtime,
+                compactionRuntime, chunkThresholdBytes, criticalValueCopy, tags, clock,
+                receiveTimestamps, backgroundConcurrency, keyStatsCapacity, statsd, callLimit);
+    }
+
+    /**
+     * Returns a copy of this config copying appended values with
+     * {@code GetPrimitiveArrayCritical} or not.
+     *
+     * <p>By default the native layer copies each value with
+     * {@code GetByteArrayRegion}. With critical copies, it reads the array's
+     * elements in place instead, which can be cheaper for values of many
+     * megabytes. The JVM may hold off garbage collection while an array is
+     * held this way, so each array is held only for its copy into native
+     * memory, never across the storage call; an append of many large values
+     * still delays collections by the time of its copies.
+     *
+     * @param criticalValueCopy whether to copy values with {@code GetPrimitiveArrayCritical}
+     * @return a new LogDbConfig
+     */
+    public LogDbConfig withCriticalValueCopy(boolean criticalValueCopy) {
+        return new LogDbConfig(storage, segmentation, profiling, watchdog, dedupWindow, faults,
+                bridgeThreads, coalesce, writeQueue, rateLimits, tailCacheBytes,
+                slowOperationThresholdMs, openTimeoutMs, reopen, memoryLimit, runtime,
+                compactionRuntime, chunkThresholdBytes, criticalValueCopy, tags, clock,
+                receiveTimestamps, backgroundConcurrency, keyStatsCapacity, statsd, callLimit);
+    }
+
+    /**
+     * Returns a copy of this config with the given tags.
+     *
+     * <p>Tags label everything the handle reports, so the telemetry of
+     * several handles, or of several benchmark workers, can be told apart
+     * once merged. With tags, every name in {@link LogDb#stats()} is followed
+     * by the tags in braces, as in
+     * {@code slow_log.logged{region="eu-west-1",topic="orders"}}, log lines
+     * of the handle start with them in brackets, and its JFR events carry
+     * them in their {@code tags} field. Tags are sorted by name and values
+     * escaped as in the Prometheus text format. Logs opened through a
+     * {@link LogDbManager} are tagged with their topic unless a
+     * {@code topic} tag is given.
+     *
+     * @param tags tag values by name; names start with a letter or underscore,
+     *             followed by letters, digits and underscores
+     * @return a new LogDbConfig
+     */
+    public LogDbConfig withTags(Map<String, String> tags) {
+        return new LogDbConfig(storage, segmentation, profiling, watchdog, dedupWindow, faults,
+                bridgeThreads, coalesce, writeQueue, rateLimits, tailCacheBytes,
+                slowOperationThresholdMs, openTimeoutMs, reopen, memoryLimit, runtime,
+                compactionRuntime, chunkThresholdBytes, criticalValueCopy, tags, clock,
+                receiveTimestamps, backgroundConcurrency, keyStatsCapacity, statsd, callLimit);
+    }
+
+    /**
+     * Returns a copy of this config with the given clock source.
+     *
+     * <p>By default records keep their {@link Record#timestampMs()}. With
+     * another source, the native layer stamps each appended batch itself,
+     * giving log append time rather than create time; see
+     * {@link ClockSource}. A {@link ClockSource#MANUAL} clock makes the
+     * timestamps of a test deterministic.
+     *
+     * @param clock source of the timestamps stored with appended records
+     * @return a new LogDbConfig
+     */
+    public LogDbConfig withClock(ClockSource clock) {
+        return new LogDbConfig(storage, segmentation, profiling, watchdog, dedupWindow, faults,
+                bridgeThreads, coalesce, writeQueue, rateLimits, tailCacheBytes,
+                slowOperationThresholdMs, openTimeoutMs, reopen, memoryLimit, runtime,
+                compactionRuntime, chunkThresholdBytes, criticalValueCopy, tags, clock,
+                receiveTimestamps, backgroundConcurrency, keyStatsCapacity, statsd, callLimit);
+    }
+
+    /**
+     * Returns a copy of this config recording receive times or not.
+     *
+     * <p>OMB latency across hosts includes the drift between the producer's
+     * clock and the reader's. With receive timestamps, the native layer
+     * stores with each appended value the time its clock read when the
+     * batch arrived, beside the record's {@link Record#timestampMs()}, in a
+     * second version of the timestamp envelope. Scans of such values, by
+     * any {@link LogDb} or {@link LogDbReader}, report the receive time minus
+     * the producer timestamp as {@code clock_skew.samples},
+     * {@code clock_skew.min_ms}, {@code clock_skew.max_ms} and
+     * {@code clock_skew.mean_ms} in their stats. Entries still return the
+     * producer timestamp. Each value grows by 8 bytes; values stored as
+     * chunks, generated by {@link LogDb#appendSynthetic(byte[], int, int)},
+     * appended through an {@link AppendRing} or imported carry no receive
+     * time.
+     *
+     * @param receiveTimestamps whether appended values record their receive time
+     * @return a new LogDbConfig
+     */
+    public LogDbConfig withReceiveTimestamps(boolean receiveTimestamps) {
+        return new LogDbConfig(storage, segmentation, profiling, watchdog, dedupWindow, faults,
+                bridgeThreads, coalesce, writeQueue, rateLimits, tailCacheBytes,
+                slowOperationThresholdMs, openTimeoutMs, reopen, memoryLimit, runtime,
+                compactionRuntime, chunkThresholdBytes, criticalValueCopy, tags, clock,
+                receiveTimestamps, backgroundConcurrency, keyStatsCapacity, statsd, callLimit);
+    }
+
+    /**
+     * Returns a copy of this config with the given background concurrency.
+     *
+     * <p>Appends and scans passed {@link OperationPriority#BACKGROUND} take
+     * one of {@code backgroundConcurrency} native permits while they access
+     * storage, after any {@link #rateLimits() rate limits} admitted them; the
+     * others wait for a permit natively. Foreground calls take no permit.
+     * Waits are reported as {@code priority.background_waits} and
+     * {@code priority.background_wait_nanos} in the handle's stats.
+     *
+     * @param backgroundConcurrency number of background calls accessing
+     *                              storage at once, at least 1
+     * @return a new LogDbConfig
+     */
+    public LogDbConfig withBackgroundConcurrency(int backgroundConcurrency) {
+        return new LogDbConfig(storage, segmentation, profiling, watchdog, dedupWindow, faults,
+                bridgeThreads, coalesce, writeQueue, rateLimits, tailCacheBytes,
+                slowOperationThresholdMs, openTimeoutMs, reopen, memoryLimit, runtime,
+                compactionRuntime, chunkThresholdBytes, criticalValueCopy, tags, clock,
+                receiveTimestamps, backgroundConcurrency, keyStatsCapacity, statsd, callLimit);
+    }
+
+    /**
+     * Returns a copy of this config with per-key statistics for the given
+     * number of keys.
+     *
+     * <p>Appends and scans then count the records and bytes they move per
+     * key, reported by {@link LogDb#hotKeys(int)}. Past {@code keyStatsCapacity}
+     * keys, the least recently used key is forgotten. The keys tracked and
+     * forgotten are reported as {@code key_stats.keys} and
+     * {@code key_stats.evicted} in the handle's stats.
+     *
+     * @param keyStatsCapacity number of keys with statistics; 0 disables them
+     * @return a new LogDbConfig
+     */
+    public LogDbConfig withKeyStatsCapacity(int keyStatsCapacity) {
+        return new LogDbConfig(storage, segmentation, profiling, watchdog, dedupWindow, faults,
+                bridgeThreads, coalesce, writeQueue, rateLimits, tailCacheBytes,
+                slowOperationThresholdMs, openTimeoutMs, reopen, memoryLimit, runtime,
+                compactionRuntime, chunkThresholdBytes, criticalValueCopy, tags, clock,
+                receiveTimestamps, backgroundConcurrency, keyStatsCapacity, statsd, callLimit);
+    }
+
+    /**
+     * Returns a copy of this config sending the native stats to a StatsD
+     * agent.
+     *
+     * <p>The agent's address is resolved when the log opens, which fails if
+     * it cannot be. Datagrams that cannot be delivered are dropped and
+     * counted. Logs opened through a {@link LogDbManager} each send their
+     * own stats, told apart by their {@code topic} tag.
+     *
+     * @param statsd export of the native stats, or
+     *               {@link StatsdConfig#DISABLED}
+     * @return a new LogDbConfig
+     */
+    public LogDbConfig withStatsd(StatsdConfig statsd) {
+        return new LogDbConfig(storage, segmentation, profiling, watchdog, dedupWindow, faults,
+                bridgeThreads, coalesce, writeQueue, rateLimits, tailCacheBytes,
+                slowOperationThresholdMs, openTimeoutMs, reopen, memoryLimit, runtime,
+                compactionRuntime, chunkThresholdBytes, criticalValueCopy, tags, clock,
+                receiveTimestamps, backgroundConcurrency, keyStatsCapacity, statsd, callLimit);
+    }
+
+    /**
+     * Returns a copy of this config limiting the blocking calls running at
+     * once.
+     *
+     * <p>Logs opened through a {@link LogDbManager} each have their own
+     * limit.
+     *
+     * @param callLimit limit on blocking calls, or
+     *                  {@link CallLimitConfig#UNLIMITED}
+     * @return a new LogDbConfig
+     */
+    public LogDbConfig withCallLimit(CallLimitConfig callLimit) {
+        return new LogDbConfig(storage, segmentation, profiling, watchdog, dedupWindow, faults,
+                bridgeThreads, coalesce, writeQueue, rateLimits, tailCacheBytes,
+                slowOperationThresholdMs, openTimeoutMs, reopen, memoryLimit, runtime,
+                compactionRuntime, chunkThresholdBytes, criticalValueCopy, tags, clock,
+                receiveTimestamps, backgroundConcurrency, keyStatsCapacity, statsd, callLimit);
     }
 
     /**
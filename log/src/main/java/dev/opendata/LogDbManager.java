@@ -0,0 +1,181 @@
+package dev.opendata;
+
+import dev.opendata.common.StorageConfig;
+
+import java.io.Closeable;
+import java.util.Collections;
+import java.util.List;
+import java.util.Map;
+import java.util.Set;
+import java.util.WeakHashMap;
+
+/**
+ * Manages topics, each its own {@link LogDb}, that share native resources.
+ *
+ * <p>Every {@link LogDb} opened standalone starts its own Tokio runtimes. A
+ * manager starts one pair of runtimes and opens each topic on them, so
+ * applications with many topics avoid a runtime per topic.
+ *
+ * <p>Topics are created and deleted through the manager and recorded in a
+ * catalog stored alongside them, so they survive restarts. With
+ * {@link StorageConfig.SlateDb} storage the catalog lives at
+ * {@code <path>/catalog} and topic data under {@code <path>/topics}. Deleting
+ * a topic removes it from the catalog without deleting its data; a topic
+ * created again under the same name starts empty. With
+ * {@link StorageConfig.InMemory} storage topics last as long as the manager.
+ *
+ * <p>Logs returned by {@link #openLog(String)} behave like any other
+ * {@link LogDb} and may be closed individually. Closing the manager closes
+ * every log it opened that is still open. The manager holds its logs weakly,
+ * so a log the application drops is closed by its cleaner as usual.
+ */
+public class LogDbManager implements Closeable {
+
+    static {
+        System.loadLibrary("opendata_log_jni");
+    }
+
+    private final long handle;
+    // Weak so that the manager neither pins dropped logs nor keeps their cleaners from running
+    private final Set<LogDb> logs = Collections.newSetFromMap(new WeakHashMap<>());
+    private boolean closed = false;
+
+    private LogDbManager(long handle) {
+        this.handle = handle;
+    }
+
+    /**
+     * Opens a manager whose logs all use the given configuration.
+     *
+     * @param config the configuration applied to every log; its storage path
+     *               is the parent of each log's storage
+     * @return a new LogDbManager
+     */
+    public static LogDbManager open(LogDbConfig config) {
+        return open(config, Map.of());
+    }
+
+    /**
+     * Opens a manager whose topics use the given configuration, except where
+     * overridden per topic.
+     *
+     * @param config       the configuration applied to every topic; its storage
+     *                     path is the parent of each topic's storage
+     * @param topicConfigs per-topic overrides of the base configuration, by topic name
+     * @return a new LogDbManager
+     */
+    public static LogDbManager open(LogDbConfig config, Map<String, TopicConfig> topicConfigs) {
+        if (config == null) {
+            throw new IllegalArgumentException("config must not be null");
+        }
+        if (topicConfigs == null) {
+            throw new IllegalArgumentException("topicConfigs must not be null");
+        }
+        topicConfigs.forEach((name, topicConfig) -> {
+            validateTopicName(name);
+            if (topicConfig == null) {
+                throw new IllegalArgumentException("config for topic " + name + " must not be null");
+            }
+        });
+        long handle = nativeCreate(config, topicConfigs);
+        if (handle == 0) {
+            throw new RuntimeException("Failed to create LogDbManager instance");
+        }
+        return new LogDbManager(handle);
+    }
+
+    /**
+     * Creates a topic.
+     *
+     * @param name the topic name, which must not be empty or contain {@code '/'}
+     * @throws IllegalArgumentException if the topic already exists
+     */
+    public synchronized void createTopic(String name) {
+        validateTopicName(name);
+        checkNotClosed();
+        nativeCreateTopic(handle, name);
+    }
+
+    /**
+     * Deletes a topic. The topic must not be open.
+     *
+     * @param name the topic name
+     * @throws IllegalArgumentException if the topic does not exist
+     * @throws IllegalStateException    if the topic is open
+     */
+    public synchronized void deleteTopic(String name) {
+        validateTopicName(name);
+        checkNotClosed();
+        nativeDeleteTopic(handle, name);
+    }
+
+    /**
+     * Returns the names of all topics in sorted order.
+     *
+     * @return the topic names
+     */
+    public synchronized List<String> listTopics() {
+        checkNotClosed();
+        return List.of(nativeListTopics(handle));
+    }
+
+    /**
+     * Opens a topic on this manager's shared runtimes.
+     *
+     * <p>A topic may only be open once at a time; close the returned log
+     * before opening the topic again.
+     *
+     * @param name the topic name
+     * @return the opened log
+     * @throws IllegalArgumentException if the topic does not exist
+     */
+    public synchronized LogDb openLog(String name) {
+        validateTopicName(name);
+        checkNotClosed();
+        long logHandle = nativeOpenLog(handle, name);
+        if (logHandle == 0) {
+            throw new RuntimeException("Failed to open log " + name);
+        }
+        LogDb log = new LogDb(logHandle);
+        logs.add(log);
+        return log;
+    }
+
+    @Override
+    public synchronized void close() {
+        if (!closed) {
+            closed = true;
+            try {
+                for (LogDb log : List.copyOf(logs)) {
+                    log.close();
+                }
+            } finally {
+                logs.clear();
+                nativeClose(handle);
+            }
+        }
+    }
+
+    static void validateTopicName(String name) {
+        if (name == null || name.isEmpty()) {
+            throw new IllegalArgumentException("name must not be empty");
+        }
+        if (name.indexOf('/') >= 0) {
+            throw new IllegalArgumentException("name must not contain '/'");
+        }
+    }
+
+    private void checkNotClosed() {
+        if (closed) {
+            throw new IllegalStateException("LogDbManager is closed");
+        }
+    }
+
+    // Native methods
+    private static native long nativeCreate(LogDbConfig config, Map<String, TopicConfig> topicConfigs);
+    private static native void nativeCreateTopic(long handle, String name);
+    private static native void nativeDeleteTopic(long handle, String name);
+    private static native String[] nativeListTopics(long handle);
+    private static native long nativeOpenLog(long handle, String name);
+    private static native void nativeClose(long handle);
+}
@@ -1,7 +1,12 @@
 package dev.opendata;
 
 import java.io.Closeable;
+import java.util.Collections;
 import java.util.List;
+import java.util.Map;
+import java.util.Objects;
+import java.util.OptionalLong;
+import java.util.TreeMap;
 
 /**
  * A read-only view of the log.
@@ -40,6 +45,7 @@ public class LogDbReader implements Closeable, LogRead {
 
     private LogDbReader(long handle) {
         this.handle = handle;
+        nativeSetOrigin(handle, NativeHandles.callerStackHash());
     }
 
     /**
@@ -76,11 +82,552 @@ public class LogDbReader implements Closeable, LogRead {
      */
     @Override
     public List<LogEntry> scan(byte[] key, long startSequence, int maxEntries) {
+        return scan(key, startSequence, maxEntries, ReadConsistency.LATEST_COMMITTED);
+    }
+
+    /**
+     * Scans entries for a key at the given consistency level.
+     *
+     * <p>With {@link ReadConsistency#READ_YOUR_WRITES}, the scan first waits
+     * natively until this reader's view includes every entry of the key
+     * appended so far through the writer paired with
+     * {@link #pairWriter(LogDb)}, so a caller that has just appended can read
+     * its entries back. They become visible once the writer has flushed them
+     * and the reader has refreshed, or at once from an
+     * {@link #attachTailCache(LogDb) attached tail cache}; the wait gives up
+     * after 30 seconds.
+     *
+     * @param key           the key to scan
+     * @param startSequence the sequence number to start scanning from
+     * @param maxEntries    maximum number of entries to return
+     * @param consistency   which writes the scan observes
+     * @return list of log entries (may be empty)
+     * @throws IllegalStateException if reading your writes without a paired
+     *         writer, or after it has been closed
+     * @throws dev.opendata.common.OpenDataNativeException if the writes do not
+     *         become visible in time
+     */
+    public List<LogEntry> scan(byte[] key, long startSequence, int maxEntries, ReadConsistency consistency) {
+        if (consistency == null) {
+            throw new IllegalArgumentException("consistency must not be null");
+        }
+        checkNotClosed();
+        LogEntry[] entries = nativeScan(handle, key, startSequence, maxEntries, consistency.ordinal());
+        return entries != null ? List.of(entries) : List.of();
+    }
+
+    @Override
+    public List<LogEntry> scan(byte[] key, long startSequence, int maxEntries, ScanFilter filter) {
+        if (filter == null) {
+            throw new IllegalArgumentException("filter must not be null");
+        }
+        checkNotClosed();
+        LogEntry[] entries = nativeScanFiltered(handle, key, startSequence, maxEntries, filter);
+        return entries != null ? List.of(entries) : List.of();
+    }
+
+    @Override
+    public List<EntryMetadata> scanMetadata(byte[] key, long startSequence, int maxEntries) {
+        checkNotClosed();
+        return EntryMetadata.fromNative(nativeScanMetadata(handle, key, startSequence, maxEntries));
+    }
+
+    /**
+     * Scans a page of entries for a key starting at a sequence number, and
+     * returns it with a token resuming the scan after it.
+     *
+     * <p>Unlike {@link #scan(byte[], long, int)}, the storage iterator
+     * reading the page is kept for a while, and a scan passing the token to
+     * {@link #scanPage(byte[], byte[], int)} continues on it instead of
+     * seeking again. Resumable scans still take entries from an attached
+     * tail cache, but never read ahead.
+     *
+     * @param key           the key to scan
+     * @param startSequence the sequence number to start scanning from
+     * @param maxEntries    maximum number of entries to return
+     * @return the page and its resume token
+     */
+    public ScanPage scanPage(byte[] key, long startSequence, int maxEntries) {
+        checkNotClosed();
+        return scanPage(key, null, startSequence, maxEntries);
+    }
+
+    /**
+     * Scans the page of entries for a key following the page that returned
+     * {@code resumeToken}.
+     *
+     * @param key         the key to scan, the one the token was issued for
+     * @param resumeToken the token of the previous page
+     * @param maxEntries  maximum number of entries to return
+     * @return the page and its resume token
+     * @throws IllegalArgumentException if the token is malformed or was
+     *                                  issued for another key
+     */
+    public ScanPage scanPage(byte[] key, byte[] resumeToken, int maxEntries) {
+        checkNotClosed();
+        return scanPage(key, Objects.requireNonNull(resumeToken, "resumeToken"), 0, maxEntries);
+    }
+
+    private ScanPage scanPage(byte[] key, byte[] resumeToken, long startSequence, int maxEntries) {
+        byte[] nextToken = new byte[ScanPage.TOKEN_LENGTH];
+        LogEntry[] entries = nativeScanResumable(handle, key, resumeToken, startSequence, maxEntries, nextToken);
+        return new ScanPage(entries != null ? List.of(entries) : List.of(), nextToken);
+    }
+
+    /**
+     * Returns the next entries for a key, continuing from this reader's position.
+     *
+     * <p>The reader tracks a position per key: it starts at sequence 0, advances
+     * past the entries each poll returns, and can be moved with
+     * {@link #seek(byte[], long)} or {@link #seekToTimestamp(byte[], long)}.
+     * Positions are held in memory; resume across restarts by seeking to a
+     * committed offset. A key should be polled from one thread at a time.
+     *
+     * @param key        the key to poll
+     * @param maxEntries maximum number of entries to return
+     * @return list of log entries (may be empty)
+     */
+    public List<LogEntry> poll(byte[] key, int maxEntries) {
+        if (key == null) {
+            throw new IllegalArgumentException("key must not be null");
+        }
+        checkNotClosed();
+        LogEntry[] entries = nativePoll(handle, key, maxEntries);
+        return entries != null ? List.of(entries) : List.of();
+    }
+
+    /**
+     * Moves this reader's position for a key.
+     *
+     * @param key      the key to seek
+     * @param sequence the next sequence {@link #poll(byte[], int)} should read from
+     */
+    public void seek(byte[] key, long sequence) {
+        if (key == null) {
+            throw new IllegalArgumentException("key must not be null");
+        }
+        if (sequence < 0) {
+            throw new IllegalArgumentException("sequence must not be negative");
+        }
         checkNotClosed();
-        LogEntry[] entries = nativeScan(handle, key, startSequence, maxEntries);
+        nativeSeek(handle, key, sequence);
+    }
+
+    /**
+     * Moves this reader's position for a key to its first entry with a
+     * timestamp at or after the given time.
+     *
+     * <p>If no entry is that recent, the position moves past the key's last
+     * entry. This scans the key from the beginning and is intended for
+     * occasional repositioning, not per-poll use.
+     *
+     * @param key         the key to seek
+     * @param timestampMs the timestamp in milliseconds since the epoch
+     * @return the new position
+     */
+    public long seekToTimestamp(byte[] key, long timestampMs) {
+        if (key == null) {
+            throw new IllegalArgumentException("key must not be null");
+        }
+        checkNotClosed();
+        return nativeSeekToTimestamp(handle, key, timestampMs);
+    }
+
+    /**
+     * Makes data written since the last refresh visible immediately.
+     *
+     * <p>The reader normally discovers new data at its configured refresh
+     * interval. Call this when another handle has just written data that must
+     * be read without waiting, for example in tests. Positions and cached
+     * offsets are kept.
+     */
+    public void refresh() {
+        checkNotClosed();
+        nativeRefresh(handle);
+    }
+
+    /**
+     * Begins a snapshot pinning this reader's current view of the log.
+     *
+     * <p>Scans through the snapshot all observe the same cut of the log,
+     * across keys, until it is closed. See {@link ReaderSnapshot}.
+     *
+     * @return the snapshot
+     */
+    public ReaderSnapshot beginSnapshot() {
+        checkNotClosed();
+        return new ReaderSnapshot(this, nativeBeginSnapshot(handle));
+    }
+
+    List<LogEntry> scanSnapshot(long snapshot, byte[] key, long startSequence, int maxEntries) {
+        checkNotClosed();
+        LogEntry[] entries = nativeScanSnapshot(handle, snapshot, key, startSequence, maxEntries);
         return entries != null ? List.of(entries) : List.of();
     }
 
+    void endSnapshot(long snapshot) {
+        // Closing the reader released every snapshot
+        if (!closed) {
+            nativeEndSnapshot(handle, snapshot);
+        }
+    }
+
+    /**
+     * Returns this reader's position for a key.
+     *
+     * @param key the key
+     * @return the next sequence {@link #poll(byte[], int)} reads from
+     */
+    public long position(byte[] key) {
+        if (key == null) {
+            throw new IllegalArgumentException("key must not be null");
+        }
+        checkNotClosed();
+        return nativePosition(handle, key);
+    }
+
+    /**
+     * Returns the native latency histogram for the given operation.
+     *
+     * <p>Readers only record {@link Operation#SCAN} latencies; other operations
+     * return an empty histogram. See {@link LogDb#latencyHistogram(Operation)}
+     * for the encoding.
+     *
+     * @param operation the operation to return latencies for
+     * @return the encoded histogram
+     */
+    public byte[] latencyHistogram(Operation operation) {
+        if (operation == null) {
+            throw new IllegalArgumentException("operation must not be null");
+        }
+        checkNotClosed();
+        return nativeGetLatencyHistogram(handle, operation.ordinal());
+    }
+
+    /**
+     * Blocks until this reader's view includes an entry for a key at or after a sequence.
+     *
+     * <p>The wait happens natively, so callers measuring end-to-end latency
+     * avoid the jitter of polling from a Java loop. Data written by other
+     * processes becomes visible at the reader's refresh interval.
+     *
+     * @param key       the key to wait on
+     * @param sequence  the sequence to wait for
+     * @param timeoutMs maximum time to wait in milliseconds
+     * @return true if the sequence became visible, false if the timeout elapsed first
+     */
+    public boolean waitForSequence(byte[] key, long sequence, long timeoutMs) {
+        if (key == null) {
+            throw new IllegalArgumentException("key must not be null");
+        }
+        if (sequence < 0) {
+            throw new IllegalArgumentException("sequence must not be negative");
+        }
+        if (timeoutMs < 0) {
+            throw new IllegalArgumentException("timeoutMs must not be negative");
+        }
+        checkNotClosed();
+        return nativeWaitForSequence(handle, key, sequence, timeoutMs);
+    }
+
+    /**
+     * Watches a key for entries in this reader's view.
+     *
+     * <p>The reader polls: once per refresh interval it scans each watched
+     * key for entries at or after the sequence it last reported, and calls
+     * the listener with the key's new tail if there are any. Entries are
+     * reported whoever appended them, including those already stored when
+     * the watch is registered, and at the latest one refresh interval after
+     * they become visible to the reader. Polling costs one scan per watched
+     * key per interval, however rarely the key is written; watching through
+     * {@link LogDb#watchAppends(byte[], long, TailListener)} in the writer's
+     * process needs none.
+     *
+     * @param key          the key to watch
+     * @param fromSequence the first sequence whose entries notify the listener
+     * @param listener     the listener, see {@link TailListener} for its threading
+     * @return the watch, closed to stop notifications; the listener is only
+     *         notified while the watch is reachable, see {@link AppendWatch}
+     */
+    public AppendWatch watchAppends(byte[] key, long fromSequence, TailListener listener) {
+        if (key == null) {
+            throw new IllegalArgumentException("key must not be null");
+        }
+        if (fromSequence < 0) {
+            throw new IllegalArgumentException("fromSequence must be >= 0");
+        }
+        if (listener == null) {
+            throw new IllegalArgumentException("listener must not be null");
+        }
+        checkNotClosed();
+        return new AppendWatch(this::unwatch, nativeWatch(handle, key, fromSequence, listener), listener);
+    }
+
+    void unwatch(long id) {
+        if (!closed) {
+            nativeUnwatch(handle, id);
+        }
+    }
+
+    /**
+     * Returns how far this reader's position for a key trails the key's tail.
+     *
+     * <p>The lag is the key's tail sequence (one past its last entry in this
+     * reader's view) minus {@link #position(byte[])}. Sequences are global
+     * across keys, so when keys are interleaved the lag counts sequences rather
+     * than entries. Repeated calls only scan entries appended since the last call.
+     *
+     * @param key the consumed key
+     * @return the lag in sequences, or 0 if the reader is caught up
+     */
+    public long lag(byte[] key) {
+        if (key == null) {
+            throw new IllegalArgumentException("key must not be null");
+        }
+        checkNotClosed();
+        return nativeGetLag(handle, key, -1);
+    }
+
+    /**
+     * Returns how far a consumer group's committed offset for a key trails the key's tail.
+     *
+     * <p>A group that has never committed for the key is measured from sequence 0.
+     *
+     * @param group the consumer group
+     * @param key   the consumed key
+     * @return the lag in sequences, or 0 if the group is caught up
+     * @see #lag(byte[])
+     */
+    public long lag(String group, byte[] key) {
+        long committed = fetchOffset(group, key).orElse(0);
+        return nativeGetLag(handle, key, committed);
+    }
+
+    /**
+     * Attaches a writer through which this reader commits offsets.
+     *
+     * <p>A LogDbReader cannot write to the log, so {@link #commitOffset} and
+     * automatic commits (see {@link AutoCommitConfig}) append through the
+     * attached writer. The reader does not keep the writer open: once the writer
+     * is closed, commits through this reader fail.
+     *
+     * @param writer the writer for the same storage
+     */
+    public void attachOffsetWriter(LogDb writer) {
+        if (writer == null) {
+            throw new IllegalArgumentException("writer must not be null");
+        }
+        checkNotClosed();
+        nativeAttachOffsetWriter(handle, writer.getHandle());
+    }
+
+    /**
+     * Attaches the tail cache of a writer in this process.
+     *
+     * <p>Scans through this reader that start within a key's entries cached
+     * by the writer (see {@link LogDbConfig#withTailCacheBytes(long)}) are
+     * answered from the cache instead of storage, which also makes entries
+     * visible before the reader would otherwise discover them. The reader
+     * does not keep the writer open: once the writer is closed, scans go to
+     * storage again.
+     *
+     * @param writer the writer for the same storage
+     * @throws IllegalArgumentException if the writer has no tail cache configured
+     */
+    public void attachTailCache(LogDb writer) {
+        if (writer == null) {
+            throw new IllegalArgumentException("writer must not be null");
+        }
+        checkNotClosed();
+        nativeAttachTailCache(handle, writer.getHandle());
+    }
+
+    /**
+     * Pairs a writer in this process whose writes this reader's
+     * {@link ReadConsistency#READ_YOUR_WRITES} scans observe.
+     *
+     * <p>Only appends through the writer are waited for. The reader does not
+     * keep the writer open: once the writer is closed, such scans fail.
+     *
+     * @param writer the writer for the same storage
+     */
+    public void pairWriter(LogDb writer) {
+        if (writer == null) {
+            throw new IllegalArgumentException("writer must not be null");
+        }
+        checkNotClosed();
+        nativePairWriter(handle, writer.getHandle());
+    }
+
+    /**
+     * Commits a consumer group's offset for a key through the attached writer.
+     *
+     * @param group    the consumer group
+     * @param key      the consumed key
+     * @param sequence the sequence to commit, typically the next sequence to read
+     * @throws IllegalStateException if no writer is attached or it has been closed
+     * @see LogDb#commitOffset(String, byte[], long)
+     */
+    public void commitOffset(String group, byte[] key, long sequence) {
+        LogDb.validateOffsetArgs(group, key);
+        if (sequence < 0) {
+            throw new IllegalArgumentException("sequence must not be negative");
+        }
+        checkNotClosed();
+        nativeCommitOffset(handle, group, key, sequence);
+    }
+
+    /**
+     * Fetches a consumer group's committed offset for a key.
+     *
+     * <p>Commits made by other processes become visible once this reader
+     * refreshes its view of the log.
+     *
+     * @param group the consumer group
+     * @param key   the consumed key
+     * @return the committed sequence, or empty if the group never committed for the key
+     */
+    public OptionalLong fetchOffset(String group, byte[] key) {
+        LogDb.validateOffsetArgs(group, key);
+        checkNotClosed();
+        long offset = nativeFetchOffset(handle, group, key);
+        return offset >= 0 ? OptionalLong.of(offset) : OptionalLong.empty();
+    }
+
+    /**
+     * Returns the accumulated per-phase timings of native calls for an operation.
+     *
+     * <p>Timings are only accumulated when profiling is enabled in the config;
+     * otherwise all values are zero.
+     *
+     * @param operation the operation to return timings for
+     * @return the accumulated timing breakdown
+     */
+    public TimingBreakdown timingBreakdown(Operation operation) {
+        if (operation == null) {
+            throw new IllegalArgumentException("operation must not be null");
+        }
+        checkNotClosed();
+        return TimingBreakdown.fromNative(nativeGetTimingBreakdown(handle, operation.ordinal()));
+    }
+
+    /**
+     * Marks the start of a benchmark warmup.
+     *
+     * <p>Discards the latency histograms, timing breakdowns and blocked caller
+     * peak recorded so far, and reports {@code warming_up} as 1 in
+     * {@link #stats()} until {@link #markWarmupEnd()} is called.
+     */
+    public void markWarmupStart() {
+        checkNotClosed();
+        nativeMarkWarmup(handle, true);
+    }
+
+    /**
+     * Marks the end of a benchmark warmup.
+     *
+     * <p>Discards everything recorded during warmup, so that histograms and
+     * timing breakdowns only cover the measured run. {@code measurement_nanos}
+     * in {@link #stats()} is the time since the last warmup mark.
+     */
+    public void markWarmupEnd() {
+        checkNotClosed();
+        nativeMarkWarmup(handle, false);
+    }
+
+    /**
+     * Blocks until the rate of an operation on this reader is steady.
+     *
+     * <p>Operations from all threads using this reader are counted, so the
+     * workload must run on other threads while this method waits. Marking
+     * warmup while waiting restarts detection.
+     *
+     * @param operation the operation whose rate is sampled
+     * @param config    the sampling windows and variation threshold
+     * @param timeoutMs maximum time to wait in milliseconds
+     * @return true if steady state was reached, false if the timeout elapsed first
+     */
+    public boolean awaitSteadyState(Operation operation, SteadyStateConfig config, long timeoutMs) {
+        if (operation == null) {
+            throw new IllegalArgumentException("operation must not be null");
+        }
+        if (config == null) {
+            throw new IllegalArgumentException("config must not be null");
+        }
+        if (timeoutMs < 0) {
+            throw new IllegalArgumentException("timeoutMs must not be negative");
+        }
+        checkNotClosed();
+        return nativeAwaitSteadyState(handle, operation.ordinal(), config, timeoutMs);
+    }
+
+    /**
+     * Returns a snapshot of native statistics, keyed by dotted metric name.
+     *
+     * <p>Runtime health is reported per Tokio runtime:
+     *
+     * <p>Each runtime reports {@code workers}, {@code alive_tasks},
+     * {@code injection_queue_depth}, {@code busy_nanos} (cumulative across
+     * workers; diff two snapshots against elapsed time to get utilization) and
+     * {@code park_count}. {@code runtime.blocked_callers} and
+     * {@code runtime.blocked_callers_peak} count Java threads blocked waiting on
+     * native calls. {@code uptime_nanos} is the time since the handle was opened;
+     * {@code warming_up} and {@code measurement_nanos} track warmup marks.
+     * With read-ahead, {@code read_ahead.prefetches} counts pages prefetched,
+     * {@code read_ahead.hits} scans served from a prefetched page,
+     * {@code read_ahead.misses} prefetches discarded, and
+     * {@code read_ahead.hit_rate_ppm} the share of hits in parts per million.
+     * {@code scan.cache_bytes} and {@code scan.storage_bytes} total the key and
+     * value bytes scans returned from an attached tail cache and through
+     * storage, including read-ahead pages. With a disk cache,
+     * {@code disk_cache.bytes} and {@code disk_cache.parts} report what the
+     * cache directory holds and {@code disk_cache.parts_added} counts parts
+     * fetched into it since the reader opened, each a read that missed the
+     * cache. With a slow-operation threshold, {@code slow_log.logged} counts
+     * the scans logged as slow.
+     *
+     * @return an unmodifiable, name-ordered map of statistics
+     */
+    public Map<String, Long> stats() {
+        checkNotClosed();
+        return Collections.unmodifiableMap(new TreeMap<>(nativeStats(handle)));
+    }
+
+    /**
+     * Returns the native metrics of this reader, typed.
+     *
+     * <p>The snapshot holds the stats of {@link #stats()} and latency
+     * summaries, each marked as a counter or a gauge. Readers have no
+     * tags, so its tags are empty. See {@link LogDb#metricsSnapshot()}.
+     *
+     * @return a snapshot of the native metrics
+     */
+    public MetricsSnapshot metricsSnapshot() {
+        checkNotClosed();
+        return nativeMetricsSnapshot(handle);
+    }
+
+    /**
+     * Returns the traffic of this reader since the previous call, or since it
+     * opened.
+     *
+     * <p>{@code interval_nanos} is the length of the interval.
+     * {@code records_in} and {@code bytes_in} count the records appended in it
+     * and their key and value bytes, and {@code records_out} and
+     * {@code bytes_out} the entries scans returned; bytes are counted as
+     * stored, with timestamp headers and chunks. Each count is followed by its
+     * per-second rate over the interval, such as {@code records_in_per_sec}.
+     * A reader never appends, so its records and bytes in are 0.
+     * Calls from several threads split the traffic between them, so a single
+     * reporter should call this method.
+     *
+     * @return an unmodifiable, name-ordered map of the interval's traffic
+     */
+    public Map<String, Long> intervalStats() {
+        checkNotClosed();
+        return Collections.unmodifiableMap(new TreeMap<>(nativeIntervalStats(handle)));
+    }
+
     @Override
     public void close() {
         if (!closed) {
@@ -97,6 +644,39 @@ public class LogDbReader implements Closeable, LogRead {
 
     // Native methods
     private static native long nativeCreate(LogDbReaderConfig config);
-    private static native LogEntry[] nativeScan(long handle, byte[] key, long startSequence, long maxEntries);
+    private static native LogEntry[] nativeScan(
+            long handle, byte[] key, long startSequence, long maxEntries, int consistency);
+    private static native long[] nativeScanMetadata(long handle, byte[] key, long startSequence, long maxEntries);
+    private static native LogEntry[] nativeScanFiltered(
+            long handle, byte[] key, long startSequence, long maxEntries, ScanFilter filter);
+    private static native LogEntry[] nativePoll(long handle, byte[] key, long maxEntries);
+    private static native LogEntry[] nativeScanResumable(
+            long handle, byte[] key, byte[] token, long startSequence, long maxEntries, byte[] nextToken);
+    private static native void nativeSeek(long handle, byte[] key, long sequence);
+    private static native long nativeSeekToTimestamp(long handle, byte[] key, long timestampMs);
+    private static native void nativeRefresh(long handle);
+    private static native long nativeBeginSnapshot(long handle);
+    private static native LogEntry[] nativeScanSnapshot(
+            long handle, long snapshot, byte[] key, long startSequence, long maxEntries);
+    private static native void nativeEndSnapshot(long handle, long snapshot);
+    private static native long nativePosition(long handle, byte[] key);
+    private static native boolean nativeWaitForSequence(long handle, byte[] key, long sequence, long timeoutMs);
+    private static native long nativeWatch(long handle, byte[] key, long fromSequence, TailListener listener);
+    private static native boolean nativeUnwatch(long handle, long id);
+    private static native long nativeGetLag(long handle, byte[] key, long fromSequence);
+    private static native void nativeAttachOffsetWriter(long handle, long writerHandle);
+    private static native void nativeAttachTailCache(long handle, long writerHandle);
+    private static native void nativePairWriter(long handle, long writerHandle);
+    private static native void nativeCommitOffset(long handle, String group, byte[] key, long sequence);
+    private static native long nativeFetchOffset(long handle, String group, byte[] key);
+    private static native byte[] nativeGetLatencyHistogram(long handle, int operation);
+    private static native long[] nativeGetTimingBreakdown(long handle, int operation);
+    private static native Map<String, Long> nativeStats(long handle);
+    private static native MetricsSnapshot nativeMetricsSnapshot(long handle);
+    private static native Map<String, Long> nativeIntervalStats(long handle);
+    private static native void nativeMarkWarmup(long handle, boolean warmingUp);
+    private static native boolean nativeAwaitSteadyState(
+            long handle, int operation, SteadyStateConfig config, long timeoutMs);
     private static native void nativeClose(long handle);
+    private static native void nativeSetOrigin(long handle, int creationStackHash);
 }
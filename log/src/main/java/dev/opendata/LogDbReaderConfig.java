@@ -8,13 +8,40 @@ import dev.opendata.common.StorageConfig;
  * <p>This record holds settings for read-only log access, including storage
  * backend configuration and automatic refresh settings.
  *
+ * <p>The refresh interval trades read freshness against object-store request
+ * cost: each refresh polls object storage for new data, so shorter intervals
+ * make other processes' writes visible sooner at the price of more requests.
+ * The effective interval, including the native default, is reported as
+ * {@code refresh_interval_ms} in {@link LogDbReader#stats()}.
+ *
+ * <p>The {@code OPENDATA_STORAGE_*} environment variables override storage
+ * settings of the config as they do for {@link LogDbConfig}.
+ *
  * @param storage           storage backend configuration
  * @param refreshIntervalMs interval in milliseconds for discovering new log data
  *                          written by other processes; null to use native default
+ * @param profiling         whether to accumulate per-phase native call timings,
+ *                          see {@link LogDbReader#timingBreakdown(Operation)}
+ * @param watchdog          watchdog configuration for stuck native calls
+ * @param autoCommit        automatic offset commit configuration, or null to disable
+ * @param readAhead         whether a scan that fills its page prefetches the next
+ *                          page of the key in the background, see
+ *                          {@link #withReadAhead(boolean)}
+ * @param slowOperationThresholdMs duration in milliseconds from which scans are
+ *                          logged as slow; 0 disables the log, see
+ *                          {@link #withSlowOperationThreshold(long)}
+ * @param runtimeMode       scheduler of the reader's native runtime, see
+ *                          {@link #withRuntimeMode(RuntimeMode)}
  */
 public record LogDbReaderConfig(
         StorageConfig storage,
-        Long refreshIntervalMs
+        Long refreshIntervalMs,
+        boolean profiling,
+        WatchdogConfig watchdog,
+        AutoCommitConfig autoCommit,
+        boolean readAhead,
+        long slowOperationThresholdMs,
+        RuntimeMode runtimeMode
 ) {
 
     /**
@@ -26,6 +53,17 @@ public record LogDbReaderConfig(
         this(storage, null);
     }
 
+    /**
+     * Creates a config with the specified storage and refresh interval.
+     *
+     * @param storage           storage backend configuration
+     * @param refreshIntervalMs refresh interval in milliseconds, or null for the native default
+     */
+    public LogDbReaderConfig(StorageConfig storage, Long refreshIntervalMs) {
+        this(storage, refreshIntervalMs, false, WatchdogConfig.DISABLED, null, false, 0,
+                RuntimeMode.MULTI_THREAD);
+    }
+
     public LogDbReaderConfig {
         if (storage == null) {
             throw new IllegalArgumentException("storage must not be null");
@@ -33,6 +71,112 @@ public record LogDbReaderConfig(
         if (refreshIntervalMs != null && refreshIntervalMs <= 0) {
             throw new IllegalArgumentException("refreshIntervalMs must be positive");
         }
+        if (watchdog == null) {
+            throw new IllegalArgumentException("watchdog must not be null");
+        }
+        if (slowOperationThresholdMs < 0) {
+            throw new IllegalArgumentException("slowOperationThresholdMs must not be negative");
+        }
+        if (runtimeMode == null) {
+            throw new IllegalArgumentException("runtimeMode must not be null");
+        }
+    }
+
+    /**
+     * Returns a copy of this config with the given refresh interval.
+     *
+     * @param refreshIntervalMs refresh interval in milliseconds, or null for the native default
+     * @return a new LogDbReaderConfig
+     */
+    public LogDbReaderConfig withRefreshIntervalMs(Long refreshIntervalMs) {
+        return new LogDbReaderConfig(storage, refreshIntervalMs, profiling, watchdog, autoCommit,
+                readAhead, slowOperationThresholdMs, runtimeMode);
+    }
+
+    /**
+     * Returns a copy of this config with profiling enabled or disabled.
+     *
+     * @param profiling whether to accumulate per-phase native call timings
+     * @return a new LogDbReaderConfig
+     */
+    public LogDbReaderConfig withProfiling(boolean profiling) {
+        return new LogDbReaderConfig(storage, refreshIntervalMs, profiling, watchdog, autoCommit,
+                readAhead, slowOperationThresholdMs, runtimeMode);
+    }
+
+    /**
+     * Returns a copy of this config with the given watchdog configuration.
+     *
+     * @param watchdog watchdog configuration for stuck native calls
+     * @return a new LogDbReaderConfig
+     */
+    public LogDbReaderConfig withWatchdog(WatchdogConfig watchdog) {
+        return new LogDbReaderConfig(storage, refreshIntervalMs, profiling, watchdog, autoCommit,
+                readAhead, slowOperationThresholdMs, runtimeMode);
+    }
+
+    /**
+     * Returns a copy of this config with the given auto-commit configuration.
+     *
+     * @param autoCommit automatic offset commit configuration, or null to disable
+     * @return a new LogDbReaderConfig
+     */
+    public LogDbReaderConfig withAutoCommit(AutoCommitConfig autoCommit) {
+        return new LogDbReaderConfig(storage, refreshIntervalMs, profiling, watchdog, autoCommit,
+                readAhead, slowOperationThresholdMs, runtimeMode);
+    }
+
+    /**
+     * Returns a copy of this config with read-ahead enabled or disabled.
+     *
+     * <p>With read-ahead, a scan that returns a full page of entries starts
+     * reading the following page of the same key in the background. A
+     * consumer that then scans from right after the last entry, for at most
+     * the same number of entries, receives the prefetched page without a
+     * storage round trip. Other scans discard the prefetch, so random access
+     * pays for reads it never uses.
+     *
+     * @param readAhead whether to prefetch the next page of sequential scans
+     * @return a new LogDbReaderConfig
+     */
+    public LogDbReaderConfig withReadAhead(boolean readAhead) {
+        return new LogDbReaderConfig(storage, refreshIntervalMs, profiling, watchdog, autoCommit,
+                readAhead, slowOperationThresholdMs, runtimeMode);
+    }
+
+    /**
+     * Returns a copy of this config with the given slow-operation threshold.
+     *
+     * <p>Every scan whose storage call takes at least the threshold is logged
+     * as a warning to the SLF4J logger {@code dev.opendata.SlowOperations},
+     * with its key and the sequences it returned. The number of scans logged
+     * is reported as {@code slow_log.logged} in {@link LogDbReader#stats()}.
+     *
+     * @param slowOperationThresholdMs threshold in milliseconds, or 0 to log nothing
+     * @return a new LogDbReaderConfig
+     */
+    public LogDbReaderConfig withSlowOperationThreshold(long slowOperationThresholdMs) {
+        return new LogDbReaderConfig(storage, refreshIntervalMs, profiling, watchdog, autoCommit,
+                readAhead, slowOperationThresholdMs, runtimeMode);
+    }
+
+    /**
+     * Returns a copy of this config with the given runtime mode.
+     *
+     * <p>By default a reader starts a native worker thread per CPU. With
+     * {@link RuntimeMode#CURRENT_THREAD}, a single native thread runs the
+     * reader's background work, such as refreshes, read-ahead and automatic
+     * commits, which keeps the idle thread count low when a process opens many
+     * readers. Calls still run on the Java thread making them, so only the
+     * background work shares the one thread. The worker count is reported as
+     * {@code runtime.workers} in {@link LogDbReader#stats()}.
+     *
+     * @param runtimeMode scheduler of the reader's native runtime
+     * @return a new LogDbReaderConfig
+     */
+    public LogDbReaderConfig withRuntimeMode(RuntimeMode runtimeMode) {
+        return new LogDbReaderConfig(storage, refreshIntervalMs, profiling, watchdog, autoCommit,
+                readAhead, slowOperationThresholdMs, runtimeMode);
     }
 
     /**
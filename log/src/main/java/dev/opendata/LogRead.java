@@ -28,4 +28,37 @@ public interface LogRead {
      * @return list of log entries (may be empty)
      */
     List<LogEntry> scan(byte[] key, long startSequence, int maxEntries);
+
+    /**
+     * Scans entries from the log for the given key starting at a sequence
+     * number, returning only those passing a filter.
+     *
+     * <p>The filter is evaluated natively and entries failing it are
+     * skipped without being copied out. The scan continues past skipped
+     * entries until it has found {@code maxEntries} matching ones or reached
+     * the end of the key, so a selective filter may read far more entries
+     * from storage than it returns.
+     *
+     * @param key           the key to scan
+     * @param startSequence the sequence number to start scanning from
+     * @param maxEntries    maximum number of entries to return
+     * @param filter        the bounds entries must fall within
+     * @return list of matching log entries (may be empty)
+     */
+    List<LogEntry> scan(byte[] key, long startSequence, int maxEntries, ScanFilter filter);
+
+    /**
+     * Scans entries like {@link #scan(byte[], long, int)}, returning only
+     * their sequence, timestamp and value length.
+     *
+     * <p>Keys and values are not copied out of native memory, which makes
+     * this cheaper for existence and lag checks, or for choosing which of a
+     * key's large payloads to fetch with a regular scan.
+     *
+     * @param key           the key to scan
+     * @param startSequence the sequence number to start scanning from
+     * @param maxEntries    maximum number of entries to return
+     * @return list of entry metadata (may be empty)
+     */
+    List<EntryMetadata> scanMetadata(byte[] key, long startSequence, int maxEntries);
 }
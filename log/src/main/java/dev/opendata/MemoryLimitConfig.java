@@ -0,0 +1,73 @@
+package dev.opendata;
+
+/**
+ * Cap on native memory held by one {@link LogDb} handle.
+ *
+ * <p>The limit applies to the total reported as {@code memory.total_bytes} by
+ * {@link LogDb#stats()}: the tail cache, records of appends in flight, scan
+ * results not yet polled and open append rings. An append of any kind that
+ * would take the total past {@code maxBytes} is handled according to
+ * {@code policy}: {@link BackpressurePolicy#BLOCK} waits until appends in
+ * flight finish or scan results are polled, {@link BackpressurePolicy#REJECT}
+ * throws {@link dev.opendata.common.OpenDataBackpressureException}. An append
+ * is always admitted when no other append is in flight, and concurrent
+ * appends may overshoot the limit by their own size, so the cap bounds
+ * runaway producers rather than every byte.
+ *
+ * <p>A caller that polls its own submitted scans should use
+ * {@code REJECT}, since a blocked caller cannot poll.
+ *
+ * @param maxBytes native bytes held before appends are held back, or 0 for
+ *                 no limit
+ * @param policy   what an append does when the limit is reached; BLOCK or
+ *                 REJECT
+ */
+public record MemoryLimitConfig(long maxBytes, BackpressurePolicy policy) {
+
+    /**
+     * Configuration without a memory limit.
+     */
+    public static final MemoryLimitConfig UNLIMITED = new MemoryLimitConfig(0, BackpressurePolicy.BLOCK);
+
+    public MemoryLimitConfig {
+        if (maxBytes < 0) {
+            throw new IllegalArgumentException("maxBytes must not be negative");
+        }
+        if (policy == null) {
+            throw new IllegalArgumentException("policy must not be null");
+        }
+        if (policy == BackpressurePolicy.NOT_READY) {
+            throw new IllegalArgumentException("policy must be BLOCK or REJECT");
+        }
+    }
+
+    /**
+     * Creates a config capping native memory, blocking appends while the
+     * limit is reached.
+     *
+     * @param maxBytes native bytes held before appends block
+     * @return a new MemoryLimitConfig
+     */
+    public static MemoryLimitConfig of(long maxBytes) {
+        return new MemoryLimitConfig(maxBytes, BackpressurePolicy.BLOCK);
+    }
+
+    /**
+     * Returns a copy of this config with the given policy.
+     *
+     * @param policy what an append does when the limit is reached
+     * @return a new MemoryLimitConfig
+     */
+    public MemoryLimitConfig withPolicy(BackpressurePolicy policy) {
+        return new MemoryLimitConfig(maxBytes, policy);
+    }
+
+    /**
+     * Returns whether native memory is capped.
+     *
+     * @return true if the limit is positive
+     */
+    public boolean enabled() {
+        return maxBytes > 0;
+    }
+}
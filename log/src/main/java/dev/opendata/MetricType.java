@@ -0,0 +1,18 @@
+package dev.opendata;
+
+/**
+ * Kind of a metric in a {@link MetricsSnapshot}.
+ *
+ * <p>The declaration order is part of the native contract: the native layer
+ * identifies types by {@link #ordinal()}.
+ */
+public enum MetricType {
+    /**
+     * A total that only grows, such as {@code tail_cache.hits}. Latency
+     * counts, such as {@code append.latency.count}, restart at warmup marks
+     * along with the histograms, see {@link LogDb#markWarmupStart()}.
+     */
+    COUNTER,
+    /** A current value that may go up and down, such as {@code write_queue.records}. */
+    GAUGE
+}
@@ -0,0 +1,68 @@
+package dev.opendata;
+
+import java.util.HashMap;
+import java.util.List;
+import java.util.Map;
+
+/**
+ * The native metrics of a handle at one point in time, as returned by
+ * {@link LogDb#metricsSnapshot()} and {@link LogDbReader#metricsSnapshot()}.
+ *
+ * <p>A snapshot holds every stat of the handle's {@code stats()} together
+ * with the count, median, 99th percentile and maximum of the append and
+ * scan latencies in microseconds, such as {@code append.latency.p99_micros},
+ * each with its {@link MetricType}. Names carry no tags; the handle's tags are
+ * returned separately, so a metrics binder can register each metric once
+ * with the tags as its own and poll snapshots on its own schedule. The
+ * snapshot is taken with a single native call.
+ *
+ * @param tags    the handle's tags, see {@link LogDbConfig#withTags(Map)},
+ *                empty without tags
+ * @param metrics the metrics, in the order the native layer reports them
+ */
+public record MetricsSnapshot(Map<String, String> tags, List<NativeMetric> metrics) {
+
+    public MetricsSnapshot {
+        if (tags == null) {
+            throw new IllegalArgumentException("tags must not be null");
+        }
+        if (metrics == null) {
+            throw new IllegalArgumentException("metrics must not be null");
+        }
+        tags = Map.copyOf(tags);
+        metrics = List.copyOf(metrics);
+    }
+
+    /**
+     * Creates a snapshot from its native representation.
+     *
+     * @param tagPairs the tags' names and values, alternating
+     * @param metrics  the metrics
+     */
+    MetricsSnapshot(String[] tagPairs, NativeMetric[] metrics) {
+        this(toMap(tagPairs), List.of(metrics));
+    }
+
+    /**
+     * Returns the value of the metric with the given name.
+     *
+     * @param name name of the metric, without tags
+     * @return the value, or null if the snapshot has no such metric
+     */
+    public Long value(String name) {
+        for (NativeMetric metric : metrics) {
+            if (metric.name().equals(name)) {
+                return metric.value();
+            }
+        }
+        return null;
+    }
+
+    private static Map<String, String> toMap(String[] tagPairs) {
+        Map<String, String> tags = new HashMap<>();
+        for (int i = 0; i + 1 < tagPairs.length; i += 2) {
+            tags.put(tagPairs[i], tagPairs[i + 1]);
+        }
+        return tags;
+    }
+}
@@ -0,0 +1,93 @@
+package dev.opendata;
+
+/**
+ * Features a native library may support, see {@link LogDb#nativeCapabilities()}.
+ *
+ * <p>The declaration order is part of the native contract: the native layer
+ * reports capabilities as a bit mask of {@link #ordinal()}s. New capabilities
+ * are only ever appended.
+ */
+public enum NativeCapability {
+    /** Values carry the 8-byte big-endian timestamp header. */
+    TIMESTAMP_ENVELOPE,
+    /** {@link LogDb#submitAppend(Record[], AckMode)} and submitted scans with polled completions. */
+    SUBMITTED_OPERATIONS,
+    /** Appends, scans and flushes completed on native bridge threads. */
+    BRIDGED_CALLS,
+    /** {@link LogDb#openPipeline(int)}. */
+    APPEND_PIPELINE,
+    /** {@link LogDb#openAppendRing(int)}. */
+    APPEND_RING,
+    /** {@link LogDb#appendIf(byte[], Record[], long)}. */
+    CONDITIONAL_APPEND,
+    /** {@link LogDb#appendIdempotent(Record[], long, long)}. */
+    IDEMPOTENT_APPEND,
+    /** Tailing reads through {@link LogDbReader#waitForSequence(byte[], long, long)}. */
+    WAIT_FOR_SEQUENCE,
+    /** {@link LogDbConfig#withTailCacheBytes(long)}. */
+    TAIL_CACHE,
+    /** {@link dev.opendata.common.DiskCacheConfig} on SlateDB storage. */
+    DISK_CACHE,
+    /** {@link LogDb#open(java.nio.file.Path)}. */
+    CONFIG_FILE,
+    /** {@code OPENDATA_*} environment-variable overrides, see {@link LogDbConfig}. */
+    ENVIRONMENT_OVERRIDES,
+    /** {@link LogDbConfig#withSlowOperationThreshold(long)}. */
+    SLOW_OPERATION_LOG,
+    /** Values carrying their receive time, see {@link LogDbConfig#withReceiveTimestamps(boolean)}. */
+    RECEIVE_TIMESTAMPS,
+    /**
+     * {@link LogDb#watchAppends(byte[], long, TailListener)} and
+     * {@link LogDbReader#watchAppends(byte[], long, TailListener)}.
+     */
+    APPEND_WATCH,
+    /** {@link LogDbReader#scanPage(byte[], byte[], int)}. */
+    SCAN_RESUME_TOKENS,
+    /** {@link LogRead#scanMetadata(byte[], long, int)}. */
+    METADATA_SCAN,
+    /** {@link LogRead#scan(byte[], long, int, ScanFilter)}. */
+    SCAN_FILTERS,
+    /** {@link LogDb#scanSample(byte[], long, long, double, long)}. */
+    SCAN_SAMPLE,
+    /** {@link LogDb#appendGrouped(Record[], AckMode)}. */
+    GROUPED_APPEND,
+    /** {@link LogDb#sync()}. */
+    DURABILITY_BARRIER,
+    /** {@link LogDb#updateConfig(ConfigUpdate)}. */
+    CONFIG_UPDATES,
+    /** {@link OperationPriority}. */
+    PRIORITY_CLASSES,
+    /** {@link LogDbReader#scan(byte[], long, int, ReadConsistency)}. */
+    READ_CONSISTENCY,
+    /** {@link LogDbReader#beginSnapshot()}. */
+    READER_SNAPSHOTS,
+    /** {@link LogDb#appendRaw(Record[], AckMode)} and {@link LogDb#scanRaw(byte[], long, int)}. */
+    RAW_ENTRIES,
+    /** {@link LogDb#scanAsync(byte[], long, long, int, ScanListener)}. */
+    ASYNC_SCANS,
+    /** {@link LogDb#intervalStats()} and {@link LogDbReader#intervalStats()}. */
+    INTERVAL_STATS,
+    /** {@link LogDb#hotKeys(int)}. */
+    KEY_STATS,
+    /** {@link LogDb#append(Record[], AckMode, OperationPriority, String)}. */
+    TRACE_CONTEXT,
+    /** {@link LogDbConfig#withStatsd(StatsdConfig)}. */
+    STATSD_EXPORT,
+    /** {@link LogDb#metricsSnapshot()} and {@link LogDbReader#metricsSnapshot()}. */
+    METRICS_SNAPSHOT,
+    /** {@link LogDb#pendingOps()}. */
+    PENDING_OPS,
+    /** {@link LogDbConfig#withCallLimit(CallLimitConfig)}. */
+    CALL_LIMIT,
+    /** {@link LogDbReaderConfig#withRuntimeMode(RuntimeMode)}. */
+    RUNTIME_MODE,
+    /** {@link LogDb#checkpoint(String, long)} and {@link LogDb#listCheckpoints()}. */
+    CHECKPOINTS,
+    /**
+     * {@link LogDb#backup(String, dev.opendata.common.StorageConfig.SlateDb, BackupListener)} and
+     * {@link LogDb#restore(LogDbConfig, dev.opendata.common.StorageConfig.SlateDb)}.
+     */
+    BACKUPS,
+    /** {@link LogDb#cloneTo(String)}. */
+    CLONES
+}
@@ -0,0 +1,115 @@
+package dev.opendata;
+
+import jdk.jfr.Category;
+import jdk.jfr.DataAmount;
+import jdk.jfr.Description;
+import jdk.jfr.Event;
+import jdk.jfr.EventType;
+import jdk.jfr.FlightRecorder;
+import jdk.jfr.FlightRecorderListener;
+import jdk.jfr.Label;
+import jdk.jfr.Name;
+import jdk.jfr.Recording;
+import jdk.jfr.Timespan;
+
+/**
+ * Commits Java Flight Recorder events for native appends, scans and flushes.
+ *
+ * <p>Called from native code only. While a recording enables the
+ * {@code dev.opendata.NativeOperation} event, the native layer reports each
+ * operation once it ends, on the thread that called it or, for asynchronous
+ * calls, on the native runtime thread that completed it. The event is
+ * committed when the operation ends, so its duration says nothing; the time
+ * spent in storage is its {@code nativeDuration}. Compactions run inside
+ * SlateDB and are not reported.
+ */
+final class NativeEvents {
+
+    private static final String[] OPERATIONS = {"append", "scan", "flush"};
+
+    private NativeEvents() {
+    }
+
+    @Name("dev.opendata.NativeOperation")
+    @Label("Native Operation")
+    @Category({"OpenData", "Native"})
+    @Description("An append, scan or flush of the native log")
+    static final class OperationEvent extends Event {
+        @Label("Operation")
+        String operation;
+
+        @Label("Native Duration")
+        @Timespan(Timespan.NANOSECONDS)
+        long nativeDuration;
+
+        @Label("Entries")
+        long entries;
+
+        @Label("Bytes")
+        @DataAmount
+        long bytes;
+
+        @Label("Succeeded")
+        boolean succeeded;
+
+        @Label("Tags")
+        @Description("Tags of the handle, see LogDbConfig.withTags")
+        String tags;
+
+        @Label("Traceparent")
+        @Description("W3C traceparent the call passed, empty without one")
+        String traceparent;
+    }
+
+    /**
+     * Reports to the native layer whether a recording enables the event,
+     * now and whenever a recording changes state.
+     */
+    static void register() {
+        if (!FlightRecorder.isAvailable()) {
+            return;
+        }
+        FlightRecorder.addListener(new FlightRecorderListener() {
+            @Override
+            public void recordingStateChanged(Recording recording) {
+                update();
+            }
+        });
+        update();
+    }
+
+    /**
+     * Commits an event for a native operation.
+     *
+     * @param operation      0 for an append, 1 for a scan, 2 for a flush
+     * @param durationNanos  time spent in storage, in nanoseconds
+     * @param entries        records appended or entries scanned
+     * @param bytes          value bytes appended or scanned, headers included
+     * @param succeeded      whether the operation succeeded
+     * @param tags           rendered tags of the handle, empty without tags
+     * @param traceparent    traceparent of the call, empty without one
+     */
+    static void commit(int operation, long durationNanos, long entries, long bytes, boolean succeeded,
+                       String tags, String traceparent) {
+        OperationEvent event = new OperationEvent();
+        if (!event.shouldCommit()) {
+            return;
+        }
+        event.operation = operation >= 0 && operation < OPERATIONS.length
+                ? OPERATIONS[operation]
+                : "unknown";
+        event.nativeDuration = durationNanos;
+        event.entries = entries;
+        event.bytes = bytes;
+        event.succeeded = succeeded;
+        event.tags = tags;
+        event.traceparent = traceparent;
+        event.commit();
+    }
+
+    private static void update() {
+        nativeSetEnabled(EventType.getEventType(OperationEvent.class).isEnabled());
+    }
+
+    private static native void nativeSetEnabled(boolean enabled);
+}
@@ -0,0 +1,28 @@
+package dev.opendata;
+
+import java.util.List;
+
+/**
+ * Origins of native handles, listed by {@link LogDb#dumpHandles()}.
+ */
+final class NativeHandles {
+
+    private static final StackWalker WALKER = StackWalker.getInstance();
+
+    private NativeHandles() {
+    }
+
+    /**
+     * Hashes the calling thread's stack, skipping frames of this package so
+     * that the hash identifies the application call site.
+     *
+     * @return the hash of the application frames of the stack
+     */
+    static int callerStackHash() {
+        List<String> frames = WALKER.walk(stack -> stack
+                .filter(frame -> !frame.getClassName().startsWith("dev.opendata."))
+                .map(frame -> frame.getClassName() + "." + frame.getMethodName() + ":" + frame.getLineNumber())
+                .toList());
+        return frames.hashCode();
+    }
+}
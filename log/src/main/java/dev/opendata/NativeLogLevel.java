@@ -0,0 +1,20 @@
+package dev.opendata;
+
+/**
+ * Most verbose level of the lines the native library logs, set with
+ * {@link ConfigUpdate#withLogLevel(NativeLogLevel)}.
+ *
+ * <p>Lines above the level are dropped natively, before the call into SLF4J
+ * that would otherwise filter them. The level applies to every open handle.
+ *
+ * <p>The declaration order is part of the native contract: the native layer
+ * identifies levels by {@link #ordinal()}.
+ */
+public enum NativeLogLevel {
+    /** No native lines are logged. */
+    OFF,
+    /** Only warnings, such as failed drains, are logged. */
+    WARN,
+    /** Warnings and informational lines, such as slow operations; the default. */
+    INFO
+}
@@ -0,0 +1,34 @@
+package dev.opendata;
+
+import org.slf4j.Logger;
+import org.slf4j.LoggerFactory;
+
+/**
+ * Forwards log lines from the native library to SLF4J.
+ *
+ * <p>Called from native code only. Each line names the SLF4J logger it is
+ * logged under, so native log output is filtered and routed like the
+ * application's own.
+ */
+final class NativeLogging {
+
+    private NativeLogging() {
+    }
+
+    /**
+     * Logs a native line.
+     *
+     * @param level   0 for error, 1 for warn, 2 for info, anything else for debug
+     * @param name    name of the logger
+     * @param message the message to log
+     */
+    static void log(int level, String name, String message) {
+        Logger logger = LoggerFactory.getLogger(name);
+        switch (level) {
+            case 0 -> logger.error(message);
+            case 1 -> logger.warn(message);
+            case 2 -> logger.info(message);
+            default -> logger.debug(message);
+        }
+    }
+}
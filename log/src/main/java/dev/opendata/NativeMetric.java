@@ -0,0 +1,32 @@
+package dev.opendata;
+
+/**
+ * A native metric in a {@link MetricsSnapshot}.
+ *
+ * @param name  name of the metric, as in {@link LogDb#stats()} but without
+ *              tags, such as {@code runtime.busy_nanos}
+ * @param type  whether the metric is a counter or a gauge
+ * @param value value of the metric when the snapshot was taken
+ */
+public record NativeMetric(String name, MetricType type, long value) {
+
+    public NativeMetric {
+        if (name == null) {
+            throw new IllegalArgumentException("name must not be null");
+        }
+        if (type == null) {
+            throw new IllegalArgumentException("type must not be null");
+        }
+    }
+
+    /**
+     * Creates a metric from its native representation.
+     *
+     * @param name    name of the metric
+     * @param ordinal ordinal of the metric's {@link MetricType}
+     * @param value   value of the metric
+     */
+    NativeMetric(String name, int ordinal, long value) {
+        this(name, MetricType.values()[ordinal], value);
+    }
+}
@@ -0,0 +1,28 @@
+package dev.opendata;
+
+/**
+ * Tells the native library when the JVM shuts down.
+ *
+ * <p>Called from native code only, when the first handle opens. Once
+ * shutdown hooks run, the hook registered here first drains every open log,
+ * as {@link LogDb#drainAll(long)} does, so that {@code System.exit} does not
+ * abort an object-store upload half-way. Native threads delivering callbacks
+ * attach to the JVM as daemons and detach when they stop; after the drain,
+ * the hook stops the native layer from attaching or detaching threads,
+ * which could crash a JVM being destroyed. Callbacks due after that point
+ * are dropped.
+ */
+final class NativeThreads {
+
+    private NativeThreads() {
+    }
+
+    /**
+     * Registers the shutdown hook.
+     */
+    static void register() {
+        Runtime.getRuntime().addShutdownHook(new Thread(NativeThreads::nativeShutdown, "opendata-shutdown"));
+    }
+
+    private static native void nativeShutdown();
+}
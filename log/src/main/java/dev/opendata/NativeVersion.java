@@ -0,0 +1,58 @@
+package dev.opendata;
+
+/**
+ * Build information of the loaded native library.
+ *
+ * <p>Returned by {@link LogDb#nativeVersion()}, so a process can log what it
+ * runs against and detect a shared library built from a different version
+ * than the JAR at startup.
+ *
+ * @param binding      version of the JNI binding crate
+ * @param logDb        version of the upstream LogDb crate, followed by its
+ *                     commit for git dependencies, as in {@code 0.1.0+74d36908ffa7}
+ * @param slateDb      version of SlateDB, in the same form
+ * @param gitSha       commit the library was built from, or {@code unknown}
+ * @param buildProfile Cargo profile of the build, such as {@code release}
+ * @param allocator    global allocator of the library: {@code system}, or
+ *                     {@code jemalloc} or {@code mimalloc} when built with
+ *                     that Cargo feature
+ */
+public record NativeVersion(
+        String binding,
+        String logDb,
+        String slateDb,
+        String gitSha,
+        String buildProfile,
+        String allocator
+) {
+
+    public NativeVersion {
+        if (binding == null || logDb == null || slateDb == null || gitSha == null
+                || buildProfile == null || allocator == null) {
+            throw new IllegalArgumentException("version fields must not be null");
+        }
+    }
+
+    /**
+     * Returns whether the library's binding version matches the version of
+     * the JAR it was loaded by.
+     *
+     * <p>Qualifiers such as {@code -SNAPSHOT} are ignored. When the JAR
+     * version is unknown, for example when running from compiled classes,
+     * the versions are taken to match.
+     *
+     * @return false if the library was built from a different version than the JAR
+     */
+    public boolean matchesJar() {
+        String jarVersion = LogDb.class.getPackage().getImplementationVersion();
+        if (jarVersion == null) {
+            return true;
+        }
+        return baseVersion(jarVersion).equals(baseVersion(binding));
+    }
+
+    private static String baseVersion(String version) {
+        int qualifier = version.indexOf('-');
+        return qualifier < 0 ? version : version.substring(0, qualifier);
+    }
+}
@@ -0,0 +1,20 @@
+package dev.opendata;
+
+/**
+ * Phases reported while opening a {@link LogDb}, see
+ * {@link LogDb#open(LogDbConfig, java.util.function.Consumer)}.
+ *
+ * <p>The declaration order is part of the native contract: the native layer
+ * identifies phases by {@link #ordinal()}.
+ */
+public enum OpenPhase {
+    /** The native runtimes have started and the storage is being opened. */
+    RUNTIMES_STARTED,
+    /**
+     * The storage has loaded its manifest and replayed its WAL. SlateDB does
+     * both in one step, so they are reported together.
+     */
+    STORAGE_OPENED,
+    /** The instance is ready for appends and scans. */
+    READY
+}
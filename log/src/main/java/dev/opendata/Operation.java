@@ -0,0 +1,14 @@
+package dev.opendata;
+
+/**
+ * Operations whose latency is recorded by the native layer.
+ *
+ * <p>The declaration order is part of the native contract: the native layer
+ * identifies operations by {@link #ordinal()}.
+ */
+public enum Operation {
+    /** Appending a batch of records. */
+    APPEND,
+    /** Scanning entries for a key. */
+    SCAN
+}
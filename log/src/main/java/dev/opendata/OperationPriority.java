@@ -0,0 +1,21 @@
+package dev.opendata;
+
+/**
+ * Priority class of an append or scan.
+ *
+ * <p>The declaration order is part of the native contract: the native layer
+ * identifies priorities by {@link #ordinal()}.
+ */
+public enum OperationPriority {
+    /**
+     * Part of the workload. Foreground calls never wait on background ones.
+     */
+    FOREGROUND,
+    /**
+     * Administrative, such as a verification scan or a backfill. At most
+     * {@link LogDbConfig#backgroundConcurrency()} background calls access
+     * storage at once; the others queue natively, so they add little to the
+     * tail latency of foreground calls.
+     */
+    BACKGROUND
+}
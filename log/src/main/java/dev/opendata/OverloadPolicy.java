@@ -0,0 +1,26 @@
+package dev.opendata;
+
+/**
+ * What a blocking {@link LogDb} call does when the handle's call limit is
+ * reached, see {@link CallLimitConfig}.
+ *
+ * <p>The declaration order is part of the native contract: the native layer
+ * identifies policies by {@link #ordinal()}.
+ */
+public enum OverloadPolicy {
+    /**
+     * Wait for a running call to return, in arrival order, while fewer than
+     * {@link CallLimitConfig#maxQueued()} callers wait; beyond them, throw
+     * {@link dev.opendata.common.OpenDataOverloadedException}.
+     */
+    QUEUE,
+    /**
+     * Wait for a running call to return, in arrival order, however many
+     * callers wait.
+     */
+    BLOCK,
+    /**
+     * Throw {@link dev.opendata.common.OpenDataOverloadedException} at once.
+     */
+    REJECT
+}
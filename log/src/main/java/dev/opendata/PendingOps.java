@@ -0,0 +1,54 @@
+package dev.opendata;
+
+/**
+ * Native operations of a {@link LogDb} that are queued or in flight, as
+ * returned by {@link LogDb#pendingOps()}.
+ *
+ * <p>Counts are read without synchronizing with each other, so an operation
+ * finishing during the call may be counted in two categories or none.
+ *
+ * @param blockingCalls       Java threads waiting in a blocking native call,
+ *                            including submitters blocked by a full write
+ *                            queue
+ * @param bridgedCalls        calls queued to or running on the bridge
+ *                            threads, see {@link LogDbConfig#withBridgeThreads(int)}
+ * @param submittedOps        operations submitted through the submission
+ *                            queue and not yet completed
+ * @param unpolledCompletions completed submitted operations not yet polled
+ * @param inFlightAppendBytes key and value bytes of appends in flight,
+ *                            however they were made
+ * @param unpolledScanBytes   key and value bytes of scan results not yet
+ *                            polled
+ */
+public record PendingOps(
+        long blockingCalls,
+        long bridgedCalls,
+        long submittedOps,
+        long unpolledCompletions,
+        long inFlightAppendBytes,
+        long unpolledScanBytes
+) {
+
+    /**
+     * Returns the operations waiting for storage: blocking calls, bridged
+     * calls and submitted operations.
+     *
+     * @return the operations in flight
+     */
+    public long inFlight() {
+        return blockingCalls + bridgedCalls + submittedOps;
+    }
+
+    /**
+     * Returns the native memory held for pending work, outside the JVM heap.
+     *
+     * @return bytes of appends in flight plus scan results not yet polled
+     */
+    public long heldBytes() {
+        return inFlightAppendBytes + unpolledScanBytes;
+    }
+
+    static PendingOps fromNative(long[] values) {
+        return new PendingOps(values[0], values[1], values[2], values[3], values[4], values[5]);
+    }
+}
@@ -0,0 +1,79 @@
+package dev.opendata;
+
+/**
+ * Native rate limits on appends and scans through one {@link LogDb}.
+ *
+ * <p>Each positive limit is enforced by a token bucket holding one second's
+ * worth of tokens. A call waits natively, on a timer, until earlier calls
+ * have been paid for, so a single batch larger than the bucket still goes
+ * through and the sustained rate converges on the limit. Scans are charged
+ * for the entries they return. Limits apply to plain and partitioned appends
+ * and to scans, whether blocking, bridged or submitted. A limit of 0 leaves
+ * that dimension unlimited.
+ *
+ * @param produceRecordsPerSec records appended per second, or 0 for no limit
+ * @param produceBytesPerSec   key and value bytes appended per second, or 0
+ *                             for no limit
+ * @param consumeRecordsPerSec entries scanned per second, or 0 for no limit
+ * @param consumeBytesPerSec   key and value bytes scanned per second, or 0
+ *                             for no limit
+ */
+public record RateLimitConfig(
+        long produceRecordsPerSec,
+        long produceBytesPerSec,
+        long consumeRecordsPerSec,
+        long consumeBytesPerSec
+) {
+
+    /**
+     * Configuration without rate limits.
+     */
+    public static final RateLimitConfig UNLIMITED = new RateLimitConfig(0, 0, 0, 0);
+
+    public RateLimitConfig {
+        if (produceRecordsPerSec < 0) {
+            throw new IllegalArgumentException("produceRecordsPerSec must not be negative");
+        }
+        if (produceBytesPerSec < 0) {
+            throw new IllegalArgumentException("produceBytesPerSec must not be negative");
+        }
+        if (consumeRecordsPerSec < 0) {
+            throw new IllegalArgumentException("consumeRecordsPerSec must not be negative");
+        }
+        if (consumeBytesPerSec < 0) {
+            throw new IllegalArgumentException("consumeBytesPerSec must not be negative");
+        }
+    }
+
+    /**
+     * Returns a copy of this config with the given produce limits.
+     *
+     * @param recordsPerSec records appended per second, or 0 for no limit
+     * @param bytesPerSec   bytes appended per second, or 0 for no limit
+     * @return a new RateLimitConfig
+     */
+    public RateLimitConfig withProduce(long recordsPerSec, long bytesPerSec) {
+        return new RateLimitConfig(recordsPerSec, bytesPerSec, consumeRecordsPerSec, consumeBytesPerSec);
+    }
+
+    /**
+     * Returns a copy of this config with the given consume limits.
+     *
+     * @param recordsPerSec entries scanned per second, or 0 for no limit
+     * @param bytesPerSec   bytes scanned per second, or 0 for no limit
+     * @return a new RateLimitConfig
+     */
+    public RateLimitConfig withConsume(long recordsPerSec, long bytesPerSec) {
+        return new RateLimitConfig(produceRecordsPerSec, produceBytesPerSec, recordsPerSec, bytesPerSec);
+    }
+
+    /**
+     * Returns whether any limit is set.
+     *
+     * @return true if any limit is positive
+     */
+    public boolean enabled() {
+        return produceRecordsPerSec > 0 || produceBytesPerSec > 0
+                || consumeRecordsPerSec > 0 || consumeBytesPerSec > 0;
+    }
+}
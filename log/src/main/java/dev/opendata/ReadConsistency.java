@@ -0,0 +1,21 @@
+package dev.opendata;
+
+/**
+ * Which writes a {@link LogDbReader} scan observes.
+ *
+ * <p>The declaration order is part of the native contract: the native layer
+ * identifies levels by {@link #ordinal()}.
+ */
+public enum ReadConsistency {
+    /**
+     * Whatever the reader's view includes when the scan starts. New data
+     * becomes visible at the reader's refresh interval.
+     */
+    LATEST_COMMITTED,
+    /**
+     * At least every entry of the scanned key appended so far through the
+     * writer paired with {@link LogDbReader#pairWriter(LogDb)}. The scan
+     * first waits natively until the reader's view reaches them.
+     */
+    READ_YOUR_WRITES
+}
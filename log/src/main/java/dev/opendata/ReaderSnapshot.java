@@ -0,0 +1,58 @@
+package dev.opendata;
+
+import java.io.Closeable;
+import java.util.List;
+
+/**
+ * A consistent view of the log pinned by a {@link LogDbReader}.
+ *
+ * <p>Every scan through a snapshot observes the log as of the moment
+ * {@link LogDbReader#beginSnapshot()} returned, whichever key it reads, so
+ * scans of several streams after a run can be compared against the same cut.
+ * Entries appended later, and refreshes of the reader, are not seen.
+ *
+ * <p>A snapshot holds a second native view of the storage, with memory of its
+ * own, until it is closed. Snapshot scans always read storage, bypassing the
+ * reader's read-ahead and any attached tail cache. Open snapshots are
+ * released by {@link LogDbReader#close()}.
+ */
+public final class ReaderSnapshot implements Closeable {
+
+    private final LogDbReader reader;
+    private final long id;
+    private volatile boolean closed = false;
+
+    ReaderSnapshot(LogDbReader reader, long id) {
+        this.reader = reader;
+        this.id = id;
+    }
+
+    /**
+     * Scans entries for a key as of the snapshot.
+     *
+     * @param key           the key to scan
+     * @param startSequence the sequence number to start scanning from
+     * @param maxEntries    maximum number of entries to return
+     * @return list of log entries (may be empty)
+     */
+    public List<LogEntry> scan(byte[] key, long startSequence, int maxEntries) {
+        if (key == null) {
+            throw new IllegalArgumentException("key must not be null");
+        }
+        if (closed) {
+            throw new IllegalStateException("ReaderSnapshot is closed");
+        }
+        return reader.scanSnapshot(id, key, startSequence, maxEntries);
+    }
+
+    /**
+     * Releases the snapshot's view.
+     */
+    @Override
+    public void close() {
+        if (!closed) {
+            closed = true;
+            reader.endSnapshot(id);
+        }
+    }
+}
@@ -0,0 +1,29 @@
+package dev.opendata;
+
+import java.util.List;
+
+/**
+ * Callback notified when a {@link GroupMember}'s assigned keys change.
+ *
+ * <p>Callbacks run on the thread calling {@link GroupMember#poll()} or
+ * {@link GroupMember#close()}, mirroring Kafka's
+ * {@code ConsumerRebalanceListener}.
+ */
+public interface RebalanceListener {
+
+    /**
+     * Called before keys are taken away from this member, for example to
+     * commit offsets for them.
+     *
+     * @param keys the keys no longer assigned to this member
+     */
+    void onKeysRevoked(List<byte[]> keys);
+
+    /**
+     * Called after keys are assigned to this member, for example to seek to
+     * their committed offsets.
+     *
+     * @param keys the keys newly assigned to this member
+     */
+    void onKeysAssigned(List<byte[]> keys);
+}
@@ -0,0 +1,74 @@
+package dev.opendata;
+
+/**
+ * Configuration for reopening a {@link LogDb} after repeated storage failures.
+ *
+ * <p>When enabled, the native layer counts appends, scans and flushes that
+ * fail in a row. Once {@code failureThreshold} of them have failed, the
+ * storage is presumed poisoned, for example after an object-store error
+ * fenced its writer, and is opened again in the background. Calls made
+ * until the new instance is in place still fail; later calls, including
+ * those of pipelines and readers committing offsets, resume on it
+ * transparently. A failed reopen is retried every {@code backoffMs}. Entries
+ * not yet flushed by the poisoned instance are lost. Each reopen is logged
+ * to the SLF4J logger {@code dev.opendata.Reopen} and counted as
+ * {@code reopen.count} in {@link LogDb#stats()}.
+ *
+ * @param failureThreshold consecutive failed storage calls that trigger a
+ *                         reopen, or 0 to disable reopening
+ * @param backoffMs        delay in milliseconds before retrying a failed reopen
+ */
+public record ReopenConfig(int failureThreshold, long backoffMs) {
+
+    /**
+     * Default delay before retrying a failed reopen, 1 second.
+     */
+    public static final long DEFAULT_BACKOFF_MS = 1_000;
+
+    /**
+     * Configuration with reopening disabled.
+     */
+    public static final ReopenConfig DISABLED = new ReopenConfig(0, DEFAULT_BACKOFF_MS);
+
+    public ReopenConfig {
+        if (failureThreshold < 0) {
+            throw new IllegalArgumentException("failureThreshold must not be negative");
+        }
+        if (backoffMs <= 0) {
+            throw new IllegalArgumentException("backoffMs must be positive");
+        }
+    }
+
+    /**
+     * Creates a config reopening after the given number of consecutive
+     * failures, with the default backoff.
+     *
+     * @param failureThreshold consecutive failed storage calls that trigger a reopen
+     * @return a new ReopenConfig
+     */
+    public static ReopenConfig after(int failureThreshold) {
+        if (failureThreshold <= 0) {
+            throw new IllegalArgumentException("failureThreshold must be positive");
+        }
+        return new ReopenConfig(failureThreshold, DEFAULT_BACKOFF_MS);
+    }
+
+    /**
+     * Returns a copy of this config with the given backoff.
+     *
+     * @param backoffMs delay in milliseconds before retrying a failed reopen
+     * @return a new ReopenConfig
+     */
+    public ReopenConfig withBackoff(long backoffMs) {
+        return new ReopenConfig(failureThreshold, backoffMs);
+    }
+
+    /**
+     * Returns whether reopening is enabled.
+     *
+     * @return true if {@code failureThreshold} is positive
+     */
+    public boolean enabled() {
+        return failureThreshold > 0;
+    }
+}
@@ -0,0 +1,113 @@
+package dev.opendata;
+
+import java.util.Set;
+import java.util.TreeSet;
+
+/**
+ * Thread names and CPU affinity of one of a {@link LogDb}'s native runtimes.
+ *
+ * <p>Each LogDb runs its calls on one runtime and SlateDB compaction on
+ * another, see {@link LogDbConfig#runtime()} and
+ * {@link LogDbConfig#compactionRuntime()}. Their threads are named
+ * {@code <threadNamePrefix>-<n>}, so profiles can attribute native CPU to
+ * either. With {@code cpus} set, the runtime starts one worker thread per
+ * core and pins all of its threads to those cores, which lets a benchmark
+ * keep native work off the cores left to JVM GC threads. Pinning is only
+ * supported on Linux, and opening fails if a core is outside the process's
+ * own affinity mask.
+ *
+ * <p>In-memory storage has no SlateDB compaction to keep apart from calls,
+ * so its compaction runtime can be {@link #SHARED} with the runtime running
+ * calls rather than start a second set of threads.
+ *
+ * @param threadNamePrefix prefix of the runtime's thread names
+ * @param cpus             cores the runtime's threads are pinned to; empty
+ *                         leaves them unpinned
+ * @param workerThreads    worker threads of the runtime; 0 starts one per
+ *                         pinned core, or per CPU without pinning
+ * @param shared           whether the compaction runtime is the runtime
+ *                         running calls; only valid for
+ *                         {@link LogDbConfig#compactionRuntime()} with
+ *                         in-memory storage
+ */
+public record RuntimeConfig(String threadNamePrefix, Set<Integer> cpus, int workerThreads, boolean shared) {
+
+    /**
+     * Default settings of the runtime running calls.
+     */
+    public static final RuntimeConfig OPERATION = new RuntimeConfig("opendata-log", Set.of(), 0, false);
+
+    /**
+     * Default settings of the runtime running SlateDB compaction.
+     */
+    public static final RuntimeConfig COMPACTION =
+            new RuntimeConfig("opendata-compaction", Set.of(), 0, false);
+
+    /**
+     * Compaction runs on the runtime running calls, for in-memory storage.
+     */
+    public static final RuntimeConfig SHARED = new RuntimeConfig("opendata-compaction", Set.of(), 0, true);
+
+    public RuntimeConfig {
+        if (threadNamePrefix == null || threadNamePrefix.isEmpty()) {
+            throw new IllegalArgumentException("threadNamePrefix must not be empty");
+        }
+        if (cpus == null) {
+            throw new IllegalArgumentException("cpus must not be null");
+        }
+        for (Integer cpu : cpus) {
+            if (cpu == null || cpu < 0) {
+                throw new IllegalArgumentException("cpus must not be negative");
+            }
+        }
+        cpus = Set.copyOf(cpus);
+        if (workerThreads < 0) {
+            throw new IllegalArgumentException("workerThreads must not be negative");
+        }
+    }
+
+    /**
+     * Returns a copy of this config with the given thread name prefix.
+     *
+     * @param threadNamePrefix prefix of the runtime's thread names
+     * @return a new RuntimeConfig
+     */
+    public RuntimeConfig withThreadNamePrefix(String threadNamePrefix) {
+        return new RuntimeConfig(threadNamePrefix, cpus, workerThreads, shared);
+    }
+
+    /**
+     * Returns a copy of this config with the given worker thread count.
+     *
+     * @param workerThreads worker threads of the runtime; 0 for the default
+     * @return a new RuntimeConfig
+     */
+    public RuntimeConfig withWorkerThreads(int workerThreads) {
+        return new RuntimeConfig(threadNamePrefix, cpus, workerThreads, shared);
+    }
+
+    /**
+     * Returns a copy of this config pinned to the given cores.
+     *
+     * @param first the first core
+     * @param rest  further cores
+     * @return a new RuntimeConfig
+     */
+    public RuntimeConfig withCpus(int first, int... rest) {
+        Set<Integer> cpus = new TreeSet<>();
+        cpus.add(first);
+        for (int cpu : rest) {
+            cpus.add(cpu);
+        }
+        return new RuntimeConfig(threadNamePrefix, cpus, workerThreads, shared);
+    }
+
+    /**
+     * Returns the pinned cores in ascending order, as read by the native layer.
+     *
+     * @return the cores
+     */
+    int[] cpuArray() {
+        return cpus.stream().mapToInt(Integer::intValue).sorted().toArray();
+    }
+}
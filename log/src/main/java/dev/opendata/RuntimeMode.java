@@ -0,0 +1,20 @@
+package dev.opendata;
+
+/**
+ * Scheduler of a {@link LogDbReader}'s native runtime, see
+ * {@link LogDbReaderConfig#withRuntimeMode(RuntimeMode)}.
+ *
+ * <p>The declaration order is part of the native contract: the native layer
+ * identifies modes by {@link #ordinal()}.
+ */
+public enum RuntimeMode {
+    /**
+     * A worker thread per CPU, sharing background work between them.
+     */
+    MULTI_THREAD,
+    /**
+     * A single native thread running all background work, for processes
+     * opening many lightweight readers.
+     */
+    CURRENT_THREAD
+}
@@ -0,0 +1,29 @@
+package dev.opendata;
+
+import dev.opendata.common.OpenDataNativeException;
+
+import java.util.List;
+
+/**
+ * Adapts a {@link ScanListener} to the arrays the native layer delivers.
+ */
+final class ScanDelivery {
+
+    private final ScanListener listener;
+
+    ScanDelivery(ScanListener listener) {
+        this.listener = listener;
+    }
+
+    boolean onBatch(LogEntry[] entries) {
+        return listener.onBatch(List.of(entries));
+    }
+
+    void onComplete(long nextSequence) {
+        listener.onComplete(nextSequence);
+    }
+
+    void onError(OpenDataNativeException error) {
+        listener.onError(error);
+    }
+}
@@ -0,0 +1,60 @@
+package dev.opendata;
+
+/**
+ * Bounds an entry must fall within to be returned by
+ * {@link LogRead#scan(byte[], long, int, ScanFilter)}.
+ *
+ * <p>The filter is evaluated natively, so entries failing it are never
+ * copied into Java. Both ranges are inclusive; start from {@link #ALL} and
+ * narrow it:
+ *
+ * <pre>{@code
+ * ScanFilter large = ScanFilter.ALL.withValueLength(1 << 20, Long.MAX_VALUE);
+ * }</pre>
+ *
+ * @param minValueLength smallest value length in bytes returned
+ * @param maxValueLength largest value length in bytes returned
+ * @param minTimestamp   earliest timestamp (epoch millis) returned
+ * @param maxTimestamp   latest timestamp (epoch millis) returned
+ */
+public record ScanFilter(long minValueLength, long maxValueLength, long minTimestamp, long maxTimestamp) {
+
+    /**
+     * A filter every entry passes.
+     */
+    public static final ScanFilter ALL = new ScanFilter(0, Long.MAX_VALUE, Long.MIN_VALUE, Long.MAX_VALUE);
+
+    public ScanFilter {
+        if (minValueLength < 0) {
+            throw new IllegalArgumentException("minValueLength must not be negative");
+        }
+        if (maxValueLength < minValueLength) {
+            throw new IllegalArgumentException("maxValueLength must be >= minValueLength");
+        }
+        if (maxTimestamp < minTimestamp) {
+            throw new IllegalArgumentException("maxTimestamp must be >= minTimestamp");
+        }
+    }
+
+    /**
+     * Returns a copy of this filter with the value length range replaced.
+     *
+     * @param min smallest value length in bytes returned
+     * @param max largest value length in bytes returned
+     * @return the new filter
+     */
+    public ScanFilter withValueLength(long min, long max) {
+        return new ScanFilter(min, max, minTimestamp, maxTimestamp);
+    }
+
+    /**
+     * Returns a copy of this filter with the timestamp range replaced.
+     *
+     * @param min earliest timestamp (epoch millis) returned
+     * @param max latest timestamp (epoch millis) returned
+     * @return the new filter
+     */
+    public ScanFilter withTimestamp(long min, long max) {
+        return new ScanFilter(minValueLength, maxValueLength, min, max);
+    }
+}
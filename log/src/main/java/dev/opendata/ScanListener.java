@@ -0,0 +1,43 @@
+package dev.opendata;
+
+import dev.opendata.common.OpenDataNativeException;
+
+import java.util.List;
+
+/**
+ * Callback receiving the entries of a scan started with
+ * {@link LogDb#scanAsync(byte[], long, long, int, ScanListener)}.
+ *
+ * <p>Callbacks run on a native runtime thread of the log, one at a time for
+ * each scan. The next batch is read only after {@link #onBatch} returned, so
+ * a listener should hand slow work off to another thread rather than hold a
+ * runtime thread of the log. Every scan ends with exactly one call to
+ * {@link #onComplete} or {@link #onError}.
+ */
+public interface ScanListener {
+
+    /**
+     * Called with the next batch of entries, in sequence order.
+     *
+     * <p>Returning false, or throwing, stops the scan, which then completes.
+     *
+     * @param entries the entries of the batch, never empty
+     * @return whether to continue the scan
+     */
+    boolean onBatch(List<LogEntry> entries);
+
+    /**
+     * Called once the scan reached the end of its range or of the key, or
+     * the listener stopped it.
+     *
+     * @param nextSequence the sequence a later scan would continue from
+     */
+    void onComplete(long nextSequence);
+
+    /**
+     * Called if a read failed, or the log was closed before the scan ended.
+     *
+     * @param error the failure
+     */
+    void onError(OpenDataNativeException error);
+}
@@ -0,0 +1,24 @@
+package dev.opendata;
+
+import java.util.List;
+
+/**
+ * A page of entries returned by {@link LogDbReader#scanPage(byte[], long, int)}
+ * or {@link LogDbReader#scanPage(byte[], byte[], int)}.
+ *
+ * <p>The resume token is opaque. Passing it to the next scan of the same key
+ * continues right after this page on the storage iterator that read it,
+ * without seeking again. A token outlives the reader that issued it: another
+ * reader, including one opened after a restart, resumes from the sequence it
+ * records.
+ *
+ * @param entries     the entries of the page (may be empty)
+ * @param resumeToken the token to pass to the next scan of the key
+ */
+public record ScanPage(List<LogEntry> entries, byte[] resumeToken) {
+
+    /**
+     * Length in bytes of a resume token.
+     */
+    static final int TOKEN_LENGTH = 25;
+}
@@ -0,0 +1,47 @@
+package dev.opendata;
+
+import dev.opendata.common.OpenDataNativeException;
+
+/**
+ * Exception thrown when a conditional append fails because the key advanced
+ * past the expected tail sequence.
+ *
+ * <p>Callers typically re-read the key from {@link #actualSequence()} onwards,
+ * re-evaluate their decision, and retry with the new tail.
+ */
+public class SequenceConflictException extends OpenDataNativeException {
+
+    private final long expectedTailSequence;
+    private final long actualSequence;
+
+    /**
+     * Creates a conflict exception.
+     *
+     * @param expectedTailSequence the tail sequence the caller expected, or -1 for an empty key
+     * @param actualSequence       the sequence of the first entry found past the expected tail
+     */
+    public SequenceConflictException(long expectedTailSequence, long actualSequence) {
+        super("Expected tail sequence " + expectedTailSequence
+                + " but key has an entry at sequence " + actualSequence);
+        this.expectedTailSequence = expectedTailSequence;
+        this.actualSequence = actualSequence;
+    }
+
+    /**
+     * Returns the tail sequence the caller expected.
+     *
+     * @return the expected tail sequence, or -1 if the key was expected to be empty
+     */
+    public long expectedTailSequence() {
+        return expectedTailSequence;
+    }
+
+    /**
+     * Returns the sequence of the first entry found past the expected tail.
+     *
+     * @return the conflicting sequence
+     */
+    public long actualSequence() {
+        return actualSequence;
+    }
+}
@@ -0,0 +1,39 @@
+package dev.opendata;
+
+/**
+ * Outcome of {@link LogDb#analyzeSequences(byte[], long)}.
+ *
+ * <p>A gap is a run of sequences between two consecutive entries of the key.
+ * Sequences are assigned across all keys of a log, so gaps only mean lost
+ * entries for a key written alone. A duplicate is an entry whose payload,
+ * without the timestamp header, hashes the same as an earlier entry's. Gaps
+ * and duplicates are listed in the order found, up to a limit; the counts
+ * cover all of them.
+ *
+ * @param entries            entries read
+ * @param firstSequence      sequence of the first entry read, or -1 if none
+ * @param lastSequence       sequence of the last entry read, or -1 if none
+ * @param gaps               gaps between consecutive entries
+ * @param missing            sequences inside gaps
+ * @param gapStarts          first missing sequence of each listed gap
+ * @param gapEnds            last missing sequence of each listed gap
+ * @param duplicates         entries repeating an earlier payload
+ * @param duplicateSequences sequence of each listed duplicate
+ * @param originalSequences  sequence of the entry each listed duplicate repeats
+ * @param error              why reading stopped before the end of the key, or
+ *                           null if every entry was read
+ */
+public record SequenceReport(
+        long entries,
+        long firstSequence,
+        long lastSequence,
+        long gaps,
+        long missing,
+        long[] gapStarts,
+        long[] gapEnds,
+        long duplicates,
+        long[] duplicateSequences,
+        long[] originalSequences,
+        String error
+) {
+}
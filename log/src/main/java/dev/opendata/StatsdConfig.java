@@ -0,0 +1,115 @@
+package dev.opendata;
+
+/**
+ * Configuration for sending a {@link LogDb}'s native stats to a StatsD agent.
+ *
+ * <p>When enabled, a native thread sends the handle's stats, as returned by
+ * {@link LogDb#stats()}, every {@code intervalMs} as UDP datagrams, together
+ * with the count, median, 99th percentile and maximum of its append and scan
+ * latencies in microseconds, such as {@code append.latency.p99_micros}. All
+ * values are sent as gauges, since the native layer already aggregates
+ * them. Names start with {@code prefix}. With {@code dogStatsd}, the
+ * handle's {@link LogDbConfig#tags() tags} are sent as DogStatsD tags;
+ * otherwise their values follow the prefix in each name. The datagrams sent
+ * and the sends that failed are reported as {@code statsd.datagrams} and
+ * {@code statsd.send_errors} in {@link LogDb#stats()}.
+ *
+ * @param host       host name or address of the agent, or null to disable
+ *                   the export
+ * @param port       UDP port of the agent
+ * @param intervalMs interval in milliseconds between two sends
+ * @param prefix     prefix of every name, empty for none
+ * @param dogStatsd  whether tags are sent in the DogStatsD format
+ */
+public record StatsdConfig(String host, int port, long intervalMs, String prefix, boolean dogStatsd) {
+
+    /**
+     * Default UDP port of StatsD agents.
+     */
+    public static final int DEFAULT_PORT = 8125;
+
+    /**
+     * Default interval between two sends, 10 seconds.
+     */
+    public static final long DEFAULT_INTERVAL_MS = 10_000;
+
+    /**
+     * Default prefix of every name.
+     */
+    public static final String DEFAULT_PREFIX = "opendata.log";
+
+    /**
+     * Configuration with the export disabled.
+     */
+    public static final StatsdConfig DISABLED = new StatsdConfig(null, 0, DEFAULT_INTERVAL_MS, DEFAULT_PREFIX, false);
+
+    public StatsdConfig {
+        if (host != null && host.isBlank()) {
+            throw new IllegalArgumentException("host must not be blank");
+        }
+        if (host != null && (port <= 0 || port > 65535)) {
+            throw new IllegalArgumentException("port must be between 1 and 65535");
+        }
+        if (intervalMs <= 0) {
+            throw new IllegalArgumentException("intervalMs must be positive");
+        }
+        if (prefix == null) {
+            throw new IllegalArgumentException("prefix must not be null");
+        }
+    }
+
+    /**
+     * Creates a config sending to a plain StatsD agent with the default
+     * interval and prefix.
+     *
+     * @param host host name or address of the agent
+     * @param port UDP port of the agent
+     * @return a new StatsdConfig
+     */
+    public static StatsdConfig to(String host, int port) {
+        if (host == null) {
+            throw new IllegalArgumentException("host must not be null");
+        }
+        return new StatsdConfig(host, port, DEFAULT_INTERVAL_MS, DEFAULT_PREFIX, false);
+    }
+
+    /**
+     * Returns whether stats are sent.
+     *
+     * @return true if an agent is configured
+     */
+    public boolean enabled() {
+        return host != null;
+    }
+
+    /**
+     * Returns a copy of this config with the given interval.
+     *
+     * @param intervalMs interval in milliseconds between two sends
+     * @return a new StatsdConfig
+     */
+    public StatsdConfig withIntervalMs(long intervalMs) {
+        return new StatsdConfig(host, port, intervalMs, prefix, dogStatsd);
+    }
+
+    /**
+     * Returns a copy of this config with the given name prefix.
+     *
+     * @param prefix prefix of every name, empty for none
+     * @return a new StatsdConfig
+     */
+    public StatsdConfig withPrefix(String prefix) {
+        return new StatsdConfig(host, port, intervalMs, prefix, dogStatsd);
+    }
+
+    /**
+     * Returns a copy of this config sending tags in the DogStatsD format, or
+     * not.
+     *
+     * @param dogStatsd whether tags are sent in the DogStatsD format
+     * @return a new StatsdConfig
+     */
+    public StatsdConfig withDogStatsd(boolean dogStatsd) {
+        return new StatsdConfig(host, port, intervalMs, prefix, dogStatsd);
+    }
+}
@@ -0,0 +1,34 @@
+package dev.opendata;
+
+/**
+ * Configuration for detecting when a benchmark has reached steady state.
+ *
+ * <p>The rate of an operation is sampled natively over consecutive windows.
+ * Steady state is reached once the coefficient of variation (standard
+ * deviation divided by mean) of the operation counts in the most recent
+ * {@code windows} windows is at most {@code maxCoefficientOfVariation}.
+ * Windows without any operations never count as steady.
+ *
+ * @param windowMs                  length of each sampling window in milliseconds
+ * @param windows                   number of most recent windows compared
+ * @param maxCoefficientOfVariation highest variation between windows considered steady
+ */
+public record SteadyStateConfig(long windowMs, int windows, double maxCoefficientOfVariation) {
+
+    /**
+     * Five one-second windows varying by at most 5%.
+     */
+    public static final SteadyStateConfig DEFAULT = new SteadyStateConfig(1_000, 5, 0.05);
+
+    public SteadyStateConfig {
+        if (windowMs <= 0) {
+            throw new IllegalArgumentException("windowMs must be positive");
+        }
+        if (windows < 2) {
+            throw new IllegalArgumentException("windows must be at least 2");
+        }
+        if (!(maxCoefficientOfVariation >= 0)) {
+            throw new IllegalArgumentException("maxCoefficientOfVariation must not be negative");
+        }
+    }
+}
@@ -0,0 +1,26 @@
+package dev.opendata;
+
+/**
+ * Callback notified of new entries of a key watched with
+ * {@link LogDb#watchAppends(byte[], long, TailListener)} or
+ * {@link LogDbReader#watchAppends(byte[], long, TailListener)}.
+ *
+ * <p>Callbacks run on a native notifier thread of the log or reader, one at
+ * a time, and may call back into it. A slow listener delays the listeners of
+ * other watches on the same log or reader, but never an append or a read.
+ * Exceptions thrown by a listener are ignored.
+ */
+@FunctionalInterface
+public interface TailListener {
+
+    /**
+     * Called after entries of the watched key became visible.
+     *
+     * <p>Calls for one watch see increasing tails. A listener that falls
+     * behind is called once with the latest tail rather than for every
+     * append.
+     *
+     * @param tailSequence one past the sequence of the key's last entry
+     */
+    void onTail(long tailSequence);
+}
@@ -0,0 +1,36 @@
+package dev.opendata;
+
+/**
+ * Accumulated per-phase timings of native calls for one {@link Operation}.
+ *
+ * <p>Only populated when profiling is enabled in the log or reader config.
+ * All durations are totals in nanoseconds across {@code calls} invocations.
+ *
+ * @param calls            number of calls recorded
+ * @param marshalInNanos   time spent converting Java arguments to native values
+ * @param queueNanos       time between entering the runtime and the storage
+ *                         future first being polled
+ * @param storageNanos     time spent awaiting the underlying LogDb future
+ * @param marshalOutNanos  time spent converting native results to Java objects
+ */
+public record TimingBreakdown(
+        long calls,
+        long marshalInNanos,
+        long queueNanos,
+        long storageNanos,
+        long marshalOutNanos
+) {
+
+    /**
+     * Returns the total time across all phases.
+     *
+     * @return total nanoseconds
+     */
+    public long totalNanos() {
+        return marshalInNanos + queueNanos + storageNanos + marshalOutNanos;
+    }
+
+    static TimingBreakdown fromNative(long[] values) {
+        return new TimingBreakdown(values[0], values[1], values[2], values[3], values[4]);
+    }
+}
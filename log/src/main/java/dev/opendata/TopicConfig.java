@@ -0,0 +1,61 @@
+package dev.opendata;
+
+/**
+ * Per-topic settings overriding a {@link LogDbManager}'s base {@link LogDbConfig}.
+ *
+ * <p>Null fields inherit from the base config.
+ *
+ * <p>Storage-level options such as compression are SlateDB settings, so a topic
+ * overrides them by pointing at its own SlateDB settings file.
+ *
+ * <p>There is no retention override. The only retention SlateDB offers is its
+ * {@code default_ttl} setting, which expires every key written to the
+ * database. A topic's database also holds the log's sequence allocation and
+ * segment records and the consumer offsets committed to the topic, and
+ * expiring those would reset sequences or lose offsets rather than age out
+ * old entries. For the same reason a topic's settings file should not set
+ * {@code default_ttl}. Time-based deletion needs upstream log support for
+ * dropping whole segments.
+ *
+ * @param settingsPath    path to a SlateDB settings file for this topic, or null
+ *                        to use the base storage's settings
+ * @param flushIntervalMs interval in milliseconds at which buffered appends are
+ *                        flushed to durable storage in the background, or null
+ *                        to flush only on explicit or durable requests
+ */
+public record TopicConfig(String settingsPath, Long flushIntervalMs) {
+
+    /**
+     * Config that inherits every setting from the base config.
+     */
+    public static final TopicConfig DEFAULT = new TopicConfig(null, null);
+
+    public TopicConfig {
+        if (settingsPath != null && settingsPath.isEmpty()) {
+            throw new IllegalArgumentException("settingsPath must not be empty");
+        }
+        if (flushIntervalMs != null && flushIntervalMs <= 0) {
+            throw new IllegalArgumentException("flushIntervalMs must be positive");
+        }
+    }
+
+    /**
+     * Returns a copy of this config with the given SlateDB settings file.
+     *
+     * @param settingsPath path to a SlateDB settings file, or null to inherit
+     * @return a new TopicConfig
+     */
+    public TopicConfig withSettingsPath(String settingsPath) {
+        return new TopicConfig(settingsPath, flushIntervalMs);
+    }
+
+    /**
+     * Returns a copy of this config with the given background flush interval.
+     *
+     * @param flushIntervalMs flush interval in milliseconds, or null to disable
+     * @return a new TopicConfig
+     */
+    public TopicConfig withFlushIntervalMs(Long flushIntervalMs) {
+        return new TopicConfig(settingsPath, flushIntervalMs);
+    }
+}
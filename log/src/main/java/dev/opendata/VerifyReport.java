@@ -0,0 +1,36 @@
+package dev.opendata;
+
+/**
+ * Outcome of {@link LogDb#verify(byte[], long, java.util.function.LongConsumer)}.
+ *
+ * <p>An entry is corrupt if its value is too short for the timestamp
+ * header, its timestamp is negative, it was returned for another key, or its
+ * sequence does not follow the one before it. Corrupt sequences are listed
+ * in the order read, up to a limit; {@code corrupt} counts all of them.
+ *
+ * @param entries          entries read
+ * @param bytes            value bytes read, timestamp headers included
+ * @param corrupt          entries failing a check
+ * @param corruptSequences sequences of the first corrupt entries
+ * @param lastSequence     sequence of the last entry read, or -1 if none
+ * @param error            why reading stopped before the end of the key, or
+ *                         null if every entry was read
+ */
+public record VerifyReport(
+        long entries,
+        long bytes,
+        long corrupt,
+        long[] corruptSequences,
+        long lastSequence,
+        String error
+) {
+
+    /**
+     * Returns whether every entry was read and none was corrupt.
+     *
+     * @return true if the key verified cleanly
+     */
+    public boolean clean() {
+        return corrupt == 0 && error == null;
+    }
+}
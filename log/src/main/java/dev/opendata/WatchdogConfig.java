@@ -0,0 +1,55 @@
+package dev.opendata;
+
+/**
+ * Configuration for the native watchdog that detects stuck calls.
+ *
+ * <p>When enabled, a native thread monitors in-flight appends, scans, and
+ * flushes. Any call running longer than the threshold is logged to standard
+ * error together with a snapshot of the native runtime and all other in-flight
+ * calls on the handle.
+ *
+ * @param thresholdMs     how long in milliseconds a call may run before it is
+ *                        reported, or null to disable the watchdog
+ * @param failStuckCalls  whether calls exceeding the threshold fail with
+ *                        {@link dev.opendata.common.OpenDataTimeoutException}
+ *                        instead of continuing to block
+ */
+public record WatchdogConfig(Long thresholdMs, boolean failStuckCalls) {
+
+    /**
+     * Configuration with the watchdog disabled.
+     */
+    public static final WatchdogConfig DISABLED = new WatchdogConfig(null, false);
+
+    public WatchdogConfig {
+        if (thresholdMs != null && thresholdMs <= 0) {
+            throw new IllegalArgumentException("thresholdMs must be positive");
+        }
+        if (thresholdMs == null && failStuckCalls) {
+            throw new IllegalArgumentException("failStuckCalls requires a thresholdMs");
+        }
+    }
+
+    /**
+     * Creates a watchdog config that only logs calls exceeding the threshold.
+     *
+     * @param thresholdMs threshold in milliseconds
+     * @return a new WatchdogConfig
+     */
+    public static WatchdogConfig logAfter(long thresholdMs) {
+        return new WatchdogConfig(thresholdMs, false);
+    }
+
+    /**
+     * Creates a watchdog config that logs and fails calls exceeding the threshold.
+     *
+     * <p>A failed append may still have been applied; callers should treat the
+     * outcome as unknown.
+     *
+     * @param thresholdMs threshold in milliseconds
+     * @return a new WatchdogConfig
+     */
+    public static WatchdogConfig failAfter(long thresholdMs) {
+        return new WatchdogConfig(thresholdMs, true);
+    }
+}
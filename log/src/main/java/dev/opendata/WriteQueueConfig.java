@@ -0,0 +1,66 @@
+package dev.opendata;
+
+/**
+ * Bound on appends queued natively by {@link LogDb#submitAppend(Record[], AckMode)}.
+ *
+ * <p>Each submitted append holds its records and its key and value bytes in
+ * the queue until it completes. A submission that would take the queue past
+ * {@code maxRecords} records or {@code maxBytes} bytes is handled according to
+ * {@code policy}. A batch larger than the whole queue is admitted once the
+ * queue is empty. A limit of 0 leaves that dimension unbounded.
+ *
+ * @param maxRecords records queued before the policy applies, or 0 for no limit
+ * @param maxBytes   key and value bytes queued before the policy applies, or 0
+ *                   for no limit
+ * @param policy     what a submission does when the queue is full
+ */
+public record WriteQueueConfig(int maxRecords, long maxBytes, BackpressurePolicy policy) {
+
+    /**
+     * Configuration without a bound on queued appends.
+     */
+    public static final WriteQueueConfig UNBOUNDED = new WriteQueueConfig(0, 0, BackpressurePolicy.BLOCK);
+
+    public WriteQueueConfig {
+        if (maxRecords < 0) {
+            throw new IllegalArgumentException("maxRecords must not be negative");
+        }
+        if (maxBytes < 0) {
+            throw new IllegalArgumentException("maxBytes must not be negative");
+        }
+        if (policy == null) {
+            throw new IllegalArgumentException("policy must not be null");
+        }
+    }
+
+    /**
+     * Creates a config bounding queued records and bytes, blocking submitters
+     * while the queue is full.
+     *
+     * @param maxRecords records queued before submitters block, or 0 for no limit
+     * @param maxBytes   bytes queued before submitters block, or 0 for no limit
+     * @return a new WriteQueueConfig
+     */
+    public static WriteQueueConfig bounded(int maxRecords, long maxBytes) {
+        return new WriteQueueConfig(maxRecords, maxBytes, BackpressurePolicy.BLOCK);
+    }
+
+    /**
+     * Returns a copy of this config with the given policy.
+     *
+     * @param policy what a submission does when the queue is full
+     * @return a new WriteQueueConfig
+     */
+    public WriteQueueConfig withPolicy(BackpressurePolicy policy) {
+        return new WriteQueueConfig(maxRecords, maxBytes, policy);
+    }
+
+    /**
+     * Returns whether the queue is bounded.
+     *
+     * @return true if either limit is positive
+     */
+    public boolean enabled() {
+        return maxRecords > 0 || maxBytes > 0;
+    }
+}
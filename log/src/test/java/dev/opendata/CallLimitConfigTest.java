@@ -0,0 +1,43 @@
+package dev.opendata;
+
+import org.junit.jupiter.api.Test;
+
+import static org.assertj.core.api.Assertions.assertThat;
+import static org.assertj.core.api.Assertions.assertThatThrownBy;
+
+class CallLimitConfigTest {
+
+    @Test
+    void shouldBeUnlimitedByDefault() {
+        var config = LogDbConfig.inMemory();
+
+        assertThat(config.callLimit()).isEqualTo(CallLimitConfig.UNLIMITED);
+        assertThat(config.callLimit().enabled()).isFalse();
+    }
+
+    @Test
+    void shouldBlockByDefaultWhenLimited() {
+        var callLimit = CallLimitConfig.of(64);
+
+        assertThat(callLimit.enabled()).isTrue();
+        assertThat(callLimit.policy()).isEqualTo(OverloadPolicy.BLOCK);
+        assertThat(callLimit.withPolicy(OverloadPolicy.QUEUE).withMaxQueued(256))
+                .isEqualTo(new CallLimitConfig(64, OverloadPolicy.QUEUE, 256));
+    }
+
+    @Test
+    void shouldRejectInvalidLimits() {
+        assertThatThrownBy(() -> CallLimitConfig.of(-1))
+                .isInstanceOf(IllegalArgumentException.class)
+                .hasMessageContaining("maxCalls");
+        assertThatThrownBy(() -> CallLimitConfig.of(1).withPolicy(null))
+                .isInstanceOf(IllegalArgumentException.class)
+                .hasMessageContaining("policy");
+        assertThatThrownBy(() -> CallLimitConfig.of(1).withMaxQueued(-1))
+                .isInstanceOf(IllegalArgumentException.class)
+                .hasMessageContaining("maxQueued");
+        assertThatThrownBy(() -> LogDbConfig.inMemory().withCallLimit(null))
+                .isInstanceOf(IllegalArgumentException.class)
+                .hasMessageContaining("callLimit");
+    }
+}
@@ -0,0 +1,47 @@
+package dev.opendata;
+
+import org.junit.jupiter.api.Test;
+
+import static org.assertj.core.api.Assertions.assertThat;
+import static org.assertj.core.api.Assertions.assertThatThrownBy;
+
+class CoalesceConfigTest {
+
+    @Test
+    void shouldBeDisabledByDefault() {
+        var config = LogDbConfig.inMemory();
+
+        assertThat(config.coalesce()).isEqualTo(CoalesceConfig.DISABLED);
+        assertThat(config.coalesce().enabled()).isFalse();
+    }
+
+    @Test
+    void shouldUseDefaultLimitsForWindow() {
+        var coalesce = CoalesceConfig.within(500).withMaxRecords(100);
+
+        assertThat(coalesce.enabled()).isTrue();
+        assertThat(coalesce.windowMicros()).isEqualTo(500);
+        assertThat(coalesce.maxRecords()).isEqualTo(100);
+        assertThat(coalesce.maxBytes()).isEqualTo(CoalesceConfig.DEFAULT_MAX_BYTES);
+    }
+
+    @Test
+    void shouldRejectNonPositiveLimits() {
+        assertThatThrownBy(() -> CoalesceConfig.within(0))
+                .isInstanceOf(IllegalArgumentException.class)
+                .hasMessageContaining("windowMicros");
+        assertThatThrownBy(() -> CoalesceConfig.within(500).withMaxRecords(0))
+                .isInstanceOf(IllegalArgumentException.class)
+                .hasMessageContaining("maxRecords");
+        assertThatThrownBy(() -> CoalesceConfig.within(500).withMaxBytes(0))
+                .isInstanceOf(IllegalArgumentException.class)
+                .hasMessageContaining("maxBytes");
+    }
+
+    @Test
+    void shouldRejectNullCoalesce() {
+        assertThatThrownBy(() -> LogDbConfig.inMemory().withCoalesce(null))
+                .isInstanceOf(IllegalArgumentException.class)
+                .hasMessageContaining("coalesce");
+    }
+}
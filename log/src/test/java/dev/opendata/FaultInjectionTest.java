@@ -0,0 +1,58 @@
+package dev.opendata;
+
+import org.junit.jupiter.api.Test;
+
+import static org.assertj.core.api.Assertions.assertThat;
+import static org.assertj.core.api.Assertions.assertThatThrownBy;
+
+class FaultInjectionTest {
+
+    @Test
+    void shouldBeDisabledByDefault() {
+        var config = LogDbConfig.inMemory();
+
+        assertThat(config.faults()).isEqualTo(FaultInjection.DISABLED);
+        assertThat(config.faults().operationMask()).isZero();
+    }
+
+    @Test
+    void shouldTargetSelectedOperations() {
+        var faults = FaultInjection.latency(5, 10).withOperations(Operation.SCAN);
+
+        assertThat(faults.operations()).containsExactly(Operation.SCAN);
+        assertThat(faults.operationMask()).isEqualTo(1 << Operation.SCAN.ordinal());
+    }
+
+    @Test
+    void shouldRejectProbabilityOutsideUnitInterval() {
+        assertThatThrownBy(() -> FaultInjection.errors(1.5))
+                .isInstanceOf(IllegalArgumentException.class)
+                .hasMessageContaining("errorProbability");
+        assertThatThrownBy(() -> FaultInjection.errors(Double.NaN))
+                .isInstanceOf(IllegalArgumentException.class);
+    }
+
+    @Test
+    void shouldSimulateThrottlingOnAllOperations() {
+        var faults = FaultInjection.throttling(0.1, 0.05, 2_000);
+
+        assertThat(faults.throttleProbability()).isEqualTo(0.1);
+        assertThat(faults.timeoutProbability()).isEqualTo(0.05);
+        assertThat(faults.timeoutMs()).isEqualTo(2_000);
+        assertThat(faults.operations()).containsExactlyInAnyOrder(Operation.values());
+    }
+
+    @Test
+    void shouldRejectProbabilitiesSummingAboveOne() {
+        assertThatThrownBy(() -> FaultInjection.errors(0.6).withThrottling(0.5, 0, 0))
+                .isInstanceOf(IllegalArgumentException.class)
+                .hasMessageContaining("sum");
+    }
+
+    @Test
+    void shouldRejectInvertedLatencyRange() {
+        assertThatThrownBy(() -> FaultInjection.latency(10, 5))
+                .isInstanceOf(IllegalArgumentException.class)
+                .hasMessageContaining("maxLatencyMs");
+    }
+}
@@ -4,6 +4,8 @@ import dev.opendata.common.ObjectStoreConfig;
 import dev.opendata.common.StorageConfig;
 import org.junit.jupiter.api.Test;
 
+import java.util.Map;
+
 import static org.assertj.core.api.Assertions.assertThat;
 import static org.assertj.core.api.Assertions.assertThatThrownBy;
 
@@ -30,6 +32,150 @@ class LogDbConfigTest {
         assertThat(config.segmentation()).isEqualTo(SegmentConfig.DEFAULT);
     }
 
+    @Test
+    void shouldDisableProfilingByDefault() {
+        var config = new LogDbConfig(new StorageConfig.InMemory());
+
+        assertThat(config.profiling()).isFalse();
+    }
+
+    @Test
+    void shouldEnableProfiling() {
+        var config = LogDbConfig.inMemory().withProfiling(true);
+
+        assertThat(config.profiling()).isTrue();
+        assertThat(config.storage()).isInstanceOf(StorageConfig.InMemory.class);
+    }
+
+    @Test
+    void shouldDisableWatchdogByDefault() {
+        var config = LogDbConfig.inMemory();
+
+        assertThat(config.watchdog()).isEqualTo(WatchdogConfig.DISABLED);
+    }
+
+    @Test
+    void shouldConfigureWatchdog() {
+        var config = LogDbConfig.inMemory().withWatchdog(WatchdogConfig.failAfter(1_000));
+
+        assertThat(config.watchdog().thresholdMs()).isEqualTo(1_000L);
+        assertThat(config.watchdog().failStuckCalls()).isTrue();
+    }
+
+    @Test
+    void shouldUseDefaultDedupWindow() {
+        var config = LogDbConfig.inMemory();
+
+        assertThat(config.dedupWindow()).isEqualTo(LogDbConfig.DEFAULT_DEDUP_WINDOW);
+    }
+
+    @Test
+    void shouldRejectNonPositiveDedupWindow() {
+        assertThatThrownBy(() -> LogDbConfig.inMemory().withDedupWindow(0))
+                .isInstanceOf(IllegalArgumentException.class)
+                .hasMessageContaining("dedupWindow");
+    }
+
+    @Test
+    void shouldNotUseBridgeThreadsByDefault() {
+        var config = LogDbConfig.inMemory();
+
+        assertThat(config.bridgeThreads()).isZero();
+    }
+
+    @Test
+    void shouldRejectNegativeBridgeThreads() {
+        assertThatThrownBy(() -> LogDbConfig.inMemory().withBridgeThreads(-1))
+                .isInstanceOf(IllegalArgumentException.class)
+                .hasMessageContaining("bridgeThreads");
+    }
+
+    @Test
+    void shouldDisableTailCacheByDefault() {
+        var config = LogDbConfig.inMemory();
+
+        assertThat(config.tailCacheBytes()).isZero();
+    }
+
+    @Test
+    void shouldRejectNegativeTailCacheBytes() {
+        assertThatThrownBy(() -> LogDbConfig.inMemory().withTailCacheBytes(-1))
+                .isInstanceOf(IllegalArgumentException.class)
+                .hasMessageContaining("tailCacheBytes");
+    }
+
+    @Test
+    void shouldRejectNegativeChunkThresholdBytes() {
+        assertThatThrownBy(() -> LogDbConfig.inMemory().withChunkThresholdBytes(-1))
+                .isInstanceOf(IllegalArgumentException.class)
+                .hasMessageContaining("chunkThresholdBytes");
+    }
+
+    @Test
+    void shouldRejectInvalidTagName() {
+        assertThatThrownBy(() -> LogDbConfig.inMemory().withTags(Map.of("worker-id", "3")))
+                .isInstanceOf(IllegalArgumentException.class)
+                .hasMessageContaining("worker-id");
+    }
+
+    @Test
+    void shouldDefaultToNoTags() {
+        assertThat(LogDbConfig.inMemory().tags()).isEmpty();
+    }
+
+    @Test
+    void shouldDefaultToRecordClock() {
+        assertThat(LogDbConfig.inMemory().clock()).isEqualTo(ClockSource.RECORD);
+    }
+
+    @Test
+    void shouldDefaultToNoReceiveTimestamps() {
+        assertThat(LogDbConfig.inMemory().receiveTimestamps()).isFalse();
+    }
+
+    @Test
+    void shouldRejectNonPositiveBackgroundConcurrency() {
+        assertThat(LogDbConfig.inMemory().backgroundConcurrency())
+                .isEqualTo(LogDbConfig.DEFAULT_BACKGROUND_CONCURRENCY);
+        assertThatThrownBy(() -> LogDbConfig.inMemory().withBackgroundConcurrency(0))
+                .isInstanceOf(IllegalArgumentException.class);
+    }
+
+    @Test
+    void shouldRejectNegativeKeyStatsCapacity() {
+        assertThat(LogDbConfig.inMemory().keyStatsCapacity()).isZero();
+        assertThatThrownBy(() -> LogDbConfig.inMemory().withKeyStatsCapacity(-1))
+                .isInstanceOf(IllegalArgumentException.class);
+    }
+
+    @Test
+    void shouldRejectNullClock() {
+        assertThatThrownBy(() -> LogDbConfig.inMemory().withClock(null))
+                .isInstanceOf(IllegalArgumentException.class)
+                .hasMessageContaining("clock");
+    }
+
+    @Test
+    void shouldRejectNegativeSlowOperationThreshold() {
+        assertThatThrownBy(() -> LogDbConfig.inMemory().withSlowOperationThreshold(-1))
+                .isInstanceOf(IllegalArgumentException.class)
+                .hasMessageContaining("slowOperationThresholdMs");
+    }
+
+    @Test
+    void shouldRejectNegativeOpenTimeout() {
+        assertThatThrownBy(() -> LogDbConfig.inMemory().withOpenTimeout(-1))
+                .isInstanceOf(IllegalArgumentException.class)
+                .hasMessageContaining("openTimeoutMs");
+    }
+
+    @Test
+    void shouldRejectNullWatchdog() {
+        assertThatThrownBy(() -> LogDbConfig.inMemory().withWatchdog(null))
+                .isInstanceOf(IllegalArgumentException.class)
+                .hasMessageContaining("watchdog");
+    }
+
     @Test
     void shouldCreateInMemoryConfig() {
         var config = LogDbConfig.inMemory();
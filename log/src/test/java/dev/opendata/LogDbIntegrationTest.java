@@ -1,13 +1,46 @@
 package dev.opendata;
 
+import com.google.flatbuffers.FlatBufferBuilder;
+import dev.opendata.common.DiskCacheConfig;
 import dev.opendata.common.ObjectStoreConfig;
+import dev.opendata.common.OpenDataBackpressureException;
+import dev.opendata.common.OpenDataNativeException;
 import dev.opendata.common.StorageConfig;
+import jdk.jfr.Recording;
+import jdk.jfr.consumer.RecordedEvent;
+import jdk.jfr.consumer.RecordingFile;
+import org.HdrHistogram.Histogram;
+import org.apache.arrow.c.ArrowArray;
+import org.apache.arrow.c.ArrowSchema;
+import org.apache.arrow.c.Data;
+import org.apache.arrow.memory.BufferAllocator;
+import org.apache.arrow.memory.RootAllocator;
+import org.apache.arrow.vector.BigIntVector;
+import org.apache.arrow.vector.LargeVarBinaryVector;
+import org.apache.arrow.vector.VectorSchemaRoot;
 import org.junit.jupiter.api.Test;
 import org.junit.jupiter.api.io.TempDir;
 
+import java.io.IOException;
+import java.lang.ref.WeakReference;
+import java.net.DatagramPacket;
+import java.net.DatagramSocket;
+import java.net.InetAddress;
+import java.nio.ByteBuffer;
 import java.nio.charset.StandardCharsets;
+import java.nio.file.Files;
 import java.nio.file.Path;
+import java.util.ArrayList;
+import java.util.Base64;
+import java.util.Collections;
 import java.util.List;
+import java.util.Map;
+import java.util.Random;
+import java.util.concurrent.BlockingQueue;
+import java.util.concurrent.CompletableFuture;
+import java.util.concurrent.LinkedBlockingQueue;
+import java.util.concurrent.TimeUnit;
+import java.util.stream.LongStream;
 
 import static org.assertj.core.api.Assertions.assertThat;
 import static org.assertj.core.api.Assertions.assertThatThrownBy;
@@ -197,6 +230,109 @@ class LogDbIntegrationTest {
         }
     }
 
+    @Test
+    void shouldOpenConcurrentlyWithoutBlocking(@TempDir Path tempDir) {
+        List<CompletableFuture<LogDb>> opening = new ArrayList<>();
+        for (int i = 0; i < 2; i++) {
+            StorageConfig storage = new StorageConfig.SlateDb(
+                    "async-open-" + i, new ObjectStoreConfig.Local(tempDir.toString()));
+            opening.add(LogDb.openAsync(new LogDbConfig(storage)));
+        }
+
+        for (CompletableFuture<LogDb> future : opening) {
+            try (LogDb log = future.join()) {
+                byte[] key = "async-open-key".getBytes(StandardCharsets.UTF_8);
+                log.append(key, "value".getBytes(StandardCharsets.UTF_8));
+
+                assertThat(log.scan(key, 0, 10)).hasSize(1);
+            }
+        }
+    }
+
+    @Test
+    void shouldReportOpenPhasesInOrder() {
+        List<OpenPhase> phases = Collections.synchronizedList(new ArrayList<>());
+
+        try (LogDb log = LogDb.open(LogDbConfig.inMemory().withOpenTimeout(30_000), phases::add)) {
+            assertThat(phases).containsExactly(OpenPhase.values());
+        }
+    }
+
+    @Test
+    void shouldOpenInMemoryWithSharedCompactionRuntime() {
+        var config = LogDbConfig.inMemory()
+                .withRuntime(RuntimeConfig.OPERATION.withWorkerThreads(2))
+                .withCompactionRuntime(RuntimeConfig.SHARED);
+
+        try (LogDb log = LogDb.open(config)) {
+            log.append("shared-key".getBytes(StandardCharsets.UTF_8), new byte[10]);
+
+            assertThat(log.scan("shared-key".getBytes(StandardCharsets.UTF_8), 0, 10)).hasSize(1);
+            assertThat(log.stats().get("runtime.workers")).isEqualTo(2L);
+            assertThat(log.stats()).doesNotContainKey("compaction_runtime.workers");
+        }
+    }
+
+    @Test
+    void shouldFailOpenPinnedToUnavailableCore() {
+        var config = LogDbConfig.inMemory()
+                .withCompactionRuntime(RuntimeConfig.COMPACTION.withCpus(1_000_000));
+
+        assertThatThrownBy(() -> LogDb.open(config))
+                .isInstanceOf(OpenDataNativeException.class)
+                .hasMessageContaining("CPU");
+    }
+
+    @Test
+    void shouldOpenWithConfigFile(@TempDir Path tempDir) throws Exception {
+        Path configFile = tempDir.resolve("log.toml");
+        Files.writeString(configFile, String.join("\n",
+                "tail_cache_bytes = 1048576",
+                "",
+                "[storage]",
+                "type = \"slatedb\"",
+                "path = \"test-data\"",
+                "object_store = { type = \"local\", path = \"" + tempDir + "\" }",
+                ""));
+
+        try (LogDb log = LogDb.open(configFile)) {
+            byte[] key = "file-config-key".getBytes(StandardCharsets.UTF_8);
+            byte[] value = "file-config-value".getBytes(StandardCharsets.UTF_8);
+
+            log.append(key, value);
+
+            assertThat(log.scan(key, 0, 10)).extracting(LogEntry::value).containsExactly(value);
+            assertThat(log.stats()).containsKey("tail_cache.bytes");
+        }
+    }
+
+    @Test
+    void shouldRejectConfigFileWithUnknownSetting(@TempDir Path tempDir) throws Exception {
+        Path configFile = tempDir.resolve("log.yaml");
+        Files.writeString(configFile, "storage:\n  type: in_memory\nbridge_thread: 4\n");
+
+        assertThatThrownBy(() -> LogDb.open(configFile))
+                .isInstanceOf(IllegalArgumentException.class)
+                .hasMessageContaining("unknown setting bridge_thread");
+    }
+
+    @Test
+    void shouldReportNativeVersion() {
+        NativeVersion version = LogDb.nativeVersion();
+
+        assertThat(version.binding()).isNotBlank();
+        assertThat(version.logDb()).isNotBlank();
+        assertThat(version.slateDb()).isNotBlank();
+        assertThat(version.buildProfile()).isIn("debug", "release");
+        assertThat(version.allocator()).isIn("system", "jemalloc", "mimalloc");
+        assertThat(version.matchesJar()).isTrue();
+    }
+
+    @Test
+    void shouldReportAllNativeCapabilities() {
+        assertThat(LogDb.nativeCapabilities()).containsExactlyInAnyOrder(NativeCapability.values());
+    }
+
     @Test
     void shouldHandleLargeValues() {
         try (LogDb log = LogDb.openInMemory()) {
@@ -266,64 +402,2266 @@ class LogDbIntegrationTest {
     }
 
     @Test
-    void shouldCoexistWriterAndReaderWithoutFencingError(@TempDir Path tempDir) {
+    void shouldExportKeyRangeToFile(@TempDir Path tempDir) throws IOException {
+        // given
+        byte[] key = "export-key".getBytes(StandardCharsets.UTF_8);
+        Path ndjson = tempDir.resolve("entries.ndjson");
+        Path raw = tempDir.resolve("payloads.bin");
+
+        try (LogDb log = LogDb.open(new LogDbConfig(new StorageConfig.InMemory()))) {
+            for (int i = 0; i < 4; i++) {
+                log.append(key, ("value-" + i).getBytes(StandardCharsets.UTF_8));
+            }
+
+            // when
+            long exported = log.export(key, 1, 3, ndjson, ExportFormat.NDJSON);
+            long dumped = log.export(key, 0, Long.MAX_VALUE, raw, ExportFormat.RAW);
+
+            // then
+            assertThat(exported).isEqualTo(2);
+            List<String> lines = Files.readAllLines(ndjson);
+            assertThat(lines).hasSize(2);
+            assertThat(lines.get(0))
+                    .startsWith("{\"sequence\":1,")
+                    .contains("\"value\":\"" + Base64.getEncoder().encodeToString(
+                            "value-1".getBytes(StandardCharsets.UTF_8)) + "\"");
+            assertThat(dumped).isEqualTo(4);
+            ByteBuffer payloads = ByteBuffer.wrap(Files.readAllBytes(raw));
+            assertThat(payloads.getInt()).isEqualTo("value-0".length());
+            assertThat(payloads.remaining()).isEqualTo(4 * (4 + "value-0".length()) - 4);
+        }
+    }
+
+    @Test
+    void shouldImportExportedEntries(@TempDir Path tempDir) {
+        // given
+        byte[] key = "import-key".getBytes(StandardCharsets.UTF_8);
+        byte[] backlog = "backlog-key".getBytes(StandardCharsets.UTF_8);
+        Path ndjson = tempDir.resolve("entries.ndjson");
+        Path raw = tempDir.resolve("payloads.bin");
+        try (LogDb source = LogDb.open(new LogDbConfig(new StorageConfig.InMemory()))) {
+            source.append(new Record[] {
+                    new Record(key, "value-0".getBytes(StandardCharsets.UTF_8), 1000),
+                    new Record(key, "value-1".getBytes(StandardCharsets.UTF_8), 1001)
+            });
+            source.export(key, 0, Long.MAX_VALUE, ndjson, ExportFormat.NDJSON);
+            source.export(key, 0, Long.MAX_VALUE, raw, ExportFormat.RAW);
+        }
+
+        try (LogDb log = LogDb.open(new LogDbConfig(new StorageConfig.InMemory()))) {
+            // when
+            long imported = log.importFile(ndjson, ExportFormat.NDJSON);
+            long loaded = log.importFile(raw, ExportFormat.RAW, backlog);
+
+            // then
+            assertThat(imported).isEqualTo(2);
+            List<LogEntry> entries = log.scan(key, 0, 10);
+            assertThat(entries).hasSize(2);
+            assertThat(entries.get(1).timestamp()).isEqualTo(1001);
+            assertThat(new String(entries.get(1).value(), StandardCharsets.UTF_8)).isEqualTo("value-1");
+            assertThat(loaded).isEqualTo(2);
+            assertThat(log.scan(backlog, 0, 10)).hasSize(2);
+        }
+    }
+
+    @Test
+    void shouldCheckpointListAndDeleteCheckpoints(@TempDir Path tempDir) {
+        // given
+        byte[] key = "checkpoint-key".getBytes(StandardCharsets.UTF_8);
+        var config = new LogDbConfig(new StorageConfig.SlateDb(
+                "checkpoint-test", new ObjectStoreConfig.Local(tempDir.toString())));
+
+        try (LogDb log = LogDb.open(config)) {
+            log.append(key, "value-0".getBytes(StandardCharsets.UTF_8));
+
+            // when
+            Checkpoint golden = log.checkpoint("golden");
+            Checkpoint expiring = log.checkpoint("expiring", 60_000);
+
+            // then
+            assertThat(golden.name()).isEqualTo("golden");
+            assertThat(golden.expiresAtMs()).isZero();
+            assertThat(expiring.expiresAtMs()).isGreaterThan(expiring.createdAtMs());
+            assertThat(log.listCheckpoints()).extracting(Checkpoint::id)
+                    .contains(golden.id(), expiring.id());
+
+            log.deleteCheckpoint(golden.id());
+            assertThat(log.listCheckpoints()).extracting(Checkpoint::id)
+                    .doesNotContain(golden.id())
+                    .contains(expiring.id());
+        }
+    }
+
+    @Test
+    void shouldBackUpCheckpointToSecondObjectStore(@TempDir Path tempDir) {
+        // given
+        byte[] key = "backup-key".getBytes(StandardCharsets.UTF_8);
+        var source = new LogDbConfig(new StorageConfig.SlateDb(
+                "backup-source", new ObjectStoreConfig.Local(tempDir.resolve("primary").toString())));
+        var destination = new StorageConfig.SlateDb(
+                "backup-copy", new ObjectStoreConfig.Local(tempDir.resolve("secondary").toString()));
+        List<long[]> progress = new ArrayList<>();
+
+        try (LogDb log = LogDb.open(source)) {
+            log.append(key, "before".getBytes(StandardCharsets.UTF_8));
+            Checkpoint checkpoint = log.checkpoint("backup");
+            log.append(key, "after".getBytes(StandardCharsets.UTF_8));
+            log.flush();
+
+            // when
+            long copied = log.backup(checkpoint.id(), destination,
+                    (objects, total, bytes) -> progress.add(new long[]{objects, total, bytes}));
+
+            // then
+            assertThat(copied).isPositive();
+            assertThat(progress.get(progress.size() - 1)[0]).isEqualTo(copied);
+            assertThat(progress.get(progress.size() - 1)[1]).isEqualTo(copied);
+            assertThat(progress.get(progress.size() - 1)[2]).isPositive();
+        }
+        try (LogDb copy = LogDb.open(new LogDbConfig(destination))) {
+            assertThat(copy.scan(key, 0, 10)).extracting(entry -> new String(entry.value(), StandardCharsets.UTF_8))
+                    .containsExactly("before");
+        }
+    }
+
+    @Test
+    void shouldRestoreBackupIntoEmptyStorage(@TempDir Path tempDir) {
+        // given
+        byte[] key = "restore-key".getBytes(StandardCharsets.UTF_8);
+        var backup = new StorageConfig.SlateDb(
+                "restore-backup", new ObjectStoreConfig.Local(tempDir.resolve("backups").toString()));
+        try (LogDb writer = LogDb.open(new LogDbConfig(new StorageConfig.SlateDb(
+                "restore-test", new ObjectStoreConfig.Local(tempDir.resolve("original").toString()))))) {
+            writer.append(key, "value-0".getBytes(StandardCharsets.UTF_8));
+            writer.append(key, "value-1".getBytes(StandardCharsets.UTF_8));
+            writer.backup(writer.checkpoint("restore").id(), backup);
+        }
+        var restoredConfig = new LogDbConfig(new StorageConfig.SlateDb(
+                "restore-test", new ObjectStoreConfig.Local(tempDir.resolve("restored").toString())));
+
+        // when
+        try (LogDb restored = LogDb.restore(restoredConfig, backup)) {
+            var appended = restored.append(key, "value-2".getBytes(StandardCharsets.UTF_8));
+            List<LogEntry> entries = restored.scan(key, 0, 10);
+
+            // then
+            assertThat(entries).hasSize(3);
+            assertThat(new String(entries.get(1).value(), StandardCharsets.UTF_8)).isEqualTo("value-1");
+            assertThat(appended.sequence()).isEqualTo(2);
+        }
+        assertThatThrownBy(() -> LogDb.restore(restoredConfig, backup))
+                .isInstanceOf(OpenDataNativeException.class)
+                .hasMessageContaining("already holds data");
+    }
+
+    @Test
+    void shouldCloneLogIndependentlyOfParent(@TempDir Path tempDir) {
+        // given
+        byte[] key = "clone-key".getBytes(StandardCharsets.UTF_8);
+        var objectStore = new ObjectStoreConfig.Local(tempDir.toString());
+        try (LogDb golden = LogDb.open(new LogDbConfig(new StorageConfig.SlateDb("golden", objectStore)))) {
+            golden.append(key, "value-0".getBytes(StandardCharsets.UTF_8));
+
+            // when
+            golden.cloneTo("fork");
+            golden.append(key, "golden-1".getBytes(StandardCharsets.UTF_8));
+
+            try (LogDb fork = LogDb.open(new LogDbConfig(new StorageConfig.SlateDb("fork", objectStore)))) {
+                fork.append(key, "fork-1".getBytes(StandardCharsets.UTF_8));
+
+                // then
+                assertThat(fork.scan(key, 0, 10)).extracting(entry -> new String(entry.value(), StandardCharsets.UTF_8))
+                        .containsExactly("value-0", "fork-1");
+                assertThat(golden.scan(key, 0, 10)).extracting(entry -> new String(entry.value(), StandardCharsets.UTF_8))
+                        .containsExactly("value-0", "golden-1");
+            }
+        }
+    }
+
+    @Test
+    void shouldRejectCheckpointsOfInMemoryStorage() {
+        try (LogDb log = LogDb.open(LogDbConfig.inMemory())) {
+            assertThatThrownBy(() -> log.checkpoint("golden"))
+                    .isInstanceOf(OpenDataNativeException.class);
+        }
+    }
+
+    @Test
+    void shouldVerifyStoredEntries() {
+        // given
+        byte[] key = "verify-key".getBytes(StandardCharsets.UTF_8);
+        List<Long> progress = new ArrayList<>();
+
+        try (LogDb log = LogDb.open(new LogDbConfig(new StorageConfig.InMemory()))) {
+            for (int i = 0; i < 3; i++) {
+                log.append(key, ("value-" + i).getBytes(StandardCharsets.UTF_8));
+            }
+
+            // when
+            VerifyReport report = log.verify(key, 1, progress::add);
+
+            // then
+            assertThat(report.clean()).isTrue();
+            assertThat(report.entries()).isEqualTo(2);
+            assertThat(report.bytes()).isEqualTo(2 * (8 + "value-0".length()));
+            assertThat(report.lastSequence()).isEqualTo(2);
+            assertThat(report.corruptSequences()).isEmpty();
+            assertThat(progress).isEmpty();
+        }
+    }
+
+    @Test
+    void shouldReportGapsAndRepeatedPayloads() {
+        // given
+        byte[] key = "analyzed-key".getBytes(StandardCharsets.UTF_8);
+        byte[] other = "other-key".getBytes(StandardCharsets.UTF_8);
+
+        try (LogDb log = LogDb.open(new LogDbConfig(new StorageConfig.InMemory()))) {
+            log.append(key, "first".getBytes(StandardCharsets.UTF_8));
+            log.append(other, "interleaved".getBytes(StandardCharsets.UTF_8));
+            log.append(key, "second".getBytes(StandardCharsets.UTF_8));
+            log.append(key, "first".getBytes(StandardCharsets.UTF_8));
+
+            // when
+            SequenceReport report = log.analyzeSequences(key, 0);
+
+            // then
+            assertThat(report.entries()).isEqualTo(3);
+            assertThat(report.firstSequence()).isEqualTo(0);
+            assertThat(report.lastSequence()).isEqualTo(3);
+            assertThat(report.gaps()).isEqualTo(1);
+            assertThat(report.gapStarts()).containsExactly(1);
+            assertThat(report.gapEnds()).containsExactly(1);
+            assertThat(report.duplicates()).isEqualTo(1);
+            assertThat(report.duplicateSequences()).containsExactly(3);
+            assertThat(report.originalSequences()).containsExactly(0);
+            assertThat(report.error()).isNull();
+        }
+    }
+
+    @Test
+    void shouldExposeDurableAppendsToSeparateReader(@TempDir Path tempDir) {
         var storage = new StorageConfig.SlateDb(
-                "concurrent-test",
+                "durable-ack-test",
                 new ObjectStoreConfig.Local(tempDir.toString())
         );
-        var writerConfig = new LogDbConfig(storage);
-        var readerConfig = new LogDbReaderConfig(storage);
-
-        byte[] key = "concurrent-key".getBytes(StandardCharsets.UTF_8);
+        byte[] key = "durable-key".getBytes(StandardCharsets.UTF_8);
 
-        // Open writer and keep it open
-        try (LogDb writer = LogDb.open(writerConfig)) {
-            // Write initial data
-            writer.append(key, "value-0".getBytes(StandardCharsets.UTF_8));
+        try (LogDb writer = LogDb.open(new LogDbConfig(storage))) {
+            var result = writer.append(key, "durable".getBytes(StandardCharsets.UTF_8), AckMode.DURABLE);
+            assertThat(result.sequence()).isEqualTo(0);
 
-            // Open reader while writer is still open - this should NOT cause fencing error
-            try (LogDbReader reader = LogDbReader.open(readerConfig)) {
-                // Reader can read the data written by writer
+            // No explicit flush: the durable ack guarantees visibility to new readers
+            try (LogDbReader reader = LogDbReader.open(new LogDbReaderConfig(storage))) {
                 List<LogEntry> entries = reader.scan(key, 0, 10);
+
                 assertThat(entries).hasSize(1);
-                assertThat(new String(entries.get(0).value(), StandardCharsets.UTF_8)).isEqualTo("value-0");
+                assertThat(new String(entries.get(0).value(), StandardCharsets.UTF_8)).isEqualTo("durable");
+            }
+        }
+    }
 
-                // Writer can still write more data while reader is open
-                writer.append(key, "value-1".getBytes(StandardCharsets.UTF_8));
-                writer.append(key, "value-2".getBytes(StandardCharsets.UTF_8));
+    @Test
+    void shouldDropRetransmittedIdempotentBatch() {
+        try (LogDb log = LogDb.openInMemory()) {
+            byte[] key = "idempotent-key".getBytes(StandardCharsets.UTF_8);
+            Record[] batch = {new Record(key, "v0".getBytes(StandardCharsets.UTF_8))};
+
+            var first = log.appendIdempotent(batch, 42, 0);
+            var retry = log.appendIdempotent(batch, 42, 0);
+
+            assertThat(retry.sequence()).isEqualTo(first.sequence());
+            assertThat(log.scan(key, 0, 10)).hasSize(1);
+        }
+    }
+
+    @Test
+    void shouldAppendDistinctIdempotentBatches() {
+        try (LogDb log = LogDb.openInMemory()) {
+            byte[] key = "idempotent-distinct-key".getBytes(StandardCharsets.UTF_8);
+            Record[] batch = {new Record(key, "v".getBytes(StandardCharsets.UTF_8))};
+
+            log.appendIdempotent(batch, 1, 0);
+            log.appendIdempotent(batch, 1, 1);
+            log.appendIdempotent(batch, 2, 0);
+
+            assertThat(log.scan(key, 0, 10)).hasSize(3);
+        }
+    }
+
+    @Test
+    void shouldRejectIdempotentBatchOlderThanWindow() {
+        try (LogDb log = LogDb.open(LogDbConfig.inMemory().withDedupWindow(1))) {
+            byte[] key = "idempotent-window-key".getBytes(StandardCharsets.UTF_8);
+            Record[] batch = {new Record(key, "v".getBytes(StandardCharsets.UTF_8))};
+            log.appendIdempotent(batch, 1, 0);
+            log.appendIdempotent(batch, 1, 1);
+
+            assertThatThrownBy(() -> log.appendIdempotent(batch, 1, 0))
+                    .isInstanceOf(IllegalStateException.class)
+                    .hasMessageContaining("dedup window");
+        }
+    }
+
+    @Test
+    void shouldCommitAndFetchOffsets() {
+        try (LogDb log = LogDb.openInMemory()) {
+            byte[] key = "offset-key".getBytes(StandardCharsets.UTF_8);
+
+            assertThat(log.fetchOffset("group", key)).isEmpty();
+
+            log.commitOffset("group", key, 5);
+            log.commitOffset("group", key, 9);
+            log.commitOffset("other-group", key, 1);
+
+            assertThat(log.fetchOffset("group", key)).hasValue(9);
+            assertThat(log.fetchOffset("other-group", key)).hasValue(1);
+            assertThat(log.scan(key, 0, 10)).isEmpty();
+        }
+    }
+
+    @Test
+    void shouldResumeFromCommittedOffsetAfterReopen(@TempDir Path tempDir) {
+        var storage = new StorageConfig.SlateDb(
+                "offsets-test",
+                new ObjectStoreConfig.Local(tempDir.toString())
+        );
+        byte[] key = "offset-reopen-key".getBytes(StandardCharsets.UTF_8);
+
+        try (LogDb writer = LogDb.open(new LogDbConfig(storage))) {
+            writer.commitOffset("group", key, 42);
+        }
+
+        try (LogDbReader reader = LogDbReader.open(new LogDbReaderConfig(storage))) {
+            assertThat(reader.fetchOffset("group", key)).hasValue(42);
+        }
+    }
+
+    @Test
+    void shouldCommitOffsetsThroughAttachedWriter(@TempDir Path tempDir) {
+        var storage = new StorageConfig.SlateDb(
+                "reader-offsets-test",
+                new ObjectStoreConfig.Local(tempDir.toString())
+        );
+        byte[] key = "reader-offset-key".getBytes(StandardCharsets.UTF_8);
+
+        try (LogDb writer = LogDb.open(new LogDbConfig(storage));
+             LogDbReader reader = LogDbReader.open(new LogDbReaderConfig(storage))) {
+            assertThatThrownBy(() -> reader.commitOffset("group", key, 1))
+                    .isInstanceOf(IllegalStateException.class)
+                    .hasMessageContaining("No offset writer");
+
+            reader.attachOffsetWriter(writer);
+            reader.commitOffset("group", key, 7);
+
+            assertThat(reader.fetchOffset("group", key)).hasValue(7);
+            assertThat(writer.fetchOffset("group", key)).hasValue(7);
+        }
+    }
+
+    @Test
+    void shouldServeSequentialScansFromReadAhead(@TempDir Path tempDir) {
+        var storage = new StorageConfig.SlateDb(
+                "read-ahead-test",
+                new ObjectStoreConfig.Local(tempDir.toString())
+        );
+        byte[] key = "read-ahead-key".getBytes(StandardCharsets.UTF_8);
+
+        try (LogDb writer = LogDb.open(new LogDbConfig(storage))) {
+            for (int i = 0; i < 100; i++) {
+                writer.append(key, ("value-" + i).getBytes(StandardCharsets.UTF_8));
             }
+            writer.flush();
+        }
 
-            // After reader closes, writer should still work
-            writer.append(key, "value-3".getBytes(StandardCharsets.UTF_8));
+        try (LogDbReader reader = LogDbReader.open(new LogDbReaderConfig(storage).withReadAhead(true))) {
+            List<LogEntry> entries = new ArrayList<>();
+            long next = 0;
+            List<LogEntry> page;
+            do {
+                page = reader.scan(key, next, 10);
+                entries.addAll(page);
+                if (!page.isEmpty()) {
+                    next = page.get(page.size() - 1).sequence() + 1;
+                }
+            } while (!page.isEmpty());
 
-            List<LogEntry> finalEntries = writer.scan(key, 0, 10);
-            assertThat(finalEntries).hasSize(4);
+            assertThat(entries).hasSize(100);
+            assertThat(entries).extracting(LogEntry::sequence).isSorted().doesNotHaveDuplicates();
+            assertThat(reader.stats().get("read_ahead.hits")).isPositive();
         }
     }
 
     @Test
-    void shouldOpenReaderWithCustomRefreshInterval(@TempDir Path tempDir) {
+    void shouldResumeScansFromTokensAcrossReaders(@TempDir Path tempDir) {
         var storage = new StorageConfig.SlateDb(
-                "custom-refresh-test",
+                "resume-token-test",
                 new ObjectStoreConfig.Local(tempDir.toString())
         );
-        var writerConfig = new LogDbConfig(storage);
-        // Use a custom refresh interval of 500ms
-        var readerConfig = new LogDbReaderConfig(storage, 500L);
+        byte[] key = "resume-key".getBytes(StandardCharsets.UTF_8);
 
-        byte[] key = "refresh-key".getBytes(StandardCharsets.UTF_8);
+        try (LogDb writer = LogDb.open(new LogDbConfig(storage))) {
+            for (int i = 0; i < 30; i++) {
+                writer.append(key, ("value-" + i).getBytes(StandardCharsets.UTF_8));
+            }
+            writer.flush();
+        }
 
-        // Write with LogDb
-        try (LogDb writer = LogDb.open(writerConfig)) {
-            writer.append(key, "value-0".getBytes(StandardCharsets.UTF_8));
+        List<LogEntry> entries = new ArrayList<>();
+        byte[] token;
+        try (LogDbReader reader = LogDbReader.open(new LogDbReaderConfig(storage))) {
+            ScanPage page = reader.scanPage(key, 0, 10);
+            entries.addAll(page.entries());
+            page = reader.scanPage(key, page.resumeToken(), 10);
+            entries.addAll(page.entries());
+            token = page.resumeToken();
+
+            assertThat(reader.stats()).containsEntry("cursors.opened", 1L);
+            assertThat(reader.stats()).containsEntry("cursors.resumed", 1L);
+            assertThatThrownBy(() -> reader.scanPage("other-key".getBytes(StandardCharsets.UTF_8), token, 10))
+                    .isInstanceOf(IllegalArgumentException.class);
         }
 
-        // Read with LogDbReader using custom refresh interval
-        try (LogDbReader reader = LogDbReader.open(readerConfig)) {
-            List<LogEntry> entries = reader.scan(key, 0, 10);
-            assertThat(entries).hasSize(1);
-            assertThat(new String(entries.get(0).value(), StandardCharsets.UTF_8)).isEqualTo("value-0");
+        try (LogDbReader reader = LogDbReader.open(new LogDbReaderConfig(storage))) {
+            entries.addAll(reader.scanPage(key, token, 100).entries());
+        }
+
+        assertThat(entries).extracting(LogEntry::sequence)
+                .containsExactlyElementsOf(LongStream.range(0, 30).boxed().toList());
+    }
+
+    @Test
+    void shouldServeTailScansFromWriterTailCache(@TempDir Path tempDir) {
+        var storage = new StorageConfig.SlateDb(
+                "tail-cache-test",
+                new ObjectStoreConfig.Local(tempDir.toString())
+        );
+        byte[] key = "tail-cache-key".getBytes(StandardCharsets.UTF_8);
+
+        try (LogDb writer = LogDb.open(new LogDbConfig(storage).withTailCacheBytes(1 << 20));
+             LogDbReader reader = LogDbReader.open(new LogDbReaderConfig(storage))) {
+            reader.attachTailCache(writer);
+            for (int i = 0; i < 10; i++) {
+                writer.append(key, ("value-" + i).getBytes(StandardCharsets.UTF_8));
+            }
+
+            List<LogEntry> fromWriter = writer.scan(key, 0, 100);
+            List<LogEntry> fromReader = reader.scan(key, 5, 100);
+
+            assertThat(fromWriter).extracting(LogEntry::sequence)
+                    .containsExactly(0L, 1L, 2L, 3L, 4L, 5L, 6L, 7L, 8L, 9L);
+            assertThat(fromReader).extracting(LogEntry::sequence)
+                    .containsExactly(5L, 6L, 7L, 8L, 9L);
+            assertThat(writer.stats().get("tail_cache.hits")).isEqualTo(2L);
+        }
+    }
+
+    @Test
+    void shouldReportBytesServedFromTailCacheAndStorage() {
+        byte[] key = "served-bytes-key".getBytes(StandardCharsets.UTF_8);
+        var config = LogDbConfig.inMemory().withTailCacheBytes(1 << 20);
+
+        try (LogDb log = LogDb.open(config)) {
+            log.scan(key, 0, 10);
+            log.append(key, "12345".getBytes(StandardCharsets.UTF_8));
+            log.scan(key, 0, 10);
+
+            Map<String, Long> stats = log.stats();
+            // Stored values carry an 8-byte timestamp header
+            assertThat(stats.get("scan.cache_bytes")).isEqualTo(key.length + 8 + 5L);
+            assertThat(stats.get("scan.storage_bytes")).isZero();
+            assertThat(stats.get("tail_cache.hit_rate_ppm")).isEqualTo(500_000L);
+        }
+    }
+
+    @Test
+    void shouldReassembleValuesSplitIntoChunks() {
+        byte[] key = "chunked-key".getBytes(StandardCharsets.UTF_8);
+        byte[] large = "0123456789".getBytes(StandardCharsets.UTF_8);
+        byte[] small = "abc".getBytes(StandardCharsets.UTF_8);
+        var config = LogDbConfig.inMemory().withChunkThresholdBytes(4);
+
+        try (LogDb log = LogDb.open(config)) {
+            log.append(new Record[]{new Record(key, large), new Record(key, small)});
+
+            List<LogEntry> entries = log.scan(key, 0, 10);
+            assertThat(entries).extracting(LogEntry::sequence).containsExactly(0L, 3L);
+            assertThat(entries.get(0).value()).isEqualTo(large);
+            assertThat(entries.get(1).value()).isEqualTo(small);
+            // Starting inside a chunked value skips to the next whole one
+            assertThat(log.scan(key, 1, 10)).extracting(LogEntry::sequence).containsExactly(3L);
+        }
+    }
+
+    @Test
+    void shouldScanNegativeTimestampsWholeWithChunkingEnabled() {
+        byte[] key = "negative-timestamp-key".getBytes(StandardCharsets.UTF_8);
+        // Reads as a chunk index and count if a timestamp bit marked chunks
+        byte[] value = {0, 0, 0, 0, 0, 0, 0, 1, 'p', 'a', 'y'};
+        var config = LogDbConfig.inMemory().withChunkThresholdBytes(64);
+
+        try (LogDb log = LogDb.open(config)) {
+            log.append(new Record[]{new Record(key, value, -1), new Record(key, value, Long.MIN_VALUE)});
+
+            List<LogEntry> entries = log.scan(key, 0, 10);
+            assertThat(entries).extracting(LogEntry::timestamp).containsExactly(-1L, Long.MIN_VALUE);
+            assertThat(entries).allSatisfy(entry -> assertThat(entry.value()).isEqualTo(value));
         }
     }
 
+    @Test
+    void shouldAppendValuesCopiedWithCriticalArrayAccess() {
+        byte[] key = "critical-key".getBytes(StandardCharsets.UTF_8);
+        byte[] value = new byte[1 << 20];
+        new Random(7).nextBytes(value);
+        var config = LogDbConfig.inMemory().withCriticalValueCopy(true);
+
+        try (LogDb log = LogDb.open(config)) {
+            log.append(new Record[]{new Record(key, value), new Record(key, new byte[0])});
+
+            List<LogEntry> entries = log.scan(key, 0, 10);
+            assertThat(entries).hasSize(2);
+            assertThat(entries.get(0).value()).isEqualTo(value);
+            assertThat(entries.get(1).value()).isEmpty();
+        }
+    }
+
+    @Test
+    void shouldScanIntoReusableBuffers() {
+        byte[] key = "scan-into-key".getBytes(StandardCharsets.UTF_8);
+        ByteBuffer dest = ByteBuffer.allocateDirect(16 + 7 + 16 + 7);
+        int[] offsets = new int[10];
+
+        try (LogDb log = LogDb.open(LogDbConfig.inMemory())) {
+            log.append(new Record[]{
+                    new Record(key, "value-0".getBytes(StandardCharsets.UTF_8)),
+                    new Record(key, "value-1".getBytes(StandardCharsets.UTF_8)),
+                    new Record(key, "value-2".getBytes(StandardCharsets.UTF_8)),
+            });
+
+            int count = log.scanInto(key, 0, dest, offsets);
+
+            assertThat(count).isEqualTo(2);
+            assertThat(offsets[count]).isEqualTo(dest.limit());
+            assertThat(dest.getLong(offsets[1])).isEqualTo(1L);
+            byte[] payload = new byte[offsets[2] - offsets[1] - 16];
+            dest.get(offsets[1] + 16, payload);
+            assertThat(payload).isEqualTo("value-1".getBytes(StandardCharsets.UTF_8));
+            assertThat(log.scanInto(key, 2, dest, offsets)).isEqualTo(1);
+        }
+    }
+
+    @Test
+    void shouldSkipEntriesFailingScanFilter() {
+        byte[] key = "filter-key".getBytes(StandardCharsets.UTF_8);
+
+        try (LogDb log = LogDb.open(LogDbConfig.inMemory())) {
+            for (int i = 0; i < 20; i++) {
+                log.append(key, new byte[i % 2 == 0 ? 10 : 1000]);
+            }
+
+            List<LogEntry> large = log.scan(key, 0, 5, ScanFilter.ALL.withValueLength(500, Long.MAX_VALUE));
+            List<LogEntry> none = log.scan(key, 0, 5, ScanFilter.ALL.withTimestamp(0, 0));
+
+            assertThat(large).extracting(LogEntry::sequence).containsExactly(1L, 3L, 5L, 7L, 9L);
+            assertThat(large).allSatisfy(entry -> assertThat(entry.value()).hasSize(1000));
+            assertThat(none).isEmpty();
+            assertThatThrownBy(() -> ScanFilter.ALL.withValueLength(10, 5))
+                    .isInstanceOf(IllegalArgumentException.class);
+        }
+    }
+
+    @Test
+    void shouldReportDurableWatermarkOfEachKey() {
+        byte[] first = "sync-a".getBytes(StandardCharsets.UTF_8);
+        byte[] second = "sync-b".getBytes(StandardCharsets.UTF_8);
+
+        try (LogDb log = LogDb.open(LogDbConfig.inMemory())) {
+            log.append(first, new byte[1]);
+            AppendResult last = log.append(second, new byte[1]);
+            AppendResult tail = log.append(first, new byte[1]);
+
+            List<KeyWatermark> watermarks = log.sync();
+
+            assertThat(watermarks).extracting(KeyWatermark::key).containsExactly(first, second);
+            assertThat(watermarks).extracting(KeyWatermark::tail)
+                    .containsExactly(tail.sequence() + 1, last.sequence() + 1);
+        }
+    }
+
+    @Test
+    void shouldGroupMultiKeyAppendsByKey() {
+        byte[] orders = "grouped-orders".getBytes(StandardCharsets.UTF_8);
+        byte[] payments = "grouped-payments".getBytes(StandardCharsets.UTF_8);
+
+        try (LogDb log = LogDb.open(LogDbConfig.inMemory())) {
+            List<KeyAppendResult> results = log.appendGrouped(new Record[]{
+                    new Record(orders, "o1".getBytes(StandardCharsets.UTF_8)),
+                    new Record(payments, "p1".getBytes(StandardCharsets.UTF_8)),
+                    new Record(orders, "o2".getBytes(StandardCharsets.UTF_8)),
+            }, AckMode.BUFFERED);
+
+            assertThat(results).hasSize(2);
+            assertThat(results.get(0).key()).isEqualTo(orders);
+            assertThat(results.get(0).count()).isEqualTo(2);
+            assertThat(results.get(1).key()).isEqualTo(payments);
+            assertThat(results.get(1).sequence()).isEqualTo(results.get(0).sequence() + 2);
+            assertThat(log.scan(orders, 0, 10)).extracting(entry -> new String(entry.value(), StandardCharsets.UTF_8))
+                    .containsExactly("o1", "o2");
+            assertThat(log.scan(orders, 0, 10)).extracting(LogEntry::sequence)
+                    .containsExactly(results.get(0).sequence(), results.get(0).sequence() + 1);
+            assertThat(log.scan(payments, 0, 10)).extracting(LogEntry::sequence)
+                    .containsExactly(results.get(1).sequence());
+        }
+    }
+
+    @Test
+    void shouldSampleEntriesReproducibly() {
+        byte[] key = "sample-key".getBytes(StandardCharsets.UTF_8);
+
+        try (LogDb log = LogDb.open(LogDbConfig.inMemory())) {
+            log.appendSynthetic(key, 16, 200);
+
+            List<LogEntry> first = log.scanSample(key, 0, Long.MAX_VALUE, 0.1, 42);
+            List<LogEntry> second = log.scanSample(key, 0, Long.MAX_VALUE, 0.1, 42);
+            List<LogEntry> sparse = log.scanSample(key, 50, 150, 0.01, 7);
+
+            assertThat(first).extracting(LogEntry::sequence)
+                    .containsExactlyElementsOf(second.stream().map(LogEntry::sequence).toList())
+                    .isSorted();
+            assertThat(sparse).extracting(LogEntry::sequence).allMatch(seq -> seq >= 50 && seq < 150);
+            assertThat(log.scanSample(key, 0, Long.MAX_VALUE, 1.0, 1)).hasSize(200);
+            assertThat(log.scanSample(key, 0, Long.MAX_VALUE, 0.0, 1)).isEmpty();
+            assertThatThrownBy(() -> log.scanSample(key, 0, 10, 1.5, 1))
+                    .isInstanceOf(IllegalArgumentException.class);
+        }
+    }
+
+    @Test
+    void shouldScanMetadataWithoutValues() {
+        byte[] key = "metadata-key".getBytes(StandardCharsets.UTF_8);
+
+        try (LogDb log = LogDb.open(LogDbConfig.inMemory())) {
+            log.append(new Record[]{
+                    new Record(key, new byte[3]),
+                    new Record(key, new byte[1024]),
+                    new Record(key, new byte[0]),
+            });
+
+            List<EntryMetadata> entries = log.scanMetadata(key, 1, 10);
+
+            assertThat(entries).extracting(EntryMetadata::sequence).containsExactly(1L, 2L);
+            assertThat(entries).extracting(EntryMetadata::valueLength).containsExactly(1024, 0);
+            assertThat(entries.get(0).timestamp()).isEqualTo(log.scan(key, 1, 1).get(0).timestamp());
+            assertThat(log.scanMetadata("absent".getBytes(StandardCharsets.UTF_8), 0, 10)).isEmpty();
+        }
+    }
+
+    @Test
+    void shouldAppendFlatBufferBatch() {
+        byte[] key = "flatbuffer-key".getBytes(StandardCharsets.UTF_8);
+        // AppendBatch { records: [Record { key, value, timestamp_ms }] }
+        var builder = new FlatBufferBuilder();
+        int[] records = new int[2];
+        for (int i = 0; i < records.length; i++) {
+            int keyOffset = builder.createByteVector(key);
+            int valueOffset = builder.createByteVector(("value-" + i).getBytes(StandardCharsets.UTF_8));
+            builder.startTable(3);
+            builder.addOffset(0, keyOffset, 0);
+            builder.addOffset(1, valueOffset, 0);
+            builder.addLong(2, 1_000L + i, 0);
+            records[i] = builder.endTable();
+        }
+        builder.startVector(4, records.length, 4);
+        for (int i = records.length - 1; i >= 0; i--) {
+            builder.addOffset(records[i]);
+        }
+        int vector = builder.endVector();
+        builder.startTable(1);
+        builder.addOffset(0, vector, 0);
+        builder.finish(builder.endTable());
+
+        try (LogDb log = LogDb.open(LogDbConfig.inMemory())) {
+            AppendResult result = log.appendFlatBuffer(builder.sizedByteArray(), AckMode.BUFFERED);
+
+            assertThat(result.timestamp()).isEqualTo(1_000L);
+            List<LogEntry> entries = log.scan(key, 0, 10);
+            assertThat(entries).extracting(LogEntry::timestamp).containsExactly(1_000L, 1_001L);
+            assertThat(entries.get(1).value()).isEqualTo("value-1".getBytes(StandardCharsets.UTF_8));
+            assertThat(log.scanFlatBuffer(key, 0, 10)).isNotEmpty();
+            assertThatThrownBy(() -> log.appendFlatBuffer(new byte[]{1, 2, 3}, AckMode.BUFFERED))
+                    .isInstanceOf(IllegalArgumentException.class);
+        }
+    }
+
+    @Test
+    void shouldTagStatNames() {
+        var config = LogDbConfig.inMemory().withTags(Map.of("workerId", "3", "region", "eu-west-1"));
+
+        try (LogDb log = LogDb.open(config)) {
+            assertThat(log.stats()).containsKey("uptime_nanos{region=\"eu-west-1\",workerId=\"3\"}");
+            assertThat(log.stats()).doesNotContainKey("uptime_nanos");
+        }
+    }
+
+    @Test
+    void shouldStampRecordsWithManualClock() {
+        byte[] key = "clock-key".getBytes(StandardCharsets.UTF_8);
+
+        try (LogDb log = LogDb.open(LogDbConfig.inMemory().withClock(ClockSource.MANUAL))) {
+            // given
+            log.setClock(5_000);
+
+            // when
+            AppendResult first = log.append(new Record[]{
+                    new Record(key, "value-0".getBytes(StandardCharsets.UTF_8), 1),
+                    new Record(key, "value-1".getBytes(StandardCharsets.UTF_8), 2),
+            });
+            long advanced = log.advanceClock(250);
+            AppendResult second = log.append(key, "value-2".getBytes(StandardCharsets.UTF_8));
+
+            // then
+            assertThat(first.timestamp()).isEqualTo(5_000);
+            assertThat(advanced).isEqualTo(5_250);
+            assertThat(second.timestamp()).isEqualTo(5_250);
+            assertThat(log.scan(key, 0, 10)).extracting(LogEntry::timestamp)
+                    .containsExactly(5_000L, 5_000L, 5_250L);
+        }
+    }
+
+    @Test
+    void shouldReportSkewOfReceiveTimestamps() {
+        byte[] key = "skew-key".getBytes(StandardCharsets.UTF_8);
+        long producedMs = System.currentTimeMillis() - 60_000;
+
+        try (LogDb log = LogDb.open(LogDbConfig.inMemory().withReceiveTimestamps(true))) {
+            // given
+            log.append(new Record[]{
+                    new Record(key, "value-0".getBytes(StandardCharsets.UTF_8), producedMs),
+            });
+
+            // when
+            List<LogEntry> entries = log.scan(key, 0, 10);
+
+            // then
+            assertThat(entries).hasSize(1);
+            assertThat(entries.get(0).timestamp()).isEqualTo(producedMs);
+            assertThat(new String(entries.get(0).value(), StandardCharsets.UTF_8)).isEqualTo("value-0");
+            Map<String, Long> stats = log.stats();
+            assertThat(stats).containsEntry("clock_skew.samples", 1L);
+            assertThat(stats.get("clock_skew.min_ms")).isGreaterThanOrEqualTo(60_000L);
+        }
+    }
+
+    @Test
+    void shouldNotifyWatchOfNewTailsOfKey() throws InterruptedException {
+        byte[] key = "watched-key".getBytes(StandardCharsets.UTF_8);
+        byte[] otherKey = "other-key".getBytes(StandardCharsets.UTF_8);
+        BlockingQueue<Long> tails = new LinkedBlockingQueue<>();
+
+        try (LogDb log = LogDb.open(LogDbConfig.inMemory())) {
+            // given
+            log.append(new Record[]{new Record(key, new byte[]{0}, 1000L)});
+            long start = log.append(new Record[]{new Record(key, new byte[]{1}, 1001L)}).sequence();
+            AppendWatch watch = log.watchAppends(key, start + 1, tails::add);
+
+            // when
+            log.append(new Record[]{new Record(otherKey, new byte[]{2}, 1002L)});
+            long last = log.append(new Record[]{
+                    new Record(key, new byte[]{3}, 1003L),
+                    new Record(otherKey, new byte[]{4}, 1004L),
+            }).sequence();
+
+            // then
+            assertThat(tails.poll(5, TimeUnit.SECONDS)).isEqualTo(last + 1);
+            watch.close();
+            log.append(new Record[]{new Record(key, new byte[]{5}, 1005L)});
+            assertThat(tails.poll(200, TimeUnit.MILLISECONDS)).isNull();
+        }
+    }
+
+    @Test
+    void shouldNotPinListenerOfUnreachableWatch() throws InterruptedException {
+        byte[] key = "forgotten-key".getBytes(StandardCharsets.UTF_8);
+
+        try (LogDb log = LogDb.open(LogDbConfig.inMemory())) {
+            // given
+            long[] seen = new long[1];
+            TailListener listener = tail -> seen[0] = tail;
+            WeakReference<TailListener> collected = new WeakReference<>(listener);
+            log.watchAppends(key, 0, listener);
+            listener = null;
+
+            // when
+            for (int i = 0; i < 50 && collected.get() != null; i++) {
+                System.gc();
+                Thread.sleep(10);
+            }
+
+            // then
+            assertThat(collected.get()).isNull();
+            log.append(key, new byte[]{1});
+        }
+    }
+
+    @Test
+    void shouldNotifyReaderWatchOfEntriesAppendedByWriter(@TempDir Path tempDir) throws InterruptedException {
+        var storage = new StorageConfig.SlateDb(
+                "reader-watch-test",
+                new ObjectStoreConfig.Local(tempDir.toString())
+        );
+        byte[] key = "polled-key".getBytes(StandardCharsets.UTF_8);
+        BlockingQueue<Long> tails = new LinkedBlockingQueue<>();
+
+        try (LogDb writer = LogDb.open(new LogDbConfig(storage))) {
+            // given
+            long stored = writer.append(key, new byte[]{0}, AckMode.DURABLE).sequence();
+            try (LogDbReader reader = LogDbReader.open(new LogDbReaderConfig(storage, 50L))) {
+                AppendWatch watch = reader.watchAppends(key, 0, tails::add);
+                assertThat(tails.poll(5, TimeUnit.SECONDS)).isEqualTo(stored + 1);
+
+                // when
+                long appended = writer.append(key, new byte[]{1}, AckMode.DURABLE).sequence();
+
+                // then
+                assertThat(tails.poll(5, TimeUnit.SECONDS)).isEqualTo(appended + 1);
+                watch.close();
+                writer.append(key, new byte[]{2}, AckMode.DURABLE);
+                assertThat(tails.poll(200, TimeUnit.MILLISECONDS)).isNull();
+            }
+        }
+    }
+
+    @Test
+    void shouldRejectSettingClockOfRecordTimestamps() {
+        try (LogDb log = LogDb.open(LogDbConfig.inMemory())) {
+            assertThatThrownBy(() -> log.setClock(5_000))
+                    .isInstanceOf(IllegalStateException.class)
+                    .hasMessageContaining("ClockSource.MANUAL");
+        }
+    }
+
+    @Test
+    void shouldEmitJfrEventsForNativeOperations(@TempDir Path tempDir) throws IOException {
+        byte[] key = "jfr-key".getBytes(StandardCharsets.UTF_8);
+        Path dump = tempDir.resolve("native.jfr");
+
+        try (LogDb log = LogDb.open(LogDbConfig.inMemory());
+             Recording recording = new Recording()) {
+            recording.enable("dev.opendata.NativeOperation");
+            recording.start();
+            log.append(key, "value".getBytes(StandardCharsets.UTF_8));
+            log.scan(key, 0, 10);
+            log.flush();
+            recording.stop();
+            recording.dump(dump);
+        }
+
+        List<RecordedEvent> events = RecordingFile.readAllEvents(dump);
+        assertThat(events).extracting(event -> event.getString("operation"))
+                .containsExactly("append", "scan", "flush");
+        assertThat(events.get(0).getLong("entries")).isEqualTo(1);
+        assertThat(events.get(1).getLong("bytes")).isEqualTo(13);
+        assertThat(events).allMatch(event -> event.getBoolean("succeeded"));
+    }
+
+    @Test
+    void shouldScanIntoArrowBatch() {
+        byte[] key = "arrow-key".getBytes(StandardCharsets.UTF_8);
+
+        try (LogDb log = LogDb.open(LogDbConfig.inMemory());
+             BufferAllocator allocator = new RootAllocator();
+             ArrowArray array = ArrowArray.allocateNew(allocator);
+             ArrowSchema schema = ArrowSchema.allocateNew(allocator)) {
+            log.append(new Record[]{
+                    new Record(key, "value-0".getBytes(StandardCharsets.UTF_8)),
+                    new Record(key, "value-1".getBytes(StandardCharsets.UTF_8)),
+            });
+
+            int count = log.scanArrow(key, 0, 10, array.memoryAddress(), schema.memoryAddress());
+
+            try (VectorSchemaRoot root = Data.importVectorSchemaRoot(allocator, array, schema, null)) {
+                assertThat(count).isEqualTo(2);
+                assertThat(root.getRowCount()).isEqualTo(2);
+                assertThat(((BigIntVector) root.getVector("sequence")).get(1)).isEqualTo(1L);
+                assertThat(((LargeVarBinaryVector) root.getVector("value")).get(0))
+                        .isEqualTo("value-0".getBytes(StandardCharsets.UTF_8));
+            }
+        }
+    }
+
+    @Test
+    void shouldReportNativeMemoryHeldByTailCache() {
+        byte[] key = "memory-key".getBytes(StandardCharsets.UTF_8);
+        var config = LogDbConfig.inMemory().withTailCacheBytes(1 << 20);
+
+        try (LogDb log = LogDb.open(config)) {
+            log.append(key, "12345".getBytes(StandardCharsets.UTF_8));
+
+            Map<String, Long> stats = log.stats();
+            // Stored values carry an 8-byte timestamp header
+            assertThat(stats.get("memory.tail_cache_bytes")).isEqualTo(key.length + 8 + 5L);
+            assertThat(stats.get("memory.in_flight_bytes")).isZero();
+            assertThat(stats.get("memory.total_bytes")).isEqualTo(key.length + 8 + 5L);
+        }
+    }
+
+    @Test
+    void shouldUpdateConfigWithoutReopening() {
+        byte[] key = "update-config-key".getBytes(StandardCharsets.UTF_8);
+        var config = LogDbConfig.inMemory().withTailCacheBytes(1 << 20);
+
+        try (LogDb log = LogDb.open(config)) {
+            log.append(key, "12345".getBytes(StandardCharsets.UTF_8));
+            assertThat(log.stats().get("tail_cache.bytes")).isPositive();
+
+            log.updateConfig(ConfigUpdate.NONE.withTailCacheBytes(0).withFlushIntervalMs(50));
+
+            assertThat(log.stats().get("tail_cache.bytes")).isZero();
+            AppendResult result = log.append(key, "67890".getBytes(StandardCharsets.UTF_8));
+            assertThat(log.scan(key, result.sequence(), 10)).hasSize(1);
+            assertThatThrownBy(() -> log.updateConfig(ConfigUpdate.NONE.withRateLimits(RateLimitConfig.UNLIMITED)))
+                    .isInstanceOf(IllegalStateException.class)
+                    .hasMessageContaining("rate limits");
+        }
+    }
+
+    @Test
+    void shouldStoreRawValuesWithoutEnvelope() {
+        byte[] key = "raw-key".getBytes(StandardCharsets.UTF_8);
+        byte[] value = "as-is".getBytes(StandardCharsets.UTF_8);
+
+        try (LogDb log = LogDb.open(LogDbConfig.inMemory())) {
+            AppendResult raw = log.appendRaw(new Record[]{new Record(key, value)}, AckMode.BUFFERED);
+            log.append(key, value);
+
+            List<LogEntry> entries = log.scanRaw(key, 0, 10);
+
+            assertThat(raw.timestamp()).isZero();
+            assertThat(entries).hasSize(2);
+            assertThat(entries.get(0).value()).isEqualTo(value);
+            assertThat(entries.get(0).timestamp()).isZero();
+            // The enveloped value carries its 8-byte timestamp header
+            assertThat(entries.get(1).value()).hasSize(8 + value.length);
+        }
+    }
+
+    @Test
+    void shouldStreamScanBatchesToListener() throws Exception {
+        byte[] key = "async-scan-key".getBytes(StandardCharsets.UTF_8);
+
+        try (LogDb log = LogDb.open(LogDbConfig.inMemory())) {
+            for (int i = 0; i < 5; i++) {
+                log.append(key, new byte[]{(byte) i});
+            }
+            List<Integer> batchSizes = Collections.synchronizedList(new ArrayList<>());
+            CompletableFuture<Long> done = new CompletableFuture<>();
+
+            log.scanAsync(key, 0, 4, 3, new ScanListener() {
+                @Override
+                public boolean onBatch(List<LogEntry> entries) {
+                    batchSizes.add(entries.size());
+                    return true;
+                }
+
+                @Override
+                public void onComplete(long nextSequence) {
+                    done.complete(nextSequence);
+                }
+
+                @Override
+                public void onError(OpenDataNativeException error) {
+                    done.completeExceptionally(error);
+                }
+            });
+
+            assertThat(done.get(10, TimeUnit.SECONDS)).isEqualTo(4L);
+            assertThat(batchSizes).containsExactly(3, 1);
+        }
+    }
+
+    @Test
+    void shouldReportTrafficSincePreviousInterval() {
+        byte[] key = "interval-key".getBytes(StandardCharsets.UTF_8);
+
+        try (LogDb log = LogDb.open(LogDbConfig.inMemory())) {
+            log.append(new Record[]{new Record(key, new byte[10]), new Record(key, new byte[10])});
+            log.scan(key, 0, 10);
+
+            Map<String, Long> first = log.intervalStats();
+            Map<String, Long> second = log.intervalStats();
+
+            assertThat(first.get("records_in")).isEqualTo(2L);
+            assertThat(first.get("records_out")).isEqualTo(2L);
+            assertThat(first.get("bytes_in")).isGreaterThanOrEqualTo(2L * (key.length + 10));
+            assertThat(second.get("records_in")).isZero();
+            assertThat(second.get("bytes_out_per_sec")).isZero();
+        }
+    }
+
+    @Test
+    void shouldReportHottestKeysFirst() {
+        byte[] hot = "hot-key".getBytes(StandardCharsets.UTF_8);
+        byte[] cold = "cold-key".getBytes(StandardCharsets.UTF_8);
+
+        try (LogDb log = LogDb.open(LogDbConfig.inMemory().withKeyStatsCapacity(16))) {
+            log.append(cold, new byte[4]);
+            for (int i = 0; i < 3; i++) {
+                log.append(hot, new byte[4]);
+            }
+            log.scan(hot, 0, 10);
+
+            List<KeyStats> keys = log.hotKeys(10);
+
+            assertThat(keys).hasSize(2);
+            assertThat(keys.get(0).key()).isEqualTo(hot);
+            assertThat(keys.get(0).recordsAppended()).isEqualTo(3);
+            assertThat(keys.get(0).entriesScanned()).isEqualTo(3);
+            assertThat(keys.get(0).lastScanMs()).isPositive();
+            assertThat(keys.get(1).key()).isEqualTo(cold);
+            assertThat(log.stats().get("key_stats.keys")).isEqualTo(2L);
+        }
+    }
+
+    @Test
+    void shouldAcceptTraceparentAndRejectMalformedOne() {
+        byte[] key = "traced-key".getBytes(StandardCharsets.UTF_8);
+        String traceparent = "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01";
+        Record[] records = {new Record(key, new byte[1])};
+
+        try (LogDb log = LogDb.open(LogDbConfig.inMemory())) {
+            log.append(records, AckMode.BUFFERED, OperationPriority.FOREGROUND, traceparent);
+
+            List<LogEntry> entries = log.scan(key, 0, 10, OperationPriority.FOREGROUND, traceparent);
+
+            assertThat(entries).hasSize(1);
+            assertThatThrownBy(() -> log.append(
+                    records, AckMode.BUFFERED, OperationPriority.FOREGROUND, "not-a-traceparent"))
+                    .isInstanceOf(IllegalArgumentException.class)
+                    .hasMessageContaining("traceparent");
+        }
+    }
+
+    @Test
+    void shouldSendStatsToStatsdAgent() throws IOException {
+        try (DatagramSocket agent = new DatagramSocket(0, InetAddress.getLoopbackAddress())) {
+            agent.setSoTimeout(10_000);
+            var statsd = StatsdConfig.to("127.0.0.1", agent.getLocalPort())
+                    .withIntervalMs(50)
+                    .withDogStatsd(true);
+            var config = LogDbConfig.inMemory().withTags(Map.of("topic", "orders")).withStatsd(statsd);
+
+            try (LogDb log = LogDb.open(config)) {
+                log.append("statsd-key".getBytes(StandardCharsets.UTF_8), new byte[1]);
+
+                DatagramPacket packet = new DatagramPacket(new byte[2048], 2048);
+                agent.receive(packet);
+
+                String lines = new String(packet.getData(), 0, packet.getLength(), StandardCharsets.UTF_8);
+                assertThat(lines).contains("opendata.log.uptime_nanos:").contains("|g|#topic:orders");
+            }
+        }
+    }
+
+    @Test
+    void shouldSnapshotTypedMetrics() {
+        byte[] key = "metrics-key".getBytes(StandardCharsets.UTF_8);
+        var config = LogDbConfig.inMemory().withTags(Map.of("topic", "orders"));
+
+        try (LogDb log = LogDb.open(config)) {
+            log.append(key, new byte[1]);
+            log.scan(key, 0, 10);
+
+            MetricsSnapshot snapshot = log.metricsSnapshot();
+
+            assertThat(snapshot.tags()).containsExactly(Map.entry("topic", "orders"));
+            assertThat(snapshot.value("append.latency.count")).isEqualTo(1L);
+            assertThat(snapshot.metrics())
+                    .contains(new NativeMetric("append.latency.count", MetricType.COUNTER, 1))
+                    .anySatisfy(metric -> {
+                        assertThat(metric.name()).isEqualTo("uptime_nanos");
+                        assertThat(metric.type()).isEqualTo(MetricType.GAUGE);
+                    })
+                    .noneSatisfy(metric -> assertThat(metric.name()).contains("{"));
+        }
+    }
+
+    @Test
+    void shouldReportPendingOpsUntilCompletionsArePolled() throws InterruptedException {
+        byte[] key = "pending-key".getBytes(StandardCharsets.UTF_8);
+
+        try (LogDb log = LogDb.open(LogDbConfig.inMemory())) {
+            PendingOps idle = log.pendingOps();
+            log.submitAppend(new Record[]{new Record(key, new byte[10])}, AckMode.BUFFERED);
+            long deadline = System.nanoTime() + TimeUnit.SECONDS.toNanos(5);
+            while (log.pendingOps().unpolledCompletions() == 0 && System.nanoTime() < deadline) {
+                Thread.sleep(10);
+            }
+
+            PendingOps completed = log.pendingOps();
+            assertThat(log.pollCompletions(1, 5_000)).hasSize(1);
+
+            assertThat(idle).isEqualTo(new PendingOps(0, 0, 0, 0, 0, 0));
+            assertThat(completed.unpolledCompletions()).isEqualTo(1);
+            assertThat(completed.inFlight()).isZero();
+            assertThat(log.pendingOps()).isEqualTo(idle);
+        }
+    }
+
+    @Test
+    void shouldRunBlockingCallsUnderCallLimit() {
+        byte[] key = "call-limit-key".getBytes(StandardCharsets.UTF_8);
+        var config = LogDbConfig.inMemory()
+                .withCallLimit(CallLimitConfig.of(1).withPolicy(OverloadPolicy.REJECT));
+
+        try (LogDb log = LogDb.open(config)) {
+            log.append(key, new byte[1]);
+            log.flush();
+
+            List<LogEntry> entries = log.scan(key, 0, 10);
+
+            assertThat(entries).hasSize(1);
+            Map<String, Long> stats = log.stats();
+            assertThat(stats.get("call_limit.max_calls")).isEqualTo(1L);
+            assertThat(stats.get("call_limit.running")).isZero();
+            assertThat(stats.get("call_limit.rejected")).isZero();
+        }
+    }
+
+    @Test
+    void shouldScanOnCurrentThreadRuntime(@TempDir Path tempDir) {
+        var storage = new StorageConfig.SlateDb(
+                "current-thread-test",
+                new ObjectStoreConfig.Local(tempDir.toString())
+        );
+        byte[] key = "current-thread-key".getBytes(StandardCharsets.UTF_8);
+
+        try (LogDb writer = LogDb.open(new LogDbConfig(storage))) {
+            for (int i = 0; i < 20; i++) {
+                writer.append(key, ("value-" + i).getBytes(StandardCharsets.UTF_8));
+            }
+            writer.flush();
+        }
+
+        var readerConfig = new LogDbReaderConfig(storage)
+                .withReadAhead(true)
+                .withRuntimeMode(RuntimeMode.CURRENT_THREAD);
+        try (LogDbReader reader = LogDbReader.open(readerConfig)) {
+            List<LogEntry> first = reader.scan(key, 0, 10);
+            List<LogEntry> second = reader.scan(key, 10, 10);
+
+            assertThat(first).hasSize(10);
+            assertThat(second).extracting(LogEntry::sequence).startsWith(10L).hasSize(10);
+            assertThat(reader.stats().get("runtime.workers")).isEqualTo(1L);
+        }
+    }
+
+    @Test
+    void shouldRunBackgroundCallsBesideForegroundOnes() {
+        byte[] key = "priority-key".getBytes(StandardCharsets.UTF_8);
+        var config = LogDbConfig.inMemory().withBackgroundConcurrency(1);
+
+        try (LogDb log = LogDb.open(config)) {
+            log.append(new Record[]{new Record(key, new byte[1])}, AckMode.BUFFERED, OperationPriority.BACKGROUND);
+            log.append(key, new byte[1]);
+
+            List<LogEntry> entries = log.scan(key, 0, 10, OperationPriority.BACKGROUND);
+
+            assertThat(entries).hasSize(2);
+            Map<String, Long> stats = log.stats();
+            assertThat(stats.get("priority.background_in_flight")).isZero();
+            assertThat(stats).containsKey("priority.background_waits");
+        }
+    }
+
+    @Test
+    void shouldRejectAppendsPastMemoryLimit() {
+        byte[] key = "memory-limit-key".getBytes(StandardCharsets.UTF_8);
+        var config = LogDbConfig.inMemory()
+                .withRateLimits(RateLimitConfig.UNLIMITED.withProduce(1, 0))
+                .withMemoryLimit(MemoryLimitConfig.of(100).withPolicy(BackpressurePolicy.REJECT));
+
+        try (LogDb log = LogDb.open(config)) {
+            // Takes the only token, so the next append waits a second in flight
+            log.submitAppend(new Record[]{new Record(key, new byte[10])}, AckMode.BUFFERED);
+            assertThat(log.pollCompletions(1, 5_000)).hasSize(1);
+            log.submitAppend(new Record[]{new Record(key, new byte[60])}, AckMode.BUFFERED);
+
+            assertThatThrownBy(() -> log.submitAppend(new Record[]{new Record(key, new byte[10])}, AckMode.BUFFERED))
+                    .isInstanceOf(OpenDataBackpressureException.class)
+                    .hasMessageContaining("memory limit");
+            assertThat(log.stats().get("memory.refused")).isEqualTo(1L);
+        }
+    }
+
+    @Test
+    void shouldReportDiskCacheOfSlateDbReads(@TempDir Path tempDir) {
+        var storage = new StorageConfig.SlateDb(
+                "disk-cache-test",
+                new ObjectStoreConfig.Local(tempDir.resolve("store").toString())
+        ).withDiskCache(new DiskCacheConfig(tempDir.resolve("cache").toString(), 64L << 20));
+        byte[] key = "disk-cache-key".getBytes(StandardCharsets.UTF_8);
+
+        try (LogDb writer = LogDb.open(new LogDbConfig(storage))) {
+            writer.append(key, "value".getBytes(StandardCharsets.UTF_8));
+            writer.flush();
+        }
+
+        try (LogDbReader reader = LogDbReader.open(new LogDbReaderConfig(storage))) {
+            assertThat(reader.scan(key, 0, 10)).hasSize(1);
+            assertThat(reader.stats()).containsKeys(
+                    "disk_cache.bytes", "disk_cache.parts", "disk_cache.parts_added");
+        }
+    }
+
+    @Test
+    void shouldReadOwnWritesThroughPairedReader(@TempDir Path tempDir) {
+        var storage = new StorageConfig.SlateDb(
+                "read-your-writes-test",
+                new ObjectStoreConfig.Local(tempDir.toString())
+        );
+        byte[] key = "read-your-writes-key".getBytes(StandardCharsets.UTF_8);
+
+        try (LogDb writer = LogDb.open(new LogDbConfig(storage));
+             LogDbReader reader = LogDbReader.open(new LogDbReaderConfig(storage))) {
+            assertThatThrownBy(() -> reader.scan(key, 0, 10, ReadConsistency.READ_YOUR_WRITES))
+                    .isInstanceOf(IllegalStateException.class)
+                    .hasMessageContaining("No writer is paired");
+            reader.pairWriter(writer);
+            AppendResult result = writer.append(key, "mine".getBytes(StandardCharsets.UTF_8));
+
+            List<LogEntry> entries = reader.scan(key, 0, 10, ReadConsistency.READ_YOUR_WRITES);
+
+            assertThat(entries).extracting(LogEntry::sequence).containsExactly(result.sequence());
+        }
+    }
+
+    @Test
+    void shouldScanSameCutOfLogThroughSnapshot(@TempDir Path tempDir) {
+        var storage = new StorageConfig.SlateDb(
+                "snapshot-test",
+                new ObjectStoreConfig.Local(tempDir.toString())
+        );
+        byte[] orders = "snapshot-orders".getBytes(StandardCharsets.UTF_8);
+        byte[] payments = "snapshot-payments".getBytes(StandardCharsets.UTF_8);
+
+        try (LogDb writer = LogDb.open(new LogDbConfig(storage))) {
+            writer.append(orders, new byte[1]);
+            writer.append(payments, new byte[1], AckMode.DURABLE);
+            try (LogDbReader reader = LogDbReader.open(new LogDbReaderConfig(storage));
+                 ReaderSnapshot snapshot = reader.beginSnapshot()) {
+                writer.append(orders, new byte[1]);
+                writer.append(payments, new byte[1], AckMode.DURABLE);
+                reader.refresh();
+
+                assertThat(reader.scan(orders, 0, 10)).hasSize(2);
+                assertThat(snapshot.scan(orders, 0, 10)).hasSize(1);
+                assertThat(snapshot.scan(payments, 0, 10)).hasSize(1);
+                assertThat(reader.stats().get("snapshots.open")).isEqualTo(1L);
+            }
+        }
+    }
+
+    @Test
+    void shouldRejectAttachingWriterWithoutTailCache() {
+        try (LogDb writer = LogDb.open(LogDbConfig.inMemory());
+             LogDbReader reader = LogDbReader.open(new LogDbReaderConfig(new StorageConfig.InMemory()))) {
+            assertThatThrownBy(() -> reader.attachTailCache(writer))
+                    .isInstanceOf(IllegalArgumentException.class)
+                    .hasMessageContaining("no tail cache");
+        }
+    }
+
+    @Test
+    void shouldAutoCommitScannedPositions(@TempDir Path tempDir) throws InterruptedException {
+        var storage = new StorageConfig.SlateDb(
+                "auto-commit-test",
+                new ObjectStoreConfig.Local(tempDir.toString())
+        );
+        byte[] key = "auto-commit-key".getBytes(StandardCharsets.UTF_8);
+        var readerConfig = new LogDbReaderConfig(storage, 100L)
+                .withAutoCommit(new AutoCommitConfig("group", 50));
+
+        try (LogDb writer = LogDb.open(new LogDbConfig(storage))) {
+            writer.append(key, "v0".getBytes(StandardCharsets.UTF_8));
+            writer.append(key, "v1".getBytes(StandardCharsets.UTF_8));
+            writer.flush();
+
+            try (LogDbReader reader = LogDbReader.open(readerConfig)) {
+                reader.attachOffsetWriter(writer);
+                assertThat(reader.scan(key, 0, 10)).hasSize(2);
+
+                long deadline = System.currentTimeMillis() + 5_000;
+                while (writer.fetchOffset("group", key).isEmpty()
+                        && System.currentTimeMillis() < deadline) {
+                    Thread.sleep(20);
+                }
+
+                assertThat(writer.fetchOffset("group", key)).hasValue(2);
+            }
+        }
+    }
+
+    @Test
+    void shouldCommitPendingPositionsOnClose(@TempDir Path tempDir) {
+        var storage = new StorageConfig.SlateDb(
+                "auto-commit-close-test",
+                new ObjectStoreConfig.Local(tempDir.toString())
+        );
+        byte[] key = "auto-commit-close-key".getBytes(StandardCharsets.UTF_8);
+        var readerConfig = new LogDbReaderConfig(storage)
+                .withAutoCommit(new AutoCommitConfig("group", 3_600_000));
+
+        try (LogDb writer = LogDb.open(new LogDbConfig(storage))) {
+            writer.append(key, "v0".getBytes(StandardCharsets.UTF_8));
+            writer.flush();
+
+            try (LogDbReader reader = LogDbReader.open(readerConfig)) {
+                reader.attachOffsetWriter(writer);
+                assertThat(reader.scan(key, 0, 10)).hasSize(1);
+            }
+
+            assertThat(writer.fetchOffset("group", key)).hasValue(1);
+        }
+    }
+
+    @Test
+    void shouldPollFromTrackedPosition(@TempDir Path tempDir) {
+        var storage = new StorageConfig.SlateDb(
+                "poll-test",
+                new ObjectStoreConfig.Local(tempDir.toString())
+        );
+        byte[] key = "poll-key".getBytes(StandardCharsets.UTF_8);
+
+        try (LogDb writer = LogDb.open(new LogDbConfig(storage))) {
+            for (int i = 0; i < 5; i++) {
+                writer.append(key, ("value-" + i).getBytes(StandardCharsets.UTF_8));
+            }
+        }
+
+        try (LogDbReader reader = LogDbReader.open(new LogDbReaderConfig(storage))) {
+            List<LogEntry> first = reader.poll(key, 3);
+            List<LogEntry> second = reader.poll(key, 3);
+
+            assertThat(first).extracting(LogEntry::sequence).containsExactly(0L, 1L, 2L);
+            assertThat(second).extracting(LogEntry::sequence).containsExactly(3L, 4L);
+            assertThat(reader.poll(key, 3)).isEmpty();
+            assertThat(reader.position(key)).isEqualTo(5);
+
+            reader.seek(key, 1);
+
+            assertThat(reader.poll(key, 1)).extracting(LogEntry::sequence).containsExactly(1L);
+        }
+    }
+
+    @Test
+    void shouldOpenIsolatedLogsThroughManager(@TempDir Path tempDir) {
+        var storage = new StorageConfig.SlateDb(
+                "managed",
+                new ObjectStoreConfig.Local(tempDir.toString())
+        );
+        byte[] key = "topic-key".getBytes(StandardCharsets.UTF_8);
+
+        try (LogDbManager manager = LogDbManager.open(new LogDbConfig(storage))) {
+            manager.createTopic("orders");
+            manager.createTopic("payments");
+            LogDb orders = manager.openLog("orders");
+            LogDb payments = manager.openLog("payments");
+
+            orders.append(key, "order".getBytes(StandardCharsets.UTF_8));
+
+            assertThat(orders.scan(key, 0, 10)).hasSize(1);
+            assertThat(payments.scan(key, 0, 10)).isEmpty();
+            assertThatThrownBy(() -> manager.openLog("orders"))
+                    .isInstanceOf(IllegalStateException.class)
+                    .hasMessageContaining("already open");
+            assertThatThrownBy(() -> manager.openLog("unknown"))
+                    .isInstanceOf(IllegalArgumentException.class)
+                    .hasMessageContaining("does not exist");
+
+            orders.close();
+            try (LogDb reopened = manager.openLog("orders")) {
+                assertThat(reopened.scan(key, 0, 10)).hasSize(1);
+            }
+        }
+    }
+
+    @Test
+    void shouldApplyTopicConfigOverrides(@TempDir Path tempDir) throws Exception {
+        var storage = new StorageConfig.SlateDb(
+                "overrides",
+                new ObjectStoreConfig.Local(tempDir.toString())
+        );
+        var topicConfigs = Map.of("events", TopicConfig.DEFAULT.withFlushIntervalMs(10L));
+        byte[] key = "event-key".getBytes(StandardCharsets.UTF_8);
+
+        try (LogDbManager manager = LogDbManager.open(new LogDbConfig(storage), topicConfigs)) {
+            manager.createTopic("events");
+            try (LogDb events = manager.openLog("events")) {
+                events.append(key, "event".getBytes(StandardCharsets.UTF_8));
+                Thread.sleep(50);
+
+                assertThat(events.scan(key, 0, 10)).hasSize(1);
+            }
+        }
+    }
+
+    @Test
+    void shouldAdministerTopicsThroughManager(@TempDir Path tempDir) {
+        var storage = new StorageConfig.SlateDb(
+                "admin",
+                new ObjectStoreConfig.Local(tempDir.toString())
+        );
+        var config = new LogDbConfig(storage);
+        byte[] key = "topic-key".getBytes(StandardCharsets.UTF_8);
+
+        try (LogDbManager manager = LogDbManager.open(config)) {
+            manager.createTopic("orders");
+            manager.createTopic("audit");
+            try (LogDb audit = manager.openLog("audit")) {
+                audit.append(key, "entry".getBytes(StandardCharsets.UTF_8));
+                assertThatThrownBy(() -> manager.deleteTopic("audit"))
+                        .isInstanceOf(IllegalStateException.class);
+            }
+            manager.deleteTopic("audit");
+            assertThatThrownBy(() -> manager.createTopic("orders"))
+                    .isInstanceOf(IllegalArgumentException.class)
+                    .hasMessageContaining("already exists");
+        }
+
+        try (LogDbManager manager = LogDbManager.open(config)) {
+            assertThat(manager.listTopics()).containsExactly("orders");
+
+            manager.createTopic("audit");
+            try (LogDb audit = manager.openLog("audit")) {
+                assertThat(audit.scan(key, 0, 10)).isEmpty();
+            }
+        }
+    }
+
+    @Test
+    void shouldNotPinClosedLogsOfManager() throws InterruptedException {
+        try (LogDbManager manager = LogDbManager.open(LogDbConfig.inMemory())) {
+            // given
+            manager.createTopic("events");
+            LogDb log = manager.openLog("events");
+            WeakReference<LogDb> collected = new WeakReference<>(log);
+            log.close();
+            log = null;
+
+            // when
+            for (int i = 0; i < 50 && collected.get() != null; i++) {
+                System.gc();
+                Thread.sleep(10);
+            }
+
+            // then
+            assertThat(collected.get()).isNull();
+            manager.openLog("events").close();
+        }
+    }
+
+    @Test
+    void shouldSeeConcurrentWritesAfterRefresh(@TempDir Path tempDir) {
+        var storage = new StorageConfig.SlateDb(
+                "refresh-now-test",
+                new ObjectStoreConfig.Local(tempDir.toString())
+        );
+        byte[] key = "refresh-now-key".getBytes(StandardCharsets.UTF_8);
+
+        try (LogDb writer = LogDb.open(new LogDbConfig(storage));
+             LogDbReader reader = LogDbReader.open(new LogDbReaderConfig(storage, 60_000L))) {
+            writer.append(key, "value".getBytes(StandardCharsets.UTF_8), AckMode.DURABLE);
+
+            reader.refresh();
+
+            assertThat(reader.scan(key, 0, 10)).hasSize(1);
+        }
+    }
+
+    @Test
+    void shouldWaitForVisibleSequence(@TempDir Path tempDir) {
+        var storage = new StorageConfig.SlateDb(
+                "wait-test",
+                new ObjectStoreConfig.Local(tempDir.toString())
+        );
+        byte[] key = "wait-key".getBytes(StandardCharsets.UTF_8);
+        long sequence;
+
+        try (LogDb writer = LogDb.open(new LogDbConfig(storage))) {
+            sequence = writer.append(key, "value".getBytes(StandardCharsets.UTF_8)).sequence();
+        }
+
+        try (LogDbReader reader = LogDbReader.open(new LogDbReaderConfig(storage))) {
+            assertThat(reader.waitForSequence(key, sequence, 5_000)).isTrue();
+            assertThat(reader.waitForSequence(key, sequence + 1, 50)).isFalse();
+        }
+    }
+
+    @Test
+    void shouldReportReaderLag(@TempDir Path tempDir) {
+        var storage = new StorageConfig.SlateDb(
+                "lag-test",
+                new ObjectStoreConfig.Local(tempDir.toString())
+        );
+        byte[] key = "lag-key".getBytes(StandardCharsets.UTF_8);
+
+        try (LogDb writer = LogDb.open(new LogDbConfig(storage))) {
+            for (int i = 0; i < 4; i++) {
+                writer.append(key, ("value-" + i).getBytes(StandardCharsets.UTF_8));
+            }
+            writer.commitOffset("group", key, 1);
+        }
+
+        try (LogDbReader reader = LogDbReader.open(new LogDbReaderConfig(storage))) {
+            assertThat(reader.lag(key)).isEqualTo(4);
+            assertThat(reader.lag("group", key)).isEqualTo(3);
+
+            reader.poll(key, 3);
+
+            assertThat(reader.lag(key)).isEqualTo(1);
+            assertThat(reader.lag("unknown-group", key)).isEqualTo(4);
+        }
+    }
+
+    @Test
+    void shouldAppendSyntheticPayloads() {
+        try (LogDb log = LogDb.openInMemory()) {
+            byte[] key = "synthetic-key".getBytes(StandardCharsets.UTF_8);
+
+            AppendResult result = log.appendSynthetic(key, 128, 3);
+
+            List<LogEntry> entries = log.scan(key, 0, 10);
+            assertThat(entries).hasSize(3);
+            assertThat(entries.get(0).sequence()).isEqualTo(result.sequence());
+            assertThat(entries).allSatisfy(entry -> {
+                assertThat(entry.value()).hasSize(128);
+                assertThat(entry.timestamp()).isEqualTo(result.timestamp());
+            });
+        }
+    }
+
+    @Test
+    void shouldRunNativeLoopbackBenchmark() {
+        try (LogDb log = LogDb.openInMemory()) {
+            BenchReport report = log.bench(new BenchConfig(64, 10, 5));
+
+            Histogram endToEnd = Histogram.decodeFromByteBuffer(
+                    ByteBuffer.wrap(report.endToEndLatencies()), 0);
+            Histogram appends = Histogram.decodeFromByteBuffer(
+                    ByteBuffer.wrap(report.appendLatencies()), 0);
+            assertThat(report.records()).isEqualTo(50);
+            assertThat(report.bytes()).isEqualTo(50 * 64);
+            assertThat(report.recordsPerSecond()).isPositive();
+            assertThat(endToEnd.getTotalCount()).isEqualTo(50);
+            assertThat(appends.getTotalCount()).isEqualTo(5);
+        }
+    }
+
+    @Test
+    void shouldShareKeysBetweenGroupMembers() {
+        List<byte[]> keys = List.of(
+                "p-0".getBytes(StandardCharsets.UTF_8),
+                "p-1".getBytes(StandardCharsets.UTF_8),
+                "p-2".getBytes(StandardCharsets.UTF_8),
+                "p-3".getBytes(StandardCharsets.UTF_8));
+        List<byte[]> revoked = new ArrayList<>();
+        var listener = new RebalanceListener() {
+            @Override
+            public void onKeysRevoked(List<byte[]> keys) {
+                revoked.addAll(keys);
+            }
+
+            @Override
+            public void onKeysAssigned(List<byte[]> keys) {
+            }
+        };
+
+        try (LogDb log = LogDb.openInMemory();
+             GroupMember first = GroupMember.join(log, "group", "member-1", keys, listener)) {
+            assertThat(first.assignment()).hasSize(4);
+
+            try (GroupMember second = GroupMember.join(log, "group", "member-2", keys, listener)) {
+                assertThat(second.assignment()).hasSize(2);
+                assertThat(first.poll()).hasSize(2);
+                assertThat(revoked).hasSize(2);
+            }
+
+            assertThat(first.poll()).hasSize(4);
+        }
+    }
+
+    @Test
+    void shouldRouteRecordsToTopicPartitions() {
+        try (LogDb log = LogDb.openInMemory()) {
+            byte[] key = "customer-42".getBytes(StandardCharsets.UTF_8);
+            int partition = LogDb.partitionForKey(key, 8);
+
+            log.appendPartitioned("orders",
+                    new Record[]{new Record(key, "order".getBytes(StandardCharsets.UTF_8))}, 8);
+
+            byte[] partitionKey = ("orders-" + partition).getBytes(StandardCharsets.UTF_8);
+            List<LogEntry> entries = log.scan(partitionKey, 0, 10);
+            assertThat(partition).isBetween(0, 7);
+            assertThat(entries).hasSize(1);
+            assertThat(new String(entries.get(0).value(), StandardCharsets.UTF_8)).isEqualTo("order");
+        }
+    }
+
+    @Test
+    void shouldSeekToTimestamp(@TempDir Path tempDir) {
+        var storage = new StorageConfig.SlateDb(
+                "seek-timestamp-test",
+                new ObjectStoreConfig.Local(tempDir.toString())
+        );
+        byte[] key = "seek-timestamp-key".getBytes(StandardCharsets.UTF_8);
+
+        try (LogDb writer = LogDb.open(new LogDbConfig(storage))) {
+            writer.append(new Record[]{
+                    new Record(key, "old".getBytes(StandardCharsets.UTF_8), 1_000),
+                    new Record(key, "mid".getBytes(StandardCharsets.UTF_8), 2_000),
+                    new Record(key, "new".getBytes(StandardCharsets.UTF_8), 3_000)
+            });
+        }
+
+        try (LogDbReader reader = LogDbReader.open(new LogDbReaderConfig(storage))) {
+            assertThat(reader.seekToTimestamp(key, 1_500)).isEqualTo(1);
+            assertThat(reader.poll(key, 10)).extracting(LogEntry::timestamp).containsExactly(2_000L, 3_000L);
+
+            assertThat(reader.seekToTimestamp(key, 9_000)).isEqualTo(3);
+            assertThat(reader.poll(key, 10)).isEmpty();
+        }
+    }
+
+    @Test
+    void shouldAppendIfKeyHasNotAdvanced() {
+        try (LogDb log = LogDb.openInMemory()) {
+            byte[] key = "cas-key".getBytes(StandardCharsets.UTF_8);
+            Record[] first = {new Record(key, "v0".getBytes(StandardCharsets.UTF_8))};
+            Record[] second = {new Record(key, "v1".getBytes(StandardCharsets.UTF_8))};
+
+            var r0 = log.appendIf(key, first, -1);
+            var r1 = log.appendIf(key, second, r0.sequence());
+
+            assertThat(r1.sequence()).isGreaterThan(r0.sequence());
+            assertThat(log.scan(key, 0, 10)).hasSize(2);
+        }
+    }
+
+    @Test
+    void shouldRejectAppendIfKeyAdvanced() {
+        try (LogDb log = LogDb.openInMemory()) {
+            byte[] key = "cas-conflict-key".getBytes(StandardCharsets.UTF_8);
+            var r0 = log.append(key, "v0".getBytes(StandardCharsets.UTF_8));
+            var r1 = log.append(key, "v1".getBytes(StandardCharsets.UTF_8));
+            Record[] stale = {new Record(key, "stale".getBytes(StandardCharsets.UTF_8))};
+
+            assertThatThrownBy(() -> log.appendIf(key, stale, r0.sequence()))
+                    .isInstanceOfSatisfying(SequenceConflictException.class, e -> {
+                        assertThat(e.expectedTailSequence()).isEqualTo(r0.sequence());
+                        assertThat(e.actualSequence()).isEqualTo(r1.sequence());
+                    });
+            assertThat(log.scan(key, 0, 10)).hasSize(2);
+        }
+    }
+
+    @Test
+    void shouldIgnoreOtherKeysWhenAppendingIf() {
+        try (LogDb log = LogDb.openInMemory()) {
+            byte[] key = "cas-own-key".getBytes(StandardCharsets.UTF_8);
+            log.append("other-key".getBytes(StandardCharsets.UTF_8), "v".getBytes(StandardCharsets.UTF_8));
+            Record[] records = {new Record(key, "v0".getBytes(StandardCharsets.UTF_8))};
+
+            var result = log.appendIf(key, records, -1);
+
+            assertThat(result.sequence()).isEqualTo(1);
+        }
+    }
+
+    @Test
+    void shouldRejectNullAckMode() {
+        try (LogDb log = LogDb.openInMemory()) {
+            byte[] key = "key".getBytes(StandardCharsets.UTF_8);
+
+            assertThatThrownBy(() -> log.append(key, new byte[0], null))
+                    .isInstanceOf(IllegalArgumentException.class)
+                    .hasMessageContaining("ackMode");
+        }
+    }
+
+    @Test
+    void shouldCoexistWriterAndReaderWithoutFencingError(@TempDir Path tempDir) {
+        var storage = new StorageConfig.SlateDb(
+                "concurrent-test",
+                new ObjectStoreConfig.Local(tempDir.toString())
+        );
+        var writerConfig = new LogDbConfig(storage);
+        var readerConfig = new LogDbReaderConfig(storage);
+
+        byte[] key = "concurrent-key".getBytes(StandardCharsets.UTF_8);
+
+        // Open writer and keep it open
+        try (LogDb writer = LogDb.open(writerConfig)) {
+            // Write initial data
+            writer.append(key, "value-0".getBytes(StandardCharsets.UTF_8));
+
+            // Open reader while writer is still open - this should NOT cause fencing error
+            try (LogDbReader reader = LogDbReader.open(readerConfig)) {
+                // Reader can read the data written by writer
+                List<LogEntry> entries = reader.scan(key, 0, 10);
+                assertThat(entries).hasSize(1);
+                assertThat(new String(entries.get(0).value(), StandardCharsets.UTF_8)).isEqualTo("value-0");
+
+                // Writer can still write more data while reader is open
+                writer.append(key, "value-1".getBytes(StandardCharsets.UTF_8));
+                writer.append(key, "value-2".getBytes(StandardCharsets.UTF_8));
+            }
+
+            // After reader closes, writer should still work
+            writer.append(key, "value-3".getBytes(StandardCharsets.UTF_8));
+
+            List<LogEntry> finalEntries = writer.scan(key, 0, 10);
+            assertThat(finalEntries).hasSize(4);
+        }
+    }
+
+    @Test
+    void shouldOpenReaderWithCustomRefreshInterval(@TempDir Path tempDir) {
+        var storage = new StorageConfig.SlateDb(
+                "custom-refresh-test",
+                new ObjectStoreConfig.Local(tempDir.toString())
+        );
+        var writerConfig = new LogDbConfig(storage);
+        // Use a custom refresh interval of 500ms
+        var readerConfig = new LogDbReaderConfig(storage, 500L);
+
+        byte[] key = "refresh-key".getBytes(StandardCharsets.UTF_8);
+
+        // Write with LogDb
+        try (LogDb writer = LogDb.open(writerConfig)) {
+            writer.append(key, "value-0".getBytes(StandardCharsets.UTF_8));
+        }
+
+        // Read with LogDbReader using custom refresh interval
+        try (LogDbReader reader = LogDbReader.open(readerConfig)) {
+            List<LogEntry> entries = reader.scan(key, 0, 10);
+            assertThat(entries).hasSize(1);
+            assertThat(new String(entries.get(0).value(), StandardCharsets.UTF_8)).isEqualTo("value-0");
+            assertThat(reader.stats().get("refresh_interval_ms")).isEqualTo(500L);
+        }
+    }
+
+    @Test
+    void shouldRecordNativeLatencyHistograms() throws Exception {
+        try (LogDb log = LogDb.openInMemory()) {
+            byte[] key = "latency-key".getBytes(StandardCharsets.UTF_8);
+            log.append(key, "value-0".getBytes(StandardCharsets.UTF_8));
+            log.append(key, "value-1".getBytes(StandardCharsets.UTF_8));
+            log.scan(key, 0, 10);
+
+            Histogram appends = Histogram.decodeFromByteBuffer(
+                    ByteBuffer.wrap(log.latencyHistogram(Operation.APPEND)), 0);
+            Histogram scans = Histogram.decodeFromByteBuffer(
+                    ByteBuffer.wrap(log.latencyHistogram(Operation.SCAN)), 0);
+
+            assertThat(appends.getTotalCount()).isEqualTo(2);
+            assertThat(scans.getTotalCount()).isEqualTo(1);
+        }
+    }
+
+    @Test
+    void shouldDiscardWarmupMetrics() {
+        try (LogDb log = LogDb.open(LogDbConfig.inMemory().withProfiling(true))) {
+            byte[] key = "warmup-key".getBytes(StandardCharsets.UTF_8);
+            log.markWarmupStart();
+            log.append(key, "warmup".getBytes(StandardCharsets.UTF_8));
+            assertThat(log.stats().get("warming_up")).isEqualTo(1L);
+
+            log.markWarmupEnd();
+            log.append(key, "measured".getBytes(StandardCharsets.UTF_8));
+
+            Histogram appends = Histogram.decodeFromByteBuffer(
+                    ByteBuffer.wrap(log.latencyHistogram(Operation.APPEND)), 0);
+            assertThat(appends.getTotalCount()).isEqualTo(1);
+            assertThat(log.timingBreakdown(Operation.APPEND).calls()).isEqualTo(1);
+            assertThat(log.stats().get("warming_up")).isEqualTo(0L);
+        }
+    }
+
+    @Test
+    void shouldNotReportIdleLogAsSteady() {
+        try (LogDb log = LogDb.openInMemory()) {
+            var config = new SteadyStateConfig(10, 3, 0.5);
+
+            boolean steady = log.awaitSteadyState(Operation.APPEND, config, 100);
+
+            assertThat(steady).isFalse();
+        }
+    }
+
+    @Test
+    void shouldRetryObjectStoreRequestsFailedByInjectedFaults() {
+        var storage = new StorageConfig.SlateDb("faults-test", new ObjectStoreConfig.InMemory());
+        var faults = FaultInjection.errors(0.5).withOperations(Operation.APPEND).withSeed(7);
+        try (LogDb log = LogDb.open(new LogDbConfig(storage).withFaults(faults))) {
+            byte[] key = "fault-key".getBytes(StandardCharsets.UTF_8);
+
+            for (int i = 0; i < 5; i++) {
+                log.append(key, new byte[]{(byte) i}, AckMode.DURABLE);
+            }
+
+            assertThat(log.scan(key, 0, 10)).hasSize(5);
+            assertThat(log.stats().get("faults.injected_errors")).isPositive();
+        }
+    }
+
+    @Test
+    void shouldRetryObjectStoreRequestsRejectedAsThrottled() {
+        var storage = new StorageConfig.SlateDb("throttling-test", new ObjectStoreConfig.InMemory());
+        var faults = FaultInjection.throttling(0.5, 0.1, 50).withSeed(11);
+        try (LogDb log = LogDb.open(new LogDbConfig(storage).withFaults(faults))) {
+            byte[] key = "throttled-key".getBytes(StandardCharsets.UTF_8);
+
+            for (int i = 0; i < 5; i++) {
+                log.append(key, new byte[]{(byte) i}, AckMode.DURABLE);
+            }
+
+            assertThat(log.scan(key, 0, 10)).hasSize(5);
+            Map<String, Long> stats = log.stats();
+            assertThat(stats.get("faults.injected_throttles")).isPositive();
+            assertThat(stats.get("faults.injected_errors")).isZero();
+        }
+    }
+
+    @Test
+    void shouldRejectFaultInjectionWithoutObjectStore() {
+        var config = LogDbConfig.inMemory().withFaults(FaultInjection.errors(0.1));
+
+        assertThatThrownBy(() -> LogDb.open(config))
+                .isInstanceOf(OpenDataNativeException.class)
+                .hasMessageContaining("object store");
+    }
+
+    @Test
+    void shouldResetConsecutiveFailuresOnSuccessfulCalls() {
+        var config = LogDbConfig.inMemory().withReopen(ReopenConfig.after(3));
+        try (LogDb log = LogDb.open(config)) {
+            byte[] key = "reopen-key".getBytes(StandardCharsets.UTF_8);
+
+            log.append(key, "value".getBytes(StandardCharsets.UTF_8));
+            log.scan(key, 0, 10);
+
+            assertThat(log.stats())
+                    .containsEntry("reopen.count", 0L)
+                    .containsEntry("reopen.consecutive_failures", 0L)
+                    .containsEntry("reopen.in_progress", 0L);
+        }
+    }
+
+    @Test
+    void shouldCountOnlyOperationsReachingSlowOperationThreshold() {
+        var config = LogDbConfig.inMemory().withSlowOperationThreshold(60_000);
+        try (LogDb log = LogDb.open(config)) {
+            byte[] key = "slow-key".getBytes(StandardCharsets.UTF_8);
+
+            log.append(key, "value".getBytes(StandardCharsets.UTF_8));
+            log.scan(key, 0, 10);
+
+            assertThat(log.stats().get("slow_log.logged")).isZero();
+        }
+        try (LogDb log = LogDb.open(LogDbConfig.inMemory())) {
+            assertThat(log.stats()).doesNotContainKey("slow_log.logged");
+        }
+    }
+
+    @Test
+    void shouldReturnSyntheticEntriesFromNoOpStorage() {
+        try (LogDb log = LogDb.open(new LogDbConfig(new StorageConfig.NoOp(32)))) {
+            byte[] key = "noop-key".getBytes(StandardCharsets.UTF_8);
+            AppendResult first = log.append(key, "a".getBytes(StandardCharsets.UTF_8));
+            log.append(key, "b".getBytes(StandardCharsets.UTF_8));
+
+            List<LogEntry> entries = log.scan(key, 0, 10);
+
+            assertThat(first.sequence()).isZero();
+            assertThat(entries).extracting(LogEntry::sequence).containsExactly(0L, 1L);
+            assertThat(entries.get(0).value()).hasSize(32);
+        }
+    }
+
+    @Test
+    void shouldCoalesceConcurrentAppends() throws Exception {
+        var config = LogDbConfig.inMemory().withCoalesce(CoalesceConfig.within(20_000));
+        try (LogDb log = LogDb.open(config)) {
+            byte[] key = "coalesce-key".getBytes(StandardCharsets.UTF_8);
+            int producers = 16;
+            List<Thread> threads = new ArrayList<>();
+            List<Long> sequences = Collections.synchronizedList(new ArrayList<>());
+            for (int i = 0; i < producers; i++) {
+                byte[] value = ("value-" + i).getBytes(StandardCharsets.UTF_8);
+                Thread thread = new Thread(() -> {
+                    for (int j = 0; j < 10; j++) {
+                        sequences.add(log.append(key, value).sequence());
+                    }
+                });
+                thread.start();
+                threads.add(thread);
+            }
+            for (Thread thread : threads) {
+                thread.join();
+            }
+
+            List<LogEntry> entries = log.scan(key, 0, 1_000);
+            Map<String, Long> stats = log.stats();
+
+            assertThat(entries).hasSize(producers * 10);
+            assertThat(sequences).doesNotHaveDuplicates()
+                    .containsExactlyInAnyOrderElementsOf(
+                            entries.stream().map(LogEntry::sequence).toList());
+            assertThat(stats.get("coalesce.records")).isEqualTo(producers * 10L);
+            assertThat(stats.get("coalesce.batches")).isLessThan(producers * 10L);
+        }
+    }
+
+    @Test
+    void shouldServeCallsThroughBridgeThreads() throws Exception {
+        try (LogDb log = LogDb.open(LogDbConfig.inMemory().withBridgeThreads(2))) {
+            byte[] key = "bridge-key".getBytes(StandardCharsets.UTF_8);
+            List<Thread> threads = new ArrayList<>();
+            for (int i = 0; i < 8; i++) {
+                byte[] value = ("value-" + i).getBytes(StandardCharsets.UTF_8);
+                Thread thread = new Thread(() -> log.append(key, value));
+                thread.start();
+                threads.add(thread);
+            }
+            for (Thread thread : threads) {
+                thread.join();
+            }
+            log.flush();
+
+            List<LogEntry> entries = log.scan(key, 0, 100);
+
+            assertThat(entries).hasSize(8);
+            assertThat(entries).extracting(LogEntry::sequence).doesNotHaveDuplicates();
+        }
+    }
+
+    @Test
+    void shouldListOpenHandlesUntilClosed() {
+        // given
+        LogDb log = LogDb.open(LogDbConfig.inMemory().withTags(Map.of("dump", "yes")));
+        log.append("dump-key".getBytes(StandardCharsets.UTF_8), new byte[]{1});
+
+        // when
+        List<HandleInfo> open = LogDb.dumpHandles().stream()
+                .filter(h -> h.tags().contains("dump=\"yes\""))
+                .toList();
+        log.close();
+        List<HandleInfo> closed = LogDb.dumpHandles().stream()
+                .filter(h -> h.tags().contains("dump=\"yes\""))
+                .toList();
+
+        // then
+        assertThat(open).hasSize(1);
+        assertThat(open.get(0).type()).isEqualTo("LogDb");
+        assertThat(open.get(0).creationStackHash()).isNotZero();
+        assertThat(open.get(0).appends()).isEqualTo(1);
+        assertThat(closed).isEmpty();
+    }
+
+    @Test
+    void shouldDrainOpenLogsWithoutClosingThem() {
+        // given
+        byte[] key = "drain-key".getBytes(StandardCharsets.UTF_8);
+        try (LogDb log = LogDb.open(LogDbConfig.inMemory())) {
+            for (int i = 0; i < 10; i++) {
+                log.submitAppend(new Record[]{new Record(key, new byte[]{(byte) i})}, AckMode.BUFFERED);
+            }
+
+            // when
+            boolean drained = LogDb.drainAll(10_000);
+
+            // then
+            assertThat(drained).isTrue();
+            assertThat(log.stats()).containsEntry("async.in_flight", 0L);
+            assertThat(log.scan(key, 0, 100)).hasSize(10);
+        }
+    }
+
+    @Test
+    void shouldCloseWithoutBlocking() {
+        // given
+        LogDb log = LogDb.open(LogDbConfig.inMemory().withBridgeThreads(1));
+        log.append("async-close-key".getBytes(StandardCharsets.UTF_8), new byte[]{1});
+
+        // when
+        long token = log.closeAsync();
+
+        // then
+        assertThat(token).isPositive();
+        assertThat(LogDb.awaitClose(token, 10_000)).isTrue();
+        assertThat(log.closeAsync()).isZero();
+        assertThatThrownBy(log::stats).isInstanceOf(IllegalStateException.class);
+    }
+
+    @Test
+    void shouldKeepUnreachableLogOpenUntilNativeCallReturns() throws Exception {
+        // given
+        var steadiness = new SteadyStateConfig(10, 3, 0.5);
+
+        // when - the waiting thread holds the only reference while it waits in native code
+        CompletableFuture<Boolean> waited = CompletableFuture.supplyAsync(
+                () -> LogDb.open(LogDbConfig.inMemory()).awaitSteadyState(Operation.APPEND, steadiness, 500));
+        for (int i = 0; i < 200 && !waited.isDone(); i++) {
+            System.gc();
+            Thread.sleep(10);
+        }
+
+        // then
+        assertThat(waited.get(10, TimeUnit.SECONDS)).isFalse();
+    }
+
+    @Test
+    void shouldDetachBridgeThreadsOnClose() {
+        try (LogDb log = LogDb.openInMemory()) {
+            // given
+            long withBridge;
+            try (LogDb bridged = LogDb.open(LogDbConfig.inMemory().withBridgeThreads(2))) {
+                bridged.append("bridge-key".getBytes(StandardCharsets.UTF_8), new byte[]{1});
+                withBridge = bridged.stats().get("jni.attached_threads");
+            }
+
+            // when
+            long afterClose = log.stats().get("jni.attached_threads");
+
+            // then
+            assertThat(withBridge).isGreaterThanOrEqualTo(2L);
+            assertThat(afterClose).isLessThanOrEqualTo(withBridge - 2);
+        }
+    }
+
+    @Test
+    void shouldAcknowledgePipelinedAppendsInSequenceOrder() {
+        try (LogDb log = LogDb.openInMemory();
+             AppendPipeline pipeline = log.openPipeline(8)) {
+            byte[] key = "pipeline-key".getBytes(StandardCharsets.UTF_8);
+            List<Long> acknowledged = Collections.synchronizedList(new ArrayList<>());
+            List<CompletableFuture<AppendResult>> futures = new ArrayList<>();
+            for (int i = 0; i < 50; i++) {
+                Record[] records = {new Record(key, ("value-" + i).getBytes(StandardCharsets.UTF_8))};
+                AckMode ackMode = i % 5 == 0 ? AckMode.DURABLE : AckMode.BUFFERED;
+                CompletableFuture<AppendResult> future = pipeline.append(records, ackMode);
+                future.thenAccept(result -> acknowledged.add(result.sequence()));
+                futures.add(future);
+            }
+
+            List<Long> sequences = futures.stream().map(f -> f.join().sequence()).toList();
+
+            assertThat(sequences).isSorted().doesNotHaveDuplicates();
+            assertThat(acknowledged).containsExactlyElementsOf(sequences);
+            assertThat(log.scan(key, 0, 100)).hasSize(50);
+        }
+    }
+
+    @Test
+    void shouldRejectPipelineWithoutBatchesInFlight() {
+        try (LogDb log = LogDb.openInMemory()) {
+            assertThatThrownBy(() -> log.openPipeline(0))
+                    .isInstanceOf(IllegalArgumentException.class);
+            assertThatThrownBy(() -> log.openPipeline(AppendPipeline.MAX_IN_FLIGHT + 1))
+                    .isInstanceOf(IllegalArgumentException.class);
+        }
+    }
+
+    @Test
+    void shouldCompleteSubmittedAppendsAndScans() {
+        try (LogDb log = LogDb.openInMemory()) {
+            byte[] key = "submit-key".getBytes(StandardCharsets.UTF_8);
+            List<Long> tickets = new ArrayList<>();
+            for (int i = 0; i < 100; i++) {
+                Record[] records = {new Record(key, ("value-" + i).getBytes(StandardCharsets.UTF_8))};
+                tickets.add(log.submitAppend(records, AckMode.BUFFERED));
+            }
+
+            List<Completion> appends = new ArrayList<>();
+            while (appends.size() < tickets.size()) {
+                appends.addAll(log.pollCompletions(64, 1_000));
+            }
+            long scanTicket = log.submitScan(key, 0, 1_000);
+            List<Completion> scans = log.pollCompletions(1, 10_000);
+
+            assertThat(appends).extracting(Completion::ticket)
+                    .containsExactlyInAnyOrderElementsOf(tickets);
+            assertThat(appends).noneMatch(Completion::failed);
+            assertThat(appends).extracting(c -> c.appendResult().sequence())
+                    .doesNotHaveDuplicates();
+            assertThat(scans).singleElement().satisfies(c -> {
+                assertThat(c.ticket()).isEqualTo(scanTicket);
+                assertThat(c.entries()).hasSize(100);
+            });
+            assertThat(log.pollCompletions(1, 0)).isEmpty();
+        }
+    }
+
+    @Test
+    void shouldRetrySubmissionsNotReadyUnderBoundedWriteQueue() {
+        var writeQueue = WriteQueueConfig.bounded(4, 0).withPolicy(BackpressurePolicy.NOT_READY);
+        try (LogDb log = LogDb.open(LogDbConfig.inMemory().withWriteQueue(writeQueue))) {
+            byte[] key = "queue-key".getBytes(StandardCharsets.UTF_8);
+            List<Completion> completions = new ArrayList<>();
+            int submitted = 0;
+            while (submitted < 100) {
+                Record[] records = {new Record(key, ("value-" + submitted).getBytes(StandardCharsets.UTF_8))};
+                if (log.submitAppend(records, AckMode.BUFFERED) == LogDb.NOT_READY) {
+                    completions.addAll(log.pollCompletions(64, 1_000));
+                } else {
+                    submitted++;
+                }
+            }
+            while (completions.size() < submitted) {
+                completions.addAll(log.pollCompletions(64, 1_000));
+            }
+
+            assertThat(completions).hasSize(100).noneMatch(Completion::failed);
+            assertThat(log.stats()).containsEntry("write_queue.records", 0L);
+        }
+    }
+
+    @Test
+    void shouldThrottleAppendsToProduceRate() {
+        var limits = RateLimitConfig.UNLIMITED.withProduce(100, 0);
+        try (LogDb log = LogDb.open(LogDbConfig.inMemory().withRateLimits(limits))) {
+            byte[] key = "limited-key".getBytes(StandardCharsets.UTF_8);
+
+            long started = System.nanoTime();
+            for (int i = 0; i < 150; i++) {
+                log.append(key, ("value-" + i).getBytes(StandardCharsets.UTF_8));
+            }
+            long elapsedMs = (System.nanoTime() - started) / 1_000_000;
+
+            // The first second's worth is a burst; the rest is paced at 100/s
+            assertThat(elapsedMs).isGreaterThanOrEqualTo(400);
+            assertThat(log.stats().get("rate_limit.produce_throttled_nanos")).isPositive();
+        }
+    }
+
+    @Test
+    void shouldAppendRecordsOfferedToRing() {
+        try (LogDb log = LogDb.openInMemory()) {
+            byte[] key = "ring-key".getBytes(StandardCharsets.UTF_8);
+            byte[] value = new byte[1000];
+
+            try (AppendRing ring = log.openAppendRing(AppendRing.MIN_CAPACITY)) {
+                // Enough records to wrap the ring several times
+                for (int i = 0; i < 20; i++) {
+                    value[0] = (byte) i;
+                    ring.offer(key, value);
+                }
+                ring.flush();
+            }
+
+            List<LogEntry> entries = log.scan(key, 0, 100);
+            assertThat(entries).hasSize(20);
+            for (int i = 0; i < 20; i++) {
+                assertThat(entries.get(i).value()).hasSize(1000);
+                assertThat(entries.get(i).value()[0]).isEqualTo((byte) i);
+            }
+        }
+    }
+
+    @Test
+    void shouldRejectRecordLargerThanRing() {
+        try (LogDb log = LogDb.openInMemory();
+             AppendRing ring = log.openAppendRing(AppendRing.MIN_CAPACITY)) {
+            byte[] value = new byte[AppendRing.MIN_CAPACITY];
+
+            assertThatThrownBy(() -> ring.offer("key".getBytes(StandardCharsets.UTF_8), value))
+                    .isInstanceOf(IllegalArgumentException.class);
+        }
+    }
+
+    @Test
+    void shouldRecordReaderScanLatencies(@TempDir Path tempDir) throws Exception {
+        var storage = new StorageConfig.SlateDb(
+                "reader-latency-test",
+                new ObjectStoreConfig.Local(tempDir.toString())
+        );
+        byte[] key = "reader-latency-key".getBytes(StandardCharsets.UTF_8);
+
+        try (LogDb writer = LogDb.open(new LogDbConfig(storage))) {
+            writer.append(key, "value".getBytes(StandardCharsets.UTF_8));
+        }
+
+        try (LogDbReader reader = LogDbReader.open(new LogDbReaderConfig(storage))) {
+            reader.scan(key, 0, 10);
+
+            Histogram scans = Histogram.decodeFromByteBuffer(
+                    ByteBuffer.wrap(reader.latencyHistogram(Operation.SCAN)), 0);
+
+            assertThat(scans.getTotalCount()).isEqualTo(1);
+        }
+    }
+
+    @Test
+    void shouldAccumulateTimingBreakdownWhenProfiling() {
+        try (LogDb log = LogDb.open(LogDbConfig.inMemory().withProfiling(true))) {
+            byte[] key = "profile-key".getBytes(StandardCharsets.UTF_8);
+            log.append(key, "value-0".getBytes(StandardCharsets.UTF_8));
+            log.append(key, "value-1".getBytes(StandardCharsets.UTF_8));
+            log.scan(key, 0, 10);
+
+            TimingBreakdown appends = log.timingBreakdown(Operation.APPEND);
+            TimingBreakdown scans = log.timingBreakdown(Operation.SCAN);
+
+            assertThat(appends.calls()).isEqualTo(2);
+            assertThat(appends.storageNanos()).isPositive();
+            assertThat(scans.calls()).isEqualTo(1);
+            assertThat(scans.totalNanos()).isGreaterThanOrEqualTo(scans.storageNanos());
+        }
+    }
+
+    @Test
+    void shouldNotAccumulateTimingBreakdownByDefault() {
+        try (LogDb log = LogDb.openInMemory()) {
+            byte[] key = "no-profile-key".getBytes(StandardCharsets.UTF_8);
+            log.append(key, "value".getBytes(StandardCharsets.UTF_8));
+
+            assertThat(log.timingBreakdown(Operation.APPEND).calls()).isZero();
+        }
+    }
+
+    @Test
+    void shouldServeCallsUnderWatchdog() {
+        var config = LogDbConfig.inMemory().withWatchdog(WatchdogConfig.failAfter(30_000));
+
+        try (LogDb log = LogDb.open(config)) {
+            byte[] key = "watchdog-key".getBytes(StandardCharsets.UTF_8);
+            log.append(key, "value".getBytes(StandardCharsets.UTF_8));
+            log.flush();
+
+            List<LogEntry> entries = log.scan(key, 0, 10);
+
+            assertThat(entries).hasSize(1);
+        }
+    }
+
+    @Test
+    void shouldReportRuntimeHealthStats() {
+        try (LogDb log = LogDb.openInMemory()) {
+            byte[] key = "stats-key".getBytes(StandardCharsets.UTF_8);
+            log.append(key, "value".getBytes(StandardCharsets.UTF_8));
+
+            Map<String, Long> stats = log.stats();
+
+            assertThat(stats.get("runtime.workers")).isPositive();
+            assertThat(stats.get("compaction_runtime.workers")).isPositive();
+            assertThat(stats).containsKeys(
+                    "uptime_nanos",
+                    "runtime.injection_queue_depth",
+                    "runtime.busy_nanos",
+                    "runtime.alive_tasks");
+            assertThat(stats.get("runtime.blocked_callers")).isZero();
+            assertThat(stats.get("runtime.blocked_callers_peak")).isEqualTo(1L);
+        }
+    }
 }
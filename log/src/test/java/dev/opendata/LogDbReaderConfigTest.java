@@ -29,6 +29,43 @@ class LogDbReaderConfigTest {
         assertThat(config.refreshIntervalMs()).isNull();
     }
 
+    @Test
+    void shouldEnableProfiling() {
+        var config = new LogDbReaderConfig(new StorageConfig.InMemory(), 500L).withProfiling(true);
+
+        assertThat(config.profiling()).isTrue();
+        assertThat(config.refreshIntervalMs()).isEqualTo(500L);
+    }
+
+    @Test
+    void shouldOverrideRefreshInterval() {
+        var config = new LogDbReaderConfig(new StorageConfig.InMemory())
+                .withProfiling(true)
+                .withRefreshIntervalMs(250L);
+
+        assertThat(config.refreshIntervalMs()).isEqualTo(250L);
+        assertThat(config.profiling()).isTrue();
+    }
+
+    @Test
+    void shouldSetSlowOperationThreshold() {
+        var config = LogDbReaderConfig.inMemory().withSlowOperationThreshold(250);
+
+        assertThat(config.slowOperationThresholdMs()).isEqualTo(250L);
+        assertThat(LogDbReaderConfig.inMemory().slowOperationThresholdMs()).isZero();
+    }
+
+    @Test
+    void shouldSetRuntimeMode() {
+        var config = LogDbReaderConfig.inMemory().withRuntimeMode(RuntimeMode.CURRENT_THREAD);
+
+        assertThat(config.runtimeMode()).isEqualTo(RuntimeMode.CURRENT_THREAD);
+        assertThat(LogDbReaderConfig.inMemory().runtimeMode()).isEqualTo(RuntimeMode.MULTI_THREAD);
+        assertThatThrownBy(() -> LogDbReaderConfig.inMemory().withRuntimeMode(null))
+                .isInstanceOf(IllegalArgumentException.class)
+                .hasMessageContaining("runtimeMode");
+    }
+
     @Test
     void shouldCreateInMemoryConfig() {
         var config = LogDbReaderConfig.inMemory();
@@ -60,6 +97,36 @@ class LogDbReaderConfigTest {
                 .hasMessageContaining("refreshIntervalMs");
     }
 
+    @Test
+    void shouldDisableAutoCommitByDefault() {
+        var config = LogDbReaderConfig.inMemory();
+
+        assertThat(config.autoCommit()).isNull();
+    }
+
+    @Test
+    void shouldEnableAutoCommit() {
+        var config = LogDbReaderConfig.inMemory().withAutoCommit(new AutoCommitConfig("group"));
+
+        assertThat(config.autoCommit().group()).isEqualTo("group");
+        assertThat(config.autoCommit().intervalMs()).isEqualTo(AutoCommitConfig.DEFAULT_INTERVAL_MS);
+    }
+
+    @Test
+    void shouldEnableReadAhead() {
+        var config = LogDbReaderConfig.inMemory();
+
+        assertThat(config.readAhead()).isFalse();
+        assertThat(config.withReadAhead(true).readAhead()).isTrue();
+    }
+
+    @Test
+    void shouldRejectAutoCommitWithoutGroup() {
+        assertThatThrownBy(() -> new AutoCommitConfig("", 1_000))
+                .isInstanceOf(IllegalArgumentException.class)
+                .hasMessageContaining("group");
+    }
+
     @Test
     void shouldCreateSlateDbConfig() {
         var config = new LogDbReaderConfig(
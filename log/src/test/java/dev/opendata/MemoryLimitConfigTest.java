@@ -0,0 +1,39 @@
+package dev.opendata;
+
+import org.junit.jupiter.api.Test;
+
+import static org.assertj.core.api.Assertions.assertThat;
+import static org.assertj.core.api.Assertions.assertThatThrownBy;
+
+class MemoryLimitConfigTest {
+
+    @Test
+    void shouldBeUnlimitedByDefault() {
+        var config = LogDbConfig.inMemory();
+
+        assertThat(config.memoryLimit()).isEqualTo(MemoryLimitConfig.UNLIMITED);
+        assertThat(config.memoryLimit().enabled()).isFalse();
+    }
+
+    @Test
+    void shouldBlockByDefaultWhenLimited() {
+        var memoryLimit = MemoryLimitConfig.of(64L << 20);
+
+        assertThat(memoryLimit.enabled()).isTrue();
+        assertThat(memoryLimit.policy()).isEqualTo(BackpressurePolicy.BLOCK);
+        assertThat(memoryLimit.withPolicy(BackpressurePolicy.REJECT).maxBytes()).isEqualTo(64L << 20);
+    }
+
+    @Test
+    void shouldRejectInvalidLimits() {
+        assertThatThrownBy(() -> MemoryLimitConfig.of(-1))
+                .isInstanceOf(IllegalArgumentException.class)
+                .hasMessageContaining("maxBytes");
+        assertThatThrownBy(() -> MemoryLimitConfig.of(1).withPolicy(null))
+                .isInstanceOf(IllegalArgumentException.class)
+                .hasMessageContaining("policy");
+        assertThatThrownBy(() -> MemoryLimitConfig.of(1).withPolicy(BackpressurePolicy.NOT_READY))
+                .isInstanceOf(IllegalArgumentException.class)
+                .hasMessageContaining("BLOCK or REJECT");
+    }
+}
@@ -0,0 +1,38 @@
+package dev.opendata;
+
+import org.junit.jupiter.api.Test;
+
+import static org.assertj.core.api.Assertions.assertThat;
+import static org.assertj.core.api.Assertions.assertThatThrownBy;
+
+class RateLimitConfigTest {
+
+    @Test
+    void shouldBeUnlimitedByDefault() {
+        var config = LogDbConfig.inMemory();
+
+        assertThat(config.rateLimits()).isEqualTo(RateLimitConfig.UNLIMITED);
+        assertThat(config.rateLimits().enabled()).isFalse();
+    }
+
+    @Test
+    void shouldSetProduceAndConsumeLimitsIndependently() {
+        var limits = RateLimitConfig.UNLIMITED.withProduce(1_000, 0).withConsume(0, 1L << 20);
+
+        assertThat(limits.enabled()).isTrue();
+        assertThat(limits.produceRecordsPerSec()).isEqualTo(1_000);
+        assertThat(limits.produceBytesPerSec()).isZero();
+        assertThat(limits.consumeRecordsPerSec()).isZero();
+        assertThat(limits.consumeBytesPerSec()).isEqualTo(1L << 20);
+    }
+
+    @Test
+    void shouldRejectNegativeLimits() {
+        assertThatThrownBy(() -> RateLimitConfig.UNLIMITED.withProduce(-1, 0))
+                .isInstanceOf(IllegalArgumentException.class)
+                .hasMessageContaining("produceRecordsPerSec");
+        assertThatThrownBy(() -> RateLimitConfig.UNLIMITED.withConsume(0, -1))
+                .isInstanceOf(IllegalArgumentException.class)
+                .hasMessageContaining("consumeBytesPerSec");
+    }
+}
@@ -0,0 +1,43 @@
+package dev.opendata;
+
+import org.junit.jupiter.api.Test;
+
+import static org.assertj.core.api.Assertions.assertThat;
+import static org.assertj.core.api.Assertions.assertThatThrownBy;
+
+class ReopenConfigTest {
+
+    @Test
+    void shouldBeDisabledByDefault() {
+        var config = LogDbConfig.inMemory();
+
+        assertThat(config.reopen()).isEqualTo(ReopenConfig.DISABLED);
+        assertThat(config.reopen().enabled()).isFalse();
+    }
+
+    @Test
+    void shouldUseDefaultBackoffForThreshold() {
+        var reopen = ReopenConfig.after(3);
+
+        assertThat(reopen.enabled()).isTrue();
+        assertThat(reopen.failureThreshold()).isEqualTo(3);
+        assertThat(reopen.backoffMs()).isEqualTo(ReopenConfig.DEFAULT_BACKOFF_MS);
+    }
+
+    @Test
+    void shouldRejectInvalidSettings() {
+        assertThatThrownBy(() -> ReopenConfig.after(0))
+                .isInstanceOf(IllegalArgumentException.class)
+                .hasMessageContaining("failureThreshold");
+        assertThatThrownBy(() -> ReopenConfig.after(3).withBackoff(0))
+                .isInstanceOf(IllegalArgumentException.class)
+                .hasMessageContaining("backoffMs");
+    }
+
+    @Test
+    void shouldRejectNullReopen() {
+        assertThatThrownBy(() -> LogDbConfig.inMemory().withReopen(null))
+                .isInstanceOf(IllegalArgumentException.class)
+                .hasMessageContaining("reopen");
+    }
+}
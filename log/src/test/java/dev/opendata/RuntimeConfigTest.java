@@ -0,0 +1,56 @@
+package dev.opendata;
+
+import dev.opendata.common.ObjectStoreConfig;
+import dev.opendata.common.StorageConfig;
+import org.junit.jupiter.api.Test;
+
+import static org.assertj.core.api.Assertions.assertThat;
+import static org.assertj.core.api.Assertions.assertThatThrownBy;
+
+class RuntimeConfigTest {
+
+    @Test
+    void shouldUseDefaultRuntimesByDefault() {
+        var config = LogDbConfig.inMemory();
+
+        assertThat(config.runtime()).isEqualTo(RuntimeConfig.OPERATION);
+        assertThat(config.compactionRuntime()).isEqualTo(RuntimeConfig.COMPACTION);
+        assertThat(config.runtime().cpus()).isEmpty();
+    }
+
+    @Test
+    void shouldSortPinnedCoresForNativeLayer() {
+        var runtime = RuntimeConfig.COMPACTION.withCpus(7, 5, 6, 5);
+
+        assertThat(runtime.cpuArray()).containsExactly(5, 6, 7);
+        assertThat(runtime.threadNamePrefix()).isEqualTo("opendata-compaction");
+    }
+
+    @Test
+    void shouldRejectInvalidSettings() {
+        assertThatThrownBy(() -> RuntimeConfig.OPERATION.withThreadNamePrefix(""))
+                .isInstanceOf(IllegalArgumentException.class)
+                .hasMessageContaining("threadNamePrefix");
+        assertThatThrownBy(() -> RuntimeConfig.OPERATION.withCpus(-1))
+                .isInstanceOf(IllegalArgumentException.class)
+                .hasMessageContaining("cpus");
+        assertThatThrownBy(() -> RuntimeConfig.COMPACTION.withWorkerThreads(-1))
+                .isInstanceOf(IllegalArgumentException.class)
+                .hasMessageContaining("workerThreads");
+    }
+
+    @Test
+    void shouldShareCompactionRuntimeOnlyWithInMemoryStorage() {
+        var slateDb = new LogDbConfig(new StorageConfig.SlateDb(
+                "data", new ObjectStoreConfig.Local("/tmp/data")));
+
+        assertThat(LogDbConfig.inMemory().withCompactionRuntime(RuntimeConfig.SHARED)
+                .compactionRuntime().shared()).isTrue();
+        assertThatThrownBy(() -> slateDb.withCompactionRuntime(RuntimeConfig.SHARED))
+                .isInstanceOf(IllegalArgumentException.class)
+                .hasMessageContaining("in-memory");
+        assertThatThrownBy(() -> LogDbConfig.inMemory().withRuntime(RuntimeConfig.SHARED))
+                .isInstanceOf(IllegalArgumentException.class)
+                .hasMessageContaining("runtime cannot be shared");
+    }
+}
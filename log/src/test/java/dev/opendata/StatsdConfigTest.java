@@ -0,0 +1,50 @@
+package dev.opendata;
+
+import org.junit.jupiter.api.Test;
+
+import static org.assertj.core.api.Assertions.assertThat;
+import static org.assertj.core.api.Assertions.assertThatThrownBy;
+
+class StatsdConfigTest {
+
+    @Test
+    void shouldBeDisabledByDefault() {
+        var config = LogDbConfig.inMemory();
+
+        assertThat(config.statsd()).isEqualTo(StatsdConfig.DISABLED);
+        assertThat(config.statsd().enabled()).isFalse();
+    }
+
+    @Test
+    void shouldUseDefaultIntervalAndPrefixForAgent() {
+        var statsd = StatsdConfig.to("localhost", StatsdConfig.DEFAULT_PORT).withDogStatsd(true);
+
+        assertThat(statsd.enabled()).isTrue();
+        assertThat(statsd.intervalMs()).isEqualTo(StatsdConfig.DEFAULT_INTERVAL_MS);
+        assertThat(statsd.prefix()).isEqualTo(StatsdConfig.DEFAULT_PREFIX);
+        assertThat(statsd.dogStatsd()).isTrue();
+    }
+
+    @Test
+    void shouldRejectInvalidSettings() {
+        assertThatThrownBy(() -> StatsdConfig.to(" ", 8125))
+                .isInstanceOf(IllegalArgumentException.class)
+                .hasMessageContaining("host");
+        assertThatThrownBy(() -> StatsdConfig.to("localhost", 0))
+                .isInstanceOf(IllegalArgumentException.class)
+                .hasMessageContaining("port");
+        assertThatThrownBy(() -> StatsdConfig.to("localhost", 8125).withIntervalMs(0))
+                .isInstanceOf(IllegalArgumentException.class)
+                .hasMessageContaining("intervalMs");
+        assertThatThrownBy(() -> StatsdConfig.to("localhost", 8125).withPrefix(null))
+                .isInstanceOf(IllegalArgumentException.class)
+                .hasMessageContaining("prefix");
+    }
+
+    @Test
+    void shouldRejectNullStatsd() {
+        assertThatThrownBy(() -> LogDbConfig.inMemory().withStatsd(null))
+                .isInstanceOf(IllegalArgumentException.class)
+                .hasMessageContaining("statsd");
+    }
+}
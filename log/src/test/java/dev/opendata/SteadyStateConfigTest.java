@@ -0,0 +1,40 @@
+package dev.opendata;
+
+import org.junit.jupiter.api.Test;
+
+import static org.assertj.core.api.Assertions.assertThat;
+import static org.assertj.core.api.Assertions.assertThatThrownBy;
+
+class SteadyStateConfigTest {
+
+    @Test
+    void shouldProvideDefaultWindows() {
+        var config = SteadyStateConfig.DEFAULT;
+
+        assertThat(config.windowMs()).isEqualTo(1_000);
+        assertThat(config.windows()).isEqualTo(5);
+        assertThat(config.maxCoefficientOfVariation()).isEqualTo(0.05);
+    }
+
+    @Test
+    void shouldRejectSingleWindow() {
+        assertThatThrownBy(() -> new SteadyStateConfig(1_000, 1, 0.05))
+                .isInstanceOf(IllegalArgumentException.class)
+                .hasMessageContaining("windows");
+    }
+
+    @Test
+    void shouldRejectNonPositiveWindowLength() {
+        assertThatThrownBy(() -> new SteadyStateConfig(0, 5, 0.05))
+                .isInstanceOf(IllegalArgumentException.class)
+                .hasMessageContaining("windowMs");
+    }
+
+    @Test
+    void shouldRejectNegativeOrNanThreshold() {
+        assertThatThrownBy(() -> new SteadyStateConfig(1_000, 5, -0.1))
+                .isInstanceOf(IllegalArgumentException.class);
+        assertThatThrownBy(() -> new SteadyStateConfig(1_000, 5, Double.NaN))
+                .isInstanceOf(IllegalArgumentException.class);
+    }
+}
@@ -0,0 +1,41 @@
+package dev.opendata;
+
+import org.junit.jupiter.api.Test;
+
+import static org.assertj.core.api.Assertions.assertThat;
+import static org.assertj.core.api.Assertions.assertThatThrownBy;
+
+class TopicConfigTest {
+
+    @Test
+    void shouldInheritEverythingByDefault() {
+        var config = TopicConfig.DEFAULT;
+
+        assertThat(config.settingsPath()).isNull();
+        assertThat(config.flushIntervalMs()).isNull();
+    }
+
+    @Test
+    void shouldOverrideSettings() {
+        var config = TopicConfig.DEFAULT
+                .withSettingsPath("compressed.toml")
+                .withFlushIntervalMs(100L);
+
+        assertThat(config.settingsPath()).isEqualTo("compressed.toml");
+        assertThat(config.flushIntervalMs()).isEqualTo(100L);
+    }
+
+    @Test
+    void shouldRejectNonPositiveFlushInterval() {
+        assertThatThrownBy(() -> TopicConfig.DEFAULT.withFlushIntervalMs(0L))
+                .isInstanceOf(IllegalArgumentException.class)
+                .hasMessageContaining("flushIntervalMs");
+    }
+
+    @Test
+    void shouldRejectEmptySettingsPath() {
+        assertThatThrownBy(() -> TopicConfig.DEFAULT.withSettingsPath(""))
+                .isInstanceOf(IllegalArgumentException.class)
+                .hasMessageContaining("settingsPath");
+    }
+}
@@ -0,0 +1,47 @@
+package dev.opendata;
+
+import org.junit.jupiter.api.Test;
+
+import static org.assertj.core.api.Assertions.assertThat;
+import static org.assertj.core.api.Assertions.assertThatThrownBy;
+
+class WatchdogConfigTest {
+
+    @Test
+    void shouldCreateDisabledConfig() {
+        var config = WatchdogConfig.DISABLED;
+
+        assertThat(config.thresholdMs()).isNull();
+        assertThat(config.failStuckCalls()).isFalse();
+    }
+
+    @Test
+    void shouldCreateLoggingConfig() {
+        var config = WatchdogConfig.logAfter(5_000);
+
+        assertThat(config.thresholdMs()).isEqualTo(5_000L);
+        assertThat(config.failStuckCalls()).isFalse();
+    }
+
+    @Test
+    void shouldCreateFailingConfig() {
+        var config = WatchdogConfig.failAfter(5_000);
+
+        assertThat(config.thresholdMs()).isEqualTo(5_000L);
+        assertThat(config.failStuckCalls()).isTrue();
+    }
+
+    @Test
+    void shouldRejectNonPositiveThreshold() {
+        assertThatThrownBy(() -> WatchdogConfig.logAfter(0))
+                .isInstanceOf(IllegalArgumentException.class)
+                .hasMessageContaining("positive");
+    }
+
+    @Test
+    void shouldRejectFailingWithoutThreshold() {
+        assertThatThrownBy(() -> new WatchdogConfig(null, true))
+                .isInstanceOf(IllegalArgumentException.class)
+                .hasMessageContaining("thresholdMs");
+    }
+}
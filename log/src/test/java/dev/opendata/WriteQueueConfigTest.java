@@ -0,0 +1,39 @@
+package dev.opendata;
+
+import org.junit.jupiter.api.Test;
+
+import static org.assertj.core.api.Assertions.assertThat;
+import static org.assertj.core.api.Assertions.assertThatThrownBy;
+
+class WriteQueueConfigTest {
+
+    @Test
+    void shouldBeUnboundedByDefault() {
+        var config = LogDbConfig.inMemory();
+
+        assertThat(config.writeQueue()).isEqualTo(WriteQueueConfig.UNBOUNDED);
+        assertThat(config.writeQueue().enabled()).isFalse();
+    }
+
+    @Test
+    void shouldBlockByDefaultWhenBounded() {
+        var writeQueue = WriteQueueConfig.bounded(1_000, 0);
+
+        assertThat(writeQueue.enabled()).isTrue();
+        assertThat(writeQueue.policy()).isEqualTo(BackpressurePolicy.BLOCK);
+        assertThat(writeQueue.withPolicy(BackpressurePolicy.REJECT).maxRecords()).isEqualTo(1_000);
+    }
+
+    @Test
+    void shouldRejectInvalidLimits() {
+        assertThatThrownBy(() -> WriteQueueConfig.bounded(-1, 0))
+                .isInstanceOf(IllegalArgumentException.class)
+                .hasMessageContaining("maxRecords");
+        assertThatThrownBy(() -> WriteQueueConfig.bounded(0, -1))
+                .isInstanceOf(IllegalArgumentException.class)
+                .hasMessageContaining("maxBytes");
+        assertThatThrownBy(() -> WriteQueueConfig.bounded(1, 0).withPolicy(null))
+                .isInstanceOf(IllegalArgumentException.class)
+                .hasMessageContaining("policy");
+    }
+}